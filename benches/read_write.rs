@@ -0,0 +1,60 @@
+//! Baselines for the hot paths of reading and writing presets so changes such
+//! as buffering can be measured. Run with `cargo bench --bench read_write`
+//! and Criterion compares each run with the previous one.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+use synthahol_phase_plant::Preset;
+
+fn test_data(components: &[&str]) -> Vec<u8> {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests");
+    path.extend(components);
+    fs::read(&path).unwrap_or_else(|error| panic!("{}: {error}", path.display()))
+}
+
+fn read(c: &mut Criterion) {
+    let init = test_data(&["init", "init-2.1.0.phaseplant"]);
+    let effects = test_data(&["effects", "filter", "filter-all_modes-2.1.0.phaseplant"]);
+
+    let mut group = c.benchmark_group("read");
+    group.throughput(Throughput::Bytes(init.len() as u64));
+    group.bench_function("full", |b| {
+        b.iter(|| Preset::read(&mut Cursor::new(black_box(&init)), None).unwrap())
+    });
+    group.bench_function("metadata", |b| {
+        b.iter(|| Preset::read_metadata(&mut Cursor::new(black_box(&init)), None).unwrap())
+    });
+    group.throughput(Throughput::Bytes(effects.len() as u64));
+    group.bench_function("effects", |b| {
+        b.iter(|| Preset::read(&mut Cursor::new(black_box(&effects)), None).unwrap())
+    });
+    group.finish();
+}
+
+fn write(c: &mut Criterion) {
+    let preset = Preset::read(
+        &mut Cursor::new(test_data(&["init", "init-2.1.0.phaseplant"])),
+        None,
+    )
+    .unwrap();
+    let mut cursor = Cursor::new(Vec::new());
+    preset.write(&mut cursor).unwrap();
+
+    let mut group = c.benchmark_group("write");
+    group.throughput(Throughput::Bytes(cursor.get_ref().len() as u64));
+    group.bench_function("full", |b| {
+        b.iter(|| {
+            cursor.set_position(0);
+            black_box(&preset).write(&mut cursor).unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, read, write);
+criterion_main!(benches);
@@ -0,0 +1,61 @@
+//! Compare writing a bank of presets in parallel with writing them one at a
+//! time. Run with `cargo bench --features parallel --bench write_many`.
+
+use std::fs;
+use std::path::PathBuf;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use synthahol_phase_plant::library::write_many;
+use synthahol_phase_plant::Preset;
+
+const BANK_SIZE: usize = 256;
+
+fn bank() -> Vec<Preset> {
+    let init_path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "tests",
+        "init",
+        "init-2.1.0.phaseplant",
+    ]
+    .iter()
+    .collect();
+    let init = Preset::read_file(init_path).expect("init preset");
+    (0..BANK_SIZE)
+        .map(|index| {
+            let mut preset = init.clone();
+            preset.metadata.name = Some(format!("Preset {index}"));
+            preset
+        })
+        .collect()
+}
+
+fn write_bank(c: &mut Criterion) {
+    let presets = bank();
+    let out_dir = std::env::temp_dir().join(format!("write_many-bench-{}", std::process::id()));
+    fs::create_dir_all(&out_dir).unwrap();
+
+    let mut group = c.benchmark_group("write_bank");
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            for (index, preset) in presets.iter().enumerate() {
+                preset
+                    .write_file(out_dir.join(format!("Preset {index}.phaseplant")))
+                    .unwrap();
+            }
+        })
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| {
+            for written in write_many(&presets, &out_dir) {
+                written.result.unwrap();
+            }
+        })
+    });
+    group.finish();
+
+    fs::remove_dir_all(&out_dir).unwrap();
+}
+
+criterion_group!(benches, write_bank);
+criterion_main!(benches);
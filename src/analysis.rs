@@ -0,0 +1,389 @@
+//! Summaries of how a collection of presets uses Phase Plant.
+//!
+//! A [`FeatureMatrix`] counts the effects, generators and modulators used by
+//! the presets, along with the [features](GatedFeature) that need a newer
+//! release of Phase Plant. Preset packs can use it to state the release they
+//! require. The matrix can be exported as CSV or JSON.
+//!
+//! A [`ParameterMatrix`] lines up the parameters that differ between
+//! presets, such as variations generated from the same preset, so they can
+//! be compared side by side.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+use serde::Serialize;
+
+use crate::diff::{PresetDiff, Value};
+use crate::*;
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeatureCategory {
+    Effect,
+    Generator,
+    Modulator,
+
+    /// A feature that needs a newer release of Phase Plant.
+    Release,
+}
+
+impl Display for FeatureCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FeatureCategory::Effect => "effect",
+            FeatureCategory::Generator => "generator",
+            FeatureCategory::Modulator => "modulator",
+            FeatureCategory::Release => "release",
+        })
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct FeatureUsage {
+    pub category: FeatureCategory,
+    pub name: String,
+
+    /// How many presets use the feature at least once.
+    pub presets: usize,
+
+    /// How many times the feature is used across all of the presets.
+    pub uses: usize,
+
+    /// The first release of Phase Plant that supports the feature. Only set
+    /// for the [`Release`](FeatureCategory::Release) category.
+    pub release: Option<PhasePlantRelease>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct FeatureMatrix {
+    /// Number of presets summarized.
+    pub preset_count: usize,
+
+    /// The features ordered by category and name.
+    pub features: Vec<FeatureUsage>,
+
+    /// The newest release required by any of the presets, or `None` if there
+    /// were no presets.
+    pub required_release: Option<PhasePlantRelease>,
+}
+
+impl FeatureMatrix {
+    pub fn feature(&self, category: FeatureCategory, name: &str) -> Option<&FeatureUsage> {
+        self.features
+            .iter()
+            .find(|usage| usage.category == category && usage.name == name)
+    }
+
+    /// One row per feature with a header row.
+    pub fn to_csv(&self) -> String {
+        let mut csv = "category,name,presets,uses,release\n".to_owned();
+        for usage in &self.features {
+            let release = usage
+                .release
+                .map(|release| release.to_string())
+                .unwrap_or_default();
+            csv += &format!(
+                "{},{},{},{},{}\n",
+                usage.category,
+                csv_field(&usage.name),
+                usage.presets,
+                usage.uses,
+                release
+            );
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Quote a field if it contains characters that are special to CSV.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Count the features used by each preset.
+pub fn feature_matrix<'a, I>(presets: I) -> FeatureMatrix
+where
+    I: IntoIterator<Item = &'a Preset>,
+{
+    let mut preset_count = 0;
+    let mut required_release = None;
+    let mut counts: BTreeMap<(FeatureCategory, String), (usize, usize)> = BTreeMap::new();
+    let mut releases = BTreeMap::new();
+
+    for preset in presets {
+        preset_count += 1;
+        required_release = required_release.max(Some(preset.required_release()));
+
+        for feature in preset.gated_features() {
+            releases.insert(feature.name, feature.release);
+        }
+        for (key, uses) in preset_features(preset) {
+            let (presets, total) = counts.entry(key).or_default();
+            *presets += 1;
+            *total += uses;
+        }
+    }
+
+    let features = counts
+        .into_iter()
+        .map(|((category, name), (presets, uses))| FeatureUsage {
+            release: match category {
+                FeatureCategory::Release => releases.get(name.as_str()).copied(),
+                _ => None,
+            },
+            category,
+            name,
+            presets,
+            uses,
+        })
+        .collect();
+    FeatureMatrix {
+        preset_count,
+        features,
+        required_release,
+    }
+}
+
+/// How many times the preset uses each feature.
+pub(crate) fn preset_features(preset: &Preset) -> BTreeMap<(FeatureCategory, String), usize> {
+    let mut uses: BTreeMap<(FeatureCategory, String), usize> = BTreeMap::new();
+    let mut count = |category: FeatureCategory, name: &str| {
+        *uses.entry((category, name.to_owned())).or_default() += 1;
+    };
+    for lane in &preset.lanes {
+        count_effects(&lane.snapins, &mut count);
+    }
+    for generator in &preset.generators {
+        if !generator.mode().is_blank() {
+            count(FeatureCategory::Generator, generator.mode().name());
+        }
+    }
+    for container in &preset.modulator_containers {
+        count(
+            FeatureCategory::Modulator,
+            &container.modulator.mode().to_string(),
+        );
+    }
+    for feature in preset.gated_features() {
+        count(FeatureCategory::Release, feature.name);
+    }
+    uses
+}
+
+fn count_effects<F: FnMut(FeatureCategory, &str)>(snapins: &[Snapin], count: &mut F) {
+    for snapin in snapins {
+        count(FeatureCategory::Effect, snapin.effect.mode().name());
+        if let Some(multipass) = snapin.effect.as_multipass() {
+            for lane in &multipass.lanes {
+                count_effects(&lane.snapins, count);
+            }
+        }
+    }
+}
+
+/// A parameter that differs between some of the compared presets.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ParameterRow {
+    /// Location of the parameter, such as `lanes.0.snapins.1.enabled`.
+    pub path: String,
+
+    /// The value in each preset, in the order of the presets. Not set for
+    /// presets that don't have the parameter.
+    pub values: Vec<Option<Value>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ParameterMatrix {
+    /// Number of presets compared.
+    pub preset_count: usize,
+
+    /// Only parameters that aren't the same in every preset are included.
+    pub rows: Vec<ParameterRow>,
+}
+
+impl ParameterMatrix {
+    pub fn row(&self, path: &str) -> Option<&ParameterRow> {
+        self.rows.iter().find(|row| row.path == path)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Compare the parameters of the presets. Each preset is
+/// [diffed](PresetDiff) against the first so the parameters are named and
+/// measured the same way as in a diff. Effects, generators and modulators
+/// have a row for each of their parameters that differs, such as
+/// `lanes.0.snapins.1.cutoff` or `generators.0.unison.voices`. An effect,
+/// generator or modulator that is added, removed or replaced by one of
+/// another type has a single row describing it. The rows are in the order
+/// the parameters are first found.
+pub fn compare_many(presets: &[Preset]) -> ParameterMatrix {
+    let mut rows: Vec<ParameterRow> = Vec::new();
+    let mut paths = BTreeSet::new();
+    let mut changed: Vec<BTreeMap<String, Option<Value>>> = Vec::new();
+    if let Some((first, others)) = presets.split_first() {
+        for other in others {
+            let mut values = BTreeMap::new();
+            for change in PresetDiff::new(first, other).changes {
+                if paths.insert(change.path.clone()) {
+                    rows.push(ParameterRow {
+                        path: change.path.clone(),
+                        values: vec![change.before.clone()],
+                    });
+                }
+                values.insert(change.path, change.after);
+            }
+            changed.push(values);
+        }
+    }
+
+    // Presets without a change to a parameter have the value of the first.
+    for row in &mut rows {
+        let first = row.values[0].clone();
+        for values in &changed {
+            row.values.push(
+                values
+                    .get(&row.path)
+                    .cloned()
+                    .unwrap_or_else(|| first.clone()),
+            );
+        }
+    }
+    ParameterMatrix {
+        preset_count: presets.len(),
+        rows,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::generator::GeneratorMode;
+    use crate::test::{read_effect_preset, read_generator_preset, read_modulator_preset};
+
+    use super::*;
+
+    #[test]
+    fn feature_matrix() {
+        let presets = [
+            read_effect_preset("gain", "gain-2.0.16.phaseplant").unwrap(),
+            read_generator_preset("granular_generator", "granular_generator-2.1.0.phaseplant")
+                .unwrap(),
+            read_modulator_preset("lfo", "lfo-note_trigger_legato-sync-2.1.0.phaseplant").unwrap(),
+            read_modulator_preset("lfo", "lfo-1.8.13.phaseplant").unwrap(),
+        ];
+        let matrix = super::feature_matrix(&presets);
+        assert_eq!(matrix.preset_count, 4);
+        assert_eq!(matrix.required_release, Some(PhasePlantRelease::V2_1_0));
+
+        let gain = matrix.feature(FeatureCategory::Effect, "Gain").unwrap();
+        assert_eq!((gain.presets, gain.uses), (1, 1));
+        assert_eq!(gain.release, None);
+        let lfo = matrix.feature(FeatureCategory::Modulator, "LFO").unwrap();
+        assert_eq!(lfo.presets, 2);
+        let granular = matrix
+            .feature(
+                FeatureCategory::Release,
+                GeneratorMode::GranularGenerator.name(),
+            )
+            .unwrap();
+        assert_eq!(granular.release, Some(PhasePlantRelease::V2_1_0));
+        assert!(matrix
+            .feature(FeatureCategory::Release, "Note Trigger Legato")
+            .is_some());
+        assert!(matrix
+            .features
+            .windows(2)
+            .all(|pair| (pair[0].category, &pair[0].name) < (pair[1].category, &pair[1].name)));
+
+        let csv = matrix.to_csv();
+        assert!(csv.starts_with("category,name,presets,uses,release\n"));
+        assert!(csv.contains("effect,Gain,1,1,\n"));
+        assert!(csv.contains(",2.1.0\n"));
+        let json = matrix.to_json().unwrap();
+        assert!(json.contains("\"required_release\": \"2.1.0\""));
+
+        assert_eq!(
+            super::feature_matrix(std::iter::empty()),
+            FeatureMatrix::default()
+        );
+    }
+
+    #[test]
+    fn compare_many() {
+        let first = Preset::default();
+        let mut second = first.clone();
+        second.polyphony = 4;
+        let mut third = first.clone();
+        third.polyphony = 12;
+        third.glide_enabled = true;
+
+        let matrix = super::compare_many(&[first.clone(), second, third]);
+        assert_eq!(matrix.preset_count, 3);
+        assert_eq!(matrix.rows.len(), 2);
+        let number = |value| {
+            Some(Value::Number {
+                value,
+                unit: crate::diff::Unit::Number,
+            })
+        };
+        assert_eq!(
+            matrix.row("polyphony").unwrap().values,
+            [number(8.0), number(4.0), number(12.0)]
+        );
+        let glide = matrix.row("glide_enabled").unwrap();
+        assert_eq!(glide.values[0], glide.values[1]);
+        assert_ne!(glide.values[0], glide.values[2]);
+        assert!(matrix
+            .to_json()
+            .unwrap()
+            .contains("\"path\": \"polyphony\""));
+
+        assert!(super::compare_many(&[first.clone(), first]).rows.is_empty());
+        assert_eq!(super::compare_many(&[]), ParameterMatrix::default());
+    }
+
+    #[test]
+    fn compare_variations() {
+        let first =
+            read_generator_preset("analog_oscillator", "analog_oscillator-2.1.0.phaseplant")
+                .unwrap();
+        let mut second = first.clone();
+        second.set("generators.1.unison.voices", "7").unwrap();
+        let mut third = first.clone();
+        third.set("generators.1.waveform", "square").unwrap();
+        third.set("generators.2.envelope.attack", "0.5 s").unwrap();
+
+        let matrix = super::compare_many(&[first.clone(), second.clone(), third.clone()]);
+        let paths: Vec<_> = matrix.rows.iter().map(|row| row.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "generators.1.unison.voices",
+                "generators.1.waveform",
+                "generators.2.envelope.attack",
+            ]
+        );
+        for row in &matrix.rows {
+            for (preset, value) in [&first, &second, &third].iter().zip(&row.values) {
+                assert_eq!(value.as_ref(), preset.get(&row.path).ok().as_ref());
+            }
+        }
+    }
+
+    #[test]
+    fn csv_field() {
+        assert_eq!(super::csv_field("Gain"), "Gain");
+        assert_eq!(super::csv_field("A, \"B\""), "\"A, \"\"B\"\"\"");
+    }
+}
@@ -0,0 +1,305 @@
+//! Random presets for testing.
+//!
+//! [`Preset::arbitrary`] builds a preset from a seed with randomly chosen
+//! generators, effects, modulators and modulations. Only parts this crate can
+//! write and read back are used, so the presets can be fed to Phase Plant to
+//! test how it copes with unusual combinations, and used as input to
+//! property tests of this crate. The same seed always builds the same
+//! preset.
+
+use uom::si::f32::Ratio;
+use uom::si::ratio::percent;
+
+use crate::effect::EffectMode;
+use crate::generator::*;
+use crate::limits::{GENERATORS_MAX, MODULATIONS_MAX, MODULATORS_MAX};
+use crate::modulation::{HostParameter, Modulation, ModulationSource, ModulationTarget, RateMode};
+use crate::modulator::*;
+use crate::*;
+
+/// Effects that can be written. Carve EQ, Convolver, Multipass, Slice EQ and
+/// Snap Heap are left out because writing them hasn't been completed yet,
+/// and Group because it needs snapins to hold.
+const EFFECT_MODES: &[EffectMode] = &[
+    EffectMode::Bitcrush,
+    EffectMode::ChannelMixer,
+    EffectMode::Chorus,
+    EffectMode::CombFilter,
+    EffectMode::Compressor,
+    EffectMode::Delay,
+    EffectMode::Disperser,
+    EffectMode::Distortion,
+    EffectMode::DualDelay,
+    EffectMode::Dynamics,
+    EffectMode::Ensemble,
+    EffectMode::Faturator,
+    EffectMode::Filter,
+    EffectMode::Flanger,
+    EffectMode::FormantFilter,
+    EffectMode::FrequencyShifter,
+    EffectMode::Gain,
+    EffectMode::Gate,
+    EffectMode::Haas,
+    EffectMode::LadderFilter,
+    EffectMode::Limiter,
+    EffectMode::NonlinearFilter,
+    EffectMode::PhaseDistortion,
+    EffectMode::Phaser,
+    EffectMode::PitchShifter,
+    EffectMode::Resonator,
+    EffectMode::Reverb,
+    EffectMode::Reverser,
+    EffectMode::RingMod,
+    EffectMode::Stereo,
+    EffectMode::TapeStop,
+    EffectMode::ThreeBandEq,
+    EffectMode::TranceGate,
+    EffectMode::TransientShaper,
+];
+
+/// SplitMix64, which is small and good enough for picking settings.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Between zero and `count`, excluding `count`.
+    fn below(&mut self, count: usize) -> usize {
+        (self.next_u64() % count as u64) as usize
+    }
+
+    /// Between zero and `max` inclusive.
+    fn up_to(&mut self, max: usize) -> usize {
+        self.below(max + 1)
+    }
+
+    fn chance(&mut self, probability: f32) -> bool {
+        self.unit() < probability
+    }
+
+    fn unit(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn between(&mut self, min: f32, max: f32) -> f32 {
+        min + self.unit() * (max - min)
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+fn arbitrary_generator(rng: &mut Rng) -> Box<dyn Generator> {
+    match rng.below(12) {
+        0 => Box::<AnalogOscillator>::default(),
+        1 => Box::<AuxRouting>::default(),
+        2 => Box::<CurveOutput>::default(),
+        3 => Box::<DistortionEffect>::default(),
+        4 => Box::<EnvelopeOutput>::default(),
+        5 => Box::<FilterEffect>::default(),
+        6 => Box::<GranularGenerator>::default(),
+        7 => Box::<MixRouting>::default(),
+        8 => Box::<NoiseGenerator>::default(),
+        9 => Box::<NonlinearFilterGenerator>::default(),
+        10 => Box::<SamplePlayer>::default(),
+        _ => Box::<WavetableOscillator>::default(),
+    }
+}
+
+fn arbitrary_modulator(rng: &mut Rng) -> Box<dyn Modulator> {
+    match rng.below(16) {
+        0 => Box::<AudioFollowerModulator>::default(),
+        1 => Box::<CurveModulator>::default(),
+        2 => Box::<EnvelopeModulator>::default(),
+        3 => Box::<LfoModulator>::default(),
+        4 => Box::<LfoTableModulator>::default(),
+        5 => Box::<LowerLimitModulator>::default(),
+        6 => Box::<MidiCcModulator>::default(),
+        7 => Box::<MpeTimbreModulator>::default(),
+        8 => Box::<PitchTrackerModulator>::default(),
+        9 => Box::<PitchWheelModulator>::default(),
+        10 => Box::<PressureModulator>::default(),
+        11 => Box::<RandomModulator>::default(),
+        12 => Box::<SampleAndHoldModulator>::default(),
+        13 => Box::<SlewLimiterModulator>::default(),
+        14 => Box::<UpperLimitModulator>::default(),
+        _ => Box::<VelocityModulator>::default(),
+    }
+}
+
+impl Preset {
+    /// Build a random preset from the seed. Up to `complexity` generators,
+    /// effects in each lane, modulators and modulations are added, within
+    /// the limits of Phase Plant. The settings of the preset, lanes and
+    /// macro controls are randomized, while generators, effects and
+    /// modulators start with their default settings.
+    ///
+    /// Modulations only come from macro controls, the mod wheel and the
+    /// modulators, and only target the master gain, lanes and macro
+    /// controls, because which parameter the identifiers of the other
+    /// targets refer to isn't known.
+    pub fn arbitrary(seed: u64, complexity: usize) -> Preset {
+        let mut rng = Rng(seed);
+        let mut preset = Preset::default();
+        preset.metadata.name = Some(format!("Arbitrary {seed}"));
+        preset.metadata.description =
+            Some(format!("Seed {seed} with a complexity of {complexity}"));
+
+        preset.master_gain = Decibels::new(rng.between(-12.0, 0.0)).linear();
+        preset.master_pitch = rng.between(-12.0, 12.0).round();
+        preset.polyphony = 1 + rng.below(16) as u32;
+        preset.glide_enabled = rng.chance(0.25);
+        preset.glide_time = rng.unit();
+        preset.mod_wheel_value = Ratio::new::<percent>(rng.between(0.0, 100.0));
+        for macro_control in &mut preset.macro_controls {
+            macro_control.value = rng.unit();
+        }
+
+        // The root group comes first and uses the first ID.
+        preset.generators.push(Box::<generator::Group>::default());
+        let generator_count = rng.up_to(complexity).min(GENERATORS_MAX as usize - 1);
+        for _ in 0..generator_count {
+            let mut generator = arbitrary_generator(&mut rng);
+            let _ = generator.set_enabled(rng.chance(0.9));
+            let _ = generator.set_level_db(Decibels::new(rng.between(-24.0, 0.0)));
+            let mut block = generator.as_block();
+            block.id = preset.generators.len() as GeneratorId;
+            preset
+                .generators
+                .push(GeneratorEnum::from(&block).into_boxed());
+        }
+
+        for lane in &mut preset.lanes {
+            lane.gain = Decibels::new(rng.between(-12.0, 0.0));
+            lane.mix = Ratio::new::<percent>(rng.between(0.0, 100.0));
+            lane.enabled = rng.chance(0.9);
+            lane.poly_count = rng.chance(0.25) as u8;
+            for index in 0..rng.up_to(complexity) {
+                let mut effect = rng.pick(EFFECT_MODES).default_effect();
+                if let Some(mix) = effect.mix_mut() {
+                    *mix = Ratio::new::<percent>(rng.between(0.0, 100.0));
+                }
+                let id = Snapin::MIN_POSITION + index as SnapinId;
+                lane.snapins
+                    .push(Snapin::new(effect, id, rng.chance(0.9), rng.chance(0.1)));
+            }
+        }
+
+        let modulator_count = rng.up_to(complexity).min(MODULATORS_MAX);
+        for id in 0..modulator_count {
+            let mut container =
+                ModulatorContainer::new(id as ModulatorId, arbitrary_modulator(&mut rng));
+            container.enabled = rng.chance(0.9);
+            container.minimized = rng.chance(0.1);
+            preset.modulator_containers.push(container);
+        }
+
+        let modulation_count = rng.up_to(complexity).min(MODULATIONS_MAX);
+        for _ in 0..modulation_count {
+            let source = match rng.below(3) {
+                0 if modulator_count > 0 => {
+                    ModulationSource::Modulator(rng.below(modulator_count) as ModulatorId)
+                }
+                1 => ModulationSource::ModWheel,
+                _ => {
+                    ModulationSource::MacroControl(rng.below(MacroControl::COUNT) as MacroControlId)
+                }
+            };
+            let lane_id = rng.below(Lane::COUNT) as LaneId;
+            let parameter = match rng.below(4) {
+                0 => HostParameter::MasterGain,
+                1 => HostParameter::LaneGain(lane_id),
+                2 => HostParameter::LaneMix(lane_id),
+                _ => HostParameter::MacroControl(rng.below(MacroControl::COUNT) as MacroControlId),
+            };
+            let target = ModulationTarget::Host {
+                parameter,
+                rate_mode: RateMode::Control,
+            };
+            let amount = Ratio::new::<percent>(rng.between(-100.0, 100.0));
+            preset
+                .modulations
+                .push(Modulation::new(source, target, amount));
+        }
+
+        preset
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use crate::diff::Value;
+
+    use super::*;
+
+    #[test]
+    fn arbitrary() {
+        assert_eq!(Preset::arbitrary(7, 4), Preset::arbitrary(7, 4));
+        assert_ne!(Preset::arbitrary(7, 4), Preset::arbitrary(8, 4));
+
+        let preset = Preset::arbitrary(1, 0);
+        assert_eq!(preset.generators.len(), 1);
+        assert!(preset.modulator_containers.is_empty());
+        assert!(preset.modulations.is_empty());
+
+        // Large complexities are limited to what Phase Plant supports.
+        for seed in 0..20 {
+            let preset = Preset::arbitrary(seed, seed as usize * 10);
+            preset.check_limits().unwrap();
+            assert!(preset.check_modulations().is_empty());
+
+            let mut cursor = Cursor::new(Vec::new());
+            preset.write(&mut cursor).unwrap();
+            cursor.set_position(0);
+            let read = Preset::read(&mut cursor, preset.metadata.name.clone()).unwrap();
+            assert_eq!(read.generators.len(), preset.generators.len(), "{seed}");
+            assert_eq!(read.modulations.len(), preset.modulations.len(), "{seed}");
+            assert_eq!(read.mod_wheel_value, preset.mod_wheel_value, "{seed}");
+            let containers = |preset: &Preset| {
+                preset
+                    .modulator_containers
+                    .iter()
+                    .map(|container| {
+                        (
+                            container.id,
+                            container.modulator.mode(),
+                            container.enabled,
+                            container.minimized,
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(containers(&read), containers(&preset), "{seed}");
+            for (read_lane, lane) in read.lanes.iter().zip(&preset.lanes) {
+                let modes = |lane: &Lane| {
+                    lane.snapins
+                        .iter()
+                        .map(|snapin| snapin.effect.mode())
+                        .collect::<Vec<_>>()
+                };
+                assert_eq!(modes(read_lane), modes(lane), "{seed}");
+            }
+
+            // Numbers may only differ by the precision they are written with.
+            for change in preset.diff(&read).changes {
+                let close = match (&change.before, &change.after) {
+                    (
+                        Some(Value::Number { value: before, .. }),
+                        Some(Value::Number { value: after, .. }),
+                    ) => (after - before).abs() <= 1e-3 * before.abs().max(1.0),
+                    _ => false,
+                };
+                assert!(close, "{seed}: {change:?}");
+            }
+        }
+    }
+}
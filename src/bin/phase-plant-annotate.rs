@@ -0,0 +1,35 @@
+//! Print a preset as a hexdump with the sections of the file marked, to help
+//! find what the unknown parts of the format are.
+//!
+//! ```text
+//! phase-plant-annotate <preset>
+//! ```
+
+use std::fs;
+use std::io::{stdout, BufWriter, Cursor, Error, Write};
+use std::process::ExitCode;
+
+use synthahol_phase_plant::{write_hexdump, Preset, ReadOptions};
+
+fn annotate(path: &str) -> Result<(), Error> {
+    let contents = fs::read(path)?;
+    let spans = Preset::read_section_map(&mut Cursor::new(&contents), &ReadOptions::default())?;
+    let mut writer = BufWriter::new(stdout().lock());
+    write_hexdump(&mut writer, &contents, &spans)?;
+    writer.flush()
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [path] = args.as_slice() else {
+        eprintln!("Usage: phase-plant-annotate <preset>");
+        return ExitCode::FAILURE;
+    };
+    match annotate(path) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{path}: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
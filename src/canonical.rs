@@ -0,0 +1,298 @@
+//! Remove differences between presets that don't change how they sound.
+//!
+//! Presets that are saved from the same patch can still differ in ways that
+//! don't matter, such as the order of the modulations or a parameter that is
+//! a rounding error away from the value shown in Phase Plant. Tools that
+//! fingerprint or deduplicate presets should
+//! [canonicalize](Preset::canonicalize) them first.
+
+use uom::si::f32::{Frequency, Ratio, Time};
+use uom::si::frequency::hertz;
+use uom::si::ratio::percent;
+use uom::si::time::second;
+
+use crate::diff::Unit;
+use crate::modulator::{ModulatorMode, GROUP_ID_NONE};
+use crate::path::{parameters_mut, FieldMut};
+use crate::*;
+
+// Displayed precision of each unit.
+const DECIBELS_STEP: f32 = 0.01;
+const PERCENT_STEP: f32 = 0.1;
+const SECONDS_STEP: f32 = 0.001;
+const SEMITONES_STEP: f32 = 0.01;
+const CENTS_STEP: f32 = 0.1;
+const HERTZ_STEP: f32 = 0.01;
+
+fn round(value: f32, step: f32) -> f32 {
+    if value.is_finite() {
+        // Avoid negative zero so it compares the same as zero.
+        (value / step).round() * step + 0.0
+    } else {
+        value
+    }
+}
+
+fn round_ratio(value: &mut Ratio) {
+    *value = Ratio::new::<percent>(round(value.get::<percent>(), PERCENT_STEP));
+}
+
+fn round_decibels(value: &mut Decibels) {
+    *value = Decibels::new(round(value.db(), DECIBELS_STEP));
+}
+
+/// Plain numbers, such as a count of voices, are left as they are.
+fn unit_step(unit: Unit) -> Option<f32> {
+    match unit {
+        Unit::Number => None,
+        Unit::Cents => Some(CENTS_STEP),
+        Unit::Decibels => Some(DECIBELS_STEP),
+        Unit::Hertz => Some(HERTZ_STEP),
+        Unit::Percent => Some(PERCENT_STEP),
+        Unit::Seconds => Some(SECONDS_STEP),
+        Unit::Semitones => Some(SEMITONES_STEP),
+    }
+}
+
+/// Round a numeric parameter in the unit it is displayed in.
+fn round_field(field: FieldMut) {
+    use FieldMut::*;
+    match field {
+        Number(value, unit) => {
+            if let Some(step) = unit_step(unit) {
+                *value = round(*value, step);
+            }
+        }
+        Percent(value) => round_ratio(value),
+        Fraction(value) => *value = round(*value * 100.0, PERCENT_STEP) / 100.0,
+        Gain(value) => round_decibels(value),
+        LinearGain(value) => {
+            *value =
+                Decibels::new(round(Decibels::from_linear(*value).db(), DECIBELS_STEP)).linear()
+        }
+        Hertz(value) => *value = Frequency::new::<hertz>(round(value.get::<hertz>(), HERTZ_STEP)),
+        Seconds(value) => *value = Time::new::<second>(round(value.get::<second>(), SECONDS_STEP)),
+        Pitch(value) => *value = Semitones::new(round(value.semitones(), SEMITONES_STEP)),
+        _ => (),
+    }
+}
+
+impl Preset {
+    /// Remove entities that have no effect, round parameters to the precision
+    /// Phase Plant displays and sort collections whose order doesn't matter,
+    /// so presets that sound the same compare equal.
+    ///
+    /// * Modulations that are disabled and have no amount are removed.
+    /// * Modulator groups that don't contain any modulators are removed.
+    /// * Modulations are sorted by the IDs of their source and target, then
+    ///   by amount.
+    ///
+    /// Every numeric parameter that can be addressed by [path](Preset::get)
+    /// is rounded in the unit it is displayed in. Values that are kept but
+    /// aren't understood are left as they are.
+    pub fn canonicalize(&mut self) {
+        self.modulations
+            .retain(|modulation| modulation.enabled || modulation.amount.value != 0.0);
+        self.remove_empty_modulator_groups();
+        self.round_parameters();
+        self.modulations.sort_by(|a, b| {
+            (a.source.id(), a.target.id())
+                .cmp(&(b.source.id(), b.target.id()))
+                .then_with(|| a.amount.value.total_cmp(&b.amount.value))
+        });
+    }
+
+    fn remove_empty_modulator_groups(&mut self) {
+        let used_groups: Vec<_> = self
+            .modulator_containers
+            .iter()
+            .map(|container| container.group_id)
+            .filter(|group_id| *group_id != GROUP_ID_NONE)
+            .collect();
+        self.modulator_containers.retain(|container| {
+            container.modulator.mode() != ModulatorMode::Group
+                || used_groups.contains(&(container.id as u32))
+        });
+    }
+
+    fn round_parameters(&mut self) {
+        self.master_gain = Decibels::new(round(
+            Decibels::from_linear(self.master_gain).db(),
+            DECIBELS_STEP,
+        ))
+        .linear();
+        self.master_pitch = round(self.master_pitch, SEMITONES_STEP);
+        round_ratio(&mut self.mod_wheel_value);
+        self.glide_time = round(self.glide_time, SECONDS_STEP);
+
+        self.unison.detune_cents = round(self.unison.detune_cents, CENTS_STEP);
+        round_ratio(&mut self.unison.spread);
+        round_ratio(&mut self.unison.blend);
+        round_ratio(&mut self.unison.bias);
+
+        for macro_control in &mut self.macro_controls {
+            macro_control.value = round(macro_control.value * 100.0, PERCENT_STEP) / 100.0;
+        }
+
+        for lane in &mut self.lanes {
+            round_decibels(&mut lane.gain);
+            round_ratio(&mut lane.mix);
+        }
+        self.visit_effects_mut(|snapin| {
+            for (_, field) in parameters_mut(snapin.effect.as_parameters_mut()) {
+                round_field(field);
+            }
+        });
+        for generator in &mut self.generators {
+            for (_, field) in parameters_mut(generator.as_parameters_mut()) {
+                round_field(field);
+            }
+        }
+        for container in &mut self.modulator_containers {
+            for (_, field) in parameters_mut(container.modulator.as_parameters_mut()) {
+                round_field(field);
+            }
+        }
+
+        for modulation in &mut self.modulations {
+            round_ratio(&mut modulation.amount);
+            round_ratio(&mut modulation.curve);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::effect::Filter;
+    use crate::generator::AnalogOscillator;
+    use crate::modulation::{ModulationSource, ModulationTarget};
+    use crate::modulator::{LfoModulator, ModulatorContainer};
+    use crate::test::read_modulator_preset;
+
+    use super::*;
+
+    #[test]
+    fn canonicalize() {
+        let mut preset = Preset {
+            master_gain: Decibels::new(-6.000_01).linear(),
+            modulations: vec![
+                Modulation::new(
+                    ModulationSource::ModWheel,
+                    ModulationTarget::default(),
+                    Ratio::new::<percent>(25.0),
+                ),
+                Modulation::new(
+                    ModulationSource::MacroControl(1),
+                    ModulationTarget::default(),
+                    Ratio::new::<percent>(50.0),
+                ),
+                Modulation {
+                    enabled: false,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        preset.lanes[0].mix = Ratio::new::<percent>(49.999_99);
+
+        let mut reordered = preset.clone();
+        reordered.modulations.swap(0, 1);
+        reordered.modulations.pop();
+        reordered.lanes[0].mix = Ratio::new::<percent>(50.0);
+
+        preset.canonicalize();
+        reordered.canonicalize();
+        assert_eq!(preset, reordered);
+        assert_eq!(preset.modulations.len(), 2);
+        assert_relative_eq!(preset.lanes[0].mix.get::<percent>(), 50.0);
+        assert_relative_eq!(
+            Decibels::from_linear(preset.master_gain).db(),
+            -6.0,
+            epsilon = 0.0001
+        );
+    }
+
+    /// The parameters of effects, generators and modulators are rounded in
+    /// the unit they are displayed in.
+    #[test]
+    fn round_parameters() {
+        let preset_with = |values: [&str; 4]| {
+            let mut preset = Preset::default();
+            preset.lanes[0]
+                .snapins
+                .push(Snapin::new(Box::<Filter>::default(), 1, true, false));
+            preset.generators.push(Box::<AnalogOscillator>::default());
+            preset
+                .modulator_containers
+                .push(ModulatorContainer::new(1, Box::<LfoModulator>::default()));
+            for (path, value) in [
+                "lanes.0.snapins.0.cutoff",
+                "generators.0.tuning",
+                "generators.0.unison.spread",
+                "modulators.0.rate.frequency",
+            ]
+            .into_iter()
+            .zip(values)
+            {
+                preset.set(path, value).unwrap();
+            }
+            preset.canonicalize();
+            preset
+        };
+        let preset = preset_with(["1500 Hz", "-7 st", "25%", "4 Hz"]);
+        let nudged = preset_with(["1500.001 Hz", "-7.0001 st", "25.0001%", "3.9999 Hz"]);
+        assert_eq!(preset.diff(&nudged).changes, []);
+        assert_eq!(preset.lanes, nudged.lanes);
+        assert_eq!(preset.generators, nudged.generators);
+        assert_eq!(preset.modulator_containers, nudged.modulator_containers);
+    }
+
+    /// Modulations are sorted by the IDs stored in the preset.
+    #[test]
+    fn modulation_order() {
+        let modulation = |source| {
+            Modulation::new(
+                source,
+                ModulationTarget::default(),
+                Ratio::new::<percent>(25.0),
+            )
+        };
+        let mut preset = Preset {
+            modulations: vec![
+                modulation(ModulationSource::ModWheel),
+                modulation(ModulationSource::Modulator(1)),
+                modulation(ModulationSource::MacroControl(7)),
+            ],
+            ..Default::default()
+        };
+        preset.canonicalize();
+        let sources = preset
+            .modulations
+            .iter()
+            .map(|modulation| modulation.source.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sources,
+            [
+                ModulationSource::MacroControl(7),
+                ModulationSource::Modulator(1),
+                ModulationSource::ModWheel,
+            ]
+        );
+    }
+
+    #[test]
+    fn modulator_groups() {
+        let mut preset = read_modulator_preset("group", "group-2.1.0.phaseplant").unwrap();
+        assert_eq!(preset.modulator_containers.len(), 1);
+        preset.canonicalize();
+        assert!(preset.modulator_containers.is_empty());
+
+        let mut preset =
+            read_modulator_preset("group", "group-3contains_lfo-2.0.14.phaseplant").unwrap();
+        preset.canonicalize();
+        assert_eq!(preset.modulator_containers.len(), 6);
+    }
+}
@@ -0,0 +1,682 @@
+//! Differences between two presets.
+//!
+//! The results can be serialized, such as to JSON with [`PresetDiff::to_json`],
+//! so scripts can consume them. The field names are part of the public API
+//! and will not change without a major version bump.
+//!
+//! Numeric parameters are compared in the units Phase Plant displays, such as
+//! decibels for gain, and have a delta. Effects, generators and modulators of
+//! the same type have a change for each parameter that differs, at the same
+//! path as [`Preset::get`] such as `lanes.0.snapins.1.cutoff` or
+//! `generators.0.unison.voices`. Effects are compared with [`effect_diff`].
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use uom::si::ratio::percent;
+
+use crate::effect::Effect;
+use crate::path::{join, parameters, Field, Parameters};
+use crate::*;
+
+/// Unit of a numeric value.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Unit {
+    /// A plain number, such as a count of voices.
+    Number,
+    Cents,
+    Decibels,
+    Hertz,
+    Percent,
+    Seconds,
+    Semitones,
+}
+
+/// Value of a parameter in one of the presets.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Value {
+    Number { value: f32, unit: Unit },
+    Bool { value: bool },
+    Text { value: String },
+}
+
+/// A parameter that is different between the presets.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Change {
+    /// Location of the parameter, such as `lanes.0.snapins.1.enabled`.
+    pub path: String,
+
+    /// Not set if the parameter was added.
+    pub before: Option<Value>,
+
+    /// Not set if the parameter was removed.
+    pub after: Option<Value>,
+
+    /// The after value minus the before value, in the unit of the values. Only
+    /// set for numeric values.
+    pub delta: Option<f32>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PresetDiff {
+    pub changes: Vec<Change>,
+}
+
+impl PresetDiff {
+    /// Find the parameters that are different between the presets.
+    pub fn new(before: &Preset, after: &Preset) -> Self {
+        let mut diff = Self::default();
+        diff.presets(before, after);
+        diff
+    }
+
+    /// If the presets have no differences.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    fn presets(&mut self, before: &Preset, after: &Preset) {
+        self.text(
+            "metadata.name",
+            &before.metadata.name.clone().unwrap_or_default(),
+            &after.metadata.name.clone().unwrap_or_default(),
+        );
+        self.text(
+            "metadata.author",
+            &before.metadata.author.clone().unwrap_or_default(),
+            &after.metadata.author.clone().unwrap_or_default(),
+        );
+        self.text(
+            "metadata.description",
+            &before.metadata.description.clone().unwrap_or_default(),
+            &after.metadata.description.clone().unwrap_or_default(),
+        );
+        self.text(
+            "metadata.category",
+            &before.metadata.category.clone().unwrap_or_default(),
+            &after.metadata.category.clone().unwrap_or_default(),
+        );
+
+        self.number(
+            "master_gain",
+            Decibels::from_linear(before.master_gain).db(),
+            Decibels::from_linear(after.master_gain).db(),
+            Unit::Decibels,
+        );
+        self.number(
+            "master_pitch",
+            before.master_pitch,
+            after.master_pitch,
+            Unit::Semitones,
+        );
+        self.number(
+            "polyphony",
+            before.polyphony as f32,
+            after.polyphony as f32,
+            Unit::Number,
+        );
+        self.percent(
+            "mod_wheel_value",
+            before.mod_wheel_value,
+            after.mod_wheel_value,
+        );
+        self.bool(
+            "retrigger_enabled",
+            before.retrigger_enabled,
+            after.retrigger_enabled,
+        );
+        self.bool("glide_enabled", before.glide_enabled, after.glide_enabled);
+        self.bool("glide_legato", before.glide_legato, after.glide_legato);
+        self.number(
+            "glide_time",
+            before.glide_time,
+            after.glide_time,
+            Unit::Seconds,
+        );
+        self.parameters("unison", &before.unison, &after.unison);
+        self.parameters(
+            "macro_controls",
+            &before.macro_controls,
+            &after.macro_controls,
+        );
+
+        for (index, (before, after)) in before.lanes.iter().zip(&after.lanes).enumerate() {
+            self.lane(&format!("lanes.{index}"), before, after);
+        }
+
+        self.list(
+            "generators",
+            &before.generators,
+            &after.generators,
+            |generator| generator.mode().to_string(),
+            |diff, path, before, after| {
+                if before.mode() != after.mode() {
+                    diff.text(
+                        &format!("{path}.mode"),
+                        &before.mode().to_string(),
+                        &after.mode().to_string(),
+                    );
+                    return;
+                }
+                diff.parameters(path, before.as_parameters(), after.as_parameters());
+            },
+        );
+
+        self.list(
+            "modulators",
+            &before.modulator_containers,
+            &after.modulator_containers,
+            |container| container.modulator.mode().to_string(),
+            |diff, path, before, after| {
+                if before.modulator.mode() != after.modulator.mode() {
+                    diff.text(
+                        &format!("{path}.mode"),
+                        &before.modulator.mode().to_string(),
+                        &after.modulator.mode().to_string(),
+                    );
+                    return;
+                }
+                diff.bool(&format!("{path}.enabled"), before.enabled, after.enabled);
+                diff.bool(
+                    &format!("{path}.minimized"),
+                    before.minimized,
+                    after.minimized,
+                );
+                diff.parameters(
+                    path,
+                    before.modulator.as_parameters(),
+                    after.modulator.as_parameters(),
+                );
+            },
+        );
+
+        self.list(
+            "modulations",
+            &before.modulations,
+            &after.modulations,
+            |modulation| modulation.to_string(),
+            |diff, path, before, after| {
+                diff.text(
+                    &format!("{path}.source"),
+                    &before.source.to_string(),
+                    &after.source.to_string(),
+                );
+                diff.text(
+                    &format!("{path}.target"),
+                    &before.target.to_string(),
+                    &after.target.to_string(),
+                );
+                diff.bool(&format!("{path}.enabled"), before.enabled, after.enabled);
+                diff.percent(&format!("{path}.amount"), before.amount, after.amount);
+                diff.percent(&format!("{path}.curve"), before.curve, after.curve);
+            },
+        );
+    }
+
+    fn lane(&mut self, path: &str, before: &Lane, after: &Lane) {
+        self.bool(&format!("{path}.enabled"), before.enabled, after.enabled);
+        self.text(
+            &format!("{path}.destination"),
+            &before.destination.to_string(),
+            &after.destination.to_string(),
+        );
+        self.number(
+            &format!("{path}.poly_count"),
+            before.poly_count as f32,
+            after.poly_count as f32,
+            Unit::Number,
+        );
+        self.bool(&format!("{path}.mute"), before.mute, after.mute);
+        self.bool(&format!("{path}.solo"), before.solo, after.solo);
+        self.number(
+            &format!("{path}.gain"),
+            before.gain.db(),
+            after.gain.db(),
+            Unit::Decibels,
+        );
+        self.percent(&format!("{path}.mix"), before.mix, after.mix);
+        self.snapins(&format!("{path}.snapins"), &before.snapins, &after.snapins);
+    }
+
+    fn snapins(&mut self, path: &str, before: &[Snapin], after: &[Snapin]) {
+        self.list(
+            path,
+            before,
+            after,
+            |snapin| snapin.effect.mode().to_string(),
+            |diff, path, before, after| {
+                if before.effect.mode() != after.effect.mode() {
+                    diff.text(
+                        &format!("{path}.mode"),
+                        &before.effect.mode().to_string(),
+                        &after.effect.mode().to_string(),
+                    );
+                    return;
+                }
+                diff.bool(&format!("{path}.enabled"), before.enabled, after.enabled);
+                diff.bool(
+                    &format!("{path}.minimized"),
+                    before.minimized,
+                    after.minimized,
+                );
+                diff.text(
+                    &format!("{path}.preset_name"),
+                    &before.preset_name,
+                    &after.preset_name,
+                );
+                diff.effect(path, before.effect.as_ref(), after.effect.as_ref());
+                if let (Some(before), Some(after)) =
+                    (before.effect.as_multipass(), after.effect.as_multipass())
+                {
+                    for (index, (before, after)) in
+                        before.lanes.iter().zip(&after.lanes).enumerate()
+                    {
+                        diff.snapins(
+                            &format!("{path}.lanes.{index}.snapins"),
+                            &before.snapins,
+                            &after.snapins,
+                        );
+                    }
+                }
+            },
+        );
+    }
+
+    /// Compare items in the same position of two lists. Items that only exist
+    /// in one of the lists are added or removed.
+    fn list<T>(
+        &mut self,
+        path: &str,
+        before: &[T],
+        after: &[T],
+        describe: impl Fn(&T) -> String,
+        mut compare: impl FnMut(&mut Self, &str, &T, &T),
+    ) {
+        for index in 0..before.len().max(after.len()) {
+            let item_path = format!("{path}.{index}");
+            match (before.get(index), after.get(index)) {
+                (Some(before), Some(after)) => compare(self, &item_path, before, after),
+                (before, after) => self.changes.push(Change {
+                    path: item_path,
+                    before: before.map(|item| Value::Text {
+                        value: describe(item),
+                    }),
+                    after: after.map(|item| Value::Text {
+                        value: describe(item),
+                    }),
+                    delta: None,
+                }),
+            }
+        }
+    }
+
+    fn push(&mut self, path: &str, before: Value, after: Value, delta: Option<f32>) {
+        self.changes.push(Change {
+            path: path.to_owned(),
+            before: Some(before),
+            after: Some(after),
+            delta,
+        });
+    }
+
+    fn number(&mut self, path: &str, before: f32, after: f32, unit: Unit) {
+        if before != after {
+            self.push(
+                path,
+                Value::Number {
+                    value: before,
+                    unit,
+                },
+                Value::Number { value: after, unit },
+                // Gains turned all the way down are negative infinity
+                // decibels, which has no difference.
+                Some(after - before).filter(|delta| delta.is_finite()),
+            );
+        }
+    }
+
+    fn percent(&mut self, path: &str, before: Ratio, after: Ratio) {
+        self.number(
+            path,
+            before.get::<percent>(),
+            after.get::<percent>(),
+            Unit::Percent,
+        );
+    }
+
+    fn bool(&mut self, path: &str, before: bool, after: bool) {
+        if before != after {
+            self.push(
+                path,
+                Value::Bool { value: before },
+                Value::Bool { value: after },
+                None,
+            );
+        }
+    }
+
+    fn text(&mut self, path: &str, before: &str, after: &str) {
+        if before != after {
+            self.push(
+                path,
+                Value::Text {
+                    value: before.to_owned(),
+                },
+                Value::Text {
+                    value: after.to_owned(),
+                },
+                None,
+            );
+        }
+    }
+
+    fn effect(&mut self, path: &str, before: &dyn Effect, after: &dyn Effect) {
+        if before.mode() != after.mode() {
+            self.text(
+                &join(path, "mode"),
+                &before.mode().to_string(),
+                &after.mode().to_string(),
+            );
+        } else if !before.box_eq(after.as_any()) {
+            self.parameters(path, before.as_parameters(), after.as_parameters());
+        }
+    }
+
+    /// Compare each parameter of two values of the same type. Parameters in
+    /// only one of them, such as a point added to a curve, were added or
+    /// removed.
+    pub(crate) fn parameters(
+        &mut self,
+        path: &str,
+        before: &dyn Parameters,
+        after: &dyn Parameters,
+    ) {
+        let before = parameters(before);
+        let mut after: BTreeMap<String, Field> = parameters(after).into_iter().collect();
+        for (name, before) in &before {
+            match after.remove(name) {
+                Some(after) if after.is_same(before) => {}
+                Some(after) => {
+                    let delta = match (before.value(), after.value()) {
+                        (
+                            Value::Number { value: before, .. },
+                            Value::Number { value: after, .. },
+                        ) => Some(after - before).filter(|delta| delta.is_finite()),
+                        _ => None,
+                    };
+                    self.changes.push(Change {
+                        path: join(path, name),
+                        before: Some(before.value()),
+                        after: Some(after.value()),
+                        delta,
+                    });
+                }
+                None => self.changes.push(Change {
+                    path: join(path, name),
+                    before: Some(before.value()),
+                    after: None,
+                    delta: None,
+                }),
+            }
+        }
+        for (name, after) in after {
+            self.changes.push(Change {
+                path: join(path, &name),
+                before: None,
+                after: Some(after.value()),
+                delta: None,
+            });
+        }
+    }
+}
+
+/// A parameter that is different between two effects. The path is the name
+/// of the field of the effect, such as `cutoff` or
+/// `patterns.0.steps.3.enabled`.
+pub type ParamDelta = Change;
+
+/// Compare two effects parameter by parameter, in the units Phase Plant
+/// displays. Effects of different types only differ in their `mode`.
+///
+/// ```
+/// use synthahol_phase_plant::diff::{effect_diff, Value};
+/// use synthahol_phase_plant::effect::Filter;
+///
+/// let before = Filter::default();
+/// let after = Filter {
+///     q: before.q * 2.0,
+///     ..Filter::default()
+/// };
+/// let deltas = effect_diff(&before, &after);
+/// assert_eq!(deltas[0].path, "q");
+/// assert_eq!(deltas[0].delta, Some(before.q));
+/// ```
+pub fn effect_diff(a: &dyn Effect, b: &dyn Effect) -> Vec<ParamDelta> {
+    let mut diff = PresetDiff::default();
+    diff.effect("", a, b);
+    diff.changes
+}
+
+impl Preset {
+    /// Find the parameters that are different in the other preset.
+    pub fn diff(&self, other: &Preset) -> PresetDiff {
+        PresetDiff::new(self, other)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use uom::si::frequency::hertz;
+
+    use crate::effect::{Filter, Gain, SliceEq, SliceEqFilter, TranceGate};
+    use crate::generator::AnalogOscillator;
+    use crate::modulator::{LfoModulator, ModulatorContainer};
+    use crate::test::read_preset;
+
+    use super::*;
+
+    #[test]
+    fn identical() {
+        let preset = read_preset("misc", "master-gain-+3db-1.8.13.phaseplant");
+        assert!(preset.diff(&preset).is_empty());
+    }
+
+    #[test]
+    fn master_gain() {
+        let before = read_preset("misc", "master-gain-+3db-1.8.13.phaseplant");
+        let after = read_preset("misc", "master-gain-+10db-1.8.13.phaseplant");
+        let diff = before.diff(&after);
+        let change = diff
+            .changes
+            .iter()
+            .find(|change| change.path == "master_gain")
+            .unwrap();
+        assert_relative_eq!(change.delta.unwrap(), 7.0, epsilon = 0.01);
+
+        let json = diff.to_json().unwrap();
+        assert!(json.contains(r#""path": "master_gain""#));
+        assert!(json.contains(r#""unit": "decibels""#));
+
+        let silent = read_preset("misc", "master-gain--inf-1.8.13.phaseplant");
+        let diff = silent.diff(&after);
+        let change = diff
+            .changes
+            .iter()
+            .find(|change| change.path == "master_gain")
+            .unwrap();
+        assert!(change.delta.is_none());
+    }
+
+    #[test]
+    fn snapins() {
+        let before = Preset::default();
+        let mut after = Preset::default();
+        after.lanes[1]
+            .snapins
+            .push(Snapin::new(Box::<Filter>::default(), 1, true, false));
+        let diff = before.diff(&after);
+        assert_eq!(
+            diff.changes,
+            [Change {
+                path: "lanes.1.snapins.0".to_owned(),
+                before: None,
+                after: Some(Value::Text {
+                    value: "Filter".to_owned()
+                }),
+                delta: None,
+            }]
+        );
+
+        let mut before = after;
+        let mut after = Preset::default();
+        after.lanes[1].snapins.push(Snapin::new(
+            Box::new(Filter {
+                q: 2.0,
+                ..Filter::default()
+            }),
+            1,
+            false,
+            false,
+        ));
+        before.generators.push(Box::<AnalogOscillator>::default());
+        after.generators.push(Box::new(AnalogOscillator {
+            harmonic: 2.0,
+            ..AnalogOscillator::default()
+        }));
+        let diff = before.diff(&after);
+        let paths: Vec<_> = diff
+            .changes
+            .iter()
+            .map(|change| change.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "lanes.1.snapins.0.enabled",
+                "lanes.1.snapins.0.q",
+                "generators.0.harmonic"
+            ]
+        );
+
+        // The paths of effect parameters can be read from the preset.
+        let change = &diff.changes[1];
+        assert_eq!(after.get(&change.path).ok(), change.after);
+        assert_eq!(change.delta, Some(2.0 - Filter::default().q));
+    }
+
+    #[test]
+    fn generators_and_modulators() {
+        let mut before = Preset::default();
+        before.generators.push(Box::<AnalogOscillator>::default());
+        before
+            .modulator_containers
+            .push(ModulatorContainer::new(1, Box::<LfoModulator>::default()));
+        let mut after = before.clone();
+        after.set("generators.0.unison.mode", "Shepard").unwrap();
+        after.set("modulators.0.rate.frequency", "4 Hz").unwrap();
+        after.set("macro_controls.1.polarity", "Bipolar").unwrap();
+        after.unison.detune_cents += 5.0;
+
+        let diff = before.diff(&after);
+        let paths: Vec<_> = diff
+            .changes
+            .iter()
+            .map(|change| change.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "unison.detune",
+                "macro_controls.1.polarity",
+                "generators.0.unison.mode",
+                "modulators.0.rate.frequency"
+            ]
+        );
+        assert_eq!(diff.changes[0].delta, Some(5.0));
+        for change in &diff.changes {
+            assert_eq!(after.get(&change.path).ok(), change.after);
+        }
+        assert_eq!(
+            diff.changes[2].before,
+            Some(Value::Text {
+                value: Unison::default().mode.to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn effect_parameters() {
+        let before = Filter::default();
+        assert!(effect_diff(&before, &before).is_empty());
+        let after = Filter {
+            cutoff: before.cutoff * 2.0,
+            gain: Decibels::new(-3.0),
+            ..Filter::default()
+        };
+        let deltas = effect_diff(&before, &after);
+        let paths: Vec<_> = deltas.iter().map(|delta| delta.path.as_str()).collect();
+        assert_eq!(paths, ["cutoff", "gain"]);
+        assert_relative_eq!(
+            deltas[0].delta.unwrap(),
+            before.cutoff.get::<hertz>(),
+            epsilon = 0.01
+        );
+        assert_relative_eq!(deltas[1].delta.unwrap(), -9.0);
+
+        let before = TranceGate::default();
+        let mut after = before.clone();
+        after.patterns[0].steps[3].enabled = !before.patterns[0].steps[3].enabled;
+        after.patterns[1].set_step_count(20).unwrap();
+        let deltas = effect_diff(&before, &after);
+        let paths: Vec<_> = deltas.iter().map(|delta| delta.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            ["patterns.0.steps.3.enabled", "patterns.1.step_count"]
+        );
+        assert!(deltas[0].delta.is_none());
+        assert_eq!(deltas[1].delta, Some(4.0));
+
+        assert_eq!(
+            deltas[0].after,
+            Some(Value::Bool {
+                value: after.patterns[0].steps[3].enabled
+            })
+        );
+
+        assert_eq!(
+            effect_diff(&Filter::default(), &Gain::default()),
+            [ParamDelta {
+                path: "mode".to_owned(),
+                before: Some(Value::Text {
+                    value: "Filter".to_owned()
+                }),
+                after: Some(Value::Text {
+                    value: "Gain".to_owned()
+                }),
+                delta: None,
+            }]
+        );
+
+        // Every filter of a Slice EQ is compared, including those added.
+        let before = SliceEq {
+            filters: vec![SliceEqFilter::default()],
+            ..SliceEq::default()
+        };
+        let mut after = before.clone();
+        after.filters.push(SliceEqFilter::default());
+        after.filters[0].q *= 2.0;
+        let deltas = effect_diff(&before, &after);
+        assert_eq!(deltas[0].path, "filters.0.q");
+        assert!(deltas[1..].iter().all(|delta| delta.before.is_none()
+            && delta
+                .path
+                .starts_with(&format!("filters.{}.", before.filters.len()))));
+    }
+}
@@ -0,0 +1,359 @@
+//! Edits to a preset that can be applied, reverted and replayed.
+//!
+//! Each [`Command`] holds what it replaces so it can be reverted without the
+//! preset it was made from. A list of commands is a log of the edits that
+//! can be applied to a copy of the original preset to get the same result.
+//! [`Tracked`](crate::tracked::Tracked) keeps the commands for undo and redo.
+//!
+//! ```
+//! use synthahol_phase_plant::edit::Command;
+//! use synthahol_phase_plant::Preset;
+//!
+//! let mut preset = Preset::default();
+//! let command = Command::set_parameter(&preset, "lanes.0.mix", "25%").unwrap();
+//! preset.apply(&command).unwrap();
+//! assert_eq!(preset.get("lanes.0.mix").unwrap().to_string(), "25%");
+//! preset.revert(&command).unwrap();
+//! assert_eq!(preset.get("lanes.0.mix").unwrap().to_string(), "100%");
+//! ```
+
+use std::io::{Error, ErrorKind};
+
+use crate::diff::{Change, Value};
+use crate::modulation::{Modulation, MODULATIONS_MAX};
+pub use crate::path::StoredValue;
+use crate::{Preset, Snapin};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    /// Change the parameter at a [path](Preset::set). The values are kept
+    /// as they are stored so reverting restores the parameter exactly.
+    SetParameter {
+        path: String,
+        before: StoredValue,
+        after: StoredValue,
+    },
+
+    /// Insert a snapin into a lane at a position in the chain. Modulations
+    /// of the snapins after it aren't changed.
+    AddSnapin {
+        lane: usize,
+        position: usize,
+        snapin: Snapin,
+    },
+
+    RemoveSnapin {
+        lane: usize,
+        position: usize,
+        snapin: Snapin,
+    },
+
+    AddModulation {
+        index: usize,
+        modulation: Modulation,
+    },
+
+    RemoveModulation {
+        index: usize,
+        modulation: Modulation,
+    },
+}
+
+impl Command {
+    /// Change the parameter at the path to the value, which is parsed the
+    /// same way as by [`Preset::set`]. Fails if the value doesn't fit the
+    /// parameter.
+    pub fn set_parameter(preset: &Preset, path: &str, value: &str) -> Result<Self, Error> {
+        Ok(Self::SetParameter {
+            path: path.to_owned(),
+            before: preset.stored(path)?,
+            after: preset.parse(path, value)?,
+        })
+    }
+
+    pub fn remove_snapin(preset: &Preset, lane: usize, position: usize) -> Result<Self, Error> {
+        let snapin = preset
+            .lanes
+            .get(lane)
+            .and_then(|lane| lane.snapins.get(position))
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Lane {lane} does not have a snapin at position {position}"),
+                )
+            })?;
+        Ok(Self::RemoveSnapin {
+            lane,
+            position,
+            snapin: snapin.clone(),
+        })
+    }
+
+    pub fn remove_modulation(preset: &Preset, index: usize) -> Result<Self, Error> {
+        let modulation = preset.modulations.get(index).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("The preset does not have modulation {index}"),
+            )
+        })?;
+        Ok(Self::RemoveModulation {
+            index,
+            modulation: modulation.clone(),
+        })
+    }
+
+    /// The command that undoes this one.
+    #[must_use]
+    pub fn inverse(&self) -> Self {
+        match self.clone() {
+            Self::SetParameter {
+                path,
+                before,
+                after,
+            } => Self::SetParameter {
+                path,
+                before: after,
+                after: before,
+            },
+            Self::AddSnapin {
+                lane,
+                position,
+                snapin,
+            } => Self::RemoveSnapin {
+                lane,
+                position,
+                snapin,
+            },
+            Self::RemoveSnapin {
+                lane,
+                position,
+                snapin,
+            } => Self::AddSnapin {
+                lane,
+                position,
+                snapin,
+            },
+            Self::AddModulation { index, modulation } => {
+                Self::RemoveModulation { index, modulation }
+            }
+            Self::RemoveModulation { index, modulation } => {
+                Self::AddModulation { index, modulation }
+            }
+        }
+    }
+
+    /// Path of what the command changes, such as `lanes.0.mix` or
+    /// `lanes.1.snapins`.
+    pub fn target(&self) -> String {
+        match self {
+            Self::SetParameter { path, .. } => path.clone(),
+            Self::AddSnapin { lane, .. } | Self::RemoveSnapin { lane, .. } => {
+                format!("lanes.{lane}.snapins")
+            }
+            Self::AddModulation { .. } | Self::RemoveModulation { .. } => "modulations".to_owned(),
+        }
+    }
+
+    /// The change to the parameter, if the command sets one.
+    pub fn change(&self) -> Option<Change> {
+        let Self::SetParameter {
+            path,
+            before,
+            after,
+        } = self
+        else {
+            return None;
+        };
+        let (before, after) = (before.value(), after.value());
+        let delta = match (&before, &after) {
+            (Value::Number { value: before, .. }, Value::Number { value: after, .. }) => {
+                Some(after - before).filter(|delta| delta.is_finite())
+            }
+            _ => None,
+        };
+        Some(Change {
+            path: path.clone(),
+            before: Some(before),
+            after: Some(after),
+            delta,
+        })
+    }
+}
+
+impl Preset {
+    /// Make the edit. Fails without changing the preset if the command
+    /// doesn't fit it, such as removing a snapin that isn't there.
+    pub fn apply(&mut self, command: &Command) -> Result<(), Error> {
+        match command {
+            Command::SetParameter { path, after, .. } => self.restore(path, after),
+            Command::AddSnapin {
+                lane,
+                position,
+                snapin,
+            } => {
+                let snapins = self.lane_snapins_mut(*lane)?;
+                if *position > snapins.len() {
+                    return Err(invalid_command(command, "the lane is shorter"));
+                }
+                if snapins.iter().any(|existing| existing.id == snapin.id) {
+                    return Err(invalid_command(command, "the ID is in use"));
+                }
+                snapins.insert(*position, snapin.clone());
+                Ok(())
+            }
+            Command::RemoveSnapin {
+                lane,
+                position,
+                snapin,
+            } => {
+                let snapins = self.lane_snapins_mut(*lane)?;
+                if snapins.get(*position) != Some(snapin) {
+                    return Err(invalid_command(command, "the snapin is different"));
+                }
+                snapins.remove(*position);
+                Ok(())
+            }
+            Command::AddModulation { index, modulation } => {
+                if *index > self.modulations.len() {
+                    return Err(invalid_command(command, "there are fewer modulations"));
+                }
+                if self.modulations.len() >= MODULATIONS_MAX {
+                    return Err(invalid_command(command, "every modulation slot is used"));
+                }
+                self.modulations.insert(*index, modulation.clone());
+                Ok(())
+            }
+            Command::RemoveModulation { index, modulation } => {
+                if self.modulations.get(*index) != Some(modulation) {
+                    return Err(invalid_command(command, "the modulation is different"));
+                }
+                self.modulations.remove(*index);
+                Ok(())
+            }
+        }
+    }
+
+    /// Undo an edit that was [applied](Self::apply).
+    pub fn revert(&mut self, command: &Command) -> Result<(), Error> {
+        self.apply(&command.inverse())
+    }
+
+    fn lane_snapins_mut(&mut self, lane: usize) -> Result<&mut Vec<Snapin>, Error> {
+        self.lanes
+            .get_mut(lane)
+            .map(|lane| &mut lane.snapins)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The preset does not have lane {lane}"),
+                )
+            })
+    }
+}
+
+fn invalid_command(command: &Command, reason: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("Can't change {} because {reason}", command.target()),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use uom::si::f32::Ratio;
+    use uom::si::ratio::percent;
+
+    use crate::effect::Gain;
+    use crate::modulation::{HostParameter, ModulationSource, ModulationTarget, RateMode};
+
+    use super::*;
+
+    #[test]
+    fn apply_and_revert() {
+        let original = Preset::default();
+        let mut preset = original.clone();
+        let gain = Snapin::new(Box::new(Gain::default()), 1, true, false);
+        let modulation = Modulation::new(
+            ModulationSource::ModWheel,
+            ModulationTarget::Host {
+                parameter: HostParameter::MasterGain,
+                rate_mode: RateMode::Control,
+            },
+            Ratio::new::<percent>(50.0),
+        );
+        let log = [
+            Command::set_parameter(&preset, "master_pitch", "2 st").unwrap(),
+            Command::AddSnapin {
+                lane: 1,
+                position: 0,
+                snapin: gain.clone(),
+            },
+            Command::AddModulation {
+                index: 0,
+                modulation: modulation.clone(),
+            },
+        ];
+        for command in &log {
+            preset.apply(command).unwrap();
+        }
+        assert_eq!(preset.lanes[1].snapins, std::slice::from_ref(&gain));
+        assert_eq!(preset.modulations, [modulation]);
+
+        // Replaying the log on another copy gives the same preset.
+        let mut replayed = original.clone();
+        log.iter()
+            .for_each(|command| replayed.apply(command).unwrap());
+        assert_eq!(replayed, preset);
+
+        // Commands that don't fit the preset don't change it.
+        let remove = Command::remove_snapin(&preset, 1, 0).unwrap();
+        assert!(preset.apply(&log[1]).is_err());
+        assert!(Command::remove_snapin(&preset, 1, 1).is_err());
+        assert!(Command::remove_modulation(&preset, 1).is_err());
+        assert_eq!(replayed, preset);
+
+        preset.apply(&remove).unwrap();
+        assert!(preset.apply(&remove).is_err());
+        preset.revert(&remove).unwrap();
+        for command in log.iter().rev() {
+            preset.revert(command).unwrap();
+        }
+        assert_eq!(preset, original);
+        assert_eq!(log[1].target(), "lanes.1.snapins");
+        assert_eq!(log[0].change().unwrap().delta, Some(2.0));
+        assert!(log[2].change().is_none());
+    }
+
+    #[test]
+    fn set_parameter() {
+        let original = Preset {
+            master_gain: 0.123_456_7,
+            ..Default::default()
+        };
+        let mut preset = original.clone();
+        let command = Command::set_parameter(&preset, "master_gain", "-6 dB").unwrap();
+        preset.apply(&command).unwrap();
+        assert_eq!(preset.get("master_gain").unwrap().to_string(), "-6 dB");
+
+        // The gain is restored exactly rather than from its decibels.
+        preset.revert(&command).unwrap();
+        assert_eq!(preset, original);
+
+        let change = command.change().unwrap();
+        assert_eq!(change.after.unwrap().to_string(), "-6 dB");
+        assert!(Command::set_parameter(&preset, "master_gain", "440 Hz").is_err());
+        assert!(Command::set_parameter(&preset, "unknown", "1").is_err());
+
+        // A value stored from another parameter doesn't fit.
+        let Command::SetParameter { after, .. } = command else {
+            unreachable!();
+        };
+        let misplaced = Command::SetParameter {
+            path: "polyphony".to_owned(),
+            before: preset.stored("polyphony").unwrap(),
+            after,
+        };
+        assert!(preset.apply(&misplaced).is_err());
+        assert_eq!(preset, original);
+    }
+}
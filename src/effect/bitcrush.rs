@@ -8,20 +8,21 @@
 //! | 2.0.12              | 1048           |
 //! | 2.0.16              | 1049           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::num::Zero;
 use uom::si::f32::{Frequency, Ratio};
 use uom::si::frequency::hertz;
 use uom::si::ratio::percent;
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::Interpolate;
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Bitcrush {
@@ -38,10 +39,6 @@ impl Bitcrush {
     pub fn new() -> Self {
         Self::default()
     }
-
-    pub fn default_version() -> EffectVersion {
-        1049
-    }
 }
 
 impl Default for Bitcrush {
@@ -58,25 +55,49 @@ impl Default for Bitcrush {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_bitcrush(&self) -> Option<&Bitcrush> {
-        self.downcast_ref::<Bitcrush>()
+impl Interpolate for Bitcrush {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            frequency: self.frequency.interpolate(&other.frequency, t),
+            quantize: self.quantize.interpolate(&other.quantize, t),
+            bits: self.bits.interpolate(&other.bits, t),
+            dither: self.dither.interpolate(&other.dither, t),
+            adc_quality: self.adc_quality.interpolate(&other.adc_quality, t),
+            dac_quality: self.dac_quality.interpolate(&other.dac_quality, t),
+            mix: self.mix.interpolate(&other.mix, t),
+        }
     }
 }
 
-impl Effect for Bitcrush {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Bitcrush {
+    frequency,
+    quantize,
+    bits,
+    dither,
+    adc_quality,
+    dac_quality,
+    mix,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Bitcrush
+impl Bitcrush {
+    /// The settings when the effect is added in Phase Plant, which stores a
+    /// frequency of about 5997 Hz rather than 6 kHz.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            frequency: Frequency::new::<hertz>(5997.206),
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(
+    Bitcrush,
+    as_bitcrush,
+    init_preset = custom,
+    default_version = 1049
+);
+
 impl EffectRead for Bitcrush {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -92,7 +113,10 @@ impl EffectRead for Bitcrush {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let frequency = reader.read_hertz()?;
 
         let bits = reader.read_f32()?;
@@ -108,16 +132,14 @@ impl EffectRead for Bitcrush {
         let dither = reader.read_ratio()?;
         let quantize = reader.read_ratio()?;
         let mix = reader.read_ratio()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "bitcrush_unknown_1")?;
         reader.expect_u32(0, "bitcrush_unknown_2")?;
 
-        let group_id = if effect_version >= 1047 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1047 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         let effect = Box::new(Bitcrush {
             frequency,
@@ -129,7 +151,7 @@ impl EffectRead for Bitcrush {
             mix,
         });
 
-        Ok(EffectReadReturn::new(effect, enabled, minimized, group_id))
+        Ok(EffectReadReturn::new(effect, state))
     }
 }
 
@@ -137,9 +159,10 @@ impl EffectWrite for Bitcrush {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_hertz(self.frequency)?;
         writer.write_f32(self.bits)?;
         writer.write_ratio(self.adc_quality)?;
@@ -147,13 +170,13 @@ impl EffectWrite for Bitcrush {
         writer.write_ratio(self.dither)?;
         writer.write_ratio(self.quantize)?;
         writer.write_ratio(self.mix)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?;
         writer.write_u32(0)?;
 
-        if snapin.effect_version >= 1048 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version >= 1047 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -164,7 +187,7 @@ impl EffectWrite for Bitcrush {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
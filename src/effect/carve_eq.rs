@@ -10,28 +10,29 @@
 
 // Phase Plant 1.8.14 added saving the zoom and pan settings of the view.
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
 use log::trace;
-use strum_macros::FromRepr;
+use strum_macros::{EnumIter, FromRepr};
 use uom::num::Zero;
 use uom::si::f32::{Frequency, Ratio};
 use uom::si::frequency::hertz;
 use uom::si::ratio::percent;
 
 use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 use crate::version::Version;
-use crate::{Decibels, Snapin};
+use crate::{Decibels, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 pub type CarveEqShape = [[f32; CarveEq::BAND_COUNT]; CarveEq::CHANNEL_COUNT];
 
-#[derive(Clone, Copy, Debug, FromRepr, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum StereoMode {
     // The discriminants correspond to the file format.
@@ -56,7 +57,9 @@ impl Display for StereoMode {
     }
 }
 
-#[derive(Clone, Copy, Debug, FromRepr, Eq, PartialEq)]
+impl_parameters!(StereoMode);
+
+#[derive(Clone, Copy, Debug, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum FalloffSpeed {
     // The discriminants correspond to the file format.
@@ -89,7 +92,9 @@ impl Display for FalloffSpeed {
     }
 }
 
-#[derive(Clone, Copy, Debug, FromRepr, Eq, PartialEq)]
+impl_parameters!(FalloffSpeed);
+
+#[derive(Clone, Copy, Debug, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum FrequencyResolution {
     // The discriminants correspond to the file format.
@@ -122,6 +127,8 @@ impl Display for FrequencyResolution {
     }
 }
 
+impl_parameters!(FrequencyResolution);
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct SpectrumView {
     pub frequency_resolution: FrequencyResolution,
@@ -165,6 +172,28 @@ impl Default for SpectrumView {
     }
 }
 
+impl Interpolate for SpectrumView {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            frequency_resolution: step(&self.frequency_resolution, &other.frequency_resolution, t),
+            falloff_speed: step(&self.falloff_speed, &other.falloff_speed, t),
+            x_min: self.x_min.interpolate(&other.x_min, t),
+            x_max: self.x_max.interpolate(&other.x_max, t),
+            y_min: self.y_min.interpolate(&other.y_min, t),
+            y_max: self.y_max.interpolate(&other.y_max, t),
+        }
+    }
+}
+
+impl_parameters!(SpectrumView {
+    frequency_resolution,
+    falloff_speed,
+    x_min,
+    x_max,
+    y_min,
+    y_max,
+});
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct CarveEq {
     pub gain: Decibels,
@@ -177,10 +206,6 @@ pub struct CarveEq {
 impl CarveEq {
     pub const BAND_COUNT: usize = 31;
     pub const CHANNEL_COUNT: usize = 2;
-
-    pub fn default_version() -> EffectVersion {
-        1034
-    }
 }
 
 impl Default for CarveEq {
@@ -200,24 +225,27 @@ impl Default for CarveEq {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_carve_eq(&self) -> Option<&CarveEq> {
-        self.downcast_ref::<CarveEq>()
+impl Interpolate for CarveEq {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            gain: self.gain.interpolate(&other.gain, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            stereo_mode: step(&self.stereo_mode, &other.stereo_mode, t),
+            spectrum_view: self.spectrum_view.interpolate(&other.spectrum_view, t),
+            shape: self.shape.interpolate(&other.shape, t),
+        }
     }
 }
 
-impl Effect for CarveEq {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(CarveEq {
+    gain,
+    mix,
+    stereo_mode,
+    spectrum_view,
+    shape,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::CarveEq
-    }
-}
+impl_effect!(CarveEq, as_carve_eq, default_version = 1034);
 
 impl EffectRead for CarveEq {
     fn read<R: Read + Seek>(
@@ -234,6 +262,8 @@ impl EffectRead for CarveEq {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         // Length to the preset name.
         let header_length = reader.read_u32()?;
         let header_start_pos = reader.stream_position()?;
@@ -293,9 +323,13 @@ impl EffectRead for CarveEq {
         reader.expect_u8(0, "carve_eq_path_1")?;
 
         let mix = reader.read_ratio()?;
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
-        trace!("carve eq: mix {mix:?} enabled {enabled} minimized {minimized}");
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
+        trace!(
+            "carve eq: mix {mix:?} enabled {} minimized {}",
+            state.enabled,
+            state.minimized
+        );
 
         let mut shape = [[0_f32; Self::BAND_COUNT]; Self::CHANNEL_COUNT];
         for band_idx in 0..Self::BAND_COUNT {
@@ -340,12 +374,11 @@ impl EffectRead for CarveEq {
 
         reader.expect_u32(0, "carve_eq_unknown_22")?;
 
-        let mut group_id = None;
         if effect_version < 1023 {
             reader.expect_u32(0, "carve_eq_unknown_23")?;
             reader.expect_u32(0, "carve_eq_unknown_24")?;
         } else if effect_version >= 1034 {
-            group_id = reader.read_snapin_position()?;
+            state.group_id = reader.read_snapin_position()?;
         }
 
         let effect = Box::new(CarveEq {
@@ -357,9 +390,7 @@ impl EffectRead for CarveEq {
         });
         Ok(EffectReadReturn {
             effect,
-            enabled,
-            minimized,
-            group_id,
+            state,
             metadata: Default::default(),
             preset_name,
             preset_path,
@@ -371,25 +402,15 @@ impl EffectRead for CarveEq {
 impl EffectWrite for CarveEq {
     fn write<W: Write + Seek>(
         &self,
-        writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        _writer: &mut PhasePlantWriter<W>,
+        _state: &SnapinState,
+        _effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool8(snapin.enabled)?; // FIXME: Duplicate
-        writer.write_u8(0)?;
-        writer.write_u8(0)?;
-        writer.write_u32(0)?;
-        writer.write_u32(0)?;
-        writer.write_u32(0)?;
-        writer.write_u8(0)?;
-        writer.write_ratio(self.mix)?;
-        writer.write_f32(self.gain.db())?;
-        writer.write_bool32(snapin.enabled)?;
-
-        for _ in 0..32 {
-            writer.skip(19)?;
-        }
-
-        Ok(())
+        // The layout of the bands is not known well enough to write them.
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "Writing Carve EQ is not supported",
+        ))
     }
 }
 
@@ -397,7 +418,7 @@ impl EffectWrite for CarveEq {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
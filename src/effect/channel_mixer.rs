@@ -7,16 +7,16 @@
 //! |---------------------|----------------|
 //! | 2.0.16              | 1002           |
 
-use crate::effect::EffectVersion;
-use std::any::Any;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 use std::ops::RangeInclusive;
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::Interpolate;
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ChannelMixer {
@@ -29,10 +29,6 @@ pub struct ChannelMixer {
 impl ChannelMixer {
     /// The minimum and maximum values for the mix levels.
     pub const MIX_RANGE: RangeInclusive<f32> = -1.0..=1.0;
-
-    pub fn default_version() -> EffectVersion {
-        1002
-    }
 }
 
 impl Default for ChannelMixer {
@@ -46,24 +42,25 @@ impl Default for ChannelMixer {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_channel_mixer(&self) -> Option<&ChannelMixer> {
-        self.downcast_ref::<ChannelMixer>()
+impl Interpolate for ChannelMixer {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            left_to_left: self.left_to_left.interpolate(&other.left_to_left, t),
+            left_to_right: self.left_to_right.interpolate(&other.left_to_right, t),
+            right_to_left: self.right_to_left.interpolate(&other.right_to_left, t),
+            right_to_right: self.right_to_right.interpolate(&other.right_to_right, t),
+        }
     }
 }
 
-impl Effect for ChannelMixer {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(ChannelMixer {
+    left_to_left: Fraction,
+    left_to_right: Fraction,
+    right_to_left: Fraction,
+    right_to_right: Fraction,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::ChannelMixer
-    }
-}
+impl_effect!(ChannelMixer, as_channel_mixer, default_version = 1002);
 
 impl EffectRead for ChannelMixer {
     fn read<R: Read + Seek>(
@@ -77,6 +74,8 @@ impl EffectRead for ChannelMixer {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let left_to_left = reader.read_f32()?;
         if !ChannelMixer::MIX_RANGE.contains(&left_to_left) {
             return Err(Error::new(
@@ -109,13 +108,13 @@ impl EffectRead for ChannelMixer {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "channel_mixer_unknown_1")?;
         reader.expect_u32(0, "channel_mixer_unknown_2")?;
 
-        let group_id = reader.read_snapin_position()?;
+        state.group_id = reader.read_snapin_position()?;
 
         Ok(EffectReadReturn::new(
             Box::new(ChannelMixer {
@@ -124,9 +123,7 @@ impl EffectRead for ChannelMixer {
                 right_to_left,
                 right_to_right,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -135,18 +132,20 @@ impl EffectWrite for ChannelMixer {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        _effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
         writer.write_f32(self.left_to_left)?;
         writer.write_f32(self.right_to_left)?;
         writer.write_f32(self.left_to_right)?;
         writer.write_f32(self.right_to_right)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // channel_mixer_unknown_1
         writer.write_u32(0)?; // channel_mixer_unknown_2
 
-        writer.write_snapin_id(snapin.group_id)?;
+        writer.write_snapin_id(state.group_id)?;
 
         Ok(())
     }
@@ -156,7 +155,7 @@ impl EffectWrite for ChannelMixer {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
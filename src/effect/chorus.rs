@@ -2,25 +2,26 @@
 //!
 //! | Phase Plant Version | Effect Version |
 //! |---------------------|----------------|
-//! | 1.6.9 to 1.8.13     | 1037
+//! | 1.6.9 to 1.8.13     | 1037           |
 //! | 2.0.0               | 1046           |
 //! | 2.0.12              | 1047           |
 //! | 2.0.16              | 1048           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::si::f32::{Frequency, Ratio, Time};
 use uom::si::frequency::hertz;
 use uom::si::ratio::percent;
 use uom::si::time::millisecond;
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Chorus {
@@ -33,10 +34,6 @@ pub struct Chorus {
 }
 
 impl Chorus {
-    pub fn default_version() -> EffectVersion {
-        1002
-    }
-
     pub fn new() -> Self {
         Default::default()
     }
@@ -55,24 +52,29 @@ impl Default for Chorus {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_chorus(&self) -> Option<&Chorus> {
-        self.downcast_ref::<Chorus>()
+impl Interpolate for Chorus {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            taps: step(&self.taps, &other.taps, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            spread: self.spread.interpolate(&other.spread, t),
+            delay: self.delay.interpolate(&other.delay, t),
+            depth: self.depth.interpolate(&other.depth, t),
+            rate: self.rate.interpolate(&other.rate, t),
+        }
     }
 }
 
-impl Effect for Chorus {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Chorus {
+    taps,
+    mix,
+    spread,
+    delay,
+    depth,
+    rate,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Chorus
-    }
-}
+impl_effect!(Chorus, as_chorus, default_version = 1048);
 
 impl EffectRead for Chorus {
     fn read<R: Read + Seek>(
@@ -89,7 +91,10 @@ impl EffectRead for Chorus {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let delay = reader.read_seconds()?;
         let rate = reader.read_hertz()?;
         let depth = reader.read_seconds()?;
@@ -106,16 +111,14 @@ impl EffectRead for Chorus {
                 ),
             )),
         }?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "chorus_unknown_1")?;
         reader.expect_u32(0, "chorus_unknown_2")?;
 
-        let group_id = if effect_version >= 1046 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1046 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Chorus {
@@ -126,9 +129,7 @@ impl EffectRead for Chorus {
                 depth,
                 rate,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -137,21 +138,24 @@ impl EffectWrite for Chorus {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_seconds(self.delay)?;
         writer.write_hertz(self.rate)?;
         writer.write_seconds(self.depth)?;
         writer.write_ratio(self.spread)?;
         writer.write_ratio(self.mix)?;
         writer.write_u32(self.taps as u32 - 2)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // chorus_unknown_1
         writer.write_u32(0)?; // chorus_unknown_2
 
-        writer.write_snapin_id(snapin.group_id)?;
+        if effect_version >= 1046 {
+            writer.write_snapin_id(state.group_id)?;
+        }
 
         Ok(())
     }
@@ -161,7 +165,7 @@ impl EffectWrite for Chorus {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -8,19 +8,20 @@
 //! | 2.0.12              | 1048           |
 //! | 2.0.16              | 1049           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::si::f32::{Frequency, Ratio};
 use uom::si::frequency::hertz;
 use uom::si::ratio::percent;
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct CombFilter {
@@ -33,12 +34,6 @@ pub struct CombFilter {
     pub mix: Ratio,
 }
 
-impl CombFilter {
-    pub fn default_version() -> EffectVersion {
-        1049
-    }
-}
-
 impl Default for CombFilter {
     fn default() -> Self {
         Self {
@@ -50,24 +45,25 @@ impl Default for CombFilter {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_comb_filter(&self) -> Option<&CombFilter> {
-        self.downcast_ref::<CombFilter>()
+impl Interpolate for CombFilter {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            frequency: self.frequency.interpolate(&other.frequency, t),
+            polarity_minus: step(&self.polarity_minus, &other.polarity_minus, t),
+            stereo: step(&self.stereo, &other.stereo, t),
+            mix: self.mix.interpolate(&other.mix, t),
+        }
     }
 }
 
-impl Effect for CombFilter {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(CombFilter {
+    frequency,
+    polarity_minus,
+    stereo,
+    mix,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::CombFilter
-    }
-}
+impl_effect!(CombFilter, as_comb_filter, default_version = 1049);
 
 impl EffectRead for CombFilter {
     fn read<R: Read + Seek>(
@@ -84,21 +80,22 @@ impl EffectRead for CombFilter {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let frequency = reader.read_hertz()?;
         let mix = reader.read_ratio()?;
         let polarity_minus = reader.read_bool32()?;
         let stereo = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "comb_filter_unknown_1")?;
         reader.expect_u32(0, "comb_filter_unknown_2")?;
 
-        let group_id = if effect_version >= 1047 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1047 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(CombFilter {
@@ -107,9 +104,7 @@ impl EffectRead for CombFilter {
                 stereo,
                 mix,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -118,20 +113,21 @@ impl EffectWrite for CombFilter {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_f32(self.frequency.get::<hertz>())?;
         writer.write_ratio(self.mix)?;
         writer.write_bool32(self.polarity_minus)?;
         writer.write_bool32(self.stereo)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?;
         writer.write_u32(0)?;
 
-        if snapin.effect_version >= 1048 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version >= 1047 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -143,7 +139,7 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::ratio::percent;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -2,28 +2,29 @@
 //!
 //! | Phase Plant Version | Effect Version |
 //! |---------------------|----------------|
-//! | 1.8.5               | 1039           |
-//! | 1.8.16              | 1039           |
+//! | 1.8.0 to 1.8.16     | 1039           |
 //! | 2.0.12              | 1049           |
 //! | 2.1.0               | 1050           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use strum_macros::FromRepr;
+use strum_macros::{Display, EnumIter, FromRepr};
 use uom::num::Zero;
 use uom::si::f32::{Ratio, Time};
 use uom::si::ratio::percent;
-use uom::si::time::millisecond;
+use uom::si::time::{millisecond, second};
 
-use crate::effect::{EffectVersion, SidechainMode};
-use crate::{Decibels, Snapin};
+use crate::effect::EffectVersion;
+use crate::effect::SidechainMode;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::{Decibels, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
-#[derive(Copy, Clone, Debug, FromRepr, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Display, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum CompressorMode {
     // The discriminants correspond to the file format.
@@ -33,6 +34,8 @@ pub enum CompressorMode {
     Fast = 2,
 }
 
+impl_parameters!(CompressorMode);
+
 impl CompressorMode {
     pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
         Self::from_repr(id).ok_or_else(|| {
@@ -55,12 +58,6 @@ pub struct Compressor {
     pub sidechain_mode: SidechainMode,
 }
 
-impl Compressor {
-    pub fn default_version() -> EffectVersion {
-        1050
-    }
-}
-
 impl Default for Compressor {
     fn default() -> Self {
         Self {
@@ -75,25 +72,51 @@ impl Default for Compressor {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_compressor(&self) -> Option<&Compressor> {
-        self.downcast_ref::<Compressor>()
+impl Interpolate for Compressor {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            mode: step(&self.mode, &other.mode, t),
+            threshold: self.threshold.interpolate(&other.threshold, t),
+            ratio: self.ratio.interpolate(&other.ratio, t),
+            attack: self.attack.interpolate(&other.attack, t),
+            release: self.release.interpolate(&other.release, t),
+            makeup: self.makeup.interpolate(&other.makeup, t),
+            sidechain_mode: step(&self.sidechain_mode, &other.sidechain_mode, t),
+        }
     }
 }
 
-impl Effect for Compressor {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Compressor {
+    mode,
+    threshold,
+    ratio,
+    attack,
+    release,
+    makeup,
+    sidechain_mode,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Compressor
+impl Compressor {
+    /// The settings when the effect is added in Phase Plant. The threshold
+    /// is stored as a linear gain so it isn't exactly -6 dB.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            threshold: Decibels::new(-6.0031567),
+            attack: Time::new::<second>(0.022999998),
+            release: Time::new::<second>(0.022999998),
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(
+    Compressor,
+    as_compressor,
+    init_preset = custom,
+    default_version = 1050
+);
+
 impl EffectRead for Compressor {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -109,23 +132,24 @@ impl EffectRead for Compressor {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let attack = reader.read_seconds()?;
         let release = reader.read_seconds()?;
         let mode = CompressorMode::from_id(reader.read_u32()?)?;
         let ratio = reader.read_ratio()?;
         let threshold = reader.read_decibels_linear()?;
         let makeup = reader.read_ratio()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "compressor_unknown_1")?;
         reader.expect_u32(0, "compressor_unknown_2")?;
 
-        let group_id = if effect_version > 1039 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1039 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         let sidechain_id = reader.read_u32()?;
         let sidechain_mode_str = reader.read_string_and_length()?;
@@ -147,9 +171,7 @@ impl EffectRead for Compressor {
                 makeup,
                 sidechain_mode,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -158,21 +180,24 @@ impl EffectWrite for Compressor {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_seconds(self.attack)?;
         writer.write_seconds(self.release)?;
         writer.write_u32(self.mode as u32)?;
         writer.write_ratio(self.ratio)?;
         writer.write_decibels_linear(self.threshold)?;
         writer.write_ratio(self.makeup)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?;
         writer.write_u32(0)?;
 
-        writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1039 {
+            writer.write_snapin_id(state.group_id)?;
+        }
         writer.write_u32(self.sidechain_mode as u32)?;
         writer.write_string_and_length(self.sidechain_mode.to_string())
     }
@@ -183,7 +208,7 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::ratio::{percent, ratio};
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
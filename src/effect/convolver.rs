@@ -10,19 +10,20 @@
 //! | 2.0.12              | 1017           |
 //! | 2.0.16 to 2.1.0     | 1018           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::num::Zero;
 use uom::si::f32::{Ratio, Time};
 use uom::si::ratio::percent;
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Convolver {
@@ -46,12 +47,6 @@ pub struct Convolver {
     pub reverse: bool,
 }
 
-impl Convolver {
-    pub fn default_version() -> EffectVersion {
-        1018
-    }
-}
-
 impl Default for Convolver {
     fn default() -> Self {
         Self {
@@ -72,24 +67,43 @@ impl Default for Convolver {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_convolver(&self) -> Option<&Convolver> {
-        self.downcast_ref::<Convolver>()
+impl Interpolate for Convolver {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            ir_name: step(&self.ir_name, &other.ir_name, t),
+            ir_path: step(&self.ir_path, &other.ir_path, t),
+            start: self.start.interpolate(&other.start, t),
+            end: self.end.interpolate(&other.end, t),
+            fade_in: self.fade_in.interpolate(&other.fade_in, t),
+            fade_out: self.fade_out.interpolate(&other.fade_out, t),
+            stretch: self.stretch.interpolate(&other.stretch, t),
+            delay: self.delay.interpolate(&other.delay, t),
+            sync: step(&self.sync, &other.sync, t),
+            tone: self.tone.interpolate(&other.tone, t),
+            feedback: self.feedback.interpolate(&other.feedback, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            reverse: step(&self.reverse, &other.reverse, t),
+        }
     }
 }
 
-impl Effect for Convolver {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
-
-    fn mode(&self) -> EffectMode {
-        EffectMode::Convolver
-    }
-}
+impl_parameters!(Convolver {
+    ir_name,
+    ir_path,
+    start,
+    end,
+    fade_in,
+    fade_out,
+    stretch,
+    delay,
+    sync,
+    tone,
+    feedback,
+    mix,
+    reverse,
+});
+
+impl_effect!(Convolver, as_convolver, default_version = 1018);
 
 impl EffectRead for Convolver {
     fn read<R: Read + Seek>(
@@ -106,13 +120,13 @@ impl EffectRead for Convolver {
             ));
         }
 
-        // FIXME
-        let group_id = None;
+        // FIXME: Group ID
+        let mut state = SnapinState::default();
 
         let mix = reader.read_ratio()?;
         let stretch = reader.read_ratio()?;
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "convolver_unknown_5")?;
         reader.expect_u32(0, "convolver_unknown_6")?;
@@ -169,12 +183,7 @@ impl EffectRead for Convolver {
             mix,
             reverse,
         };
-        Ok(EffectReadReturn::new(
-            Box::new(effect),
-            enabled,
-            minimized,
-            group_id,
-        ))
+        Ok(EffectReadReturn::new(Box::new(effect), state))
     }
 }
 
@@ -182,9 +191,13 @@ impl EffectWrite for Convolver {
     fn write<W: Write + Seek>(
         &self,
         _writer: &mut PhasePlantWriter<W>,
-        _snapin: &Snapin,
+        _state: &SnapinState,
+        _effect_version: EffectVersion,
     ) -> io::Result<()> {
-        todo!()
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "Writing Convolver is not supported",
+        ))
     }
 }
 
@@ -195,7 +208,7 @@ mod test {
     use uom::si::ratio::percent;
     use uom::si::time::millisecond;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
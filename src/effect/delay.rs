@@ -5,24 +5,25 @@
 //! | 1.8.5 to 1.8.14     | 1037           |
 //! | 2.0.0               | 1046           |
 //! | 2.0.12              | 1049           |
-//! | 2.1.16 to 2.1.0     | 1050           |
+//! | 2.0.16 to 2.1.0     | 1050           |
 
 // The tone control was added in Phase Plant 2.0.9.
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::num::Zero;
 use uom::si::f32::{Ratio, Time};
 use uom::si::ratio::{percent, ratio};
 use uom::si::time::second;
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::{NoteValue, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug)]
 pub struct Delay {
@@ -42,14 +43,13 @@ pub struct Delay {
     pub pan: Ratio,
     pub mix: Ratio,
     pub tone: Ratio,
-    unknown2: u32,
-    unknown3: u32,
-}
 
-impl Delay {
-    pub fn default_version() -> EffectVersion {
-        1050
-    }
+    /// Number of notes in the delay when sync is enabled, such as 3 for a
+    /// delay of 3/16.
+    pub numerator: u32,
+
+    /// Length of the notes in the delay when sync is enabled.
+    pub denominator: NoteValue,
 }
 
 impl PartialEq for Delay {
@@ -62,6 +62,8 @@ impl PartialEq for Delay {
             && self.pan == other.pan
             && self.mix == other.mix
             && self.tone == other.tone
+            && self.numerator == other.numerator
+            && self.denominator == other.denominator
     }
 }
 
@@ -79,31 +81,61 @@ impl Default for Delay {
             pan: Ratio::zero(),
             mix: Ratio::new::<percent>(50.0),
             tone: Ratio::zero(),
-            unknown2: 3,
-            unknown3: 4,
+            numerator: 3,
+            denominator: NoteValue::Sixteenth,
         }
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_delay(&self) -> Option<&Delay> {
-        self.downcast_ref::<Delay>()
+impl Interpolate for Delay {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            time: self.time.interpolate(&other.time, t),
+            sync: step(&self.sync, &other.sync, t),
+            feedback: self.feedback.interpolate(&other.feedback, t),
+            bounce: step(&self.bounce, &other.bounce, t),
+            duck: self.duck.interpolate(&other.duck, t),
+            pan: self.pan.interpolate(&other.pan, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            tone: self.tone.interpolate(&other.tone, t),
+            numerator: step(&self.numerator, &other.numerator, t),
+            denominator: step(&self.denominator, &other.denominator, t),
+        }
     }
 }
 
-impl Effect for Delay {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Delay {
+    time,
+    sync,
+    feedback,
+    bounce,
+    duck,
+    pan,
+    mix,
+    tone,
+    numerator,
+    denominator,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Delay
+impl Delay {
+    /// The settings when the effect is added in Phase Plant 2. Phase Plant 1
+    /// used the [default](Self::default) feedback of 50%.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            feedback: Ratio::new::<ratio>(0.4972512),
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(
+    Delay,
+    as_delay,
+    init_preset = custom,
+    default_version = 1050
+);
+
 impl EffectRead for Delay {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -119,11 +151,14 @@ impl EffectRead for Delay {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let time = reader.read_seconds()?;
 
-        let unknown2 = reader.read_u32()?;
-        let unknown3 = reader.read_u32()?;
+        let numerator = reader.read_u32()?;
+        let denominator = reader.read_forward_compatible()?;
 
         let sync = reader.read_bool32()?;
         let feedback = reader.read_ratio()?;
@@ -131,16 +166,14 @@ impl EffectRead for Delay {
         let bounce = reader.read_bool32()?;
         let duck = reader.read_ratio()?;
         let mix = reader.read_ratio()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "delay_unknown_5")?;
         reader.expect_u32(0, "delay_unknown_6")?;
 
-        let group_id = if effect_version >= 1046 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1046 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         let mut tone = Ratio::zero();
         if effect_version >= 1049 {
@@ -157,12 +190,10 @@ impl EffectRead for Delay {
                 pan,
                 mix,
                 tone,
-                unknown2,
-                unknown3,
+                numerator,
+                denominator,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -171,13 +202,14 @@ impl EffectWrite for Delay {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_f32(self.time.get::<second>())?;
 
-        writer.write_u32(self.unknown2)?;
-        writer.write_u32(self.unknown3)?;
+        writer.write_u32(self.numerator)?;
+        writer.write_u32(self.denominator.id())?;
 
         writer.write_bool32(self.sync)?;
         writer.write_f32(self.feedback.get::<ratio>())?;
@@ -185,15 +217,16 @@ impl EffectWrite for Delay {
         writer.write_bool32(self.bounce)?;
         writer.write_f32(self.duck.get::<ratio>())?;
         writer.write_ratio(self.mix)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
-        writer.write_u32(0)?;
-        writer.write_u32(0)?;
-        writer.write_u32(0)?;
+        writer.write_u32(0)?; // delay_unknown_5
+        writer.write_u32(0)?; // delay_unknown_6
 
-        if snapin.effect_version >= 1049 {
-            writer.write_snapin_id(snapin.group_id)?;
-            writer.write_f32(self.tone.get::<ratio>())?;
+        if effect_version >= 1046 {
+            writer.write_snapin_id(state.group_id)?;
+        }
+        if effect_version >= 1049 {
+            writer.write_ratio(self.tone)?;
         }
 
         Ok(())
@@ -205,7 +238,7 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::time::second;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -220,6 +253,8 @@ mod test {
         assert_relative_eq!(effect.duck.get::<percent>(), 0.0);
         assert_relative_eq!(effect.pan.get::<percent>(), 0.0);
         assert_relative_eq!(effect.mix.get::<percent>(), 50.0);
+        assert_eq!(effect.numerator, 3);
+        assert_eq!(effect.denominator, NoteValue::Sixteenth);
     }
 
     #[test]
@@ -314,6 +349,9 @@ mod test {
         assert!(!snapin.preset_edited);
         let effect = snapin.effect.as_delay().unwrap();
         assert!(effect.bounce);
+        assert!(effect.sync);
+        assert_eq!(effect.numerator, 1);
+        assert_eq!(effect.denominator, NoteValue::ThirtySecond);
     }
 
     #[test]
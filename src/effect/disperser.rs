@@ -6,31 +6,36 @@
 //! | 1.8.5 to 1.8.16     | 1039           |
 //! | 2.0.12              | 1050           |
 
-use std::any::Any;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::si::f32::Frequency;
 use uom::si::frequency::hertz;
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
+/// Disperser smears the phase of the signal around a frequency by running
+/// it through a chain of all-pass filters.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Disperser {
+    /// Center of the phase shift, between [`FREQUENCY_MIN`](Self::FREQUENCY_MIN)
+    /// and [`FREQUENCY_MAX`](Self::FREQUENCY_MAX) hertz. Stored in hertz.
     pub frequency: Frequency,
+
+    /// Number of all-pass filters, up to [`AMOUNT_MAX`](Self::AMOUNT_MAX).
+    /// Stored as the count itself.
     pub amount: u32,
+
+    /// Sharpness of the phase shift around the frequency. Stored as shown
+    /// in Phase Plant.
     pub pinch: f32,
-    unknown2: bool,
-}
 
-impl Disperser {
-    pub fn default_version() -> EffectVersion {
-        1050
-    }
+    unknown2: bool,
 }
 impl Default for Disperser {
     fn default() -> Self {
@@ -43,25 +48,81 @@ impl Default for Disperser {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_disperser(&self) -> Option<&Disperser> {
-        self.downcast_ref::<Disperser>()
+impl Interpolate for Disperser {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            frequency: self.frequency.interpolate(&other.frequency, t),
+            amount: step(&self.amount, &other.amount, t),
+            pinch: self.pinch.interpolate(&other.pinch, t),
+            unknown2: step(&self.unknown2, &other.unknown2, t),
+        }
     }
 }
 
-impl Effect for Disperser {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
+impl_parameters!(Disperser {
+    frequency,
+    amount,
+    pinch,
+});
+
+impl Disperser {
+    // Frequency::new is not a const fn yet.
+    pub const FREQUENCY_MIN: f32 = 20.0;
+    pub const FREQUENCY_MAX: f32 = 20000.0;
+
+    /// Maximum number of all-pass filters.
+    pub const AMOUNT_MAX: u32 = 200;
+
+    /// The settings when the effect is added in Phase Plant, which stores a
+    /// frequency of about 130.5 Hz.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            frequency: Frequency::new::<hertz>(130.49657),
+            ..Default::default()
+        }
     }
+}
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Disperser
+fn check_disperser(effect: &Disperser) -> Result<(), Error> {
+    if effect.amount > Disperser::AMOUNT_MAX {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Disperser has up to {} all-pass filters, not {}",
+                Disperser::AMOUNT_MAX,
+                effect.amount
+            ),
+        ));
+    }
+    let frequency = effect.frequency.get::<hertz>();
+    if !(Disperser::FREQUENCY_MIN..=Disperser::FREQUENCY_MAX).contains(&frequency) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Disperser frequency of {frequency} Hz is not between {} and {} Hz",
+                Disperser::FREQUENCY_MIN,
+                Disperser::FREQUENCY_MAX
+            ),
+        ));
     }
+    if !effect.pinch.is_finite() || effect.pinch < 0.0 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Disperser pinch of {} is not valid", effect.pinch),
+        ));
+    }
+    Ok(())
 }
 
+impl_effect!(
+    Disperser,
+    as_disperser,
+    unknown = [unknown2],
+    init_preset = custom,
+    default_version = 1050
+);
+
 impl EffectRead for Disperser {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -74,23 +135,23 @@ impl EffectRead for Disperser {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let amount = reader.read_u32()?;
         let frequency = reader.read_hertz()?;
         let pinch = reader.read_f32()?;
 
         let unknown2 = reader.read_bool32()?;
 
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "disperser_unknown_1")?;
         reader.expect_u32(0, "disperser_unknown_2")?;
 
-        let group_id = if effect_version > 1039 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1039 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Disperser {
@@ -99,9 +160,7 @@ impl EffectRead for Disperser {
                 pinch,
                 unknown2,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -110,22 +169,24 @@ impl EffectWrite for Disperser {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
+        check_disperser(self)?;
         writer.write_u32(self.amount)?;
         writer.write_hertz(self.frequency)?;
         writer.write_f32(self.pinch)?;
 
         writer.write_bool32(self.unknown2)?;
 
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?;
         writer.write_u32(0)?;
 
-        if snapin.effect_version > 1039 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1039 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -136,8 +197,9 @@ impl EffectWrite for Disperser {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
-    use crate::test::read_effect_preset;
+    use crate::effect::{Effect, Filter};
+    use crate::test::{read_effect_preset, rewrite_effect};
+    use crate::Snapin;
 
     use super::*;
 
@@ -200,4 +262,42 @@ mod test {
         assert_eq!(effect.amount, 18);
         assert_relative_eq!(effect.pinch, 3.0, epsilon = 0.1);
     }
+
+    #[test]
+    fn extremes() {
+        for (hertz_value, amount, pinch) in [
+            (Disperser::FREQUENCY_MIN, 0, 0.0),
+            (Disperser::FREQUENCY_MAX, Disperser::AMOUNT_MAX, 10.0),
+        ] {
+            let effect = Disperser {
+                frequency: Frequency::new::<hertz>(hertz_value),
+                amount,
+                pinch,
+                ..Default::default()
+            };
+            let snapin = Snapin::new(Box::new(effect.clone()), 1, true, false);
+            let rewritten = rewrite_effect(&snapin).unwrap();
+            assert_eq!(rewritten.effect.as_disperser(), Some(&effect));
+        }
+
+        for effect in [
+            Disperser {
+                amount: Disperser::AMOUNT_MAX + 1,
+                ..Default::default()
+            },
+            Disperser {
+                frequency: Frequency::new::<hertz>(Disperser::FREQUENCY_MAX * 2.0),
+                ..Default::default()
+            },
+            Disperser {
+                pinch: f32::NAN,
+                ..Default::default()
+            },
+        ] {
+            let snapin = Snapin::new(Box::new(effect), 1, true, false);
+            assert!(
+                matches!(rewrite_effect(&snapin), Err(error) if error.kind() == ErrorKind::InvalidInput)
+            );
+        }
+    }
 }
@@ -8,22 +8,25 @@
 //! | 2.0.12              | 1049           |
 //! | 2.0.16              | 1050           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
+use std::fmt::{Display, Formatter};
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use strum_macros::FromRepr;
+use strum_macros::{EnumIter, FromRepr};
 use uom::num::Zero;
 use uom::si::f32::Ratio;
-use uom::si::ratio::percent;
+use uom::si::ratio::{percent, ratio};
 
 use crate::effect::EffectVersion;
-use crate::{Decibels, Snapin};
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::{Decibels, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
-#[derive(Copy, Clone, Debug, FromRepr, Eq, PartialEq)]
+/// Every mode available in Phase Plant 2.1.
+#[derive(Copy, Clone, Debug, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum DistortionMode {
     // The discriminants correspond to the file format.
@@ -46,8 +49,86 @@ impl DistortionMode {
             )
         })
     }
+
+    /// Name displayed by Phase Plant.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DistortionMode::Overdrive => "Overdrive",
+            DistortionMode::Saturate => "Saturate",
+            DistortionMode::Foldback => "Foldback",
+            DistortionMode::Sine => "Sine",
+            DistortionMode::HardClip => "Hard Clip",
+            DistortionMode::Quantize => "Quantize",
+        }
+    }
+
+    /// The harmonics the mode adds. The transfer curves are symmetric so
+    /// they add odd harmonics. Bias makes them asymmetric, which adds even
+    /// harmonics.
+    pub fn harmonics(&self) -> &'static str {
+        match self {
+            DistortionMode::Overdrive => "Odd harmonics that fall off quickly",
+            DistortionMode::Saturate => "Odd harmonics that grow gradually with the drive",
+            DistortionMode::Foldback => "Dense high harmonics that grow with each fold",
+            DistortionMode::Sine => "Dense harmonics that shift as the drive increases",
+            DistortionMode::HardClip => "Strong odd harmonics, approaching a square wave",
+            DistortionMode::Quantize => "Harsh stepped distortion that is not harmonic",
+        }
+    }
+
+    /// Short description of the shape of the transfer curve.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            DistortionMode::Overdrive => "Soft clipping that gently rounds off peaks",
+            DistortionMode::Saturate => {
+                "Smooth saturation that approaches the limits asymptotically"
+            }
+            DistortionMode::Foldback => "Signal above the limits is folded back towards zero",
+            DistortionMode::Sine => {
+                "Signal is passed through a sine wave so loud input wraps around"
+            }
+            DistortionMode::HardClip => "Signal is cut off abruptly at the limits",
+            DistortionMode::Quantize => "Signal is rounded to fewer levels as the drive increases",
+        }
+    }
+
+    /// Approximate shaping function of the mode applied to a signal that has
+    /// already had the drive and bias applied.
+    fn shape(&self, x: f32, drive: f32) -> f32 {
+        use std::f32::consts::FRAC_PI_2;
+        match self {
+            DistortionMode::Overdrive => {
+                let x = x.clamp(-1.0, 1.0);
+                1.5 * (x - x.powi(3) / 3.0)
+            }
+            DistortionMode::Saturate => x.tanh(),
+            DistortionMode::Foldback => {
+                // Reflect off of the limits of -1 and 1.
+                let folded = (x + 1.0).rem_euclid(4.0);
+                if folded > 2.0 {
+                    3.0 - folded
+                } else {
+                    folded - 1.0
+                }
+            }
+            DistortionMode::Sine => (x * FRAC_PI_2).sin(),
+            DistortionMode::HardClip => x.clamp(-1.0, 1.0),
+            DistortionMode::Quantize => {
+                let levels = (8.0 / drive).max(1.0);
+                ((x * levels).round() / levels).clamp(-1.0, 1.0)
+            }
+        }
+    }
 }
 
+impl Display for DistortionMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl_parameters!(DistortionMode);
+
 #[derive(Debug, PartialEq)]
 pub struct Distortion {
     pub mode: DistortionMode,
@@ -63,19 +144,28 @@ pub struct Distortion {
 }
 
 impl Distortion {
-    pub fn default_version() -> EffectVersion {
-        1050
-    }
-
     pub fn new() -> Self {
         Self::default()
     }
-}
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_distortion(&self) -> Option<&Distortion> {
-        self.downcast_ref::<Distortion>()
+    /// Drive as the linear gain stored in the preset.
+    pub fn drive_linear(&self) -> f32 {
+        self.drive.linear()
+    }
+
+    /// Set the drive from a linear gain, as stored in the preset.
+    pub fn set_drive_linear(&mut self, linear: f32) {
+        self.drive = Decibels::from_linear(linear);
+    }
+
+    /// Approximate the output of the distortion for an input sample between
+    /// -1 and 1. Useful for drawing the transfer curve, it does not account for
+    /// dynamics, spread or the DC filter.
+    pub fn transfer(&self, x: f32) -> f32 {
+        let drive = self.drive_linear();
+        let wet = self.mode.shape(x * drive + self.bias.get::<ratio>(), drive);
+        let mix = self.mix.get::<ratio>();
+        x * (1.0 - mix) + wet * mix
     }
 }
 
@@ -99,18 +189,50 @@ impl Default for Distortion {
     }
 }
 
-impl Effect for Distortion {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
+impl Interpolate for Distortion {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            mode: step(&self.mode, &other.mode, t),
+            drive: self.drive.interpolate(&other.drive, t),
+            dynamics: self.dynamics.interpolate(&other.dynamics, t),
+            bias: self.bias.interpolate(&other.bias, t),
+            spread: self.spread.interpolate(&other.spread, t),
+            dc_filter: step(&self.dc_filter, &other.dc_filter, t),
+            mix: self.mix.interpolate(&other.mix, t),
+        }
     }
+}
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Distortion
+impl_parameters!(Distortion {
+    mode,
+    drive,
+    dynamics,
+    bias,
+    spread,
+    dc_filter,
+    mix,
+});
+
+impl Distortion {
+    /// The settings when the effect is added in Phase Plant. The drive is
+    /// stored as a linear gain so it isn't exactly 6 dB.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            drive: Decibels::new(6.0042477),
+            dynamics: Ratio::new::<ratio>(0.49875095),
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(
+    Distortion,
+    as_distortion,
+    init_preset = custom,
+    default_version = 1050
+);
+
 impl EffectRead for Distortion {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -126,22 +248,24 @@ impl EffectRead for Distortion {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let drive = reader.read_decibels_linear()?;
         let bias = reader.read_ratio()?;
         let spread = reader.read_ratio()?;
         let mode = DistortionMode::from_id(reader.read_u32()?)?;
         let dynamics = reader.read_ratio()?;
         let mix = reader.read_ratio()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "distortion_unknown_1")?;
         reader.expect_u32(0, "distortion_unknown_2")?;
 
         let mut dc_filter = true;
-        let mut group_id = None;
         if effect_version > 1038 {
-            group_id = reader.read_snapin_position()?;
+            state.group_id = reader.read_snapin_position()?;
             dc_filter = reader.read_bool32()?;
         }
 
@@ -155,9 +279,7 @@ impl EffectRead for Distortion {
                 dc_filter,
                 mix,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -166,22 +288,23 @@ impl EffectWrite for Distortion {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_decibels_linear(self.drive)?;
         writer.write_ratio(self.bias)?;
         writer.write_ratio(self.spread)?;
         writer.write_u32(self.mode as u32)?;
         writer.write_ratio(self.dynamics)?;
         writer.write_ratio(self.mix)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?;
         writer.write_u32(0)?;
 
-        if snapin.effect_version > 1038 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1038 {
+            writer.write_snapin_id(state.group_id)?;
             writer.write_bool32(self.dc_filter)?;
         }
 
@@ -192,8 +315,9 @@ impl EffectWrite for Distortion {
 #[cfg(test)]
 mod test {
     use approx::assert_relative_eq;
+    use strum::IntoEnumIterator;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -281,4 +405,48 @@ mod test {
         assert_eq!(effect.spread.get::<percent>(), 66.0);
         assert_eq!(effect.mix.get::<percent>(), 70.0);
     }
+
+    #[test]
+    fn modes() {
+        assert_eq!(DistortionMode::iter().count(), 6);
+        for mode in DistortionMode::iter() {
+            assert_eq!(DistortionMode::from_id(mode as u32).unwrap(), mode);
+        }
+        assert_eq!(DistortionMode::HardClip.to_string(), "Hard Clip");
+    }
+
+    #[test]
+    fn drive_linear() {
+        let mut effect = Distortion::default();
+        assert_relative_eq!(effect.drive_linear(), 1.9952623, epsilon = 0.0001);
+        effect.set_drive_linear(1.0);
+        assert_relative_eq!(effect.drive.db(), 0.0);
+    }
+
+    #[test]
+    fn transfer() {
+        let mut effect = Distortion {
+            drive: Decibels::ZERO,
+            ..Distortion::default()
+        };
+        for mode in DistortionMode::iter() {
+            effect.mode = mode;
+            assert!(!mode.describe().is_empty());
+            assert!(!mode.harmonics().is_empty());
+            assert_relative_eq!(effect.transfer(0.0), 0.0);
+            for x in [-1.0, -0.5, 0.25, 1.0] {
+                assert!(effect.transfer(x).abs() <= 1.0, "{mode:?} {x}");
+            }
+        }
+
+        effect.mode = DistortionMode::HardClip;
+        effect.set_drive_linear(4.0);
+        assert_relative_eq!(effect.transfer(0.5), 1.0);
+        assert_relative_eq!(effect.transfer(-0.5), -1.0);
+        effect.mode = DistortionMode::Foldback;
+        assert_relative_eq!(effect.transfer(0.375), 0.5, epsilon = 0.0001);
+
+        effect.mix = Ratio::zero();
+        assert_relative_eq!(effect.transfer(0.375), 0.375);
+    }
 }
@@ -8,20 +8,21 @@
 //! | 2.0.12              | 1012           |
 //! | 2.0.16              | 1013           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::num::Zero;
 use uom::si::f32::{Ratio, Time};
 use uom::si::ratio::{percent, ratio};
 use uom::si::time::{millisecond, second};
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::{NoteValue, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct DualDelay {
@@ -34,12 +35,13 @@ pub struct DualDelay {
     pub duck: Ratio,
     pub crosstalk: Ratio,
     pub mix: Ratio,
-}
 
-impl DualDelay {
-    pub fn default_version() -> EffectVersion {
-        1013
-    }
+    /// Number of notes in the first delay when sync is enabled, such as 3
+    /// for a delay of 3/16.
+    pub numerator: u32,
+
+    /// Length of the notes in the first delay when sync is enabled.
+    pub denominator: NoteValue,
 }
 
 impl Default for DualDelay {
@@ -54,29 +56,65 @@ impl Default for DualDelay {
             duck: Ratio::zero(),
             crosstalk: Ratio::new::<percent>(50.0),
             mix: Ratio::new::<ratio>(1.0 / 3.0),
+            numerator: 3,
+            denominator: NoteValue::Sixteenth,
         }
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_dual_delay(&self) -> Option<&DualDelay> {
-        self.downcast_ref::<DualDelay>()
+impl Interpolate for DualDelay {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            time: self.time.interpolate(&other.time, t),
+            second_delay_length: self
+                .second_delay_length
+                .interpolate(&other.second_delay_length, t),
+            sync: step(&self.sync, &other.sync, t),
+            tone: self.tone.interpolate(&other.tone, t),
+            feedback: self.feedback.interpolate(&other.feedback, t),
+            spread: self.spread.interpolate(&other.spread, t),
+            duck: self.duck.interpolate(&other.duck, t),
+            crosstalk: self.crosstalk.interpolate(&other.crosstalk, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            numerator: step(&self.numerator, &other.numerator, t),
+            denominator: step(&self.denominator, &other.denominator, t),
+        }
     }
 }
 
-impl Effect for DualDelay {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(DualDelay {
+    time,
+    second_delay_length,
+    sync,
+    tone,
+    feedback,
+    spread,
+    duck,
+    crosstalk,
+    mix,
+    numerator,
+    denominator,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::DualDelay
+impl DualDelay {
+    /// The settings when the effect is added in Phase Plant, with a mix a
+    /// little under a third.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            mix: Ratio::new::<ratio>(0.3333333),
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(
+    DualDelay,
+    as_dual_delay,
+    init_preset = custom,
+    default_version = 1013
+);
+
 impl EffectRead for DualDelay {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -92,6 +130,8 @@ impl EffectRead for DualDelay {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let time = reader.read_seconds()?;
         let second_delay_length = reader.read_ratio()?;
         let feedback = reader.read_ratio()?;
@@ -99,16 +139,14 @@ impl EffectRead for DualDelay {
         let spread = reader.read_ratio()?;
         let tone = reader.read_ratio()?;
         let mix = reader.read_ratio()?;
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "dual_delay_unknown_3")?;
         reader.expect_u32(0, "dual_delay_unknown_4")?;
-        reader.expect_u32(0, "dual_delay_unknown_5")?;
-        reader.expect_u32(3, "dual_delay_unknown_6")?;
-
-        // TODO: Group position is a bit of a guess.
-        let group_id = reader.read_snapin_position()?;
+        state.group_id = reader.read_snapin_position()?;
+        let numerator = reader.read_u32()?;
+        let denominator = reader.read_forward_compatible()?;
 
         let sync = reader.read_bool32()?;
         let duck = reader.read_ratio()?;
@@ -124,10 +162,10 @@ impl EffectRead for DualDelay {
                 duck,
                 crosstalk,
                 mix,
+                numerator,
+                denominator,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -136,7 +174,8 @@ impl EffectWrite for DualDelay {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        _effect_version: EffectVersion,
     ) -> io::Result<()> {
         writer.write_f32(self.time.get::<second>())?;
         writer.write_f32(self.second_delay_length.get::<ratio>())?;
@@ -145,20 +184,18 @@ impl EffectWrite for DualDelay {
         writer.write_f32(self.spread.get::<ratio>())?;
         writer.write_f32(self.tone.get::<ratio>())?;
         writer.write_ratio(self.mix)?;
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // dual_delay_unknown_3
         writer.write_u32(0)?; // dual_delay_unknown_4
-        writer.write_u32(0)?; // dual_delay_unknown_5
-        writer.write_u32(3)?; // dual_delay_unknown_6
-
-        writer.write_snapin_id(snapin.group_id)?;
+        writer.write_snapin_id(state.group_id)?;
+        writer.write_u32(self.numerator)?;
+        writer.write_u32(self.denominator.id())?;
 
         writer.write_bool32(self.sync)?;
         writer.write_f32(self.duck.get::<ratio>())?;
 
-        writer.write_u32(0)?;
         Ok(())
     }
 }
@@ -168,8 +205,8 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::time::{millisecond, second};
 
-    use crate::effect::Filter;
-    use crate::test::read_effect_preset;
+    use crate::effect::{Effect, Filter};
+    use crate::test::{read_effect_preset, rewrite_effect};
 
     use super::*;
 
@@ -185,6 +222,8 @@ mod test {
         assert_relative_eq!(effect.duck.get::<percent>(), 0.0);
         assert_relative_eq!(effect.crosstalk.get::<percent>(), 50.0);
         assert_relative_eq!(effect.mix.get::<ratio>(), 1.0 / 3.0);
+        assert_eq!(effect.numerator, 3);
+        assert_eq!(effect.denominator, NoteValue::Sixteenth);
     }
 
     #[test]
@@ -206,6 +245,7 @@ mod test {
             let snapin = &preset.lanes[0].snapins[0];
             assert!(snapin.enabled);
             assert!(!snapin.minimized);
+            assert_eq!(snapin.group_id, None);
             let effect = snapin.effect.as_dual_delay().unwrap();
             assert_eq!(effect.time.get::<second>(), 0.200);
             assert_relative_eq!(effect.second_delay_length.get::<ratio>(), 1.618034);
@@ -319,6 +359,8 @@ mod test {
         assert_relative_eq!(effect.time.get::<millisecond>(), 200.0, epsilon = 0.001);
         assert_relative_eq!(effect.mix.get::<percent>(), 45.1, epsilon = 0.01);
         assert!(effect.sync);
+        assert_eq!(effect.numerator, 3);
+        assert_eq!(effect.denominator, NoteValue::Sixteenth);
 
         let preset =
             read_effect_preset("dual_delay", "dual_delay-x2-minimized-2.0.12.phaseplant").unwrap();
@@ -337,4 +379,27 @@ mod test {
         let effect = snapin.effect.as_dual_delay().unwrap();
         assert_relative_eq!(effect.tone.get::<percent>(), -25.0, epsilon = 0.01);
     }
+
+    /// The note length of a synced delay is written back.
+    #[test]
+    fn sync_note_length() {
+        let mut preset = read_effect_preset(
+            "dual_delay",
+            "dual_delay-1.25-sync-duck25-2.0.16.phaseplant",
+        )
+        .unwrap();
+        let snapin = &mut preset.lanes[0].snapins[0];
+        let effect = snapin.effect.as_dual_delay().unwrap();
+        assert!(effect.sync);
+        assert_eq!(effect.numerator, 3);
+        assert_eq!(effect.denominator, NoteValue::Sixteenth);
+
+        let effect = snapin.effect_as_mut::<DualDelay>().unwrap();
+        effect.numerator = 5;
+        effect.denominator = NoteValue::EightTriplet;
+        let rewritten = rewrite_effect(snapin).unwrap();
+        let effect = rewritten.effect.as_dual_delay().unwrap();
+        assert_eq!(effect.numerator, 5);
+        assert_eq!(effect.denominator, NoteValue::EightTriplet);
+    }
 }
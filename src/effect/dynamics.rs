@@ -9,7 +9,7 @@
 //! | 1.8.14              | 1003           |
 //! | 2.0.16              | 1014           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
@@ -17,30 +17,67 @@ use uom::si::f32::Ratio;
 use uom::si::ratio::percent;
 
 use crate::effect::EffectVersion;
-use crate::{Decibels, Snapin};
+use crate::interpolate::Interpolate;
+use crate::path::impl_parameters;
+use crate::{Decibels, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
+/// The levels between the low and high thresholds are left alone. Levels
+/// below the low threshold are compressed upward or expanded downward and
+/// levels above the high threshold are compressed downward or expanded
+/// upward. Phase Plant doesn't have separate attack and release times or a
+/// range for each threshold.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Dynamics {
+    /// Shown as a percentage by Phase Plant, 100% by default.
     pub attack: Ratio,
+
+    /// Shown as a percentage by Phase Plant, 100% by default.
     pub release: Ratio,
+
+    /// Width of the transition at each threshold, 2.5 dB by default.
     pub knee: Decibels,
+
     pub in_gain: Decibels,
     pub out_gain: Decibels,
     pub mix: Ratio,
+
+    /// -30 dB by default.
     pub low_threshold: Decibels,
+
+    /// -20 dB by default.
     pub high_threshold: Decibels,
 
-    /// Ratios are `1.0 / value`
+    /// Stored as the reciprocal of the ratio shown by Phase Plant, see
+    /// [`displayed_low_ratio`](Self::displayed_low_ratio). A value above one
+    /// is downward expansion and a value below one is upward compression.
     pub low_ratio: f32,
+
+    /// Stored as the reciprocal of the ratio shown by Phase Plant, see
+    /// [`displayed_high_ratio`](Self::displayed_high_ratio). A value below
+    /// one is downward compression and a value above one is upward
+    /// expansion.
     pub high_ratio: f32,
 }
 
 impl Dynamics {
-    pub fn default_version() -> EffectVersion {
-        1014
+    /// The low ratio as Phase Plant shows it, such as 2 for 2:1.
+    pub fn displayed_low_ratio(&self) -> f32 {
+        1.0 / self.low_ratio
+    }
+
+    pub fn set_displayed_low_ratio(&mut self, ratio: f32) {
+        self.low_ratio = 1.0 / ratio;
+    }
+
+    /// The high ratio as Phase Plant shows it, such as 3 for 3:1.
+    pub fn displayed_high_ratio(&self) -> f32 {
+        1.0 / self.high_ratio
+    }
+
+    pub fn set_displayed_high_ratio(&mut self, ratio: f32) {
+        self.high_ratio = 1.0 / ratio;
     }
 }
 
@@ -61,24 +98,37 @@ impl Default for Dynamics {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_dynamics(&self) -> Option<&Dynamics> {
-        self.downcast_ref::<Dynamics>()
+impl Interpolate for Dynamics {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            attack: self.attack.interpolate(&other.attack, t),
+            release: self.release.interpolate(&other.release, t),
+            knee: self.knee.interpolate(&other.knee, t),
+            in_gain: self.in_gain.interpolate(&other.in_gain, t),
+            out_gain: self.out_gain.interpolate(&other.out_gain, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            low_threshold: self.low_threshold.interpolate(&other.low_threshold, t),
+            high_threshold: self.high_threshold.interpolate(&other.high_threshold, t),
+            low_ratio: self.low_ratio.interpolate(&other.low_ratio, t),
+            high_ratio: self.high_ratio.interpolate(&other.high_ratio, t),
+        }
     }
 }
 
-impl Effect for Dynamics {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
-
-    fn mode(&self) -> EffectMode {
-        EffectMode::Dynamics
-    }
-}
+impl_parameters!(Dynamics {
+    attack,
+    release,
+    knee,
+    in_gain,
+    out_gain,
+    mix,
+    low_threshold,
+    high_threshold,
+    low_ratio,
+    high_ratio,
+});
+
+impl_effect!(Dynamics, as_dynamics, default_version = 1014);
 
 impl EffectRead for Dynamics {
     fn read<R: Read + Seek>(
@@ -95,6 +145,8 @@ impl EffectRead for Dynamics {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let in_gain = reader.read_decibels_db()?;
         let out_gain = reader.read_decibels_db()?;
         let low_threshold = reader.read_decibels_db()?;
@@ -103,8 +155,8 @@ impl EffectRead for Dynamics {
         let high_ratio = reader.read_f32()?;
         let release = reader.read_ratio()?;
         let mix = reader.read_ratio()?;
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "dynamics_unknown1")?;
         reader.expect_u32(0, "dynamics_unknown2")?;
@@ -112,11 +164,9 @@ impl EffectRead for Dynamics {
         let attack = reader.read_ratio()?;
         let knee = reader.read_decibels_db()?;
 
-        let group_id = if effect_version > 1003 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1003 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Dynamics {
@@ -131,9 +181,7 @@ impl EffectRead for Dynamics {
                 low_ratio,
                 high_ratio,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -142,7 +190,8 @@ impl EffectWrite for Dynamics {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
         writer.write_f32(self.in_gain.db())?;
         writer.write_f32(self.out_gain.db())?;
@@ -152,8 +201,8 @@ impl EffectWrite for Dynamics {
         writer.write_f32(self.high_ratio)?;
         writer.write_ratio(self.release)?;
         writer.write_ratio(self.mix)?;
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // dynamics_unknown_1
         writer.write_u32(0)?; // dynamics_unknown_2
@@ -161,8 +210,8 @@ impl EffectWrite for Dynamics {
         writer.write_ratio(self.attack)?;
         writer.write_f32(self.knee.db())?;
 
-        if snapin.effect_version > 1003 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1003 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -173,9 +222,9 @@ impl EffectWrite for Dynamics {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
-    use crate::test::read_effect_preset;
-    use crate::Decibels;
+    use crate::effect::{Effect, Filter};
+    use crate::test::{read_effect_preset, rewrite_effect};
+    use crate::{Decibels, Snapin};
 
     use super::*;
 
@@ -214,6 +263,31 @@ mod test {
         }
     }
 
+    #[test]
+    fn rewrite() {
+        let mut effect = Dynamics {
+            attack: Ratio::new::<percent>(40.0),
+            release: Ratio::new::<percent>(250.0),
+            knee: Decibels::new(0.0),
+            in_gain: Decibels::new(-4.0),
+            out_gain: Decibels::new(6.0),
+            mix: Ratio::new::<percent>(60.0),
+            low_threshold: Decibels::new(-45.0),
+            high_threshold: Decibels::new(-3.0),
+            ..Default::default()
+        };
+
+        // Expansion below the low threshold.
+        effect.set_displayed_low_ratio(0.5);
+        effect.set_displayed_high_ratio(4.0);
+        assert_eq!(effect.low_ratio, 2.0);
+
+        let snapin = Snapin::new(Box::new(effect), 1, false, true);
+        let rewritten = rewrite_effect(&snapin).unwrap();
+        assert_eq!(rewritten.effect.as_dynamics(), snapin.effect.as_dynamics());
+        assert_eq!(rewritten.state, snapin.state());
+    }
+
     #[test]
     fn parts_version_1() {
         let preset = read_effect_preset(
@@ -237,6 +311,8 @@ mod test {
         let effect = snapin.effect.as_dynamics().unwrap();
         assert_relative_eq!(effect.low_ratio, 1.0 / 2.0);
         assert_relative_eq!(effect.high_ratio, 1.0 / 3.0);
+        assert_relative_eq!(effect.displayed_low_ratio(), 2.0);
+        assert_relative_eq!(effect.displayed_high_ratio(), 3.0);
 
         let preset = read_effect_preset(
             "dynamics",
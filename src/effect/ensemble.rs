@@ -4,26 +4,27 @@
 //! | Phase Plant Version | Effect Version |
 //! |---------------------|----------------|
 //! | 1.8.5 to 1.8.13     | 1003           |
-//! | 2.0.?               | 1012           |
+//! | 2.0.0               | 1012           |
 //! | 2.0.12              | 1013           |
 //! | 2.0.16              | 1014           |
 
-use std::any::Any;
+use std::any::type_name;
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
-use strum_macros::FromRepr;
+use strum_macros::{EnumIter, FromRepr};
 use uom::si::f32::Ratio;
 use uom::si::ratio::{percent, ratio};
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
-#[derive(Copy, Clone, Debug, FromRepr, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum MotionMode {
     // The discriminants correspond to the file format.
@@ -54,6 +55,8 @@ impl Display for MotionMode {
     }
 }
 
+impl_parameters!(MotionMode);
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Ensemble {
     pub voices: u32,
@@ -63,12 +66,6 @@ pub struct Ensemble {
     pub motion_mode: MotionMode,
 }
 
-impl Ensemble {
-    pub fn default_version() -> EffectVersion {
-        1014
-    }
-}
-
 impl Default for Ensemble {
     fn default() -> Self {
         Self {
@@ -81,24 +78,27 @@ impl Default for Ensemble {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_ensemble(&self) -> Option<&Ensemble> {
-        self.downcast_ref::<Ensemble>()
+impl Interpolate for Ensemble {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            voices: step(&self.voices, &other.voices, t),
+            detune: self.detune.interpolate(&other.detune, t),
+            spread: self.spread.interpolate(&other.spread, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            motion_mode: step(&self.motion_mode, &other.motion_mode, t),
+        }
     }
 }
 
-impl Effect for Ensemble {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Ensemble {
+    voices,
+    detune,
+    spread,
+    mix,
+    motion_mode,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Ensemble
-    }
-}
+impl_effect!(Ensemble, as_ensemble, default_version = 1014);
 
 impl EffectRead for Ensemble {
     fn read<R: Read + Seek>(
@@ -108,17 +108,22 @@ impl EffectRead for Ensemble {
         if effect_version < 1003 {
             return Err(Error::new(
                 ErrorKind::InvalidData,
-                format!("Ensemble effect version {effect_version}"),
+                format!(
+                    "Version {effect_version} of {} is not supported",
+                    type_name::<Self>()
+                ),
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let voices = reader.read_u32()?;
         let detune = reader.read_ratio()?;
         let spread = reader.read_ratio()?;
         let mix = reader.read_ratio()?;
-        let enabled = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
 
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
         reader.expect_u32(0, "ensemble_u3")?;
 
         // FIXME: VERIFY THIS IS MOTION MODE WitH SINE TEST
@@ -126,11 +131,9 @@ impl EffectRead for Ensemble {
 
         reader.expect_u32(0, "ensemble_u4")?;
 
-        let group_id = if effect_version >= 1012 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1012 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Ensemble {
@@ -140,9 +143,7 @@ impl EffectRead for Ensemble {
                 mix,
                 motion_mode,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -151,14 +152,15 @@ impl EffectWrite for Ensemble {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
         writer.write_u32(self.voices)?;
         writer.write_f32(self.detune.get::<ratio>())?;
         writer.write_f32(self.spread.get::<ratio>())?;
         writer.write_ratio(self.mix)?;
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?;
 
@@ -166,8 +168,8 @@ impl EffectWrite for Ensemble {
 
         writer.write_u32(0)?;
 
-        if snapin.effect_version >= 1013 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version >= 1012 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -176,7 +178,7 @@ impl EffectWrite for Ensemble {
 
 #[cfg(test)]
 mod test {
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
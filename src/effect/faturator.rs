@@ -4,25 +4,29 @@
 //! | Phase Plant Version | Effect Version |
 //! |---------------------|----------------|
 //! | 1.6.9 to 1.8.13     | 1040           |
+//! | 2.0.0               | 1049           |
 //! | 2.0.16 to 2.1.0     | 1051           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::num::Zero;
 use uom::si::f32::{Frequency, Ratio};
 use uom::si::frequency::hertz;
 use uom::si::ratio::{percent, ratio};
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::Interpolate;
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Faturator {
+    /// Unlike [`Distortion`](super::Distortion) the drive is a percentage
+    /// rather than a gain so it has no equivalent in decibels.
     pub drive: Ratio,
     pub fuzz: Ratio,
     pub color: Frequency,
@@ -30,12 +34,6 @@ pub struct Faturator {
     pub mix: Ratio,
 }
 
-impl Faturator {
-    pub fn default_version() -> EffectVersion {
-        1051
-    }
-}
-
 impl Default for Faturator {
     fn default() -> Self {
         Self {
@@ -48,25 +46,44 @@ impl Default for Faturator {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_faturator(&self) -> Option<&Faturator> {
-        self.downcast_ref::<Faturator>()
+impl Interpolate for Faturator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            drive: self.drive.interpolate(&other.drive, t),
+            fuzz: self.fuzz.interpolate(&other.fuzz, t),
+            color: self.color.interpolate(&other.color, t),
+            stereo_turbo: self.stereo_turbo.interpolate(&other.stereo_turbo, t),
+            mix: self.mix.interpolate(&other.mix, t),
+        }
     }
 }
 
-impl Effect for Faturator {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Faturator {
+    drive,
+    fuzz,
+    color,
+    stereo_turbo,
+    mix,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Faturator
+impl Faturator {
+    /// The settings when the effect is added in Phase Plant.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            drive: Ratio::new::<ratio>(0.51800007),
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(
+    Faturator,
+    as_faturator,
+    init_preset = custom,
+    default_version = 1051
+);
+
 impl EffectRead for Faturator {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -82,22 +99,22 @@ impl EffectRead for Faturator {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let drive = reader.read_ratio()?;
         let fuzz = reader.read_ratio()?;
         let stereo_turbo = reader.read_ratio()?;
         let color = reader.read_hertz()?;
         let mix = reader.read_ratio()?;
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "faturator_unknown_1")?;
         reader.expect_u32(0, "faturator_unknown_2")?;
 
-        let group_id = if effect_version > 1040 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1040 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Faturator {
@@ -107,9 +124,7 @@ impl EffectRead for Faturator {
                 stereo_turbo,
                 mix,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -118,20 +133,23 @@ impl EffectWrite for Faturator {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
         writer.write_f32(self.drive.get::<ratio>())?;
         writer.write_f32(self.fuzz.get::<ratio>())?;
         writer.write_f32(self.stereo_turbo.get::<ratio>())?;
         writer.write_f32(self.color.get::<hertz>())?;
         writer.write_ratio(self.mix)?;
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // faturator_unknown_1
         writer.write_u32(0)?; // faturator_unknown_2
 
-        writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1040 {
+            writer.write_snapin_id(state.group_id)?;
+        }
 
         Ok(())
     }
@@ -141,7 +159,7 @@ impl EffectWrite for Faturator {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
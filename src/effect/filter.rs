@@ -8,22 +8,23 @@
 //! | 1.8.13 to 1.8.16    | 1040           |
 //! | 2.0.16              | 1051           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use strum_macros::FromRepr;
+use strum_macros::{EnumIter, FromRepr};
 use uom::si::f32::Frequency;
 use uom::si::frequency::hertz;
 
 use crate::effect::EffectVersion;
-use crate::{Decibels, Snapin};
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::{Decibels, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
-#[derive(Copy, Clone, Debug, FromRepr, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum FilterMode {
     // The discriminants correspond to the file format.
@@ -34,12 +35,39 @@ pub enum FilterMode {
     LowShelf = 4,
     Peak = 5,
     HighShelf = 6,
+
+    /// Added by a newer version of Phase Plant. The value is kept so it can be
+    /// written back.
+    #[strum(disabled)]
+    Unknown(u32),
 }
 
 impl FilterMode {
-    pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
-        Self::from_repr(id)
-            .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("Unknown filter mode {id}")))
+    /// The value stored in presets.
+    pub fn id(&self) -> u32 {
+        use FilterMode::*;
+        match self {
+            LowPass => 0,
+            BandPass => 1,
+            HighPass => 2,
+            Notch => 3,
+            LowShelf => 4,
+            Peak => 5,
+            HighShelf => 6,
+            Unknown(id) => *id,
+        }
+    }
+}
+
+impl ForwardCompatible for FilterMode {
+    const DESCRIPTION: &'static str = "filter mode";
+
+    fn from_id(id: u32) -> Self {
+        Self::from_repr(id).unwrap_or(Self::Unknown(id))
+    }
+
+    fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown(_))
     }
 }
 
@@ -56,11 +84,14 @@ impl Display for FilterMode {
             LowShelf => "Low shelf",
             Peak => "Peak",
             HighShelf => "High shelf",
+            Unknown(id) => return write!(f, "Unknown ({id})"),
         };
         f.write_str(name)
     }
 }
 
+impl_parameters!(FilterMode);
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Filter {
     pub filter_mode: FilterMode,
@@ -72,17 +103,6 @@ pub struct Filter {
 
 impl Filter {
     pub const RESONANCE_MIN: f64 = 0.1;
-
-    pub fn default_version() -> EffectVersion {
-        1051
-    }
-}
-
-impl dyn Effect {
-    #[must_use]
-    pub fn as_filter(&self) -> Option<&Filter> {
-        self.downcast_ref::<Filter>()
-    }
 }
 
 impl Default for Filter {
@@ -97,18 +117,45 @@ impl Default for Filter {
     }
 }
 
-impl Effect for Filter {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
+impl Interpolate for Filter {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            filter_mode: step(&self.filter_mode, &other.filter_mode, t),
+            cutoff: self.cutoff.interpolate(&other.cutoff, t),
+            q: self.q.interpolate(&other.q, t),
+            gain: self.gain.interpolate(&other.gain, t),
+            slope: step(&self.slope, &other.slope, t),
+        }
     }
+}
+
+impl_parameters!(Filter {
+    filter_mode,
+    cutoff,
+    q,
+    gain,
+    slope,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Filter
+impl Filter {
+    /// The settings when the effect is added in Phase Plant, with the gain
+    /// stored a fraction under 6 dB.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            gain: Decibels::new(5.9999657),
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(
+    Filter,
+    as_filter,
+    init_preset = custom,
+    default_version = 1051
+);
+
 impl EffectRead for Filter {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -124,12 +171,15 @@ impl EffectRead for Filter {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
-        let mode = FilterMode::from_id(reader.read_u32()?)?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
+        let mode = reader.read_forward_compatible()?;
         let cutoff = reader.read_hertz()?;
         let q = reader.read_f32()?;
         let gain = reader.read_decibels_db()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "filter_unknown1")?;
         reader.expect_u32(0, "filter_unknown2")?;
@@ -140,11 +190,9 @@ impl EffectRead for Filter {
             1
         };
 
-        let group_id = if effect_version > 1040 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1040 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Filter {
@@ -154,9 +202,7 @@ impl EffectRead for Filter {
                 gain,
                 slope,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -165,22 +211,23 @@ impl EffectWrite for Filter {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_u32(self.filter_mode as u32)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_u32(self.filter_mode.id())?;
         writer.write_f32(self.cutoff.get::<hertz>())?;
         writer.write_f32(self.q)?;
         writer.write_f32(self.gain.db())?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // filter_unknown1
         writer.write_u32(0)?; // filter_unknown2
 
         writer.write_u32(self.slope)?;
 
-        if snapin.effect_version > 1040 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1040 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -191,12 +238,34 @@ impl EffectWrite for Filter {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::{Bitcrush, Filter};
+    use crate::effect::{Bitcrush, Effect, Filter};
     use crate::test::read_effect_preset;
     use crate::Decibels;
 
     use super::*;
 
+    #[test]
+    fn unknown_mode() {
+        let mut bytes = Vec::new();
+        for value in [6, 1, 2, 99] {
+            bytes.extend_from_slice(&u32::to_le_bytes(value));
+        }
+        let mut reader = PhasePlantReader::new(std::io::Cursor::new(&bytes)).unwrap();
+        let mode: FilterMode = reader.read_forward_compatible().unwrap();
+        assert_eq!(mode, FilterMode::Unknown(99));
+        assert_eq!(mode.id(), 99);
+        assert_eq!(mode.to_string(), "Unknown (99)");
+
+        let mut reader = PhasePlantReader::new(std::io::Cursor::new(&bytes)).unwrap();
+        reader.strict = true;
+        let error = reader.read_forward_compatible::<FilterMode>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        for id in 0..=6 {
+            assert_eq!(FilterMode::from_id(id).id(), id);
+        }
+    }
+
     #[test]
     fn default() {
         let effect = Filter::default();
@@ -3,25 +3,26 @@
 //!
 //! | Phase Plant Version | Effect Version |
 //! |---------------------|----------------|
-//! | 1.8.5 to 1.8.14     | 1002           |
+//! | 1.8.0 to 1.8.14     | 1002           |
 //! | 2.0.0               | 1011           |
 //! | 2.0.16              | 1013           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::num::Zero;
 use uom::si::f32::{Frequency, Ratio, Time};
 use uom::si::frequency::hertz;
 use uom::si::ratio::{percent, ratio};
 use uom::si::time::second;
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Flanger {
@@ -40,10 +41,6 @@ pub struct Flanger {
 }
 
 impl Flanger {
-    pub fn default_version() -> EffectVersion {
-        1013
-    }
-
     pub fn offset_degrees(&self) -> f32 {
         self.offset.get::<ratio>() * 360.0
     }
@@ -65,25 +62,54 @@ impl Default for Flanger {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_flanger(&self) -> Option<&Flanger> {
-        self.downcast_ref::<Flanger>()
+impl Interpolate for Flanger {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            delay: self.delay.interpolate(&other.delay, t),
+            depth: self.depth.interpolate(&other.depth, t),
+            rate: self.rate.interpolate(&other.rate, t),
+            scroll: step(&self.scroll, &other.scroll, t),
+            offset: self.offset.interpolate(&other.offset, t),
+            motion: self.motion.interpolate(&other.motion, t),
+            spread: self.spread.interpolate(&other.spread, t),
+            feedback: self.feedback.interpolate(&other.feedback, t),
+            mix: self.mix.interpolate(&other.mix, t),
+        }
     }
 }
 
-impl Effect for Flanger {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Flanger {
+    delay,
+    depth,
+    rate,
+    scroll,
+    offset,
+    motion,
+    spread,
+    feedback,
+    mix,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Flanger
+impl Flanger {
+    /// The settings when the effect is added in Phase Plant, which stores a
+    /// depth of about 1.026 ms rather than the 1.03 ms it displays.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            depth: Time::new::<second>(0.0010256782),
+            motion: Frequency::new::<hertz>(0.50000006),
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(
+    Flanger,
+    as_flanger,
+    init_preset = custom,
+    default_version = 1013
+);
+
 impl EffectRead for Flanger {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -99,6 +125,8 @@ impl EffectRead for Flanger {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let delay = reader.read_seconds()?;
         let depth = reader.read_seconds()?;
         let rate = reader.read_hertz()?;
@@ -116,17 +144,15 @@ impl EffectRead for Flanger {
         let spread = reader.read_ratio()?;
         let mix = reader.read_ratio()?;
         let scroll = reader.read_bool32()?;
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "flanger_unknown_1")?;
         reader.expect_u32(0, "flanger_unknown_2")?;
 
-        let group_id = if effect_version > 1002 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1002 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Flanger {
@@ -140,9 +166,7 @@ impl EffectRead for Flanger {
                 feedback,
                 mix,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -151,25 +175,26 @@ impl EffectWrite for Flanger {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
         writer.write_f32(self.delay.get::<second>())?;
         writer.write_f32(self.depth.get::<second>())?;
         writer.write_hertz(self.rate)?;
-        writer.write_f32(self.offset.get::<percent>())?;
+        writer.write_ratio(self.offset)?;
         writer.write_hertz(self.motion)?;
         writer.write_ratio(self.feedback)?;
         writer.write_ratio(self.spread)?;
         writer.write_ratio(self.mix)?;
         writer.write_bool32(self.scroll)?;
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // flanger_unknown_1
         writer.write_u32(0)?; // flanger_unknown_2
 
-        if snapin.effect_version > 1002 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1002 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -181,7 +206,7 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::time::{millisecond, second};
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -6,33 +6,111 @@
 //! | 1.8.5               | 1037           |
 //! | 1.8.16              | 1037           |
 //! | 2.0.12              | 1047           |
-//! | 2.1.0               | 1048           |
+//! | 2.0.16 to 2.1.0     | 1048           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::si::f32::Frequency;
 use uom::si::frequency::hertz;
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
+
+/// Vowels placed by the average first and second formants of adult male
+/// speakers measured by Peterson and Barney (1952). Phase Plant doesn't
+/// store which vowel the pad is on, only the two formant frequencies, so
+/// these are the textbook positions rather than the letters drawn on the pad.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Vowel {
+    /// As in "father".
+    A,
+
+    /// As in "bed".
+    E,
+
+    /// As in "heed".
+    I,
+
+    /// As in "bought".
+    O,
+
+    /// As in "who'd".
+    U,
+}
+
+impl Vowel {
+    pub const ALL: [Vowel; 5] = [Vowel::A, Vowel::E, Vowel::I, Vowel::O, Vowel::U];
+
+    /// First and second formants in hertz.
+    fn formant_hertz(&self) -> (f32, f32) {
+        match self {
+            Vowel::A => (730.0, 1090.0),
+            Vowel::E => (530.0, 1840.0),
+            Vowel::I => (270.0, 2290.0),
+            Vowel::O => (570.0, 840.0),
+            Vowel::U => (300.0, 870.0),
+        }
+    }
+
+    /// First and second formants.
+    pub fn formants(&self) -> (Frequency, Frequency) {
+        let (first, second) = self.formant_hertz();
+        (
+            Frequency::new::<hertz>(first),
+            Frequency::new::<hertz>(second),
+        )
+    }
+}
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct FormantFilter {
     pub q: f32,
     pub lows: bool,
     pub highs: bool,
+
+    /// Frequency of the first formant, the horizontal position on the pad.
     pub x: Frequency,
+
+    /// Frequency of the second formant, the vertical position on the pad.
     pub y: Frequency,
 }
 
 impl FormantFilter {
-    pub fn default_version() -> EffectVersion {
-        1048
+    /// Move the pad to the formants of the vowel.
+    pub fn set_vowel(&mut self, vowel: Vowel) {
+        (self.x, self.y) = vowel.formants();
+    }
+
+    /// Move the pad between two vowels, where zero is `from` and one is
+    /// `to`. The formants move evenly in octaves, like they do across the
+    /// pad.
+    pub fn set_vowel_blend(&mut self, from: Vowel, to: Vowel, amount: f32) {
+        let amount = amount.clamp(0.0, 1.0);
+        let blend = |from: f32, to: f32| from * (to / from).powf(amount);
+        let (from_x, from_y) = from.formant_hertz();
+        let (to_x, to_y) = to.formant_hertz();
+        self.x = Frequency::new::<hertz>(blend(from_x, to_x));
+        self.y = Frequency::new::<hertz>(blend(from_y, to_y));
+    }
+
+    /// The vowel with the closest formants, measured in octaves.
+    pub fn nearest_vowel(&self) -> Vowel {
+        let x = self.x.get::<hertz>();
+        let y = self.y.get::<hertz>();
+        let distance = |vowel: &Vowel| {
+            let (first, second) = vowel.formant_hertz();
+            (x / first).log2().powi(2) + (y / second).log2().powi(2)
+        };
+        Vowel::ALL
+            .into_iter()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .unwrap_or(Vowel::A)
     }
 }
 
@@ -48,24 +126,27 @@ impl Default for FormantFilter {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_formant_filter(&self) -> Option<&FormantFilter> {
-        self.downcast_ref::<FormantFilter>()
+impl Interpolate for FormantFilter {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            q: self.q.interpolate(&other.q, t),
+            lows: step(&self.lows, &other.lows, t),
+            highs: step(&self.highs, &other.highs, t),
+            x: self.x.interpolate(&other.x, t),
+            y: self.y.interpolate(&other.y, t),
+        }
     }
 }
 
-impl Effect for FormantFilter {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(FormantFilter {
+    q,
+    lows,
+    highs,
+    x,
+    y,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::FormantFilter
-    }
-}
+impl_effect!(FormantFilter, as_formant_filter, default_version = 1048);
 
 impl EffectRead for FormantFilter {
     fn read<R: Read + Seek>(
@@ -82,22 +163,23 @@ impl EffectRead for FormantFilter {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let x = reader.read_hertz()?;
         let y = reader.read_hertz()?;
         let q = reader.read_f32()?;
         let lows = reader.read_bool32()?;
         let highs = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "formant_filter_unknown_1")?;
         reader.expect_u32(0, "formant_filter_unknown_2")?;
 
-        let group_id = if effect_version >= 1038 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1038 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(FormantFilter {
@@ -107,9 +189,7 @@ impl EffectRead for FormantFilter {
                 x,
                 y,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -118,19 +198,22 @@ impl EffectWrite for FormantFilter {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_hertz(self.x)?;
         writer.write_hertz(self.y)?;
         writer.write_f32(self.q)?;
         writer.write_bool32(self.lows)?;
         writer.write_bool32(self.highs)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // formant_filter_unknown_1
         writer.write_u32(0)?; // formant_filter_unknown_2
-        writer.write_snapin_id(snapin.group_id)?;
+        if effect_version >= 1038 {
+            writer.write_snapin_id(state.group_id)?;
+        }
 
         Ok(())
     }
@@ -138,7 +221,9 @@ impl EffectWrite for FormantFilter {
 
 #[cfg(test)]
 mod test {
-    use crate::effect::Filter;
+    use approx::assert_relative_eq;
+
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -227,5 +312,24 @@ mod test {
         assert_eq!(effect.x.get::<hertz>(), 200.0);
         assert_eq!(effect.y.get::<hertz>(), 2500.0);
         assert_eq!(effect.q, 10.0);
+        assert_eq!(effect.nearest_vowel(), Vowel::I);
+    }
+
+    #[test]
+    fn vowels() {
+        let mut effect = FormantFilter::default();
+        assert_eq!(effect.nearest_vowel(), Vowel::E);
+        for vowel in Vowel::ALL {
+            effect.set_vowel(vowel);
+            assert_eq!(effect.nearest_vowel(), vowel);
+        }
+
+        effect.set_vowel_blend(Vowel::U, Vowel::I, 0.0);
+        assert_eq!(effect.nearest_vowel(), Vowel::U);
+        effect.set_vowel_blend(Vowel::U, Vowel::I, 2.0);
+        assert_eq!((effect.x, effect.y), Vowel::I.formants());
+        effect.set_vowel_blend(Vowel::A, Vowel::I, 0.5);
+        assert_relative_eq!(effect.x.get::<hertz>(), (730.0f32 * 270.0).sqrt());
+        assert_relative_eq!(effect.y.get::<hertz>(), (1090.0f32 * 2290.0).sqrt());
     }
 }
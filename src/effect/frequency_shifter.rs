@@ -7,19 +7,21 @@
 //! | 2.0.12              | 1047           |
 //! | 2.0.16              | 1048           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
+use std::ops::RangeInclusive;
 
-use crate::effect::EffectVersion;
 use uom::num::Zero;
 use uom::si::f32::Frequency;
-use uom::si::frequency::kilohertz;
+use uom::si::frequency::{hertz, kilohertz};
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::Interpolate;
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct FrequencyShifter {
@@ -27,12 +29,23 @@ pub struct FrequencyShifter {
 }
 
 impl FrequencyShifter {
-    // TODO: Enable when uom supports const fn.
-    // pub const MIN_FREQUENCY: Frequency = Frequency::new::<hertz>(-5000.0);
-    // pub const MAX_FREQUENCY: Frequency = Frequency::new::<hertz>(5000.0);
-
-    pub fn default_version() -> EffectVersion {
-        1048
+    // TODO: Switch to Frequency when uom supports const fn.
+    /// Shift in hertz that the Phase Plant interface allows.
+    pub const FREQUENCY_RANGE_HERTZ: RangeInclusive<f32> = -5000.0..=5000.0;
+
+    /// Set the shift, checking that it's in the range Phase Plant allows.
+    pub fn set_frequency(&mut self, frequency: Frequency) -> Result<(), Error> {
+        let shift = frequency.get::<hertz>();
+        if !Self::FREQUENCY_RANGE_HERTZ.contains(&shift) {
+            let msg = format!(
+                "Frequency {shift} Hz is outside of {} to {} Hz",
+                Self::FREQUENCY_RANGE_HERTZ.start(),
+                Self::FREQUENCY_RANGE_HERTZ.end()
+            );
+            return Err(Error::new(ErrorKind::InvalidInput, msg));
+        }
+        self.frequency = frequency;
+        Ok(())
     }
 }
 
@@ -44,24 +57,21 @@ impl Default for FrequencyShifter {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_frequency_shifter(&self) -> Option<&FrequencyShifter> {
-        self.downcast_ref::<FrequencyShifter>()
+impl Interpolate for FrequencyShifter {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            frequency: self.frequency.interpolate(&other.frequency, t),
+        }
     }
 }
 
-impl Effect for FrequencyShifter {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(FrequencyShifter { frequency });
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::FrequencyShifter
-    }
-}
+impl_effect!(
+    FrequencyShifter,
+    as_frequency_shifter,
+    default_version = 1048
+);
 
 impl EffectRead for FrequencyShifter {
     fn read<R: Read + Seek>(
@@ -78,24 +88,23 @@ impl EffectRead for FrequencyShifter {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let frequency = Frequency::new::<kilohertz>(reader.read_f32()?);
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "frequency_shifter_unknown_1")?;
         reader.expect_u32(0, "frequency_shifter_unknown_2")?;
 
-        let group_id = if effect_version > 1037 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1037 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(FrequencyShifter { frequency }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -104,17 +113,18 @@ impl EffectWrite for FrequencyShifter {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_f32(self.frequency.get::<kilohertz>())?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // frequency_shifter_unknown_1
         writer.write_u32(0)?; // frequency_shifter_unknown_2
 
-        if snapin.effect_version > 1037 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1037 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -125,9 +135,8 @@ impl EffectWrite for FrequencyShifter {
 mod test {
     use approx::assert_relative_eq;
     use uom::si::f32::Frequency;
-    use uom::si::frequency::hertz;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -187,4 +196,20 @@ mod test {
         let effect = snapin.effect.as_frequency_shifter().unwrap();
         assert_relative_eq!(effect.frequency.get::<hertz>(), 1000.0, epsilon = 1.0);
     }
+
+    #[test]
+    fn set_frequency() {
+        let mut effect = FrequencyShifter::default();
+        effect
+            .set_frequency(Frequency::new::<hertz>(-250.0))
+            .unwrap();
+        assert_eq!(effect.frequency, Frequency::new::<hertz>(-250.0));
+        assert_eq!(
+            effect
+                .set_frequency(Frequency::new::<hertz>(5001.0))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+    }
 }
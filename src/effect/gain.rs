@@ -7,15 +7,16 @@
 //! | 2.0.0               | 1048           |
 //! | 2.0.16              | 1050           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
 use crate::effect::EffectVersion;
-use crate::{Decibels, Snapin};
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::{Decibels, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 // Phase Plant 1.8.6 added a percent fade mode in addition to existing decibel trim mode.
 
@@ -43,10 +44,6 @@ impl Gain {
         let range = Self::GAIN_AMOUNT_MAX - Self::GAIN_AMOUNT_MIN;
         (self.amount_db().db() - Self::GAIN_AMOUNT_MIN) / range * 2.0 // 0 to 200%
     }
-
-    pub fn default_version() -> EffectVersion {
-        1050
-    }
 }
 
 impl Default for Gain {
@@ -58,24 +55,21 @@ impl Default for Gain {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_gain(&self) -> Option<&Gain> {
-        self.downcast_ref::<Gain>()
+impl Interpolate for Gain {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            amount: self.amount.interpolate(&other.amount, t),
+            percentage: step(&self.percentage, &other.percentage, t),
+        }
     }
 }
 
-impl Effect for Gain {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Gain {
+    amount: LinearGain,
+    percentage,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Gain
-    }
-}
+impl_effect!(Gain, as_gain, default_version = 1050);
 
 impl EffectRead for Gain {
     fn read<R: Read + Seek>(
@@ -92,9 +86,12 @@ impl EffectRead for Gain {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let amount = reader.read_f32()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "gain_unknown_1")?;
 
@@ -104,17 +101,13 @@ impl EffectRead for Gain {
             reader.expect_u32(0, "gain_unknown_2")?;
         }
 
-        let group_id = if effect_version >= 1048 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1048 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Gain { amount, percentage }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -123,20 +116,23 @@ impl EffectWrite for Gain {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_f32(self.amount)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?;
 
         writer.write_bool32(self.percentage)?;
 
-        writer.write_u32(0)?;
+        if effect_version > 1038 {
+            writer.write_u32(0)?;
+        }
 
-        if snapin.effect_version >= 1050 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version >= 1048 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -147,7 +143,7 @@ impl EffectWrite for Gain {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
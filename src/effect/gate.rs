@@ -5,18 +5,19 @@
 //! | 1.8.13 to 1.8.20    | 1029           |
 //! | 2.0.16              | 1040           |
 
-use std::any::Any;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
 use uom::si::f32::Time;
 use uom::si::time::{millisecond, second};
 
-use crate::effect::{EffectVersion, SidechainMode};
-use crate::{Decibels, Snapin};
+use crate::effect::EffectVersion;
+use crate::effect::SidechainMode;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::{Decibels, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Gate {
@@ -43,19 +44,6 @@ pub struct Gate {
     pub sidechain_mode: SidechainMode,
 }
 
-impl Gate {
-    pub fn default_version() -> EffectVersion {
-        1040
-    }
-}
-
-impl dyn Effect {
-    #[must_use]
-    pub fn as_gate(&self) -> Option<&Gate> {
-        self.downcast_ref::<Gate>()
-    }
-}
-
 impl Gate {
     const DEFAULT_TOLERANCE_DB: f64 = 6.020599913279624;
     const DEFAULT_THRESHOLD_DB: f64 = -30.00000046767787;
@@ -77,18 +65,49 @@ impl Default for Gate {
     }
 }
 
-impl Effect for Gate {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
+impl Interpolate for Gate {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            threshold: self.threshold.interpolate(&other.threshold, t),
+            range: self.range.interpolate(&other.range, t),
+            tolerance: self.tolerance.interpolate(&other.tolerance, t),
+            hold: self.hold.interpolate(&other.hold, t),
+            attack: self.attack.interpolate(&other.attack, t),
+            release: self.release.interpolate(&other.release, t),
+            look_ahead: step(&self.look_ahead, &other.look_ahead, t),
+            flip: step(&self.flip, &other.flip, t),
+            sidechain_mode: step(&self.sidechain_mode, &other.sidechain_mode, t),
+        }
     }
+}
+
+impl_parameters!(Gate {
+    threshold,
+    range: Number(Decibels),
+    tolerance,
+    hold,
+    attack,
+    release,
+    look_ahead,
+    flip,
+    sidechain_mode,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Gate
+impl Gate {
+    /// The settings when the effect is added in Phase Plant. The tolerance is
+    /// a linear gain of 2 and the attack is exactly 5 ms.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            tolerance: Decibels::new(6.0206003),
+            attack: Time::new::<second>(0.005),
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(Gate, as_gate, init_preset = custom, default_version = 1040);
+
 impl EffectRead for Gate {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -101,6 +120,8 @@ impl EffectRead for Gate {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let attack = reader.read_seconds()?;
         let hold = reader.read_seconds()?;
         let release = reader.read_seconds()?;
@@ -109,17 +130,15 @@ impl EffectRead for Gate {
         let range = reader.read_f32()?;
         let look_ahead = reader.read_bool32()?; //?
         let flip = reader.read_bool32()?;
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "gate_unknown1")?;
         reader.expect_u32(0, "gate_unknown2")?;
 
-        let group_id = if effect_version > 1029 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1029 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         let sidechain_id = reader.read_u32()?;
         let sidechain_mode_str = reader.read_string_and_length()?;
@@ -143,9 +162,7 @@ impl EffectRead for Gate {
                 flip,
                 sidechain_mode,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -154,7 +171,8 @@ impl EffectWrite for Gate {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
         writer.write_f32(self.attack.get::<second>())?;
         writer.write_f32(self.hold.get::<second>())?;
@@ -164,14 +182,15 @@ impl EffectWrite for Gate {
         writer.write_f32(self.range)?;
 
         writer.write_bool32(self.look_ahead)?;
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(self.flip)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // gate_unknown1
         writer.write_u32(0)?; // gate_unknown2
 
-        if snapin.effect_version > 1029 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1029 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         writer.write_u32(self.sidechain_mode as u32)?;
@@ -184,7 +203,7 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::time::second;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
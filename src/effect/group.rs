@@ -8,45 +8,32 @@
 //! | 2.0.0               | 1007           |
 //! | 2.0.16              | 1007           |
 
-use crate::effect::EffectVersion;
-use std::any::Any;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Group {
     pub name: Option<String>,
 }
 
-impl Group {
-    pub fn default_version() -> EffectVersion {
-        1007
-    }
-}
-
-impl dyn Effect {
-    #[must_use]
-    pub fn as_group(&self) -> Option<&Group> {
-        self.downcast_ref::<Group>()
+impl Interpolate for Group {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            name: step(&self.name, &other.name, t),
+        }
     }
 }
 
-impl Effect for Group {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Group { name });
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Group
-    }
-}
+impl_effect!(Group, as_group, default_version = 1007);
 
 impl EffectRead for Group {
     fn read<R: Read + Seek>(
@@ -60,21 +47,19 @@ impl EffectRead for Group {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            minimized: reader.read_bool32()?,
+            ..Default::default()
+        };
 
         reader.expect_u32(0, "group_unknown_1")?;
         reader.expect_u32(0, "group_unknown_2")?;
 
-        let group_id = reader.read_snapin_position()?;
+        state.group_id = reader.read_snapin_position()?;
         let name = reader.read_string_and_length()?;
 
-        Ok(EffectReadReturn::new(
-            Box::new(Group { name }),
-            enabled,
-            minimized,
-            group_id,
-        ))
+        Ok(EffectReadReturn::new(Box::new(Group { name }), state))
     }
 }
 
@@ -82,23 +67,27 @@ impl EffectWrite for Group {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        _effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // group_unknown_1
         writer.write_u32(0)?; // group_unknown_2
 
-        writer.write_snapin_id(snapin.group_id)?;
+        writer.write_snapin_id(state.group_id)?;
         writer.write_string_and_length_opt(&self.name)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::effect::Filter;
+    use std::io::Cursor;
+
+    use crate::effect::{Effect, Filter, Gain};
     use crate::test::read_effect_preset;
+    use crate::{Preset, Snapin};
 
     use super::*;
 
@@ -181,4 +170,62 @@ mod test {
         assert!(snapins[1].effect.as_group().is_some());
         assert!(snapins[2].effect.as_group().is_some());
     }
+
+    #[test]
+    fn set_group() {
+        let mut preset = Preset::default();
+        let lane = &mut preset.lanes[0];
+        lane.snapins = vec![
+            Snapin::new(Box::<Gain>::default(), 1, true, false),
+            Snapin::new(Box::<Group>::default(), 2, true, false),
+            Snapin::new(Box::<Gain>::default(), 3, true, false),
+        ];
+        lane.set_group(3, Some(2)).unwrap();
+        assert_eq!(lane.snapins[2].group(lane).unwrap().id, 2);
+        assert!(lane.snapins[0].group(lane).is_none());
+        lane.check_groups().unwrap();
+
+        // The group must be a Group effect before the snapin.
+        for (snapin_id, group_id) in [(3, Some(1)), (1, Some(2)), (3, Some(4)), (4, None)] {
+            let error = lane.set_group(snapin_id, group_id).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        }
+        lane.set_group(3, None).unwrap();
+        assert!(lane.snapins[2].group(lane).is_none());
+
+        lane.snapins[0].group_id = Some(2);
+        let result = preset.write(&mut Cursor::new(Vec::new()));
+        assert!(
+            matches!(result, Err(error) if error.kind() == ErrorKind::InvalidInput
+                && error.to_string().contains("is not before it"))
+        );
+    }
+
+    #[test]
+    fn flattened_chain() {
+        let mut preset = Preset::default();
+        let lane = &mut preset.lanes[0];
+        lane.snapins = vec![
+            Snapin::new(Box::<Group>::default(), 1, false, false),
+            Snapin::new(Box::<Group>::default(), 2, true, false),
+            Snapin::new(Box::<Gain>::default(), 3, true, false),
+            Snapin::new(Box::<Gain>::default(), 4, true, false),
+            Snapin::new(Box::<Gain>::default(), 5, false, false),
+        ];
+        lane.set_group(2, Some(1)).unwrap();
+        lane.set_group(3, Some(2)).unwrap();
+        let chain = lane.flattened_chain();
+        let steps: Vec<_> = chain
+            .iter()
+            .map(|step| (step.snapin.id, step.position, step.enabled))
+            .collect();
+        assert_eq!(steps, [(3, 2, false), (4, 3, true), (5, 4, false)]);
+        assert_eq!(chain[0].groups, [1, 2]);
+        assert!(chain[1].groups.is_empty());
+
+        lane.snapins[0].enabled = true;
+        assert!(lane.flattened_chain()[0].enabled);
+        lane.enabled = false;
+        assert!(lane.flattened_chain().iter().all(|step| !step.enabled));
+    }
 }
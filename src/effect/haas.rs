@@ -2,22 +2,23 @@
 //!
 //! | Phase Plant Version | Effect Version |
 //! |---------------------|----------------|
-//! | 1.8.5 to 1.8.14     | 1037           |
+//! | 1.7.0 to 1.8.14     | 1037           |
 //! | 2.0.0               | 1046           |
 //! | 2.0.16              | 1048           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::si::f32::Time;
-use uom::si::time::millisecond;
+use uom::si::time::{millisecond, second};
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Haas {
@@ -25,12 +26,6 @@ pub struct Haas {
     pub delay: Time,
 }
 
-impl Haas {
-    pub fn default_version() -> EffectVersion {
-        1048
-    }
-}
-
 impl Default for Haas {
     fn default() -> Self {
         Haas {
@@ -40,25 +35,31 @@ impl Default for Haas {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_haas(&self) -> Option<&Haas> {
-        self.downcast_ref::<Haas>()
+impl Interpolate for Haas {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            right: step(&self.right, &other.right, t),
+            delay: self.delay.interpolate(&other.delay, t),
+        }
     }
 }
 
-impl Effect for Haas {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Haas { right, delay });
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Haas
+impl Haas {
+    /// The settings when the effect is added in Phase Plant, with the delay
+    /// stored as exactly 5 ms.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            delay: Time::new::<second>(0.005),
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(Haas, as_haas, init_preset = custom, default_version = 1048);
+
 impl EffectRead for Haas {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -74,25 +75,24 @@ impl EffectRead for Haas {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let right = reader.read_bool32()?;
         let delay = reader.read_seconds()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "haas_unknown_1")?;
         reader.expect_u32(0, "haas_unknown_2")?;
 
-        let group_id = if effect_version >= 1046 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1046 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Haas { right, delay }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -101,18 +101,19 @@ impl EffectWrite for Haas {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_bool32(self.right)?;
         writer.write_seconds(self.delay)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?;
         writer.write_u32(0)?;
 
-        if snapin.effect_version >= 1048 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version >= 1046 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -123,7 +124,7 @@ impl EffectWrite for Haas {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
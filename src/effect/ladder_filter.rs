@@ -7,7 +7,7 @@
 //! | 2.0.0               | 1038           |
 //! | 2.0.16              | 1040           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
@@ -16,10 +16,11 @@ use uom::si::f32::{Frequency, Ratio};
 use uom::si::frequency::hertz;
 
 use crate::effect::EffectVersion;
-use crate::{Decibels, Snapin};
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::{Decibels, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct LadderFilter {
@@ -39,10 +40,6 @@ pub struct LadderFilter {
 impl LadderFilter {
     pub const DRIVE_MIN: Decibels = Decibels::new(0.0);
     pub const DRIVE_MAX: Decibels = Decibels::new(45.0);
-
-    pub fn default_version() -> EffectVersion {
-        1040
-    }
 }
 
 impl Default for LadderFilter {
@@ -58,24 +55,29 @@ impl Default for LadderFilter {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_ladder_filter(&self) -> Option<&LadderFilter> {
-        self.downcast_ref::<LadderFilter>()
+impl Interpolate for LadderFilter {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            cutoff: self.cutoff.interpolate(&other.cutoff, t),
+            saturate: step(&self.saturate, &other.saturate, t),
+            resonance: self.resonance.interpolate(&other.resonance, t),
+            drive: self.drive.interpolate(&other.drive, t),
+            bias: self.bias.interpolate(&other.bias, t),
+            diode: step(&self.diode, &other.diode, t),
+        }
     }
 }
 
-impl Effect for LadderFilter {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(LadderFilter {
+    cutoff,
+    saturate,
+    resonance,
+    drive,
+    bias,
+    diode,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::LadderFilter
-    }
-}
+impl_effect!(LadderFilter, as_ladder_filter, default_version = 1040);
 
 impl EffectRead for LadderFilter {
     fn read<R: Read + Seek>(
@@ -92,6 +94,8 @@ impl EffectRead for LadderFilter {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let cutoff = reader.read_hertz()?;
         let resonance = reader.read_ratio()?;
 
@@ -114,17 +118,15 @@ impl EffectRead for LadderFilter {
         let bias = reader.read_ratio()?;
         let diode = reader.read_bool32()?;
         let saturate = reader.read_bool32()?;
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "ladder_filter_unknown_1")?;
         reader.expect_u32(0, "ladder_filter_unknown_2")?;
 
-        let group_id = if effect_version >= 1038 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1038 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(LadderFilter {
@@ -135,9 +137,7 @@ impl EffectRead for LadderFilter {
                 bias,
                 diode,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -146,7 +146,8 @@ impl EffectWrite for LadderFilter {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
         writer.write_hertz(self.cutoff)?;
         writer.write_ratio(self.resonance)?;
@@ -154,14 +155,14 @@ impl EffectWrite for LadderFilter {
         writer.write_ratio(self.bias)?;
         writer.write_bool32(self.diode)?;
         writer.write_bool32(self.saturate)?;
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // ladder_filter_unknown_1
         writer.write_u32(0)?; // ladder_filter_unknown_2
 
-        if snapin.effect_version >= 1038 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version >= 1038 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -173,7 +174,7 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::ratio::percent;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
     use crate::Decibels;
 
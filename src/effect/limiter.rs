@@ -7,7 +7,6 @@
 //! | 2.0.0               | 1047           |
 //! | 2.0.12              | 1048           |
 
-use std::any::Any;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
@@ -15,10 +14,11 @@ use uom::si::f32::Time;
 use uom::si::time::second;
 
 use crate::effect::EffectVersion;
-use crate::{Decibels, Snapin};
+use crate::interpolate::Interpolate;
+use crate::path::impl_parameters;
+use crate::{Decibels, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Limiter {
@@ -28,12 +28,6 @@ pub struct Limiter {
     pub out_gain: Decibels,
 }
 
-impl Limiter {
-    pub fn default_version() -> EffectVersion {
-        1048
-    }
-}
-
 impl Default for Limiter {
     fn default() -> Self {
         Self {
@@ -45,24 +39,25 @@ impl Default for Limiter {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_limiter(&self) -> Option<&Limiter> {
-        self.downcast_ref::<Limiter>()
+impl Interpolate for Limiter {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            threshold: self.threshold.interpolate(&other.threshold, t),
+            release: self.release.interpolate(&other.release, t),
+            in_gain: self.in_gain.interpolate(&other.in_gain, t),
+            out_gain: self.out_gain.interpolate(&other.out_gain, t),
+        }
     }
 }
 
-impl Effect for Limiter {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Limiter {
+    threshold,
+    release,
+    in_gain,
+    out_gain,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Limiter
-    }
-}
+impl_effect!(Limiter, as_limiter, default_version = 1048);
 
 impl EffectRead for Limiter {
     fn read<R: Read + Seek>(
@@ -76,21 +71,22 @@ impl EffectRead for Limiter {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let in_gain = reader.read_decibels_linear()?;
         let out_gain = reader.read_decibels_linear()?;
         let threshold = reader.read_decibels_linear()?;
         let release = reader.read_seconds()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "limiter_unknown_1")?;
         reader.expect_u32(0, "limiter_unknown_2")?;
 
-        let group_id = if effect_version >= 1047 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1047 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Limiter {
@@ -99,9 +95,7 @@ impl EffectRead for Limiter {
                 in_gain,
                 out_gain,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -110,20 +104,21 @@ impl EffectWrite for Limiter {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_decibels_linear(self.in_gain)?;
         writer.write_decibels_linear(self.out_gain)?;
         writer.write_decibels_linear(self.threshold)?;
         writer.write_seconds(self.release)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?;
         writer.write_u32(0)?;
 
-        if snapin.effect_version >= 1047 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version >= 1047 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -134,7 +129,7 @@ impl EffectWrite for Limiter {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
     use crate::Decibels;
 
@@ -7,13 +7,84 @@ use std::any::Any;
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek};
+use std::str::FromStr;
 
 use downcast_rs::{impl_downcast, Downcast};
 use strum::IntoEnumIterator;
 use strum_macros::{EnumIter, FromRepr};
+use uom::si::f32::Ratio;
 
 use crate::io::effects::{EffectRead, EffectReadReturn};
-use crate::PhasePlantReader;
+use crate::path::impl_parameters;
+use crate::{PhasePlantReader, PhasePlantRelease, Preset, Snapin};
+
+/// Implement [`Effect`] for an effect, add the `as_` methods to get the
+/// effect from a `dyn Effect` and the effect version that is written by
+/// default. Effects whose settings when added by Phase Plant aren't the same
+/// as [`Default`] implement `init_preset` themselves and pass
+/// `init_preset = custom`.
+macro_rules! impl_effect {
+    (
+        $type:ident,
+        $as_fn:ident,
+        $(as_mut = $as_mut_fn:ident,)?
+        $(unknown = [$($unknown:ident),+],)?
+        $(init_preset = $init_preset:ident,)?
+        default_version = $version:literal
+    ) => {
+        impl $type {
+            pub fn default_version() -> $crate::effect::EffectVersion {
+                $version
+            }
+        }
+
+        impl_effect!(@init_preset $type $($init_preset)?);
+
+        impl dyn $crate::effect::Effect {
+            #[must_use]
+            pub fn $as_fn(&self) -> Option<&$type> {
+                self.downcast_ref::<$type>()
+            }
+
+            $(
+                #[must_use]
+                pub fn $as_mut_fn(&mut self) -> Option<&mut $type> {
+                    self.downcast_mut::<$type>()
+                }
+            )?
+        }
+
+        impl $crate::effect::Effect for $type {
+            fn box_eq(&self, other: &dyn std::any::Any) -> bool {
+                other
+                    .downcast_ref::<Self>()
+                    .map_or(false, |other| self == other)
+            }
+
+            fn mode(&self) -> $crate::effect::EffectMode {
+                $crate::effect::EffectMode::$type
+            }
+
+            $(
+                #[cfg(feature = "format-research")]
+                fn unknown_fields(&self) -> Vec<(&'static str, $crate::effect::UnknownValue)> {
+                    vec![$((stringify!($unknown), self.$unknown.into())),+]
+                }
+            )?
+        }
+    };
+    (@init_preset $type:ident) => {
+        impl $type {
+            /// The settings when the effect is added in Phase Plant, the
+            /// same as [`Default`].
+            #[must_use]
+            pub fn init_preset() -> Self {
+                Self::default()
+            }
+        }
+    };
+    (@init_preset $type:ident custom) => {};
+}
 
 pub use self::bitcrush::*;
 pub use self::carve_eq::*;
@@ -99,32 +170,155 @@ mod transient_shaper;
 
 pub type EffectVersion = u32;
 
-pub trait Effect: Downcast + std::fmt::Debug {
+pub trait Effect: Downcast + std::fmt::Debug + EffectClone + Send + Sync {
     #[must_use]
     fn box_eq(&self, other: &dyn Any) -> bool;
 
     #[must_use]
     fn mode(&self) -> EffectMode;
+
+    /// Values that are read and written back but whose meaning isn't known,
+    /// by the name of their field. Comparing them with the settings of many
+    /// presets can help to identify them.
+    #[cfg(feature = "format-research")]
+    #[must_use]
+    fn unknown_fields(&self) -> Vec<(&'static str, UnknownValue)> {
+        Vec::new()
+    }
+}
+
+/// A value of an effect whose meaning isn't known.
+#[cfg(feature = "format-research")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UnknownValue {
+    Bool(bool),
+    U32(u32),
+}
+
+#[cfg(feature = "format-research")]
+impl From<bool> for UnknownValue {
+    fn from(value: bool) -> Self {
+        UnknownValue::Bool(value)
+    }
+}
+
+#[cfg(feature = "format-research")]
+impl From<u32> for UnknownValue {
+    fn from(value: u32) -> Self {
+        UnknownValue::U32(value)
+    }
 }
 
 impl_downcast!(Effect);
 
+/// Clone a boxed effect without knowing its concrete type.
+pub trait EffectClone {
+    fn clone_box(&self) -> Box<dyn Effect>;
+}
+
+impl<T: Effect + Clone> EffectClone for T {
+    fn clone_box(&self) -> Box<dyn Effect> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Effect> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
 impl PartialEq for Box<dyn Effect> {
     fn eq(&self, other: &Box<dyn Effect>) -> bool {
-        self.box_eq(other.as_any())
+        // Dereference so the concrete value is compared rather than the box.
+        self.box_eq((**other).as_any())
     }
 }
 
 impl Eq for Box<dyn Effect> {}
 
+/// Downcast to the effects that have a dry/wet mix and borrow it.
+macro_rules! mix_field {
+    ($effect:expr, $downcast:ident $(, $mut_:tt)?) => {
+        mix_field!(@match $effect, $downcast, ($($mut_)?), [
+            Bitcrush, CarveEq, Chorus, CombFilter, Convolver, Delay, Distortion,
+            DualDelay, Dynamics, Ensemble, Faturator, Flanger, Multipass,
+            PhaseDistortion, Phaser, PitchShifter, Resonator, Reverb, Reverser,
+            RingMod, SliceEq, SnapHeap, TranceGate
+        ])
+    };
+    (@match $effect:expr, $downcast:ident, $borrow:tt, [$($type:ident),+]) => {
+        match $effect.mode() {
+            $(EffectMode::$type => mix_field!(@field $effect, $downcast, $type, $borrow),)+
+            _ => None,
+        }
+    };
+    (@field $effect:expr, $downcast:ident, $type:ident, ($($mut_:tt)?)) => {
+        $effect
+            .$downcast::<$type>()
+            .map(|effect| &$($mut_)? effect.mix)
+    };
+}
+
+impl dyn Effect {
+    /// The dry/wet mix, or `None` if the effect doesn't have one.
+    #[must_use]
+    pub fn mix(&self) -> Option<&Ratio> {
+        mix_field!(self, downcast_ref)
+    }
+
+    #[must_use]
+    pub fn mix_mut(&mut self) -> Option<&mut Ratio> {
+        mix_field!(self, downcast_mut, mut)
+    }
+}
+
+impl Preset {
+    /// Call the visitor with every snapin in the lanes, including the snapins
+    /// hosted inside of Multipass. Useful for changing effects in bulk.
+    ///
+    /// ```
+    /// use synthahol_phase_plant::effect::Reverb;
+    /// use synthahol_phase_plant::Preset;
+    /// use uom::si::ratio::percent;
+    /// use uom::si::f32::Ratio;
+    ///
+    /// let mut preset = Preset::default();
+    /// preset.visit_effects_mut(|snapin| {
+    ///     if let Some(reverb) = snapin.effect.downcast_mut::<Reverb>() {
+    ///         reverb.mix += Ratio::new::<percent>(5.0);
+    ///     }
+    /// });
+    /// ```
+    pub fn visit_effects_mut<F: FnMut(&mut Snapin)>(&mut self, mut visitor: F) {
+        for lane in &mut self.lanes {
+            visit_snapins_mut(&mut lane.snapins, &mut visitor);
+        }
+    }
+}
+
+fn visit_snapins_mut<F: FnMut(&mut Snapin)>(snapins: &mut [Snapin], visitor: &mut F) {
+    for snapin in snapins {
+        visitor(snapin);
+        if let Some(multipass) = snapin.effect.downcast_mut::<Multipass>() {
+            for lane in &mut multipass.lanes {
+                visit_snapins_mut(&mut lane.snapins, visitor);
+            }
+        }
+    }
+}
+
 /// The discriminants are the four-byte ID stored in the preset file.
 ///
 /// ```
 /// use synthahol_phase_plant::effect::EffectMode;
 ///
 /// assert_eq!(u32::from_le_bytes(*b"ksbc"), EffectMode::Bitcrush as u32);
+/// assert_eq!(EffectMode::Bitcrush.four_cc(), "ksbc");
+/// assert_eq!("ksrm".parse::<EffectMode>().unwrap(), EffectMode::RingMod);
+/// assert_eq!("Ring Mod".parse::<EffectMode>().unwrap(), EffectMode::RingMod);
 /// ```
-#[derive(Debug, FromRepr, PartialEq)]
+#[derive(Clone, Copy, Debug, EnumIter, Eq, FromRepr, Hash, PartialEq)]
 #[repr(u32)]
 pub enum EffectMode {
     Bitcrush = u32::from_le_bytes(*b"ksbc"),
@@ -175,7 +369,30 @@ impl Display for EffectMode {
     }
 }
 
+/// Parses the [four-character code](EffectMode::four_cc), the
+/// [name](EffectMode::name) or the name of the variant, ignoring case.
+impl FromStr for EffectMode {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        EffectMode::iter()
+            .find(|mode| {
+                mode.four_cc().eq_ignore_ascii_case(text)
+                    || mode.name().eq_ignore_ascii_case(text)
+                    || format!("{mode:?}").eq_ignore_ascii_case(text)
+            })
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("Unknown effect '{text}'")))
+    }
+}
+
 impl EffectMode {
+    /// The four characters that identify the effect in the preset file,
+    /// such as "ksrm" for Ring Mod.
+    pub fn four_cc(&self) -> String {
+        String::from_utf8_lossy(&(*self as u32).to_le_bytes()).into_owned()
+    }
+
     pub fn default_version(&self) -> EffectVersion {
         match self {
             EffectMode::Bitcrush => Bitcrush::default_version(),
@@ -221,6 +438,234 @@ impl EffectMode {
         }
     }
 
+    /// Each version of the effect and the release of Phase Plant that
+    /// introduced it, oldest first. Only versions seen in presets are
+    /// listed. Releases that aren't a [`PhasePlantRelease`] are given as the
+    /// closest listed release before them, so a version may be newer than
+    /// its release.
+    ///
+    /// ```
+    /// use synthahol_phase_plant::effect::EffectMode;
+    /// use synthahol_phase_plant::PhasePlantRelease;
+    ///
+    /// for (version, release) in EffectMode::Convolver.versions() {
+    ///     println!("| {release} | {version} |");
+    /// }
+    /// assert_eq!(
+    ///     EffectMode::Convolver.release_for_version(1018),
+    ///     Some(PhasePlantRelease::V2_0_16)
+    /// );
+    /// ```
+    pub fn versions(&self) -> &'static [(EffectVersion, PhasePlantRelease)] {
+        use PhasePlantRelease::*;
+        match self {
+            EffectMode::Bitcrush => &[
+                (1038, V1_8_5),
+                (1047, V2_0_0),
+                (1048, V2_0_12),
+                (1049, V2_0_16),
+            ],
+            EffectMode::CarveEq => &[(1022, V1_7_0), (1023, V1_8_11), (1034, V2_0_16)],
+            EffectMode::ChannelMixer => &[(1002, V2_0_16)],
+            EffectMode::Chorus => &[
+                (1037, V1_6_9),
+                (1046, V2_0_0),
+                (1047, V2_0_12),
+                (1048, V2_0_16),
+            ],
+            EffectMode::CombFilter => &[
+                (1038, V1_8_5),
+                (1047, V2_0_0),
+                (1048, V2_0_12),
+                (1049, V2_0_16),
+            ],
+            EffectMode::Compressor => &[(1039, V1_8_0), (1049, V2_0_12), (1050, V2_1_0)],
+            EffectMode::Convolver => &[
+                (1007, V1_8_11),
+                (1016, V2_0_0),
+                (1017, V2_0_12),
+                (1018, V2_0_16),
+            ],
+            EffectMode::Delay => &[
+                (1037, V1_8_5),
+                (1046, V2_0_0),
+                (1049, V2_0_12),
+                (1050, V2_0_16),
+            ],
+            EffectMode::Disperser => &[(1039, V1_8_5), (1050, V2_0_12)],
+            EffectMode::Distortion => &[
+                (1037, V1_6_9),
+                (1038, V1_8_5),
+                (1049, V2_0_12),
+                (1050, V2_0_16),
+            ],
+            EffectMode::DualDelay => &[(1012, V2_0_12), (1013, V2_0_16)],
+            EffectMode::Dynamics => &[(1003, V1_8_5), (1014, V2_0_16)],
+            EffectMode::Ensemble => &[
+                (1003, V1_8_5),
+                (1012, V2_0_0),
+                (1013, V2_0_12),
+                (1014, V2_0_16),
+            ],
+            EffectMode::Faturator => &[(1040, V1_6_9), (1049, V2_0_0), (1051, V2_0_16)],
+            EffectMode::Filter => &[
+                (1039, V1_6_9),
+                (1038, V1_8_5),
+                (1040, V1_8_11),
+                (1051, V2_0_16),
+            ],
+            EffectMode::Flanger => &[(1002, V1_8_0), (1011, V2_0_0), (1013, V2_0_16)],
+            EffectMode::FormantFilter => &[(1037, V1_8_5), (1047, V2_0_12), (1048, V2_0_16)],
+            EffectMode::FrequencyShifter => &[(1037, V1_8_5), (1047, V2_0_12), (1048, V2_0_16)],
+            EffectMode::Gain => &[
+                (1038, V1_8_0),
+                (1039, V1_8_5),
+                (1048, V2_0_0),
+                (1050, V2_0_16),
+            ],
+            EffectMode::Gate => &[(1029, V1_8_11), (1040, V2_0_16)],
+            EffectMode::Group => &[(1007, V2_0_0)],
+            EffectMode::Haas => &[(1037, V1_7_0), (1046, V2_0_0), (1048, V2_0_16)],
+            EffectMode::LadderFilter => &[(1029, V1_8_5), (1038, V2_0_0), (1040, V2_0_16)],
+            EffectMode::Limiter => &[(1038, V1_8_5), (1047, V2_0_0), (1048, V2_0_12)],
+            EffectMode::Multipass => &[
+                (1044, V1_8_0),
+                (1056, V2_0_0),
+                (1057, V2_0_12),
+                (1058, V2_0_16),
+            ],
+            EffectMode::NonlinearFilter => &[(1000, V1_8_11), (1011, V2_0_16), (1012, V2_1_1)],
+            EffectMode::PhaseDistortion => &[(1023, V1_8_5), (1034, V2_0_16)],
+            EffectMode::Phaser => &[(1037, V1_8_5), (1046, V2_0_0), (1048, V2_0_16)],
+            EffectMode::PitchShifter => &[(1038, V1_6_9), (1039, V1_8_5), (1050, V2_0_16)],
+            EffectMode::Resonator => &[(1038, V1_8_5), (1049, V2_0_16)],
+            EffectMode::Reverb => &[(1032, V1_7_0), (1043, V2_0_16)],
+            EffectMode::Reverser => &[(1033, V1_8_5), (1044, V2_0_16)],
+            EffectMode::RingMod => &[(1032, V1_8_5), (1043, V2_0_16)],
+            EffectMode::SliceEq => &[
+                (1019, V1_7_0),
+                (1020, V1_8_0),
+                (1021, V1_8_11),
+                (1032, V2_0_16),
+            ],
+            EffectMode::SnapHeap => &[(1038, V1_8_0), (1050, V2_0_12), (1051, V2_0_16)],
+            EffectMode::Stereo => &[(1038, V1_7_5), (1047, V2_0_0), (1049, V2_0_16)],
+            EffectMode::TapeStop => &[(1034, V1_8_5), (1045, V2_0_16)],
+            EffectMode::ThreeBandEq => &[
+                (1015, V1_8_5),
+                (1024, V2_0_0),
+                (1025, V2_0_12),
+                (1026, V2_0_16),
+            ],
+            EffectMode::TranceGate => &[(1038, V1_8_11), (1049, V2_0_16)],
+            EffectMode::TransientShaper => &[(1027, V1_8_5), (1037, V2_0_12), (1038, V2_0_16)],
+        }
+    }
+
+    /// The release of Phase Plant that introduced a version of the effect,
+    /// or `None` if the version isn't known.
+    pub fn release_for_version(&self, version: EffectVersion) -> Option<PhasePlantRelease> {
+        self.versions()
+            .iter()
+            .find(|(known, _)| *known == version)
+            .map(|(_, release)| *release)
+    }
+
+    /// A new effect of this mode with the default settings.
+    pub fn default_effect(&self) -> Box<dyn Effect> {
+        match self {
+            EffectMode::Bitcrush => Box::<Bitcrush>::default(),
+            EffectMode::CarveEq => Box::<CarveEq>::default(),
+            EffectMode::ChannelMixer => Box::<ChannelMixer>::default(),
+            EffectMode::Chorus => Box::<Chorus>::default(),
+            EffectMode::CombFilter => Box::<CombFilter>::default(),
+            EffectMode::Compressor => Box::<Compressor>::default(),
+            EffectMode::Convolver => Box::<Convolver>::default(),
+            EffectMode::Delay => Box::<Delay>::default(),
+            EffectMode::Disperser => Box::<Disperser>::default(),
+            EffectMode::Distortion => Box::<Distortion>::default(),
+            EffectMode::DualDelay => Box::<DualDelay>::default(),
+            EffectMode::Dynamics => Box::<Dynamics>::default(),
+            EffectMode::Ensemble => Box::<Ensemble>::default(),
+            EffectMode::Faturator => Box::<Faturator>::default(),
+            EffectMode::Filter => Box::<Filter>::default(),
+            EffectMode::Flanger => Box::<Flanger>::default(),
+            EffectMode::FormantFilter => Box::<FormantFilter>::default(),
+            EffectMode::FrequencyShifter => Box::<FrequencyShifter>::default(),
+            EffectMode::Gain => Box::<Gain>::default(),
+            EffectMode::Gate => Box::<Gate>::default(),
+            EffectMode::Group => Box::<Group>::default(),
+            EffectMode::Haas => Box::<Haas>::default(),
+            EffectMode::LadderFilter => Box::<LadderFilter>::default(),
+            EffectMode::Limiter => Box::<Limiter>::default(),
+            EffectMode::Multipass => Box::<Multipass>::default(),
+            EffectMode::NonlinearFilter => Box::<NonlinearFilter>::default(),
+            EffectMode::PhaseDistortion => Box::<PhaseDistortion>::default(),
+            EffectMode::Phaser => Box::<Phaser>::default(),
+            EffectMode::PitchShifter => Box::<PitchShifter>::default(),
+            EffectMode::Resonator => Box::<Resonator>::default(),
+            EffectMode::Reverb => Box::<Reverb>::default(),
+            EffectMode::Reverser => Box::<Reverser>::default(),
+            EffectMode::RingMod => Box::<RingMod>::default(),
+            EffectMode::SliceEq => Box::<SliceEq>::default(),
+            EffectMode::SnapHeap => Box::<SnapHeap>::default(),
+            EffectMode::Stereo => Box::<Stereo>::default(),
+            EffectMode::TapeStop => Box::<TapeStop>::default(),
+            EffectMode::ThreeBandEq => Box::<ThreeBandEq>::default(),
+            EffectMode::TranceGate => Box::<TranceGate>::default(),
+            EffectMode::TransientShaper => Box::<TransientShaper>::default(),
+        }
+    }
+
+    /// A new effect of this mode with the settings Phase Plant uses when the
+    /// effect is added to a lane. They can differ slightly from the
+    /// [default effect](Self::default_effect) because Phase Plant stores the
+    /// values of its controls rather than the values they display.
+    pub fn init_effect(&self) -> Box<dyn Effect> {
+        match self {
+            EffectMode::Bitcrush => Box::new(Bitcrush::init_preset()),
+            EffectMode::CarveEq => Box::new(CarveEq::init_preset()),
+            EffectMode::ChannelMixer => Box::new(ChannelMixer::init_preset()),
+            EffectMode::Chorus => Box::new(Chorus::init_preset()),
+            EffectMode::CombFilter => Box::new(CombFilter::init_preset()),
+            EffectMode::Compressor => Box::new(Compressor::init_preset()),
+            EffectMode::Convolver => Box::new(Convolver::init_preset()),
+            EffectMode::Delay => Box::new(Delay::init_preset()),
+            EffectMode::Disperser => Box::new(Disperser::init_preset()),
+            EffectMode::Distortion => Box::new(Distortion::init_preset()),
+            EffectMode::DualDelay => Box::new(DualDelay::init_preset()),
+            EffectMode::Dynamics => Box::new(Dynamics::init_preset()),
+            EffectMode::Ensemble => Box::new(Ensemble::init_preset()),
+            EffectMode::Faturator => Box::new(Faturator::init_preset()),
+            EffectMode::Filter => Box::new(Filter::init_preset()),
+            EffectMode::Flanger => Box::new(Flanger::init_preset()),
+            EffectMode::FormantFilter => Box::new(FormantFilter::init_preset()),
+            EffectMode::FrequencyShifter => Box::new(FrequencyShifter::init_preset()),
+            EffectMode::Gain => Box::new(Gain::init_preset()),
+            EffectMode::Gate => Box::new(Gate::init_preset()),
+            EffectMode::Group => Box::new(Group::init_preset()),
+            EffectMode::Haas => Box::new(Haas::init_preset()),
+            EffectMode::LadderFilter => Box::new(LadderFilter::init_preset()),
+            EffectMode::Limiter => Box::new(Limiter::init_preset()),
+            EffectMode::Multipass => Box::new(Multipass::init_preset()),
+            EffectMode::NonlinearFilter => Box::new(NonlinearFilter::init_preset()),
+            EffectMode::PhaseDistortion => Box::new(PhaseDistortion::init_preset()),
+            EffectMode::Phaser => Box::new(Phaser::init_preset()),
+            EffectMode::PitchShifter => Box::new(PitchShifter::init_preset()),
+            EffectMode::Resonator => Box::new(Resonator::init_preset()),
+            EffectMode::Reverb => Box::new(Reverb::init_preset()),
+            EffectMode::Reverser => Box::new(Reverser::init_preset()),
+            EffectMode::RingMod => Box::new(RingMod::init_preset()),
+            EffectMode::SliceEq => Box::new(SliceEq::init_preset()),
+            EffectMode::SnapHeap => Box::new(SnapHeap::init_preset()),
+            EffectMode::Stereo => Box::new(Stereo::init_preset()),
+            EffectMode::TapeStop => Box::new(TapeStop::init_preset()),
+            EffectMode::ThreeBandEq => Box::new(ThreeBandEq::init_preset()),
+            EffectMode::TranceGate => Box::new(TranceGate::init_preset()),
+            EffectMode::TransientShaper => Box::new(TransientShaper::init_preset()),
+        }
+    }
+
     pub(crate) fn is_host(&self) -> bool {
         use EffectMode::*;
         match self {
@@ -232,7 +677,7 @@ impl EffectMode {
         }
     }
 
-    pub fn name(&self) -> &str {
+    pub fn name(&self) -> &'static str {
         use EffectMode::*;
         match self {
             Bitcrush => "Bitcrush",
@@ -360,3 +805,204 @@ impl Display for SidechainMode {
         f.write_str(msg)
     }
 }
+
+impl_parameters!(SidechainMode);
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "format-research")]
+    use crate::test::read_effect_preset;
+    use std::fs;
+
+    use crate::test::{rewrite_effect, write_effect};
+    use crate::tests::test_data_path;
+
+    use super::*;
+
+    #[test]
+    fn mode_from_str() {
+        for mode in EffectMode::iter() {
+            assert_eq!(mode.four_cc().len(), 4);
+            assert_eq!(mode.four_cc().parse::<EffectMode>().unwrap(), mode);
+            assert_eq!(mode.name().parse::<EffectMode>().unwrap(), mode);
+            assert_eq!(format!("{mode:?}").parse::<EffectMode>().unwrap(), mode);
+        }
+        assert_eq!(EffectMode::RingMod.four_cc(), "ksrm");
+        assert_eq!(
+            "3-band eq".parse::<EffectMode>().unwrap(),
+            EffectMode::ThreeBandEq
+        );
+        let error = "Wobble".parse::<EffectMode>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+
+    /// Compare with the newest preset of each effect that was saved right
+    /// after adding the effect.
+    #[test]
+    fn init_presets() {
+        let mut compared = 0;
+        for entry in fs::read_dir(test_data_path(&["effects"])).unwrap() {
+            let dir = entry.unwrap().path();
+            let effect_name = dir.file_name().unwrap().to_string_lossy().into_owned();
+            let newest = fs::read_dir(&dir)
+                .unwrap()
+                .filter_map(|entry| {
+                    let path = entry.unwrap().path();
+                    let stem = path.file_stem()?.to_str()?;
+                    let version = stem
+                        .strip_prefix(&format!("{effect_name}-"))?
+                        .split('.')
+                        .map(|part| part.parse::<u32>().ok())
+                        .collect::<Option<Vec<_>>>()?;
+                    Some((version, path))
+                })
+                .max();
+            let Some((_, path)) = newest else {
+                continue;
+            };
+            let preset = Preset::read_file(&path).unwrap();
+            let effect = &preset.lanes[0].snapins[0].effect;
+            assert_eq!(effect, &effect.mode().init_effect(), "{path:?}");
+            compared += 1;
+        }
+        assert!(compared > 30);
+    }
+
+    /// The effects of every fixture are written back the same way with the
+    /// layout of their effect version, including those saved by Phase
+    /// Plant 1.7 and 1.8 before the group was stored.
+    #[test]
+    fn rewrite_fixtures() {
+        let mut failures = Vec::new();
+        for entry in fs::read_dir(test_data_path(&["effects"])).unwrap() {
+            for entry in fs::read_dir(entry.unwrap().path()).unwrap() {
+                let path = entry.unwrap().path();
+                // Some Multipass presets can't be read yet.
+                let Ok(preset) = Preset::read_file(&path) else {
+                    continue;
+                };
+                for snapin in preset.lanes.iter().flat_map(|lane| &lane.snapins) {
+                    match rewrite_effect(snapin) {
+                        Ok(rewritten) => {
+                            if !rewritten.effect.eq(&snapin.effect)
+                                || rewritten.state != snapin.state()
+                            {
+                                failures
+                                    .push(format!("{path:?} {}: differs", snapin.effect.mode()));
+                            }
+                        }
+                        Err(error) if error.kind() == ErrorKind::Unsupported => (),
+                        Err(error) => {
+                            failures.push(format!("{path:?} {}: {error}", snapin.effect.mode()))
+                        }
+                    }
+                }
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    /// Every effect version in the presets is known, and the release that
+    /// introduced it isn't newer than the release that saved the preset.
+    #[test]
+    fn versions() {
+        for mode in EffectMode::iter() {
+            let versions = mode.versions();
+            assert!(!versions.is_empty(), "{mode}");
+            assert!(versions.windows(2).all(|pair| pair[0].1 < pair[1].1));
+        }
+
+        let mut checked = 0;
+        for entry in fs::read_dir(test_data_path(&["effects"])).unwrap() {
+            for entry in fs::read_dir(entry.unwrap().path()).unwrap() {
+                let path = entry.unwrap().path();
+                // Some Multipass presets can't be read yet.
+                let Ok(preset) = Preset::read_file(&path) else {
+                    continue;
+                };
+                for snapin in preset.lanes.iter().flat_map(|lane| &lane.snapins) {
+                    let mode = snapin.effect.mode();
+                    let release = mode
+                        .release_for_version(snapin.effect_version)
+                        .unwrap_or_else(|| {
+                            panic!("{path:?} {mode} version {}", snapin.effect_version)
+                        });
+                    if snapin.host_version.major > 0 {
+                        assert!(
+                            snapin.host_version.is_at_least(&release.version()),
+                            "{path:?}"
+                        );
+                    }
+                    checked += 1;
+                }
+            }
+        }
+        assert!(checked > 100);
+    }
+
+    /// Every effect must read back what it wrote, including the group it is
+    /// in. The bytes are compared rather than the effects because some
+    /// parameters, such as decibels stored as linear gain, can't be converted
+    /// back exactly.
+    #[test]
+    fn rewrite_defaults() {
+        // Writing these effects has not been completed yet so it must fail
+        // rather than write a corrupt preset.
+        let unsupported = [
+            EffectMode::CarveEq,
+            EffectMode::Convolver,
+            EffectMode::Multipass,
+            EffectMode::SliceEq,
+            EffectMode::SnapHeap,
+        ];
+
+        let mut failures = Vec::new();
+        for mode in unsupported {
+            let snapin = Snapin::new(mode.default_effect(), 1, false, true);
+            match write_effect(&snapin) {
+                Err(error) if error.kind() == ErrorKind::Unsupported => (),
+                _ => failures.push(format!("{mode} must not be written")),
+            }
+        }
+        for mode in EffectMode::iter().filter(|mode| !unsupported.contains(mode)) {
+            let effect = mode.default_effect();
+            assert_eq!(effect.mode(), mode);
+            let mut snapin = Snapin::new(effect, 1, false, true);
+            snapin.group_id = Some(2);
+            let rewritten = rewrite_effect(&snapin).and_then(|read| {
+                let mut rewritten = Snapin {
+                    effect: read.effect,
+                    ..snapin.clone()
+                };
+                rewritten.set_state(read.state);
+                Ok((write_effect(&snapin)?, write_effect(&rewritten)?))
+            });
+            match rewritten {
+                Ok((before, after)) if before != after => {
+                    failures.push(format!("{mode} changed when rewritten"))
+                }
+                Ok(_) => (),
+                Err(error) => failures.push(format!("{mode}: {error}")),
+            }
+        }
+        assert!(failures.is_empty(), "{failures:#?}");
+    }
+
+    #[cfg(feature = "format-research")]
+    #[test]
+    fn unknown_fields() {
+        let preset = read_effect_preset("reverser", "reverser-2.0.16.phaseplant").unwrap();
+        assert_eq!(
+            preset.lanes[0].snapins[0].effect.unknown_fields(),
+            [
+                ("unknown2", UnknownValue::U32(4)),
+                ("unknown3", UnknownValue::U32(4)),
+            ]
+        );
+        assert!(Gain::default().unknown_fields().is_empty());
+        assert_eq!(
+            Disperser::default().unknown_fields(),
+            [("unknown2", UnknownValue::Bool(true))]
+        );
+    }
+}
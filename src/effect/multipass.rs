@@ -8,26 +8,32 @@
 //! | 1.8.0 to 1.8.5      | 1044           |
 //! | 2.0.0               | 1056           |
 //! | 2.0.12              | 1057           |
-//! | 2.1.0               | 1058           |
+//! | 2.0.16 to 2.1.0     | 1058           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
+use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
 use log::trace;
 use strum_macros::EnumIter;
-use uom::si::f32::Ratio;
+use uom::si::f32::{Frequency, Ratio};
+use uom::si::frequency::hertz;
 use uom::si::ratio::percent;
 
 use crate::effect::EffectVersion;
-use crate::{Decibels, MacroControl, Snapin};
+use crate::interpolate::{interpolate_snapins, step, Interpolate};
+use crate::path::impl_parameters;
+use crate::{Decibels, MacroControl, Snapin, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
-#[derive(Debug, PartialEq)]
+/// The first [`BAND_COUNT`](Multipass::BAND_COUNT) lanes are the frequency
+/// bands, followed by the lanes before and after the bands.
+#[derive(Clone, Debug, PartialEq)]
 pub struct Lane {
+    /// Bands that are disabled are not shown.
     pub enabled: bool,
 
     /// There is no restriction on the number of snapins.
@@ -41,6 +47,16 @@ pub struct Lane {
     pub post: Ratio,
 }
 
+impl_parameters!(Lane {
+    enabled,
+    mute,
+    solo,
+    gain,
+    mix,
+    pan,
+    post,
+});
+
 impl Lane {
     pub const COUNT: usize = 7;
 }
@@ -60,6 +76,22 @@ impl Default for Lane {
     }
 }
 
+impl Lane {
+    /// Blend two lanes that have the same types of effects in the same order.
+    pub fn interpolate(&self, other: &Lane, t: f32) -> Result<Lane, Error> {
+        Ok(Lane {
+            enabled: step(&self.enabled, &other.enabled, t),
+            snapins: interpolate_snapins(&self.snapins, &other.snapins, t)?,
+            mute: step(&self.mute, &other.mute, t),
+            solo: step(&self.solo, &other.solo, t),
+            gain: self.gain.interpolate(&other.gain, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            pan: self.pan.interpolate(&other.pan, t),
+            post: self.post.interpolate(&other.post, t),
+        })
+    }
+}
+
 #[derive(Clone, Copy, Debug, EnumIter, Eq, PartialEq)]
 #[repr(u8)]
 pub enum ExternalInputMode {
@@ -89,7 +121,9 @@ impl Display for ExternalInputMode {
     }
 }
 
-#[derive(Debug, PartialEq)]
+impl_parameters!(ExternalInputMode);
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Multipass {
     pub name: Option<String>,
     pub gain: Decibels,
@@ -98,47 +132,151 @@ pub struct Multipass {
     pub external_input_mode: ExternalInputMode,
     pub lanes: [Lane; Lane::COUNT],
     pub macro_controls: [MacroControl; MacroControl::COUNT],
-}
 
-impl Multipass {
-    pub fn default_version() -> EffectVersion {
-        1058
-    }
+    /// The frequencies that split the bands, lowest first. Crossover `n` is
+    /// between band `n` and band `n + 1`. There are always
+    /// [`CROSSOVER_COUNT`](Multipass::CROSSOVER_COUNT) crossovers, including
+    /// those next to bands that are disabled.
+    pub crossovers: Vec<Frequency>,
 }
 
+impl_parameters!(Multipass {
+    name,
+    gain,
+    pan,
+    mix,
+    external_input_mode,
+    lanes,
+    macro_controls,
+    crossovers,
+});
+
 impl Default for Multipass {
     fn default() -> Self {
+        let mut lanes: [Lane; Lane::COUNT] = Default::default();
+        lanes[0].enabled = false;
+        lanes[Multipass::BAND_COUNT - 1].enabled = false;
         Self {
             name: None,
             gain: Decibels::ZERO,
             pan: Ratio::new::<percent>(50.0),
             mix: Ratio::new::<percent>(100.0),
             external_input_mode: ExternalInputMode::Off,
-            lanes: Default::default(),
+            lanes,
             macro_controls: MacroControl::defaults(),
+            crossovers: Multipass::CROSSOVERS_DEFAULT_HERTZ
+                .iter()
+                .map(|crossover| Frequency::new::<hertz>(*crossover))
+                .collect(),
         }
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_multipass(&self) -> Option<&Multipass> {
-        self.downcast_ref::<Multipass>()
+impl Multipass {
+    /// Number of lanes that are frequency bands.
+    pub const BAND_COUNT: usize = 5;
+
+    pub const CROSSOVER_COUNT: usize = Self::BAND_COUNT - 1;
+
+    const CROSSOVERS_DEFAULT_HERTZ: [f32; Self::CROSSOVER_COUNT] =
+        [169.0, 249.627_18, 2_995.340_6, 4_040.0];
+
+    /// The crossovers between the bands that are enabled.
+    pub fn band_crossovers(&self) -> &[Frequency] {
+        let enabled = |lane: &Lane| lane.enabled;
+        let bands = &self.lanes[..Self::BAND_COUNT];
+        match (
+            bands.iter().position(enabled),
+            bands.iter().rposition(enabled),
+        ) {
+            (Some(first), Some(last)) => &self.crossovers[first..last],
+            _ => &[],
+        }
     }
-}
 
-impl Effect for Multipass {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
+    /// Replace the crossovers, checking that there is one for each pair of
+    /// bands and that they are in order.
+    pub fn set_crossovers(&mut self, crossovers: Vec<Frequency>) -> Result<(), Error> {
+        check_crossovers(&crossovers)?;
+        self.crossovers = crossovers;
+        Ok(())
     }
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Multipass
+    /// Move the crossover between band `index` and the band after it. It
+    /// can't pass the crossovers on either side.
+    pub fn set_crossover(&mut self, index: usize, frequency: Frequency) -> Result<(), Error> {
+        let mut crossovers = self.crossovers.clone();
+        match crossovers.get_mut(index) {
+            Some(crossover) => *crossover = frequency,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Crossover {index} is past the last of the {} crossovers",
+                        self.crossovers.len()
+                    ),
+                ))
+            }
+        }
+        self.set_crossovers(crossovers)
+    }
+
+    /// Blend two instances of Multipass whose lanes have the same types of
+    /// effects in the same order.
+    pub fn interpolate(&self, other: &Multipass, t: f32) -> Result<Multipass, Error> {
+        let mut lanes: [Lane; Lane::COUNT] = Default::default();
+        for (index, lane) in lanes.iter_mut().enumerate() {
+            *lane = self.lanes[index].interpolate(&other.lanes[index], t)?;
+        }
+        Ok(Multipass {
+            name: step(&self.name, &other.name, t),
+            gain: self.gain.interpolate(&other.gain, t),
+            pan: self.pan.interpolate(&other.pan, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            external_input_mode: step(&self.external_input_mode, &other.external_input_mode, t),
+            lanes,
+            macro_controls: self.macro_controls.interpolate(&other.macro_controls, t),
+            crossovers: self.crossovers.interpolate(&other.crossovers, t),
+        })
     }
 }
 
+fn check_crossovers(crossovers: &[Frequency]) -> Result<(), Error> {
+    if crossovers.len() != Multipass::CROSSOVER_COUNT {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Multipass has {} crossovers, not {}",
+                Multipass::CROSSOVER_COUNT,
+                crossovers.len()
+            ),
+        ));
+    }
+    if let Some(index) = crossovers.windows(2).position(|pair| {
+        !matches!(
+            pair[0].partial_cmp(&pair[1]),
+            Some(Ordering::Less | Ordering::Equal)
+        )
+    }) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Crossover {index} at {} Hz is above the next crossover at {} Hz",
+                crossovers[index].get::<hertz>(),
+                crossovers[index + 1].get::<hertz>()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+impl_effect!(
+    Multipass,
+    as_multipass,
+    as_mut = as_multipass_mut,
+    default_version = 1058
+);
+
 impl EffectRead for Multipass {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -154,6 +292,8 @@ impl EffectRead for Multipass {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         // FIXME: Metadata is before the start.
 
         let mut effect = Multipass::default();
@@ -165,21 +305,22 @@ impl EffectRead for Multipass {
             "multipass: preset name {preset_name:?}, path {preset_path:?}, edited {preset_edited}"
         );
 
-        let enabled = true;
-        let group_id = None;
-
         reader.expect_bool32(true, "multipass_1")?;
-        reader.skip(20)?;
+        for crossover in &mut effect.crossovers {
+            *crossover = reader.read_hertz()?;
+        }
+        trace!("multipass: crossovers {:?}", effect.crossovers);
+        reader.skip(4)?;
 
         trace!("multipass: lanes pos {}", reader.pos());
         for lane in &mut effect.lanes {
-            reader.skip(4)?; // FIXME: Decode.
+            lane.enabled = reader.read_bool32()?;
             lane.gain = reader.read_decibels_linear()?;
             lane.pan = reader.read_ratio()?;
             lane.mix = reader.read_ratio()?;
             lane.post = reader.read_ratio()?;
-            reader.skip(4)?;
-            reader.skip(4)?;
+            lane.mute = reader.read_bool32()?;
+            lane.solo = reader.read_bool32()?;
             trace!("multipass: lane {lane:?}");
         }
 
@@ -189,7 +330,7 @@ impl EffectRead for Multipass {
 
         reader.skip(1696 - 276)?;
 
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
         reader.skip(272)?;
 
         if effect_version >= 1056 {
@@ -211,9 +352,7 @@ impl EffectRead for Multipass {
 
         Ok(EffectReadReturn {
             effect: Box::new(effect),
-            enabled,
-            minimized,
-            group_id,
+            state,
             metadata: Default::default(),
             preset_name,
             preset_path,
@@ -225,16 +364,16 @@ impl EffectRead for Multipass {
 impl EffectWrite for Multipass {
     fn write<W: Write + Seek>(
         &self,
-        writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        _writer: &mut PhasePlantWriter<W>,
+        _state: &SnapinState,
+        _effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_string_and_length(&snapin.preset_name)?;
-        writer.write_path(&snapin.preset_path)?;
-        writer.write_bool8(snapin.preset_edited)?;
-
-        // TODO: Finish writing Multipass
-
-        Ok(())
+        // The lanes and their effects are not written yet so the file
+        // would not be readable.
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "Writing Multipass is not supported",
+        ))
     }
 }
 
@@ -242,8 +381,9 @@ impl EffectWrite for Multipass {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
-    use crate::test::read_effect_preset;
+    use crate::effect::{Effect, EffectMode, Filter};
+    use crate::test::{read_effect_preset, rewrite_effect};
+    use crate::Preset;
 
     use super::*;
 
@@ -378,7 +518,107 @@ mod test {
         assert!(!snapin.minimized);
         let effect = snapin.effect.as_multipass().unwrap();
         assert_relative_eq!(effect.macro_controls[0].value, 0.1);
-        // FIXME: Frequency splits
+    }
+
+    /// The second and third crossovers are the ones between the bands that
+    /// are enabled by default.
+    #[test]
+    fn crossovers() {
+        let preset = read_effect_preset(
+            "multipass",
+            "multipass-split_2_100-split_3_2000-disabled-1.8.0.phaseplant",
+        )
+        .unwrap();
+        let effect = preset.lanes[0].snapins[0].effect.as_multipass().unwrap();
+        let crossovers: Vec<f32> = effect
+            .band_crossovers()
+            .iter()
+            .map(|crossover| crossover.get::<hertz>())
+            .collect();
+        assert_eq!(crossovers.len(), 2);
+        assert_relative_eq!(crossovers[0], 100.0, epsilon = 0.001);
+        assert_relative_eq!(crossovers[1], 2000.0, epsilon = 0.001);
+        assert_eq!(effect.crossovers[0], Multipass::default().crossovers[0]);
+
+        let mut effect = Multipass::default();
+        assert_eq!(effect.band_crossovers(), &effect.crossovers[1..3]);
+        effect
+            .set_crossover(1, Frequency::new::<hertz>(200.0))
+            .unwrap();
+        assert_eq!(effect.crossovers[1].get::<hertz>(), 200.0);
+        for (index, frequency) in [(1, 100.0), (2, 5000.0), (4, 1000.0)] {
+            let error = effect
+                .set_crossover(index, Frequency::new::<hertz>(frequency))
+                .unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        }
+        assert!(effect
+            .set_crossovers(vec![Frequency::new::<hertz>(100.0)])
+            .is_err());
+        assert!(effect
+            .set_crossover(0, Frequency::new::<hertz>(f32::NAN))
+            .is_err());
+        assert_eq!(effect.crossovers[1].get::<hertz>(), 200.0);
+
+        for lane in &mut effect.lanes {
+            lane.enabled = false;
+        }
+        assert!(effect.band_crossovers().is_empty());
+        effect.lanes[2].enabled = true;
+        assert!(effect.band_crossovers().is_empty());
+    }
+
+    #[test]
+    fn lanes_enabled_mute_solo() {
+        let lanes = |file: &str| {
+            let preset = read_effect_preset("multipass", file).unwrap();
+            preset.lanes[0].snapins[0]
+                .effect
+                .as_multipass()
+                .unwrap()
+                .lanes
+                .clone()
+        };
+        let disabled = lanes("multipass-lanes-disabled-2.1.0.phaseplant");
+        assert!(disabled.iter().all(|lane| !lane.enabled));
+        let muted = lanes("multipass-lanes-mute-2.1.0.phaseplant");
+        assert!(muted[..Multipass::BAND_COUNT].iter().all(|lane| lane.mute));
+        assert!(!muted[Multipass::BAND_COUNT].mute);
+        let soloed = lanes("multipass-lanes-solo-2.1.0.phaseplant");
+        assert!(soloed[..Lane::COUNT - 1].iter().all(|lane| lane.solo));
+        assert!(!soloed[Lane::COUNT - 1].solo);
+    }
+
+    #[test]
+    fn visit_effects() {
+        let mut multipass = Multipass::default();
+        multipass.lanes[1]
+            .snapins
+            .push(Snapin::new(Box::new(Filter::default()), 1, true, false));
+        let mut preset = Preset::default();
+        preset.lanes[0]
+            .snapins
+            .push(Snapin::new(Box::new(multipass), 1, true, false));
+
+        let mut modes = Vec::new();
+        preset.visit_effects_mut(|snapin| {
+            modes.push(snapin.effect.mode());
+            snapin.enabled = false;
+        });
+        assert_eq!(modes, [EffectMode::Multipass, EffectMode::Filter]);
+
+        let snapin = &preset.lanes[0].snapins[0];
+        assert!(!snapin.enabled);
+        let multipass = snapin.effect.as_multipass().unwrap();
+        assert!(!multipass.lanes[1].snapins[0].enabled);
+    }
+
+    #[test]
+    fn write_unsupported() {
+        let snapin = Snapin::new(Box::new(Multipass::default()), 1, true, false);
+        assert!(
+            matches!(rewrite_effect(&snapin), Err(error) if error.kind() == ErrorKind::Unsupported)
+        );
     }
 
     // #[test]
@@ -7,23 +7,26 @@
 //! |---------------------|----------------|
 //! | 1.8.16              | 1000           |
 //! | 2.0.16              | 1011           |
+//! | 2.1.3               | 1012           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use strum_macros::FromRepr;
+use strum_macros::{EnumIter, FromRepr};
 use uom::si::f32::Frequency;
 use uom::si::frequency::hertz;
 
-use crate::effect::{EffectVersion, FilterMode};
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::effect::FilterMode;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
-#[derive(Copy, Clone, Debug, FromRepr, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum NonlinearFilterMode {
     // The discriminants correspond to the file format.
@@ -67,6 +70,8 @@ impl Display for NonlinearFilterMode {
     }
 }
 
+impl_parameters!(NonlinearFilterMode);
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct NonlinearFilter {
     pub cutoff: Frequency,
@@ -76,19 +81,6 @@ pub struct NonlinearFilter {
     pub filter_mode: FilterMode,
 }
 
-impl NonlinearFilter {
-    pub fn default_version() -> EffectVersion {
-        1011
-    }
-}
-
-impl dyn Effect {
-    #[must_use]
-    pub fn as_nonlinear_filter(&self) -> Option<&NonlinearFilter> {
-        self.downcast_ref::<NonlinearFilter>()
-    }
-}
-
 impl Default for NonlinearFilter {
     fn default() -> Self {
         Self {
@@ -101,18 +93,28 @@ impl Default for NonlinearFilter {
     }
 }
 
-impl Effect for NonlinearFilter {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
-
-    fn mode(&self) -> EffectMode {
-        EffectMode::NonlinearFilter
+impl Interpolate for NonlinearFilter {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            cutoff: self.cutoff.interpolate(&other.cutoff, t),
+            q: self.q.interpolate(&other.q, t),
+            drive: self.drive.interpolate(&other.drive, t),
+            mode: step(&self.mode, &other.mode, t),
+            filter_mode: step(&self.filter_mode, &other.filter_mode, t),
+        }
     }
 }
 
+impl_parameters!(NonlinearFilter {
+    cutoff,
+    q,
+    drive,
+    mode,
+    filter_mode,
+});
+
+impl_effect!(NonlinearFilter, as_nonlinear_filter, default_version = 1011);
+
 impl EffectRead for NonlinearFilter {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -128,22 +130,22 @@ impl EffectRead for NonlinearFilter {
             ));
         }
 
-        let filter_mode = FilterMode::from_id(reader.read_u32()?)?;
+        let mut state = SnapinState::default();
+
+        let filter_mode = reader.read_forward_compatible()?;
         let mode = NonlinearFilterMode::from_id(reader.read_u32()?)?;
         let cutoff = reader.read_hertz()?;
         let q = reader.read_f32()?;
         let drive = reader.read_f32()?;
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "nonlinear_filter_unknown1")?;
         reader.expect_u32(0, "nonlinear_filter_unknown2")?;
 
-        let group_id = if effect_version > 1000 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1000 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(NonlinearFilter {
@@ -153,9 +155,7 @@ impl EffectRead for NonlinearFilter {
                 mode,
                 filter_mode,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -164,21 +164,22 @@ impl EffectWrite for NonlinearFilter {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_u32(self.filter_mode as u32)?;
+        writer.write_u32(self.filter_mode.id())?;
         writer.write_u32(self.mode as u32)?;
         writer.write_f32(self.cutoff.get::<hertz>())?;
         writer.write_f32(self.q)?;
         writer.write_f32(self.drive)?;
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // nonlinear_filter_unknown1
         writer.write_u32(0)?; // nonlinear_filter_unknown2
 
-        if snapin.effect_version > 1000 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1000 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -190,7 +191,7 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::frequency::hertz;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -3,35 +3,44 @@
 //!
 //! | Phase Plant Version | Effect Version |
 //! |---------------------|----------------|
-//! | 1.8.5 to 1.8.1.6    | 1023           |
+//! | 1.8.5 to 1.8.16     | 1023           |
 //! | 2.0.16              | 1034           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
 use uom::num::Zero;
-use uom::si::f32::{Frequency, Ratio};
+use uom::si::angle::revolution;
+use uom::si::f32::{Angle, Frequency, Ratio};
 use uom::si::frequency::hertz;
-use uom::si::ratio::percent;
+use uom::si::ratio::{percent, ratio};
 
-use crate::effect::{EffectVersion, SidechainMode};
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::effect::SidechainMode;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct PhaseDistortion {
+    /// Amount of phase modulation, from zero to one.
     pub drive: f32,
+
+    /// How much the level of the modulating signal is evened out, from zero
+    /// to one.
     pub normalize: f32,
 
+    /// Cutoff of the filter on the signal that modulates the phase.
     pub tone: Frequency,
 
-    /// Percentage of 360 degrees
+    /// Fraction of a full turn, so 50% is 180 degrees. See
+    /// [`bias_angle`](Self::bias_angle).
     pub bias: Ratio,
 
-    /// Percentage of 360 degrees
+    /// Fraction of a full turn, see [`spread_angle`](Self::spread_angle).
     pub spread: Ratio,
 
     pub mix: Ratio,
@@ -39,30 +48,27 @@ pub struct PhaseDistortion {
 }
 
 impl PhaseDistortion {
-    pub fn default_version() -> EffectVersion {
-        1034
+    /// Offset added to the phase before it is distorted.
+    pub fn bias_angle(&self) -> Angle {
+        Angle::new::<revolution>(self.bias.get::<ratio>())
     }
-}
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_phase_distortion(&self) -> Option<&PhaseDistortion> {
-        self.downcast_ref::<PhaseDistortion>()
+    pub fn set_bias_angle(&mut self, angle: Angle) {
+        self.bias = Ratio::new::<ratio>(angle.get::<revolution>());
     }
-}
 
-impl Effect for PhaseDistortion {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
+    /// Difference in phase offset between the left and right channels.
+    pub fn spread_angle(&self) -> Angle {
+        Angle::new::<revolution>(self.spread.get::<ratio>())
     }
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::PhaseDistortion
+    pub fn set_spread_angle(&mut self, angle: Angle) {
+        self.spread = Ratio::new::<ratio>(angle.get::<revolution>());
     }
 }
 
+impl_effect!(PhaseDistortion, as_phase_distortion, default_version = 1034);
+
 impl EffectRead for PhaseDistortion {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -78,22 +84,22 @@ impl EffectRead for PhaseDistortion {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let drive = reader.read_f32()?;
-        let spread = Ratio::new::<percent>(reader.read_f32()?);
+        let spread = reader.read_ratio()?;
         let mix = reader.read_ratio()?;
         let normalize = reader.read_f32()?;
         let tone = reader.read_hertz()?;
-        let bias = Ratio::new::<percent>(reader.read_f32()?);
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        let bias = reader.read_ratio()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "phase_distortion_unknown1")?;
         reader.expect_u32(0, "phase_distortion_unknown2")?;
-        let group_id = if effect_version >= 1034 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1034 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         let sidechain_id = reader.read_u32()?;
         let sidechain_mode_str = reader.read_string_and_length()?;
@@ -115,9 +121,7 @@ impl EffectRead for PhaseDistortion {
                 mix,
                 sidechain_mode,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -126,22 +130,23 @@ impl EffectWrite for PhaseDistortion {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
         writer.write_f32(self.drive)?;
-        writer.write_f32(self.spread.get::<percent>())?;
+        writer.write_ratio(self.spread)?;
         writer.write_ratio(self.mix)?;
         writer.write_f32(self.normalize)?;
         writer.write_f32(self.tone.get::<hertz>())?;
-        writer.write_f32(self.bias.get::<percent>())?;
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_ratio(self.bias)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // phase_distortion_unknown1
         writer.write_u32(0)?; // phase_distortion_unknown2
 
-        if snapin.effect_version >= 1034 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version >= 1034 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         writer.write_u32(self.sidechain_mode as u32)?;
@@ -163,15 +168,40 @@ impl Default for PhaseDistortion {
     }
 }
 
+impl Interpolate for PhaseDistortion {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            drive: self.drive.interpolate(&other.drive, t),
+            normalize: self.normalize.interpolate(&other.normalize, t),
+            tone: self.tone.interpolate(&other.tone, t),
+            bias: self.bias.interpolate(&other.bias, t),
+            spread: self.spread.interpolate(&other.spread, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            sidechain_mode: step(&self.sidechain_mode, &other.sidechain_mode, t),
+        }
+    }
+}
+
+impl_parameters!(PhaseDistortion {
+    drive: Fraction,
+    normalize: Fraction,
+    tone,
+    bias,
+    spread,
+    mix,
+    sidechain_mode,
+});
+
 #[cfg(test)]
 mod test {
     use approx::assert_relative_eq;
     use uom::num::Zero;
+    use uom::si::angle::degree;
     use uom::si::f32::Ratio;
     use uom::si::frequency::hertz;
     use uom::si::ratio::percent;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -232,7 +262,11 @@ mod test {
         .unwrap();
         let snapin = &preset.lanes[0].snapins[0];
         let effect = snapin.effect.as_phase_distortion().unwrap();
-        assert_relative_eq!(effect.spread.get::<percent>(), 25.0 / 360.0);
+        assert_relative_eq!(
+            effect.spread.get::<percent>(),
+            100.0 * 25.0 / 360.0,
+            epsilon = 0.0001
+        );
         assert_relative_eq!(effect.mix.get::<percent>(), 50.0);
 
         let preset = read_effect_preset(
@@ -245,7 +279,31 @@ mod test {
         assert!(snapin.minimized);
         let effect = snapin.effect.as_phase_distortion().unwrap();
         assert_relative_eq!(effect.tone.get::<hertz>(), 25.0);
-        assert_relative_eq!(effect.bias.get::<percent>(), 10.0 / 360.0);
+        assert_relative_eq!(
+            effect.bias.get::<percent>(),
+            100.0 * 10.0 / 360.0,
+            epsilon = 0.0001
+        );
+        assert_relative_eq!(effect.bias_angle().get::<degree>(), 10.0, epsilon = 0.0001);
+    }
+
+    #[test]
+    fn angles() {
+        let mut effect = PhaseDistortion::default();
+        assert_eq!(effect.bias_angle(), Angle::zero());
+        effect.set_bias_angle(Angle::new::<degree>(90.0));
+        effect.set_spread_angle(Angle::new::<degree>(25.0));
+        assert_relative_eq!(effect.bias.get::<percent>(), 25.0);
+        assert_relative_eq!(
+            effect.spread.get::<percent>(),
+            100.0 * 25.0 / 360.0,
+            epsilon = 0.0001
+        );
+        assert_relative_eq!(
+            effect.spread_angle().get::<degree>(),
+            25.0,
+            epsilon = 0.0001
+        );
     }
 
     #[test]
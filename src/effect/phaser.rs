@@ -6,19 +6,20 @@
 //! | 2.0.0               | 1046           |
 //! | 2.0.16              | 1048           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::si::f32::{Frequency, Ratio};
 use uom::si::frequency::hertz;
 use uom::si::ratio::percent;
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Phaser {
@@ -30,12 +31,6 @@ pub struct Phaser {
     pub mix: Ratio,
 }
 
-impl Phaser {
-    pub fn default_version() -> EffectVersion {
-        1048
-    }
-}
-
 impl Default for Phaser {
     fn default() -> Self {
         Self {
@@ -49,24 +44,29 @@ impl Default for Phaser {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_phaser(&self) -> Option<&Phaser> {
-        self.downcast_ref::<Phaser>()
+impl Interpolate for Phaser {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            cutoff: self.cutoff.interpolate(&other.cutoff, t),
+            rate: self.rate.interpolate(&other.rate, t),
+            depth: self.depth.interpolate(&other.depth, t),
+            order: step(&self.order, &other.order, t),
+            spread: self.spread.interpolate(&other.spread, t),
+            mix: self.mix.interpolate(&other.mix, t),
+        }
     }
 }
 
-impl Effect for Phaser {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Phaser {
+    cutoff,
+    rate,
+    depth,
+    order,
+    spread,
+    mix,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Phaser
-    }
-}
+impl_effect!(Phaser, as_phaser, default_version = 1048);
 
 impl EffectRead for Phaser {
     fn read<R: Read + Seek>(
@@ -83,23 +83,24 @@ impl EffectRead for Phaser {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let order = reader.read_u32()?;
         let cutoff = reader.read_hertz()?;
         let depth = reader.read_ratio()?;
         let rate = reader.read_hertz()?;
         let spread = reader.read_ratio()?;
         let mix = reader.read_ratio()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "phaser_unknown_1")?;
         reader.expect_u32(0, "phaser_unknown_2")?;
 
-        let group_id = if effect_version >= 1046 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1046 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Phaser {
@@ -110,9 +111,7 @@ impl EffectRead for Phaser {
                 spread,
                 mix,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -121,22 +120,23 @@ impl EffectWrite for Phaser {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_u32(self.order)?;
         writer.write_hertz(self.cutoff)?;
         writer.write_ratio(self.depth)?;
         writer.write_hertz(self.rate)?;
         writer.write_ratio(self.spread)?;
         writer.write_ratio(self.mix)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?;
         writer.write_u32(0)?;
 
-        if snapin.effect_version >= 1048 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version >= 1046 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -147,7 +147,7 @@ impl EffectWrite for Phaser {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
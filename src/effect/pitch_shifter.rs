@@ -7,24 +7,26 @@
 //! | 1.8.5 to 1.8.13     | 1039           |
 //! | 2.0.16              | 1050           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
+use std::ops::RangeInclusive;
 
-use crate::effect::EffectVersion;
-use strum_macros::FromRepr;
+use strum_macros::{EnumIter, FromRepr};
 use uom::num::Zero;
-use uom::si::f32::{Frequency, Ratio, Time};
+use uom::si::f32::{Ratio, Time};
 use uom::si::ratio::percent;
-use uom::si::time::millisecond;
+use uom::si::time::{millisecond, second};
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::{Semitones, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
-#[derive(Clone, Copy, Debug, FromRepr, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum CompensationMode {
     // The discriminants correspond to the file format.
@@ -55,9 +57,11 @@ impl Display for CompensationMode {
     }
 }
 
+impl_parameters!(CompensationMode);
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct PitchShifter {
-    pub pitch: Frequency,
+    pub pitch: Semitones,
     pub jitter: Ratio,
     pub grain_size: Time,
     pub mix: Ratio,
@@ -66,15 +70,28 @@ pub struct PitchShifter {
 }
 
 impl PitchShifter {
-    pub fn default_version() -> EffectVersion {
-        1050
+    /// Pitches the Phase Plant interface allows.
+    pub const PITCH_RANGE: RangeInclusive<Semitones> = Semitones::new(-24.0)..=Semitones::new(24.0);
+
+    /// Set the pitch, checking that it's in the range Phase Plant allows.
+    pub fn set_pitch(&mut self, pitch: Semitones) -> Result<(), Error> {
+        if !Self::PITCH_RANGE.contains(&pitch) {
+            let msg = format!(
+                "Pitch {pitch} is outside of {} to {}",
+                Self::PITCH_RANGE.start(),
+                Self::PITCH_RANGE.end()
+            );
+            return Err(Error::new(ErrorKind::InvalidInput, msg));
+        }
+        self.pitch = pitch;
+        Ok(())
     }
 }
 
 impl Default for PitchShifter {
     fn default() -> Self {
         Self {
-            pitch: Frequency::zero(),
+            pitch: Semitones::ZERO,
             jitter: Ratio::zero(),
             grain_size: Time::new::<millisecond>(80.0),
             mix: Ratio::new::<percent>(100.0),
@@ -84,25 +101,47 @@ impl Default for PitchShifter {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_pitch_shifter(&self) -> Option<&PitchShifter> {
-        self.downcast_ref::<PitchShifter>()
+impl Interpolate for PitchShifter {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            pitch: self.pitch.interpolate(&other.pitch, t),
+            jitter: self.jitter.interpolate(&other.jitter, t),
+            grain_size: self.grain_size.interpolate(&other.grain_size, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            correlate: step(&self.correlate, &other.correlate, t),
+            compensation_mode: step(&self.compensation_mode, &other.compensation_mode, t),
+        }
     }
 }
 
-impl Effect for PitchShifter {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(PitchShifter {
+    pitch,
+    jitter,
+    grain_size,
+    mix,
+    correlate,
+    compensation_mode,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::PitchShifter
+impl PitchShifter {
+    /// The settings when the effect is added in Phase Plant, with the grain
+    /// size stored as exactly 80 ms.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            grain_size: Time::new::<second>(0.08),
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(
+    PitchShifter,
+    as_pitch_shifter,
+    init_preset = custom,
+    default_version = 1050
+);
+
 impl EffectRead for PitchShifter {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -118,24 +157,25 @@ impl EffectRead for PitchShifter {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
-        let pitch = reader.read_hertz()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
+        let pitch = Semitones::new(reader.read_f32()?);
         let jitter = reader.read_ratio()?;
         let grain_size = reader.read_seconds()?;
         let mix = reader.read_ratio()?;
         let correlate = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "pitch_shifter_unknown_1")?;
         reader.expect_u32(0, "pitch_shifter_unknown_2")?;
 
         let compensation_mode = CompensationMode::from_id(reader.read_u32()?)?;
 
-        let group_id = if effect_version > 1039 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1039 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(PitchShifter {
@@ -146,9 +186,7 @@ impl EffectRead for PitchShifter {
                 correlate,
                 compensation_mode,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -157,23 +195,24 @@ impl EffectWrite for PitchShifter {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_hertz(self.pitch)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_f32(self.pitch.semitones())?;
         writer.write_ratio(self.jitter)?;
         writer.write_seconds(self.grain_size)?;
         writer.write_ratio(self.mix)?;
         writer.write_bool32(self.correlate)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // pitch_shifter_unknown_1
         writer.write_u32(0)?; // pitch_shifter_unknown_2
 
         writer.write_u32(self.compensation_mode as u32)?;
 
-        if snapin.effect_version > 1039 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1039 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -184,10 +223,9 @@ impl EffectWrite for PitchShifter {
 mod test {
     use approx::assert_relative_eq;
     use uom::si::f32::Time;
-    use uom::si::frequency::hertz;
     use uom::si::time::millisecond;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -195,7 +233,7 @@ mod test {
     #[test]
     fn default() {
         let effect = PitchShifter::default();
-        assert_eq!(effect.pitch.get::<hertz>(), 0.0);
+        assert_eq!(effect.pitch, Semitones::ZERO);
         assert_eq!(effect.jitter.get::<percent>(), 0.0);
         assert_eq!(effect.grain_size, Time::new::<millisecond>(80.0));
         assert_eq!(effect.mix.get::<percent>(), 100.0);
@@ -222,7 +260,7 @@ mod test {
             assert!(snapin.enabled);
             assert!(!snapin.minimized);
             let effect = snapin.effect.as_pitch_shifter().unwrap();
-            assert_eq!(effect.pitch.get::<hertz>(), 0.0);
+            assert_eq!(effect.pitch, Semitones::ZERO);
             assert_eq!(effect.jitter.get::<percent>(), 0.0);
             assert_relative_eq!(effect.grain_size.get::<millisecond>(), 80.0);
             assert_eq!(effect.mix.get::<percent>(), 100.0);
@@ -274,6 +312,18 @@ mod test {
         assert!(!snapin.enabled);
         assert!(!snapin.minimized);
         let effect = snapin.effect.as_pitch_shifter().unwrap();
-        assert_eq!(effect.pitch.get::<hertz>(), 5.0);
+        assert_eq!(effect.pitch.semitones(), 5.0);
+    }
+
+    #[test]
+    fn set_pitch() {
+        let mut effect = PitchShifter::default();
+        effect.set_pitch(Semitones::new(-12.0)).unwrap();
+        assert_eq!(effect.pitch, Semitones::new(-12.0));
+        assert_eq!(
+            effect.set_pitch(Semitones::new(25.0)).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(effect.pitch, Semitones::new(-12.0));
     }
 }
@@ -6,19 +6,20 @@
 //! | 1.8.5 to 1.8.13     | 1038           |
 //! | 2.0.16              | 1049           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::si::f32::{Ratio, Time};
 use uom::si::ratio::percent;
 use uom::si::time::{millisecond, second};
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Resonator {
@@ -33,12 +34,6 @@ pub struct Resonator {
     pub mix: Ratio,
 }
 
-impl Resonator {
-    pub fn default_version() -> EffectVersion {
-        1049
-    }
-}
-
 impl Default for Resonator {
     fn default() -> Self {
         Self {
@@ -51,25 +46,46 @@ impl Default for Resonator {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_resonator(&self) -> Option<&Resonator> {
-        self.downcast_ref::<Resonator>()
+impl Interpolate for Resonator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            note: self.note.interpolate(&other.note, t),
+            sawtooth: step(&self.sawtooth, &other.sawtooth, t),
+            decay: self.decay.interpolate(&other.decay, t),
+            intensity: self.intensity.interpolate(&other.intensity, t),
+            mix: self.mix.interpolate(&other.mix, t),
+        }
     }
 }
 
-impl Effect for Resonator {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Resonator {
+    note,
+    sawtooth,
+    decay,
+    intensity,
+    mix,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Resonator
+impl Resonator {
+    /// The settings when the effect is added in Phase Plant, which stores a
+    /// slightly longer decay and higher intensity than it displays.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            decay: Time::new::<second>(0.010002589),
+            intensity: 0.5012474,
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(
+    Resonator,
+    as_resonator,
+    init_preset = custom,
+    default_version = 1049
+);
+
 impl EffectRead for Resonator {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -85,22 +101,23 @@ impl EffectRead for Resonator {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let note = reader.read_f32()?;
         let decay = reader.read_seconds()?;
         let intensity = reader.read_f32()?;
         let sawtooth = !reader.read_bool32()?;
         let mix = reader.read_ratio()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "resonator_unknown_1")?;
         reader.expect_u32(0, "resonator_unknown_2")?;
 
-        let group_id = if effect_version > 1038 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1038 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Resonator {
@@ -110,9 +127,7 @@ impl EffectRead for Resonator {
                 intensity,
                 mix,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -121,21 +136,22 @@ impl EffectWrite for Resonator {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_f32(self.note)?;
         writer.write_f32(self.decay.get::<second>())?;
         writer.write_f32(self.intensity)?;
         writer.write_bool32(!self.sawtooth)?;
         writer.write_ratio(self.mix)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // resonator_unknown_1
         writer.write_u32(0)?; // resonator_unknown_2
 
-        if snapin.effect_version > 1038 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1038 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -147,7 +163,7 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::time::second;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -4,21 +4,26 @@
 //! | Phase Plant Version | Effect Version |
 //! |---------------------|----------------|
 //! | 1.8.5 to 1.8.17     | 1032           |
-//! | 2.0.16              | 1049           |
+//! | 2.0.16              | 1043           |
 
-use std::any::{type_name, Any};
+// Unlike Delay and Dual Delay, Reverb has no freeze, duck or sync controls.
+// The two values after the minimized state are zero in every preset seen so
+// far.
+
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
 use uom::si::f32::{Ratio, Time};
-use uom::si::ratio::percent;
+use uom::si::ratio::{percent, ratio};
 use uom::si::time::second;
 
 use crate::effect::EffectVersion;
-use crate::{Decibels, Snapin};
+use crate::interpolate::Interpolate;
+use crate::path::impl_parameters;
+use crate::{Decibels, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Reverb {
@@ -33,12 +38,6 @@ pub struct Reverb {
     pub mix: Ratio,
 }
 
-impl Reverb {
-    pub fn default_version() -> EffectVersion {
-        1049
-    }
-}
-
 impl Default for Reverb {
     fn default() -> Self {
         Self {
@@ -52,25 +51,49 @@ impl Default for Reverb {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_reverb(&self) -> Option<&Reverb> {
-        self.downcast_ref::<Reverb>()
+impl Interpolate for Reverb {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            decay: self.decay.interpolate(&other.decay, t),
+            dampen: self.dampen.interpolate(&other.dampen, t),
+            size: self.size.interpolate(&other.size, t),
+            width: self.width.interpolate(&other.width, t),
+            early: self.early.interpolate(&other.early, t),
+            mix: self.mix.interpolate(&other.mix, t),
+        }
     }
 }
 
-impl Effect for Reverb {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Reverb {
+    decay,
+    dampen,
+    size,
+    width,
+    early,
+    mix,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Reverb
+impl Reverb {
+    /// The settings when the effect is added in Phase Plant. The dampen,
+    /// early reflections and mix are near but not exactly the displayed values.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            dampen: Decibels::new(25.033337),
+            early: Ratio::new::<ratio>(0.24833316),
+            mix: Ratio::new::<ratio>(0.24999982),
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(
+    Reverb,
+    as_reverb,
+    init_preset = custom,
+    default_version = 1049
+);
+
 impl EffectRead for Reverb {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -86,23 +109,23 @@ impl EffectRead for Reverb {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let size = reader.read_ratio()?;
         let decay = reader.read_seconds()?;
         let dampen = reader.read_decibels_db()?;
         let width = reader.read_ratio()?;
         let mix = reader.read_ratio()?;
         let early = reader.read_ratio()?;
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "reverb_unknown_1")?;
         reader.expect_u32(0, "reverb_unknown_2")?;
 
-        let group_id = if effect_version > 1032 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1032 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Reverb {
@@ -113,9 +136,7 @@ impl EffectRead for Reverb {
                 early,
                 mix,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -124,7 +145,8 @@ impl EffectWrite for Reverb {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
         writer.write_ratio(self.size)?;
         writer.write_seconds(self.decay)?;
@@ -132,14 +154,14 @@ impl EffectWrite for Reverb {
         writer.write_ratio(self.width)?;
         writer.write_ratio(self.mix)?;
         writer.write_ratio(self.early)?;
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // reverb_unknown1
         writer.write_u32(0)?; // reverb_unknown2
 
-        if snapin.effect_version > 1032 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1032 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -151,7 +173,7 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::time::second;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
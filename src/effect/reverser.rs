@@ -5,19 +5,20 @@
 //! | 1.8.5 to 1.8.13     | 1033           |
 //! | 2.0.16              | 1044           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::si::f32::{Ratio, Time};
-use uom::si::ratio::percent;
+use uom::si::ratio::{percent, ratio};
 use uom::si::time::{millisecond, second};
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Reverser {
@@ -29,12 +30,6 @@ pub struct Reverser {
     unknown3: u32,
 }
 
-impl Reverser {
-    pub fn default_version() -> EffectVersion {
-        1049
-    }
-}
-
 impl Default for Reverser {
     fn default() -> Self {
         Self {
@@ -48,25 +43,46 @@ impl Default for Reverser {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_reverser(&self) -> Option<&Reverser> {
-        self.downcast_ref::<Reverser>()
+impl Interpolate for Reverser {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            time: self.time.interpolate(&other.time, t),
+            sync: step(&self.sync, &other.sync, t),
+            crossfade: self.crossfade.interpolate(&other.crossfade, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            unknown2: step(&self.unknown2, &other.unknown2, t),
+            unknown3: step(&self.unknown3, &other.unknown3, t),
+        }
     }
 }
 
-impl Effect for Reverser {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Reverser {
+    time,
+    sync,
+    crossfade,
+    mix,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Reverser
+impl Reverser {
+    /// The settings when the effect is added in Phase Plant, where the
+    /// crossfade is stored as exactly 10%.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            crossfade: Ratio::new::<ratio>(0.1),
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(
+    Reverser,
+    as_reverser,
+    unknown = [unknown2, unknown3],
+    init_preset = custom,
+    default_version = 1049
+);
+
 impl EffectRead for Reverser {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -82,6 +98,8 @@ impl EffectRead for Reverser {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let time = reader.read_seconds()?;
 
         let unknown2 = reader.read_u32()?;
@@ -90,17 +108,15 @@ impl EffectRead for Reverser {
         let sync = reader.read_bool32()?;
         let mix = reader.read_ratio()?;
         let crossfade = reader.read_ratio()?;
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "reverser_unknown_1")?;
         reader.expect_u32(0, "reverser_unknown_2")?;
 
-        let group_id = if effect_version > 1038 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1038 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Reverser {
@@ -111,9 +127,7 @@ impl EffectRead for Reverser {
                 unknown2,
                 unknown3,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -122,7 +136,8 @@ impl EffectWrite for Reverser {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
         writer.write_f32(self.time.get::<second>())?;
 
@@ -132,14 +147,14 @@ impl EffectWrite for Reverser {
         writer.write_bool32(self.sync)?;
         writer.write_ratio(self.mix)?;
         writer.write_ratio(self.crossfade)?;
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?;
         writer.write_u32(0)?;
 
-        if snapin.effect_version > 1038 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1038 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -151,7 +166,7 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::time::{millisecond, second};
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -6,12 +6,11 @@
 //! | 1.8.5 to 1.8.13     | 1032           |
 //! | 2.0.16              | 1043           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 use uom::num::Zero;
@@ -19,10 +18,12 @@ use uom::si::f32::{Frequency, Ratio};
 use uom::si::frequency::hertz;
 use uom::si::ratio::percent;
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 /// The file format stores the names rather than a discriminant.
 #[derive(Copy, Clone, Debug, EnumIter, Eq, PartialEq)]
@@ -65,6 +66,8 @@ impl Display for ModulationMode {
     }
 }
 
+impl_parameters!(ModulationMode);
+
 #[derive(Clone, Debug)]
 pub struct RingMod {
     pub bias: Ratio,
@@ -73,13 +76,9 @@ pub struct RingMod {
     pub spread: Ratio,
     pub mix: Ratio,
     pub modulation_mode: ModulationMode,
-    unknown3: u32,
-}
 
-impl RingMod {
-    pub fn default_version() -> EffectVersion {
-        1043
-    }
+    /// Changes with the modulation mode, such as -4 for the sine oscillator.
+    unknown3: u32,
 }
 
 impl Eq for RingMod {}
@@ -104,29 +103,40 @@ impl Default for RingMod {
             spread: Ratio::zero(),
             mix: Ratio::new::<percent>(100.0),
             modulation_mode: ModulationMode::SineOscillator,
-            unknown3: 0,
+            unknown3: 0xffff_fffc,
         }
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_ring_mod(&self) -> Option<&RingMod> {
-        self.downcast_ref::<RingMod>()
+impl Interpolate for RingMod {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            bias: self.bias.interpolate(&other.bias, t),
+            rectify: self.rectify.interpolate(&other.rectify, t),
+            frequency: self.frequency.interpolate(&other.frequency, t),
+            spread: self.spread.interpolate(&other.spread, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            modulation_mode: step(&self.modulation_mode, &other.modulation_mode, t),
+            unknown3: step(&self.unknown3, &other.unknown3, t),
+        }
     }
 }
 
-impl Effect for RingMod {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
-
-    fn mode(&self) -> EffectMode {
-        EffectMode::RingMod
-    }
-}
+impl_parameters!(RingMod {
+    bias,
+    rectify,
+    frequency,
+    spread,
+    mix,
+    modulation_mode,
+});
+
+impl_effect!(
+    RingMod,
+    as_ring_mod,
+    unknown = [unknown3],
+    default_version = 1043
+);
 
 impl EffectRead for RingMod {
     fn read<R: Read + Seek>(
@@ -143,24 +153,24 @@ impl EffectRead for RingMod {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let frequency = reader.read_hertz()?;
         let spread = reader.read_ratio()?;
         let mix = reader.read_ratio()?;
         let bias = reader.read_ratio()?;
         let rectify = reader.read_ratio()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "ring_mod_unknown_4")?;
         reader.expect_u32(0, "ring_mod_unknown_5")?;
+        if effect_version > 1032 {
+            state.group_id = reader.read_snapin_position()?;
+        }
         let unknown3 = reader.read_u32()?;
 
-        let group_id = if effect_version > 1032 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
-
         let mode_str = reader.read_string_and_length()?;
         let modulation_mode = ModulationMode::from_str(&mode_str.unwrap_or_default())?;
 
@@ -174,9 +184,7 @@ impl EffectRead for RingMod {
                 modulation_mode,
                 unknown3,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -185,23 +193,23 @@ impl EffectWrite for RingMod {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_hertz(self.frequency)?;
         writer.write_ratio(self.spread)?;
         writer.write_ratio(self.mix)?;
         writer.write_ratio(self.bias)?;
         writer.write_ratio(self.rectify)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?;
         writer.write_u32(0)?;
-        writer.write_u32(self.unknown3)?;
-
-        if snapin.effect_version > 1032 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1032 {
+            writer.write_snapin_id(state.group_id)?;
         }
+        writer.write_u32(self.unknown3)?;
 
         writer.write_string_and_length(self.modulation_mode.to_string())
     }
@@ -211,7 +219,7 @@ impl EffectWrite for RingMod {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -271,6 +279,7 @@ mod test {
             let snapin = &preset.lanes[0].snapins[0];
             assert!(snapin.enabled);
             assert!(!snapin.minimized);
+            assert_eq!(snapin.group_id, None);
             let effect = snapin.effect.as_ring_mod().unwrap();
             assert_eq!(effect, &RingMod::default())
         }
@@ -9,25 +9,27 @@
 
 // Phase Plant 1.8.14 added saving the zoom and pan settings of the view.
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
 use log::trace;
-use strum_macros::{Display, FromRepr};
+use strum_macros::{Display, EnumIter, FromRepr};
 use uom::si::f32::{Frequency, Ratio};
 use uom::si::frequency::hertz;
 use uom::si::ratio::percent;
 
-use crate::effect::{EffectVersion, FalloffSpeed, FrequencyResolution, SpectrumView, StereoMode};
+use crate::effect::EffectVersion;
+use crate::effect::{FalloffSpeed, FrequencyResolution, SpectrumView, StereoMode};
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 use crate::version::Version;
-use crate::{Decibels, PhasePlantRelease, Snapin};
+use crate::{Decibels, PhasePlantRelease, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
-#[derive(Copy, Clone, Debug, FromRepr, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum SliceEqFilterMode {
     // The discriminants correspond to the file format.
@@ -68,7 +70,9 @@ impl Display for SliceEqFilterMode {
     }
 }
 
-#[derive(Copy, Clone, Debug, Display, FromRepr, Eq, PartialEq)]
+impl_parameters!(SliceEqFilterMode);
+
+#[derive(Copy, Clone, Debug, Display, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum ChannelMode {
     // The discriminants correspond to the file format.
@@ -77,6 +81,8 @@ pub enum ChannelMode {
     Side = 2,
 }
 
+impl_parameters!(ChannelMode);
+
 impl ChannelMode {
     pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
         Self::from_repr(id)
@@ -84,7 +90,7 @@ impl ChannelMode {
     }
 }
 
-#[derive(Clone, Copy, Debug, FromRepr, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum OversampleMode {
     // The discriminants correspond to the file format.
@@ -115,6 +121,8 @@ impl Display for OversampleMode {
     }
 }
 
+impl_parameters!(OversampleMode);
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct SliceEqFilter {
     pub id: u32,
@@ -163,6 +171,33 @@ impl Default for SliceEqFilter {
     }
 }
 
+impl Interpolate for SliceEqFilter {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            id: step(&self.id, &other.id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            channel_mode: step(&self.channel_mode, &other.channel_mode, t),
+            filter_mode: step(&self.filter_mode, &other.filter_mode, t),
+            cutoff_frequency: self
+                .cutoff_frequency
+                .interpolate(&other.cutoff_frequency, t),
+            gain: self.gain.interpolate(&other.gain, t),
+            q: self.q.interpolate(&other.q, t),
+            order: step(&self.order, &other.order, t),
+        }
+    }
+}
+
+impl_parameters!(SliceEqFilter {
+    enabled,
+    channel_mode,
+    filter_mode,
+    cutoff_frequency,
+    gain,
+    q,
+    order,
+});
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct SliceEq {
     /// May contain up to [`SliceEq::FILTER_COUNT_MAX`] filters.
@@ -181,10 +216,6 @@ pub struct SliceEq {
 
 impl SliceEq {
     pub const FILTER_COUNT_MAX: usize = 32;
-
-    pub fn default_version() -> EffectVersion {
-        1032
-    }
 }
 
 impl Default for SliceEq {
@@ -202,24 +233,35 @@ impl Default for SliceEq {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_slice_eq(&self) -> Option<&SliceEq> {
-        self.downcast_ref::<SliceEq>()
+impl Interpolate for SliceEq {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            filters: self.filters.interpolate(&other.filters, t),
+            offset_semitones: self
+                .offset_semitones
+                .interpolate(&other.offset_semitones, t),
+            gain: self.gain.interpolate(&other.gain, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            oversample_mode: step(&self.oversample_mode, &other.oversample_mode, t),
+            edit_mode: step(&self.edit_mode, &other.edit_mode, t),
+            stereo_mode: step(&self.stereo_mode, &other.stereo_mode, t),
+            spectrum_view: self.spectrum_view.interpolate(&other.spectrum_view, t),
+        }
     }
 }
 
-impl Effect for SliceEq {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(SliceEq {
+    filters,
+    offset_semitones: Number(Semitones),
+    gain,
+    mix,
+    oversample_mode,
+    edit_mode,
+    stereo_mode,
+    spectrum_view,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::SliceEq
-    }
-}
+impl_effect!(SliceEq, as_slice_eq, default_version = 1032);
 
 impl EffectRead for SliceEq {
     fn read<R: Read + Seek>(
@@ -236,6 +278,8 @@ impl EffectRead for SliceEq {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         // 1210 for Phase Plant 1.7.9, 1218 for Phase Plant 1.8.0
         let _header_length = reader.read_u32()?; // FIXME: GUESS
         let _version_a_major = reader.read_u32()?;
@@ -263,8 +307,8 @@ impl EffectRead for SliceEq {
         reader.skip(1)?; // 0 or 1
 
         let oversample_mode = OversampleMode::from_id(reader.read_u32()?)?;
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         let mut edit_mode = ChannelMode::Both;
         let mut stereo_mode = StereoMode::MidSide;
@@ -359,11 +403,9 @@ impl EffectRead for SliceEq {
             reader.expect_u32(0, "slice_eq_23")?;
         }
 
-        let group_id = if effect_version >= 1030 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1030 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         let effect = Box::new(SliceEq {
             filters,
@@ -377,9 +419,7 @@ impl EffectRead for SliceEq {
         });
         Ok(EffectReadReturn {
             effect,
-            enabled,
-            minimized,
-            group_id,
+            state,
             metadata: Default::default(),
             preset_name,
             preset_path,
@@ -392,10 +432,15 @@ impl EffectWrite for SliceEq {
     fn write<W: Write + Seek>(
         &self,
         _writer: &mut PhasePlantWriter<W>,
-        _snapin: &Snapin,
+        _state: &SnapinState,
+        _effect_version: EffectVersion,
     ) -> io::Result<()> {
-        // TODO: Write Slice EQ
-        Ok(())
+        // The filters are not written yet so the file would not be
+        // readable.
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "Writing Slice EQ is not supported",
+        ))
     }
 }
 
@@ -403,7 +448,7 @@ impl EffectWrite for SliceEq {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -8,7 +8,7 @@
 //! | 2.0.12              | 1050           |
 //! | 2.0.16 to 2.1.0     | 1051           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
@@ -17,10 +17,11 @@ use uom::si::ratio::percent;
 
 use crate::effect::multipass::ExternalInputMode;
 use crate::effect::EffectVersion;
-use crate::{Decibels, MacroControl, Snapin};
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::{Decibels, MacroControl, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct SnapHeap {
@@ -30,12 +31,6 @@ pub struct SnapHeap {
     pub macro_controls: [MacroControl; MacroControl::COUNT],
 }
 
-impl SnapHeap {
-    pub fn default_version() -> EffectVersion {
-        1051
-    }
-}
-
 impl Default for SnapHeap {
     fn default() -> Self {
         Self {
@@ -47,24 +42,25 @@ impl Default for SnapHeap {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_snap_heap(&self) -> Option<&SnapHeap> {
-        self.downcast_ref::<SnapHeap>()
+impl Interpolate for SnapHeap {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            gain: self.gain.interpolate(&other.gain, t),
+            mix: self.mix.interpolate(&other.mix, t),
+            external_input_mode: step(&self.external_input_mode, &other.external_input_mode, t),
+            macro_controls: self.macro_controls.interpolate(&other.macro_controls, t),
+        }
     }
 }
 
-impl Effect for SnapHeap {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(SnapHeap {
+    gain,
+    mix,
+    external_input_mode,
+    macro_controls,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::SnapHeap
-    }
-}
+impl_effect!(SnapHeap, as_snap_heap, default_version = 1051);
 
 impl EffectRead for SnapHeap {
     fn read<R: Read + Seek>(
@@ -82,9 +78,7 @@ impl EffectRead for SnapHeap {
         }
 
         let effect = SnapHeap::default();
-        let enabled = true;
-        let minimized = false;
-        let group_id = None;
+        let state = SnapinState::default();
 
         reader.skip(1849)?;
 
@@ -96,12 +90,7 @@ impl EffectRead for SnapHeap {
             reader.skip(128)?;
         }
 
-        Ok(EffectReadReturn::new(
-            Box::new(effect),
-            enabled,
-            minimized,
-            group_id,
-        ))
+        Ok(EffectReadReturn::new(Box::new(effect), state))
     }
 }
 
@@ -109,9 +98,13 @@ impl EffectWrite for SnapHeap {
     fn write<W: Write + Seek>(
         &self,
         _writer: &mut PhasePlantWriter<W>,
-        _snapin: &Snapin,
+        _state: &SnapinState,
+        _effect_version: EffectVersion,
     ) -> io::Result<()> {
-        todo!()
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "Writing Snap Heap is not supported",
+        ))
     }
 }
 
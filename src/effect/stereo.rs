@@ -3,23 +3,24 @@
 //!
 //! | Phase Plant Version | Effect Version |
 //! |---------------------|----------------|
-//! | 1.8.5 to 1.8.13     | 1038           |
+//! | 1.7.7 to 1.8.13     | 1038           |
 //! | 2.0.0               | 1047           |
 //! | 2.0.16              | 1049           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::num::Zero;
 use uom::si::f32::Ratio;
 use uom::si::ratio::{percent, ratio};
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::Interpolate;
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Stereo {
@@ -28,12 +29,6 @@ pub struct Stereo {
     pub pan: Ratio,
 }
 
-impl Stereo {
-    pub fn default_version() -> EffectVersion {
-        1049
-    }
-}
-
 impl Default for Stereo {
     fn default() -> Self {
         Self {
@@ -44,24 +39,19 @@ impl Default for Stereo {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_stereo(&self) -> Option<&Stereo> {
-        self.downcast_ref::<Stereo>()
+impl Interpolate for Stereo {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            mid: self.mid.interpolate(&other.mid, t),
+            width: self.width.interpolate(&other.width, t),
+            pan: self.pan.interpolate(&other.pan, t),
+        }
     }
 }
 
-impl Effect for Stereo {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(Stereo { mid, width, pan });
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::Stereo
-    }
-}
+impl_effect!(Stereo, as_stereo, default_version = 1049);
 
 impl EffectRead for Stereo {
     fn read<R: Read + Seek>(
@@ -78,26 +68,25 @@ impl EffectRead for Stereo {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let width = reader.read_ratio()?;
         let pan = reader.read_ratio()?;
         let mid = reader.read_ratio()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "stereo_unknown_1")?;
         reader.expect_u32(0, "stereo_unknown_2")?;
 
-        let group_id = if effect_version >= 1047 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1047 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(Stereo { mid, width, pan }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -106,20 +95,20 @@ impl EffectWrite for Stereo {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_f32(self.width.get::<ratio>())?;
         writer.write_f32(self.pan.get::<ratio>())?;
         writer.write_f32(self.mid.get::<ratio>())?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
-        writer.write_u32(0)?;
-        writer.write_u32(0)?;
-        writer.write_u32(0)?;
+        writer.write_u32(0)?; // stereo_unknown_1
+        writer.write_u32(0)?; // stereo_unknown_2
 
-        if snapin.effect_version >= 1047 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version >= 1047 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -130,7 +119,7 @@ impl EffectWrite for Stereo {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -6,18 +6,18 @@
 //! | 1.8.5 to 1.8.14     | 1034           |
 //! | 2.0.16              | 1045           |
 
-use std::any::Any;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
 use uom::si::f32::Time;
 use uom::si::time::second;
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct TapeStop {
@@ -27,12 +27,6 @@ pub struct TapeStop {
     pub curve: f32,
 }
 
-impl TapeStop {
-    pub fn default_version() -> EffectVersion {
-        1045
-    }
-}
-
 impl Default for TapeStop {
     fn default() -> Self {
         Self {
@@ -44,24 +38,25 @@ impl Default for TapeStop {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_tape_stop(&self) -> Option<&TapeStop> {
-        self.downcast_ref::<TapeStop>()
+impl Interpolate for TapeStop {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            running: step(&self.running, &other.running, t),
+            stop_time: self.stop_time.interpolate(&other.stop_time, t),
+            start_time: self.start_time.interpolate(&other.start_time, t),
+            curve: self.curve.interpolate(&other.curve, t),
+        }
     }
 }
 
-impl Effect for TapeStop {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(TapeStop {
+    running,
+    stop_time,
+    start_time,
+    curve,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::TapeStop
-    }
-}
+impl_effect!(TapeStop, as_tape_stop, default_version = 1045);
 
 impl EffectRead for TapeStop {
     fn read<R: Read + Seek>(
@@ -75,21 +70,21 @@ impl EffectRead for TapeStop {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let running = reader.read_bool32()?;
         let start_time = reader.read_seconds()?;
         let stop_time = reader.read_seconds()?;
-        let enabled = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
         let curve = reader.read_f32()?;
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "tape_stop_unknown2")?;
         reader.expect_u32(0, "tape_stop_unknown3")?;
 
-        let group_id = if effect_version > 1038 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1038 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(TapeStop {
@@ -98,9 +93,7 @@ impl EffectRead for TapeStop {
                 start_time,
                 curve,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -109,20 +102,21 @@ impl EffectWrite for TapeStop {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
         writer.write_bool32(self.running)?;
         writer.write_seconds(self.start_time)?;
         writer.write_seconds(self.stop_time)?;
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_f32(self.curve)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?;
         writer.write_u32(0)?;
 
-        if snapin.effect_version > 1038 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1038 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -134,7 +128,7 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::time::second;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
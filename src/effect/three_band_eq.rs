@@ -8,7 +8,7 @@
 //! | 2.0.12              | 1025           |
 //! | 2.0.16              | 1026           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
@@ -16,10 +16,11 @@ use uom::si::f32::Frequency;
 use uom::si::frequency::hertz;
 
 use crate::effect::EffectVersion;
-use crate::{Decibels, Snapin};
+use crate::interpolate::Interpolate;
+use crate::path::impl_parameters;
+use crate::{Decibels, SnapinState};
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ThreeBandEq {
@@ -34,12 +35,6 @@ pub struct ThreeBandEq {
     pub high_gain: Decibels,
 }
 
-impl ThreeBandEq {
-    pub fn default_version() -> EffectVersion {
-        1026
-    }
-}
-
 impl Default for ThreeBandEq {
     fn default() -> Self {
         Self {
@@ -52,24 +47,27 @@ impl Default for ThreeBandEq {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_three_band_eq(&self) -> Option<&ThreeBandEq> {
-        self.downcast_ref::<ThreeBandEq>()
+impl Interpolate for ThreeBandEq {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            low_freq: self.low_freq.interpolate(&other.low_freq, t),
+            high_freq: self.high_freq.interpolate(&other.high_freq, t),
+            low_gain: self.low_gain.interpolate(&other.low_gain, t),
+            mid_gain: self.mid_gain.interpolate(&other.mid_gain, t),
+            high_gain: self.high_gain.interpolate(&other.high_gain, t),
+        }
     }
 }
 
-impl Effect for ThreeBandEq {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(ThreeBandEq {
+    low_freq,
+    high_freq,
+    low_gain,
+    mid_gain,
+    high_gain,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::ThreeBandEq
-    }
-}
+impl_effect!(ThreeBandEq, as_three_band_eq, default_version = 1026);
 
 impl EffectRead for ThreeBandEq {
     fn read<R: Read + Seek>(
@@ -86,22 +84,22 @@ impl EffectRead for ThreeBandEq {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let low_gain = reader.read_decibels_db()?;
         let mid_gain = reader.read_decibels_db()?;
         let high_gain = reader.read_decibels_db()?;
         let low_freq = reader.read_hertz()?;
         let high_freq = reader.read_hertz()?;
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "three_band_eq_unknown_1")?;
         reader.expect_u32(0, "three_band_eq_unknown_2")?;
 
-        let group_id = if effect_version >= 1024 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1024 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(ThreeBandEq {
@@ -111,9 +109,7 @@ impl EffectRead for ThreeBandEq {
                 mid_gain,
                 high_gain,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -122,21 +118,22 @@ impl EffectWrite for ThreeBandEq {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
         writer.write_f32(self.low_gain.db())?;
         writer.write_f32(self.mid_gain.db())?;
         writer.write_f32(self.high_gain.db())?;
         writer.write_f32(self.low_freq.get::<hertz>())?;
         writer.write_f32(self.high_freq.get::<hertz>())?;
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // three_band_eq_unknown_1
         writer.write_u32(0)?; // three_band_eq_unknown_2
 
-        if snapin.effect_version >= 1025 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version >= 1024 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -149,7 +146,7 @@ mod test {
     use uom::si::f32::Frequency;
     use uom::si::frequency::hertz;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
     use crate::Decibels;
 
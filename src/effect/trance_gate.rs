@@ -6,23 +6,24 @@
 //! | 1.8.14              | 1038           |
 //! | 2.0.16              | 1049           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
-use crate::effect::EffectVersion;
-use strum_macros::FromRepr;
+use strum_macros::{EnumIter, FromRepr};
 use uom::si::f32::{Ratio, Time};
-use uom::si::ratio::percent;
-use uom::si::time::millisecond;
+use uom::si::ratio::{percent, ratio};
+use uom::si::time::{millisecond, second};
 
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::interpolate::{step, Interpolate};
+use crate::path::{impl_parameters, join, Field, FieldMut, Parameters};
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
-#[derive(Clone, Copy, Debug, FromRepr, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum PatternResolution {
     // The discriminants correspond to the file format.
@@ -67,17 +68,124 @@ impl Display for PatternResolution {
     }
 }
 
+impl_parameters!(PatternResolution);
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct GateStep {
+    pub enabled: bool,
+
+    /// Continue the gate into the next step without releasing it.
+    pub tied: bool,
+}
+
+impl_parameters!(GateStep { enabled, tied });
+
+/// The steps of one of the patterns of a [`TranceGate`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GatePattern {
+    step_count: usize,
+
+    /// Every step stored for the pattern, including those past the end of
+    /// it. Phase Plant keeps them so shortening a pattern and lengthening it
+    /// again brings them back.
+    pub steps: [GateStep; TranceGate::STEPS_MAX],
+}
+
+impl GatePattern {
+    /// A pattern with every step disabled.
+    pub fn new(step_count: usize) -> Result<Self, Error> {
+        check_step_count(step_count)?;
+        Ok(Self {
+            step_count,
+            steps: [GateStep::default(); TranceGate::STEPS_MAX],
+        })
+    }
+
+    /// How many steps are played, between one and
+    /// [`STEPS_MAX`](TranceGate::STEPS_MAX).
+    pub fn step_count(&self) -> usize {
+        self.step_count
+    }
+
+    /// Lengthen or shorten the pattern. The steps past the end are kept.
+    pub fn set_step_count(&mut self, step_count: usize) -> Result<(), Error> {
+        check_step_count(step_count)?;
+        self.step_count = step_count;
+        Ok(())
+    }
+
+    /// The steps that are played.
+    pub fn played_steps(&self) -> &[GateStep] {
+        &self.steps[..self.step_count]
+    }
+
+    /// One of the played steps.
+    pub fn step_mut(&mut self, index: usize) -> Result<&mut GateStep, Error> {
+        let step_count = self.step_count;
+        self.steps[..step_count].get_mut(index).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Step {index} is past the end of the pattern of {step_count} steps"),
+            )
+        })
+    }
+
+    fn from_defaults(pattern_index: usize) -> Self {
+        Self {
+            step_count: TranceGate::STEP_COUNT_DEFAULT[pattern_index],
+            steps: std::array::from_fn(|step_index| GateStep {
+                enabled: TranceGate::STEP_ENABLED_DEFAULT[pattern_index][step_index],
+                tied: TranceGate::STEP_TIED_DEFAULT[pattern_index][step_index],
+            }),
+        }
+    }
+}
+
+impl Interpolate for GatePattern {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        step(self, other, t)
+    }
+}
+
+/// The steps past the end of the pattern are included, so they are compared
+/// along with the rest.
+impl Parameters for GatePattern {
+    fn parameters<'a>(&'a self, path: &str, parameters: &mut Vec<(String, Field<'a>)>) {
+        parameters.push((
+            join(path, "step_count"),
+            Field::Size(&self.step_count, 1..=TranceGate::STEPS_MAX),
+        ));
+        self.steps.parameters(&join(path, "steps"), parameters);
+    }
+
+    fn parameters_mut<'a>(&'a mut self, path: &str, parameters: &mut Vec<(String, FieldMut<'a>)>) {
+        parameters.push((
+            join(path, "step_count"),
+            FieldMut::Size(&mut self.step_count, 1..=TranceGate::STEPS_MAX),
+        ));
+        self.steps.parameters_mut(&join(path, "steps"), parameters);
+    }
+}
+
+fn check_step_count(step_count: usize) -> Result<(), Error> {
+    if !(1..=TranceGate::STEPS_MAX).contains(&step_count) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "A Trance Gate pattern has 1 to {} steps, not {step_count}",
+                TranceGate::STEPS_MAX
+            ),
+        ));
+    }
+    Ok(())
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct TranceGate {
+    /// The selected pattern, starting at one.
     pub pattern_number: u32,
 
-    /// How many steps are in the pattern. There are the full amount of
-    /// [STEPS_MAX](Self::STEPS_MAX) steps for every pattern but only the first
-    /// step count of steps are used.
-    pub step_count: [usize; Self::PATTERN_COUNT],
-
-    pub step_enabled: [[bool; Self::STEPS_MAX]; Self::PATTERN_COUNT],
-    pub step_tied: [[bool; Self::STEPS_MAX]; Self::PATTERN_COUNT],
+    pub patterns: [GatePattern; Self::PATTERN_COUNT],
     pub attack: Time,
     pub decay: Time,
     pub sustain: Ratio,
@@ -92,6 +200,17 @@ impl TranceGate {
     /// Maximum number of steps in a pattern.
     pub const STEPS_MAX: usize = 64;
 
+    /// The selected pattern.
+    pub fn pattern(&self) -> Option<&GatePattern> {
+        let index = (self.pattern_number as usize).checked_sub(1)?;
+        self.patterns.get(index)
+    }
+
+    pub fn pattern_mut(&mut self) -> Option<&mut GatePattern> {
+        let index = (self.pattern_number as usize).checked_sub(1)?;
+        self.patterns.get_mut(index)
+    }
+
     pub(crate) const STEP_COUNT_DEFAULT: [usize; Self::PATTERN_COUNT] =
         [16, 16, 32, 16, 16, 16, 16, 64];
     pub(crate) const STEP_ENABLED_DEFAULT: [[bool; Self::STEPS_MAX]; Self::PATTERN_COUNT] = [
@@ -225,19 +344,13 @@ impl TranceGate {
             false, false, false,
         ],
     ];
-
-    pub fn default_version() -> EffectVersion {
-        1049
-    }
 }
 
 impl Default for TranceGate {
     fn default() -> Self {
         Self {
             pattern_number: 1,
-            step_count: TranceGate::STEP_COUNT_DEFAULT,
-            step_enabled: TranceGate::STEP_ENABLED_DEFAULT,
-            step_tied: TranceGate::STEP_TIED_DEFAULT,
+            patterns: std::array::from_fn(GatePattern::from_defaults),
             attack: Time::new::<millisecond>(13.2),
             decay: Time::new::<millisecond>(55.6),
             sustain: Ratio::new::<percent>(50.0),
@@ -248,25 +361,54 @@ impl Default for TranceGate {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_trance_gate(&self) -> Option<&TranceGate> {
-        self.downcast_ref::<TranceGate>()
+impl Interpolate for TranceGate {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            pattern_number: step(&self.pattern_number, &other.pattern_number, t),
+            patterns: self.patterns.interpolate(&other.patterns, t),
+            attack: self.attack.interpolate(&other.attack, t),
+            decay: self.decay.interpolate(&other.decay, t),
+            sustain: self.sustain.interpolate(&other.sustain, t),
+            release: self.release.interpolate(&other.release, t),
+            resolution: step(&self.resolution, &other.resolution, t),
+            mix: self.mix.interpolate(&other.mix, t),
+        }
     }
 }
 
-impl Effect for TranceGate {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(TranceGate {
+    pattern_number,
+    patterns,
+    attack,
+    decay,
+    sustain,
+    release,
+    resolution,
+    mix,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::TranceGate
+impl TranceGate {
+    /// The settings when the effect is added in Phase Plant. The envelope
+    /// times are stored with more precision than the UI shows.
+    #[must_use]
+    pub fn init_preset() -> Self {
+        Self {
+            attack: Time::new::<second>(0.013234291),
+            decay: Time::new::<second>(0.055628452),
+            sustain: Ratio::new::<ratio>(0.49999934),
+            release: Time::new::<second>(0.017638082),
+            ..Default::default()
+        }
     }
 }
 
+impl_effect!(
+    TranceGate,
+    as_trance_gate,
+    init_preset = custom,
+    default_version = 1049
+);
+
 impl EffectRead for TranceGate {
     fn read<R: Read + Seek>(
         reader: &mut PhasePlantReader<R>,
@@ -282,7 +424,10 @@ impl EffectRead for TranceGate {
             ));
         }
 
-        let enabled = reader.read_bool32()?;
+        let mut state = SnapinState {
+            enabled: reader.read_bool32()?,
+            ..Default::default()
+        };
         let attack = reader.read_seconds()?;
         let decay = reader.read_seconds()?;
         let sustain = reader.read_ratio()?;
@@ -291,34 +436,31 @@ impl EffectRead for TranceGate {
         let resolution = PatternResolution::from_id(reader.read_u32()?)?;
         let pattern_number = reader.read_u32()?;
 
-        let mut step_count = [0_usize; TranceGate::PATTERN_COUNT];
-        let mut step_enabled = [[false; TranceGate::STEPS_MAX]; TranceGate::PATTERN_COUNT];
-        let mut step_tied = [[false; TranceGate::STEPS_MAX]; TranceGate::PATTERN_COUNT];
-        for pattern_index in 0..TranceGate::PATTERN_COUNT {
-            step_count[pattern_index] = reader.read_u32()? as usize;
-            for sequence_index in 0..TranceGate::STEPS_MAX {
-                step_enabled[pattern_index][sequence_index] = reader.read_bool32()?;
-                step_tied[pattern_index][sequence_index] = reader.read_bool32()?;
+        let mut patterns: [GatePattern; TranceGate::PATTERN_COUNT] =
+            std::array::from_fn(|_| GatePattern::from_defaults(0));
+        for pattern in &mut patterns {
+            pattern.step_count = reader.read_u32()? as usize;
+            check_step_count(pattern.step_count)
+                .map_err(|error| Error::new(ErrorKind::InvalidData, error.to_string()))?;
+            for gate_step in &mut pattern.steps {
+                gate_step.enabled = reader.read_bool32()?;
+                gate_step.tied = reader.read_bool32()?;
             }
         }
 
-        let minimized = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "trance_gate_unknown_1")?;
         reader.expect_u32(0, "trance_gate_unknown_2")?;
 
-        let group_id = if effect_version > 1038 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version > 1038 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         Ok(EffectReadReturn::new(
             Box::new(TranceGate {
                 pattern_number,
-                step_count,
-                step_enabled,
-                step_tied,
+                patterns,
                 attack,
                 decay,
                 sustain,
@@ -326,9 +468,7 @@ impl EffectRead for TranceGate {
                 resolution,
                 mix,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -337,9 +477,10 @@ impl EffectWrite for TranceGate {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
-        writer.write_bool32(snapin.enabled)?;
+        writer.write_bool32(state.enabled)?;
         writer.write_seconds(self.attack)?;
         writer.write_seconds(self.decay)?;
         writer.write_ratio(self.sustain)?;
@@ -347,21 +488,21 @@ impl EffectWrite for TranceGate {
         writer.write_ratio(self.mix)?;
         writer.write_u32(self.resolution as u32)?;
         writer.write_u32(self.pattern_number)?;
-        for pattern_index in 0..TranceGate::PATTERN_COUNT {
-            writer.write_u32(self.step_count[pattern_index] as u32)?;
-            for sequence_index in 0..TranceGate::STEPS_MAX {
-                writer.write_bool32(self.step_enabled[pattern_index][sequence_index])?;
-                writer.write_bool32(self.step_tied[pattern_index][sequence_index])?;
+        for pattern in &self.patterns {
+            writer.write_u32(pattern.step_count as u32)?;
+            for gate_step in &pattern.steps {
+                writer.write_bool32(gate_step.enabled)?;
+                writer.write_bool32(gate_step.tied)?;
             }
         }
 
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // trace_gate_unknown_1
         writer.write_u32(0)?; // trace_gate_unknown_2
 
-        if snapin.effect_version > 1038 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version > 1038 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         Ok(())
@@ -373,8 +514,9 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::time::millisecond;
 
-    use crate::effect::Filter;
-    use crate::test::read_effect_preset;
+    use crate::effect::{Effect, Filter};
+    use crate::test::{read_effect_preset, rewrite_effect};
+    use crate::Snapin;
 
     use super::*;
 
@@ -382,9 +524,24 @@ mod test {
     fn default() {
         let effect = TranceGate::default();
         assert_eq!(effect.pattern_number, 1);
-        assert_eq!(effect.step_count, TranceGate::STEP_COUNT_DEFAULT);
-        assert_eq!(effect.step_enabled, TranceGate::STEP_ENABLED_DEFAULT);
-        assert_eq!(effect.step_tied, TranceGate::STEP_TIED_DEFAULT);
+        for (index, pattern) in effect.patterns.iter().enumerate() {
+            assert_eq!(pattern.step_count(), TranceGate::STEP_COUNT_DEFAULT[index]);
+        }
+        assert_eq!(
+            effect.pattern().unwrap().steps[..4],
+            [
+                GateStep {
+                    enabled: true,
+                    tied: false
+                },
+                GateStep::default(),
+                GateStep {
+                    enabled: true,
+                    tied: false
+                },
+                GateStep::default(),
+            ]
+        );
         assert_eq!(effect.attack.get::<millisecond>(), 13.2);
         assert_eq!(effect.decay.get::<millisecond>(), 55.6);
         assert_eq!(effect.sustain.get::<percent>(), 50.0);
@@ -422,9 +579,7 @@ mod test {
             assert!(!snapin.minimized);
             let effect = snapin.effect.as_trance_gate().unwrap();
             assert_eq!(effect.pattern_number, 1);
-            assert_eq!(effect.step_count, TranceGate::STEP_COUNT_DEFAULT);
-            assert_eq!(effect.step_enabled, TranceGate::STEP_ENABLED_DEFAULT);
-            assert_eq!(effect.step_tied, TranceGate::STEP_TIED_DEFAULT);
+            assert_eq!(effect.patterns, TranceGate::default().patterns);
             assert_relative_eq!(effect.attack.get::<millisecond>(), 13.2, epsilon = 0.1);
             assert_relative_eq!(effect.decay.get::<millisecond>(), 55.6, epsilon = 0.1);
             assert_relative_eq!(effect.sustain.get::<percent>(), 50.0, epsilon = 0.001);
@@ -452,7 +607,13 @@ mod test {
         .unwrap();
         let snapin = &preset.lanes[0].snapins[0];
         let effect = snapin.effect.as_trance_gate().unwrap();
-        assert_eq!(effect.step_count[0], 11);
+        // The steps past the end of the shortened pattern are kept.
+        assert_eq!(effect.patterns[0].step_count(), 11);
+        assert_eq!(effect.patterns[0].played_steps().len(), 11);
+        assert_eq!(
+            effect.patterns[0].steps,
+            TranceGate::default().patterns[0].steps
+        );
         assert_relative_eq!(effect.sustain.get::<percent>(), 80.0);
         assert_relative_eq!(effect.release.get::<millisecond>(), 25.0, epsilon = 0.001);
 
@@ -502,15 +663,12 @@ mod test {
         let snapin = &preset.lanes[0].snapins[0];
         let effect = snapin.effect.as_trance_gate().unwrap();
         assert_eq!(effect.resolution, PatternResolution::Sixteenth);
-        assert!(effect
-            .step_count
-            .into_iter()
-            .all(|c| c == TranceGate::STEPS_MAX));
-        for pattern in effect.step_enabled {
-            assert!(pattern.into_iter().all(|enabled| !enabled));
-        }
-        for pattern in effect.step_tied {
-            assert!(pattern.into_iter().all(|tied| !tied));
+        for pattern in &effect.patterns {
+            assert_eq!(pattern.step_count(), TranceGate::STEPS_MAX);
+            assert!(pattern
+                .steps
+                .iter()
+                .all(|step| *step == GateStep::default()));
         }
 
         // The first pattern has only one tied step. The second pattern has
@@ -523,16 +681,49 @@ mod test {
         let snapin = &preset.lanes[0].snapins[0];
         let effect = snapin.effect.as_trance_gate().unwrap();
         assert_eq!(effect.resolution, PatternResolution::SixteenthTriplet);
-        assert!(effect
-            .step_count
-            .into_iter()
-            .all(|c| c == TranceGate::STEPS_MAX));
-        for pattern in effect.step_enabled {
-            assert!(pattern.into_iter().all(|enabled| enabled));
+        for pattern in &effect.patterns {
+            assert_eq!(pattern.step_count(), TranceGate::STEPS_MAX);
+            assert!(pattern.steps.iter().all(|step| step.enabled));
         }
-        assert!(effect.step_tied[0][..effect.step_tied[0].len() - 1]
-            .iter()
-            .all(|tied| *tied));
-        assert!(effect.step_tied[1].into_iter().all(|tied| !tied));
+        let steps = &effect.patterns[0].steps;
+        assert!(steps[..steps.len() - 1].iter().all(|step| step.tied));
+        assert!(effect.patterns[1].steps.iter().all(|step| !step.tied));
+    }
+
+    #[test]
+    fn edit_patterns() {
+        let mut effect = TranceGate {
+            pattern_number: 3,
+            ..Default::default()
+        };
+        let pattern = effect.pattern_mut().unwrap();
+        assert_eq!(pattern.step_count(), 32);
+        pattern.set_step_count(40).unwrap();
+        assert_eq!(pattern.played_steps()[39], GateStep::default());
+        pattern.step_mut(39).unwrap().enabled = true;
+        assert_eq!(
+            pattern.step_mut(40).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        assert!(pattern.set_step_count(0).is_err());
+        assert!(pattern.set_step_count(TranceGate::STEPS_MAX + 1).is_err());
+        assert_eq!(pattern.step_count(), 40);
+
+        // Shortening the pattern keeps the steps past the end.
+        pattern.set_step_count(8).unwrap();
+        assert_eq!(pattern.played_steps().len(), 8);
+        assert!(pattern.steps[39].enabled);
+        assert_eq!(GatePattern::new(5).unwrap().step_count(), 5);
+        assert!(GatePattern::new(0).is_err());
+
+        effect.pattern_number = 3;
+        let snapin = Snapin::new(Box::new(effect.clone()), 1, true, false);
+        let rewritten = rewrite_effect(&snapin).unwrap();
+        assert_eq!(rewritten.effect.as_trance_gate(), Some(&effect));
+
+        effect.pattern_number = 0;
+        assert!(effect.pattern().is_none());
+        effect.pattern_number = 9;
+        assert!(effect.pattern().is_none());
     }
 }
@@ -4,9 +4,10 @@
 //! | Phase Plant Version | Effect Version |
 //! |---------------------|----------------|
 //! | 1.8.5 to 1.8.13     | 1027           |
-//! | 2.0.16              | 1037           |
+//! | 2.0.12              | 1037           |
+//! | 2.0.16              | 1038           |
 
-use std::any::{type_name, Any};
+use std::any::type_name;
 use std::io;
 use std::io::{Error, ErrorKind, Read, Seek, Write};
 
@@ -14,11 +15,13 @@ use uom::num::Zero;
 use uom::si::f32::Ratio;
 use uom::si::ratio::percent;
 
-use crate::effect::{EffectVersion, SidechainMode};
-use crate::Snapin;
+use crate::effect::EffectVersion;
+use crate::effect::SidechainMode;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::SnapinState;
 
 use super::super::io::*;
-use super::{Effect, EffectMode};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct TransientShaper {
@@ -30,12 +33,6 @@ pub struct TransientShaper {
     pub sidechain_mode: SidechainMode,
 }
 
-impl TransientShaper {
-    pub fn default_version() -> EffectVersion {
-        1037
-    }
-}
-
 impl Default for TransientShaper {
     fn default() -> Self {
         Self {
@@ -49,24 +46,29 @@ impl Default for TransientShaper {
     }
 }
 
-impl dyn Effect {
-    #[must_use]
-    pub fn as_transient_shaper(&self) -> Option<&TransientShaper> {
-        self.downcast_ref::<TransientShaper>()
+impl Interpolate for TransientShaper {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            attack: self.attack.interpolate(&other.attack, t),
+            pump: self.pump.interpolate(&other.pump, t),
+            sustain: self.sustain.interpolate(&other.sustain, t),
+            speed: self.speed.interpolate(&other.speed, t),
+            clip: step(&self.clip, &other.clip, t),
+            sidechain_mode: step(&self.sidechain_mode, &other.sidechain_mode, t),
+        }
     }
 }
 
-impl Effect for TransientShaper {
-    fn box_eq(&self, other: &dyn Any) -> bool {
-        other
-            .downcast_ref::<Self>()
-            .map_or(false, |other| self == other)
-    }
+impl_parameters!(TransientShaper {
+    attack,
+    pump,
+    sustain,
+    speed,
+    clip,
+    sidechain_mode,
+});
 
-    fn mode(&self) -> EffectMode {
-        EffectMode::TransientShaper
-    }
-}
+impl_effect!(TransientShaper, as_transient_shaper, default_version = 1037);
 
 impl EffectRead for TransientShaper {
     fn read<R: Read + Seek>(
@@ -83,22 +85,22 @@ impl EffectRead for TransientShaper {
             ));
         }
 
+        let mut state = SnapinState::default();
+
         let attack = reader.read_ratio()?;
         let pump = reader.read_ratio()?;
         let sustain = reader.read_ratio()?;
         let speed = reader.read_ratio()?;
         let clip = reader.read_bool32()?;
-        let enabled = reader.read_bool32()?;
-        let minimized = reader.read_bool32()?;
+        state.enabled = reader.read_bool32()?;
+        state.minimized = reader.read_bool32()?;
 
         reader.expect_u32(0, "transient_shaper_unknown_1")?;
         reader.expect_u32(0, "transient_shaper_unknown_2")?;
 
-        let group_id = if effect_version >= 1034 {
-            reader.read_snapin_position()?
-        } else {
-            None
-        };
+        if effect_version >= 1034 {
+            state.group_id = reader.read_snapin_position()?;
+        }
 
         let sidechain_id = reader.read_u32()?;
         let sidechain_mode_str = reader.read_string_and_length()?;
@@ -119,9 +121,7 @@ impl EffectRead for TransientShaper {
                 clip,
                 sidechain_mode,
             }),
-            enabled,
-            minimized,
-            group_id,
+            state,
         ))
     }
 }
@@ -130,21 +130,22 @@ impl EffectWrite for TransientShaper {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
         writer.write_ratio(self.attack)?;
         writer.write_ratio(self.pump)?;
         writer.write_ratio(self.sustain)?;
         writer.write_ratio(self.speed)?;
         writer.write_bool32(self.clip)?;
-        writer.write_bool32(snapin.enabled)?;
-        writer.write_bool32(snapin.minimized)?;
+        writer.write_bool32(state.enabled)?;
+        writer.write_bool32(state.minimized)?;
 
         writer.write_u32(0)?; // transient_shaper_unknown_1
         writer.write_u32(0)?; // transient_shaper_unknown_2
 
-        if snapin.effect_version >= 1034 {
-            writer.write_snapin_id(snapin.group_id)?;
+        if effect_version >= 1034 {
+            writer.write_snapin_id(state.group_id)?;
         }
 
         writer.write_u32(self.sidechain_mode as u32)?;
@@ -156,7 +157,7 @@ impl EffectWrite for TransientShaper {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::Filter;
+    use crate::effect::{Effect, Filter};
     use crate::test::read_effect_preset;
 
     use super::*;
@@ -1,3 +1,4 @@
+use crate::path::impl_parameters;
 use uom::num::Zero;
 use uom::si::f32::{Ratio, Time};
 use uom::si::time::second;
@@ -28,6 +29,18 @@ pub struct Envelope {
     pub release_falloff: f32,
 }
 
+impl_parameters!(Envelope {
+    delay,
+    attack,
+    attack_curve,
+    hold,
+    decay,
+    decay_falloff,
+    sustain,
+    release,
+    release_falloff,
+});
+
 impl Default for Envelope {
     fn default() -> Self {
         Self {
@@ -0,0 +1,187 @@
+//! Generate small presets for testing instead of saving them from Phase Plant.
+//!
+//! The presets in the `tests` folder were saved by Phase Plant, each with a
+//! single change from the init preset. Contributors without them can create
+//! a similar corpus with [`effect_fixtures`] and [`write_fixtures`]. The
+//! generated presets are written by this library so they check that presets
+//! read back the same, not that the format was understood correctly.
+//!
+//! ```no_run
+//! use synthahol_phase_plant::effect::{EffectMode, Gain};
+//! use synthahol_phase_plant::fixture::{effect_fixtures, write_fixtures, Fixture};
+//!
+//! let mut fixtures = effect_fixtures();
+//! fixtures.push(Fixture::effect(EffectMode::Gain).changed("125%", |preset| {
+//!     let effect = &mut preset.lanes[0].snapins[0].effect;
+//!     effect.downcast_mut::<Gain>().unwrap().amount = 1.25;
+//! }));
+//! write_fixtures("generated", &fixtures).unwrap();
+//! ```
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Cursor, Write};
+use std::path::{Path, PathBuf};
+
+use strum::IntoEnumIterator;
+
+use crate::effect::EffectMode;
+use crate::io::WRITE_SAME_AS;
+use crate::{Preset, Snapin};
+
+/// A preset and where it belongs in a folder of test presets.
+#[derive(Clone, Debug)]
+pub struct Fixture {
+    /// Folder relative to the root of the test presets, such as
+    /// `effects/ring_mod`.
+    pub folder: PathBuf,
+
+    /// Start of the file name, such as `ring_mod-disabled`. The version of
+    /// Phase Plant is added after it.
+    pub name: String,
+
+    pub preset: Preset,
+}
+
+impl Fixture {
+    pub fn new<P: Into<PathBuf>>(folder: P, name: &str, preset: Preset) -> Self {
+        Self {
+            folder: folder.into(),
+            name: name.to_owned(),
+            preset,
+        }
+    }
+
+    /// The init preset with the effect added to the first lane the way Phase
+    /// Plant adds it.
+    pub fn effect(mode: EffectMode) -> Self {
+        let name = snake_case(&format!("{mode:?}"));
+        let mut preset = Preset::init_for(WRITE_SAME_AS);
+        preset.lanes[0]
+            .snapins
+            .push(Snapin::new(mode.init_effect(), 1, true, false));
+        Self::new(Path::new("effects").join(&name), &name, preset)
+    }
+
+    /// A copy of the fixture with one change, named after the change.
+    #[must_use]
+    pub fn changed<F: FnOnce(&mut Preset)>(&self, change: &str, update: F) -> Self {
+        let mut fixture = Self {
+            name: format!("{}-{change}", self.name),
+            ..self.clone()
+        };
+        update(&mut fixture.preset);
+        fixture
+    }
+
+    /// Path relative to the root of the test presets, following the naming
+    /// of the presets in the `tests` folder.
+    pub fn path(&self) -> PathBuf {
+        self.folder.join(format!(
+            "{}-{}.phaseplant",
+            self.name,
+            WRITE_SAME_AS.version()
+        ))
+    }
+
+    /// Write the preset below `root`, creating the folders it needs. Fails
+    /// instead of replacing a preset that is already there.
+    pub fn write<P: AsRef<Path>>(&self, root: P) -> io::Result<PathBuf> {
+        let path = root.as_ref().join(self.path());
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut cursor = Cursor::new(Vec::new());
+        self.preset.write(&mut cursor)?;
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?
+            .write_all(cursor.get_ref())?;
+        Ok(path)
+    }
+}
+
+/// Every effect as Phase Plant adds it, disabled and minimized. Effects
+/// that can't be written and read back yet are left out so the corpus grows
+/// as writing is completed.
+pub fn effect_fixtures() -> Vec<Fixture> {
+    EffectMode::iter()
+        .map(Fixture::effect)
+        .filter(|init| reads_back(&init.preset))
+        .flat_map(|init| {
+            let disabled = init.changed("disabled", |preset| {
+                preset.lanes[0].snapins[0].enabled = false;
+            });
+            let minimized = init.changed("minimized", |preset| {
+                preset.lanes[0].snapins[0].minimized = true;
+            });
+            [init, disabled, minimized]
+        })
+        .collect()
+}
+
+/// Write each fixture below `root` and return the paths that were written.
+pub fn write_fixtures<P: AsRef<Path>>(root: P, fixtures: &[Fixture]) -> io::Result<Vec<PathBuf>> {
+    fixtures
+        .iter()
+        .map(|fixture| fixture.write(root.as_ref()))
+        .collect()
+}
+
+/// If the lanes are the same after the preset is written and read.
+fn reads_back(preset: &Preset) -> bool {
+    let mut cursor = Cursor::new(Vec::new());
+    if preset.write(&mut cursor).is_err() {
+        return false;
+    }
+    cursor.set_position(0);
+    Preset::read(&mut cursor, None).is_ok_and(|read| read.lanes == preset.lanes)
+}
+
+fn snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (index, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && index > 0 {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tests::test_data_path;
+
+    use super::*;
+
+    #[test]
+    fn effects() {
+        let fixtures = effect_fixtures();
+        assert!(fixtures.len() > 30 * 3);
+        for unsupported in ["carve_eq", "convolver", "slice_eq"] {
+            assert!(!fixtures
+                .iter()
+                .any(|fixture| fixture.name.starts_with(unsupported)));
+        }
+        assert_eq!(
+            Fixture::effect(EffectMode::RingMod).path(),
+            Path::new("effects/ring_mod/ring_mod-2.1.0.phaseplant")
+        );
+
+        // Same folders as the presets saved by Phase Plant.
+        for fixture in &fixtures {
+            assert!(test_data_path(&[]).join(&fixture.folder).is_dir());
+        }
+
+        let root = std::env::temp_dir().join(format!("fixtures-{}", std::process::id()));
+        let paths = write_fixtures(&root, &fixtures).unwrap();
+        for (fixture, path) in fixtures.iter().zip(&paths) {
+            let preset = Preset::read_file(path).unwrap();
+            assert_eq!(preset.lanes[0].snapins, fixture.preset.lanes[0].snapins);
+        }
+        let error = fixtures[0].write(&root).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -3,14 +3,17 @@
 
 use std::any::Any;
 
-use strum_macros::Display;
+use strum_macros::{Display, EnumIter};
 use uom::si::f32::Frequency;
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
 // TODO: Needs preset name and path
 
-#[derive(Copy, Clone, Debug, Display, Eq, FromRepr, PartialEq)]
+/// Every waveform available in Phase Plant 2.1.
+#[derive(Copy, Clone, Debug, Display, EnumIter, Eq, FromRepr, PartialEq)]
 #[repr(u32)]
 pub enum AnalogWaveform {
     // The discriminants correspond to the file format.
@@ -20,6 +23,8 @@ pub enum AnalogWaveform {
     Triangle = 2,
 }
 
+impl_parameters!(AnalogWaveform);
+
 impl AnalogWaveform {
     pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
         Self::from_repr(id).ok_or_else(|| {
@@ -29,6 +34,32 @@ impl AnalogWaveform {
             )
         })
     }
+
+    /// Name displayed by Phase Plant.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnalogWaveform::Saw => "Saw",
+            AnalogWaveform::Square => "Square",
+            AnalogWaveform::Sine => "Sine",
+            AnalogWaveform::Triangle => "Triangle",
+        }
+    }
+
+    /// The harmonics in the waveform.
+    pub fn harmonics(&self) -> &'static str {
+        match self {
+            AnalogWaveform::Saw => "All harmonics, falling 6 dB per octave",
+            AnalogWaveform::Square => "Odd harmonics, falling 6 dB per octave",
+            AnalogWaveform::Sine => "Only the fundamental",
+            AnalogWaveform::Triangle => "Odd harmonics, falling 12 dB per octave",
+        }
+    }
+
+    /// If the [pulse width](AnalogOscillator::pulse_width) changes the
+    /// waveform. Moving the pulse width away from 50% adds even harmonics.
+    pub fn uses_pulse_width(&self) -> bool {
+        *self == AnalogWaveform::Square
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -63,6 +94,41 @@ impl Default for AnalogOscillator {
     }
 }
 
+impl Interpolate for AnalogOscillator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            id: step(&self.id, &other.id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            name: step(&self.name, &other.name, t),
+            tuning: self.tuning.interpolate(&other.tuning, t),
+            harmonic: self.harmonic.interpolate(&other.harmonic, t),
+            shift: self.shift.interpolate(&other.shift, t),
+            phase_offset: self.phase_offset.interpolate(&other.phase_offset, t),
+            phase_jitter: self.phase_jitter.interpolate(&other.phase_jitter, t),
+            level: self.level.interpolate(&other.level, t),
+            sync_multiplier: self.sync_multiplier.interpolate(&other.sync_multiplier, t),
+            pulse_width: self.pulse_width.interpolate(&other.pulse_width, t),
+            unison: self.unison.interpolate(&other.unison, t),
+            waveform: step(&self.waveform, &other.waveform, t),
+        }
+    }
+}
+
+impl_parameters!(AnalogOscillator {
+    enabled,
+    name,
+    tuning: Number(Semitones),
+    harmonic,
+    shift,
+    phase_offset,
+    phase_jitter,
+    level,
+    sync_multiplier,
+    pulse_width,
+    unison,
+    waveform,
+});
+
 impl From<&GeneratorBlock> for AnalogOscillator {
     fn from(block: &GeneratorBlock) -> Self {
         Self {
@@ -109,6 +175,25 @@ impl Generator for AnalogOscillator {
     fn name(&self) -> String {
         self.name.to_owned()
     }
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+        self.enabled = enabled;
+        Ok(())
+    }
+
+    fn set_name(&mut self, name: &str) -> Result<(), Error> {
+        self.name = name.to_owned();
+        Ok(())
+    }
+
+    fn set_level_db(&mut self, level: Decibels) -> Result<(), Error> {
+        self.level = level_from_decibels(level);
+        Ok(())
+    }
+
+    fn level_db(&self) -> Option<Decibels> {
+        Some(decibels_from_level(self.level))
+    }
 }
 
 impl dyn Generator {
@@ -125,7 +210,10 @@ mod test {
     use uom::si::frequency::hertz;
     use uom::si::ratio::ratio;
 
+    use strum::IntoEnumIterator;
+
     use crate::test::read_generator_preset;
+    use crate::tests::test_data_path;
 
     use super::*;
 
@@ -262,4 +350,29 @@ mod test {
         assert_eq!(generator.unison.mode, UnisonMode::Octaves);
         assert_relative_eq!(generator.unison.bias.get::<percent>(), 35.0);
     }
+
+    /// There are no presets saved with the square and triangle waveforms so
+    /// the waveform of the sine preset is changed.
+    #[test]
+    fn waveforms() {
+        const WAVEFORM_POSITION: usize = 5259;
+        let path = test_data_path(&[
+            "generators",
+            "analog_oscillator",
+            "analog_oscillator-sine-1.8.13.phaseplant",
+        ]);
+        let mut bytes = std::fs::read(path).unwrap();
+        for waveform in AnalogWaveform::iter() {
+            bytes[WAVEFORM_POSITION..WAVEFORM_POSITION + 4]
+                .copy_from_slice(&(waveform as u32).to_le_bytes());
+            let preset = Preset::read(&mut std::io::Cursor::new(&bytes), None).unwrap();
+            let generator: &AnalogOscillator = preset.generator(1).unwrap();
+            assert_eq!(generator.waveform, waveform);
+            assert_eq!(waveform.name(), waveform.to_string());
+            assert!(!waveform.harmonics().is_empty());
+        }
+        assert_eq!(AnalogWaveform::iter().count(), 4);
+        assert!(AnalogWaveform::Square.uses_pulse_width());
+        assert!(!AnalogWaveform::Saw.uses_pulse_width());
+    }
 }
@@ -6,7 +6,11 @@
 
 use std::any::Any;
 
-use crate::generator::{Generator, GeneratorId, GeneratorMode};
+use crate::generator::{
+    decibels_from_level, level_from_decibels, Generator, GeneratorId, GeneratorMode,
+};
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 use crate::*;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -27,6 +31,25 @@ impl Default for AuxRouting {
     }
 }
 
+impl Interpolate for AuxRouting {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            id: step(&self.id, &other.id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            name: step(&self.name, &other.name, t),
+            invert: step(&self.invert, &other.invert, t),
+            level: self.level.interpolate(&other.level, t),
+        }
+    }
+}
+
+impl_parameters!(AuxRouting {
+    enabled,
+    name,
+    invert,
+    level,
+});
+
 impl From<&GeneratorBlock> for AuxRouting {
     fn from(block: &GeneratorBlock) -> Self {
         Self {
@@ -65,6 +88,25 @@ impl Generator for AuxRouting {
     fn name(&self) -> String {
         self.name.to_owned()
     }
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+        self.enabled = enabled;
+        Ok(())
+    }
+
+    fn set_name(&mut self, name: &str) -> Result<(), Error> {
+        self.name = name.to_owned();
+        Ok(())
+    }
+
+    fn set_level_db(&mut self, level: Decibels) -> Result<(), Error> {
+        self.level = level_from_decibels(level);
+        Ok(())
+    }
+
+    fn level_db(&self) -> Option<Decibels> {
+        Some(decibels_from_level(self.level))
+    }
 }
 
 impl dyn Generator {
@@ -4,6 +4,8 @@
 use std::any::Any;
 
 use super::*;
+use crate::interpolate::Interpolate;
+use crate::path::impl_parameters;
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct BlankGenerator {}
@@ -14,6 +16,14 @@ impl From<&GeneratorBlock> for BlankGenerator {
     }
 }
 
+impl Interpolate for BlankGenerator {
+    fn interpolate(&self, _other: &Self, _t: f32) -> Self {
+        Self {}
+    }
+}
+
+impl_parameters!(BlankGenerator {});
+
 impl Generator for BlankGenerator {
     fn id(&self) -> Option<GeneratorId> {
         None
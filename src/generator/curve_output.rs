@@ -5,6 +5,8 @@
 
 use std::any::Any;
 
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 use crate::point::CurvePoint;
 
 use super::*;
@@ -13,7 +15,12 @@ use super::*;
 pub struct CurveOutput {
     pub id: GeneratorId,
     pub enabled: bool,
+
+    /// The output switch. Nothing is sent to the
+    /// [destination](Self::destination) unless the generator is enabled as
+    /// well.
     pub output_enabled: bool,
+
     pub name: String,
     pub gain: Decibels,
     pub pan: Ratio,
@@ -40,6 +47,49 @@ impl Default for CurveOutput {
     }
 }
 
+impl Interpolate for CurveOutput {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            id: step(&self.id, &other.id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            output_enabled: step(&self.output_enabled, &other.output_enabled, t),
+            name: step(&self.name, &other.name, t),
+            gain: self.gain.interpolate(&other.gain, t),
+            pan: self.pan.interpolate(&other.pan, t),
+            destination: step(&self.destination, &other.destination, t),
+            loop_mode: step(&self.loop_mode, &other.loop_mode, t),
+            loop_start: self.loop_start.interpolate(&other.loop_start, t),
+            loop_length: self.loop_length.interpolate(&other.loop_length, t),
+            rate: self.rate.interpolate(&other.rate, t),
+            settings_locked: step(&self.settings_locked, &other.settings_locked, t),
+            curve: self.curve.interpolate(&other.curve, t),
+            curve_edited: step(&self.curve_edited, &other.curve_edited, t),
+            curve_length: self.curve_length.interpolate(&other.curve_length, t),
+            curve_name: step(&self.curve_name, &other.curve_name, t),
+            curve_path: step(&self.curve_path, &other.curve_path, t),
+        }
+    }
+}
+
+impl_parameters!(CurveOutput {
+    enabled,
+    output_enabled,
+    name,
+    gain,
+    pan,
+    destination,
+    loop_mode,
+    loop_start,
+    loop_length,
+    rate,
+    settings_locked,
+    curve,
+    curve_edited,
+    curve_length,
+    curve_name,
+    curve_path,
+});
+
 impl From<&GeneratorBlock> for CurveOutput {
     fn from(block: &GeneratorBlock) -> Self {
         Self {
@@ -90,6 +140,48 @@ impl Generator for CurveOutput {
     fn name(&self) -> String {
         self.name.to_owned()
     }
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+        self.enabled = enabled;
+        Ok(())
+    }
+
+    fn set_name(&mut self, name: &str) -> Result<(), Error> {
+        self.name = name.to_owned();
+        Ok(())
+    }
+
+    fn output_enabled(&self) -> Option<bool> {
+        Some(self.output_enabled)
+    }
+
+    fn set_output_enabled(&mut self, output_enabled: bool) -> Result<(), Error> {
+        self.output_enabled = output_enabled;
+        Ok(())
+    }
+
+    fn set_output_destination(&mut self, destination: OutputDestination) -> Result<(), Error> {
+        self.destination = destination;
+        Ok(())
+    }
+
+    fn set_level_db(&mut self, level: Decibels) -> Result<(), Error> {
+        self.gain = level;
+        Ok(())
+    }
+
+    fn level_db(&self) -> Option<Decibels> {
+        Some(self.gain)
+    }
+
+    fn pan(&self) -> Option<Ratio> {
+        Some(self.pan)
+    }
+
+    fn set_pan(&mut self, pan: Ratio) -> Result<(), Error> {
+        self.pan = check_pan(pan)?;
+        Ok(())
+    }
 }
 
 impl dyn Generator {
@@ -105,10 +197,22 @@ mod test {
     use uom::si::ratio::percent;
     use uom::si::time::{millisecond, second};
 
-    use crate::test::read_generator_preset;
+    use crate::test::{read_generator_preset, rewrite};
+    use crate::tests::test_data_path;
 
     use super::*;
 
+    /// The curve of every Curve Output is written back the same.
+    #[test]
+    fn rewrite_curves() {
+        for entry in std::fs::read_dir(test_data_path(&["generators", "curve_output"])).unwrap() {
+            let path = entry.unwrap().path();
+            let preset = Preset::read_file(&path).unwrap();
+            let written = rewrite(&preset).unwrap();
+            assert_eq!(written.generators, preset.generators, "{path:?}");
+        }
+    }
+
     /// The default configuration is with the Slope curve. This tests the
     /// default configuration with all points removed from the curve.
     #[test]
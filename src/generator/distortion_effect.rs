@@ -1,10 +1,18 @@
 //! [Distortion Effect](https://kilohearts.com/docs/phase_plant/#distortion_effect)
 //! generator
+//!
+//! The generator uses the same [`Distortion`] as the snapin but it doesn't
+//! have the dynamics or DC filter controls, so they are read as no dynamics
+//! with the DC filter on. Spread was added to the generator in Phase Plant
+//! 1.8.0.
 
 use std::any::Any;
 
-use crate::effect::Distortion;
+use crate::effect::{Distortion, EffectMode};
 use crate::generator::{Generator, GeneratorMode};
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::{Snapin, SnapinId};
 
 use super::*;
 
@@ -25,6 +33,68 @@ impl Default for DistortionEffect {
     }
 }
 
+impl DistortionEffect {
+    /// A Distortion snapin that sounds the same as the generator.
+    pub fn to_snapin(&self, id: SnapinId) -> Snapin {
+        Snapin {
+            name: self.name.clone(),
+            ..Snapin::new(Box::new(self.effect.clone()), id, self.enabled, false)
+        }
+    }
+
+    /// A generator that sounds the same as a Distortion snapin. Fails if the
+    /// snapin isn't a Distortion or it uses the dynamics or turns off the DC
+    /// filter, which the generator doesn't have.
+    pub fn from_snapin(snapin: &Snapin, id: GeneratorId) -> Result<Self, Error> {
+        let Some(effect) = snapin.effect.downcast_ref::<Distortion>() else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{} can't be a {} generator",
+                    snapin.effect.mode(),
+                    GeneratorMode::DistortionEffect.name()
+                ),
+            ));
+        };
+        if effect.dynamics.value != 0.0 || !effect.dc_filter {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The {} generator doesn't have dynamics or a DC filter switch",
+                    GeneratorMode::DistortionEffect.name()
+                ),
+            ));
+        }
+        Ok(Self {
+            id,
+            enabled: snapin.enabled,
+            name: if snapin.name.is_empty() {
+                EffectMode::Distortion.name().to_owned()
+            } else {
+                snapin.name.clone()
+            },
+            effect: effect.clone(),
+        })
+    }
+}
+
+impl Interpolate for DistortionEffect {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            id: step(&self.id, &other.id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            name: step(&self.name, &other.name, t),
+            effect: self.effect.interpolate(&other.effect, t),
+        }
+    }
+}
+
+impl_parameters!(DistortionEffect {
+    enabled,
+    name,
+    effect,
+});
+
 impl From<&GeneratorBlock> for DistortionEffect {
     fn from(block: &GeneratorBlock) -> Self {
         Self {
@@ -62,6 +132,16 @@ impl Generator for DistortionEffect {
     fn name(&self) -> String {
         self.name.to_owned()
     }
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+        self.enabled = enabled;
+        Ok(())
+    }
+
+    fn set_name(&mut self, name: &str) -> Result<(), Error> {
+        self.name = name.to_owned();
+        Ok(())
+    }
 }
 
 impl dyn Generator {
@@ -75,7 +155,7 @@ impl dyn Generator {
 mod test {
     use approx::assert_relative_eq;
 
-    use crate::effect::DistortionMode;
+    use crate::effect::{DistortionMode, Filter};
     use crate::generator::Generator;
     use crate::test::read_generator_preset;
 
@@ -160,6 +240,30 @@ mod test {
         assert_eq!(generator.effect.drive.db(), 10.0);
     }
 
+    #[test]
+    fn snapin() {
+        let preset = read_generator_preset(
+            "distortion_effect",
+            "distortion_effect-saturate-drive10db-1.8.13.phaseplant",
+        )
+        .unwrap();
+        let generator: &DistortionEffect = preset.generator(1).unwrap();
+        let snapin = generator.to_snapin(1);
+        assert_eq!(snapin.name, generator.name);
+        assert_eq!(snapin.effect.as_distortion(), Some(&generator.effect));
+        assert_eq!(
+            &DistortionEffect::from_snapin(&snapin, generator.id).unwrap(),
+            generator
+        );
+
+        // The defaults of the snapin use the dynamics.
+        let snapin = Snapin::new(Box::<Distortion>::default(), 1, true, false);
+        let error = DistortionEffect::from_snapin(&snapin, 1).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        let snapin = Snapin::new(Box::<Filter>::default(), 1, true, false);
+        assert!(DistortionEffect::from_snapin(&snapin, 1).is_err());
+    }
+
     #[test]
     fn sine_and_spread() {
         let preset = read_generator_preset(
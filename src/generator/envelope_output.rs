@@ -7,12 +7,20 @@ use uom::si::f32::Time;
 use uom::si::time::second;
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct EnvelopeOutput {
     pub id: GeneratorId,
     pub enabled: bool,
+
+    /// The output switch. The output only reaches the
+    /// [destination](Self::destination) when both the generator and its
+    /// output are enabled. Phase Plant 1 didn't have the switch so it's on
+    /// for presets saved by Phase Plant 1.
     pub output_enabled: bool,
+
     pub name: String,
     pub gain: Decibels,
     pub pan: Ratio,
@@ -41,6 +49,31 @@ impl Default for EnvelopeOutput {
     }
 }
 
+impl Interpolate for EnvelopeOutput {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            id: step(&self.id, &other.id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            output_enabled: step(&self.output_enabled, &other.output_enabled, t),
+            name: step(&self.name, &other.name, t),
+            gain: self.gain.interpolate(&other.gain, t),
+            pan: self.pan.interpolate(&other.pan, t),
+            destination: step(&self.destination, &other.destination, t),
+            envelope: self.envelope.interpolate(&other.envelope, t),
+        }
+    }
+}
+
+impl_parameters!(EnvelopeOutput {
+    enabled,
+    output_enabled,
+    name,
+    gain,
+    pan,
+    destination,
+    envelope,
+});
+
 impl From<&GeneratorBlock> for EnvelopeOutput {
     fn from(block: &GeneratorBlock) -> Self {
         EnvelopeOutput {
@@ -82,6 +115,48 @@ impl Generator for EnvelopeOutput {
     fn name(&self) -> String {
         self.name.to_owned()
     }
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+        self.enabled = enabled;
+        Ok(())
+    }
+
+    fn set_name(&mut self, name: &str) -> Result<(), Error> {
+        self.name = name.to_owned();
+        Ok(())
+    }
+
+    fn output_enabled(&self) -> Option<bool> {
+        Some(self.output_enabled)
+    }
+
+    fn set_output_enabled(&mut self, output_enabled: bool) -> Result<(), Error> {
+        self.output_enabled = output_enabled;
+        Ok(())
+    }
+
+    fn set_output_destination(&mut self, destination: OutputDestination) -> Result<(), Error> {
+        self.destination = destination;
+        Ok(())
+    }
+
+    fn set_level_db(&mut self, level: Decibels) -> Result<(), Error> {
+        self.gain = level;
+        Ok(())
+    }
+
+    fn level_db(&self) -> Option<Decibels> {
+        Some(self.gain)
+    }
+
+    fn pan(&self) -> Option<Ratio> {
+        Some(self.pan)
+    }
+
+    fn set_pan(&mut self, pan: Ratio) -> Result<(), Error> {
+        self.pan = check_pan(pan)?;
+        Ok(())
+    }
 }
 
 impl dyn Generator {
@@ -1,11 +1,17 @@
 //! The [Filter Effect](https://kilohearts.com/docs/phase_plant/#filter_effect)
 //! generator is an effect module that works like the [Filter] Snapin effect.
+//!
+//! The generator has every control of the snapin. Only the defaults differ,
+//! the generator starts with a cutoff of 440 Hz and no gain. Slope was added
+//! to the generator in Phase Plant 1.8.0.
 
 use std::any::Any;
 
-use crate::effect::Filter;
+use crate::effect::{EffectMode, Filter};
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
+use crate::{Snapin, SnapinId};
 
-// FIXME: Slope control added in 1.8.0
 use super::*;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -25,6 +31,58 @@ impl Default for FilterEffect {
     }
 }
 
+impl FilterEffect {
+    /// A Filter snapin that sounds the same as the generator.
+    pub fn to_snapin(&self, id: SnapinId) -> Snapin {
+        Snapin {
+            name: self.name.clone(),
+            ..Snapin::new(Box::new(self.effect.clone()), id, self.enabled, false)
+        }
+    }
+
+    /// A generator that sounds the same as a Filter snapin. Fails if the
+    /// snapin isn't a Filter.
+    pub fn from_snapin(snapin: &Snapin, id: GeneratorId) -> Result<Self, Error> {
+        let Some(effect) = snapin.effect.downcast_ref::<Filter>() else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{} can't be a {} generator",
+                    snapin.effect.mode(),
+                    GeneratorMode::FilterEffect.name()
+                ),
+            ));
+        };
+        Ok(Self {
+            id,
+            enabled: snapin.enabled,
+            name: if snapin.name.is_empty() {
+                EffectMode::Filter.name().to_owned()
+            } else {
+                snapin.name.clone()
+            },
+            effect: effect.clone(),
+        })
+    }
+}
+
+impl Interpolate for FilterEffect {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            id: step(&self.id, &other.id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            name: step(&self.name, &other.name, t),
+            effect: self.effect.interpolate(&other.effect, t),
+        }
+    }
+}
+
+impl_parameters!(FilterEffect {
+    enabled,
+    name,
+    effect,
+});
+
 impl From<&GeneratorBlock> for FilterEffect {
     fn from(block: &GeneratorBlock) -> Self {
         FilterEffect {
@@ -62,6 +120,16 @@ impl Generator for FilterEffect {
     fn name(&self) -> String {
         self.name.to_owned()
     }
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+        self.enabled = enabled;
+        Ok(())
+    }
+
+    fn set_name(&mut self, name: &str) -> Result<(), Error> {
+        self.name = name.to_owned();
+        Ok(())
+    }
 }
 
 impl dyn Generator {
@@ -76,7 +144,7 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::frequency::hertz;
 
-    use crate::effect::FilterMode;
+    use crate::effect::{Distortion, FilterMode};
     use crate::test::read_generator_preset;
     use crate::Decibels;
 
@@ -105,6 +173,31 @@ mod test {
         assert!(!generator.enabled);
     }
 
+    #[test]
+    fn snapin() {
+        let preset = read_generator_preset(
+            "filter_effect",
+            "filter_effect-high_shelf-slope3-1.8.16.phaseplant",
+        )
+        .unwrap();
+        let generator: &FilterEffect = preset.generator(1).unwrap();
+        let snapin = generator.to_snapin(2);
+        assert_eq!(snapin.id, 2);
+        assert_eq!(snapin.effect.as_filter(), Some(&generator.effect));
+        assert_eq!(
+            &FilterEffect::from_snapin(&snapin, generator.id).unwrap(),
+            generator
+        );
+
+        let snapin = Snapin::new(Box::<Filter>::default(), 1, false, false);
+        let generator = FilterEffect::from_snapin(&snapin, 3).unwrap();
+        assert!(!generator.enabled);
+        assert_eq!(generator.effect, Filter::default());
+        let snapin = Snapin::new(Box::<Distortion>::default(), 1, true, false);
+        let error = FilterEffect::from_snapin(&snapin, 1).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+
     #[test]
     fn init() {
         for file in &[
@@ -0,0 +1,154 @@
+//! Generators by value instead of boxed.
+//!
+//! [`Preset::generators`] holds boxed trait objects that have to be
+//! downcast to reach the settings of a mode. Loops that analyze a whole
+//! library can read the generators as a [`GeneratorEnum`] with
+//! [`Preset::read_generator_enums`] and match on the variant instead.
+
+use std::io::{Error, ErrorKind};
+
+use super::*;
+use crate::io::GeneratorBlock;
+
+macro_rules! generator_enum {
+    ($($variant:ident($generator:ty)),+ $(,)?) => {
+        /// A generator of any mode, stored by value.
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum GeneratorEnum {
+            $($variant($generator)),+
+        }
+
+        impl GeneratorEnum {
+            pub fn as_generator(&self) -> &dyn Generator {
+                match self {
+                    $(Self::$variant(generator) => generator),+
+                }
+            }
+
+            pub fn as_generator_mut(&mut self) -> &mut dyn Generator {
+                match self {
+                    $(Self::$variant(generator) => generator),+
+                }
+            }
+
+            /// Box the generator so it can be added to [`Preset::generators`].
+            pub fn into_boxed(self) -> Box<dyn Generator> {
+                match self {
+                    $(Self::$variant(generator) => Box::new(generator)),+
+                }
+            }
+
+            pub fn id(&self) -> Option<GeneratorId> {
+                match self {
+                    $(Self::$variant(generator) => generator.id()),+
+                }
+            }
+
+            pub fn is_enabled(&self) -> bool {
+                match self {
+                    $(Self::$variant(generator) => generator.is_enabled()),+
+                }
+            }
+
+            pub fn mode(&self) -> GeneratorMode {
+                match self {
+                    $(Self::$variant(_) => GeneratorMode::$variant),+
+                }
+            }
+
+            pub fn name(&self) -> String {
+                match self {
+                    $(Self::$variant(generator) => generator.name()),+
+                }
+            }
+        }
+
+        impl From<&GeneratorBlock> for GeneratorEnum {
+            fn from(block: &GeneratorBlock) -> Self {
+                match block.mode {
+                    $(GeneratorMode::$variant => Self::$variant(<$generator>::from(block))),+
+                }
+            }
+        }
+
+        /// Fails for generators that aren't one of the modes in this
+        /// library.
+        impl TryFrom<Box<dyn Generator>> for GeneratorEnum {
+            type Error = Error;
+
+            fn try_from(generator: Box<dyn Generator>) -> Result<Self, Self::Error> {
+                let unboxed = match generator.mode() {
+                    $(GeneratorMode::$variant => generator
+                        .downcast::<$generator>()
+                        .map(|generator| Self::$variant(*generator))),+
+                };
+                unboxed.map_err(|generator| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "Generator '{}' isn't a {} generator from this library",
+                            generator.name(),
+                            generator.mode().name()
+                        ),
+                    )
+                })
+            }
+        }
+
+        $(
+            impl From<$generator> for GeneratorEnum {
+                fn from(generator: $generator) -> Self {
+                    Self::$variant(generator)
+                }
+            }
+        )+
+    };
+}
+
+generator_enum!(
+    AnalogOscillator(AnalogOscillator),
+    AuxRouting(AuxRouting),
+    Blank(BlankGenerator),
+    CurveOutput(CurveOutput),
+    DistortionEffect(DistortionEffect),
+    EnvelopeOutput(EnvelopeOutput),
+    FilterEffect(FilterEffect),
+    GranularGenerator(GranularGenerator),
+    Group(Group),
+    MixRouting(MixRouting),
+    NoiseGenerator(NoiseGenerator),
+    NonlinearFilterGenerator(NonlinearFilterGenerator),
+    SamplePlayer(SamplePlayer),
+    WavetableOscillator(WavetableOscillator),
+);
+
+#[cfg(test)]
+mod test {
+    use std::fs::File;
+    use std::io::BufReader;
+
+    use crate::io::ReadOptions;
+    use crate::tests::test_data_path;
+
+    use super::*;
+
+    #[test]
+    fn read_generator_enums() {
+        let path = test_data_path(&["generators", "generators-all-1.8.13.phaseplant"]);
+        let preset = Preset::read_file(&path).unwrap();
+        let mut reader = BufReader::new(File::open(&path).unwrap());
+        let generators =
+            Preset::read_generator_enums(&mut reader, &ReadOptions::default()).unwrap();
+        assert_eq!(generators.len(), preset.generators.len());
+        for (generator, boxed) in generators.iter().zip(&preset.generators) {
+            assert_eq!(generator.mode(), boxed.mode());
+            assert_eq!(generator.id(), boxed.id());
+            assert_eq!(generator.name(), boxed.name());
+            assert_eq!(&generator.clone().into_boxed(), boxed);
+            assert_eq!(&GeneratorEnum::try_from(boxed.clone()).unwrap(), generator);
+        }
+        assert!(generators
+            .iter()
+            .any(|generator| matches!(generator, GeneratorEnum::SamplePlayer(_))));
+    }
+}
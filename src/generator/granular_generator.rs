@@ -9,9 +9,11 @@ use uom::si::f32::Frequency;
 use uom::si::ratio::percent;
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
 /// Strumming pattern.
-#[derive(Clone, Copy, Debug, Eq, FromRepr, PartialEq)]
+#[derive(Clone, Copy, Debug, EnumIter, Eq, FromRepr, PartialEq)]
 #[repr(u32)]
 pub enum ChordPickingPattern {
     // The discriminants correspond to the file format.
@@ -30,6 +32,21 @@ impl ChordPickingPattern {
             )
         })
     }
+
+    /// Positions of the notes of a chord in the order they are picked,
+    /// starting with the lowest note. The order repeats once every note has
+    /// been picked. Up-Down doesn't pick the highest and lowest notes twice
+    /// in a row. Not set for Random.
+    pub fn order(&self, note_count: usize) -> Option<Vec<usize>> {
+        use ChordPickingPattern::*;
+        let up = 0..note_count;
+        Some(match self {
+            Up => up.collect(),
+            Down => up.rev().collect(),
+            UpDown => up.clone().chain(up.skip(1).rev().skip(1)).collect(),
+            Random => return None,
+        })
+    }
 }
 
 impl Display for ChordPickingPattern {
@@ -45,7 +62,9 @@ impl Display for ChordPickingPattern {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, FromRepr, PartialEq)]
+impl_parameters!(ChordPickingPattern);
+
+#[derive(Clone, Copy, Debug, EnumIter, Eq, FromRepr, PartialEq)]
 #[repr(u32)]
 pub enum GranularSpawnRateMode {
     // The discriminants correspond to the file format.
@@ -76,6 +95,8 @@ impl Display for GranularSpawnRateMode {
     }
 }
 
+impl_parameters!(GranularSpawnRateMode);
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct GranularRandomization {
     pub position: Ratio,
@@ -99,7 +120,29 @@ impl Default for GranularRandomization {
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, FromRepr, PartialEq)]
+impl Interpolate for GranularRandomization {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            position: self.position.interpolate(&other.position, t),
+            timing: self.timing.interpolate(&other.timing, t),
+            pitch: self.pitch.interpolate(&other.pitch, t),
+            level: self.level.interpolate(&other.level, t),
+            pan: self.pan.interpolate(&other.pan, t),
+            reverse: self.reverse.interpolate(&other.reverse, t),
+        }
+    }
+}
+
+impl_parameters!(GranularRandomization {
+    position,
+    timing,
+    pitch,
+    level,
+    pan,
+    reverse,
+});
+
+#[derive(Copy, Clone, Debug, Display, EnumIter, Eq, FromRepr, PartialEq)]
 #[repr(u32)]
 pub enum GranularChordMode {
     // The discriminants correspond to the file format.
@@ -119,11 +162,50 @@ pub enum GranularChordMode {
     PentatonicMinor = 13,
 }
 
+impl_parameters!(GranularChordMode);
+
 impl GranularChordMode {
     pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
         Self::from_repr(id)
             .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("Unknown chord mode {id}")))
     }
+
+    /// Semitones above the note of each note of the chord in one octave.
+    /// The chord repeats an octave higher across the
+    /// [range](GranularChord::range_octaves).
+    pub fn intervals(&self) -> &'static [u8] {
+        use GranularChordMode::*;
+        match self {
+            Octaves => &[0],
+            Fifths => &[0, 7],
+            Minor => &[0, 3, 7],
+            MinorMin7 => &[0, 3, 7, 10],
+            MinorMaj7 => &[0, 3, 7, 11],
+            Major => &[0, 4, 7],
+            MajorMin7 => &[0, 4, 7, 10],
+            MajorMaj7 => &[0, 4, 7, 11],
+            Sus2 => &[0, 2, 7],
+            Sus4 => &[0, 5, 7],
+            Dim => &[0, 3, 6],
+            Dim7 => &[0, 3, 6, 9],
+            PentatonicMaj => &[0, 2, 4, 7, 9],
+            PentatonicMinor => &[0, 3, 5, 7, 10],
+        }
+    }
+
+    /// The mode that plays the intervals, in any order.
+    pub fn from_intervals(intervals: &[u8]) -> Option<Self> {
+        let mut sorted = intervals.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        Self::iter().find(|mode| mode.intervals() == sorted)
+    }
+
+    /// The unison mode that plays the same chord. Not set for the modes
+    /// unison doesn't have.
+    pub fn unison_mode(&self) -> Option<UnisonMode> {
+        UnisonMode::iter().find(|mode| mode.chord_intervals() == Some(self.intervals()))
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -134,6 +216,49 @@ pub struct GranularChord {
     pub range_octaves: f32,
 }
 
+impl GranularChord {
+    /// An enabled chord.
+    pub fn new(
+        mode: GranularChordMode,
+        picking_pattern: ChordPickingPattern,
+        range_octaves: f32,
+    ) -> Self {
+        Self {
+            enabled: true,
+            picking_pattern,
+            mode,
+            range_octaves,
+        }
+    }
+
+    /// Semitones above the note of every note the chord can play, from
+    /// lowest to highest. The note at the top of the range is included so
+    /// a range of zero only plays the note.
+    pub fn notes(&self) -> Vec<u8> {
+        let range = (self.range_octaves * 12.0)
+            .round()
+            .clamp(0.0, u8::MAX as f32) as u32;
+        let intervals = self.mode.intervals();
+        (0..)
+            .flat_map(|octave| {
+                intervals
+                    .iter()
+                    .map(move |&interval| interval as u32 + 12 * octave)
+            })
+            .take_while(|&semitones| semitones <= range)
+            .map(|semitones| semitones as u8)
+            .collect()
+    }
+
+    /// The notes in the order they are picked, once through. Not set for
+    /// the random picking pattern.
+    pub fn picked_notes(&self) -> Option<Vec<u8>> {
+        let notes = self.notes();
+        let order = self.picking_pattern.order(notes.len())?;
+        Some(order.into_iter().map(|index| notes[index]).collect())
+    }
+}
+
 impl Default for GranularChord {
     fn default() -> Self {
         Self {
@@ -145,8 +270,26 @@ impl Default for GranularChord {
     }
 }
 
+impl Interpolate for GranularChord {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            enabled: step(&self.enabled, &other.enabled, t),
+            picking_pattern: step(&self.picking_pattern, &other.picking_pattern, t),
+            mode: step(&self.mode, &other.mode, t),
+            range_octaves: self.range_octaves.interpolate(&other.range_octaves, t),
+        }
+    }
+}
+
+impl_parameters!(GranularChord {
+    enabled,
+    picking_pattern,
+    mode,
+    range_octaves,
+});
+
 /// Directions new grains travel.
-#[derive(Copy, Clone, Debug, Eq, FromRepr, PartialEq)]
+#[derive(Copy, Clone, Debug, Display, EnumIter, Eq, FromRepr, PartialEq)]
 #[repr(u32)]
 pub enum GranularDirection {
     // The discriminants correspond to the file format.
@@ -157,6 +300,8 @@ pub enum GranularDirection {
     Midpoint = 1,
 }
 
+impl_parameters!(GranularDirection);
+
 impl GranularDirection {
     pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
         Self::from_repr(id).ok_or_else(|| {
@@ -187,6 +332,24 @@ impl Default for GranularEnvelope {
     }
 }
 
+impl Interpolate for GranularEnvelope {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            attack_time: self.attack_time.interpolate(&other.attack_time, t),
+            attack_curve: self.attack_curve.interpolate(&other.attack_curve, t),
+            decay_time: self.decay_time.interpolate(&other.decay_time, t),
+            decay_curve: self.decay_curve.interpolate(&other.decay_curve, t),
+        }
+    }
+}
+
+impl_parameters!(GranularEnvelope {
+    attack_time,
+    attack_curve,
+    decay_time,
+    decay_curve,
+});
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct GranularGenerator {
     pub id: GeneratorId,
@@ -205,7 +368,9 @@ pub struct GranularGenerator {
     /// Amplitude of the waveform. Gain is set in the Out generator.
     pub level: Ratio,
 
-    /// A file containing the samples to play back. Usually in a format like FLAC, MP3 or WAV.
+    /// The embedded sample the grains are taken from. It is a FLAC file even
+    /// when the original sample was a WAV or MP3, and is empty for factory
+    /// samples.
     pub sample_contents: Vec<u8>,
 
     pub sample_name: Option<String>,
@@ -233,6 +398,18 @@ pub struct GranularGenerator {
     pub warm_start: bool,
 }
 
+impl GranularGenerator {
+    /// How the sample is stored when the preset is written, `None` if there
+    /// is no sample.
+    pub fn sample_data_mode(&self) -> Option<SampleDataMode> {
+        SampleDataMode::for_sample(
+            GeneratorMode::GranularGenerator,
+            &self.sample_path,
+            &self.sample_contents,
+        )
+    }
+}
+
 impl Default for GranularGenerator {
     fn default() -> Self {
         Self::from(&GeneratorBlock {
@@ -244,6 +421,65 @@ impl Default for GranularGenerator {
     }
 }
 
+impl Interpolate for GranularGenerator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            id: step(&self.id, &other.id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            name: step(&self.name, &other.name, t),
+            fine_tuning: self.fine_tuning.interpolate(&other.fine_tuning, t),
+            harmonic: self.harmonic.interpolate(&other.harmonic, t),
+            shift: self.shift.interpolate(&other.shift, t),
+            phase_offset: self.phase_offset.interpolate(&other.phase_offset, t),
+            phase_jitter: self.phase_jitter.interpolate(&other.phase_jitter, t),
+            level: self.level.interpolate(&other.level, t),
+            sample_contents: step(&self.sample_contents, &other.sample_contents, t),
+            sample_name: step(&self.sample_name, &other.sample_name, t),
+            sample_path: step(&self.sample_path, &other.sample_path, t),
+            base_pitch: self.base_pitch.interpolate(&other.base_pitch, t),
+            base_pitch_locked: step(&self.base_pitch_locked, &other.base_pitch_locked, t),
+            position: self.position.interpolate(&other.position, t),
+            direction: step(&self.direction, &other.direction, t),
+            envelope: self.envelope.interpolate(&other.envelope, t),
+            align_phases: step(&self.align_phases, &other.align_phases, t),
+            grains: self.grains.interpolate(&other.grains, t),
+            grain_length: self.grain_length.interpolate(&other.grain_length, t),
+            auto_grain_length: step(&self.auto_grain_length, &other.auto_grain_length, t),
+            spawn_rate_mode: step(&self.spawn_rate_mode, &other.spawn_rate_mode, t),
+            randomization: self.randomization.interpolate(&other.randomization, t),
+            chord: self.chord.interpolate(&other.chord, t),
+            warm_start: step(&self.warm_start, &other.warm_start, t),
+        }
+    }
+}
+
+impl_parameters!(GranularGenerator {
+    enabled,
+    name,
+    fine_tuning: Number(Semitones),
+    harmonic,
+    shift,
+    phase_offset,
+    phase_jitter,
+    level,
+    sample_contents: Data,
+    sample_name,
+    sample_path,
+    base_pitch,
+    base_pitch_locked,
+    position,
+    direction,
+    envelope,
+    align_phases,
+    grains,
+    grain_length,
+    auto_grain_length,
+    spawn_rate_mode,
+    randomization,
+    chord,
+    warm_start,
+});
+
 impl From<&GeneratorBlock> for GranularGenerator {
     fn from(block: &GeneratorBlock) -> Self {
         Self {
@@ -302,6 +538,25 @@ impl Generator for GranularGenerator {
     fn name(&self) -> String {
         self.name.to_owned()
     }
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+        self.enabled = enabled;
+        Ok(())
+    }
+
+    fn set_name(&mut self, name: &str) -> Result<(), Error> {
+        self.name = name.to_owned();
+        Ok(())
+    }
+
+    fn set_level_db(&mut self, level: Decibels) -> Result<(), Error> {
+        self.level = level_from_decibels(level);
+        Ok(())
+    }
+
+    fn level_db(&self) -> Option<Decibels> {
+        Some(decibels_from_level(self.level))
+    }
 }
 
 impl dyn Generator {
@@ -314,13 +569,17 @@ impl dyn Generator {
 #[cfg(test)]
 mod test {
     use approx::assert_relative_eq;
+    use std::io::Cursor;
+
     use music_note::midi;
     use uom::si::frequency::hertz;
     use uom::si::ratio::percent;
     use uom::si::ratio::ratio;
     use uom::si::time::millisecond;
 
+    use crate::lowlevel::Blocks;
     use crate::test::read_generator_preset;
+    use crate::tests::test_data_path;
 
     use super::*;
 
@@ -390,6 +649,42 @@ mod test {
         assert_eq!(generator.chord.picking_pattern, ChordPickingPattern::UpDown);
     }
 
+    #[test]
+    fn chord_notes() {
+        let chord = GranularChord::new(GranularChordMode::Fifths, ChordPickingPattern::Up, 2.0);
+        assert_eq!(chord.notes(), [0, 7, 12, 19, 24]);
+        assert_eq!(chord.picked_notes().unwrap(), chord.notes());
+
+        let chord = GranularChord {
+            picking_pattern: ChordPickingPattern::UpDown,
+            ..chord
+        };
+        assert_eq!(chord.picked_notes().unwrap(), [0, 7, 12, 19, 24, 19, 12, 7]);
+        assert_eq!(ChordPickingPattern::Down.order(3).unwrap(), [2, 1, 0]);
+        assert_eq!(ChordPickingPattern::UpDown.order(1).unwrap(), [0]);
+        assert!(ChordPickingPattern::Random.order(3).is_none());
+
+        // A range of zero only plays the note.
+        let sus2 = GranularChord::new(GranularChordMode::Sus2, ChordPickingPattern::Up, 0.0);
+        assert_eq!(sus2.notes(), [0]);
+
+        for mode in GranularChordMode::iter() {
+            assert_eq!(
+                GranularChordMode::from_intervals(mode.intervals()),
+                Some(mode)
+            );
+        }
+        assert_eq!(
+            GranularChordMode::from_intervals(&[7, 0, 4]),
+            Some(GranularChordMode::Major)
+        );
+        assert_eq!(
+            GranularChordMode::MajorMin7.unison_mode(),
+            Some(UnisonMode::Major7)
+        );
+        assert!(GranularChordMode::PentatonicMaj.unison_mode().is_none());
+    }
+
     #[test]
     fn default() {
         let generator = GranularGenerator::default();
@@ -586,4 +881,48 @@ mod test {
         assert_eq!(generator.sample_contents.len(), 78186);
         assert_eq!(&generator.sample_contents[0..4], b"fLaC");
     }
+
+    /// The granular settings added in Phase Plant 2.1 survive being written.
+    #[test]
+    fn write() {
+        for file_name in [
+            "granular_generator-chord-pent_min-range8oct-pick_down-2.1.0.phaseplant",
+            "granular_generator-randomize-pos10-timing20-pitch2-level50-2.1.0.phaseplant",
+            "granular_generator-shift125-phase_offset10-sync-2.1.0.phaseplant",
+        ] {
+            let preset = read_generator_preset("granular_generator", file_name).unwrap();
+            let mut cursor = Cursor::new(Vec::new());
+            preset.write(&mut cursor).unwrap();
+            cursor.set_position(0);
+            let written = Preset::read(&mut cursor, None).unwrap();
+            let original: &GranularGenerator = preset.generator(1).unwrap();
+            let generator: &GranularGenerator = written.generator(1).unwrap();
+            assert_eq!(generator, original, "{file_name}");
+        }
+    }
+
+    #[test]
+    fn sample_data_mode() {
+        for file_name in [
+            "granular_generator-chaotic_saw-2.1.0.phaseplant",
+            "granular_generator-sample_custom-2.1.0.phaseplant",
+        ] {
+            let path = test_data_path(&["generators", "granular_generator", file_name]);
+            let blocks = Blocks::read_file(path).unwrap();
+            let block = blocks.generators().nth(1).unwrap();
+            assert_eq!(block.sample_data_mode(), Some(SampleDataMode::PathWithFlag));
+            let generator: &GranularGenerator = blocks.preset().generator(1).unwrap();
+            assert_eq!(generator.sample_data_mode(), block.sample_data_mode());
+
+            // Factory samples are written without contents.
+            let mut cursor = Cursor::new(Vec::new());
+            blocks.preset().write(&mut cursor).unwrap();
+            cursor.set_position(0);
+            let written = Blocks::read(&mut cursor).unwrap();
+            let written_block = written.generators().nth(1).unwrap();
+            assert_eq!(written_block.sample_data_mode(), block.sample_data_mode());
+            let written_generator: &GranularGenerator = written.preset().generator(1).unwrap();
+            assert_eq!(written_generator.sample_contents, generator.sample_contents);
+        }
+    }
 }
@@ -4,6 +4,8 @@
 use std::any::Any;
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Group {
@@ -14,7 +16,7 @@ pub struct Group {
 }
 
 impl Group {
-    pub const MAX_NAME_LENGTH: usize = 45; // From Phase Plant 1.8.20
+    pub const MAX_NAME_LENGTH: usize = crate::limits::GROUP_NAME_LENGTH_MAX;
 }
 
 impl Default for Group {
@@ -26,6 +28,23 @@ impl Default for Group {
     }
 }
 
+impl Interpolate for Group {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            id: step(&self.id, &other.id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            minimized: step(&self.minimized, &other.minimized, t),
+            name: step(&self.name, &other.name, t),
+        }
+    }
+}
+
+impl_parameters!(Group {
+    enabled,
+    minimized,
+    name,
+});
+
 impl From<&GeneratorBlock> for Group {
     fn from(block: &GeneratorBlock) -> Self {
         Group {
@@ -63,6 +82,16 @@ impl Generator for Group {
     fn name(&self) -> String {
         self.name.clone()
     }
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+        self.enabled = enabled;
+        Ok(())
+    }
+
+    fn set_name(&mut self, name: &str) -> Result<(), Error> {
+        self.name = name.to_owned();
+        Ok(())
+    }
 }
 
 impl dyn Generator {
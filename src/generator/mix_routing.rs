@@ -4,6 +4,8 @@
 use std::any::Any;
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct MixRouting {
@@ -23,6 +25,25 @@ impl Default for MixRouting {
     }
 }
 
+impl Interpolate for MixRouting {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            id: step(&self.id, &other.id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            name: step(&self.name, &other.name, t),
+            level: self.level.interpolate(&other.level, t),
+            invert: step(&self.invert, &other.invert, t),
+        }
+    }
+}
+
+impl_parameters!(MixRouting {
+    enabled,
+    name,
+    level,
+    invert,
+});
+
 impl From<&GeneratorBlock> for MixRouting {
     fn from(block: &GeneratorBlock) -> Self {
         MixRouting {
@@ -61,6 +82,25 @@ impl Generator for MixRouting {
     fn name(&self) -> String {
         self.name.to_owned()
     }
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+        self.enabled = enabled;
+        Ok(())
+    }
+
+    fn set_name(&mut self, name: &str) -> Result<(), Error> {
+        self.name = name.to_owned();
+        Ok(())
+    }
+
+    fn set_level_db(&mut self, level: Decibels) -> Result<(), Error> {
+        self.level = level_from_decibels(level);
+        Ok(())
+    }
+
+    fn level_db(&self) -> Option<Decibels> {
+        Some(decibels_from_level(self.level))
+    }
 }
 
 impl dyn Generator {
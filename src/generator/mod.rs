@@ -1,9 +1,21 @@
 //! Generators create and affect audio.
+//!
+//! # Levels, gain and pan
+//!
+//! Phase Plant shows the level of the generators that make sound as a
+//! percentage of the linear amplitude, so a level of 50% is about -6 dB.
+//! Levels are a [`Ratio`]. The Curve and Envelope outputs show their gain in
+//! decibels so it is a [`Decibels`]. [`Generator::level_db`] and
+//! [`Generator::set_level_db`] use decibels for both.
+//!
+//! Pan is a signed [`Ratio`] from -100% for left to 100% for right.
 
 use std::any::Any;
+use std::str::FromStr;
 
 use downcast_rs::{impl_downcast, Downcast};
-use strum_macros::Display;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
 
 pub use analog_oscillator::*;
 pub use aux_routing::*;
@@ -12,14 +24,18 @@ pub use curve_output::*;
 pub use distortion_effect::*;
 pub use envelope_output::*;
 pub use filter_effect::*;
+pub use generator_enum::*;
 pub use granular_generator::*;
 pub use group::*;
 pub use mix_routing::*;
 pub use noise_generator::*;
 pub use nonlinear_filter_generator::*;
+pub use pitch::*;
+pub use sample_info::*;
 pub use sample_player::*;
 pub use wavetable_oscillator::*;
 
+use crate::path::impl_parameters;
 use crate::*;
 
 mod analog_oscillator;
@@ -29,18 +45,21 @@ mod curve_output;
 mod distortion_effect;
 mod envelope_output;
 mod filter_effect;
+mod generator_enum;
 mod granular_generator;
 mod group;
 mod mix_routing;
 mod noise_generator;
 mod nonlinear_filter_generator;
+mod pitch;
+mod sample_info;
 mod sample_player;
 mod wavetable_oscillator;
 
 pub type GeneratorId = u16;
 
 /// The sample player does not include the `Off` option.
-#[derive(Copy, Clone, Debug, Display, Eq, FromRepr, PartialEq)]
+#[derive(Copy, Clone, Debug, Display, EnumIter, Eq, FromRepr, PartialEq)]
 #[repr(u32)]
 pub enum LoopMode {
     // The discriminants correspond to the file format.
@@ -49,17 +68,44 @@ pub enum LoopMode {
     Sustain = 2,
     PingPong = 3,
     Reverse = 4,
+
+    /// Added by a newer version of Phase Plant. The value is kept so it can be
+    /// written back.
+    #[strum(disabled)]
+    Unknown(u32),
 }
 
+impl_parameters!(LoopMode);
+
 impl LoopMode {
-    pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
-        Self::from_repr(id)
-            .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("Unknown loop mode {id}")))
+    /// The value stored in presets.
+    pub fn id(&self) -> u32 {
+        use LoopMode::*;
+        match self {
+            Off => 0,
+            Infinite => 1,
+            Sustain => 2,
+            PingPong => 3,
+            Reverse => 4,
+            Unknown(id) => *id,
+        }
+    }
+}
+
+impl ForwardCompatible for LoopMode {
+    const DESCRIPTION: &'static str = "loop mode";
+
+    fn from_id(id: u32) -> Self {
+        Self::from_repr(id).unwrap_or(Self::Unknown(id))
+    }
+
+    fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown(_))
     }
 }
 
 /// Not the same discriminants as [`LaneDestination`].
-#[derive(Copy, Clone, Debug, Eq, FromRepr, PartialEq)]
+#[derive(Copy, Clone, Debug, EnumIter, Eq, FromRepr, Hash, PartialEq)]
 #[repr(u32)]
 pub enum OutputDestination {
     None = 0,
@@ -79,6 +125,63 @@ impl OutputDestination {
             )
         })
     }
+
+    /// The value stored in the file.
+    pub(crate) fn id(&self) -> u32 {
+        *self as u32
+    }
+
+    /// The destination for the lane with the given zero-based index.
+    pub fn lane(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(OutputDestination::Lane1),
+            1 => Some(OutputDestination::Lane2),
+            2 => Some(OutputDestination::Lane3),
+            _ => None,
+        }
+    }
+
+    /// Zero-based index of the lane, if the destination is a lane.
+    pub fn lane_index(&self) -> Option<usize> {
+        match self {
+            OutputDestination::Lane1 => Some(0),
+            OutputDestination::Lane2 => Some(1),
+            OutputDestination::Lane3 => Some(2),
+            _ => None,
+        }
+    }
+}
+
+impl From<LaneDestination> for OutputDestination {
+    fn from(destination: LaneDestination) -> Self {
+        match destination {
+            LaneDestination::Lane1 => OutputDestination::Lane1,
+            LaneDestination::Lane2 => OutputDestination::Lane2,
+            LaneDestination::Lane3 => OutputDestination::Lane3,
+            LaneDestination::Master => OutputDestination::Master,
+            LaneDestination::Sideband => OutputDestination::Sideband,
+        }
+    }
+}
+
+/// Lanes always have a destination so [`OutputDestination::None`] can't be
+/// converted.
+impl TryFrom<OutputDestination> for LaneDestination {
+    type Error = Error;
+
+    fn try_from(destination: OutputDestination) -> Result<Self, Self::Error> {
+        match destination {
+            OutputDestination::None => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Lanes must have a destination",
+            )),
+            OutputDestination::Lane1 => Ok(LaneDestination::Lane1),
+            OutputDestination::Lane2 => Ok(LaneDestination::Lane2),
+            OutputDestination::Lane3 => Ok(LaneDestination::Lane3),
+            OutputDestination::Master => Ok(LaneDestination::Master),
+            OutputDestination::Sideband => Ok(LaneDestination::Sideband),
+        }
+    }
 }
 
 impl Display for OutputDestination {
@@ -96,7 +199,9 @@ impl Display for OutputDestination {
     }
 }
 
-#[derive(Copy, Clone, Debug, Default, Display, Eq, FromRepr, PartialEq)]
+impl_parameters!(OutputDestination);
+
+#[derive(Copy, Clone, Debug, Default, Display, EnumIter, Eq, FromRepr, PartialEq)]
 #[repr(u32)]
 pub enum SeedMode {
     // The discriminates match the file format. Using an enumeration provides
@@ -106,6 +211,8 @@ pub enum SeedMode {
     Random,
 }
 
+impl_parameters!(SeedMode);
+
 impl SeedMode {
     pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
         Self::from_repr(id)
@@ -118,10 +225,18 @@ impl Preset {
     pub fn generator<T: Generator>(&self, generator_index: usize) -> Option<&T> {
         self.generators.get(generator_index)?.downcast_ref::<T>()
     }
+
+    /// Call the visitor with every generator. Useful for changing generators
+    /// in bulk.
+    pub fn visit_generators_mut<F: FnMut(&mut dyn Generator)>(&mut self, mut visitor: F) {
+        for generator in &mut self.generators {
+            visitor(generator.as_mut());
+        }
+    }
 }
 
 #[repr(u32)]
-#[derive(Copy, Clone, Debug, Display, Eq, FromRepr, PartialEq)]
+#[derive(Copy, Clone, Debug, Display, EnumIter, Eq, FromRepr, Hash, PartialEq)]
 pub enum GeneratorMode {
     // The discriminants correspond to the file format.
     AnalogOscillator = 2,
@@ -155,6 +270,15 @@ impl GeneratorMode {
         self == &GeneratorMode::Blank
     }
 
+    /// A new generator of this mode with the default settings.
+    pub(crate) fn default_generator(&self) -> Box<dyn Generator> {
+        GeneratorEnum::from(&crate::io::GeneratorBlock {
+            mode: *self,
+            ..Default::default()
+        })
+        .into_boxed()
+    }
+
     pub fn name(&self) -> &'static str {
         use GeneratorMode::*;
         match self {
@@ -176,7 +300,28 @@ impl GeneratorMode {
     }
 }
 
-pub trait Generator: Downcast + std::fmt::Debug {
+/// Parses the [name](GeneratorMode::name) or the name of the variant,
+/// ignoring case.
+impl FromStr for GeneratorMode {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        GeneratorMode::iter()
+            .find(|mode| {
+                mode.name().eq_ignore_ascii_case(text)
+                    || mode.to_string().eq_ignore_ascii_case(text)
+            })
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Unknown generator '{text}'"),
+                )
+            })
+    }
+}
+
+pub trait Generator: Downcast + std::fmt::Debug + GeneratorClone + Send + Sync {
     /// Not every generator has an assignable ID. The blank generator in
     /// particular does not.
     fn id(&self) -> Option<GeneratorId>;
@@ -189,23 +334,137 @@ pub trait Generator: Downcast + std::fmt::Debug {
 
     /// Not all generators have presets
     fn set_preset_name(&mut self, _preset_name_opt: Option<String>) {}
+
+    /// Fails for the blank generator.
+    fn set_enabled(&mut self, _enabled: bool) -> Result<(), Error> {
+        Err(unsupported(self.mode(), "be enabled"))
+    }
+
+    /// Fails for the blank generator.
+    fn set_name(&mut self, _name: &str) -> Result<(), Error> {
+        Err(unsupported(self.mode(), "be renamed"))
+    }
+
+    /// If the output of the envelope and curve output generators is on.
+    /// `None` for the other generators, which don't have an output switch.
+    fn output_enabled(&self) -> Option<bool> {
+        None
+    }
+
+    /// Only the envelope and curve output generators can switch their output
+    /// off.
+    fn set_output_enabled(&mut self, _output_enabled: bool) -> Result<(), Error> {
+        Err(unsupported(self.mode(), "switch their output off"))
+    }
+
+    /// Only the envelope and curve output generators have a destination.
+    fn set_output_destination(&mut self, _destination: OutputDestination) -> Result<(), Error> {
+        Err(unsupported(self.mode(), "have an output destination"))
+    }
+
+    /// Set the output gain of the output generators or the level of the
+    /// generators that make sound. Effects and groups don't have a level.
+    fn set_level_db(&mut self, _level: Decibels) -> Result<(), Error> {
+        Err(unsupported(self.mode(), "have a level"))
+    }
+
+    /// The output gain of the output generators or the level of the
+    /// generators that make sound.
+    fn level_db(&self) -> Option<Decibels> {
+        None
+    }
+
+    /// Only the envelope and curve output generators can be panned.
+    fn pan(&self) -> Option<Ratio> {
+        None
+    }
+
+    /// Fails if the generator can't be panned or the pan is outside of -100%
+    /// to 100%.
+    fn set_pan(&mut self, _pan: Ratio) -> Result<(), Error> {
+        Err(unsupported(self.mode(), "be panned"))
+    }
+}
+
+fn unsupported(mode: GeneratorMode, what: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("{} generators can't {what}", mode.name()),
+    )
+}
+
+/// Levels are stored as a linear amplitude.
+fn level_from_decibels(level: Decibels) -> Ratio {
+    Ratio::new::<percent>(level.linear() * 100.0)
+}
+
+fn decibels_from_level(level: Ratio) -> Decibels {
+    Decibels::from_linear(level.get::<percent>() / 100.0)
+}
+
+fn check_pan(pan: Ratio) -> Result<Ratio, Error> {
+    if (-100.0..=100.0).contains(&pan.get::<percent>()) {
+        Ok(pan)
+    } else {
+        Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Pan of {}% is outside of -100% to 100%",
+                pan.get::<percent>()
+            ),
+        ))
+    }
 }
 
 impl_downcast!(Generator);
 
+/// Clone a boxed generator without knowing its concrete type.
+pub trait GeneratorClone {
+    fn clone_box(&self) -> Box<dyn Generator>;
+}
+
+impl<T: Generator + Clone> GeneratorClone for T {
+    fn clone_box(&self) -> Box<dyn Generator> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Generator> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
 impl PartialEq for Box<dyn Generator> {
     fn eq(&self, other: &Box<dyn Generator>) -> bool {
-        self.box_eq(other.as_any())
+        // Dereference so the concrete value is compared rather than the box.
+        self.box_eq((**other).as_any())
     }
 }
 
 #[cfg(test)]
 mod test {
+    use approx::assert_relative_eq;
+
     use crate::effect::{DistortionMode, FilterMode};
-    use crate::test::read_preset;
+    use crate::test::{read_generator_preset, read_preset};
 
     use super::*;
 
+    #[test]
+    fn mode_from_str() {
+        for mode in GeneratorMode::iter() {
+            assert_eq!(mode.name().parse::<GeneratorMode>().unwrap(), mode);
+            assert_eq!(mode.to_string().parse::<GeneratorMode>().unwrap(), mode);
+        }
+        assert_eq!(
+            "sampler".parse::<GeneratorMode>().unwrap(),
+            GeneratorMode::SamplePlayer
+        );
+        let error = "Wobble".parse::<GeneratorMode>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+
     /// Preset with every generator in one group. At least one property of each generator is tested.
     #[test]
     fn all_version_1() {
@@ -261,4 +520,137 @@ mod test {
         let output: &EnvelopeOutput = preset.generator(9).unwrap();
         assert_eq!(output.destination, OutputDestination::Lane1);
     }
+
+    #[test]
+    fn output_enabled() {
+        let mut preset = read_generator_preset(
+            "envelope_output",
+            "envelope_output-out_disabled-2.1.0.phaseplant",
+        )
+        .unwrap();
+        assert_eq!(preset.generators[0].output_enabled(), None);
+        assert!(preset.generators[0].set_output_enabled(false).is_err());
+        assert_eq!(preset.generators[1].output_enabled(), Some(false));
+
+        preset.generators[1].set_output_enabled(true).unwrap();
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        preset.write(&mut cursor).unwrap();
+        cursor.set_position(0);
+        let read = Preset::read(&mut cursor, None).unwrap();
+        assert_eq!(read.generators[1].output_enabled(), Some(true));
+        assert_eq!(CurveOutput::default().output_enabled(), Some(true));
+    }
+
+    #[test]
+    fn visit_generators() {
+        let mut preset = read_preset("generators", "generators-all-1.8.13.phaseplant");
+        preset.visit_generators_mut(|generator| {
+            if let Some(analog) = generator.downcast_mut::<AnalogOscillator>() {
+                analog.unison.detune_cents = analog.unison.detune_cents.min(10.0);
+            }
+        });
+        let analog: &AnalogOscillator = preset.generator(1).unwrap();
+        assert!(analog.unison.detune_cents <= 10.0);
+    }
+
+    #[test]
+    fn setters() {
+        let mut preset = read_preset("generators", "generators-all-1.8.13.phaseplant");
+        for generator in &mut preset.generators {
+            generator.set_enabled(false).unwrap();
+            generator.set_name("Renamed").unwrap();
+        }
+        assert!(preset.generators.iter().all(|gen| !gen.is_enabled()));
+        assert!(preset.generators.iter().all(|gen| gen.name() == "Renamed"));
+
+        let analog = &mut preset.generators[1];
+        analog.set_level_db(Decibels::new(-6.0)).unwrap();
+        let level = analog.as_analog().unwrap().level.get::<percent>();
+        assert_relative_eq!(level, 50.1, epsilon = 0.1);
+        assert_eq!(
+            analog
+                .set_output_destination(OutputDestination::Master)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+
+        let output = &mut preset.generators[9];
+        output
+            .set_output_destination(OutputDestination::Lane3)
+            .unwrap();
+        output.set_level_db(Decibels::new(-12.0)).unwrap();
+        let output = output.as_envelope_output().unwrap();
+        assert_eq!(output.destination, OutputDestination::Lane3);
+        assert_eq!(output.gain, Decibels::new(-12.0));
+
+        assert_relative_eq!(
+            preset.generators[1].level_db().unwrap().db(),
+            -6.0,
+            epsilon = 0.001
+        );
+
+        let output = &mut preset.generators[9];
+        assert_eq!(output.level_db(), Some(Decibels::new(-12.0)));
+        output.set_pan(Ratio::new::<percent>(-25.0)).unwrap();
+        assert_relative_eq!(output.pan().unwrap().get::<percent>(), -25.0);
+        let error = output.set_pan(Ratio::new::<percent>(150.0)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+        let group = &mut preset.generators[0];
+        assert!(group.set_level_db(Decibels::ZERO).is_err());
+        assert_eq!(group.level_db(), None);
+        assert!(group.set_pan(Ratio::zero()).is_err());
+
+        let mut blank = BlankGenerator::default();
+        assert!(blank.set_enabled(true).is_err());
+        let error = blank.set_name("Blank").unwrap_err();
+        assert_eq!(error.to_string(), "Blank generators can't be renamed");
+    }
+
+    #[test]
+    fn destinations() {
+        for destination in OutputDestination::iter() {
+            assert_eq!(
+                OutputDestination::from_id(destination.id()).unwrap(),
+                destination
+            );
+            match LaneDestination::try_from(destination) {
+                Ok(lane_destination) => {
+                    assert_eq!(OutputDestination::from(lane_destination), destination);
+                    assert_eq!(lane_destination.to_string(), destination.to_string());
+                    assert_eq!(lane_destination.lane_index(), destination.lane_index());
+                }
+                Err(error) => {
+                    assert_eq!(destination, OutputDestination::None);
+                    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+                }
+            }
+        }
+        for destination in LaneDestination::iter() {
+            assert_eq!(
+                LaneDestination::from_id(destination.id()).unwrap(),
+                destination
+            );
+        }
+        assert!(LaneDestination::from_id(4).is_err());
+        assert!(OutputDestination::from_id(6).is_err());
+
+        let preset = read_generator_preset(
+            "envelope_output",
+            "envelope_output-gain-20-pan50-sideband-1.8.16.phaseplant",
+        )
+        .unwrap();
+        let output: &EnvelopeOutput = preset.generator(1).unwrap();
+        assert_eq!(output.destination, OutputDestination::Sideband);
+        assert_eq!(output.destination.id(), 5);
+        assert_eq!(
+            preset
+                .lanes
+                .iter()
+                .map(|lane| lane.destination.id())
+                .collect::<Vec<_>>(),
+            [2, 0, 1]
+        );
+    }
 }
@@ -3,12 +3,15 @@
 
 use std::any::Any;
 
-use strum_macros::Display;
+use strum_macros::{Display, EnumIter};
 use uom::si::f32::Frequency;
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
-#[derive(Copy, Clone, Debug, Display, Eq, FromRepr, PartialEq)]
+/// Every waveform available in Phase Plant 2.1.
+#[derive(Copy, Clone, Debug, Display, EnumIter, Eq, FromRepr, PartialEq)]
 #[repr(u32)]
 pub enum NoiseWaveform {
     // The discriminants correspond to the file format.
@@ -17,6 +20,8 @@ pub enum NoiseWaveform {
     KeytrackedSmooth = 2,
 }
 
+impl_parameters!(NoiseWaveform);
+
 impl NoiseWaveform {
     pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
         Self::from_repr(id).ok_or_else(|| {
@@ -26,6 +31,28 @@ impl NoiseWaveform {
             )
         })
     }
+
+    /// Name displayed by Phase Plant.
+    pub fn name(&self) -> &'static str {
+        match self {
+            NoiseWaveform::Colored => "Colored",
+            NoiseWaveform::KeytrackedStepped => "Keytracked Stepped",
+            NoiseWaveform::KeytrackedSmooth => "Keytracked Smooth",
+        }
+    }
+
+    /// The character of the noise.
+    pub fn harmonics(&self) -> &'static str {
+        match self {
+            NoiseWaveform::Colored => "No pitch, the spectrum is tilted by the slope",
+            NoiseWaveform::KeytrackedStepped => {
+                "Random steps at the note frequency, pitched with bright harmonics"
+            }
+            NoiseWaveform::KeytrackedSmooth => {
+                "Random values smoothed at the note frequency, pitched with soft harmonics"
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -67,6 +94,41 @@ impl Default for NoiseGenerator {
     }
 }
 
+impl Interpolate for NoiseGenerator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            id: step(&self.id, &other.id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            name: step(&self.name, &other.name, t),
+            semi_cent: self.semi_cent.interpolate(&other.semi_cent, t),
+            harmonic: self.harmonic.interpolate(&other.harmonic, t),
+            shift: self.shift.interpolate(&other.shift, t),
+            phase_offset: self.phase_offset.interpolate(&other.phase_offset, t),
+            phase_jitter: self.phase_jitter.interpolate(&other.phase_jitter, t),
+            level: self.level.interpolate(&other.level, t),
+            waveform: step(&self.waveform, &other.waveform, t),
+            slope: self.slope.interpolate(&other.slope, t),
+            stereo: self.stereo.interpolate(&other.stereo, t),
+            seed_mode: step(&self.seed_mode, &other.seed_mode, t),
+        }
+    }
+}
+
+impl_parameters!(NoiseGenerator {
+    enabled,
+    name,
+    semi_cent: Number(Semitones),
+    harmonic,
+    shift,
+    phase_offset,
+    phase_jitter,
+    level,
+    waveform,
+    slope,
+    stereo,
+    seed_mode,
+});
+
 impl From<&GeneratorBlock> for NoiseGenerator {
     fn from(block: &GeneratorBlock) -> Self {
         NoiseGenerator {
@@ -113,6 +175,25 @@ impl Generator for NoiseGenerator {
     fn name(&self) -> String {
         self.name.to_owned()
     }
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+        self.enabled = enabled;
+        Ok(())
+    }
+
+    fn set_name(&mut self, name: &str) -> Result<(), Error> {
+        self.name = name.to_owned();
+        Ok(())
+    }
+
+    fn set_level_db(&mut self, level: Decibels) -> Result<(), Error> {
+        self.level = level_from_decibels(level);
+        Ok(())
+    }
+
+    fn level_db(&self) -> Option<Decibels> {
+        Some(decibels_from_level(self.level))
+    }
 }
 
 impl dyn Generator {
@@ -127,6 +208,8 @@ mod test {
     use approx::assert_relative_eq;
     use uom::si::f32::Frequency;
 
+    use strum::IntoEnumIterator;
+
     use crate::test::read_generator_preset;
 
     use super::*;
@@ -219,4 +302,15 @@ mod test {
         let generator: &NoiseGenerator = preset.generator(1).unwrap();
         assert_eq!(generator.semi_cent, 23.0);
     }
+
+    #[test]
+    fn waveforms() {
+        assert_eq!(NoiseWaveform::iter().count(), 3);
+        for waveform in NoiseWaveform::iter() {
+            assert_eq!(NoiseWaveform::from_id(waveform as u32).unwrap(), waveform);
+            assert!(!waveform.name().is_empty());
+            assert!(!waveform.harmonics().is_empty());
+        }
+        assert_eq!(NoiseWaveform::KeytrackedSmooth.name(), "Keytracked Smooth");
+    }
 }
@@ -6,6 +6,8 @@
 use std::any::Any;
 
 use crate::effect::NonlinearFilter;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
 use super::*;
 
@@ -26,6 +28,23 @@ impl Default for NonlinearFilterGenerator {
     }
 }
 
+impl Interpolate for NonlinearFilterGenerator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            id: step(&self.id, &other.id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            name: step(&self.name, &other.name, t),
+            effect: self.effect.interpolate(&other.effect, t),
+        }
+    }
+}
+
+impl_parameters!(NonlinearFilterGenerator {
+    enabled,
+    name,
+    effect,
+});
+
 impl From<&GeneratorBlock> for NonlinearFilterGenerator {
     fn from(block: &GeneratorBlock) -> Self {
         NonlinearFilterGenerator {
@@ -63,6 +82,16 @@ impl Generator for NonlinearFilterGenerator {
     fn name(&self) -> String {
         self.name.to_owned()
     }
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+        self.enabled = enabled;
+        Ok(())
+    }
+
+    fn set_name(&mut self, name: &str) -> Result<(), Error> {
+        self.name = name.to_owned();
+        Ok(())
+    }
 }
 
 impl dyn Generator {
@@ -0,0 +1,164 @@
+//! The pitch of the generators that play notes.
+//!
+//! Each generator stores its tuning as semitones with the cents as the
+//! fraction, a harmonic that multiplies the frequency and a shift in hertz
+//! that is added to it. The fields have different names depending on the
+//! generator. [`PitchSettings`] gathers them together.
+
+use uom::si::f32::Frequency;
+use uom::si::frequency::hertz;
+
+use super::*;
+
+/// Frequency of A4, MIDI note 69.
+const A4_FREQUENCY: f32 = 440.0;
+const A4_NOTE: u8 = 69;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PitchSettings {
+    /// Whole semitones of tuning.
+    pub semitones: i32,
+
+    /// Fine tuning in hundredths of a semitone, from -99 to 99. Has the same
+    /// sign as the semitones.
+    pub cents: f32,
+
+    /// Multiplies the frequency of the note.
+    pub harmonic: f32,
+
+    /// Added to the frequency after the harmonic.
+    pub shift: Frequency,
+}
+
+impl PitchSettings {
+    /// Split a tuning in semitones, with the cents as the fraction.
+    pub fn new(tuning: f32, harmonic: f32, shift: Frequency) -> Self {
+        Self {
+            semitones: tuning.trunc() as i32,
+            cents: tuning.fract() * 100.0,
+            harmonic,
+            shift,
+        }
+    }
+
+    /// Semitones with the cents as the fraction, as stored in the preset.
+    pub fn tuning(&self) -> f32 {
+        self.semitones as f32 + self.cents / 100.0
+    }
+
+    /// The frequency produced when a MIDI note is played, with A4 (note 69)
+    /// tuned to 440 Hz. Sample based generators play their root note at
+    /// this frequency.
+    pub fn frequency(&self, note: u8) -> Frequency {
+        let semitones = note as f32 - A4_NOTE as f32 + self.tuning();
+        let frequency = A4_FREQUENCY * (semitones / 12.0).exp2() * self.harmonic;
+        Frequency::new::<hertz>(frequency) + self.shift
+    }
+}
+
+impl Default for PitchSettings {
+    fn default() -> Self {
+        Self::new(0.0, 1.0, Frequency::zero())
+    }
+}
+
+macro_rules! impl_pitch {
+    ($generator:ty, $tuning:ident) => {
+        impl $generator {
+            pub fn pitch(&self) -> PitchSettings {
+                PitchSettings::new(self.$tuning, self.harmonic, self.shift)
+            }
+
+            pub fn set_pitch(&mut self, pitch: &PitchSettings) {
+                self.$tuning = pitch.tuning();
+                self.harmonic = pitch.harmonic;
+                self.shift = pitch.shift;
+            }
+        }
+    };
+}
+
+impl_pitch!(AnalogOscillator, tuning);
+impl_pitch!(GranularGenerator, fine_tuning);
+impl_pitch!(NoiseGenerator, semi_cent);
+impl_pitch!(SamplePlayer, semi_cent);
+impl_pitch!(WavetableOscillator, tuning);
+
+impl dyn Generator {
+    /// The pitch of generators that play notes.
+    #[must_use]
+    pub fn pitch(&self) -> Option<PitchSettings> {
+        if let Some(generator) = self.as_analog() {
+            Some(generator.pitch())
+        } else if let Some(generator) = self.as_granular() {
+            Some(generator.pitch())
+        } else if let Some(generator) = self.downcast_ref::<NoiseGenerator>() {
+            Some(generator.pitch())
+        } else if let Some(generator) = self.as_sampler() {
+            Some(generator.pitch())
+        } else {
+            self.as_wavetable().map(|generator| generator.pitch())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::test::read_generator_preset;
+
+    use super::*;
+
+    #[test]
+    fn tuning() {
+        let pitch = PitchSettings::new(11.5, 1.0, Frequency::zero());
+        assert_eq!(pitch.semitones, 11);
+        assert_relative_eq!(pitch.cents, 50.0);
+        assert_relative_eq!(pitch.tuning(), 11.5);
+
+        let pitch = PitchSettings::new(-1.25, 1.0, Frequency::zero());
+        assert_eq!(pitch.semitones, -1);
+        assert_relative_eq!(pitch.cents, -25.0);
+        assert_relative_eq!(pitch.tuning(), -1.25);
+    }
+
+    #[test]
+    fn frequency() {
+        let pitch = PitchSettings::default();
+        assert_relative_eq!(pitch.frequency(69).get::<hertz>(), 440.0);
+        assert_relative_eq!(pitch.frequency(57).get::<hertz>(), 220.0);
+        assert_relative_eq!(
+            pitch.frequency(60).get::<hertz>(),
+            261.6256,
+            epsilon = 0.001
+        );
+
+        let pitch = PitchSettings {
+            semitones: 12,
+            harmonic: 3.0,
+            shift: Frequency::new::<hertz>(-99.0),
+            ..Default::default()
+        };
+        assert_relative_eq!(pitch.frequency(69).get::<hertz>(), 2541.0, epsilon = 0.01);
+    }
+
+    #[test]
+    fn generators() {
+        let preset = read_generator_preset(
+            "analog_oscillator",
+            "analog_oscillator-level90%-semi11.5-harmonic3-1.8.13.phaseplant",
+        )
+        .unwrap();
+        let pitch = preset.generators[1].pitch().unwrap();
+        assert_eq!(pitch.semitones, 11);
+        assert_relative_eq!(pitch.cents, 50.0);
+        assert_eq!(pitch.harmonic, 3.0);
+        assert!(preset.generators[0].pitch().is_none());
+
+        let mut generator = SamplePlayer::default();
+        generator.set_pitch(&pitch);
+        assert_eq!(generator.semi_cent, 11.5);
+        assert_eq!(generator.pitch(), pitch);
+    }
+}
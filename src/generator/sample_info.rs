@@ -0,0 +1,104 @@
+//! Length and sample rate of an embedded sample.
+//!
+//! Positions in a sample, such as where a Sampler starts playing, are stored
+//! as a fraction of its length. Converting them to seconds or sample frames
+//! needs the length and sample rate from the sample itself.
+
+use std::io::{Error, ErrorKind};
+
+use uom::si::f32::{Ratio, Time};
+use uom::si::ratio::ratio;
+use uom::si::time::second;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SampleInfo {
+    /// Frames per second.
+    pub sample_rate: u32,
+
+    pub channels: u8,
+    pub bits_per_sample: u8,
+
+    /// Number of samples in each channel.
+    pub frames: u64,
+}
+
+impl SampleInfo {
+    /// Read the stream information at the start of a FLAC file. Embedded
+    /// samples and wavetables are always stored as FLAC.
+    pub fn from_flac(contents: &[u8]) -> Result<Self, Error> {
+        let invalid = |problem: &str| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Sample is not a FLAC file, {problem}"),
+            )
+        };
+        if contents.get(..4) != Some(b"fLaC") {
+            return Err(invalid("the signature is missing"));
+        }
+
+        // The stream information is always the first metadata block.
+        if contents.get(4).map(|header| header & 0x7f) != Some(0) {
+            return Err(invalid("the stream information is missing"));
+        }
+        let packed = contents
+            .get(18..26)
+            .ok_or_else(|| invalid("the stream information is truncated"))?;
+        let packed = u64::from_be_bytes(packed.try_into().expect("eight bytes"));
+        let info = Self {
+            sample_rate: (packed >> 44) as u32,
+            channels: ((packed >> 41) & 0x07) as u8 + 1,
+            bits_per_sample: ((packed >> 36) & 0x1f) as u8 + 1,
+            frames: packed & 0x0f_ffff_ffff,
+        };
+        if info.sample_rate == 0 || info.frames == 0 {
+            return Err(invalid("the length is not known"));
+        }
+        Ok(info)
+    }
+
+    pub fn duration(&self) -> Time {
+        Time::new::<second>(self.frames as f32 / self.sample_rate as f32)
+    }
+
+    /// The frame at a fraction of the length, rounded to the nearest frame.
+    pub fn frames_at(&self, position: Ratio) -> u64 {
+        (position.get::<ratio>().clamp(0.0, 1.0) as f64 * self.frames as f64).round() as u64
+    }
+
+    pub fn time_at(&self, position: Ratio) -> Time {
+        self.duration() * position.get::<ratio>().clamp(0.0, 1.0)
+    }
+
+    /// The fraction of the length. Fails if the frame is past the end.
+    pub fn position_of_frames(&self, frames: u64) -> Result<Ratio, Error> {
+        if frames > self.frames {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Frame {frames} is past the end of the {} frames",
+                    self.frames
+                ),
+            ));
+        }
+        Ok(Ratio::new::<ratio>(
+            (frames as f64 / self.frames as f64) as f32,
+        ))
+    }
+
+    /// The fraction of the length. Fails if the time is negative or past the
+    /// end.
+    pub fn position_of_time(&self, time: Time) -> Result<Ratio, Error> {
+        let duration = self.duration();
+        if !(0.0..=duration.get::<second>()).contains(&time.get::<second>()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{} seconds is outside of the {} second sample",
+                    time.get::<second>(),
+                    duration.get::<second>()
+                ),
+            ));
+        }
+        Ok(time / duration)
+    }
+}
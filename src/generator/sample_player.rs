@@ -6,9 +6,11 @@
 use std::any::Any;
 
 use log::trace;
-use uom::si::f32::Frequency;
+use uom::si::f32::{Frequency, Time};
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
 #[doc(alias = "Sampler")]
 #[derive(Clone, Debug, PartialEq)]
@@ -40,7 +42,9 @@ pub struct SamplePlayer {
     pub loop_mode: LoopMode,
     pub crossfade_amount: Ratio,
 
-    /// A file containing the samples to play back. Usually in a format like FLAC, MP3 or WAV.
+    /// The embedded sample to play back, empty if it isn't embedded. Phase
+    /// Plant converts the sample to FLAC when embedding it, whatever the
+    /// format of the original file in [`sample_path`](Self::sample_path).
     pub sample_contents: Vec<u8>,
 
     pub sample_name: Option<String>,
@@ -51,6 +55,91 @@ pub struct SamplePlayer {
     pub base_pitch_locked: bool,
 }
 
+/// A position or length in the sample that is stored as a fraction of the
+/// length of the sample.
+#[derive(Copy, Clone, Debug, EnumIter, Eq, PartialEq)]
+pub enum SampleMarker {
+    Offset,
+    LoopStart,
+    LoopLength,
+
+    /// Assumed to be a fraction of the sample like the loop.
+    Crossfade,
+}
+
+impl SamplePlayer {
+    /// How the sample is stored when the preset is written, `None` if there
+    /// is no sample.
+    pub fn sample_data_mode(&self) -> Option<SampleDataMode> {
+        SampleDataMode::for_sample(
+            GeneratorMode::SamplePlayer,
+            &self.sample_path,
+            &self.sample_contents,
+        )
+    }
+
+    /// Length and sample rate of the embedded sample. Fails if the sample
+    /// isn't embedded. The information of samples that aren't embedded can
+    /// be read from the sample file with [`SampleInfo::from_flac`].
+    pub fn sample_info(&self) -> Result<SampleInfo, Error> {
+        if self.sample_contents.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The sample is not embedded",
+            ));
+        }
+        SampleInfo::from_flac(&self.sample_contents)
+    }
+
+    pub fn marker(&self, marker: SampleMarker) -> Ratio {
+        match marker {
+            SampleMarker::Offset => self.offset_position,
+            SampleMarker::LoopStart => self.loop_start_position,
+            SampleMarker::LoopLength => self.loop_length,
+            SampleMarker::Crossfade => self.crossfade_amount,
+        }
+    }
+
+    fn marker_mut(&mut self, marker: SampleMarker) -> &mut Ratio {
+        match marker {
+            SampleMarker::Offset => &mut self.offset_position,
+            SampleMarker::LoopStart => &mut self.loop_start_position,
+            SampleMarker::LoopLength => &mut self.loop_length,
+            SampleMarker::Crossfade => &mut self.crossfade_amount,
+        }
+    }
+
+    pub fn marker_frames(&self, marker: SampleMarker, info: &SampleInfo) -> u64 {
+        info.frames_at(self.marker(marker))
+    }
+
+    pub fn marker_time(&self, marker: SampleMarker, info: &SampleInfo) -> Time {
+        info.time_at(self.marker(marker))
+    }
+
+    /// Fails if the frame is past the end of the sample.
+    pub fn set_marker_frames(
+        &mut self,
+        marker: SampleMarker,
+        info: &SampleInfo,
+        frames: u64,
+    ) -> Result<(), Error> {
+        *self.marker_mut(marker) = info.position_of_frames(frames)?;
+        Ok(())
+    }
+
+    /// Fails if the time is outside of the sample.
+    pub fn set_marker_time(
+        &mut self,
+        marker: SampleMarker,
+        info: &SampleInfo,
+        time: Time,
+    ) -> Result<(), Error> {
+        *self.marker_mut(marker) = info.position_of_time(time)?;
+        Ok(())
+    }
+}
+
 impl Default for SamplePlayer {
     fn default() -> Self {
         Self::from(&GeneratorBlock {
@@ -63,6 +152,65 @@ impl Default for SamplePlayer {
     }
 }
 
+impl Interpolate for SamplePlayer {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            id: step(&self.id, &other.id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            name: step(&self.name, &other.name, t),
+            semi_cent: self.semi_cent.interpolate(&other.semi_cent, t),
+            harmonic: self.harmonic.interpolate(&other.harmonic, t),
+            shift: self.shift.interpolate(&other.shift, t),
+            phase_offset: self.phase_offset.interpolate(&other.phase_offset, t),
+            phase_jitter: self.phase_jitter.interpolate(&other.phase_jitter, t),
+            level: self.level.interpolate(&other.level, t),
+            unison: self.unison.interpolate(&other.unison, t),
+            offset_position: self.offset_position.interpolate(&other.offset_position, t),
+            offset_locked: step(&self.offset_locked, &other.offset_locked, t),
+            loop_start_position: self
+                .loop_start_position
+                .interpolate(&other.loop_start_position, t),
+            loop_locked: step(&self.loop_locked, &other.loop_locked, t),
+            loop_length: self.loop_length.interpolate(&other.loop_length, t),
+            loop_enabled: step(&self.loop_enabled, &other.loop_enabled, t),
+            loop_mode: step(&self.loop_mode, &other.loop_mode, t),
+            crossfade_amount: self
+                .crossfade_amount
+                .interpolate(&other.crossfade_amount, t),
+            sample_contents: step(&self.sample_contents, &other.sample_contents, t),
+            sample_name: step(&self.sample_name, &other.sample_name, t),
+            sample_path: step(&self.sample_path, &other.sample_path, t),
+            base_pitch: self.base_pitch.interpolate(&other.base_pitch, t),
+            base_pitch_locked: step(&self.base_pitch_locked, &other.base_pitch_locked, t),
+        }
+    }
+}
+
+impl_parameters!(SamplePlayer {
+    enabled,
+    name,
+    semi_cent: Number(Semitones),
+    harmonic,
+    shift,
+    phase_offset,
+    phase_jitter,
+    level,
+    unison,
+    offset_position,
+    offset_locked,
+    loop_start_position,
+    loop_locked,
+    loop_length,
+    loop_enabled,
+    loop_mode,
+    crossfade_amount,
+    sample_contents: Data,
+    sample_name,
+    sample_path,
+    base_pitch,
+    base_pitch_locked,
+});
+
 impl From<&GeneratorBlock> for SamplePlayer {
     fn from(block: &GeneratorBlock) -> Self {
         trace!(
@@ -123,6 +271,25 @@ impl Generator for SamplePlayer {
     fn name(&self) -> String {
         self.name.to_owned()
     }
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+        self.enabled = enabled;
+        Ok(())
+    }
+
+    fn set_name(&mut self, name: &str) -> Result<(), Error> {
+        self.name = name.to_owned();
+        Ok(())
+    }
+
+    fn set_level_db(&mut self, level: Decibels) -> Result<(), Error> {
+        self.level = level_from_decibels(level);
+        Ok(())
+    }
+
+    fn level_db(&self) -> Option<Decibels> {
+        Some(decibels_from_level(self.level))
+    }
 }
 
 impl dyn Generator {
@@ -136,9 +303,10 @@ impl dyn Generator {
 mod test {
     use approx::assert_relative_eq;
     use music_note::midi;
-    use uom::si::f32::Frequency;
+    use uom::si::f32::{Frequency, Time};
     use uom::si::frequency::hertz;
     use uom::si::ratio::ratio;
+    use uom::si::time::second;
 
     use crate::test::read_generator_preset;
 
@@ -231,6 +399,64 @@ mod test {
         );
         assert_eq!(generator.sample_contents.len(), 217344);
         assert_eq!(&generator.sample_contents[..4], "fLaC".as_bytes());
+        assert_eq!(
+            generator.sample_data_mode(),
+            Some(SampleDataMode::PathAndContents)
+        );
+        assert_eq!(SamplePlayer::default().sample_data_mode(), None);
+    }
+
+    #[test]
+    fn sample_markers() {
+        let preset = read_generator_preset(
+            "sample_player",
+            "sample_player-custom-sample-1.8.16.phaseplant",
+        )
+        .unwrap();
+        let mut generator: SamplePlayer = preset.generator::<SamplePlayer>(1).unwrap().clone();
+        let info = generator.sample_info().unwrap();
+        assert_eq!(
+            info,
+            SampleInfo {
+                sample_rate: 44100,
+                channels: 1,
+                bits_per_sample: 24,
+                frames: 132300,
+            }
+        );
+        assert_relative_eq!(info.duration().get::<second>(), 3.0);
+        assert_eq!(
+            generator.marker_frames(SampleMarker::LoopStart, &info),
+            66150
+        );
+        assert_relative_eq!(
+            generator
+                .marker_time(SampleMarker::LoopLength, &info)
+                .get::<second>(),
+            0.75
+        );
+
+        generator
+            .set_marker_frames(SampleMarker::Offset, &info, 44100)
+            .unwrap();
+        assert_relative_eq!(generator.offset_position.get::<ratio>(), 1.0 / 3.0);
+        generator
+            .set_marker_time(SampleMarker::Crossfade, &info, Time::new::<second>(0.3))
+            .unwrap();
+        assert_relative_eq!(generator.crossfade_amount.get::<percent>(), 10.0);
+        assert_eq!(
+            generator.marker_frames(SampleMarker::Crossfade, &info),
+            13230
+        );
+
+        for marker in SampleMarker::iter() {
+            assert!(generator.set_marker_frames(marker, &info, 132301).is_err());
+            let past_end = Time::new::<second>(3.1);
+            assert!(generator.set_marker_time(marker, &info, past_end).is_err());
+        }
+        assert!(SamplePlayer::default().sample_info().is_err());
+        let error = SampleInfo::from_flac(b"RIFF").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
     }
 
     #[test]
@@ -7,6 +7,8 @@ use std::any::Any;
 use uom::si::f32::Frequency;
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
 // const SAMPLE_COUNT: usize = 2048;
 // const FRAME_COUNT: usize = 256;
@@ -28,6 +30,8 @@ pub struct WavetableOscillator {
     pub frame: f32,
     pub band_limit: Frequency,
     pub unison: Unison,
+    /// A user wavetable embedded in the preset. Phase Plant stores it as
+    /// FLAC so it is already compressed.
     pub wavetable_contents: Vec<u8>,
     pub wavetable_edited: bool,
     pub wavetable_name: Option<String>,
@@ -43,6 +47,47 @@ impl Default for WavetableOscillator {
     }
 }
 
+impl Interpolate for WavetableOscillator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            id: step(&self.id, &other.id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            name: step(&self.name, &other.name, t),
+            tuning: self.tuning.interpolate(&other.tuning, t),
+            harmonic: self.harmonic.interpolate(&other.harmonic, t),
+            shift: self.shift.interpolate(&other.shift, t),
+            phase_offset: self.phase_offset.interpolate(&other.phase_offset, t),
+            phase_jitter: self.phase_jitter.interpolate(&other.phase_jitter, t),
+            level: self.level.interpolate(&other.level, t),
+            frame: self.frame.interpolate(&other.frame, t),
+            band_limit: self.band_limit.interpolate(&other.band_limit, t),
+            unison: self.unison.interpolate(&other.unison, t),
+            wavetable_contents: step(&self.wavetable_contents, &other.wavetable_contents, t),
+            wavetable_edited: step(&self.wavetable_edited, &other.wavetable_edited, t),
+            wavetable_name: step(&self.wavetable_name, &other.wavetable_name, t),
+            wavetable_path: step(&self.wavetable_path, &other.wavetable_path, t),
+        }
+    }
+}
+
+impl_parameters!(WavetableOscillator {
+    enabled,
+    name,
+    tuning: Number(Semitones),
+    harmonic,
+    shift,
+    phase_offset,
+    phase_jitter,
+    level,
+    frame,
+    band_limit,
+    unison,
+    wavetable_contents: Data,
+    wavetable_edited,
+    wavetable_name,
+    wavetable_path,
+});
+
 impl From<&GeneratorBlock> for WavetableOscillator {
     fn from(block: &GeneratorBlock) -> Self {
         WavetableOscillator {
@@ -66,6 +111,14 @@ impl From<&GeneratorBlock> for WavetableOscillator {
     }
 }
 
+impl WavetableOscillator {
+    /// How the wavetable is stored when the preset is written, `None` if
+    /// there is no wavetable.
+    pub fn wavetable_data_mode(&self) -> Option<WavetableDataMode> {
+        WavetableDataMode::for_wavetable(&self.wavetable_path, &self.wavetable_contents)
+    }
+}
+
 impl Generator for WavetableOscillator {
     fn id(&self) -> Option<GeneratorId> {
         Some(self.id)
@@ -92,6 +145,25 @@ impl Generator for WavetableOscillator {
     fn name(&self) -> String {
         self.name.clone()
     }
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+        self.enabled = enabled;
+        Ok(())
+    }
+
+    fn set_name(&mut self, name: &str) -> Result<(), Error> {
+        self.name = name.to_owned();
+        Ok(())
+    }
+
+    fn set_level_db(&mut self, level: Decibels) -> Result<(), Error> {
+        self.level = level_from_decibels(level);
+        Ok(())
+    }
+
+    fn level_db(&self) -> Option<Decibels> {
+        Some(decibels_from_level(self.level))
+    }
 }
 
 impl dyn Generator {
@@ -107,7 +179,7 @@ mod test {
     use uom::si::f32::Frequency;
     use uom::si::frequency::hertz;
 
-    use crate::test::read_generator_preset;
+    use crate::test::{read_generator_preset, rewrite};
 
     use super::WavetableOscillator;
     use super::*;
@@ -191,6 +263,7 @@ mod test {
             generator.wavetable_path,
             Some("user/BrassEdited.flac".to_owned())
         );
+        assert_eq!(&generator.wavetable_contents[0..4], b"fLaC");
 
         let preset = read_generator_preset(
             "wavetable_oscillator",
@@ -280,4 +353,25 @@ mod test {
         assert_eq!(generator.unison.voices, 2);
         assert_eq!(generator.unison.mode, UnisonMode::Fifths);
     }
+
+    #[test]
+    fn rewrite_wavetables() {
+        for file in &[
+            "wavetable_oscillator-1.7.0.phaseplant",
+            "wavetable_oscillator-1.8.17.phaseplant",
+            "wavetable_oscillator-2.1.0.phaseplant",
+            "wavetable_oscillator-brass-edited-1.8.17.phaseplant",
+            "wavetable_oscillator-newspeak-1.8.17.phaseplant",
+        ] {
+            let preset = read_generator_preset("wavetable_oscillator", file).unwrap();
+            let generator: &WavetableOscillator = preset.generator(1).unwrap();
+            assert_eq!(
+                generator.wavetable_data_mode(),
+                Some(WavetableDataMode::PathWithEdited)
+            );
+            let written = rewrite(&preset).unwrap();
+            assert_eq!(written.generators, preset.generators, "{file}");
+        }
+        assert_eq!(WavetableOscillator::default().wavetable_data_mode(), None);
+    }
 }
@@ -0,0 +1,173 @@
+//! Identifiers for generators, modulators and snapins.
+//!
+//! Modulation routing refers to generators, modulators and snapins by their
+//! identifier rather than their position, so the identifiers of new objects
+//! must not collide with existing ones. Phase Plant gives a new object the
+//! lowest identifier that isn't in use, which is why the identifiers in a
+//! preset are always contiguous even after objects have been reordered. An
+//! [`IdAllocator`] follows the same strategy so presets that are created by
+//! other tools and later edited in Phase Plant get the same identifiers Phase
+//! Plant would have assigned.
+//!
+//! | Object    | First ID                   | Scope  |
+//! |-----------|----------------------------|--------|
+//! | Generator | 0, used by the root group  | Preset |
+//! | Modulator | 0                          | Preset |
+//! | Snapin    | [`Snapin::MIN_POSITION`]   | Lane   |
+
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+
+use crate::generator::GeneratorId;
+use crate::modulator::ModulatorId;
+use crate::*;
+
+/// Hands out the lowest identifier that is not in use.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IdAllocator<T> {
+    first: T,
+    last: T,
+    used: BTreeSet<T>,
+}
+
+impl<T> IdAllocator<T>
+where
+    T: Copy + Debug + Ord + Into<u32> + TryFrom<u32>,
+{
+    /// Allocate identifiers between `first` and `last` inclusive.
+    pub fn new(first: T, last: T) -> Self {
+        Self {
+            first,
+            last,
+            used: BTreeSet::new(),
+        }
+    }
+
+    /// Mark an identifier as used. Returns `false` if it was already used.
+    pub fn reserve(&mut self, id: T) -> bool {
+        self.used.insert(id)
+    }
+
+    /// Make an identifier available again, such as after the object using
+    /// it was removed.
+    pub fn release(&mut self, id: T) -> bool {
+        self.used.remove(&id)
+    }
+
+    pub fn is_used(&self, id: T) -> bool {
+        self.used.contains(&id)
+    }
+
+    /// The identifier [`allocate`](Self::allocate) will return next.
+    pub fn peek(&self) -> Option<T> {
+        (self.first.into()..=self.last.into())
+            .filter_map(|id| T::try_from(id).ok())
+            .find(|id| !self.used.contains(id))
+    }
+
+    /// The lowest unused identifier, or `None` if every identifier is used.
+    pub fn allocate(&mut self) -> Option<T> {
+        let id = self.peek()?;
+        self.used.insert(id);
+        Some(id)
+    }
+}
+
+impl IdAllocator<GeneratorId> {
+    /// Generator identifiers with the identifiers of the generators in the
+    /// preset already used.
+    pub fn for_generators(preset: &Preset) -> Self {
+        let mut allocator = Self::new(0, GENERATORS_MAX - 1);
+        for id in preset
+            .generators
+            .iter()
+            .filter_map(|generator| generator.id())
+        {
+            allocator.reserve(id);
+        }
+        allocator
+    }
+}
+
+impl IdAllocator<SnapinId> {
+    /// Snapin identifiers with the identifiers of the snapins in the lane
+    /// already used. Snapin identifiers are only unique within a lane.
+    pub fn for_snapins(snapins: &[Snapin]) -> Self {
+        let mut allocator = Self::new(Snapin::MIN_POSITION, SnapinId::MAX);
+        for snapin in snapins {
+            allocator.reserve(snapin.id);
+        }
+        allocator
+    }
+}
+
+impl IdAllocator<ModulatorId> {
+    /// Modulator identifiers with the identifiers of the modulators in the
+    /// preset already used.
+    pub fn for_modulators(preset: &Preset) -> Self {
+        let mut allocator = Self::new(0, (MODULATORS_MAX - 1) as ModulatorId);
+        for container in &preset.modulator_containers {
+            allocator.reserve(container.id);
+        }
+        allocator
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::effect::Filter;
+    use crate::test::{read_generator_preset, read_modulator_preset};
+
+    use super::*;
+
+    #[test]
+    fn lowest_unused() {
+        let mut allocator = IdAllocator::<u16>::new(1, 3);
+        assert!(allocator.reserve(2));
+        assert!(!allocator.reserve(2));
+        assert_eq!(allocator.allocate(), Some(1));
+        assert_eq!(allocator.allocate(), Some(3));
+        assert_eq!(allocator.allocate(), None);
+        assert!(allocator.release(2));
+        assert_eq!(allocator.peek(), Some(2));
+        assert_eq!(allocator.allocate(), Some(2));
+        assert!(allocator.is_used(2));
+    }
+
+    #[test]
+    fn generators() {
+        let preset = Preset::default();
+        assert_eq!(IdAllocator::for_generators(&preset).peek(), Some(0));
+
+        // Group, Analog, Envelope
+        let preset =
+            read_generator_preset("analog_oscillator", "analog_oscillator-1.8.13.phaseplant")
+                .unwrap();
+        let mut allocator = IdAllocator::for_generators(&preset);
+        assert_eq!(allocator.allocate(), Some(3));
+        assert_eq!(allocator.allocate(), Some(4));
+    }
+
+    #[test]
+    fn modulators() {
+        let preset = read_modulator_preset("lfo", "lfo-1.8.13.phaseplant").unwrap();
+        assert_eq!(preset.modulator_containers.len(), 1);
+        assert_eq!(IdAllocator::for_modulators(&preset).peek(), Some(1));
+    }
+
+    #[test]
+    fn snapins() {
+        let mut snapins = Vec::new();
+        for _ in 0..3 {
+            let id = IdAllocator::for_snapins(&snapins).allocate().unwrap();
+            snapins.push(Snapin::new(Box::<Filter>::default(), id, true, false));
+        }
+        assert_eq!(
+            snapins.iter().map(|snapin| snapin.id).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+
+        snapins.remove(0);
+        assert_eq!(IdAllocator::for_snapins(&snapins).peek(), Some(1));
+    }
+}
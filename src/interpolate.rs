@@ -0,0 +1,597 @@
+//! Morphing between presets that share the same structure.
+//!
+//! Continuous parameters are linearly interpolated in their own units.
+//! Discrete parameters such as switches, modes, counts and names can't be
+//! blended so they come from the first value until the halfway point and the
+//! second value after it.
+
+use std::io::{Error, ErrorKind};
+
+use uom::si::f32::{Frequency, Ratio, Time};
+
+use crate::effect::*;
+use crate::generator::{self, Generator};
+use crate::modulation::Modulation;
+use crate::modulator::{self, Modulator, ModulatorContainer};
+use crate::*;
+
+/// Blend between two values of the same type.
+pub trait Interpolate: Sized {
+    /// A `t` of zero is `self`, one is `other` and values in between are a
+    /// blend of the two.
+    #[must_use]
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+}
+
+/// Discrete values switch over halfway between the two.
+pub(crate) fn step<T: Clone>(a: &T, b: &T, t: f32) -> T {
+    if t < 0.5 {
+        a.clone()
+    } else {
+        b.clone()
+    }
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self * (1.0 - t) + other * t
+    }
+}
+
+impl Interpolate for Frequency {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        *self * (1.0 - t) + *other * t
+    }
+}
+
+impl Interpolate for Ratio {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        *self * (1.0 - t) + *other * t
+    }
+}
+
+impl Interpolate for Time {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        *self * (1.0 - t) + *other * t
+    }
+}
+
+impl Interpolate for Decibels {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        if self.db().is_finite() && other.db().is_finite() {
+            Decibels::new(self.db().interpolate(&other.db(), t))
+        } else {
+            // Silence can only be blended as a linear amplitude.
+            Decibels::from_linear(self.linear().interpolate(&other.linear(), t))
+        }
+    }
+}
+
+impl Interpolate for Semitones {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Semitones::new(self.semitones().interpolate(&other.semitones(), t))
+    }
+}
+
+impl<T: Clone + Interpolate, const N: usize> Interpolate for [T; N] {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        std::array::from_fn(|index| self[index].interpolate(&other[index], t))
+    }
+}
+
+/// Lists of different lengths, such as curves with a different number of
+/// points, can't be blended and switch over halfway.
+impl<T: Clone + Interpolate> Interpolate for Vec<T> {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        if self.len() != other.len() {
+            return step(self, other, t);
+        }
+        self.iter()
+            .zip(other)
+            .map(|(a, b)| a.interpolate(b, t))
+            .collect()
+    }
+}
+
+impl Interpolate for CurvePoint {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            mode: step(&self.mode, &other.mode, t),
+            x: self.x.interpolate(&other.x, t),
+            y: self.y.interpolate(&other.y, t),
+            curve_x: self.curve_x.interpolate(&other.curve_x, t),
+            curve_y: self.curve_y.interpolate(&other.curve_y, t),
+        }
+    }
+}
+
+impl Interpolate for Envelope {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            delay: self.delay.interpolate(&other.delay, t),
+            attack: self.attack.interpolate(&other.attack, t),
+            attack_curve: self.attack_curve.interpolate(&other.attack_curve, t),
+            hold: self.hold.interpolate(&other.hold, t),
+            decay: self.decay.interpolate(&other.decay, t),
+            decay_falloff: self.decay_falloff.interpolate(&other.decay_falloff, t),
+            sustain: self.sustain.interpolate(&other.sustain, t),
+            release: self.release.interpolate(&other.release, t),
+            release_falloff: self.release_falloff.interpolate(&other.release_falloff, t),
+        }
+    }
+}
+
+impl Interpolate for MacroControl {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            name: step(&self.name, &other.name, t),
+            value: self.value.interpolate(&other.value, t),
+            polarity: step(&self.polarity, &other.polarity, t),
+        }
+    }
+}
+
+impl Interpolate for Rate {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            frequency: self.frequency.interpolate(&other.frequency, t),
+            numerator: step(&self.numerator, &other.numerator, t),
+            denominator: step(&self.denominator, &other.denominator, t),
+            sync: step(&self.sync, &other.sync, t),
+        }
+    }
+}
+
+impl Interpolate for Unison {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            enabled: step(&self.enabled, &other.enabled, t),
+            voices: step(&self.voices, &other.voices, t),
+            mode: step(&self.mode, &other.mode, t),
+            detune_cents: self.detune_cents.interpolate(&other.detune_cents, t),
+            spread: self.spread.interpolate(&other.spread, t),
+            blend: self.blend.interpolate(&other.blend, t),
+            bias: self.bias.interpolate(&other.bias, t),
+        }
+    }
+}
+
+fn interpolate_effect<T: Effect + Interpolate>(
+    a: &dyn Effect,
+    b: &dyn Effect,
+    t: f32,
+) -> Box<dyn Effect> {
+    // The modes have already been compared so both effects are the same type.
+    let a = a.downcast_ref::<T>().expect("effect type");
+    let b = b.downcast_ref::<T>().expect("effect type");
+    Box::new(a.interpolate(b, t))
+}
+
+impl dyn Effect {
+    /// Blend two effects of the same type.
+    pub fn interpolate(&self, other: &dyn Effect, t: f32) -> Result<Box<dyn Effect>, Error> {
+        if self.mode() != other.mode() {
+            let msg = format!(
+                "Cannot interpolate effect {} with {}",
+                self.mode(),
+                other.mode()
+            );
+            return Err(Error::new(ErrorKind::InvalidInput, msg));
+        }
+
+        // Not the greatest fan of the lack of dynamic dispatch here.
+        let effect = match self.mode() {
+            EffectMode::Bitcrush => interpolate_effect::<Bitcrush>(self, other, t),
+            EffectMode::CarveEq => interpolate_effect::<CarveEq>(self, other, t),
+            EffectMode::ChannelMixer => interpolate_effect::<ChannelMixer>(self, other, t),
+            EffectMode::Chorus => interpolate_effect::<Chorus>(self, other, t),
+            EffectMode::CombFilter => interpolate_effect::<CombFilter>(self, other, t),
+            EffectMode::Compressor => interpolate_effect::<Compressor>(self, other, t),
+            EffectMode::Convolver => interpolate_effect::<Convolver>(self, other, t),
+            EffectMode::Delay => interpolate_effect::<Delay>(self, other, t),
+            EffectMode::Disperser => interpolate_effect::<Disperser>(self, other, t),
+            EffectMode::Distortion => interpolate_effect::<Distortion>(self, other, t),
+            EffectMode::DualDelay => interpolate_effect::<DualDelay>(self, other, t),
+            EffectMode::Dynamics => interpolate_effect::<Dynamics>(self, other, t),
+            EffectMode::Ensemble => interpolate_effect::<Ensemble>(self, other, t),
+            EffectMode::Faturator => interpolate_effect::<Faturator>(self, other, t),
+            EffectMode::Filter => interpolate_effect::<Filter>(self, other, t),
+            EffectMode::Flanger => interpolate_effect::<Flanger>(self, other, t),
+            EffectMode::FormantFilter => interpolate_effect::<FormantFilter>(self, other, t),
+            EffectMode::FrequencyShifter => interpolate_effect::<FrequencyShifter>(self, other, t),
+            EffectMode::Gain => interpolate_effect::<Gain>(self, other, t),
+            EffectMode::Gate => interpolate_effect::<Gate>(self, other, t),
+            EffectMode::Group => interpolate_effect::<Group>(self, other, t),
+            EffectMode::Haas => interpolate_effect::<Haas>(self, other, t),
+            EffectMode::LadderFilter => interpolate_effect::<LadderFilter>(self, other, t),
+            EffectMode::Limiter => interpolate_effect::<Limiter>(self, other, t),
+            EffectMode::Multipass => {
+                let a = self.as_multipass().expect("multipass");
+                let b = other.as_multipass().expect("multipass");
+                Box::new(a.interpolate(b, t)?)
+            }
+            EffectMode::NonlinearFilter => interpolate_effect::<NonlinearFilter>(self, other, t),
+            EffectMode::PhaseDistortion => interpolate_effect::<PhaseDistortion>(self, other, t),
+            EffectMode::Phaser => interpolate_effect::<Phaser>(self, other, t),
+            EffectMode::PitchShifter => interpolate_effect::<PitchShifter>(self, other, t),
+            EffectMode::Resonator => interpolate_effect::<Resonator>(self, other, t),
+            EffectMode::Reverb => interpolate_effect::<Reverb>(self, other, t),
+            EffectMode::Reverser => interpolate_effect::<Reverser>(self, other, t),
+            EffectMode::RingMod => interpolate_effect::<RingMod>(self, other, t),
+            EffectMode::SliceEq => interpolate_effect::<SliceEq>(self, other, t),
+            EffectMode::SnapHeap => interpolate_effect::<SnapHeap>(self, other, t),
+            EffectMode::Stereo => interpolate_effect::<Stereo>(self, other, t),
+            EffectMode::TapeStop => interpolate_effect::<TapeStop>(self, other, t),
+            EffectMode::ThreeBandEq => interpolate_effect::<ThreeBandEq>(self, other, t),
+            EffectMode::TranceGate => interpolate_effect::<TranceGate>(self, other, t),
+            EffectMode::TransientShaper => interpolate_effect::<TransientShaper>(self, other, t),
+        };
+        Ok(effect)
+    }
+}
+
+impl Snapin {
+    /// Blend two snapins that host the same type of effect.
+    pub fn interpolate(&self, other: &Snapin, t: f32) -> Result<Snapin, Error> {
+        Ok(Snapin {
+            id: step(&self.id, &other.id, t),
+            name: step(&self.name, &other.name, t),
+            metadata: step(&self.metadata, &other.metadata, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            minimized: step(&self.minimized, &other.minimized, t),
+            group_id: step(&self.group_id, &other.group_id, t),
+            preset_name: step(&self.preset_name, &other.preset_name, t),
+            preset_path: step(&self.preset_path, &other.preset_path, t),
+            preset_edited: step(&self.preset_edited, &other.preset_edited, t),
+            host_version: step(&self.host_version, &other.host_version, t),
+            effect_version: step(&self.effect_version, &other.effect_version, t),
+            effect: self.effect.interpolate(other.effect.as_ref(), t)?,
+        })
+    }
+}
+
+/// Blend two lists of snapins that host the same types of effects in the
+/// same order.
+pub(crate) fn interpolate_snapins(
+    a: &[Snapin],
+    b: &[Snapin],
+    t: f32,
+) -> Result<Vec<Snapin>, Error> {
+    if a.len() != b.len() {
+        let msg = format!("Cannot interpolate {} snapins with {}", a.len(), b.len());
+        return Err(Error::new(ErrorKind::InvalidInput, msg));
+    }
+    a.iter().zip(b).map(|(a, b)| a.interpolate(b, t)).collect()
+}
+
+impl Lane {
+    /// Blend two lanes that have the same types of effects in the same order.
+    pub fn interpolate(&self, other: &Lane, t: f32) -> Result<Lane, Error> {
+        Ok(Lane {
+            enabled: step(&self.enabled, &other.enabled, t),
+            snapins: interpolate_snapins(&self.snapins, &other.snapins, t)?,
+            destination: step(&self.destination, &other.destination, t),
+            poly_count: step(&self.poly_count, &other.poly_count, t),
+            mute: step(&self.mute, &other.mute, t),
+            solo: step(&self.solo, &other.solo, t),
+            gain: self.gain.interpolate(&other.gain, t),
+            mix: self.mix.interpolate(&other.mix, t),
+        })
+    }
+}
+
+fn interpolate_generator<T: Generator + Interpolate>(
+    a: &dyn Generator,
+    b: &dyn Generator,
+    t: f32,
+) -> Box<dyn Generator> {
+    // The modes have already been compared so both generators are the same type.
+    let a = a.downcast_ref::<T>().expect("generator type");
+    let b = b.downcast_ref::<T>().expect("generator type");
+    Box::new(a.interpolate(b, t))
+}
+
+impl dyn Generator {
+    /// Blend two generators of the same type.
+    pub fn interpolate(&self, other: &dyn Generator, t: f32) -> Result<Box<dyn Generator>, Error> {
+        if self.mode() != other.mode() {
+            let msg = format!(
+                "Cannot interpolate generator {} with {}",
+                self.mode(),
+                other.mode()
+            );
+            return Err(Error::new(ErrorKind::InvalidInput, msg));
+        }
+
+        use generator::*;
+        let generator = match self.mode() {
+            GeneratorMode::AnalogOscillator => {
+                interpolate_generator::<AnalogOscillator>(self, other, t)
+            }
+            GeneratorMode::AuxRouting => interpolate_generator::<AuxRouting>(self, other, t),
+            GeneratorMode::Blank => interpolate_generator::<BlankGenerator>(self, other, t),
+            GeneratorMode::CurveOutput => interpolate_generator::<CurveOutput>(self, other, t),
+            GeneratorMode::DistortionEffect => {
+                interpolate_generator::<DistortionEffect>(self, other, t)
+            }
+            GeneratorMode::EnvelopeOutput => {
+                interpolate_generator::<EnvelopeOutput>(self, other, t)
+            }
+            GeneratorMode::FilterEffect => interpolate_generator::<FilterEffect>(self, other, t),
+            GeneratorMode::GranularGenerator => {
+                interpolate_generator::<GranularGenerator>(self, other, t)
+            }
+            GeneratorMode::Group => interpolate_generator::<generator::Group>(self, other, t),
+            GeneratorMode::MixRouting => interpolate_generator::<MixRouting>(self, other, t),
+            GeneratorMode::NoiseGenerator => {
+                interpolate_generator::<NoiseGenerator>(self, other, t)
+            }
+            GeneratorMode::NonlinearFilterGenerator => {
+                interpolate_generator::<NonlinearFilterGenerator>(self, other, t)
+            }
+            GeneratorMode::SamplePlayer => interpolate_generator::<SamplePlayer>(self, other, t),
+            GeneratorMode::WavetableOscillator => {
+                interpolate_generator::<WavetableOscillator>(self, other, t)
+            }
+        };
+        Ok(generator)
+    }
+}
+
+fn interpolate_modulator<T: Modulator + Interpolate>(
+    a: &dyn Modulator,
+    b: &dyn Modulator,
+    t: f32,
+) -> Box<dyn Modulator> {
+    // The modes have already been compared so both modulators are the same type.
+    let a = a.downcast_ref::<T>().expect("modulator type");
+    let b = b.downcast_ref::<T>().expect("modulator type");
+    Box::new(a.interpolate(b, t))
+}
+
+impl dyn Modulator {
+    /// Blend two modulators of the same type.
+    pub fn interpolate(&self, other: &dyn Modulator, t: f32) -> Result<Box<dyn Modulator>, Error> {
+        if self.mode() != other.mode() {
+            let msg = format!(
+                "Cannot interpolate modulator {} with {}",
+                self.mode(),
+                other.mode()
+            );
+            return Err(Error::new(ErrorKind::InvalidInput, msg));
+        }
+
+        use modulator::*;
+        let modulator = match self.mode() {
+            ModulatorMode::AudioFollower => {
+                interpolate_modulator::<AudioFollowerModulator>(self, other, t)
+            }
+            ModulatorMode::Aftertouch | ModulatorMode::Pressure => {
+                interpolate_modulator::<PressureModulator>(self, other, t)
+            }
+            ModulatorMode::Blank => interpolate_modulator::<BlankModulator>(self, other, t),
+            ModulatorMode::Curve => interpolate_modulator::<CurveModulator>(self, other, t),
+            ModulatorMode::Envelope => interpolate_modulator::<EnvelopeModulator>(self, other, t),
+            ModulatorMode::Group => interpolate_modulator::<modulator::Group>(self, other, t),
+            ModulatorMode::Lfo => interpolate_modulator::<LfoModulator>(self, other, t),
+            ModulatorMode::LfoTable => interpolate_modulator::<LfoTableModulator>(self, other, t),
+            ModulatorMode::LowerLimit => {
+                interpolate_modulator::<LowerLimitModulator>(self, other, t)
+            }
+            ModulatorMode::MidiCc => interpolate_modulator::<MidiCcModulator>(self, other, t),
+            ModulatorMode::MpeTimbre => interpolate_modulator::<MpeTimbreModulator>(self, other, t),
+            ModulatorMode::Note => interpolate_modulator::<NoteModulator>(self, other, t),
+            ModulatorMode::NoteGate => interpolate_modulator::<NoteGateModulator>(self, other, t),
+            ModulatorMode::PitchTracker => {
+                interpolate_modulator::<PitchTrackerModulator>(self, other, t)
+            }
+            ModulatorMode::PitchWheel => {
+                interpolate_modulator::<PitchWheelModulator>(self, other, t)
+            }
+            ModulatorMode::Random => interpolate_modulator::<RandomModulator>(self, other, t),
+            ModulatorMode::Remap => interpolate_modulator::<RemapModulator>(self, other, t),
+            ModulatorMode::SampleAndHold => {
+                interpolate_modulator::<SampleAndHoldModulator>(self, other, t)
+            }
+            ModulatorMode::Scale => interpolate_modulator::<ScaleModulator>(self, other, t),
+            ModulatorMode::SlewLimiter => {
+                interpolate_modulator::<SlewLimiterModulator>(self, other, t)
+            }
+            ModulatorMode::UpperLimit => {
+                interpolate_modulator::<UpperLimitModulator>(self, other, t)
+            }
+            ModulatorMode::Velocity => interpolate_modulator::<VelocityModulator>(self, other, t),
+        };
+        Ok(modulator)
+    }
+}
+
+impl ModulatorContainer {
+    /// Blend two containers that hold the same type of modulator.
+    pub fn interpolate(
+        &self,
+        other: &ModulatorContainer,
+        t: f32,
+    ) -> Result<ModulatorContainer, Error> {
+        Ok(ModulatorContainer {
+            id: step(&self.id, &other.id, t),
+            group_id: step(&self.group_id, &other.group_id, t),
+            enabled: step(&self.enabled, &other.enabled, t),
+            minimized: step(&self.minimized, &other.minimized, t),
+            modulator: self.modulator.interpolate(other.modulator.as_ref(), t)?,
+            second_data_block: step(&self.second_data_block, &other.second_data_block, t),
+            unknown: step(&self.unknown, &other.unknown, t),
+        })
+    }
+}
+
+impl Modulation {
+    /// Blend two modulations that connect the same source and target.
+    pub fn interpolate(&self, other: &Modulation, t: f32) -> Result<Modulation, Error> {
+        if self.source != other.source || self.target != other.target {
+            let msg = format!("Cannot interpolate modulation {self} with {other}");
+            return Err(Error::new(ErrorKind::InvalidInput, msg));
+        }
+        Ok(Modulation {
+            enabled: step(&self.enabled, &other.enabled, t),
+            source: self.source.clone(),
+            target: self.target.clone(),
+            amount: self.amount.interpolate(&other.amount, t),
+            curve: self.curve.interpolate(&other.curve, t),
+        })
+    }
+}
+
+/// Blend lists whose items must pair up one to one.
+fn interpolate_all<T, U, F>(what: &str, a: &[T], b: &[T], mut f: F) -> Result<Vec<U>, Error>
+where
+    F: FnMut(&T, &T) -> Result<U, Error>,
+{
+    if a.len() != b.len() {
+        let msg = format!("Cannot interpolate {} {what} with {}", a.len(), b.len());
+        return Err(Error::new(ErrorKind::InvalidInput, msg));
+    }
+    a.iter().zip(b).map(|(a, b)| f(a, b)).collect()
+}
+
+impl Preset {
+    /// Morph between two presets. The presets must have the same structure:
+    /// the same generators, effects, modulators and modulations in the same
+    /// order. A `t` of zero is preset `a` and one is preset `b`.
+    pub fn interpolate(a: &Preset, b: &Preset, t: f32) -> Result<Preset, Error> {
+        if !(0.0..=1.0).contains(&t) {
+            let msg = format!("Interpolation position {t} must be between 0 and 1");
+            return Err(Error::new(ErrorKind::InvalidInput, msg));
+        }
+
+        Ok(Preset {
+            format_version: step(&a.format_version, &b.format_version, t),
+            generators: interpolate_all("generators", &a.generators, &b.generators, |a, b| {
+                a.interpolate(b.as_ref(), t)
+            })?,
+            mod_wheel_value: a.mod_wheel_value.interpolate(&b.mod_wheel_value, t),
+            glide_enabled: step(&a.glide_enabled, &b.glide_enabled, t),
+            glide_time: a.glide_time.interpolate(&b.glide_time, t),
+            glide_legato: step(&a.glide_legato, &b.glide_legato, t),
+            lanes: interpolate_all("lanes", &a.lanes, &b.lanes, |a, b| a.interpolate(b, t))?,
+            macro_controls: interpolate_all(
+                "macro controls",
+                &a.macro_controls,
+                &b.macro_controls,
+                |a, b| Ok(a.interpolate(b, t)),
+            )?,
+            master_gain: a.master_gain.interpolate(&b.master_gain, t),
+            master_pitch: a.master_pitch.interpolate(&b.master_pitch, t),
+            metadata: step(&a.metadata, &b.metadata, t),
+            modulations: interpolate_all("modulations", &a.modulations, &b.modulations, |a, b| {
+                a.interpolate(b, t)
+            })?,
+            modulator_containers: interpolate_all(
+                "modulators",
+                &a.modulator_containers,
+                &b.modulator_containers,
+                |a, b| a.interpolate(b, t),
+            )?,
+            polyphony: step(&a.polyphony, &b.polyphony, t),
+            retrigger_enabled: step(&a.retrigger_enabled, &b.retrigger_enabled, t),
+            unison: a.unison.interpolate(&b.unison, t),
+            unused_modulation_slots: step(
+                &a.unused_modulation_slots,
+                &b.unused_modulation_slots,
+                t,
+            ),
+            embedded_locations: step(&a.embedded_locations, &b.embedded_locations, t),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use uom::si::frequency::hertz;
+    use uom::si::ratio::percent;
+
+    use crate::test::read_effect_preset;
+
+    use super::*;
+
+    #[test]
+    fn decibels() {
+        let quiet = Decibels::new(-12.0);
+        let loud = Decibels::new(6.0);
+        assert_relative_eq!(quiet.interpolate(&loud, 0.5).db(), -3.0);
+        assert_eq!(
+            Decibels::new(f32::NEG_INFINITY).interpolate(&Decibels::ZERO, 1.0),
+            Decibels::ZERO
+        );
+        assert_relative_eq!(
+            Decibels::new(f32::NEG_INFINITY)
+                .interpolate(&Decibels::ZERO, 0.5)
+                .linear(),
+            0.5
+        );
+    }
+
+    #[test]
+    fn discrete() {
+        assert!(!step(&false, &true, 0.49));
+        assert!(step(&false, &true, 0.5));
+    }
+
+    #[test]
+    fn effects() {
+        let a: Box<dyn Effect> = Box::new(Filter {
+            cutoff: Frequency::new::<hertz>(200.0),
+            ..Filter::default()
+        });
+        let b: Box<dyn Effect> = Box::new(Filter {
+            cutoff: Frequency::new::<hertz>(1000.0),
+            ..Filter::default()
+        });
+        let morph = a.interpolate(b.as_ref(), 0.5).unwrap();
+        assert_relative_eq!(
+            morph.as_filter().unwrap().cutoff.get::<hertz>(),
+            600.0,
+            epsilon = 0.001
+        );
+
+        let gain: Box<dyn Effect> = Box::new(Gain::default());
+        assert_eq!(
+            a.interpolate(gain.as_ref(), 0.5).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn preset() {
+        let a = read_effect_preset("haas", "haas-small_width-1.8.13.phaseplant").unwrap();
+        let b = read_effect_preset("haas", "haas-small_width-1.8.13.phaseplant").unwrap();
+        assert_eq!(Preset::interpolate(&a, &b, 0.0).unwrap(), a);
+        assert_eq!(Preset::interpolate(&a, &b, 1.0).unwrap(), b);
+        assert!(Preset::interpolate(&a, &b, 1.5).is_err());
+
+        let a = Preset {
+            mod_wheel_value: Ratio::new::<percent>(10.0),
+            ..Preset::default()
+        };
+        let b = Preset {
+            mod_wheel_value: Ratio::new::<percent>(50.0),
+            ..Preset::default()
+        };
+        let morph = Preset::interpolate(&a, &b, 0.25).unwrap();
+        assert_relative_eq!(
+            morph.mod_wheel_value.get::<percent>(),
+            20.0,
+            epsilon = 0.0001
+        );
+
+        let empty = Preset {
+            lanes: Vec::new(),
+            ..Preset::default()
+        };
+        assert_eq!(
+            Preset::interpolate(&a, &empty, 0.5).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+}
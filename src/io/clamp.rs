@@ -0,0 +1,176 @@
+//! Bring parameters back into the ranges Phase Plant accepts.
+//!
+//! Values set from code aren't checked when they are assigned. Phase Plant
+//! loads presets with out of range values but they behave strangely, such as
+//! a mix above 100%. [`Preset::clamp_out_of_range`] is applied when writing
+//! with [`WriteOptions::clamp_out_of_range`].
+
+use log::Level;
+use uom::si::f32::Ratio;
+use uom::si::ratio::percent;
+
+use crate::generator::{AnalogOscillator, SamplePlayer, WavetableOscillator};
+use crate::io::write::Message;
+use crate::{Preset, Unison};
+
+/// Percentages are clamped as percentages so the messages match what Phase
+/// Plant shows. Not-a-number becomes the minimum.
+fn clamp_percent(value: &mut Ratio, min: f32, max: f32, what: &str, messages: &mut Vec<Message>) {
+    let percent_value = value.get::<percent>();
+    if (min..=max).contains(&percent_value) {
+        return;
+    }
+    let clamped = if percent_value.is_nan() {
+        min
+    } else {
+        percent_value.clamp(min, max)
+    };
+    *value = Ratio::new::<percent>(clamped);
+    messages.push(warning(format!(
+        "{what} of {percent_value}% changed to {clamped}%"
+    )));
+}
+
+fn clamp_voices(unison: &mut Unison, what: &str, messages: &mut Vec<Message>) {
+    let voices = unison.voices.clamp(1, Unison::VOICES_MAX);
+    if voices != unison.voices {
+        messages.push(warning(format!(
+            "{what} unison voices of {} changed to {voices}",
+            unison.voices
+        )));
+        unison.voices = voices;
+    }
+}
+
+fn warning(description: String) -> Message {
+    Message {
+        level: Level::Warn,
+        description,
+    }
+}
+
+impl Preset {
+    /// Clamp the unison voices, mixes, pans, macro control values and
+    /// modulation amounts that are outside of the ranges Phase Plant
+    /// accepts. Returns a warning for each value that was changed.
+    pub fn clamp_out_of_range(&mut self) -> Vec<Message> {
+        let mut messages = Vec::new();
+        clamp_voices(&mut self.unison, "Preset", &mut messages);
+
+        for (index, generator) in self.generators.iter_mut().enumerate() {
+            let what = format!("Generator {} \"{}\"", index + 1, generator.name());
+            if let Some(analog) = generator.downcast_mut::<AnalogOscillator>() {
+                clamp_voices(&mut analog.unison, &what, &mut messages);
+            } else if let Some(sampler) = generator.downcast_mut::<SamplePlayer>() {
+                clamp_voices(&mut sampler.unison, &what, &mut messages);
+            } else if let Some(wavetable) = generator.downcast_mut::<WavetableOscillator>() {
+                clamp_voices(&mut wavetable.unison, &what, &mut messages);
+            }
+            if let Some(mut pan) = generator.pan() {
+                clamp_percent(
+                    &mut pan,
+                    -100.0,
+                    100.0,
+                    &format!("{what} pan"),
+                    &mut messages,
+                );
+                // The pan is now in range so it can't fail.
+                let _ = generator.set_pan(pan);
+            }
+        }
+
+        for (index, lane) in self.lanes.iter_mut().enumerate() {
+            let what = format!("Lane {} mix", index + 1);
+            clamp_percent(&mut lane.mix, 0.0, 100.0, &what, &mut messages);
+        }
+        self.visit_effects_mut(|snapin| {
+            let what = format!("{} mix", snapin.name);
+            if let Some(mix) = snapin.effect.mix_mut() {
+                clamp_percent(mix, 0.0, 100.0, &what, &mut messages);
+            }
+        });
+
+        for (index, macro_control) in self.macro_controls.iter_mut().enumerate() {
+            let value = macro_control.value;
+            if !(0.0..=1.0).contains(&value) {
+                macro_control.value = if value.is_nan() {
+                    0.0
+                } else {
+                    value.clamp(0.0, 1.0)
+                };
+                messages.push(warning(format!(
+                    "Macro control {} value of {value} changed to {}",
+                    index + 1,
+                    macro_control.value
+                )));
+            }
+        }
+        for (index, modulation) in self.modulations.iter_mut().enumerate() {
+            let what = format!("Modulation {} amount", index + 1);
+            clamp_percent(&mut modulation.amount, -100.0, 100.0, &what, &mut messages);
+        }
+        messages
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::effect::Reverb;
+    use crate::{Modulation, Snapin};
+
+    use super::*;
+
+    #[test]
+    fn clamp_out_of_range() {
+        let mut preset = Preset::default();
+        assert!(preset.clamp_out_of_range().is_empty());
+
+        preset.unison.voices = 12;
+        preset.generators.push(Box::new(AnalogOscillator {
+            unison: Unison {
+                voices: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        }));
+        preset.lanes[0].mix = Ratio::new::<percent>(150.0);
+        preset.lanes[1].snapins.push(Snapin::new(
+            Box::new(Reverb {
+                mix: Ratio::new::<percent>(f32::NAN),
+                ..Default::default()
+            }),
+            1,
+            true,
+            false,
+        ));
+        preset.macro_controls[0].value = -0.5;
+        preset.modulations.push(Modulation {
+            amount: Ratio::new::<percent>(-250.0),
+            ..Default::default()
+        });
+
+        let messages = preset.clamp_out_of_range();
+        assert_eq!(messages.len(), 6);
+        assert!(messages.iter().all(|message| message.level == Level::Warn));
+        assert_eq!(
+            messages[0].description,
+            "Preset unison voices of 12 changed to 8"
+        );
+        assert_eq!(preset.unison.voices, 8);
+        assert_eq!(preset.generators[0].as_analog().unwrap().unison.voices, 1);
+        assert_eq!(preset.lanes[0].mix.get::<percent>(), 100.0);
+        assert_eq!(
+            preset.lanes[1].snapins[0]
+                .effect
+                .mix()
+                .unwrap()
+                .get::<percent>(),
+            0.0
+        );
+        assert_eq!(preset.macro_controls[0].value, 0.0);
+        assert_eq!(preset.modulations[0].amount.get::<percent>(), -100.0);
+
+        // Clamped values stay the same.
+        assert!(preset.clamp_out_of_range().is_empty());
+    }
+}
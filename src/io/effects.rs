@@ -1,14 +1,15 @@
 use std::io;
 use std::io::{Read, Seek, Write};
 
-use crate::effect::EffectMode;
+use crate::effect::{EffectMode, EffectVersion};
 use crate::*;
 
 pub struct EffectReadReturn {
     pub effect: Box<dyn Effect>,
-    pub enabled: bool,
-    pub minimized: bool,
-    pub group_id: Option<SnapinId>,
+
+    /// Effects store the state of their snapin amongst their own settings.
+    pub state: SnapinState,
+
     pub metadata: Metadata,
     pub preset_name: Option<String>,
     pub preset_path: Vec<String>,
@@ -16,17 +17,10 @@ pub struct EffectReadReturn {
 }
 
 impl EffectReadReturn {
-    pub(crate) fn new(
-        effect: Box<dyn Effect>,
-        enabled: bool,
-        minimized: bool,
-        group_id: Option<SnapinId>,
-    ) -> Self {
+    pub(crate) fn new(effect: Box<dyn Effect>, state: SnapinState) -> Self {
         Self {
             effect,
-            enabled,
-            minimized,
-            group_id,
+            state,
             metadata: Default::default(),
             preset_name: None,
             preset_path: vec![],
@@ -46,59 +40,80 @@ pub(crate) trait EffectWrite {
     fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()>;
 }
 
+impl Snapin {
+    /// Write the settings of the effect. Effects store the state of the
+    /// snapin amongst them.
+    pub(crate) fn write_effect<W: Write + Seek>(
+        &self,
+        writer: &mut PhasePlantWriter<W>,
+    ) -> io::Result<()> {
+        self.effect
+            .write(writer, &self.state(), self.effect_version)
+    }
+}
+
 impl dyn Effect {
-    pub(crate) fn write<W: Write + Seek>(
+    fn write<W: Write + Seek>(
         &self,
         writer: &mut PhasePlantWriter<W>,
-        snapin: &Snapin,
+        state: &SnapinState,
+        effect_version: EffectVersion,
     ) -> io::Result<()> {
         use EffectMode::*;
+        macro_rules! write_as {
+            ($as_effect:ident) => {
+                self.$as_effect()
+                    .unwrap()
+                    .write(writer, state, effect_version)
+            };
+        }
         // Not the greatest fan of the lack of dynamic dispatch here.
         match self.mode() {
-            Bitcrush => self.as_bitcrush().unwrap().write(writer, snapin),
-            CarveEq => self.as_carve_eq().unwrap().write(writer, snapin),
-            ChannelMixer => self.as_channel_mixer().unwrap().write(writer, snapin),
-            Chorus => self.as_chorus().unwrap().write(writer, snapin),
-            CombFilter => self.as_comb_filter().unwrap().write(writer, snapin),
-            Compressor => self.as_compressor().unwrap().write(writer, snapin),
-            Convolver => self.as_convolver().unwrap().write(writer, snapin),
-            Delay => self.as_delay().unwrap().write(writer, snapin),
-            Disperser => self.as_disperser().unwrap().write(writer, snapin),
-            Distortion => self.as_distortion().unwrap().write(writer, snapin),
-            DualDelay => self.as_dual_delay().unwrap().write(writer, snapin),
-            Dynamics => self.as_dynamics().unwrap().write(writer, snapin),
-            Ensemble => self.as_ensemble().unwrap().write(writer, snapin),
-            Faturator => self.as_faturator().unwrap().write(writer, snapin),
-            Filter => self.as_filter().unwrap().write(writer, snapin),
-            Flanger => self.as_flanger().unwrap().write(writer, snapin),
-            FormantFilter => self.as_formant_filter().unwrap().write(writer, snapin),
-            FrequencyShifter => self.as_frequency_shifter().unwrap().write(writer, snapin),
-            Gain => self.as_gain().unwrap().write(writer, snapin),
-            Gate => self.as_gate().unwrap().write(writer, snapin),
-            Group => self.as_group().unwrap().write(writer, snapin),
-            Haas => self.as_haas().unwrap().write(writer, snapin),
-            LadderFilter => self.as_ladder_filter().unwrap().write(writer, snapin),
-            Limiter => self.as_limiter().unwrap().write(writer, snapin),
-            Multipass => self.as_multipass().unwrap().write(writer, snapin),
-            NonlinearFilter => self.as_nonlinear_filter().unwrap().write(writer, snapin),
-            PhaseDistortion => self.as_phase_distortion().unwrap().write(writer, snapin),
-            Phaser => self.as_phaser().unwrap().write(writer, snapin),
-            PitchShifter => self.as_pitch_shifter().unwrap().write(writer, snapin),
-            Resonator => self.as_resonator().unwrap().write(writer, snapin),
-            Reverb => self.as_reverb().unwrap().write(writer, snapin),
-            Reverser => self.as_reverser().unwrap().write(writer, snapin),
-            RingMod => self.as_ring_mod().unwrap().write(writer, snapin),
-            SliceEq => self.as_slice_eq().unwrap().write(writer, snapin),
-            SnapHeap => self.as_snap_heap().unwrap().write(writer, snapin),
-            Stereo => self.as_stereo().unwrap().write(writer, snapin),
-            TapeStop => self.as_tape_stop().unwrap().write(writer, snapin),
-            ThreeBandEq => self.as_three_band_eq().unwrap().write(writer, snapin),
-            TranceGate => self.as_trance_gate().unwrap().write(writer, snapin),
-            TransientShaper => self.as_transient_shaper().unwrap().write(writer, snapin),
+            Bitcrush => write_as!(as_bitcrush),
+            CarveEq => write_as!(as_carve_eq),
+            ChannelMixer => write_as!(as_channel_mixer),
+            Chorus => write_as!(as_chorus),
+            CombFilter => write_as!(as_comb_filter),
+            Compressor => write_as!(as_compressor),
+            Convolver => write_as!(as_convolver),
+            Delay => write_as!(as_delay),
+            Disperser => write_as!(as_disperser),
+            Distortion => write_as!(as_distortion),
+            DualDelay => write_as!(as_dual_delay),
+            Dynamics => write_as!(as_dynamics),
+            Ensemble => write_as!(as_ensemble),
+            Faturator => write_as!(as_faturator),
+            Filter => write_as!(as_filter),
+            Flanger => write_as!(as_flanger),
+            FormantFilter => write_as!(as_formant_filter),
+            FrequencyShifter => write_as!(as_frequency_shifter),
+            Gain => write_as!(as_gain),
+            Gate => write_as!(as_gate),
+            Group => write_as!(as_group),
+            Haas => write_as!(as_haas),
+            LadderFilter => write_as!(as_ladder_filter),
+            Limiter => write_as!(as_limiter),
+            Multipass => write_as!(as_multipass),
+            NonlinearFilter => write_as!(as_nonlinear_filter),
+            PhaseDistortion => write_as!(as_phase_distortion),
+            Phaser => write_as!(as_phaser),
+            PitchShifter => write_as!(as_pitch_shifter),
+            Resonator => write_as!(as_resonator),
+            Reverb => write_as!(as_reverb),
+            Reverser => write_as!(as_reverser),
+            RingMod => write_as!(as_ring_mod),
+            SliceEq => write_as!(as_slice_eq),
+            SnapHeap => write_as!(as_snap_heap),
+            Stereo => write_as!(as_stereo),
+            TapeStop => write_as!(as_tape_stop),
+            ThreeBandEq => write_as!(as_three_band_eq),
+            TranceGate => write_as!(as_trance_gate),
+            TransientShaper => write_as!(as_transient_shaper),
         }
     }
 }
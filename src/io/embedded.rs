@@ -0,0 +1,187 @@
+//! Reading presets without their embedded samples and wavetables.
+//!
+//! Samples and wavetables that aren't part of the factory content are
+//! embedded in the preset and are most of its size. Tools that index a
+//! library can [skip](EmbeddedData::Skip) them, or only record where they are
+//! with [`EmbeddedData::Length`] and [load](Preset::load_embedded) them later
+//! from the same file.
+
+use std::io::{Error, ErrorKind, Read, Seek, SeekFrom};
+
+use crate::generator::{GeneratorId, GranularGenerator, SamplePlayer, WavetableOscillator};
+use crate::io::recovery::ReadProgress;
+use crate::limits::ReadLimits;
+use crate::modulator::{LfoTableModulator, ModulatorId};
+use crate::Preset;
+
+/// How to read the samples and wavetables embedded in a preset.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EmbeddedData {
+    /// Leave the contents empty. The preset can't be written without losing
+    /// the embedded data.
+    Skip,
+
+    /// Leave the contents empty but record their
+    /// [locations](Preset::embedded_locations) so they can be loaded later.
+    Length,
+
+    /// Read the contents.
+    #[default]
+    Full,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ReadOptions {
+    /// Fail on settings that aren't known and numbers that aren't valid. See
+    /// [`Preset::read_strict`].
+    pub strict: bool,
+
+    pub embedded_data: EmbeddedData,
+
+    pub limits: ReadLimits,
+
+    /// Keep a copy of the bytes of each generator and modulator block, as
+    /// [`lowlevel::Blocks`](crate::lowlevel::Blocks) does.
+    pub raw_blocks: bool,
+}
+
+/// What the embedded data belongs to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EmbeddedOwner {
+    /// The sample of a Sample Player or Granular generator.
+    Sample(GeneratorId),
+
+    /// The wavetable of a Wavetable Oscillator.
+    Wavetable(GeneratorId),
+
+    LfoTable(ModulatorId),
+}
+
+/// Where embedded data that hasn't been loaded is in the preset file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EmbeddedLocation {
+    pub owner: EmbeddedOwner,
+
+    /// Position of the contents from the start of the file.
+    pub offset: u64,
+
+    pub length: u32,
+}
+
+impl Preset {
+    pub fn read_with_options<R: Read + Seek>(
+        reader: &mut R,
+        name: Option<String>,
+        options: &ReadOptions,
+    ) -> Result<Preset, Error> {
+        Self::read_sections(reader, name, options, &mut ReadProgress::default())
+            .map(|(preset, _, _)| preset)
+    }
+
+    /// Read the embedded data that was left out when the preset was read with
+    /// [`EmbeddedData::Length`]. The reader must be of the same file.
+    pub fn load_embedded<R: Read + Seek>(&mut self, reader: &mut R) -> Result<(), Error> {
+        for location in std::mem::take(&mut self.embedded_locations) {
+            reader.seek(SeekFrom::Start(location.offset))?;
+            let mut contents = vec![0u8; location.length as usize];
+            reader.read_exact(&mut contents)?;
+            let destination = self.embedded_contents_mut(location.owner).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Nothing in the preset owns the embedded {:?}",
+                        location.owner
+                    ),
+                )
+            })?;
+            *destination = contents;
+        }
+        Ok(())
+    }
+
+    fn embedded_contents_mut(&mut self, owner: EmbeddedOwner) -> Option<&mut Vec<u8>> {
+        match owner {
+            EmbeddedOwner::Sample(id) | EmbeddedOwner::Wavetable(id) => {
+                let generator = self
+                    .generators
+                    .iter_mut()
+                    .find(|generator| generator.id() == Some(id))?;
+                if let EmbeddedOwner::Wavetable(_) = owner {
+                    return generator
+                        .downcast_mut::<WavetableOscillator>()
+                        .map(|oscillator| &mut oscillator.wavetable_contents);
+                }
+                if generator.is::<SamplePlayer>() {
+                    generator
+                        .downcast_mut::<SamplePlayer>()
+                        .map(|player| &mut player.sample_contents)
+                } else {
+                    generator
+                        .downcast_mut::<GranularGenerator>()
+                        .map(|granular| &mut granular.sample_contents)
+                }
+            }
+            EmbeddedOwner::LfoTable(id) => self
+                .modulator_containers
+                .iter_mut()
+                .find(|container| container.id == id)?
+                .modulator
+                .downcast_mut::<LfoTableModulator>()
+                .map(|lfo_table| &mut lfo_table.wavetable_contents),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::File;
+    use std::io::{BufReader, Cursor};
+
+    use crate::generator::SamplePlayer;
+    use crate::tests::test_data_path;
+
+    use super::*;
+
+    fn read(path: &[&str], embedded_data: EmbeddedData) -> (Preset, BufReader<File>) {
+        let mut reader = BufReader::new(File::open(test_data_path(path)).unwrap());
+        let options = ReadOptions {
+            embedded_data,
+            ..Default::default()
+        };
+        let preset = Preset::read_with_options(&mut reader, None, &options).unwrap();
+        (preset, reader)
+    }
+
+    #[test]
+    fn embedded_data() {
+        let path = [
+            "generators",
+            "sample_player",
+            "sample_player-custom-sample-1.8.16.phaseplant",
+        ];
+        let (full, _) = read(&path, EmbeddedData::Full);
+        let generator: &SamplePlayer = full.generator(1).unwrap();
+        let contents = &generator.sample_contents;
+        assert!(!contents.is_empty());
+        assert!(full.embedded_locations.is_empty());
+
+        let (skipped, _) = read(&path, EmbeddedData::Skip);
+        let generator: &SamplePlayer = skipped.generator(1).unwrap();
+        assert!(generator.sample_contents.is_empty());
+        assert!(skipped.embedded_locations.is_empty());
+
+        let (mut lengths, mut reader) = read(&path, EmbeddedData::Length);
+        assert_eq!(
+            lengths.embedded_locations,
+            [EmbeddedLocation {
+                owner: EmbeddedOwner::Sample(generator.id),
+                offset: lengths.embedded_locations[0].offset,
+                length: contents.len() as u32,
+            }]
+        );
+        let written = lengths.write(&mut Cursor::new(Vec::new()));
+        assert!(matches!(written, Err(error) if error.kind() == ErrorKind::InvalidInput));
+        lengths.load_embedded(&mut reader).unwrap();
+        assert_eq!(lengths, full);
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::io::{Error, Read, Seek};
 
 use music_note::midi;
@@ -82,8 +83,15 @@ pub struct GeneratorBlock {
 
     pub envelope: Envelope,
 
+    /// A user wavetable embedded in the preset. Phase Plant stores it as
+    /// FLAC so it is already compressed.
     pub wavetable_contents: Vec<u8>,
     pub wavetable_edited: bool,
+
+    /// How the wavetable was stored, `None` if there was no wavetable or
+    /// the block was not read.
+    pub wavetable_data_mode: Option<WavetableDataMode>,
+
     pub wavetable_frame: f32,
     pub wavetable_name: Option<String>,
     pub wavetable_path: Option<String>,
@@ -99,10 +107,15 @@ pub struct GeneratorBlock {
     pub curve_loop_start: Ratio,
     pub curve_loop_length: Ratio,
 
-    /// A file containing the samples to play back. Usually in a format like FLAC, MP3 or WAV.
+    /// The embedded sample as a FLAC file, which is what Phase Plant converts
+    /// every sample to.
     /// If there are no sample contents then it is a factory sample.
     pub sample_contents: Vec<u8>,
 
+    /// How the sample was stored, `None` if there was no sample or the
+    /// block was not read.
+    pub sample_data_mode: Option<SampleDataMode>,
+
     pub sample_rate: f32,
     pub sample_name: Option<String>,
     pub sample_path: Option<String>,
@@ -123,6 +136,15 @@ pub struct GeneratorBlock {
     pub granular_randomization: GranularRandomization,
     pub granular_chord: GranularChord,
     pub granular_warm_start: bool,
+
+    /// The block as it was read from the file. Empty when the block was not
+    /// read or [`ReadOptions::raw_blocks`](crate::io::ReadOptions::raw_blocks)
+    /// was not set.
+    pub raw: Vec<u8>,
+
+    /// Values that are read but whose meaning is not known, stored as their
+    /// raw bits.
+    pub unknown: BTreeMap<&'static str, u32>,
 }
 
 impl GeneratorBlock {
@@ -175,7 +197,7 @@ impl Default for GeneratorBlock {
             shift: Frequency::zero(),
             rate: Rate {
                 sync: false,
-                frequency: Frequency::new::<hertz>(100.0),
+                frequency: Frequency::new::<hertz>(1.0),
                 numerator: 4,
                 denominator: NoteValue::Sixteenth,
             },
@@ -217,6 +239,7 @@ impl Default for GeneratorBlock {
 
             wavetable_contents: Vec::new(),
             wavetable_edited: false,
+            wavetable_data_mode: None,
             wavetable_frame: 0.0,
             wavetable_name: None,
             wavetable_path: None,
@@ -231,6 +254,7 @@ impl Default for GeneratorBlock {
             curve_loop_length: Ratio::new::<percent>(100.0),
 
             sample_contents: Vec::new(),
+            sample_data_mode: None,
             sample_rate: 0.0,
             sample_name: None,
             sample_path: None,
@@ -251,6 +275,8 @@ impl Default for GeneratorBlock {
             granular_randomization: Default::default(),
             granular_chord: Default::default(),
             granular_warm_start: false,
+            raw: Vec::new(),
+            unknown: BTreeMap::new(),
         }
     }
 }
@@ -352,7 +378,8 @@ impl From<&CurveOutput> for GeneratorBlock {
             curve_path: generator.curve_path.clone(),
             curve_loop_mode: generator.loop_mode,
             curve_loop_start: generator.loop_start,
-            curve_loop_length: generator.loop_start,
+            curve_loop_length: generator.loop_length,
+            rate: generator.rate.clone(),
             ..Default::default()
         }
     }
@@ -536,6 +563,8 @@ impl From<&WavetableOscillator> for GeneratorBlock {
             wavetable_frame: generator.frame,
             band_limit: generator.band_limit,
             unison: generator.unison,
+            wavetable_contents: generator.wavetable_contents.clone(),
+            wavetable_edited: generator.wavetable_edited,
             wavetable_name: generator.wavetable_name.clone(),
             wavetable_path: generator.wavetable_path.clone(),
             ..Default::default()
@@ -11,24 +11,44 @@
 use std::mem::size_of;
 
 use serde::{Deserialize, Serialize};
+use strum_macros::FromRepr;
+
+use crate::generator::GeneratorMode;
 
 pub(crate) use generators::GeneratorBlock;
 pub(crate) use modulators::ModulatorBlock;
 
 pub use self::effects::*;
+pub use self::embedded::*;
 pub use self::read::*;
+pub use self::recovery::*;
+pub use self::section_map::*;
+pub use self::stats::*;
 pub use self::write::*;
 
+mod clamp;
 pub(crate) mod effects;
+mod embedded;
 mod generators;
 mod modulators;
 mod read;
+mod recovery;
+mod section_map;
+mod snapin_frame;
+mod stats;
 mod write;
 
+/// Missing fields are read as empty and unknown fields are kept.
 #[derive(Debug, Serialize, Deserialize)]
 struct MetadataJson {
+    #[serde(default)]
     pub description: Option<String>,
+
+    #[serde(default)]
     pub author: Option<String>,
+
+    #[serde(flatten)]
+    pub other_fields: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug)]
@@ -60,7 +80,7 @@ impl DataBlockHeader {
         }
     }
 
-    /// * `data_length` - size of the block, not including the header mode
+    /// * `data_length` - size of the block, not including the header and mode
     fn new_used(data_length: usize, mode_id: u32) -> Self {
         Self {
             data_length,
@@ -87,3 +107,74 @@ impl DataBlockHeader {
         self.mode_id
     }
 }
+
+/// A data block whose contents aren't understood. It is kept as it was read
+/// so it can be written again.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RawDataBlock {
+    pub mode_id: u32,
+    pub contents: Vec<u8>,
+}
+
+/// How the sample of a Sampler or Granular generator is stored in its data
+/// block. Embedded contents follow the path in every mode. A generator
+/// without a sample doesn't use the block.
+#[derive(Copy, Clone, Debug, Eq, FromRepr, PartialEq)]
+#[repr(u32)]
+pub enum SampleDataMode {
+    // The discriminants correspond to the file format.
+    /// Not written by the releases of Phase Plant in the test presets.
+    PathOnly = 1,
+
+    PathAndContents = 2,
+
+    /// Written for the Granular generator. The path is followed by a flag
+    /// that has only been seen as false.
+    PathWithFlag = 3,
+}
+
+impl SampleDataMode {
+    /// The mode Phase Plant uses to store a sample, or `None` if there is no
+    /// sample.
+    pub(crate) fn for_sample(
+        generator_mode: GeneratorMode,
+        path: &Option<String>,
+        contents: &[u8],
+    ) -> Option<Self> {
+        let has_path = path.as_ref().is_some_and(|path| !path.is_empty());
+        if !has_path && contents.is_empty() {
+            None
+        } else if generator_mode == GeneratorMode::GranularGenerator {
+            Some(SampleDataMode::PathWithFlag)
+        } else {
+            Some(SampleDataMode::PathAndContents)
+        }
+    }
+}
+
+/// How the wavetable of a Wavetable Oscillator is stored in its data block.
+/// Embedded contents follow the path in every mode. A generator without a
+/// wavetable doesn't use the block.
+#[derive(Copy, Clone, Debug, Eq, FromRepr, PartialEq)]
+#[repr(u32)]
+pub enum WavetableDataMode {
+    // The discriminants correspond to the file format.
+    /// Written by Phase Plant 1.7.
+    Path = 1,
+
+    /// The path is followed by if the wavetable was edited.
+    PathWithEdited = 3,
+}
+
+impl WavetableDataMode {
+    /// The mode Phase Plant uses to store a wavetable, or `None` if there is
+    /// no wavetable.
+    pub(crate) fn for_wavetable(path: &Option<String>, contents: &[u8]) -> Option<Self> {
+        let has_path = path.as_ref().is_some_and(|path| !path.is_empty());
+        if !has_path && contents.is_empty() {
+            None
+        } else {
+            Some(WavetableDataMode::PathWithEdited)
+        }
+    }
+}
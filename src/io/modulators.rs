@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::io;
 use std::io::{Read, Seek};
 
@@ -40,12 +41,8 @@ pub struct ModulatorBlock {
     pub multiplier: f32,
 
     pub depth: Ratio,
-    pub retrigger: bool,
     pub phase_offset: Ratio,
 
-    /// One shot was removed from LFO modulator and replaced with [loop_mode](Self::loop_mode)
-    pub one_shot: bool,
-
     pub envelope: Envelope,
     pub rate: Rate,
     pub velocity_trigger_mode: VelocityTriggerMode,
@@ -70,6 +67,8 @@ pub struct ModulatorBlock {
     // LFO Table
     pub lfo_table_smooth: Ratio,
     pub lfo_table_frame: f32,
+    /// An embedded user wavetable, stored as FLAC like those of Wavetable
+    /// generators.
     pub lfo_table_wavetable_contents: Vec<u8>,
     pub lfo_table_wavetable_path: Option<String>,
 
@@ -103,6 +102,19 @@ pub struct ModulatorBlock {
     // Which group contains this modulator. `GROUP_ID_NONE` if it is not
     // contained in a group.
     pub group_id: GroupId,
+
+    /// Each modulator has a second data block after the one that holds the
+    /// shape. None of the known presets use it.
+    pub second_data_block: Option<RawDataBlock>,
+
+    /// The block as it was read from the file. Empty when the block was not
+    /// read or [`ReadOptions::raw_blocks`](crate::io::ReadOptions::raw_blocks)
+    /// was not set.
+    pub raw: Vec<u8>,
+
+    /// Values that are read but whose meaning is not known, stored as their
+    /// raw bits.
+    pub unknown: BTreeMap<&'static str, u32>,
 }
 
 impl Default for ModulatorBlock {
@@ -113,13 +125,11 @@ impl Default for ModulatorBlock {
             enabled: true,
             minimized: false,
             output_range: OutputRange::Unipolar,
-            loop_mode: LoopMode::Infinite,
+            loop_mode: LoopMode::Off,
             input_a: 0.0,
             input_b: 0.0,
             multiplier: 1.0,
             depth: Ratio::new::<ratio>(1.0),
-            retrigger: true,
-            one_shot: false,
             rate: Rate {
                 sync: false,
                 frequency: Frequency::new::<hertz>(1.0),
@@ -157,7 +167,7 @@ impl Default for ModulatorBlock {
             pitch_tracker_lowest: midi!(C, 2).into_byte() as u32,
             pitch_tracker_root: midi!(A, 4).into_byte() as u32,
             pitch_tracker_highest: midi!(C, 6).into_byte() as u32,
-            pitch_tracker_sensitivity: Ratio::zero(),
+            pitch_tracker_sensitivity: Ratio::new::<percent>(50.0),
 
             // Random
             random_jitter: Ratio::zero(),
@@ -173,11 +183,53 @@ impl Default for ModulatorBlock {
             audio_source: AudioSourceId::default(),
             metering_mode: MeteringMode::RootMeanSquared,
             group_id: GROUP_ID_NONE,
+            second_data_block: None,
+            raw: Vec::new(),
+            unknown: BTreeMap::new(),
         }
     }
 }
 
 impl ModulatorBlock {
+    /// Convert the block to the modulator of its mode.
+    pub(crate) fn modulator(&self) -> Box<dyn Modulator> {
+        use ModulatorMode::*;
+        match self.mode {
+            AudioFollower => Box::new(AudioFollowerModulator::from(self)),
+
+            // Convert the legacy Aftertouch modulator to Pressure.
+            Aftertouch => Box::new(PressureModulator::from(self)),
+
+            Blank => Box::new(BlankModulator::from(self)),
+            Curve => Box::new(CurveModulator::from(self)),
+            Envelope => Box::new(EnvelopeModulator::from(self)),
+            Group => Box::new(modulator::Group::from(self)),
+            Lfo => Box::new(LfoModulator::from(self)),
+            LfoTable => Box::new(LfoTableModulator::from(self)),
+            LowerLimit => Box::new(LowerLimitModulator::from(self)),
+            MidiCc => Box::new(MidiCcModulator::from(self)),
+            MpeTimbre => Box::new(MpeTimbreModulator::from(self)),
+            Note => Box::new(NoteModulator::from(self)),
+            NoteGate => Box::new(NoteGateModulator::from(self)),
+            PitchTracker => Box::new(PitchTrackerModulator::from(self)),
+            PitchWheel => Box::new(PitchWheelModulator::from(self)),
+            Pressure => Box::new(PressureModulator::from(self)),
+            Scale => Box::new(ScaleModulator::from(self)),
+            Random => Box::new(RandomModulator::from(self)),
+            Remap => Box::new(RemapModulator::from(self)),
+            SampleAndHold => Box::new(SampleAndHoldModulator::from(self)),
+            SlewLimiter => Box::new(SlewLimiterModulator::from(self)),
+            UpperLimit => Box::new(UpperLimitModulator::from(self)),
+            Velocity => Box::new(VelocityModulator::from(self)),
+        }
+    }
+
+    /// Raw bits of a value whose meaning is not known, or what Phase Plant
+    /// usually stores if it wasn't read.
+    pub(crate) fn unknown_or(&self, name: &str, default: u32) -> u32 {
+        self.unknown.get(name).copied().unwrap_or(default)
+    }
+
     pub(crate) fn read_data_block<R: Read + Seek>(
         &mut self,
         reader: &mut PhasePlantReader<R>,
@@ -315,6 +367,7 @@ impl From<&CurveModulator> for ModulatorBlock {
             rate: modulator.rate.clone(),
             curve_time: modulator.rate.frequency.recip(),
             note_trigger_mode: modulator.note_trigger_mode,
+            trigger_threshold: modulator.trigger_threshold,
             depth: modulator.depth,
             shape: modulator.shape.clone(),
             shape_name: modulator.shape_name.clone(),
@@ -362,6 +415,7 @@ impl From<&ModulatorBlock> for Group {
 impl From<&Group> for ModulatorBlock {
     fn from(modulator: &Group) -> Self {
         Self {
+            mode: modulator.mode(),
             shape_name: modulator.name.clone(),
             ..Default::default()
         }
@@ -395,6 +449,7 @@ impl From<&LfoModulator> for ModulatorBlock {
             loop_mode: modulator.loop_mode,
             rate: modulator.rate.clone(),
             note_trigger_mode: modulator.note_trigger_mode,
+            trigger_threshold: modulator.trigger_threshold,
             phase_offset: modulator.phase_offset,
 
             // The LFO Modulator doesn't use an envelope but the preset files
@@ -442,6 +497,7 @@ impl From<&LfoTableModulator> for ModulatorBlock {
             rate: modulator.rate.clone(),
             loop_mode: modulator.loop_mode,
             note_trigger_mode: modulator.note_trigger_mode,
+            trigger_threshold: modulator.trigger_threshold,
             phase_offset: modulator.phase_offset,
             lfo_table_smooth: modulator.smooth,
             lfo_table_frame: modulator.frame,
@@ -588,6 +644,11 @@ impl From<&PitchTrackerModulator> for ModulatorBlock {
             pitch_tracker_root: modulator.root_note,
             pitch_tracker_highest: modulator.highest_note,
             pitch_tracker_sensitivity: modulator.sensitivity,
+
+            // The Pitch Tracker doesn't use the trigger threshold but the
+            // preset files contain -50 dB. Overriding the default makes the
+            // files exactly match.
+            trigger_threshold: Ratio::new::<ratio>(Decibels::new(-50.0).linear()),
             ..Default::default()
         }
     }
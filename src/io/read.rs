@@ -1,6 +1,7 @@
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::prelude::*;
-use std::io::{Cursor, Error, ErrorKind, Seek};
+use std::io::{BufReader, Cursor, Error, ErrorKind, Seek, SeekFrom};
 use std::mem::size_of;
 use std::path::Path;
 use std::str;
@@ -17,23 +18,52 @@ use crate::effect::*;
 use crate::generator::*;
 use crate::io::generators::GeneratorBlock;
 use crate::io::modulators::*;
+use crate::io::recovery::ReadProgress;
+use crate::io::snapin_frame::SnapinFrame;
 use crate::io::MetadataJson;
+use crate::limits::{Limit, LimitExceeded, ReadLimits};
 use crate::modulation::{ModulationSource, ModulationTarget, MODULATIONS_MAX};
 use crate::modulator::*;
 use crate::text::TextOptionExt;
 use crate::*;
 
-/// Maximum length of a general purpose string. Helps to avoid corrupt files
-/// from consuming larges amounts of resources.
-const READ_STRING_LENGTH_MAX: u32 = 1024;
+/// Maximum length in bytes of a general purpose string. Helps to avoid
+/// corrupt files from consuming larges amounts of resources.
+pub(crate) const READ_STRING_LENGTH_MAX: u32 = crate::limits::STRING_LENGTH_MAX as u32;
 
 pub const MIN_SUPPORTED_RELEASE: PhasePlantRelease = PhasePlantRelease::V1_6_9;
 
+/// Settings stored as a number that newer versions of Phase Plant may add
+/// values to. Unknown values are kept instead of failing to read the preset.
+pub(crate) trait ForwardCompatible: Sized {
+    /// Name of the setting for error messages.
+    const DESCRIPTION: &'static str;
+
+    fn from_id(id: u32) -> Self;
+
+    fn is_unknown(&self) -> bool;
+}
+
 /// Make reading the Phase Plant format less verbose. Phase Plant version 1
 /// and 2 presets are supported.
 pub struct PhasePlantReader<T: Read + Seek> {
     inner: T,
     pub(crate) format_version: Version<u32>,
+
+    /// Fail on values that aren't known or numbers that aren't valid instead
+    /// of keeping them.
+    pub(crate) strict: bool,
+
+    pub(crate) embedded_data: EmbeddedData,
+
+    pub(crate) limits: ReadLimits,
+
+    /// Keep a copy of the bytes of each generator and modulator block.
+    pub(crate) raw_blocks: bool,
+
+    /// Number of embedded contents read into memory and their total size.
+    pub(crate) embedded_allocations: usize,
+    pub(crate) embedded_bytes: u64,
 }
 
 impl<T: Read + Seek> PhasePlantReader<T> {
@@ -41,12 +71,19 @@ impl<T: Read + Seek> PhasePlantReader<T> {
         let mut reader = Self {
             inner,
             format_version: Version::new(0, 0, 0, 0),
+            strict: false,
+            embedded_data: EmbeddedData::Full,
+            limits: ReadLimits::default(),
+            raw_blocks: false,
+            embedded_allocations: 0,
+            embedded_bytes: 0,
         };
 
         let format_major = reader.read_u32()?;
-        if format_major == 0x504b0304 {
+        if format_major == u32::from_le_bytes(*b"PK\x03\x04") {
             // ZIP header
-            return Err(Error::other(
+            return Err(Error::new(
+                ErrorKind::Unsupported,
                 "Version 2.2 and later presets are not supported",
             ));
         }
@@ -181,16 +218,60 @@ impl<T: Read + Seek> PhasePlantReader<T> {
         self.inner.read_u32::<LittleEndian>()
     }
 
+    /// Read a setting that may have values added by newer versions of Phase
+    /// Plant.
+    pub(crate) fn read_forward_compatible<V: ForwardCompatible>(&mut self) -> Result<V, Error> {
+        let id = self.read_u32()?;
+        let value = V::from_id(id);
+        if value.is_unknown() {
+            let message = format!("Unknown {} {id} at position {}", V::DESCRIPTION, self.pos());
+            if self.strict {
+                return Err(Error::new(ErrorKind::InvalidData, message));
+            }
+            warn!("{message}");
+        }
+        Ok(value)
+    }
+
+    /// Read a number that Phase Plant always writes as a finite value.
+    /// Not-a-number and infinity are only in corrupt files. They fail strict
+    /// reading and are otherwise kept with a warning.
     pub(crate) fn read_f32(&mut self) -> Result<f32, Error> {
-        self.inner.read_f32::<LittleEndian>()
+        let value = self.inner.read_f32::<LittleEndian>()?;
+        self.check_number(value, value.is_finite())
     }
 
+    /// Negative infinity is silence.
     pub(crate) fn read_decibels_db(&mut self) -> Result<Decibels, Error> {
-        self.read_f32().map(Decibels::new)
+        let value = self.inner.read_f32::<LittleEndian>()?;
+        self.check_number(value, value.is_finite() || value == f32::NEG_INFINITY)
+            .map(Decibels::new)
+    }
+
+    /// Read a gain stored as a linear factor. Zero is negative infinity
+    /// decibels, which is what Phase Plant writes when a gain is turned all
+    /// the way down.
+    pub(crate) fn read_linear_gain(&mut self) -> Result<f32, Error> {
+        let value = self.inner.read_f32::<LittleEndian>()?;
+        self.check_number(value, value.is_finite() && value >= 0.0)
+    }
+
+    fn check_number(&mut self, value: f32, valid: bool) -> Result<f32, Error> {
+        if !valid {
+            let position = self
+                .stream_position()
+                .map_or(0, |pos| pos.saturating_sub(4));
+            let message = format!("Invalid number {value} at position {position}");
+            if self.strict {
+                return Err(Error::new(ErrorKind::InvalidData, message));
+            }
+            warn!("{message}");
+        }
+        Ok(value)
     }
 
     pub(crate) fn read_decibels_linear(&mut self) -> Result<Decibels, Error> {
-        self.read_f32().map(Decibels::from_linear)
+        self.read_linear_gain().map(Decibels::from_linear)
     }
 
     pub(crate) fn read_hertz(&mut self) -> Result<Frequency, Error> {
@@ -214,26 +295,32 @@ impl<T: Read + Seek> PhasePlantReader<T> {
     /// the string exceeds [`READ_STRING_LENGTH_MAX`].
     pub(crate) fn read_string_and_length(&mut self) -> Result<Option<String>, Error> {
         let len = self.read_u32()?;
-        let string_pos = self.stream_position()?;
         if len > READ_STRING_LENGTH_MAX {
             Err(Error::new(
                 ErrorKind::InvalidData,
                 format!(
-                    "Text length of {len} ({len:#x}) exceeds {READ_STRING_LENGTH_MAX} characters at position {}",
+                    "Text length of {len} ({len:#x}) exceeds {READ_STRING_LENGTH_MAX} bytes at position {}",
                     self.stream_position()? as usize - size_of::<u32>()
                 ),
             ))
         } else if len == 0 {
             Ok(None)
         } else {
+            // The buffer becomes the string without being copied.
             let mut buffer = vec![0u8; len as usize];
             self.inner.read_exact(&mut buffer)?;
-            match str::from_utf8(&buffer) {
-                Ok(text) => Ok(Some(text.to_string())),
-                Err(err) => Err(Error::new(
-                    ErrorKind::InvalidData,
-                    format!("Cannot convert text to UTF-8 at position {string_pos}: {err}"),
-                )),
+            match String::from_utf8(buffer) {
+                Ok(text) => Ok(Some(text)),
+                Err(err) => {
+                    let string_pos = self.stream_position()? - len as u64;
+                    Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "Cannot convert text to UTF-8 at position {string_pos}: {}",
+                            err.utf8_error()
+                        ),
+                    ))
+                }
             }
         }
     }
@@ -241,12 +328,21 @@ impl<T: Read + Seek> PhasePlantReader<T> {
     pub(crate) fn read_metadata(&mut self) -> Result<Metadata, Error> {
         // Length includes the byte before the JSON actually starts.
         let metadata_length = self.read_u32()? as usize;
-        if metadata_length > METADATA_LENGTH_MAX {
+        if metadata_length == 0 {
             return Err(Error::new(
                 ErrorKind::InvalidData,
-                format!("Metadata length of {metadata_length} is too large"),
+                "Metadata length of 0 is invalid",
             ));
         }
+        if metadata_length > self.limits.metadata_length_max {
+            return Err(LimitExceeded {
+                limit: Limit::MetadataLength,
+                value: metadata_length,
+                maximum: self.limits.metadata_length_max,
+                position: self.stream_position()? - size_of::<u32>() as u64,
+            }
+            .into());
+        }
 
         // Unknown byte before the metadata starts.
         let unknown_m1 = self.read_u8()?;
@@ -260,7 +356,11 @@ impl<T: Read + Seek> PhasePlantReader<T> {
         let mut json_buffer = vec![0_u8; metadata_length - 1];
         self.read_exact(&mut json_buffer)?;
 
-        let mut deserializer = serde_json::Deserializer::from_reader(Cursor::new(json_buffer));
+        // Other tools may start the JSON with a byte order mark.
+        let json = json_buffer
+            .strip_prefix(b"\xEF\xBB\xBF")
+            .unwrap_or(&json_buffer);
+        let mut deserializer = serde_json::Deserializer::from_reader(Cursor::new(json));
         let metadata_json = MetadataJson::deserialize(&mut deserializer)?;
         let author = metadata_json.author.trim().empty_to_none();
         let description = metadata_json.description.trim().empty_to_none();
@@ -269,20 +369,21 @@ impl<T: Read + Seek> PhasePlantReader<T> {
             author,
             description,
             category: None,
+            other_fields: metadata_json.other_fields,
         })
     }
 
     /// Read a multi-part path.
     pub(crate) fn read_path(&mut self) -> Result<Vec<String>, Error> {
         let component_count = self.read_u32()? as usize;
-        if component_count > PATH_COMPONENT_COUNT_MAX {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                format!(
-                    "Path component count of {component_count} exceeds {PATH_COMPONENT_COUNT_MAX} at position {}",
-                    self.pos()
-                ),
-            ));
+        if component_count > self.limits.path_component_count_max {
+            return Err(LimitExceeded {
+                limit: Limit::PathComponentCount,
+                value: component_count,
+                maximum: self.limits.path_component_count_max,
+                position: self.stream_position()? - size_of::<u32>() as u64,
+            }
+            .into());
         }
 
         let mut path = Vec::with_capacity(component_count);
@@ -296,6 +397,19 @@ impl<T: Read + Seek> PhasePlantReader<T> {
         self.inner.read_exact(buf)
     }
 
+    /// Read again the bytes of a block from its start up to the current
+    /// position. Empty unless the raw blocks were asked for.
+    pub(crate) fn read_raw_since(&mut self, start_pos: u64) -> Result<Vec<u8>, Error> {
+        if !self.raw_blocks {
+            return Ok(Vec::new());
+        }
+        let end_pos = self.stream_position()?;
+        self.inner.seek(SeekFrom::Start(start_pos))?;
+        let mut raw = vec![0; (end_pos - start_pos) as usize];
+        self.read_exact(&mut raw)?;
+        Ok(raw)
+    }
+
     pub(crate) fn read_block_header(&mut self) -> Result<DataBlockHeader, Error> {
         let length = self.read_u32()? as usize;
         if length == 0 {
@@ -339,65 +453,177 @@ impl<T: Read + Seek> PhasePlantReader<T> {
         })
     }
 
+    /// Read the length and contents of a sample or wavetable embedded in the
+    /// preset. Depending on [`EmbeddedData`] the contents may be skipped and
+    /// their location recorded instead.
+    pub(crate) fn read_embedded(
+        &mut self,
+        owner: EmbeddedOwner,
+        locations: &mut Vec<EmbeddedLocation>,
+    ) -> Result<Vec<u8>, Error> {
+        let length = self.read_u32()?;
+        trace!("embedded: {owner:?}, length {length}, pos {}", self.pos());
+        match self.embedded_data {
+            EmbeddedData::Full => {
+                let mut contents = vec![0u8; length as usize];
+                self.read_exact(&mut contents)?;
+                self.embedded_allocations += 1;
+                self.embedded_bytes += length as u64;
+                return Ok(contents);
+            }
+            EmbeddedData::Length => locations.push(EmbeddedLocation {
+                owner,
+                offset: self.stream_position()?,
+                length,
+            }),
+            EmbeddedData::Skip => (),
+        }
+        self.skip(length as i64)?;
+        Ok(Vec::new())
+    }
+
     /// Prefer reading the data and comparing to expected values instead
     /// of blindly skipping over parts of the file. This will help ensure the
     /// understanding of the preset format is correct.
-    pub(crate) fn skip(&mut self, bytes: i64) -> Result<u64, Error> {
-        // BufReader has seek_relative
-        self.inner.seek(std::io::SeekFrom::Current(bytes))
+    ///
+    /// Seeks relative to the current position so a buffered reader can skip
+    /// within its buffer instead of discarding it.
+    pub(crate) fn skip(&mut self, bytes: i64) -> Result<(), Error> {
+        self.inner.seek_relative(bytes)
     }
 }
 
 impl Preset {
     pub fn read_file<P: AsRef<Path>>(path: P) -> Result<Preset, Error> {
-        let mut file = File::open(path.as_ref())?;
+        let file = File::open(path.as_ref())?;
         let name_opt = path
             .as_ref()
             .file_stem()
             .map(|os_str| os_str.to_string_lossy());
         let name_str = name_opt.map(|name| name.to_string());
-        Self::read(&mut file, name_str)
+
+        // Presets are read with many small reads.
+        Self::read(&mut BufReader::new(file), name_str)
     }
 
     pub fn read<R: Read + Seek>(reader: &mut R, name: Option<String>) -> Result<Preset, Error> {
-        let mut reader = PhasePlantReader::new(reader)?;
+        Self::read_with_options(reader, name, &ReadOptions::default())
+    }
 
-        //
-        // Header
-        //
+    /// Read only the header and metadata of a preset, which is much faster
+    /// than reading the whole preset when listing a library.
+    pub fn read_metadata<R: Read + Seek>(
+        reader: &mut R,
+        name: Option<String>,
+    ) -> Result<Metadata, Error> {
+        let mut reader = PhasePlantReader::new(reader)?;
+        Self::check_format_version(&reader.format_version)?;
+        let mut metadata = reader.read_metadata()?;
+        if metadata.name.is_none() {
+            metadata.name = name;
+        }
+        Ok(metadata)
+    }
 
-        debug!("Preset format version {}", reader.format_version);
-        if !PhasePlantRelease::is_likely_format_version(&reader.format_version) {
+    fn check_format_version(format_version: &Version<u32>) -> Result<(), Error> {
+        debug!("Preset format version {format_version}");
+        if !PhasePlantRelease::is_likely_format_version(format_version) {
             return Err(Error::new(
                 ErrorKind::InvalidData,
                 "Not a Phase Plant preset",
             ));
-        } else if !reader
-            .format_version
-            .is_at_least(&MIN_SUPPORTED_RELEASE.format_version())
-        {
-            let message = format!(
-                "Version {} presets are not supported",
-                reader.format_version
-            );
+        } else if !format_version.is_at_least(&MIN_SUPPORTED_RELEASE.format_version()) {
+            let message = format!("Version {format_version} presets are not supported");
             return Err(Error::new(ErrorKind::InvalidData, message));
         }
+        Ok(())
+    }
+
+    /// Read a preset but fail if it has settings that aren't known, such as a
+    /// filter mode added by a newer version of Phase Plant. [`read`](Self::read)
+    /// keeps unknown values so they can be written back. Also fails on
+    /// numbers that Phase Plant never writes, such as not-a-number, which
+    /// [`read`](Self::read) keeps with a warning.
+    pub fn read_strict<R: Read + Seek>(
+        reader: &mut R,
+        name: Option<String>,
+    ) -> Result<Preset, Error> {
+        let options = ReadOptions {
+            strict: true,
+            ..Default::default()
+        };
+        Self::read_with_options(reader, name, &options)
+    }
+
+    /// Read only the generators of a preset, by value so they can be matched
+    /// on instead of downcast.
+    pub fn read_generator_enums<R: Read + Seek>(
+        reader: &mut R,
+        options: &ReadOptions,
+    ) -> Result<Vec<GeneratorEnum>, Error> {
+        let (_, gen_blocks, _) =
+            Self::read_sections(reader, None, options, &mut ReadProgress::default())?;
+        Ok(Self::generator_enums(&gen_blocks).collect())
+    }
+
+    /// Read a preset and also return the generator and modulator blocks it
+    /// was converted from, including the raw bytes of each block.
+    pub(crate) fn read_with_blocks<R: Read + Seek>(
+        reader: &mut R,
+        name: Option<String>,
+    ) -> Result<(Preset, Vec<GeneratorBlock>, Vec<ModulatorBlock>), Error> {
+        let options = ReadOptions {
+            raw_blocks: true,
+            ..Default::default()
+        };
+        Self::read_sections(reader, name, &options, &mut ReadProgress::default())
+    }
+
+    /// Read a preset, recording what has been read so far in `progress`.
+    pub(crate) fn read_sections<R: Read + Seek>(
+        reader: &mut R,
+        name: Option<String>,
+        options: &ReadOptions,
+        progress: &mut ReadProgress,
+    ) -> Result<(Preset, Vec<GeneratorBlock>, Vec<ModulatorBlock>), Error> {
+        let start_position = reader.stream_position()?;
+        progress.start(PresetSection::Header, start_position);
+        let mut reader = PhasePlantReader::new(reader)?;
+        reader.strict = options.strict;
+        reader.embedded_data = options.embedded_data;
+        reader.limits = options.limits;
+        reader.raw_blocks = options.raw_blocks;
+        let mut embedded_locations = Vec::new();
+
+        //
+        // Header
+        //
+
+        Self::check_format_version(&reader.format_version)?;
+        progress.complete(PresetSection::Header, |preset| {
+            preset.format_version = reader.format_version
+        });
 
         //
         // Metadata
         //
 
+        progress.start(PresetSection::Metadata, reader.stream_position()?);
         let mut metadata = reader.read_metadata()?;
         if metadata.name.is_none() {
             metadata.name = name;
         }
 
         reader.expect_bool32(true, "unknown_read_1")?;
+        progress.complete(PresetSection::Metadata, |preset| {
+            preset.metadata = metadata.clone()
+        });
 
         //
         // Modulation
         //
 
+        progress.start(PresetSection::Modulations, reader.stream_position()?);
         trace!("modulation: start pos {}", reader.pos());
         let modulation_count = reader.read_u32()? as usize;
         if modulation_count > MODULATIONS_MAX {
@@ -407,8 +633,9 @@ impl Preset {
             ));
         }
 
-        let mut modulations = Vec::with_capacity(modulation_count);
-        for modulation_index in 0..modulation_count {
+        // Every slot is read so values left in the unused slots are kept.
+        let mut modulations = Vec::with_capacity(MODULATIONS_MAX);
+        for modulation_index in 0..MODULATIONS_MAX {
             trace!("modulation: index {modulation_index}, pos {}", reader.pos());
 
             let source_id = reader.read_u32()?;
@@ -417,31 +644,33 @@ impl Preset {
             trace!("modulation: source {source_id:#x}, destination = {destination_id:#x}, percent = {amount:?}");
 
             let source: ModulationSource = source_id.into();
-            if let ModulationSource::Unknown { .. } = source {
-                warn!("Unknown modulation source: {source}");
-            }
-
             let destination: ModulationTarget = destination_id.into();
-            if let ModulationTarget::Unknown { .. } = destination {
-                warn!("Unknown modulation destination {destination}");
+            if modulation_index < modulation_count {
+                if let ModulationSource::Unknown { .. } = source {
+                    warn!("Unknown modulation source: {source}");
+                }
+                if let ModulationTarget::Unknown { .. } = destination {
+                    warn!("Unknown modulation destination {destination}");
+                }
             }
 
             // The curve and enabled state are later in the preset.
             modulations.push(Modulation::new(source, destination, amount))
         }
 
-        // Skip over the unused modulations.
-        let unused_modulation_count = MODULATIONS_MAX - modulation_count;
-        trace!("modulation: skipping {unused_modulation_count} unused modulations");
-        reader.skip((12 * unused_modulation_count) as i64)?;
+        let mut unused_modulations = modulations.split_off(modulation_count);
         trace!("modulation: end pos {}", reader.pos());
 
         reader.expect_u32(1, "unknown_m3")?;
+        progress.complete(PresetSection::Modulations, |preset| {
+            preset.modulations = modulations.clone()
+        });
 
         //
         // Lanes
         //
 
+        progress.start(PresetSection::Lanes, reader.stream_position()?);
         let mut lanes = Vec::with_capacity(Lane::COUNT);
         for lane_index in 0..Lane::COUNT {
             trace!("lane {}: pos {}", lane_index, reader.pos());
@@ -468,11 +697,13 @@ impl Preset {
                 mix,
             });
         }
+        progress.complete(PresetSection::Lanes, |preset| preset.lanes = lanes.clone());
 
         //
         // Macro values
         //
 
+        progress.start(PresetSection::MacroControls, reader.stream_position()?);
         // The macros names are later in the file in the string pool.
         trace!("macro controls: pos {}", reader.pos());
         let mut macro_controls = Vec::with_capacity(MacroControl::COUNT);
@@ -490,14 +721,19 @@ impl Preset {
                 .map(|ctrl| ctrl.value)
                 .collect::<Vec<_>>()
         );
+        progress.complete(PresetSection::MacroControls, |preset| {
+            preset.macro_controls = macro_controls.clone()
+        });
 
         //
         // Modulators
         //
 
+        progress.start(PresetSection::Modulators, reader.stream_position()?);
         trace!("modulators: pos {}", reader.pos());
         let mut mod_blocks = Vec::with_capacity(MODULATORS_MAX);
         for _ in 0..MODULATORS_MAX {
+            let block_start_pos = reader.stream_position()?;
             let mode = ModulatorMode::from_id(reader.read_u32()?)?;
 
             let id = reader.read_u32()?;
@@ -524,18 +760,14 @@ impl Preset {
             let depth = reader.read_ratio()?;
 
             // Retrigger was replaced by NoteTriggerMode in Phase Plant 2.
-            let note_trigger_mode = if reader.read_bool32()? {
-                NoteTriggerMode::Auto
-            } else {
-                NoteTriggerMode::Never
-            };
+            let note_trigger_mode = NoteTriggerMode::from_retrigger(reader.read_bool32()?);
 
             let output_range = OutputRange::from_id(reader.read_u32()?)?;
 
             let rate = Rate {
                 frequency: reader.read_hertz()?,
                 numerator: reader.read_u32()?,
-                denominator: NoteValue::from_id(reader.read_u32()?)?,
+                denominator: reader.read_forward_compatible()?,
                 sync: reader.read_bool32()?,
             };
 
@@ -560,7 +792,7 @@ impl Preset {
             // Laser Faller preset in the Polychrome content bank. It does
             // not appear to possible to set this value in the Phase Plant
             // 2.1.0 envelope modulator.
-            let _unknown_1 = reader.read_f32()?;
+            let unknown_1 = reader.read_u32()?;
 
             let random_smooth = reader.read_ratio()?;
             let random_jitter = reader.read_ratio()?;
@@ -583,6 +815,8 @@ impl Preset {
                 random_jitter,
                 random_smooth,
                 random_chaos,
+                raw: reader.read_raw_since(block_start_pos)?,
+                unknown: BTreeMap::from([("unknown_1", unknown_1)]),
                 ..Default::default()
             };
             mod_blocks.push(block);
@@ -598,7 +832,11 @@ impl Preset {
         if !reader.is_release_at_least(PhasePlantRelease::V1_6_10) {
             reader.expect_u32(0, "early_version_extra_1")?;
         }
+        progress.complete(PresetSection::Modulators, |preset| {
+            preset.modulator_containers = Self::modulator_containers(&mod_blocks)
+        });
 
+        progress.start(PresetSection::Settings, reader.stream_position()?);
         let mod_wheel_value = reader.read_ratio()?;
         let master_pitch = reader.read_f32()?;
         let polyphony = reader.read_u32()?;
@@ -611,11 +849,21 @@ impl Preset {
         let glide_legato = reader.read_bool32()?;
         let glide_time = reader.read_f32()?;
         trace!("glide: enabled {glide_enabled}, legato {glide_legato}, time {glide_time}");
+        progress.complete(PresetSection::Settings, |preset| {
+            preset.mod_wheel_value = mod_wheel_value;
+            preset.master_pitch = master_pitch;
+            preset.polyphony = polyphony;
+            preset.retrigger_enabled = retrigger_enabled;
+            preset.glide_enabled = glide_enabled;
+            preset.glide_legato = glide_legato;
+            preset.glide_time = glide_time;
+        });
 
         //
         // Generators
         //
 
+        progress.start(PresetSection::Generators, reader.stream_position()?);
         let mut gen_blocks = Vec::with_capacity(GENERATORS_MAX as usize);
         for gen_index in 0..GENERATORS_MAX {
             let start_pos = reader.stream_position()?;
@@ -660,7 +908,7 @@ impl Preset {
             // Sample player
             let base_pitch = reader.read_f32()?;
             let offset_position = reader.read_ratio()?;
-            let sample_loop_mode = LoopMode::from_id(reader.read_u32()?)?;
+            let sample_loop_mode = reader.read_forward_compatible()?;
             let loop_start_position = reader.read_ratio()?;
             let loop_length = reader.read_ratio()?;
             let crossfade_amount = reader.read_ratio()?;
@@ -680,7 +928,7 @@ impl Preset {
 
             let seed_mode = SeedMode::from_id(reader.read_u32()?)?;
 
-            let filter_mode = FilterMode::from_id(reader.read_u32()?)?;
+            let filter_mode = reader.read_forward_compatible()?;
             let filter_effect = Filter {
                 filter_mode,
                 cutoff: reader.read_hertz()?,
@@ -746,6 +994,7 @@ impl Preset {
                 output_destination,
                 envelope,
                 wavetable_frame,
+                raw: reader.read_raw_since(start_pos)?,
                 ..Default::default()
             };
             gen_blocks.push(block);
@@ -759,11 +1008,17 @@ impl Preset {
                 return Err(Error::new(ErrorKind::InvalidData, msg));
             }
         }
+        progress.complete(PresetSection::Generators, |preset| {
+            preset.generators = Self::generator_enums(&gen_blocks)
+                .map(GeneratorEnum::into_boxed)
+                .collect()
+        });
 
         //
         // Unison
         //
 
+        progress.start(PresetSection::Settings, reader.stream_position()?);
         trace!("global unison: pos {}", reader.pos());
         let unison_voices = reader.read_u32()?;
         if !(1..=Unison::VOICES_MAX).contains(&unison_voices) {
@@ -776,12 +1031,16 @@ impl Preset {
         let unison_spread = reader.read_ratio()?;
         let unison_blend = reader.read_ratio()?;
 
-        let master_gain = reader.read_f32()?;
+        let master_gain = reader.read_linear_gain()?;
+        progress.complete(PresetSection::Settings, |preset| {
+            preset.master_gain = master_gain
+        });
 
         //
         // Lanes
         //
 
+        progress.start(PresetSection::LaneSettings, reader.stream_position()?);
         trace!("lanes: pos {}", reader.stream_position()?);
         for (lane_index, lane) in lanes.iter_mut().enumerate() {
             // How many lanes from left to right are poly.
@@ -801,17 +1060,18 @@ impl Preset {
                 reader.expect_u8(0, "lane_unknown_4")?;
             }
         }
+        progress.complete(PresetSection::LaneSettings, |preset| {
+            preset.lanes = lanes.clone()
+        });
+        progress.start(PresetSection::BlockSettings, reader.stream_position()?);
 
         //
         // Minimized
         //
 
         trace!("modulator: minimized pos {}", reader.pos());
-        for mod_index in 0..MODULATORS_MAX {
-            let minimized = reader.read_bool32()?;
-            if mod_index < mod_blocks.len() {
-                mod_blocks[mod_index].minimized = minimized;
-            }
+        for block in &mut mod_blocks {
+            block.minimized = reader.read_bool32()?;
         }
 
         trace!("generator: minimized pos {}", reader.pos());
@@ -894,6 +1154,7 @@ impl Preset {
         // Unison
         //
 
+        progress.start(PresetSection::Unison, reader.stream_position()?);
         trace!("generator: unison mode pos {}", reader.pos());
         let unison = if reader.is_release_at_least(PhasePlantRelease::V1_8_5) {
             for block in &mut gen_blocks {
@@ -927,6 +1188,8 @@ impl Preset {
             Unison::default()
         };
         debug!("global unison: {:?}", unison);
+        progress.complete(PresetSection::Unison, |preset| preset.unison = unison);
+        progress.start(PresetSection::BlockSettings, reader.stream_position()?);
 
         //
         // Loop enabled
@@ -941,16 +1204,11 @@ impl Preset {
 
         if reader.is_version_at_least_2_0() {
             trace!("modulation: curves pos {}", reader.pos());
-            for modulation in &mut modulations {
+            for modulation in modulations.iter_mut().chain(&mut unused_modulations) {
                 modulation.curve = Ratio::new::<percent>(reader.read_f32()?);
                 modulation.enabled = reader.read_bool32()?;
             }
 
-            // Skip unused modulations. They have not always been reset back
-            // to the default values.
-            let unused_modulation_count = MODULATIONS_MAX - modulations.len();
-            reader.skip((8 * unused_modulation_count) as i64)?;
-
             trace!("macro controls: polarities pos {}", reader.pos());
             for macro_control in &mut macro_controls {
                 macro_control.polarity = OutputRange::from_id(reader.read_u32()?)?;
@@ -984,26 +1242,29 @@ impl Preset {
 
             for mod_block in &mut mod_blocks {
                 reader.expect_f32(1.0, "block_f_unknown_1")?;
-                mod_block.loop_mode = LoopMode::from_id(reader.read_u32()?)?;
+                mod_block.loop_mode = reader.read_forward_compatible()?;
                 reader.expect_u32(0, "block_f_unknown_2")?;
 
                 // These two values are not known. They are general 0.0 and
                 // 1.0 respectively.  They have sometimes changed when
                 // adding a third point to the Slope preset in the Curve
                 // modulator. They often add up to 1.0 but not always.
-                let _unknown_block_f_3 = reader.read_f32()?;
-                let _unknown_block_f_4 = reader.read_f32()?;
+                let unknown_3 = reader.read_u32()?;
+                let unknown_4 = reader.read_u32()?;
+                mod_block.unknown.insert("loop_unknown_3", unknown_3);
+                mod_block.unknown.insert("loop_unknown_4", unknown_4);
             }
 
             for gen in &mut gen_blocks {
                 gen.curve_edited = reader.read_bool32()?;
-                let _curve_block_unknown_1 = reader.read_bool32()?;
+                let curve_unknown_1 = reader.read_u32()?;
+                gen.unknown.insert("curve_unknown_1", curve_unknown_1);
                 reader.expect_f32(1.0, "block_g3_3")?;
                 gen.rate.frequency = reader.read_hertz()?;
                 gen.rate.numerator = reader.read_u32()?;
-                gen.rate.denominator = NoteValue::from_id(reader.read_u32()?)?;
+                gen.rate.denominator = reader.read_forward_compatible()?;
                 gen.rate.sync = reader.read_bool32()?;
-                gen.curve_loop_mode = LoopMode::from_id(reader.read_u32()?)?;
+                gen.curve_loop_mode = reader.read_forward_compatible()?;
                 gen.curve_loop_start = reader.read_ratio()?;
                 gen.curve_loop_length = reader.read_ratio()?;
                 gen.settings_locked = reader.read_bool32()?;
@@ -1014,7 +1275,8 @@ impl Preset {
 
                 // The reciprocal of the rate frequency for the LFO Table
                 // modulator.
-                let _lfo_table_time = reader.read_seconds()?;
+                let lfo_table_time = reader.read_u32()?;
+                mod_block.unknown.insert("lfo_table_time", lfo_table_time);
             }
 
             for gen in &mut gen_blocks {
@@ -1093,11 +1355,16 @@ impl Preset {
 
             // Granular generator
             for gen_block in &mut gen_blocks {
-                let _granular_block_unknown_1 = reader.read_f32()?;
+                let granular_unknown_1 = reader.read_u32()?;
 
                 // Have seen 2 and 4 in these next two positions.
-                let _granular_block_unknown_2 = reader.read_u32()?;
-                let _granular_block_unknown_3 = reader.read_u32()?;
+                let granular_unknown_2 = reader.read_u32()?;
+                let granular_unknown_3 = reader.read_u32()?;
+                gen_block.unknown.extend([
+                    ("granular_unknown_1", granular_unknown_1),
+                    ("granular_unknown_2", granular_unknown_2),
+                    ("granular_unknown_3", granular_unknown_3),
+                ]);
 
                 gen_block.granular_spawn_rate_mode =
                     GranularSpawnRateMode::from_id(reader.read_u32()?)?;
@@ -1124,8 +1391,9 @@ impl Preset {
         // Lanes
         //
 
+        progress.start(PresetSection::Snapins, reader.stream_position()?);
         trace!("lane: start of lanes pos {}", reader.pos());
-        for lane in &mut lanes {
+        for (lane_index, lane) in lanes.iter_mut().enumerate() {
             let snapin_count = reader.read_u32()?;
             for snapin_index in 0..snapin_count {
                 trace!("lane snapin: index {snapin_index}, pos {}", reader.pos());
@@ -1174,60 +1442,12 @@ impl Preset {
                 let effect_length = reader.read_u32()?;
                 let effect_start_pos = reader.stream_position()?;
 
-                let slot_format_major = reader.read_u32()?;
-                if slot_format_major == 1 {
-                    let _header_length = reader.read_u32()?;
-                    let _format_major = reader.read_u32()?;
-                }
-
-                let effect_version = reader.read_u32()?;
-                debug!("lane snapin: slot format {slot_format_major}, host version {host_version}, effect length {effect_length}, start location {effect_start_pos}, is host {}", effect_mode.is_host());
-
-                let effect_read_return = if effect_mode.is_host() {
-                    let format_version_major = reader.read_u32()?;
-                    trace!("lane snapin: host format version {format_version_major}");
-
-                    let metadata = reader.read_metadata()?;
-
-                    let mut effect_read_return =
-                        effect_mode.read_effect(&mut reader, effect_version)?;
-
-                    let effect_remaining = effect_length as i64
-                        - (reader.stream_position()? - effect_start_pos) as i64;
-                    if effect_remaining != 0 {
-                        let msg = format!("Snapin host {effect_mode} version {effect_version} had {effect_remaining} bytes remaining");
-                        return Err(Error::new(ErrorKind::InvalidData, msg));
-                    }
-                    effect_read_return.metadata = metadata;
-                    effect_read_return
-                } else {
-                    let preset_name = if reader.read_bool32()? {
-                        reader.read_string_and_length()?
-                    } else {
-                        None
-                    };
-                    trace!("lane snapin: preset name {preset_name:?}");
-
-                    let mut preset_path = Vec::new();
-                    let mut preset_edited = false;
-                    if slot_format_major > 5 {
-                        preset_path = reader.read_path()?;
-                        let _unknown = reader.read_bool8()?;
-                        preset_edited = reader.read_bool32()?;
-                        trace!("lane snapin: preset path {preset_path:?}, preset edited {preset_edited}");
-                    } else {
-                        let _unknown = reader.read_bool8()?;
-                        reader.expect_u32(0, "lane_snapin_effect_unknown_1")?;
-                    }
-
-                    let mut effect_read_return =
-                        effect_mode.read_effect(&mut reader, effect_version)?;
-
-                    effect_read_return.preset_name = preset_name;
-                    effect_read_return.preset_path = preset_path;
-                    effect_read_return.preset_edited = preset_edited;
-                    effect_read_return
-                };
+                let frame = SnapinFrame::read(&mut reader, &effect_mode)?;
+                let effect_version = frame.effect_version;
+                debug!("lane snapin: host version {host_version}, effect length {effect_length}, start location {effect_start_pos}");
+                let mut effect_read_return =
+                    effect_mode.read_effect(&mut reader, effect_version)?;
+                frame.apply(&effect_mode, &mut effect_read_return);
 
                 let effect_end_pos = reader.stream_position()?;
                 let remaining = effect_length as i64 - (effect_end_pos - effect_start_pos) as i64;
@@ -1236,12 +1456,13 @@ impl Preset {
                     return Err(Error::new(ErrorKind::InvalidData, msg));
                 }
 
+                let state = effect_read_return.state;
                 let snapin = Snapin {
-                    name: name_opt.unwrap().to_string(),
-                    enabled: effect_read_return.enabled,
-                    minimized: effect_read_return.minimized,
+                    name: name_opt.unwrap_or_default(),
+                    enabled: state.enabled,
+                    minimized: state.minimized,
                     id: position,
-                    group_id: effect_read_return.group_id,
+                    group_id: state.group_id,
                     metadata: effect_read_return.metadata,
                     preset_name: effect_read_return.preset_name.unwrap_or_default(),
                     preset_path: effect_read_return.preset_path,
@@ -1251,15 +1472,19 @@ impl Preset {
                     effect: effect_read_return.effect,
                 };
                 debug!("snapin {:?}", snapin);
+                progress.complete(PresetSection::Snapins, |preset| {
+                    preset.lanes[lane_index].snapins.push(snapin.clone())
+                });
                 lane.snapins.push(snapin);
             }
         }
         trace!("lane: end of lanes pos {}", reader.pos());
+        progress.start(PresetSection::BlockSettings, reader.stream_position()?);
 
-        // Audio sources used by the Audio Follower modulator.
+        // Audio sources used by the Audio Follower and Pitch Tracker modulators.
         if reader.is_version_at_least_2_0() {
             for mod_block in &mut mod_blocks {
-                let id = u32::from_le(reader.read_u32()?);
+                let id = reader.read_u32()?;
                 let name = reader.read_string_and_length()?.unwrap_or_default();
                 mod_block.audio_source = AudioSourceId::new(id, name);
             }
@@ -1269,6 +1494,7 @@ impl Preset {
         // String pool
         //
 
+        progress.start(PresetSection::StringPool, reader.stream_position()?);
         trace!("string pool: pos {}", reader.pos());
         let string_pool_len = if reader.is_release_at_least(PhasePlantRelease::V1_8_5) {
             200
@@ -1286,32 +1512,36 @@ impl Preset {
             string_pool.len()
         );
 
+        // Each entry is used once so the strings are moved out of the pool
+        // instead of being copied.
         for (index, gen_block) in gen_blocks.iter_mut().enumerate() {
-            gen_block.sample_name = string_pool[index].to_owned();
+            gen_block.sample_name = string_pool[index].take();
         }
 
         for (index, mod_block) in mod_blocks.iter_mut().enumerate() {
-            mod_block.shape_name = string_pool[index + 32].clone();
+            mod_block.shape_name = string_pool[index + 32].take();
         }
 
         for (index, gen_block) in gen_blocks.iter_mut().enumerate() {
-            let name = string_pool[index + 64].to_owned().unwrap_or_default();
-            if !name.is_empty() {
+            if let Some(name) = string_pool[index + 64].take() {
                 gen_block.name = name;
             }
         }
 
         for (index, macro_control) in macro_controls.iter_mut().enumerate() {
-            macro_control.name = string_pool[index + 96].clone().unwrap_or_default();
+            macro_control.name = string_pool[index + 96].take().unwrap_or_default();
         }
+        progress.complete(PresetSection::StringPool, |preset| {
+            preset.macro_controls = macro_controls.clone()
+        });
 
         for (index, gen_block) in gen_blocks.iter_mut().enumerate() {
-            gen_block.wavetable_name = string_pool[index + 104].to_owned();
+            gen_block.wavetable_name = string_pool[index + 104].take();
         }
 
         if reader.is_release_at_least(PhasePlantRelease::V1_8_0) {
             for (index, mod_block) in mod_blocks.iter_mut().enumerate() {
-                mod_block.shape_path = string_pool[index + 136].clone();
+                mod_block.shape_path = string_pool[index + 136].take();
             }
         }
 
@@ -1336,10 +1566,22 @@ impl Preset {
             }
         }
 
+        // The generators and modulators have all their settings except those
+        // in the data blocks.
+        progress.complete(PresetSection::Generators, |preset| {
+            preset.generators = Self::generator_enums(&gen_blocks)
+                .map(GeneratorEnum::into_boxed)
+                .collect()
+        });
+        progress.complete(PresetSection::Modulators, |preset| {
+            preset.modulator_containers = Self::modulator_containers(&mod_blocks)
+        });
+
         //
         // Data blocks
         //
 
+        progress.start(PresetSection::DataBlocks, reader.stream_position()?);
         trace!(
             "data block: modulators {:?} pos {}",
             mod_blocks
@@ -1350,12 +1592,24 @@ impl Preset {
         );
 
         for mod_block in &mut mod_blocks {
-            // Each modulator has two consecutive data blocks.
+            // Each modulator has two consecutive data blocks. The first holds
+            // the shape. What the second holds isn't known so it is kept as is.
             for data_block_index in 0..2 {
                 let data_pos = reader.stream_position()?;
                 let data_header = reader.read_block_header()?;
-                if data_header.is_used {
+                if data_header.is_used && data_block_index == 0 {
                     mod_block.read_data_block(&mut reader, &data_header)?;
+                } else if data_header.is_used {
+                    trace!(
+                        "data block: modulator {} second data block, header {data_header:?}, pos {data_pos}",
+                        mod_block.mode
+                    );
+                    let mut contents = vec![0u8; data_header.data_length];
+                    reader.read_exact(&mut contents)?;
+                    mod_block.second_data_block = Some(RawDataBlock {
+                        mode_id: data_header.mode_id().unwrap_or_default(),
+                        contents,
+                    });
                 }
 
                 let remaining = -(reader.stream_position()? as i64
@@ -1387,26 +1641,25 @@ impl Preset {
 
             if header.is_used() {
                 let mode_id = header.mode_id().expect("sampler header mode");
-                gen_block.sample_path = reader.read_string_and_length()?;
-
-                if mode_id == 1 {
-                    // No additional data.
-                } else if mode_id == 3 {
-                    reader.expect_u8(0, "sample_player_contents_1")?;
-                } else if mode_id != 2 {
+                let Some(mode) = SampleDataMode::from_repr(mode_id) else {
                     let msg = format!(
-                        "Unknown sample player data block mode {mode_id} at position {}",
+                        "Unknown sample data block mode {mode_id} at position {}",
                         reader.stream_position()? - size_of::<u32>() as u64
                     );
                     return Err(Error::new(ErrorKind::InvalidData, msg));
+                };
+                gen_block.sample_data_mode = Some(mode);
+                gen_block.sample_path = reader.read_string_and_length()?;
+                if mode == SampleDataMode::PathWithFlag {
+                    reader.expect_u8(0, "sample_player_contents_1")?;
                 }
 
                 let remaining = expected_end_pos as i64 - reader.stream_position()? as i64;
                 if remaining != 0 {
-                    let contents_length = reader.read_u32()?;
-                    let mut contents = vec![0u8; contents_length as usize];
-                    reader.read_exact(&mut contents)?;
-                    gen_block.sample_contents = contents;
+                    gen_block.sample_contents = reader.read_embedded(
+                        EmbeddedOwner::Sample(gen_block.id),
+                        &mut embedded_locations,
+                    )?;
                 }
             }
 
@@ -1429,13 +1682,14 @@ impl Preset {
 
             if header.is_used {
                 let mode_id = header.mode_id().expect("wavetable header mode");
-                if mode_id != 1 && mode_id != 3 {
+                let Some(mode) = WavetableDataMode::from_repr(mode_id) else {
                     let msg = format!(
                         "Unknown wavetable data block mode {mode_id} at position {}",
                         reader.stream_position()? - size_of::<u32>() as u64
                     );
                     return Err(Error::new(ErrorKind::InvalidData, msg));
-                }
+                };
+                gen_block.wavetable_data_mode = Some(mode);
 
                 gen_block.wavetable_path = reader.read_string_and_length()?;
                 trace!(
@@ -1444,17 +1698,16 @@ impl Preset {
                     gen_block.sample_name
                 );
 
-                if mode_id == 3 {
+                if mode == WavetableDataMode::PathWithEdited {
                     gen_block.wavetable_edited = reader.read_bool8()?;
                 }
 
                 let remaining = expected_end_pos as i64 - reader.stream_position()? as i64;
                 if remaining != 0 {
-                    let contents_length = reader.read_u32()?;
-                    trace!("data block: wavetable contents length {contents_length}");
-                    let mut contents = vec![0u8; contents_length as usize];
-                    reader.read_exact(&mut contents)?;
-                    gen_block.wavetable_contents = contents;
+                    gen_block.wavetable_contents = reader.read_embedded(
+                        EmbeddedOwner::Wavetable(gen_block.id),
+                        &mut embedded_locations,
+                    )?;
                 }
             }
 
@@ -1477,10 +1730,10 @@ impl Preset {
 
                     let has_contents = reader.read_bool8()?;
                     if has_contents {
-                        let contents_length = reader.read_u32()?;
-                        let mut contents = vec![0u8; contents_length as usize];
-                        reader.read_exact(&mut contents)?;
-                        mod_block.lfo_table_wavetable_contents = contents;
+                        mod_block.lfo_table_wavetable_contents = reader.read_embedded(
+                            EmbeddedOwner::LfoTable(mod_block.id),
+                            &mut embedded_locations,
+                        )?;
                     }
 
                     // The factory preset Tutorials/FM1 in Phase Plant 2.1.0
@@ -1525,82 +1778,27 @@ impl Preset {
             );
         }
 
-        // Convert the modulator blocks to modulators.
-        let mut modulator_containers: Vec<ModulatorContainer> = Vec::with_capacity(MODULATORS_MAX);
-        for block in mod_blocks
-            .iter()
-            .filter(|block| block.mode != ModulatorMode::Blank)
-        {
-            use ModulatorMode::*;
-            let modulator: Box<dyn Modulator> = match block.mode {
-                AudioFollower => Box::new(AudioFollowerModulator::from(block)),
-
-                // Convert the legacy Aftertouch modulator to Pressure.
-                Aftertouch => Box::new(PressureModulator::from(block)),
-
-                Blank => Box::new(BlankModulator::from(block)),
-                Curve => Box::new(CurveModulator::from(block)),
-                Envelope => Box::new(EnvelopeModulator::from(block)),
-                Group => Box::new(modulator::Group::from(block)),
-                Lfo => Box::new(LfoModulator::from(block)),
-                LfoTable => Box::new(LfoTableModulator::from(block)),
-                LowerLimit => Box::new(LowerLimitModulator::from(block)),
-                MidiCc => Box::new(MidiCcModulator::from(block)),
-                MpeTimbre => Box::new(MpeTimbreModulator::from(block)),
-                Note => Box::new(NoteModulator::from(block)),
-                NoteGate => Box::new(NoteGateModulator::from(block)),
-                PitchTracker => Box::new(PitchTrackerModulator::from(block)),
-                PitchWheel => Box::new(PitchWheelModulator::from(block)),
-                Pressure => Box::new(PressureModulator::from(block)),
-                Scale => Box::new(ScaleModulator::from(block)),
-                Random => Box::new(RandomModulator::from(block)),
-                Remap => Box::new(RemapModulator::from(block)),
-                SampleAndHold => Box::new(SampleAndHoldModulator::from(block)),
-                SlewLimiter => Box::new(SlewLimiterModulator::from(block)),
-                UpperLimit => Box::new(UpperLimitModulator::from(block)),
-                Velocity => Box::new(VelocityModulator::from(block)),
-            };
+        let modulator_containers = Self::modulator_containers(&mod_blocks);
 
-            let container = ModulatorContainer {
-                id: block.id,
-                group_id: block.group_id,
-                enabled: block.enabled,
-                minimized: block.minimized,
-                modulator,
-            };
+        let generators: Vec<Box<dyn Generator>> = Self::generator_enums(&gen_blocks)
+            .map(GeneratorEnum::into_boxed)
+            .collect();
 
-            modulator_containers.push(container);
-        }
+        progress.finish(
+            reader.stream_position()? - start_position,
+            reader.embedded_allocations,
+            reader.embedded_bytes,
+        );
 
-        // Convert the generator blocks to generators, removing the Blank generators.
-        // FIXME: Sort by the position.
-        let mut generators: Vec<Box<dyn Generator>> = Vec::with_capacity(GENERATORS_MAX as usize);
-        for block in gen_blocks
-            .iter()
-            .filter(|block| block.mode != GeneratorMode::Blank)
-        {
-            let generator: Box<dyn Generator> = match block.mode {
-                GeneratorMode::AnalogOscillator => Box::new(AnalogOscillator::from(block)),
-                GeneratorMode::AuxRouting => Box::new(AuxRouting::from(block)),
-                GeneratorMode::Blank => Box::new(BlankGenerator::from(block)),
-                GeneratorMode::CurveOutput => Box::new(CurveOutput::from(block)),
-                GeneratorMode::EnvelopeOutput => Box::new(EnvelopeOutput::from(block)),
-                GeneratorMode::DistortionEffect => Box::new(DistortionEffect::from(block)),
-                GeneratorMode::FilterEffect => Box::new(FilterEffect::from(block)),
-                GeneratorMode::GranularGenerator => Box::new(GranularGenerator::from(block)),
-                GeneratorMode::Group => Box::new(generator::Group::from(block)),
-                GeneratorMode::MixRouting => Box::new(MixRouting::from(block)),
-                GeneratorMode::NoiseGenerator => Box::new(NoiseGenerator::from(block)),
-                GeneratorMode::NonlinearFilterGenerator => {
-                    Box::new(NonlinearFilterGenerator::from(block))
-                }
-                GeneratorMode::SamplePlayer => Box::new(SamplePlayer::from(block)),
-                GeneratorMode::WavetableOscillator => Box::new(WavetableOscillator::from(block)),
-            };
-            generators.push(generator);
-        }
+        // Unused slots have not always been reset back to the default values.
+        let unused_modulation_slots = unused_modulations
+            .into_iter()
+            .enumerate()
+            .map(|(index, modulation)| (modulation_count + index, modulation))
+            .filter(|(_, modulation)| !modulation.is_unused())
+            .collect();
 
-        Ok(Preset {
+        let preset = Preset {
             format_version: reader.format_version,
             generators,
             mod_wheel_value,
@@ -1613,20 +1811,59 @@ impl Preset {
             master_pitch,
             metadata,
             modulations,
+            unused_modulation_slots,
             modulator_containers,
             polyphony,
             retrigger_enabled,
             unison,
-        })
+            embedded_locations,
+        };
+        Ok((preset, gen_blocks, mod_blocks))
+    }
+
+    /// Convert the modulator blocks that are used to modulators.
+    fn modulator_containers(mod_blocks: &[ModulatorBlock]) -> Vec<ModulatorContainer> {
+        let mut modulator_containers: Vec<ModulatorContainer> = Vec::with_capacity(MODULATORS_MAX);
+        for block in mod_blocks
+            .iter()
+            .filter(|block| block.mode != ModulatorMode::Blank)
+        {
+            let modulator = block.modulator();
+
+            let container = ModulatorContainer {
+                id: block.id,
+                group_id: block.group_id,
+                enabled: block.enabled,
+                minimized: block.minimized,
+                modulator,
+                second_data_block: block.second_data_block.clone(),
+                unknown: block.unknown.clone(),
+            };
+
+            modulator_containers.push(container);
+        }
+        modulator_containers
+    }
+
+    /// Convert the generator blocks to generators, removing the Blank
+    /// generators.
+    fn generator_enums(gen_blocks: &[GeneratorBlock]) -> impl Iterator<Item = GeneratorEnum> + '_ {
+        // FIXME: Sort by the position.
+        gen_blocks
+            .iter()
+            .filter(|block| block.mode != GeneratorMode::Blank)
+            .map(GeneratorEnum::from)
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::io::Cursor;
     use std::str;
 
     use approx::assert_relative_eq;
 
+    use crate::io::PhasePlantWriter;
     use crate::test::read_preset;
     use crate::tests::test_data_path;
     use crate::*;
@@ -1644,6 +1881,25 @@ mod test {
         assert_relative_eq!(preset.glide_time, 0.042);
     }
 
+    /// Lanes have no sidechain or routing setting other than the destination.
+    /// The bytes after the lane settings are checked to be zero when reading
+    /// so none of the test presets can have one hiding there.
+    #[test]
+    fn lane_padding() {
+        let paths = crate::library::preset_paths(&test_data_path(&[])).unwrap();
+        assert!(paths.len() > 100);
+        for path in paths {
+            let mut file = std::fs::File::open(&path).unwrap();
+            if let Err(error) = Preset::read(&mut file, None) {
+                assert!(
+                    !error.to_string().contains("lane_unknown"),
+                    "{}: {error}",
+                    path.display()
+                );
+            }
+        }
+    }
+
     /// Test all the presets in the init directory. They must have the file
     /// name `init-#.#.#.phaseplant`.
     #[test]
@@ -1748,6 +2004,8 @@ mod test {
         let preset = read_preset("lanes", "lane-1poly-2mute-3solo-2.0.12.phaseplant");
         assert!(preset.lanes[0].enabled);
         assert_eq!(preset.lanes[0].poly_count, 1);
+        assert!(preset.is_lane_poly(0));
+        assert!(!preset.is_lane_poly(1));
         assert!(!preset.lanes[0].mute);
         assert!(!preset.lanes[0].solo);
         assert!(preset.lanes[1].enabled);
@@ -1791,10 +2049,117 @@ mod test {
         );
     }
 
+    #[test]
+    fn invalid_numbers() {
+        let read = |preset: &Preset, strict: bool| {
+            let mut cursor = Cursor::new(Vec::new());
+            preset.write(&mut cursor).unwrap();
+            cursor.set_position(0);
+            let options = ReadOptions {
+                strict,
+                ..Default::default()
+            };
+            Preset::read_with_options(&mut cursor, None, &options)
+        };
+
+        // Turning the master gain all the way down isn't an invalid number.
+        let silent = Preset {
+            master_gain: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(read(&silent, true).unwrap().master_gain, 0.0);
+
+        for preset in [
+            Preset {
+                glide_time: f32::NAN,
+                ..Default::default()
+            },
+            Preset {
+                master_gain: -1.0,
+                ..Default::default()
+            },
+        ] {
+            let error = read(&preset, true).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidData);
+            assert!(error.to_string().starts_with("Invalid number"));
+            assert!(read(&preset, false).is_ok());
+        }
+    }
+
+    #[test]
+    fn metadata_only() {
+        let path = test_data_path(&["misc", "unicode-name-desc-macro-1.8.13.phaseplant"]);
+        let mut file = std::fs::File::open(&path).unwrap();
+        let name = Some("unicode-name-desc-macro-1.8.13".to_owned());
+        let metadata = Preset::read_metadata(&mut file, name).unwrap();
+        assert_eq!(metadata, Preset::read_file(&path).unwrap().metadata);
+
+        let path = test_data_path(&["init", "init-2.2.0.phaseplant"]);
+        let mut file = std::fs::File::open(path).unwrap();
+        let error = Preset::read_metadata(&mut file, None).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Unsupported);
+    }
+
+    /// Read metadata JSON as if it were written by another tool.
+    fn read_metadata_json(json: &[u8]) -> Result<Metadata, Error> {
+        let mut cursor = Cursor::new(Vec::new());
+        let mut writer = PhasePlantWriter::new(&mut cursor);
+        let format_version = PhasePlantRelease::V2_1_0.format_version();
+        writer.write_u32(format_version.major)?;
+        writer.write_u32(format_version.patch)?;
+        writer.write_u32(format_version.minor)?;
+        writer.write_u32(json.len() as u32 + 1)?;
+        writer.write_u8(0)?;
+        writer.write_all_u8(json)?;
+        cursor.set_position(0);
+        Preset::read_metadata(&mut cursor, None)
+    }
+
+    #[test]
+    fn metadata_json() {
+        let metadata = read_metadata_json(br#"{"author":"Author","description":"Bass"}"#).unwrap();
+        assert_eq!(metadata.author, Some("Author".to_owned()));
+        assert_eq!(metadata.description, Some("Bass".to_owned()));
+        assert!(metadata.other_fields.is_empty());
+
+        // Missing fields
+        let metadata = read_metadata_json(b"{}").unwrap();
+        assert_eq!(metadata, Metadata::default());
+        let metadata = read_metadata_json(br#"{"author": null}"#).unwrap();
+        assert_eq!(metadata.author, None);
+
+        // Extra fields, different whitespace and a byte order mark
+        let json = b"\xEF\xBB\xBF{\r\n\t\"rating\" : 5,\"author\":\"Author\",\r\n\t\"tags\":[\"bass\"]}\r\n";
+        let metadata = read_metadata_json(json).unwrap();
+        assert_eq!(metadata.author, Some("Author".to_owned()));
+        assert_eq!(metadata.other_fields.len(), 2);
+        assert_eq!(metadata.other_fields["rating"], 5);
+
+        // The extra fields are written back.
+        let mut preset = Preset {
+            metadata,
+            ..Default::default()
+        };
+        preset.metadata.name = None;
+        let mut cursor = Cursor::new(Vec::new());
+        preset.write(&mut cursor).unwrap();
+        cursor.set_position(0);
+        let rewritten = Preset::read_metadata(&mut cursor, None).unwrap();
+        assert_eq!(rewritten, preset.metadata);
+
+        let error = read_metadata_json(b"{\"author\": ").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+        let error = read_metadata_json(b"[]").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
     #[test]
     fn master_pitch() {
         let preset = read_preset("misc", "master-pitch-12semis-50cents-1.8.13.phaseplant");
         assert_relative_eq!(preset.master_pitch, 12.5);
+        let tuning = preset.master_tuning();
+        assert_eq!(tuning.semitones, 12);
+        assert_relative_eq!(tuning.cents, 50.0);
     }
 
     #[test]
@@ -1913,6 +2278,7 @@ mod test {
     #[test]
     fn unsupported() {
         let path = test_data_path(&["init", "init-2.2.0.phaseplant"]);
-        assert!(Preset::read_file(&path).is_err());
+        let error = Preset::read_file(&path).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Unsupported);
     }
 }
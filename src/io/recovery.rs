@@ -0,0 +1,328 @@
+//! Best-effort reading of truncated or corrupt presets.
+//!
+//! A preset is read from start to end so a problem partway through normally
+//! loses everything. [`Preset::read_with_recovery`] keeps the
+//! [sections](PresetSection) that were read before the problem and reports
+//! the damage.
+
+use std::fmt::{Display, Formatter};
+use std::io::{Error, Read, Seek};
+
+use crate::io::stats::{ReadStats, SectionTimer};
+use crate::Preset;
+
+/// Parts of a preset file in the order they are stored.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum PresetSection {
+    Header,
+    Metadata,
+    Modulations,
+    Lanes,
+    MacroControls,
+    Modulators,
+
+    /// Mod wheel, master gain and pitch, polyphony, retrigger and glide.
+    Settings,
+
+    Generators,
+
+    /// Poly count, mute and solo of each lane.
+    LaneSettings,
+
+    /// Settings of the generators and modulators stored after their blocks.
+    BlockSettings,
+
+    Unison,
+    Snapins,
+
+    /// Names of the generators, modulators and macro controls.
+    StringPool,
+
+    /// Samples, wavetables and the settings added in Phase Plant 2.
+    DataBlocks,
+}
+
+impl Display for PresetSection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use PresetSection::*;
+        f.write_str(match self {
+            Header => "header",
+            Metadata => "metadata",
+            Modulations => "modulations",
+            Lanes => "lanes",
+            MacroControls => "macro controls",
+            Modulators => "modulators",
+            Settings => "settings",
+            Generators => "generators",
+            LaneSettings => "lane settings",
+            BlockSettings => "block settings",
+            Unison => "unison",
+            Snapins => "snapins",
+            StringPool => "string pool",
+            DataBlocks => "data blocks",
+        })
+    }
+}
+
+/// What was lost when a preset could not be read completely.
+#[derive(Debug)]
+pub struct DamageReport {
+    /// The problem that stopped reading.
+    pub error: Error,
+
+    /// Position in the file where reading stopped.
+    pub position: u64,
+
+    /// The section that was being read.
+    pub section: PresetSection,
+
+    /// Sections whose values are in the recovered preset, in file order. The
+    /// snapins are included if any of them could be read. The generators and
+    /// modulators are included once their blocks are read, although the
+    /// settings stored after the blocks have their default values until
+    /// those sections are read too.
+    pub recovered: Vec<PresetSection>,
+}
+
+impl Display for DamageReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Reading stopped in the {} at position {}: {}",
+            self.section, self.position, self.error
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct RecoveredPreset {
+    /// Everything that could be read. Anything that couldn't has its default
+    /// value.
+    pub preset: Preset,
+
+    /// Not set if the whole preset was read.
+    pub damage: Option<DamageReport>,
+}
+
+/// Record what has been read so far. Nothing is recorded unless recovery was
+/// requested so reading presets normally doesn't pay for copies. Sections
+/// are only timed when [statistics](ReadStats) were requested. Where each
+/// section starts in the file is kept when `section_starts` is set.
+#[derive(Default)]
+pub(crate) struct ReadProgress {
+    recording: bool,
+    section: Option<PresetSection>,
+    preset: Preset,
+    recovered: Vec<PresetSection>,
+    timer: Option<SectionTimer>,
+    pub(crate) stats: Option<ReadStats>,
+    pub(crate) section_starts: Option<Vec<(PresetSection, u64)>>,
+}
+
+impl ReadProgress {
+    fn recording() -> Self {
+        Self {
+            recording: true,
+            preset: Preset {
+                lanes: Vec::new(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn timed() -> Self {
+        Self {
+            timer: Some(SectionTimer::new()),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn mapped() -> Self {
+        Self {
+            section_starts: Some(Vec::new()),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn start(&mut self, section: PresetSection, position: u64) {
+        self.section = Some(section);
+        if let Some(timer) = &mut self.timer {
+            timer.start(section);
+        }
+        if let Some(starts) = &mut self.section_starts {
+            starts.push((section, position));
+        }
+    }
+
+    /// Called once the whole preset has been read.
+    pub(crate) fn finish(
+        &mut self,
+        bytes_read: u64,
+        embedded_allocations: usize,
+        embedded_bytes: u64,
+    ) {
+        if let Some(timer) = self.timer.take() {
+            self.stats = Some(timer.finish(bytes_read, embedded_allocations, embedded_bytes));
+        }
+    }
+
+    /// Apply the values read from a section to the recovered preset.
+    pub(crate) fn complete<F: FnOnce(&mut Preset)>(&mut self, section: PresetSection, update: F) {
+        if self.recording {
+            update(&mut self.preset);
+            if !self.recovered.contains(&section) {
+                self.recovered.push(section);
+            }
+        }
+    }
+}
+
+impl Preset {
+    /// Read as much of a preset as possible. If the preset is truncated or
+    /// corrupt the sections read before the problem are returned with a
+    /// report of the damage. Fails if the file isn't a supported Phase Plant
+    /// preset.
+    pub fn read_with_recovery<R: Read + Seek>(
+        reader: &mut R,
+        name: Option<String>,
+    ) -> Result<RecoveredPreset, Error> {
+        let mut progress = ReadProgress::recording();
+        match Self::read_sections(reader, name.clone(), &Default::default(), &mut progress) {
+            Ok((preset, _, _)) => Ok(RecoveredPreset {
+                preset,
+                damage: None,
+            }),
+            Err(error) => {
+                let section = progress.section.unwrap_or(PresetSection::Header);
+                if section == PresetSection::Header {
+                    return Err(error);
+                }
+                let mut preset = progress.preset;
+                if preset.lanes.is_empty() {
+                    preset.lanes = Preset::default().lanes;
+                }
+                if preset.metadata.name.is_none() {
+                    preset.metadata.name = name;
+                }
+                Ok(RecoveredPreset {
+                    preset,
+                    damage: Some(DamageReport {
+                        error,
+                        position: reader.stream_position()?,
+                        section,
+                        recovered: progress.recovered,
+                    }),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::io::{Cursor, ErrorKind};
+
+    use crate::effect::Gain;
+    use crate::tests::test_data_path;
+
+    use super::*;
+
+    fn read_truncated(len: usize) -> Result<RecoveredPreset, Error> {
+        let path = test_data_path(&["effects", "gain", "gain-2.0.16.phaseplant"]);
+        let mut bytes = fs::read(path).unwrap();
+        bytes.truncate(len);
+        Preset::read_with_recovery(&mut Cursor::new(bytes), Some("Truncated".to_owned()))
+    }
+
+    #[test]
+    fn complete() {
+        let recovered = read_truncated(usize::MAX).unwrap();
+        assert!(recovered.damage.is_none());
+        assert!(recovered.preset.lanes[0].snapins[0]
+            .effect
+            .downcast_ref::<Gain>()
+            .is_some());
+    }
+
+    #[test]
+    fn truncated() {
+        let path = test_data_path(&["effects", "gain", "gain-2.0.16.phaseplant"]);
+        let len = fs::metadata(path).unwrap().len() as usize;
+
+        // Missing the end of the data blocks.
+        let recovered = read_truncated(len - 100).unwrap();
+        let damage = recovered.damage.unwrap();
+        assert_eq!(damage.error.kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(damage.section, PresetSection::DataBlocks);
+        assert_eq!(damage.position, len as u64 - 100);
+        assert!(damage.to_string().contains("data blocks"));
+        assert_eq!(
+            damage.recovered,
+            [
+                PresetSection::Header,
+                PresetSection::Metadata,
+                PresetSection::Modulations,
+                PresetSection::Lanes,
+                PresetSection::MacroControls,
+                PresetSection::Modulators,
+                PresetSection::Settings,
+                PresetSection::Generators,
+                PresetSection::LaneSettings,
+                PresetSection::Unison,
+                PresetSection::Snapins,
+                PresetSection::StringPool,
+            ]
+        );
+        let preset = recovered.preset;
+        assert_eq!(preset.metadata.name, Some("Truncated".to_owned()));
+        assert_eq!(preset.lanes.len(), 3);
+        assert!(preset.lanes[0].snapins[0]
+            .effect
+            .downcast_ref::<Gain>()
+            .is_some());
+
+        // Only the header and the start of the metadata.
+        let recovered = read_truncated(40).unwrap();
+        let damage = recovered.damage.unwrap();
+        assert_eq!(damage.section, PresetSection::Metadata);
+        assert_eq!(damage.recovered, [PresetSection::Header]);
+        assert_eq!(recovered.preset.lanes.len(), 3);
+
+        // Not enough to know it's a preset.
+        assert!(read_truncated(8).is_err());
+    }
+
+    #[test]
+    fn generators_and_modulators() {
+        let path = test_data_path(&["modulation", "random-modulates-other-2.1.0.phaseplant"]);
+        let mut bytes = fs::read(path).unwrap();
+        let full = Preset::read(&mut Cursor::new(&bytes), None).unwrap();
+        assert!(!full.generators.is_empty());
+        assert!(!full.modulator_containers.is_empty());
+
+        bytes.truncate(bytes.len() - 100);
+        let recovered = Preset::read_with_recovery(&mut Cursor::new(bytes), None).unwrap();
+        let damage = recovered.damage.unwrap();
+        assert_eq!(damage.section, PresetSection::DataBlocks);
+        assert!(damage.recovered.contains(&PresetSection::Generators));
+        assert!(damage.recovered.contains(&PresetSection::Modulators));
+        let preset = recovered.preset;
+        assert_eq!(preset.generators.len(), full.generators.len());
+        assert!(preset
+            .generators
+            .iter()
+            .zip(&full.generators)
+            .all(|(recovered, full)| recovered.mode() == full.mode()
+                && recovered.name() == full.name()));
+        assert_eq!(
+            preset.modulator_containers.len(),
+            full.modulator_containers.len()
+        );
+        assert_eq!(
+            preset.modulator_containers[0].modulator.mode(),
+            full.modulator_containers[0].modulator.mode()
+        );
+    }
+}
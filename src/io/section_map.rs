@@ -0,0 +1,143 @@
+//! Where each section of a preset is stored in the file.
+//!
+//! Much of the format is still unknown. Knowing which [section](PresetSection)
+//! a region of the file belongs to narrows down which part of the Phase
+//! Plant interface could have written it. [`write_hexdump`] prints the bytes
+//! with each section marked, which is what the `phase-plant-annotate` tool
+//! uses.
+
+use std::io::{Error, Read, Seek, Write};
+use std::ops::Range;
+
+use crate::io::recovery::ReadProgress;
+use crate::io::{PresetSection, ReadOptions};
+use crate::Preset;
+
+/// Bytes shown on each line of a hexdump.
+const HEXDUMP_WIDTH: usize = 16;
+
+/// Part of a file that was read as one section. Some sections, such as the
+/// [block settings](PresetSection::BlockSettings), are stored in several
+/// places.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SectionSpan {
+    pub section: PresetSection,
+
+    /// Positions from the start of the preset.
+    pub range: Range<u64>,
+}
+
+impl Preset {
+    /// Read a preset and return where each section was read from, in file
+    /// order.
+    pub fn read_section_map<R: Read + Seek>(
+        reader: &mut R,
+        options: &ReadOptions,
+    ) -> Result<Vec<SectionSpan>, Error> {
+        let start_position = reader.stream_position()?;
+        let mut progress = ReadProgress::mapped();
+        Self::read_sections(reader, None, options, &mut progress)?;
+        let end_position = reader.stream_position()?;
+
+        let starts = progress.section_starts.unwrap_or_default();
+        let ends = starts
+            .iter()
+            .skip(1)
+            .map(|(_, start)| *start)
+            .chain([end_position]);
+        Ok(starts
+            .iter()
+            .zip(ends)
+            .map(|(&(section, start), end)| SectionSpan {
+                section,
+                range: start - start_position..end - start_position,
+            })
+            .collect())
+    }
+}
+
+/// Print the contents of a preset as rows of hexadecimal bytes, starting a
+/// new row with a heading at each section. Bytes after the last section are
+/// marked as unread.
+pub fn write_hexdump<W: Write>(
+    writer: &mut W,
+    contents: &[u8],
+    spans: &[SectionSpan],
+) -> Result<(), Error> {
+    let mut regions: Vec<(String, Range<usize>)> = spans
+        .iter()
+        .map(|span| {
+            let start = (span.range.start as usize).min(contents.len());
+            let end = (span.range.end as usize).clamp(start, contents.len());
+            (span.section.to_string(), start..end)
+        })
+        .collect();
+    let read = regions.last().map_or(0, |(_, range)| range.end);
+    if read < contents.len() {
+        regions.push(("unread".to_owned(), read..contents.len()));
+    }
+
+    for (name, range) in regions {
+        writeln!(
+            writer,
+            "== {name} at {:#x}, {} bytes",
+            range.start,
+            range.len()
+        )?;
+        for (row, bytes) in contents[range.clone()].chunks(HEXDUMP_WIDTH).enumerate() {
+            let hex: Vec<String> = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+            let text: String = bytes
+                .iter()
+                .map(|&byte| {
+                    if byte.is_ascii_graphic() || byte == b' ' {
+                        byte as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            writeln!(
+                writer,
+                "{:08x}  {:<width$}  |{text}|",
+                range.start + row * HEXDUMP_WIDTH,
+                hex.join(" "),
+                width = HEXDUMP_WIDTH * 3 - 1
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::io::Cursor;
+
+    use crate::tests::test_data_path;
+
+    use super::*;
+
+    #[test]
+    fn section_map() {
+        let path = test_data_path(&["effects", "gain", "gain-2.0.16.phaseplant"]);
+        let contents = fs::read(path).unwrap();
+        let spans =
+            Preset::read_section_map(&mut Cursor::new(&contents), &ReadOptions::default()).unwrap();
+        assert_eq!(spans[0].section, PresetSection::Header);
+        assert_eq!(spans[0].range.start, 0);
+        assert_eq!(spans[1].section, PresetSection::Metadata);
+        assert_eq!(spans[1].range.start, 12);
+        assert!(spans
+            .windows(2)
+            .all(|pair| pair[0].range.end == pair[1].range.start));
+        assert_eq!(spans.last().unwrap().section, PresetSection::DataBlocks);
+        assert_eq!(spans.last().unwrap().range.end, contents.len() as u64);
+
+        let mut dump = Vec::new();
+        write_hexdump(&mut dump, &contents, &spans).unwrap();
+        let dump = String::from_utf8(dump).unwrap();
+        assert!(dump.starts_with("== header at 0x0, 12 bytes\n00000000  "));
+        assert!(dump.contains("\n== metadata at 0xc, "));
+        assert!(!dump.contains("unread"));
+    }
+}
@@ -0,0 +1,319 @@
+//! The start of every snapin's effect data, before the effect itself.
+//!
+//! The layout depends on the slot format stored first and on whether the
+//! effect hosts other snapins. Effects that are hosts store metadata instead
+//! of a preset name, and read their preset name themselves.
+
+use std::io::{Error, ErrorKind, Read, Seek, Write};
+
+use log::trace;
+
+use crate::effect::EffectMode;
+use crate::io::EffectReadReturn;
+use crate::*;
+
+/// The known layouts of the start of a snapin.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum SlotFormat {
+    /// Used by Multipass and Snap Heap. A header length and a format version
+    /// follow.
+    Container,
+
+    /// Phase Plant 1.7, before factory preset paths were stored.
+    Version5,
+
+    /// Phase Plant 1.8 and later.
+    Version6,
+}
+
+impl SlotFormat {
+    pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
+        match id {
+            1 => Ok(SlotFormat::Container),
+            5 => Ok(SlotFormat::Version5),
+            6 => Ok(SlotFormat::Version6),
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unknown snapin slot format {id}"),
+            )),
+        }
+    }
+
+    pub(crate) fn id(&self) -> u32 {
+        match self {
+            SlotFormat::Container => 1,
+            SlotFormat::Version5 => 5,
+            SlotFormat::Version6 => 6,
+        }
+    }
+
+    /// The format Phase Plant 2 uses for an effect.
+    pub(crate) fn for_effect(effect_mode: EffectMode) -> Self {
+        match effect_mode {
+            EffectMode::Multipass | EffectMode::SnapHeap => SlotFormat::Container,
+            _ => SlotFormat::Version6,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SnapinFrame {
+    pub slot_format: SlotFormat,
+    pub effect_version: u32,
+
+    /// Only stored for hosts.
+    pub metadata: Metadata,
+
+    /// Not stored for hosts, they store it themselves.
+    pub preset_name: Option<String>,
+
+    /// Only stored by [`SlotFormat::Version6`] for effects that aren't hosts.
+    pub preset_path: Vec<String>,
+    pub preset_edited: bool,
+}
+
+impl SnapinFrame {
+    /// The frame of a snapin that is about to be written.
+    pub(crate) fn new(snapin: &Snapin) -> Self {
+        Self {
+            slot_format: SlotFormat::for_effect(snapin.effect.mode()),
+            effect_version: snapin.effect_version,
+            metadata: snapin.metadata.clone(),
+            preset_name: Some(snapin.preset_name.clone()),
+            preset_path: snapin.preset_path.clone(),
+            preset_edited: snapin.preset_edited,
+        }
+    }
+
+    pub(crate) fn read<R: Read + Seek>(
+        reader: &mut PhasePlantReader<R>,
+        effect_mode: &EffectMode,
+    ) -> Result<Self, Error> {
+        let slot_format = SlotFormat::from_id(reader.read_u32()?)?;
+        if slot_format == SlotFormat::Container {
+            if !effect_mode.is_host() {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Snapin slot format {} is only used by hosts, not {effect_mode}",
+                        slot_format.id()
+                    ),
+                ));
+            }
+            let _header_length = reader.read_u32()?;
+            let _format_major = reader.read_u32()?;
+        }
+
+        let effect_version = reader.read_u32()?;
+        trace!(
+            "snapin frame: slot format {slot_format:?}, effect version {effect_version}, is host {}",
+            effect_mode.is_host()
+        );
+        let mut frame = Self {
+            slot_format,
+            effect_version,
+            metadata: Metadata::default(),
+            preset_name: None,
+            preset_path: Vec::new(),
+            preset_edited: false,
+        };
+
+        if effect_mode.is_host() {
+            let format_version_major = reader.read_u32()?;
+            trace!("snapin frame: host format version {format_version_major}");
+            frame.metadata = reader.read_metadata()?;
+            return Ok(frame);
+        }
+
+        if reader.read_bool32()? {
+            frame.preset_name = reader.read_string_and_length()?;
+        }
+        match slot_format {
+            SlotFormat::Version6 => {
+                frame.preset_path = reader.read_path()?;
+                let _unknown = reader.read_bool8()?;
+                frame.preset_edited = reader.read_bool32()?;
+            }
+            _ => {
+                let _unknown = reader.read_bool8()?;
+                reader.expect_u32(0, "lane_snapin_effect_unknown_1")?;
+            }
+        }
+        trace!(
+            "snapin frame: preset name {:?}, path {:?}, edited {}",
+            frame.preset_name,
+            frame.preset_path,
+            frame.preset_edited
+        );
+        Ok(frame)
+    }
+
+    /// The header length of a [`SlotFormat::Container`] is written as zero
+    /// because its meaning isn't known. It is ignored when reading.
+    pub(crate) fn write<W: Write + Seek>(
+        &self,
+        writer: &mut PhasePlantWriter<W>,
+        effect_mode: &EffectMode,
+    ) -> Result<(), Error> {
+        writer.write_u32(self.slot_format.id())?;
+        if self.slot_format == SlotFormat::Container {
+            writer.write_u32(0)?; // Header length
+            writer.write_u32(6)?; // Format version
+        }
+        writer.write_u32(self.effect_version)?;
+
+        if effect_mode.is_host() {
+            writer.write_u32(1)?; // Host format version
+            return writer.write_metadata(&self.metadata);
+        }
+
+        writer.write_bool32(true)?;
+        writer.write_string_and_length(self.preset_name.as_deref().unwrap_or_default())?;
+        match self.slot_format {
+            SlotFormat::Version6 => {
+                writer.write_path(&self.preset_path)?;
+                writer.write_bool8(false)?;
+                writer.write_bool32(self.preset_edited)
+            }
+            _ => {
+                writer.write_bool8(false)?;
+                writer.write_u32(0)
+            }
+        }
+    }
+
+    /// Apply the values stored in the frame to what the effect read. Hosts
+    /// read their own preset name.
+    pub(crate) fn apply(self, effect_mode: &EffectMode, effect_read_return: &mut EffectReadReturn) {
+        if effect_mode.is_host() {
+            effect_read_return.metadata = self.metadata;
+        } else {
+            effect_read_return.preset_name = self.preset_name;
+            effect_read_return.preset_path = self.preset_path;
+            effect_read_return.preset_edited = self.preset_edited;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use crate::effect::{CarveEq, Faturator, Gain, Multipass};
+    use crate::test::read_effect_preset;
+
+    use super::*;
+
+    fn rewrite(frame: &SnapinFrame, effect_mode: &EffectMode) -> Result<SnapinFrame, Error> {
+        let mut cursor = Cursor::new(Vec::new());
+        let mut writer = PhasePlantWriter::new(&mut cursor);
+        let format_version = PhasePlantRelease::V2_1_0.format_version();
+        writer.write_u32(format_version.major)?;
+        writer.write_u32(format_version.patch)?;
+        writer.write_u32(format_version.minor)?;
+        frame.write(&mut writer, effect_mode)?;
+        cursor.set_position(0);
+        let mut reader = PhasePlantReader::new(cursor)?;
+        SnapinFrame::read(&mut reader, effect_mode)
+    }
+
+    #[test]
+    fn slot_formats() {
+        for slot_format in [
+            SlotFormat::Container,
+            SlotFormat::Version5,
+            SlotFormat::Version6,
+        ] {
+            assert_eq!(SlotFormat::from_id(slot_format.id()).unwrap(), slot_format);
+        }
+        let error = SlotFormat::from_id(7).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            SlotFormat::for_effect(EffectMode::Multipass),
+            SlotFormat::Container
+        );
+        assert_eq!(
+            SlotFormat::for_effect(EffectMode::CarveEq),
+            SlotFormat::Version6
+        );
+    }
+
+    #[test]
+    fn rewrite_frames() {
+        let snapin = Snapin {
+            preset_name: "Bass Driver".to_owned(),
+            preset_path: vec!["factory".to_owned(), "Bass Driver.ksfa".to_owned()],
+            preset_edited: true,
+            ..Snapin::new(Box::<Faturator>::default(), 1, true, false)
+        };
+        let frame = SnapinFrame::new(&snapin);
+        assert_eq!(frame.slot_format, SlotFormat::Version6);
+        assert_eq!(rewrite(&frame, &EffectMode::Faturator).unwrap(), frame);
+
+        // Phase Plant 1.7 didn't store the path.
+        let frame = SnapinFrame {
+            slot_format: SlotFormat::Version5,
+            preset_path: Vec::new(),
+            preset_edited: false,
+            ..frame
+        };
+        assert_eq!(rewrite(&frame, &EffectMode::Faturator).unwrap(), frame);
+
+        // Hosts store metadata instead of the preset.
+        let mut snapin = Snapin::new(Box::<Multipass>::default(), 1, true, false);
+        snapin.metadata.author = Some("Author".to_owned());
+        let frame = SnapinFrame::new(&snapin);
+        assert_eq!(frame.slot_format, SlotFormat::Container);
+        let read = rewrite(&frame, &EffectMode::Multipass).unwrap();
+        assert_eq!(read.slot_format, SlotFormat::Container);
+        assert_eq!(read.metadata.author, Some("Author".to_owned()));
+        assert_eq!(read.preset_name, None);
+
+        let frame = SnapinFrame::new(&Snapin::new(Box::<CarveEq>::default(), 1, true, false));
+        assert_eq!(
+            rewrite(&frame, &EffectMode::CarveEq).unwrap().slot_format,
+            SlotFormat::Version6
+        );
+
+        // Only hosts use the container format.
+        let frame = SnapinFrame {
+            slot_format: SlotFormat::Container,
+            ..SnapinFrame::new(&Snapin::new(Box::<Gain>::default(), 1, true, false))
+        };
+        let error = rewrite(&frame, &EffectMode::Gain).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    /// A preset from each generation of the slot format.
+    #[test]
+    fn generations() {
+        // Version 5
+        let preset =
+            read_effect_preset("faturator", "faturator-bass_driver-1.7.0.phaseplant").unwrap();
+        assert!(preset.lanes[0].snapins[0]
+            .preset_name
+            .ends_with("Bass Driver.kfat"));
+        assert!(preset.lanes[0].snapins[0].preset_path.is_empty());
+        let preset =
+            read_effect_preset("carve_eq", "carve_eq-preset_name-1.7.0.phaseplant").unwrap();
+        assert_eq!(
+            preset.lanes[0].snapins[0].effect.mode(),
+            EffectMode::CarveEq
+        );
+
+        // Version 6
+        let preset = read_effect_preset("haas", "haas-small_width-1.8.13.phaseplant").unwrap();
+        assert_eq!(
+            preset.lanes[0].snapins[0].preset_path,
+            ["factory", "Small Width.ksha"]
+        );
+
+        // Container
+        let preset =
+            read_effect_preset("multipass", "multipass-metadata-2.1.0.phaseplant").unwrap();
+        assert_eq!(
+            preset.lanes[0].snapins[0].effect.mode(),
+            EffectMode::Multipass
+        );
+    }
+}
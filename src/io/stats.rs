@@ -0,0 +1,150 @@
+//! Measurements of reading a preset.
+//!
+//! [`Preset::read_with_stats`] reports how much of the file was read, how
+//! long each [section](PresetSection) took and how much memory went to
+//! embedded samples and wavetables. Pipelines that read large libraries can
+//! use them to find the presets that are slow or expensive to read.
+
+use std::collections::BTreeMap;
+use std::io::{Error, Read, Seek};
+use std::time::{Duration, Instant};
+
+use crate::io::recovery::ReadProgress;
+use crate::io::{PresetSection, ReadOptions};
+use crate::Preset;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReadStats {
+    /// Bytes from the start of the preset to where reading finished,
+    /// including embedded data that was skipped.
+    pub bytes_read: u64,
+
+    pub duration: Duration,
+
+    /// Sections that are stored in several places in the file are added
+    /// together.
+    pub section_durations: BTreeMap<PresetSection, Duration>,
+
+    /// Number of samples and wavetables whose contents were read into memory.
+    pub embedded_allocations: usize,
+
+    /// Total size of the embedded contents read into memory.
+    pub embedded_bytes: u64,
+}
+
+impl ReadStats {
+    /// The section that took the longest to read.
+    pub fn slowest_section(&self) -> Option<(PresetSection, Duration)> {
+        self.section_durations
+            .iter()
+            .max_by_key(|(_, duration)| **duration)
+            .map(|(section, duration)| (*section, *duration))
+    }
+}
+
+/// Time the sections as they are read.
+pub(crate) struct SectionTimer {
+    started: Instant,
+    current: Option<(PresetSection, Instant)>,
+    stats: ReadStats,
+}
+
+impl SectionTimer {
+    pub(crate) fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            current: None,
+            stats: ReadStats::default(),
+        }
+    }
+
+    pub(crate) fn start(&mut self, section: PresetSection) {
+        let now = Instant::now();
+        self.stop(now);
+        self.current = Some((section, now));
+    }
+
+    fn stop(&mut self, now: Instant) {
+        if let Some((section, started)) = self.current.take() {
+            *self.stats.section_durations.entry(section).or_default() += now - started;
+        }
+    }
+
+    pub(crate) fn finish(
+        mut self,
+        bytes_read: u64,
+        embedded_allocations: usize,
+        embedded_bytes: u64,
+    ) -> ReadStats {
+        let now = Instant::now();
+        self.stop(now);
+        ReadStats {
+            bytes_read,
+            duration: now - self.started,
+            embedded_allocations,
+            embedded_bytes,
+            ..self.stats
+        }
+    }
+}
+
+impl Preset {
+    /// Read a preset like [`read_with_options`](Self::read_with_options) and
+    /// measure the reading.
+    pub fn read_with_stats<R: Read + Seek>(
+        reader: &mut R,
+        name: Option<String>,
+        options: &ReadOptions,
+    ) -> Result<(Preset, ReadStats), Error> {
+        let mut progress = ReadProgress::timed();
+        let (preset, _, _) = Self::read_sections(reader, name, options, &mut progress)?;
+        let stats = progress.stats.take().unwrap_or_default();
+        Ok((preset, stats))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::File;
+    use std::io::BufReader;
+
+    use crate::io::EmbeddedData;
+    use crate::tests::test_data_path;
+
+    use super::*;
+
+    fn read(path: &[&str], embedded_data: EmbeddedData) -> ReadStats {
+        let path = test_data_path(path);
+        let mut reader = BufReader::new(File::open(&path).unwrap());
+        let options = ReadOptions {
+            embedded_data,
+            ..Default::default()
+        };
+        let (preset, stats) = Preset::read_with_stats(&mut reader, None, &options).unwrap();
+        assert!(!preset.lanes.is_empty());
+        assert_eq!(stats.bytes_read, path.metadata().unwrap().len());
+        stats
+    }
+
+    #[test]
+    fn read_stats() {
+        let path = [
+            "generators",
+            "sample_player",
+            "sample_player-custom-sample-1.8.16.phaseplant",
+        ];
+        let stats = read(&path, EmbeddedData::Full);
+        assert_eq!(stats.embedded_allocations, 1);
+        assert!(stats.embedded_bytes > 0);
+        assert!(stats
+            .section_durations
+            .contains_key(&PresetSection::DataBlocks));
+        assert!(stats.slowest_section().is_some());
+        let sections: Duration = stats.section_durations.values().sum();
+        assert!(sections <= stats.duration);
+
+        let stats = read(&path, EmbeddedData::Skip);
+        assert_eq!(stats.embedded_allocations, 0);
+        assert_eq!(stats.embedded_bytes, 0);
+    }
+}
@@ -3,8 +3,11 @@
 //! All presets are upgraded to the most currently supported file format when
 //! written.
 
-use std::io::{Error, ErrorKind, Result, Seek, SeekFrom, Write};
+use std::borrow::Cow;
+use std::fs;
+use std::io::{Cursor, Error, ErrorKind, Result, Seek, SeekFrom, Write};
 use std::mem::size_of;
+use std::path::Path;
 
 use byteorder::{LittleEndian, WriteBytesExt};
 use log::{trace, Level};
@@ -12,23 +15,23 @@ use serde::Serialize;
 use serde_json::ser::PrettyFormatter;
 use serde_json::Serializer;
 use uom::si::frequency::hertz;
-use uom::si::ratio::ratio;
+use uom::si::ratio::{percent, ratio};
 use uom::si::time::second;
 
 use crate::generator::{BlankGenerator, Generator, GeneratorMode, Group};
 use crate::io::generators::GeneratorBlock;
 use crate::io::modulators::ModulatorBlock;
+use crate::io::read::READ_STRING_LENGTH_MAX;
+use crate::io::snapin_frame::SnapinFrame;
 use crate::io::MetadataJson;
 use crate::modulation::*;
-use crate::modulator::{BlankModulator, Modulator};
-use crate::text::HashTag;
+use crate::modulator::{BlankModulator, Modulator, ModulatorContainer, ModulatorMode, OutputRange};
+use crate::text::{is_valid_name, sanitize_name, truncate_chars, HashTag};
 use crate::*;
 
 /// Files are written the same as this version of Phase Plant.
 pub(crate) const WRITE_SAME_AS: PhasePlantRelease = PhasePlantRelease::V2_1_0;
 
-const FORMAT_VERSION: Version<u32> = WRITE_SAME_AS.format_version();
-
 const STRING_POOL_COUNT: usize = 200;
 
 pub struct Message {
@@ -45,6 +48,21 @@ impl Display for Message {
     }
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct WriteOptions {
+    /// Bring values that are outside of the ranges Phase Plant accepts back
+    /// into range instead of writing them as they are. A warning is returned
+    /// for each value that is changed. See [`Preset::clamp_out_of_range`].
+    pub clamp_out_of_range: bool,
+
+    /// Write the preset in the layout of the release it was read from
+    /// instead of the latest supported release, so hosts that check the
+    /// version load it the same way. Only Phase Plant 2.0 and 2.1.0 layouts
+    /// can be written. Other presets, and presets that use features newer
+    /// than their release, are written as the latest release with a warning.
+    pub preserve_format_version: bool,
+}
+
 pub struct WritePresetResult {
     pub messages: Vec<Message>,
 }
@@ -85,9 +103,19 @@ impl<T: Write + Seek> PhasePlantWriter<T> {
             .unwrap_or_else(|_| "<unknown>".to_owned())
     }
 
+    /// The mode is only written if the header has one.
     pub(crate) fn write_block_header(&mut self, header: &DataBlockHeader) -> Result<()> {
-        self.write_u32(header.data_length as u32 + 1)?; // Include `used` field
-        self.write_bool8(header.is_used)
+        match header.mode_id {
+            Some(mode_id) => {
+                self.write_u32(header.data_length as u32 + 5)?; // Include `used` and mode
+                self.write_bool8(header.is_used)?;
+                self.write_u32(mode_id)
+            }
+            None => {
+                self.write_u32(header.data_length as u32 + 1)?; // Include `used` field
+                self.write_bool8(header.is_used)
+            }
+        }
     }
 
     pub(crate) fn write_bool8(&mut self, value: bool) -> Result<()> {
@@ -156,9 +184,22 @@ impl<T: Write + Seek> PhasePlantWriter<T> {
         Ok(())
     }
 
+    /// The length is in bytes of UTF-8. Fails if the text is too long to be
+    /// read back.
     pub(crate) fn write_string_and_length<S: AsRef<str>>(&mut self, value: S) -> Result<()> {
-        self.write_u32(value.as_ref().len() as u32)?;
-        self.write_all_u8(value.as_ref().as_bytes())
+        let value = value.as_ref();
+        if value.len() > READ_STRING_LENGTH_MAX as usize {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Text of {} bytes exceeds the limit of {READ_STRING_LENGTH_MAX} bytes: {}...",
+                    value.len(),
+                    truncate_chars(value, 20)
+                ),
+            ));
+        }
+        self.write_u32(value.len() as u32)?;
+        self.write_all_u8(value.as_bytes())
     }
 
     pub(crate) fn write_string_and_length_opt(&mut self, value: &Option<String>) -> Result<()> {
@@ -172,6 +213,20 @@ impl<T: Write + Seek> PhasePlantWriter<T> {
         self.inner.write_all(buf)
     }
 
+    /// The point count followed by each point, as stored in the shape of
+    /// a modulator and the curve of a Curve Output.
+    pub(crate) fn write_curve_points(&mut self, points: &[CurvePoint]) -> Result<()> {
+        self.write_u32(points.len() as u32)?;
+        for point in points {
+            self.write_f32(point.x)?;
+            self.write_f32(point.y)?;
+            self.write_f32(point.curve_x)?;
+            self.write_f32(point.curve_y)?;
+            self.write_u32(point.mode as u32)?;
+        }
+        Ok(())
+    }
+
     pub(crate) fn write_envelope(&mut self, envelope: &Envelope) -> Result<()> {
         self.write_seconds(envelope.delay)?;
         self.write_seconds(envelope.attack)?;
@@ -179,10 +234,48 @@ impl<T: Write + Seek> PhasePlantWriter<T> {
         self.write_seconds(envelope.hold)?;
         self.write_seconds(envelope.decay)?;
         self.write_f32(envelope.decay_falloff)?;
-        self.write_ratio(envelope.sustain)?;
+        self.write_f32(envelope.sustain.get::<percent>())?;
         self.write_seconds(envelope.release)?;
         self.write_f32(envelope.release_falloff)
     }
+
+    /// The description, category and author as JSON.
+    pub(crate) fn write_metadata(&mut self, metadata: &Metadata) -> Result<()> {
+        // Phase Plant traditionally uses hashtags at the end of the description for categorization.
+        let mut description = String::with_capacity(64);
+        if let Some(desc) = &metadata.description {
+            description.push_str(desc.as_str())
+        }
+        if let Some(cat) = &metadata.category {
+            if !description.is_empty() {
+                description.push(' ');
+            }
+            description.push_str(<dyn HashTag>::from_lossy(cat).as_str())
+        }
+
+        let metadata = MetadataJson {
+            description: Some(description), // Use a blank string instead of null
+            author: metadata.author.clone().or_else(|| Some(String::new())),
+            other_fields: metadata.other_fields.clone(),
+        };
+
+        // Use the same spacing as Phase Plant so the files match as closely as
+        // possibles to make comparing the files easier.
+        let mut metadata_json = Vec::with_capacity(128);
+        let formatter = PrettyFormatter::with_indent(b"    ");
+        let mut serializer = Serializer::with_formatter(&mut metadata_json, formatter);
+        metadata.serialize(&mut serializer)?;
+        metadata_json.push(b'\n');
+
+        // Use the exact metadata from test presets when comparing during debugging. serde
+        // doesn't use the same formatting as Phase Plant.
+        // let metadata_json = "{\n    \"description\": \"\",\n    \"author\": \"softdev.ca\"\n}\n";
+        // let metadata_json = "{\n    \"description\": \"\",\n    \"author\": \"\"\n}\n";
+
+        self.write_u32(metadata_json.len() as u32 + 1)?;
+        self.write_u8(0)?; // Unknown value, always 0
+        self.write_all_u8(&metadata_json)
+    }
 }
 
 impl GeneratorBlock {
@@ -223,7 +316,7 @@ impl GeneratorBlock {
         );
         writer.write_f32(self.base_pitch)?;
         writer.write_ratio(self.offset_position)?;
-        writer.write_u32(self.sample_loop_mode as u32)?;
+        writer.write_u32(self.sample_loop_mode.id())?;
         writer.write_ratio(self.loop_start_position)?;
         writer.write_ratio(self.loop_length)?;
         writer.write_ratio(self.crossfade_amount)?;
@@ -241,7 +334,7 @@ impl GeneratorBlock {
         writer.write_u32(self.noise_waveform as u32)?;
 
         trace!("generator: filter effect pos {}", writer.pos_text());
-        writer.write_u32(self.filter_effect.filter_mode as u32)?;
+        writer.write_u32(self.filter_effect.filter_mode.id())?;
         writer.write_hertz(self.filter_effect.cutoff)?;
         writer.write_f32(self.filter_effect.q)?;
         writer.write_decibels_linear(self.filter_effect.gain)?;
@@ -255,63 +348,268 @@ impl GeneratorBlock {
         writer.write_ratio(self.mix_level)?;
         writer.write_decibels_linear(self.output_gain)?;
         writer.write_ratio(self.pan)?;
-        writer.write_u32(self.output_destination as u32)?;
+        writer.write_u32(self.output_destination.id())?;
 
         trace!("generator: envelope pos {}", writer.pos_text());
         writer.write_envelope(&self.envelope)
     }
 }
 
+/// Describe the entities that don't fit in the file.
+fn over_limit<T, F: Fn(&T) -> String>(
+    kind: &str,
+    items: &[T],
+    max: usize,
+    describe: F,
+) -> Option<String> {
+    if items.len() <= max {
+        return None;
+    }
+    let extra = items[max..]
+        .iter()
+        .enumerate()
+        .map(|(index, item)| format!("{} {}", max + index + 1, describe(item)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(format!(
+        "{} {kind} is more than {max}, the extra {kind} are {extra}",
+        items.len()
+    ))
+}
+
 impl Preset {
-    pub fn write<W: Write + Seek>(&self, writer: &mut W) -> Result<WritePresetResult> {
-        let mut writer = PhasePlantWriter::new(writer);
+    /// The modulation to write to a slot.
+    fn modulation_slot<'a>(&'a self, index: usize, unused: &'a Modulation) -> &'a Modulation {
+        self.modulations
+            .get(index)
+            .or_else(|| self.unused_modulation_slots.get(&index))
+            .unwrap_or(unused)
+    }
 
-        //
-        // Header
-        //
+    /// Check that the preset doesn't have more generators, modulators,
+    /// modulations or macro controls than Phase Plant supports. The error
+    /// lists every entity that doesn't fit. Presets are checked before they
+    /// are written.
+    ///
+    /// Modulators are written to the slot of their ID, so an ID that is
+    /// used twice or doesn't have a slot fails with
+    /// [`ErrorKind::InvalidInput`].
+    pub fn check_limits(&self) -> Result<()> {
+        let problems: Vec<String> = [
+            over_limit(
+                "generators",
+                &self.generators,
+                GENERATORS_MAX as usize,
+                |generator| format!("\"{}\" ({})", generator.name(), generator.mode().name()),
+            ),
+            over_limit(
+                "modulators",
+                &self.modulator_containers,
+                MODULATORS_MAX,
+                |container| format!("{} (ID {})", container.modulator.mode(), container.id),
+            ),
+            over_limit(
+                "modulations",
+                &self.modulations,
+                MODULATIONS_MAX,
+                |modulation| format!("({modulation})"),
+            ),
+            over_limit(
+                "macro controls",
+                &self.macro_controls,
+                MacroControl::COUNT,
+                |macro_control| format!("\"{}\"", macro_control.name),
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if problems.is_empty() {
+            self.check_modulator_ids()
+        } else {
+            Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Preset exceeds the limits of Phase Plant: {}",
+                    problems.join("; ")
+                ),
+            ))
+        }
+    }
 
-        // All of the presets are written with the currently supported version.
-        writer.write_u32(FORMAT_VERSION.major)?;
-        writer.write_u32(FORMAT_VERSION.patch)?;
-        writer.write_u32(FORMAT_VERSION.minor)?;
+    fn check_modulator_ids(&self) -> Result<()> {
+        let mut used = [false; MODULATORS_MAX];
+        for container in &self.modulator_containers {
+            let id = container.id as usize;
+            let message = match used.get_mut(id) {
+                Some(true) => format!("Modulator ID {id} is used more than once"),
+                Some(used) => {
+                    *used = true;
+                    continue;
+                }
+                None => format!("Modulator ID {id} is not less than {MODULATORS_MAX}"),
+            };
+            return Err(Error::new(ErrorKind::InvalidInput, message));
+        }
+        Ok(())
+    }
 
-        // Phase Plant traditionally uses hashtags at the end of the description for categorization.
-        let mut description = String::with_capacity(64);
-        if let Some(desc) = &self.metadata.description {
-            description.push_str(desc.as_str())
+    /// Fail if a name that Phase Plant shows contains control characters,
+    /// such as line breaks. [`sanitize_names`](Self::sanitize_names) removes
+    /// them.
+    pub fn check_names(&self) -> Result<()> {
+        let names = self
+            .lanes
+            .iter()
+            .flat_map(|lane| &lane.snapins)
+            .flat_map(|snapin| {
+                let group_name = snapin
+                    .effect
+                    .as_group()
+                    .and_then(|group| group.name.clone());
+                [Some(snapin.name.clone()), group_name]
+            })
+            .flatten()
+            .chain(self.generators.iter().map(|generator| generator.name()))
+            .chain(
+                self.macro_controls
+                    .iter()
+                    .map(|macro_control| macro_control.name.clone()),
+            );
+        let invalid: Vec<String> = names
+            .filter(|name| !is_valid_name(name))
+            .map(|name| format!("{name:?}"))
+            .collect();
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Names can't contain control characters: {}",
+                    invalid.join(", ")
+                ),
+            ))
         }
-        if let Some(cat) = &self.metadata.category {
-            if !description.is_empty() {
-                description.push(' ');
+    }
+
+    /// Replace the control characters in the names of the snapins,
+    /// generators and macro controls with spaces.
+    pub fn sanitize_names(&mut self) {
+        self.visit_effects_mut(|snapin| {
+            snapin.name = sanitize_name(&snapin.name);
+            if let Some(group) = snapin.effect.downcast_mut::<effect::Group>() {
+                group.name = group.name.as_deref().map(sanitize_name);
             }
-            description.push_str(<dyn HashTag>::from_lossy(cat).as_str())
+        });
+        for generator in &mut self.generators {
+            let name = generator.name();
+            if !is_valid_name(&name) {
+                // Only the blank generator can't be renamed and its name is fixed.
+                let _ = generator.set_name(&sanitize_name(&name));
+            }
+        }
+        for macro_control in &mut self.macro_controls {
+            macro_control.name = sanitize_name(&macro_control.name);
         }
+    }
 
-        //
-        // Metadata
-        //
+    /// Write the preset to a file. The preset is written to memory first so a
+    /// preset that can't be written doesn't leave a partial file behind.
+    pub fn write_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut cursor = Cursor::new(Vec::new());
+        self.write(&mut cursor)?;
+        fs::write(path, cursor.into_inner())
+    }
 
-        let metadata = MetadataJson {
-            description: Some(description), // Use a blank string instead of null
-            author: self.metadata.author.clone().or_else(|| Some(String::new())),
+    pub fn write_with_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        options: &WriteOptions,
+    ) -> Result<WritePresetResult> {
+        let mut messages = Vec::new();
+        let release = if options.preserve_format_version {
+            self.preserved_release(&mut messages)
+        } else {
+            WRITE_SAME_AS
+        };
+        let mut result = if options.clamp_out_of_range {
+            let mut clamped = self.clone();
+            messages.append(&mut clamped.clamp_out_of_range());
+            clamped.write_as(writer, release)?
+        } else {
+            self.write_as(writer, release)?
         };
+        messages.append(&mut result.messages);
+        Ok(WritePresetResult { messages })
+    }
 
-        // Use the same spacing as Phase Plant so the files match as closely as
-        // possibles to make comparing the files easier.
-        let mut metadata_json = Vec::with_capacity(128);
-        let formatter = PrettyFormatter::with_indent(b"    ");
-        let mut serializer = Serializer::with_formatter(&mut metadata_json, formatter);
-        metadata.serialize(&mut serializer)?;
-        metadata_json.push(b'\n');
+    /// The newest release that writes the format version of the preset, if
+    /// its layout can be written and the preset doesn't need a newer one.
+    fn preserved_release(&self, messages: &mut Vec<Message>) -> PhasePlantRelease {
+        let release = PhasePlantRelease::all()
+            .iter()
+            .rev()
+            .find(|release| release.format_version() == self.format_version)
+            .copied();
+        let problem = match release {
+            Some(release) if !(PhasePlantRelease::V2_0_0..=WRITE_SAME_AS).contains(&release) => {
+                format!("the layout of Phase Plant {release} can't be written")
+            }
+            Some(release) if self.required_release() > release => format!(
+                "it uses features that need Phase Plant {}",
+                self.required_release()
+            ),
+            Some(release) => return release,
+            None => format!("format version {} is not known", self.format_version),
+        };
+        messages.push(Message {
+            level: Level::Warn,
+            description: format!(
+                "Preset written for Phase Plant {WRITE_SAME_AS} because {problem}"
+            ),
+        });
+        WRITE_SAME_AS
+    }
 
-        // Use the exact metadata from test presets when comparing during debugging. serde
-        // doesn't use the same formatting as Phase Plant.
-        // let metadata_json = "{\n    \"description\": \"\",\n    \"author\": \"softdev.ca\"\n}\n";
-        // let metadata_json = "{\n    \"description\": \"\",\n    \"author\": \"\"\n}\n";
+    pub fn write<W: Write + Seek>(&self, writer: &mut W) -> Result<WritePresetResult> {
+        self.write_as(writer, WRITE_SAME_AS)
+    }
 
-        writer.write_u32(metadata_json.len() as u32 + 1)?;
-        writer.write_u8(0)?; // Unknown value, always 0
-        writer.write_all_u8(&metadata_json)?;
+    /// Write the preset in the layout of a Phase Plant 2 release.
+    fn write_as<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        release: PhasePlantRelease,
+    ) -> Result<WritePresetResult> {
+        self.check_limits()?;
+        self.check_names()?;
+        for lane in &self.lanes {
+            lane.check_groups()?;
+        }
+        if !self.embedded_locations.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Load the embedded samples and wavetables before writing the preset",
+            ));
+        }
+        let mut writer = PhasePlantWriter::new(writer);
+        let mut messages = Vec::new();
+
+        //
+        // Header
+        //
+
+        let format_version = release.format_version();
+        writer.write_u32(format_version.major)?;
+        writer.write_u32(format_version.patch)?;
+        writer.write_u32(format_version.minor)?;
+
+        //
+        // Metadata
+        //
+
+        writer.write_metadata(&self.metadata)?;
         writer.write_u32(1)?; // Unknown value, always 1
 
         //
@@ -323,20 +621,11 @@ impl Preset {
             "modulation: count {modulation_count}, pos {}",
             writer.pos_text()
         );
-        if modulation_count > MODULATIONS_MAX {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                format!("Unexpected number of modulation items ({modulation_count} is more than {MODULATIONS_MAX})"),
-            ));
-        }
         writer.write_u32(modulation_count as u32)?;
 
-        let default_modulation = Modulation::default();
+        let unused_modulation = Modulation::unused();
         for modulation_index in 0..MODULATIONS_MAX {
-            let modulation = self
-                .modulations
-                .get(modulation_index)
-                .unwrap_or(&default_modulation);
+            let modulation = self.modulation_slot(modulation_index, &unused_modulation);
             writer.write_u32(modulation.source.id())?;
             writer.write_u32(modulation.target.id())?;
             writer.write_f32(modulation.amount.value)?;
@@ -363,7 +652,7 @@ impl Preset {
             writer.write_bool32(lane.enabled)?;
             writer.write_decibels_linear(lane.gain)?;
             writer.write_ratio(lane.mix)?;
-            writer.write_u32(lane.destination as u32)?;
+            writer.write_u32(lane.destination.id())?;
         }
 
         //
@@ -400,10 +689,8 @@ impl Preset {
             Box::new(BlankModulator {})
         }
         let default_mod = &default_mod();
-        for mod_index in 0..MODULATORS_MAX {
-            let modulator = self
-                .modulator_containers
-                .get(mod_index)
+        for container in modulator_slots(&self.modulator_containers) {
+            let modulator = container
                 .map(|container| &container.modulator)
                 .unwrap_or_else(|| default_mod);
 
@@ -412,8 +699,25 @@ impl Preset {
             if modulator.mode().is_blank() {
                 mod_block.id = 0;
             }
+            if let Some(container) = container {
+                mod_block.id = container.id;
+                mod_block.group_id = container.group_id;
+                mod_block.enabled = container.enabled;
+                mod_block.minimized = container.minimized;
+                mod_block.second_data_block = container.second_data_block.clone();
+                mod_block.unknown.extend(&container.unknown);
+            }
 
-            assert_eq!(modulator.mode(), mod_block.mode);
+            if mod_block.mode != modulator.mode() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "{} modulator was converted to a {} block",
+                        modulator.mode(),
+                        mod_block.mode
+                    ),
+                ));
+            }
             mod_blocks.push(mod_block);
         }
 
@@ -435,11 +739,14 @@ impl Preset {
                 trace!("modulator: start of LFO block pos {}", writer.pos_text());
             }
             writer.write_ratio(block.depth)?;
-            writer.write_bool32(block.retrigger)?;
+
+            // Phase Plant 2 keeps the retrigger switch of Phase Plant 1 set
+            // and uses the note trigger mode instead.
+            writer.write_bool32(true)?;
             writer.write_u32(block.output_range as u32)?;
             writer.write_hertz(block.rate.frequency)?;
             writer.write_u32(block.rate.numerator)?;
-            writer.write_u32(block.rate.denominator as u32)?;
+            writer.write_u32(block.rate.denominator.id())?;
             writer.write_bool32(block.rate.sync)?;
             if !block.mode.is_blank() {
                 trace!(
@@ -453,7 +760,9 @@ impl Preset {
                 trace!("modulator: envelope end pos {}", writer.pos_text());
             }
             writer.write_ratio(block.phase_offset)?;
-            writer.write_bool32(block.one_shot)?;
+
+            // One shot was replaced by the loop mode, so it's always clear.
+            writer.write_bool32(false)?;
             writer.write_f32(block.multiplier)?;
             if !block.mode.is_blank() {
                 trace!("modulator: unknown pos {}", writer.pos_text());
@@ -468,8 +777,8 @@ impl Preset {
                 .skip(MODULATOR_BLOCK_SIZE - (modulator_end_pos - mod_start_pos - 12) as usize)?;
         }
 
-        writer.skip(4)?; // unknown_f
-        writer.skip(4)?; // unknown_g
+        writer.write_ratio(self.mod_wheel_value)?;
+        writer.write_f32(self.master_pitch)?;
 
         writer.write_u32(self.polyphony)?;
 
@@ -643,96 +952,148 @@ impl Preset {
             writer.write_bool32(block.loop_enabled)?;
         }
 
-        trace!("version 2: block A: pos {}", writer.pos_text());
-        for _ in 0..100 {
-            writer.write_u32(0)?;
-            writer.write_bool32(true)?;
+        trace!("modulation: curves pos {}", writer.pos_text());
+        for modulation_index in 0..MODULATIONS_MAX {
+            let modulation = self.modulation_slot(modulation_index, &unused_modulation);
+            writer.write_f32(modulation.curve.get::<percent>())?;
+            writer.write_bool32(modulation.enabled)?;
         }
 
-        trace!("version 2: block B: pos {}", writer.pos_text());
-        for _ in 0..32 {
-            writer.write_u8(0)?;
+        trace!("macro controls: polarities pos {}", writer.pos_text());
+        for index in 0..MacroControl::COUNT {
+            let polarity = self
+                .macro_controls
+                .get(index)
+                .map_or(OutputRange::Unipolar, |macro_control| {
+                    macro_control.polarity
+                });
+            writer.write_u32(polarity as u32)?;
         }
 
-        trace!("version 2: block D: pos {}", writer.pos_text());
-        for mod_block in &mut mod_blocks {
-            writer.write_f32(1.0)?;
+        trace!("modulator: triggers pos {}", writer.pos_text());
+        for mod_block in &mod_blocks {
+            writer.write_decibels_linear(mod_block.gain)?;
             writer.write_u32(mod_block.group_id)?;
-            writer.write_f32(0.5)?;
-            writer.write_u32(3)?;
-            writer.write_u32(0)?;
-            writer.write_bool32(true)?;
-            writer.write_u32(36)?; // 0x24
-            writer.write_u32(84)?; // 0x54
-            writer.write_f32(0.5)?;
-            writer.write_u32(69)?;
-            writer.write_u32(0xFFFFFFFF)?; // -1 as i32
+            writer.write_ratio(mod_block.trigger_threshold)?;
+            writer.write_u32(mod_block.note_trigger_mode as u32)?;
             writer.write_u32(0)?;
+            writer.write_u32(mod_block.metering_mode as u32)?;
+
+            // Pitch Tracker
+            writer.write_u32(mod_block.pitch_tracker_lowest)?;
+            writer.write_u32(mod_block.pitch_tracker_highest)?;
+            writer.write_ratio(mod_block.pitch_tracker_sensitivity)?;
+            writer.write_u32(mod_block.pitch_tracker_root)?;
+
+            writer.write_u32(mod_block.controller_slot.unwrap_or(0xFFFFFFFF))?;
+            writer.write_u32(mod_block.velocity_trigger_mode as u32)?;
         }
 
-        trace!("version 2: block E: pos {}", writer.pos_text());
-        for _ in 0..32 {
-            writer.write_u32(0)?;
+        for mod_block in &mod_blocks {
+            writer.write_f32(mod_block.lfo_table_frame)?;
         }
 
-        trace!("version 2: block F: pos {}", writer.pos_text());
-        for _ in 0..32 {
-            writer.write_f32(1.0)?;
-            writer.write_u32(0)?;
-            writer.write_u32(0)?;
+        trace!("modulator: loop mode pos {}", writer.pos_text());
+        for mod_block in &mod_blocks {
             writer.write_f32(1.0)?;
+            writer.write_u32(mod_block.loop_mode.id())?;
             writer.write_u32(0)?;
+            writer.write_u32(mod_block.unknown_or("loop_unknown_3", 1.0_f32.to_bits()))?;
+            writer.write_u32(mod_block.unknown_or("loop_unknown_4", 0))?;
         }
 
-        trace!("version 2: block G: pos {}", writer.pos_text());
-        for _ in 0..32 {
-            writer.write_u32(0)?;
-            writer.write_u32(0)?;
-            writer.write_f32(1.0)?;
-            writer.write_f32(1.0)?;
-            writer.write_u32(4)?;
-            writer.write_u32(4)?;
-            writer.write_u32(0)?;
-            writer.write_u32(0)?;
-            writer.write_u32(0)?;
+        trace!("generator: curve pos {}", writer.pos_text());
+        for block in &gen_blocks {
+            writer.write_bool32(block.curve_edited)?;
+            writer.write_bool32(false)?;
             writer.write_f32(1.0)?;
-            writer.write_u32(0)?;
+            writer.write_hertz(block.rate.frequency)?;
+            writer.write_u32(block.rate.numerator)?;
+            writer.write_u32(block.rate.denominator.id())?;
+            writer.write_bool32(block.rate.sync)?;
+            writer.write_u32(block.curve_loop_mode.id())?;
+            writer.write_ratio(block.curve_loop_start)?;
+            writer.write_ratio(block.curve_loop_length)?;
+            writer.write_bool32(block.settings_locked)?;
         }
 
-        trace!("version 2: block H: pos {}", writer.pos_text());
-        for _ in 0..32 {
-            writer.write_f32(1.0)?;
-            writer.write_f32(0.002)?; // 0x6F 12 03 3B
+        for mod_block in &mod_blocks {
+            writer.write_seconds(mod_block.curve_time)?;
+            writer.write_u32(mod_block.unknown_or("lfo_table_time", 0.002_f32.to_bits()))?;
         }
 
-        trace!("version 2: block I: pos {}", writer.pos_text());
-        for _ in 0..32 {
-            writer.write_f32(1.0)?; // 0x00 00 80 3F
+        for block in &gen_blocks {
+            writer.write_seconds(block.curve_length)?;
         }
 
-        trace!("version 2: block J: pos {}", writer.pos_text());
-        for _ in 0..32 {
-            writer.write_u32(0)?;
-            writer.write_u32(0)?;
+        for mod_block in &mod_blocks {
+            writer.write_bool32(mod_block.envelope_seamless)?;
+            writer.write_u32(mod_block.voice_mode as u32)?;
             writer.write_u32(0)?;
         }
 
-        trace!("version 2: block K: pos {}", writer.pos_text());
-        for _ in 0..32 {
-            writer.write_bool32(true)?;
+        for block in &gen_blocks {
+            writer.write_bool32(block.output_enabled)?;
         }
 
         // Added in Phase Plant 2.0.12
-        trace!("version 2: block L: pos {}", writer.pos_text());
-        for _ in 0..32 {
-            writer.write_f32(0.1)?; // 0xCD CC CC 3D
-            writer.write_f32(0.1)?; // 0xCD CC CC 3D
+        trace!("modulator: slew limiter pos {}", writer.pos_text());
+        if release >= PhasePlantRelease::V2_0_12 {
+            for mod_block in &mod_blocks {
+                writer.write_seconds(mod_block.slew_limiter_attack)?;
+                writer.write_seconds(mod_block.slew_limiter_decay)?;
+            }
         }
 
         // Added in Phase Plant 2.0.13
-        trace!("version 2: block M: pos {}", writer.pos_text());
-        for _ in 0..32 {
-            writer.write_bool32(true)?;
+        if release >= PhasePlantRelease::V2_0_13 {
+            for mod_block in &mod_blocks {
+                writer.write_bool32(mod_block.slew_limiter_linked)?;
+            }
+        }
+
+        // Granular generator, added in Phase Plant 2.1.0
+        trace!("granular: pos {}", writer.pos_text());
+        let granular_blocks = if release >= PhasePlantRelease::V2_1_0 {
+            &gen_blocks[..]
+        } else {
+            &[]
+        };
+        for block in granular_blocks {
+            writer.write_ratio(block.granular_position)?;
+            writer.write_u32(block.granular_direction as u32)?;
+            writer.write_f32(block.granular_grains)?;
+
+            // Randomization
+            writer.write_ratio(block.granular_randomization.position)?;
+            writer.write_ratio(block.granular_randomization.timing)?;
+            writer.write_hertz(block.granular_randomization.pitch)?;
+            writer.write_ratio(block.granular_randomization.pan)?;
+            writer.write_ratio(block.granular_randomization.level)?;
+            writer.write_ratio(block.granular_randomization.reverse)?;
+
+            writer.write_bool32(block.granular_align_phases)?;
+            writer.write_bool32(block.granular_warm_start)?;
+            writer.write_bool32(block.granular_auto_grain_length)?;
+
+            writer.write_ratio(block.granular_envelope.attack_time)?;
+            writer.write_f32(block.granular_envelope.attack_curve)?;
+            writer.write_ratio(block.granular_envelope.decay_time)?;
+            writer.write_f32(block.granular_envelope.decay_curve)?;
+
+            writer.write_seconds(block.granular_grain_length)?;
+            writer.write_bool32(block.granular_chord.enabled)?;
+            writer.write_f32(block.granular_chord.range_octaves)?;
+            writer.write_u32(block.granular_chord.mode as u32)?;
+        }
+        for block in granular_blocks {
+            // Always the same in the presets saved by Phase Plant.
+            writer.write_f32(10.0)?; // granular_unknown_1
+            writer.write_u32(4)?; // granular_unknown_2
+            writer.write_u32(4)?; // granular_unknown_3
+
+            writer.write_u32(block.granular_spawn_rate_mode as u32)?;
+            writer.write_u32(block.granular_chord.picking_pattern as u32)?;
         }
 
         //
@@ -765,10 +1126,10 @@ impl Preset {
 
                     // The version of Phase Plant the preset was saved with. The host can adjust
                     // the effect based on the version so it sounds the same across versions.
-                    writer.write_u8(WRITE_SAME_AS.version().patch)?;
-                    writer.write_u8(WRITE_SAME_AS.version().minor)?;
-                    writer.write_u8(WRITE_SAME_AS.version().major)?;
-                    writer.write_u8(WRITE_SAME_AS.version().extra)?;
+                    writer.write_u8(release.version().patch)?;
+                    writer.write_u8(release.version().minor)?;
+                    writer.write_u8(release.version().major)?;
+                    writer.write_u8(release.version().extra)?;
 
                     writer.write_string_and_length(snapin.name.as_str())?;
                     writer.write_u16(snapin.id)?;
@@ -776,15 +1137,8 @@ impl Preset {
                     // Effect
                     let effect_start_pos = writer.stream_position()?;
                     writer.write_u32(0)?; // Length, updated later
-                    writer.write_u32(FORMAT_VERSION.major)?;
-                    writer.write_u32(snapin.effect_version)?;
-                    writer.write_bool32(true)?; // Unknown snapin bool
-                    writer.write_string_and_length(snapin.preset_name.as_str())?;
-                    // FIXME: Probably need to do something different with effects with metadata. See reading.
-                    writer.write_path(&snapin.preset_path)?;
-                    writer.write_bool32(snapin.preset_edited)?;
-                    writer.write_u8(0)?; // snapin_prologue_unknown
-                    snapin.effect.write(&mut writer, snapin)?;
+                    SnapinFrame::new(snapin).write(&mut writer, &snapin.effect.mode())?;
+                    snapin.write_effect(&mut writer)?;
                     let effect_end_pos = writer.stream_position()?;
                     writer.inner.seek(SeekFrom::Start(effect_start_pos))?;
                     writer.write_u32(
@@ -796,10 +1150,10 @@ impl Preset {
             }
         }
 
-        for _ in 0..32 {
-            // "niam" is "main" reversed
-            writer.write_all_u8("niam".as_bytes())?;
-            writer.write_string_and_length("Master")?;
+        // Audio sources used by the Audio Follower and Pitch Tracker modulators.
+        for block in &mod_blocks {
+            writer.write_u32(block.audio_source.id())?;
+            writer.write_string_and_length(block.audio_source.name())?;
         }
 
         //
@@ -807,13 +1161,14 @@ impl Preset {
         //
 
         trace!("string pool: pos {}", writer.pos_text());
-        let mut string_pool: Vec<Option<String>> = Vec::with_capacity(STRING_POOL_COUNT);
+        // The strings are borrowed from the preset.
+        let mut string_pool: Vec<Cow<str>> = Vec::with_capacity(STRING_POOL_COUNT);
         for block in &gen_blocks {
-            string_pool.push(block.sample_name.clone());
+            string_pool.push(block.sample_name.as_deref().unwrap_or_default().into());
         }
 
         for block in &mod_blocks {
-            string_pool.push(block.shape_name.clone());
+            string_pool.push(block.shape_name.as_deref().unwrap_or_default().into());
         }
 
         for block in &gen_blocks {
@@ -821,47 +1176,51 @@ impl Preset {
             if block.name != block.mode.name() {
                 // Phase Plant 1.8 has a limit on the length of group names.  The file format
                 // doesn't have a limit but the application does.
-                let mut truncated = block.name.to_owned();
-                if block.mode == GeneratorMode::Group {
-                    truncated.truncate(Group::MAX_NAME_LENGTH)
+                let truncated = truncate_chars(&block.name, Group::MAX_NAME_LENGTH);
+                if block.mode == GeneratorMode::Group && truncated.len() < block.name.len() {
+                    messages.push(Message {
+                        level: Level::Warn,
+                        description: format!(
+                            "Group name \"{}\" was shortened to {} characters",
+                            block.name,
+                            Group::MAX_NAME_LENGTH
+                        ),
+                    });
+                    string_pool.push(truncated.into());
+                } else {
+                    string_pool.push(block.name.as_str().into());
                 }
-                string_pool.push(Some(truncated))
             } else {
-                string_pool.push(None);
+                string_pool.push("".into());
             }
         }
 
         for index in 0..MacroControl::COUNT {
-            string_pool.push(self.macro_controls.get(index).map(|c| c.name.clone()));
+            let name = self.macro_controls.get(index).map(|c| c.name.as_str());
+            string_pool.push(name.unwrap_or_default().into());
         }
 
         // 104
         for block in &gen_blocks {
-            string_pool.push(block.wavetable_name.clone());
+            string_pool.push(block.wavetable_name.as_deref().unwrap_or_default().into());
         }
 
         // 136
         for block in &mod_blocks {
-            string_pool.push(block.shape_path.clone());
+            string_pool.push(block.shape_path.as_deref().unwrap_or_default().into());
         }
 
-        for _ in 0..(STRING_POOL_COUNT - string_pool.len()) {
-            string_pool.push(None)
-        }
-        assert_eq!(STRING_POOL_COUNT, string_pool.len());
+        string_pool.resize(STRING_POOL_COUNT, "".into());
         trace!("string pool: contents: {:?}", string_pool);
 
         for item in &string_pool {
-            match item {
-                Some(s) => writer.write_string_and_length(s),
-                _ => writer.write_string_and_length(""),
-            }?
+            writer.write_string_and_length(item)?;
         }
 
-        trace!("version 2: block Q: pos {}", writer.pos_text());
-        for _ in 0..32 {
-            writer.write_u32(0)?;
-            writer.write_u32(0)?;
+        trace!("curve output names: pos {}", writer.pos_text());
+        for block in &gen_blocks {
+            writer.write_string_and_length_opt(&block.curve_name)?;
+            writer.write_string_and_length_opt(&block.curve_path)?;
         }
 
         //
@@ -879,29 +1238,28 @@ impl Preset {
 
         // Each modulator has two consecutive data blocks.
         for mod_block in &mod_blocks {
-            let version = WRITE_SAME_AS.format_version().major;
             if !mod_block.shape.is_empty() {
                 let point_count = mod_block.shape.len();
-                let data_length = point_count * (5 * size_of::<u32>()) + (2 * size_of::<u32>());
+                let data_length = point_count * (5 * size_of::<u32>()) + size_of::<u32>();
                 trace!(
                     "data block: shape, points {point_count}, data length {data_length}, pos {}",
-                    writer.stream_position()? - 4
+                    writer.pos_text()
                 );
-                writer.write_block_header(&DataBlockHeader::new_used(data_length, version))?;
-                writer.write_u32(1)?; // FIXME: unknown_a, see reader
-                writer.write_u32(point_count as u32)?;
-                for point in &mod_block.shape {
-                    writer.write_f32(point.x)?;
-                    writer.write_f32(point.y)?;
-                    writer.write_f32(point.curve_x)?;
-                    writer.write_f32(point.curve_y)?;
-                    writer.write_u32(point.mode as u32)?;
-                }
-
-                writer.write_block_header(&DataBlockHeader::new_unused())?;
+                writer.write_block_header(&DataBlockHeader::new_used(data_length, 1))?;
+                writer.write_curve_points(&mod_block.shape)?;
             } else {
                 writer.write_block_header(&DataBlockHeader::new_unused())?;
-                writer.write_block_header(&DataBlockHeader::new_unused())?;
+            }
+
+            match &mod_block.second_data_block {
+                Some(block) => {
+                    writer.write_block_header(&DataBlockHeader::new_used(
+                        block.contents.len(),
+                        block.mode_id,
+                    ))?;
+                    writer.write_all_u8(&block.contents)?;
+                }
+                None => writer.write_block_header(&DataBlockHeader::new_unused())?,
             }
         }
 
@@ -918,71 +1276,153 @@ impl Preset {
             trace!("data block: generator pos {}", writer.pos_text());
 
             // Sample path and contents
-            let sample_path_len = block.sample_path.as_ref().map(|s| s.len()).unwrap_or(0);
-            let sample_used = sample_path_len > 0 || !block.sample_contents.is_empty();
-            if sample_used {
+            let sample_data_mode =
+                SampleDataMode::for_sample(block.mode, &block.sample_path, &block.sample_contents);
+            if let Some(mode) = sample_data_mode {
+                let sample_path_len = block.sample_path.as_ref().map(|s| s.len()).unwrap_or(0);
+                let flag_len = if mode == SampleDataMode::PathWithFlag {
+                    1
+                } else {
+                    0
+                };
+                let contents_len = if block.sample_contents.is_empty() {
+                    0
+                } else {
+                    4 /* Sample contents length */ + block.sample_contents.len()
+                };
                 writer.write_u32(
-                    (sample_path_len + block.sample_contents.len()) as u32 + 4 /* Mode */
+                    (sample_path_len + flag_len + contents_len) as u32
+                        + 4 /* Mode */
                         + 4 /* Path string length */
-                        + 4 /* Sample contents length */
                         + 1, /* Used */
                 )?;
-                writer.write_bool8(sample_used)?;
-
-                writer.write_u32(2)?; // Mode
+                writer.write_bool8(true)?;
+                writer.write_u32(mode as u32)?;
                 writer.write_string_and_length_opt(&block.sample_path)?;
+                if mode == SampleDataMode::PathWithFlag {
+                    writer.write_bool8(false)?;
+                }
 
-                // Sample contents
-                writer.write_u32(block.sample_contents.len() as u32)?;
-                writer.write_all_u8(&block.sample_contents)?;
+                // Only embedded samples have contents.
+                if !block.sample_contents.is_empty() {
+                    writer.write_u32(block.sample_contents.len() as u32)?;
+                    writer.write_all_u8(&block.sample_contents)?;
+                }
             } else {
                 writer.write_u32(1)?;
                 writer.write_bool8(false)?;
             }
 
-            // Wavetable path
-            let wavetable_path_len = block.wavetable_path.as_ref().map(|s| s.len()).unwrap_or(0);
-            let wavetable_used = wavetable_path_len > 0 || !block.wavetable_contents.is_empty();
-            if wavetable_used {
-                writer
-                    .write_u32((wavetable_path_len + block.wavetable_contents.len()) as u32 + 13)?;
-                writer.write_bool8(wavetable_used)?;
-                writer.write_u32(3)?; // Mode
+            // Wavetable path and contents
+            let wavetable_data_mode =
+                WavetableDataMode::for_wavetable(&block.wavetable_path, &block.wavetable_contents);
+            if let Some(mode) = wavetable_data_mode {
+                let wavetable_path_len =
+                    block.wavetable_path.as_ref().map(|s| s.len()).unwrap_or(0);
+                let edited_len = if mode == WavetableDataMode::PathWithEdited {
+                    1
+                } else {
+                    0
+                };
+                let contents_len = if block.wavetable_contents.is_empty() {
+                    0
+                } else {
+                    4 /* Wavetable contents length */ + block.wavetable_contents.len()
+                };
+                let data_length =
+                    4 /* Path string length */ + wavetable_path_len + edited_len + contents_len;
+                writer.write_block_header(&DataBlockHeader::new_used(data_length, mode as u32))?;
                 writer.write_string_and_length_opt(&block.wavetable_path)?;
+                if mode == WavetableDataMode::PathWithEdited {
+                    writer.write_bool8(block.wavetable_edited)?;
+                }
 
-                // Wavetable contents
-                writer.write_u32(block.wavetable_contents.len() as u32)?;
-                writer.write_all_u8(&block.wavetable_contents)?;
+                // Only embedded wavetables have contents.
+                if !block.wavetable_contents.is_empty() {
+                    writer.write_u32(block.wavetable_contents.len() as u32)?;
+                    writer.write_all_u8(&block.wavetable_contents)?;
+                }
             } else {
-                writer.write_u32(1)?;
-                writer.write_bool8(false)?;
+                writer.write_block_header(&DataBlockHeader::new_unused())?;
             }
         }
 
-        for _ in 0..32 {
-            writer.write_block_header(&DataBlockHeader::new_unused())?;
+        // LFO Table wavetables. Every LFO Table has a block, even if it
+        // doesn't have a wavetable path.
+        trace!("data block: lfo table pos {}", writer.pos_text());
+        for mod_block in &mod_blocks {
+            if mod_block.mode == ModulatorMode::LfoTable {
+                let path_len = mod_block
+                    .lfo_table_wavetable_path
+                    .as_ref()
+                    .map(|s| s.len())
+                    .unwrap_or(0);
+                let has_contents = !mod_block.lfo_table_wavetable_contents.is_empty();
+                let contents_len = if has_contents {
+                    4 /* Wavetable contents length */ + mod_block.lfo_table_wavetable_contents.len()
+                } else {
+                    0
+                };
+                let data_length =
+                    4 /* Path string length */ + path_len + 1 /* Has contents */ + contents_len;
+                writer.write_block_header(&DataBlockHeader::new_used(data_length, 3))?;
+                writer.write_string_and_length_opt(&mod_block.lfo_table_wavetable_path)?;
+                writer.write_bool8(has_contents)?;
+                if has_contents {
+                    writer.write_u32(mod_block.lfo_table_wavetable_contents.len() as u32)?;
+                    writer.write_all_u8(&mod_block.lfo_table_wavetable_contents)?;
+                }
+            } else {
+                writer.write_block_header(&DataBlockHeader::new_unused())?;
+            }
         }
 
-        for _ in 0..32 {
-            writer.write_block_header(&DataBlockHeader::new_unused())?;
+        // Curve Output shapes. Every Curve Output has a block, even if the
+        // curve has no points.
+        trace!("data block: curve output pos {}", writer.pos_text());
+        for block in &gen_blocks {
+            if block.mode == GeneratorMode::CurveOutput {
+                let data_length = block.curve.len() * (5 * size_of::<u32>()) + size_of::<u32>();
+                writer.write_block_header(&DataBlockHeader::new_used(data_length, 1))?;
+                writer.write_curve_points(&block.curve)?;
+            } else {
+                writer.write_block_header(&DataBlockHeader::new_unused())?;
+            }
         }
 
         writer.inner.flush()?;
 
-        Ok(WritePresetResult {
-            messages: Vec::new(),
-        })
+        Ok(WritePresetResult { messages })
     }
 }
 
+/// The modulator in each slot of the file. Modulators are stored in the
+/// slot of their ID so modulators deleted from the middle leave the same
+/// blank slots behind. The IDs have been checked by
+/// [`Preset::check_limits`].
+fn modulator_slots(containers: &[ModulatorContainer]) -> Vec<Option<&ModulatorContainer>> {
+    let mut slots = vec![None; MODULATORS_MAX];
+    for container in containers {
+        slots[container.id as usize] = Some(container);
+    }
+    slots
+}
+
 #[cfg(test)]
 mod test {
+    use std::fmt::Debug;
+    use std::fs::File;
     use std::io::{Cursor, Seek, SeekFrom};
 
-    use crate::test::read_preset;
+    use crate::generator::LoopMode;
+    use crate::modulator::{LfoModulator, NoteTriggerMode};
+    use crate::test::{read_modulator_preset, read_preset};
+    use crate::tests::test_data_path;
 
     use super::*;
 
+    const FORMAT_VERSION: Version<u32> = WRITE_SAME_AS.format_version();
+
     #[test]
     fn init_version_1() {
         let mut init_preset = read_preset("init", "init-1.8.13.phaseplant");
@@ -1029,4 +1469,502 @@ mod test {
         let read_back_preset = Preset::read(&mut cursor, None).expect("default preset");
         assert_eq!(default_preset, read_back_preset);
     }
+
+    /// The settings of a modulator that are stored in the blocks added in
+    /// Phase Plant 2.
+    fn version_2_modulator_settings(block: &ModulatorBlock) -> impl Debug + PartialEq {
+        (
+            (
+                block.gain,
+                block.group_id,
+                block.trigger_threshold,
+                block.note_trigger_mode,
+                block.metering_mode,
+                block.controller_slot,
+                block.velocity_trigger_mode,
+            ),
+            (
+                block.pitch_tracker_lowest,
+                block.pitch_tracker_highest,
+                block.pitch_tracker_sensitivity,
+                block.pitch_tracker_root,
+            ),
+            (
+                block.lfo_table_frame,
+                block.loop_mode,
+                block.curve_time,
+                block.envelope_seamless,
+                block.voice_mode,
+            ),
+            (
+                block.slew_limiter_attack,
+                block.slew_limiter_decay,
+                block.slew_limiter_linked,
+            ),
+            block.unknown.clone(),
+        )
+    }
+
+    /// The settings of a generator that are stored in the blocks added in
+    /// Phase Plant 2.
+    fn version_2_generator_settings(block: &GeneratorBlock) -> impl Debug + PartialEq {
+        (
+            block.curve_edited,
+            block.rate.clone(),
+            block.curve_loop_mode,
+            block.curve_loop_start,
+            block.curve_loop_length,
+            block.settings_locked,
+            block.curve_length,
+            block.output_enabled,
+        )
+    }
+
+    /// Settings stored in the blocks added in Phase Plant 2 are written from
+    /// the generators, modulators, macros and modulations.
+    #[test]
+    fn version_2_blocks_match() {
+        for path in [
+            "generators/curve_output/curve_output-0ms,50-500ms,25-2.1.0.phaseplant",
+            "generators/curve_output/curve_output-5sec-settings_locked-2.1.0.phaseplant",
+            "generators/curve_output/curve_output-loop_start25-loop_length50-2.1.0.phaseplant",
+            "generators/curve_output/curve_output-sync-reverse-2.1.0.phaseplant",
+            "generators/envelope_output/envelope_output-out_disabled-2.1.0.phaseplant",
+            "macros/macro1_unipolar-macro2_bipolar-macro3_inverted-2.1.0.phaseplant",
+            "modulation/note-to-curve_outputs-2.1.0.phaseplant",
+            "modulators/envelope/envelope-note_trigger_always-2.1.0.phaseplant",
+            "modulators/envelope/envelope-seamless-2.1.0.phaseplant",
+            "modulators/lfo/lfo-ping_pong-trigger15-2.1.0.phaseplant",
+            "modulators/curve/curve-point_appended-2.1.0.phaseplant",
+            "modulators/lfo_table/lfo_table-2.1.0.phaseplant",
+            "modulators/lfo_table/lfo_table-frame10-smooth20-phase180-2.0.0.phaseplant",
+            "modulators/midi_cc/midi_cc-slot25-2.1.0.phaseplant",
+            "modulators/pitch_tracker/pitch_tracker-d1-a5-d7-2.1.0.phaseplant",
+            "modulators/random/random-trigger25-legato-independent-2.1.0.phaseplant",
+            "modulators/slew_limiter/slew_limiter-att200-dec300-unlinked-2.1.0.phaseplant",
+            "modulators/velocity/velocity-both-bipolar-2.1.0.phaseplant",
+        ] {
+            let path = test_data_path(&[path]);
+            let (preset, gen_blocks, mod_blocks) =
+                Preset::read_with_blocks(&mut File::open(&path).unwrap(), None).unwrap();
+            let mut cursor = Cursor::new(Vec::new());
+            preset.write(&mut cursor).unwrap();
+            cursor.seek(SeekFrom::Start(0)).unwrap();
+            let (read_back, read_back_gen_blocks, read_back_mod_blocks) =
+                Preset::read_with_blocks(&mut cursor, None).unwrap();
+
+            let name = path.display();
+            assert_eq!(read_back.modulations, preset.modulations, "{name}");
+            let polarities = |preset: &Preset| {
+                preset
+                    .macro_controls
+                    .iter()
+                    .map(|macro_control| macro_control.polarity)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(polarities(&read_back), polarities(&preset), "{name}");
+            for (block, read_back_block) in mod_blocks.iter().zip(&read_back_mod_blocks) {
+                assert_eq!(
+                    version_2_modulator_settings(read_back_block),
+                    version_2_modulator_settings(block),
+                    "{name}"
+                );
+            }
+            for (block, read_back_block) in gen_blocks.iter().zip(&read_back_gen_blocks) {
+                assert_eq!(
+                    version_2_generator_settings(read_back_block),
+                    version_2_generator_settings(block),
+                    "{name}"
+                );
+            }
+        }
+    }
+
+    /// The blocks of generators and modulators that don't use a setting
+    /// store the block defaults.
+    #[test]
+    fn version_2_block_defaults() {
+        let path = test_data_path(&["modulation", "note-to-curve_outputs-2.1.0.phaseplant"]);
+        let (_, gen_blocks, mod_blocks) =
+            Preset::read_with_blocks(&mut File::open(path).unwrap(), None).unwrap();
+        let default_gen_block = GeneratorBlock::default();
+        for block in gen_blocks
+            .iter()
+            .filter(|block| block.mode != GeneratorMode::CurveOutput)
+        {
+            assert_eq!(block.rate, default_gen_block.rate);
+        }
+        let default_mod_block = ModulatorBlock::default();
+        for block in &mod_blocks {
+            assert_eq!(block.loop_mode, default_mod_block.loop_mode);
+            assert_eq!(
+                block.pitch_tracker_sensitivity,
+                default_mod_block.pitch_tracker_sensitivity
+            );
+        }
+    }
+
+    #[test]
+    fn master_pitch() {
+        let preset = read_preset("misc", "master-pitch-12semis-50cents-1.8.13.phaseplant");
+        let mut cursor = Cursor::new(Vec::new());
+        preset.write(&mut cursor).unwrap();
+        cursor.seek(SeekFrom::Start(0)).unwrap();
+        let read = Preset::read(&mut cursor, None).unwrap();
+        assert_eq!(read.master_pitch, 12.5);
+    }
+
+    /// Phase Plant 1 stored a retrigger switch and a one shot switch with
+    /// each modulator. They are converted to the note trigger mode and the
+    /// loop mode when read and aren't written back.
+    #[test]
+    fn legacy_triggers() {
+        let preset =
+            read_modulator_preset("lfo", "lfo-retrig_off-1shot-depth50-1.8.14.phaseplant").unwrap();
+        let mut cursor = Cursor::new(Vec::new());
+        preset.write(&mut cursor).unwrap();
+        cursor.seek(SeekFrom::Start(0)).unwrap();
+        let read = Preset::read(&mut cursor, None).unwrap();
+        let modulator: &LfoModulator = read.modulator(0).unwrap();
+        assert_eq!(modulator.note_trigger_mode, NoteTriggerMode::Never);
+        assert_eq!(modulator.loop_mode, LoopMode::Off);
+    }
+
+    #[test]
+    fn modulator_slots() {
+        let flags = |preset: &Preset| {
+            preset
+                .modulator_containers
+                .iter()
+                .map(|container| {
+                    (
+                        container.id,
+                        container.group_id,
+                        container.enabled,
+                        container.minimized,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let rewrite = |preset: &Preset| {
+            let mut cursor = Cursor::new(Vec::new());
+            preset.write(&mut cursor).unwrap();
+            cursor.seek(SeekFrom::Start(0)).unwrap();
+            crate::lowlevel::Blocks::read(&mut cursor).unwrap()
+        };
+        for file in [
+            "envelope-minimized-depth50-1.8.13.phaseplant",
+            "envelope-disabled-1.8.13.phaseplant",
+        ] {
+            let preset = read_modulator_preset("envelope", file).unwrap();
+            let read = rewrite(&preset);
+            assert_eq!(flags(read.preset()), flags(&preset), "{file}");
+        }
+
+        // Deleting a modulator from the middle leaves a blank slot.
+        let mut preset = read_preset("modulation", "random-modulates-other-2.1.0.phaseplant");
+        assert_eq!(preset.modulator_containers[1].id, 1);
+        preset.modulator_containers[1].minimized = true;
+        preset.modulator_containers.remove(0);
+        let read = rewrite(&preset);
+        let slots: Vec<_> = read
+            .modulators()
+            .take(2)
+            .map(|block| (block.mode(), block.id(), block.minimized()))
+            .collect();
+        assert_eq!(slots[0].0, crate::modulator::ModulatorMode::Blank);
+        assert_eq!(
+            slots[1],
+            (preset.modulator_containers[0].modulator.mode(), 1, true)
+        );
+        assert_eq!(
+            read.preset().modulator_containers,
+            preset.modulator_containers
+        );
+    }
+
+    /// The first data block of a modulator holds the shape. The second is
+    /// kept as it was read.
+    #[test]
+    fn modulator_data_blocks() {
+        let mut preset = read_modulator_preset("lfo", "lfo-2.1.0.phaseplant").unwrap();
+        let shape = preset.modulator_containers[0]
+            .modulator
+            .as_block()
+            .shape
+            .clone();
+        assert!(!shape.is_empty());
+        assert_eq!(preset.modulator_containers[0].second_data_block, None);
+
+        let second_data_block = RawDataBlock {
+            mode_id: 1,
+            contents: vec![1, 2, 3, 4, 5],
+        };
+        preset.modulator_containers[0].second_data_block = Some(second_data_block.clone());
+        let mut cursor = Cursor::new(Vec::new());
+        preset.write(&mut cursor).unwrap();
+        cursor.seek(SeekFrom::Start(0)).unwrap();
+        let read = Preset::read(&mut cursor, None).unwrap();
+        let container = &read.modulator_containers[0];
+        assert_eq!(container.modulator.as_block().shape, shape);
+        assert_eq!(container.second_data_block, Some(second_data_block));
+    }
+
+    /// None of the fixtures have a second modulator data block, so one is
+    /// spliced into a fixture after the shape of its LFO, where Phase Plant
+    /// stores it.
+    #[test]
+    fn modulator_second_data_block_fixture() {
+        let path = test_data_path(&["modulators", "lfo", "lfo-2.1.0.phaseplant"]);
+        let mut bytes = fs::read(path).unwrap();
+        let preset = Preset::read(&mut Cursor::new(&bytes), None).unwrap();
+        let shape = preset.modulator_containers[0]
+            .modulator
+            .as_block()
+            .shape
+            .clone();
+
+        // The used shape block followed by the unused second block.
+        let mut blocks = Cursor::new(Vec::new());
+        let mut writer = PhasePlantWriter::new(&mut blocks);
+        let data_length = shape.len() * (5 * size_of::<u32>()) + size_of::<u32>();
+        writer
+            .write_block_header(&DataBlockHeader::new_used(data_length, 1))
+            .unwrap();
+        writer.write_curve_points(&shape).unwrap();
+        let shape_end = writer.inner.stream_position().unwrap() as usize;
+        writer
+            .write_block_header(&DataBlockHeader::new_unused())
+            .unwrap();
+        let blocks = blocks.into_inner();
+        let start = bytes
+            .windows(blocks.len())
+            .position(|window| window == blocks.as_slice())
+            .expect("shape data block");
+
+        let second_data_block = RawDataBlock {
+            mode_id: 2,
+            contents: vec![1, 2, 3, 4, 5, 6, 7],
+        };
+        let mut second_block = Cursor::new(Vec::new());
+        let mut writer = PhasePlantWriter::new(&mut second_block);
+        writer
+            .write_block_header(&DataBlockHeader::new_used(
+                second_data_block.contents.len(),
+                second_data_block.mode_id,
+            ))
+            .unwrap();
+        writer.write_all_u8(&second_data_block.contents).unwrap();
+        let second_block = second_block.into_inner();
+        bytes.splice(
+            start + shape_end..start + blocks.len(),
+            second_block.clone(),
+        );
+
+        let preset = Preset::read(&mut Cursor::new(&bytes), None).unwrap();
+        let container = &preset.modulator_containers[0];
+        assert_eq!(container.modulator.as_block().shape, shape);
+        assert_eq!(container.second_data_block, Some(second_data_block.clone()));
+
+        let mut cursor = Cursor::new(Vec::new());
+        preset.write(&mut cursor).unwrap();
+        let written = cursor.into_inner();
+        let mut expected = blocks[..shape_end].to_vec();
+        expected.extend_from_slice(&second_block);
+        assert!(written
+            .windows(expected.len())
+            .any(|window| window == expected.as_slice()));
+        let read = Preset::read(&mut Cursor::new(&written), None).unwrap();
+        assert_eq!(
+            read.modulator_containers[0].second_data_block,
+            Some(second_data_block)
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let mut preset = Preset::default();
+        assert!(preset.check_limits().is_ok());
+
+        for index in 0..=GENERATORS_MAX {
+            preset
+                .generators
+                .push(Box::new(crate::generator::AnalogOscillator {
+                    name: format!("Osc {}", index + 1),
+                    ..Default::default()
+                }));
+        }
+        preset.macro_controls.push(MacroControl::new("Extra"));
+        let mut cursor = Cursor::new(Vec::new());
+        let error = preset.write(&mut cursor).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            "Preset exceeds the limits of Phase Plant: 33 generators is more than 32, \
+            the extra generators are 33 \"Osc 33\" (Analog); 9 macro controls is more \
+            than 8, the extra macro controls are 9 \"Extra\""
+        );
+        assert!(cursor.into_inner().is_empty());
+    }
+
+    #[test]
+    fn modulator_ids() {
+        let mut preset = read_preset("modulation", "random-modulates-other-2.1.0.phaseplant");
+        assert!(preset.check_limits().is_ok());
+
+        preset.modulator_containers[1].id = preset.modulator_containers[0].id;
+        let error = preset.check_limits().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Modulator ID {} is used more than once",
+                preset.modulator_containers[0].id
+            )
+        );
+
+        preset.modulator_containers[1].id = MODULATORS_MAX as crate::modulator::ModulatorId;
+        let mut cursor = Cursor::new(Vec::new());
+        let error = preset.write(&mut cursor).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), "Modulator ID 32 is not less than 32");
+        assert!(cursor.into_inner().is_empty());
+    }
+
+    #[test]
+    fn group_name_truncation() {
+        let mut preset = Preset::default();
+        preset.generators.push(Box::new(crate::generator::Group {
+            name: "💖".repeat(Group::MAX_NAME_LENGTH + 5),
+            ..Default::default()
+        }));
+        let mut cursor = Cursor::new(Vec::new());
+        let result = preset.write(&mut cursor).unwrap();
+        assert_eq!(result.messages.len(), 1);
+        assert_eq!(result.messages[0].level, Level::Warn);
+        cursor.seek(SeekFrom::Start(0)).unwrap();
+        let read = Preset::read(&mut cursor, None).unwrap();
+        assert_eq!(
+            read.generators[0].name(),
+            "💖".repeat(Group::MAX_NAME_LENGTH)
+        );
+    }
+
+    #[test]
+    fn names() {
+        let mut preset = Preset::default();
+        preset.macro_controls[0].name = "Bass\nLead".to_owned();
+        preset.lanes[0].snapins.push(Snapin {
+            name: "Gain\t".to_owned(),
+            ..Snapin::new(Box::<crate::effect::Gain>::default(), 1, true, false)
+        });
+        let error = preset.write(&mut Cursor::new(Vec::new())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            "Names can't contain control characters: \"Gain\\t\", \"Bass\\nLead\""
+        );
+
+        preset.sanitize_names();
+        assert!(preset.check_names().is_ok());
+        assert_eq!(preset.macro_controls[0].name, "Bass Lead");
+        assert_eq!(preset.lanes[0].snapins[0].name, "Gain");
+
+        // Text that couldn't be read back.
+        preset.lanes[0].snapins[0].preset_name = "a".repeat(READ_STRING_LENGTH_MAX as usize + 1);
+        let error = preset.write(&mut Cursor::new(Vec::new())).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn unused_modulation_slots() {
+        let stale = Modulation {
+            enabled: false,
+            amount: Ratio::new::<percent>(50.0),
+            ..Modulation::unused()
+        };
+        let mut preset = Preset::default();
+        preset.modulations.push(Modulation {
+            enabled: false,
+            ..Default::default()
+        });
+        preset.unused_modulation_slots.insert(1, stale.clone());
+        preset.unused_modulation_slots.insert(5, stale.clone());
+
+        let mut cursor = Cursor::new(Vec::new());
+        preset.write(&mut cursor).unwrap();
+        cursor.seek(SeekFrom::Start(0)).unwrap();
+        let read = Preset::read(&mut cursor, None).unwrap();
+        assert_eq!(read.modulations.len(), 1);
+        assert!(!read.modulations[0].enabled);
+        assert_eq!(read.unused_modulation_slots, preset.unused_modulation_slots);
+
+        // A modulation in the slot replaces the unused values.
+        preset.modulations.push(Modulation::default());
+        let mut cursor = Cursor::new(Vec::new());
+        preset.write(&mut cursor).unwrap();
+        cursor.seek(SeekFrom::Start(0)).unwrap();
+        let read = Preset::read(&mut cursor, None).unwrap();
+        assert_eq!(read.modulations.len(), 2);
+        assert_eq!(read.unused_modulation_slots.len(), 1);
+        assert_eq!(read.unused_modulation_slots.get(&5), Some(&stale));
+    }
+
+    #[test]
+    fn write_with_options() {
+        let mut preset = Preset::default();
+        preset.unison.voices = 12;
+        let options = WriteOptions {
+            clamp_out_of_range: true,
+            ..Default::default()
+        };
+        let mut cursor = Cursor::new(Vec::new());
+        let result = preset.write_with_options(&mut cursor, &options).unwrap();
+        assert_eq!(result.messages.len(), 1);
+        assert_eq!(preset.unison.voices, 12);
+        cursor.seek(SeekFrom::Start(0)).unwrap();
+        let read = Preset::read(&mut cursor, None).unwrap();
+        assert_eq!(read.unison.voices, Unison::VOICES_MAX);
+    }
+
+    #[test]
+    fn preserve_format_version() {
+        let options = WriteOptions {
+            preserve_format_version: true,
+            ..Default::default()
+        };
+        let rewrite = |preset: &Preset| {
+            let mut cursor = Cursor::new(Vec::new());
+            let result = preset.write_with_options(&mut cursor, &options).unwrap();
+            cursor.seek(SeekFrom::Start(0)).unwrap();
+            (Preset::read(&mut cursor, None).unwrap(), result.messages)
+        };
+
+        for (dir, file) in [
+            ("init", "init-2.0.0.phaseplant"),
+            ("init", "init-2.0.12.phaseplant"),
+            ("effects/filter", "filter-2.0.16.phaseplant"),
+            ("modulators/slew_limiter", "slew_limiter-2.0.13.phaseplant"),
+        ] {
+            // The name comes from the file name rather than its contents.
+            let mut preset = read_preset(dir, file);
+            preset.metadata.name = None;
+            let (read, messages) = rewrite(&preset);
+            assert!(messages.is_empty(), "{file}");
+            assert_eq!(read, preset, "{file}");
+        }
+
+        // Older layouts and newer features are written as the latest release.
+        let mut preset = read_preset("init", "init-1.8.13.phaseplant");
+        let (read, messages) = rewrite(&preset);
+        assert_eq!(read.format_version, FORMAT_VERSION);
+        assert_eq!(messages[0].level, Level::Warn);
+        preset.format_version = PhasePlantRelease::V2_0_16.format_version();
+        preset
+            .generators
+            .push(Box::<crate::generator::GranularGenerator>::default());
+        let (read, messages) = rewrite(&preset);
+        assert_eq!(read.format_version, FORMAT_VERSION);
+        assert!(messages[0].description.contains("2.1.0"));
+    }
 }
@@ -3,21 +3,38 @@
 //!
 //! Phase Plant presets can be combined into a bank using the
 //! [`kibank`](https://crates.io/crates/kibank) application and library.
-
+//!
+//! # Thread Safety
+//!
+//! A [`Preset`] is `Send + Sync` and [`Clone`]. The effect, generator and
+//! modulator trait objects it holds require `Send + Sync` and can be cloned
+//! through `clone_box`, so a preset can be shared between threads behind an
+//! [`Arc`](std::sync::Arc) and cloned when a thread needs its own copy to
+//! change. Nothing in a preset uses interior mutability, so shared references
+//! never observe changes.
+
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::io::{Error, ErrorKind};
 
-use strum_macros::FromRepr;
+use strum_macros::{EnumIter, FromRepr};
 use uom::num::Zero;
 use uom::si::f32::{Frequency, Ratio, Time};
 use uom::si::ratio::percent;
 
 pub use decibels::*;
 pub use envelope::*;
+pub use id_allocator::IdAllocator;
+pub use interpolate::Interpolate;
 pub use io::*;
+use limits::{GENERATORS_MAX, MODULATORS_MAX, PATH_COMPONENT_COUNT_MAX};
 pub use macro_control::*;
 pub use metadata::*;
 pub use point::*;
+pub use resolve::*;
+pub use routing::*;
+pub use semitones::*;
+pub use size::*;
 pub use snapin::*;
 pub use unison::*;
 pub use version::*;
@@ -26,42 +43,56 @@ use crate::effect::Effect;
 use crate::generator::{Generator, GeneratorId};
 use crate::modulation::Modulation;
 use crate::modulator::{Modulator, ModulatorContainer};
+use crate::path::impl_parameters;
 
+pub mod analysis;
+mod arbitrary;
+mod canonical;
 mod decibels;
+pub mod diff;
+pub mod edit;
 pub mod effect;
 mod envelope;
+pub mod fixture;
 pub mod generator;
+mod id_allocator;
+mod interpolate;
 mod io;
+pub mod library;
+pub mod limits;
+pub mod lowlevel;
 mod macro_control;
 mod metadata;
 pub mod modulation;
 pub mod modulator;
+mod patch_sheet;
+mod path;
 mod point;
+pub mod relink;
+mod resolve;
+mod routing;
+mod script;
+pub mod search;
+mod semitones;
+mod size;
 mod snapin;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+pub mod store;
 mod text;
+pub mod tracked;
 mod unison;
+pub mod variation;
+pub mod verify;
 mod version;
 
-/// Number of generators. Unused generators in the file are ignored.
-const GENERATORS_MAX: GeneratorId = 32;
-
-/// Upper limit on the size of the JSON metadata. The length is stored as a u32 so it
-/// could be use as a denial of service if there was no other limit.
-const METADATA_LENGTH_MAX: usize = 64 * 1024;
-
-/// Number of modulator blocks. Unused modulator blocks in the file are ignored.
-const MODULATORS_MAX: usize = 32;
-
 /// Each modulator is allocated 100 bytes plus a plus a header.
 const MODULATOR_BLOCK_SIZE: usize = 100;
 
-/// How many parts are allowed when specifying a path.
-const PATH_COMPONENT_COUNT_MAX: usize = 100; // TODO: Operating system limit?
-
 /// Length of a note.
 ///
 /// See also: [`PatternResolution`](effect::PatternResolution)
-#[derive(Clone, Copy, Debug, Eq, FromRepr, PartialEq)]
+#[derive(Clone, Copy, Debug, EnumIter, Eq, FromRepr, PartialEq)]
 #[repr(u32)]
 pub enum NoteValue {
     // The discriminants correspond to the file format.
@@ -74,12 +105,41 @@ pub enum NoteValue {
     ThirtySecond,
     ThirtySecondTriplet,
     SixtyFourth,
+
+    /// Added by a newer version of Phase Plant. The value is kept so it can be
+    /// written back.
+    #[strum(disabled)]
+    Unknown(u32),
 }
 
 impl NoteValue {
-    pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
-        Self::from_repr(id)
-            .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("Unknown note value {id}")))
+    /// The value stored in presets.
+    pub fn id(&self) -> u32 {
+        use NoteValue::*;
+        match self {
+            Quarter => 0,
+            QuarterTriplet => 1,
+            Eighth => 2,
+            EightTriplet => 3,
+            Sixteenth => 4,
+            SixteenthTriplet => 5,
+            ThirtySecond => 6,
+            ThirtySecondTriplet => 7,
+            SixtyFourth => 8,
+            Unknown(id) => *id,
+        }
+    }
+}
+
+impl ForwardCompatible for NoteValue {
+    const DESCRIPTION: &'static str = "note value";
+
+    fn from_id(id: u32) -> Self {
+        Self::from_repr(id).unwrap_or(Self::Unknown(id))
+    }
+
+    fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown(_))
     }
 }
 
@@ -96,11 +156,14 @@ impl Display for NoteValue {
             ThirtySecond => "1/32",
             ThirtySecondTriplet => "1/32T",
             SixtyFourth => "1/64",
+            Unknown(id) => return write!(f, "Unknown ({id})"),
         };
         f.write_str(msg)
     }
 }
 
+impl_parameters!(NoteValue);
+
 /// A rate defines the speed of an operation. It can be determined by frequency
 /// or based on the song tempo.
 #[derive(Clone, Debug, PartialEq)]
@@ -114,7 +177,14 @@ pub struct Rate {
     pub sync: bool,
 }
 
-#[derive(Copy, Clone, Debug, Default, Eq, FromRepr, PartialEq)]
+impl_parameters!(Rate {
+    frequency,
+    numerator,
+    denominator,
+    sync,
+});
+
+#[derive(Copy, Clone, Debug, Default, EnumIter, Eq, FromRepr, Hash, PartialEq)]
 #[repr(u32)]
 pub enum LaneDestination {
     // The discriminants correspond to the file format. They are not the same
@@ -124,7 +194,11 @@ pub enum LaneDestination {
     #[default]
     Master = 1,
     Lane1 = 3,
-    // FIXME: Value is guessed, it's not in the data for the lanes, will find it in the noise generator
+
+    /// None of the test presets send a lane to the sideband so the value is
+    /// assumed to be the one generators use for
+    /// [`OutputDestination::Sideband`](generator::OutputDestination::Sideband).
+    // FIXME: Confirm with a preset that sends a lane to the sideband.
     Sideband = 5,
 }
 
@@ -137,6 +211,21 @@ impl LaneDestination {
             )
         })
     }
+
+    /// The value stored in the file.
+    pub(crate) fn id(&self) -> u32 {
+        *self as u32
+    }
+
+    /// Zero-based index of the lane, if the destination is a lane.
+    pub fn lane_index(&self) -> Option<usize> {
+        match self {
+            LaneDestination::Lane1 => Some(0),
+            LaneDestination::Lane2 => Some(1),
+            LaneDestination::Lane3 => Some(2),
+            _ => None,
+        }
+    }
 }
 
 impl Display for LaneDestination {
@@ -155,7 +244,15 @@ impl Display for LaneDestination {
 
 pub type LaneId = u8;
 
-#[derive(Debug, PartialEq)]
+/// One of the three effect lanes.
+///
+/// Lanes only receive the output of generators and can't take the external
+/// sidechain input directly. The bytes around the lane settings that aren't
+/// decoded are zero in every test preset, so there is no routing setting
+/// left to expose. Effects that listen to the sidechain choose it
+/// themselves with [`SidechainMode`](effect::SidechainMode), and a lane can
+/// send its output to the sideband with [`LaneDestination::Sideband`].
+#[derive(Clone, Debug, PartialEq)]
 pub struct Lane {
     pub enabled: bool,
 
@@ -174,18 +271,136 @@ pub struct Lane {
 }
 
 impl Lane {
-    pub const COUNT: usize = 3;
+    pub const COUNT: usize = limits::LANE_COUNT;
 
     /// Find the first snapin that has an effect with the given type.
     pub fn find_effect<T: Effect>(&self) -> Option<(&Snapin, &T)> {
         // Returns the effect so it's already the right type
         self.snapins
             .iter()
-            .find(|snapin| snapin.effect.downcast_ref::<T>().is_some())
-            .map(|snapin| (snapin, snapin.effect.downcast_ref::<T>().unwrap()))
+            .find_map(|snapin| snapin.effect_as::<T>().map(|effect| (snapin, effect)))
+    }
+
+    /// The effects in the lane with the given type, in lane order. Effects
+    /// nested in a Multipass are not included.
+    ///
+    /// Checking the type of an effect only compares type identifiers so the
+    /// snapins are not indexed by mode. An index could not be kept up to date
+    /// because the snapins can be changed directly.
+    pub fn effects_of<T: Effect>(&self) -> impl Iterator<Item = &T> {
+        self.snapins.iter().filter_map(Snapin::effect_as::<T>)
+    }
+
+    /// Put the snapin with the ID in a group, or take it out of its group
+    /// with `None`. The group must be a [`Group`](effect::Group) effect
+    /// before the snapin in the lane.
+    pub fn set_group(
+        &mut self,
+        snapin_id: SnapinId,
+        group_id: Option<SnapinId>,
+    ) -> Result<(), Error> {
+        let index = self
+            .snapins
+            .iter()
+            .position(|snapin| snapin.id == snapin_id)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("There is no snapin with ID {snapin_id} in the lane"),
+                )
+            })?;
+        if let Some(problem) = group_id.and_then(|group_id| self.group_problem(index, group_id)) {
+            return Err(Error::new(ErrorKind::InvalidInput, problem));
+        }
+        self.snapins[index].group_id = group_id;
+        Ok(())
+    }
+
+    /// Check that every snapin in a group refers to a
+    /// [`Group`](effect::Group) effect before it in the lane.
+    pub fn check_groups(&self) -> Result<(), Error> {
+        let problems: Vec<String> = self
+            .snapins
+            .iter()
+            .enumerate()
+            .filter_map(|(index, snapin)| {
+                snapin
+                    .group_id
+                    .and_then(|group_id| self.group_problem(index, group_id))
+            })
+            .collect();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::new(ErrorKind::InvalidInput, problems.join("; ")))
+        }
+    }
+
+    /// The snapins in the order they process audio, without the
+    /// [`Group`](effect::Group) snapins that only contain them. A snapin is
+    /// only enabled if the lane and every group it is nested in are enabled.
+    /// A Multipass is a single step because its lanes are processed in
+    /// parallel.
+    pub fn flattened_chain(&self) -> Vec<ChainStep<'_>> {
+        self.snapins
+            .iter()
+            .enumerate()
+            .filter(|(_, snapin)| snapin.effect.as_group().is_none())
+            .map(|(position, snapin)| {
+                let mut groups = Vec::new();
+                let mut enabled = self.enabled && snapin.enabled;
+                let mut member = snapin;
+                // Limited in case the groups refer to each other.
+                while let Some(group) = member.group(self) {
+                    if groups.len() >= self.snapins.len() {
+                        break;
+                    }
+                    enabled &= group.enabled;
+                    groups.insert(0, group.id);
+                    member = group;
+                }
+                ChainStep {
+                    snapin,
+                    position,
+                    enabled,
+                    groups,
+                }
+            })
+            .collect()
+    }
+
+    fn group_problem(&self, index: usize, group_id: SnapinId) -> Option<String> {
+        let snapin = &self.snapins[index];
+        let problem = match self.snapins.iter().position(|group| group.id == group_id) {
+            None => "does not exist",
+            Some(group_index) if self.snapins[group_index].effect.as_group().is_none() => {
+                "is not a Group effect"
+            }
+            Some(group_index) if group_index >= index => "is not before it",
+            Some(_) => return None,
+        };
+        Some(format!(
+            "Group {group_id} of snapin \"{}\" (ID {}) {problem}",
+            snapin.name, snapin.id
+        ))
     }
 }
 
+/// A snapin in the [flattened chain](Lane::flattened_chain) of a lane.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainStep<'a> {
+    pub snapin: &'a Snapin,
+
+    /// Index of the snapin in the lane.
+    pub position: usize,
+
+    /// If the snapin processes audio.
+    pub enabled: bool,
+
+    /// IDs of the groups the snapin is in, outermost first.
+    pub groups: Vec<SnapinId>,
+}
+
 impl Default for Lane {
     fn default() -> Self {
         Self {
@@ -201,7 +416,7 @@ impl Default for Lane {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Preset {
     pub format_version: Version<u32>,
     pub generators: Vec<Box<dyn Generator>>,
@@ -225,13 +440,25 @@ pub struct Preset {
 
     pub master_pitch: f32,
     pub metadata: Metadata,
+    /// The position of a modulation is the slot it's stored in.
     pub modulations: Vec<Modulation>,
+
+    /// Values left in the slots after the modulations, by slot index. Phase
+    /// Plant doesn't always reset a slot when its modulation is removed so
+    /// they are written back to the same slots unless a modulation now uses
+    /// the slot.
+    pub unused_modulation_slots: BTreeMap<usize, Modulation>,
+
     pub modulator_containers: Vec<ModulatorContainer>,
     pub polyphony: u32,
 
     /// When enabled LFO restarts for each new voice, disabled all voices share a global LFO.
     pub retrigger_enabled: bool,
     pub unison: Unison,
+
+    /// Samples and wavetables that haven't been loaded because the preset
+    /// was read with [`EmbeddedData::Length`]. See [`Preset::load_embedded`].
+    pub embedded_locations: Vec<EmbeddedLocation>,
 }
 
 impl Default for Preset {
@@ -264,10 +491,12 @@ impl Default for Preset {
             master_pitch: 0.0,
             metadata: Default::default(),
             modulations: Vec::new(),
+            unused_modulation_slots: BTreeMap::new(),
             modulator_containers: Vec::new(),
             polyphony: 8,
             retrigger_enabled: true,
             unison: Default::default(),
+            embedded_locations: Vec::new(),
         }
     }
 }
@@ -342,6 +571,51 @@ pub(crate) mod test {
         Ok(preset)
     }
 
+    /// Write the effect of the snapin after a format version header.
+    pub(crate) fn write_effect(snapin: &Snapin) -> io::Result<Vec<u8>> {
+        let mut cursor = Cursor::new(Vec::with_capacity(1024));
+        let mut writer = PhasePlantWriter::new(&mut cursor);
+        let format_version = PhasePlantRelease::V2_1_0.format_version();
+        writer.write_u32(format_version.major)?;
+        writer.write_u32(format_version.patch)?;
+        writer.write_u32(format_version.minor)?;
+        snapin.write_effect(&mut writer)?;
+        Ok(cursor.into_inner())
+    }
+
+    /// Write the preset then read it back with the same name.
+    pub(crate) fn rewrite(preset: &Preset) -> io::Result<Preset> {
+        let mut cursor = Cursor::new(Vec::with_capacity(16 * 1024));
+        preset.write(&mut cursor)?;
+        cursor.set_position(0);
+        Preset::read(&mut cursor, preset.metadata.name.clone())
+    }
+
+    /// Write the effect of the snapin then read it back using the effect
+    /// version of the snapin. Fails if the effect doesn't read back all of
+    /// what was written.
+    pub(crate) fn rewrite_effect(snapin: &Snapin) -> io::Result<EffectReadReturn> {
+        let written = write_effect(snapin)?;
+        let length = written.len() as u64;
+        let mut reader = PhasePlantReader::new(Cursor::new(written))?;
+        let read = snapin
+            .effect
+            .mode()
+            .read_effect(&mut reader, snapin.effect_version)?;
+        let remaining = length - reader.stream_position()?;
+        if remaining != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} version {} had {remaining} bytes remaining",
+                    snapin.effect.mode(),
+                    snapin.effect_version
+                ),
+            ));
+        }
+        Ok(read)
+    }
+
     fn _rewrite_preset(preset: &Preset, file_name: &str) -> Preset {
         let mut write_cursor = Cursor::new(Vec::with_capacity(16 * 1024));
         match preset.write(&mut write_cursor) {
@@ -459,12 +733,51 @@ pub(crate) mod test {
         }
     }
 
+    #[test]
+    fn clone() {
+        let preset = read_preset("misc", "analog-3ofwithgroup-1.8.13.phaseplant");
+        assert!(!preset.generators.is_empty());
+        assert_eq!(preset.clone(), preset);
+
+        let preset = read_modulator_preset("lfo", "lfo-1.8.13.phaseplant").unwrap();
+        assert!(!preset.modulator_containers.is_empty());
+        assert_eq!(preset.clone(), preset);
+
+        let preset = read_effect_preset("multipass", "multipass-2.1.0.phaseplant").unwrap();
+        assert_eq!(preset.clone(), preset);
+    }
+
+    #[test]
+    fn effects_of() {
+        let preset = read_effect_preset("stereo", "stereo-5of-1.8.13.phaseplant").unwrap();
+        let lane = &preset.lanes[0];
+        assert_eq!(lane.effects_of::<effect::Stereo>().count(), 5);
+        assert_eq!(lane.effects_of::<effect::Gain>().count(), 0);
+        let (snapin, stereo) = lane.find_effect::<effect::Stereo>().unwrap();
+        assert_eq!(snapin.effect_as::<effect::Stereo>(), Some(stereo));
+    }
+
+    #[test]
+    fn send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Preset>();
+        assert_send_sync::<Box<dyn Effect>>();
+        assert_send_sync::<Box<dyn Generator>>();
+        assert_send_sync::<Box<dyn Modulator>>();
+
+        let preset = std::sync::Arc::new(read_preset("misc", "glide-on-1.8.13.phaseplant"));
+        let shared = preset.clone();
+        let glide_enabled = std::thread::spawn(move || shared.glide_enabled)
+            .join()
+            .unwrap();
+        assert_eq!(glide_enabled, preset.glide_enabled);
+    }
+
     #[test]
     fn default() {
         let preset = Preset::default();
         assert_eq!(preset.format_version.major, 6);
 
-        // FIXME: USE FOR INIT PRESET
         let metadata = &preset.metadata;
         assert!(metadata.author.is_none());
         assert!(metadata.description.is_none());
@@ -0,0 +1,841 @@
+//! Operations across a collection of presets, such as a folder of presets
+//! that are about to be shared.
+
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::{Cursor, ErrorKind};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use log::warn;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::diff::PresetDiff;
+use crate::generator::{GranularGenerator, SamplePlayer, WavetableOscillator};
+use crate::modulator::LfoTableModulator;
+use crate::{Preset, Snapin};
+
+/// File extension of Phase Plant presets.
+pub const PRESET_EXTENSION: &str = "phaseplant";
+
+/// Which Kilohearts factory presets are used by the snapins in a library of
+/// presets. Useful for auditing what a preset depends on before sharing it.
+#[derive(Debug, Default)]
+pub struct FactoryPresetIndex {
+    /// Preset path of the factory preset to the presets that use it.
+    references: BTreeMap<Vec<String>, Vec<PathBuf>>,
+
+    /// Presets that could not be read while scanning.
+    pub unreadable: Vec<PathBuf>,
+}
+
+impl FactoryPresetIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recursively scan a directory for presets and index the factory presets
+    /// they use. Presets that cannot be read are recorded in
+    /// [`unreadable`](Self::unreadable) instead of stopping the scan.
+    pub fn scan_dir<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        let mut index = Self::new();
+        for path in preset_paths(dir.as_ref())? {
+            match Preset::read_file(&path) {
+                Ok(preset) => index.add_preset(&path, &preset),
+                Err(error) => {
+                    warn!("Unable to read preset {path:?}: {error}");
+                    index.unreadable.push(path);
+                }
+            }
+        }
+        Ok(index)
+    }
+
+    /// Index the factory presets used by the snapins of the preset, including
+    /// snapins hosted inside of Multipass.
+    pub fn add_preset(&mut self, path: &Path, preset: &Preset) {
+        for lane in &preset.lanes {
+            self.add_snapins(path, &lane.snapins);
+        }
+    }
+
+    fn add_snapins(&mut self, path: &Path, snapins: &[Snapin]) {
+        for snapin in snapins {
+            if snapin.is_factory_preset() {
+                let presets = self
+                    .references
+                    .entry(snapin.preset_path.clone())
+                    .or_default();
+                if !presets.iter().any(|p| p == path) {
+                    presets.push(path.to_path_buf());
+                }
+            }
+            if let Some(multipass) = snapin.effect.as_multipass() {
+                for lane in &multipass.lanes {
+                    self.add_snapins(path, &lane.snapins);
+                }
+            }
+        }
+    }
+
+    /// Preset paths of every factory preset that is used, in sorted order.
+    pub fn factory_presets(&self) -> impl Iterator<Item = &[String]> {
+        self.references.keys().map(Vec::as_slice)
+    }
+
+    /// Presets that use the factory preset with the given preset path.
+    pub fn presets_using(&self, preset_path: &[String]) -> &[PathBuf] {
+        self.references
+            .get(preset_path)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.references.is_empty()
+    }
+
+    /// Number of distinct factory presets that are used.
+    pub fn len(&self) -> usize {
+        self.references.len()
+    }
+}
+
+/// Step of a round trip where a preset failed.
+#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum RoundTripStage {
+    /// Reading the original file.
+    Read,
+    /// Checking the preset that was read can be written, such as being
+    /// within the limits of Phase Plant and having modulations whose targets
+    /// exist.
+    Validate,
+    /// Writing the preset that was read.
+    Write,
+    /// Reading the preset that was written.
+    Reread,
+    /// The preset that was read back has different parameters.
+    Compare,
+}
+
+impl Display for RoundTripStage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use RoundTripStage::*;
+        f.write_str(match self {
+            Read => "read",
+            Validate => "validate",
+            Write => "write",
+            Reread => "re-read",
+            Compare => "compare",
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RoundTripFailure {
+    pub path: PathBuf,
+    pub stage: RoundTripStage,
+    pub message: String,
+
+    /// The stage panicked instead of returning an error.
+    pub panicked: bool,
+}
+
+/// Parameters that the writer changes on purpose. The name of a preset comes
+/// from its file name and isn't written.
+const ROUND_TRIP_IGNORED: &[&str] = &["metadata.name"];
+
+/// Why a stage of a round trip failed.
+struct StageError {
+    stage: RoundTripStage,
+    error: io::Error,
+    panicked: bool,
+}
+
+/// Results of reading, writing and reading back every preset in a library.
+/// Running it over a large collection of presets shows which parts of the
+/// format are not yet understood.
+#[derive(Debug, Default)]
+pub struct RoundTripReport {
+    /// Number of presets that made it through every stage.
+    pub passed: usize,
+
+    /// Presets saved by versions of Phase Plant that are not supported.
+    pub unsupported: Vec<PathBuf>,
+
+    pub failures: Vec<RoundTripFailure>,
+}
+
+impl RoundTripReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recursively check every preset in a directory.
+    pub fn scan_dir<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        let mut report = Self::new();
+        for path in preset_paths(dir.as_ref())? {
+            report.check_file(&path);
+        }
+        Ok(report)
+    }
+
+    /// Read, validate, write and read back a single preset, recording the
+    /// first stage that fails. A stage that panics is recorded as a failure
+    /// with the panic message instead of stopping the check.
+    pub fn check_file(&mut self, path: &Path) {
+        match Self::round_trip(path) {
+            Ok(()) => self.passed += 1,
+            Err(failure) if !failure.panicked && failure.error.kind() == ErrorKind::Unsupported => {
+                self.unsupported.push(path.to_path_buf())
+            }
+            Err(failure) => self.failures.push(RoundTripFailure {
+                path: path.to_path_buf(),
+                stage: failure.stage,
+                message: failure.error.to_string(),
+                panicked: failure.panicked,
+            }),
+        }
+    }
+
+    fn round_trip(path: &Path) -> Result<(), StageError> {
+        use RoundTripStage::*;
+        let preset = run_stage(Read, || Preset::read_file(path))?;
+        run_stage(Validate, || validate(&preset))?;
+
+        let mut cursor = Cursor::new(Vec::new());
+        run_stage(Write, || preset.write(&mut cursor))?;
+        cursor.set_position(0);
+        let written = run_stage(Reread, || Preset::read(&mut cursor, None))?;
+
+        run_stage(Compare, || {
+            let mut diff = PresetDiff::new(&preset, &written);
+            diff.changes
+                .retain(|change| !ROUND_TRIP_IGNORED.contains(&change.path.as_str()));
+            match diff.changes.as_slice() {
+                [] => Ok(()),
+                [change, ..] => Err(io::Error::other(format!(
+                    "{} parameters differ, the first is {}",
+                    diff.changes.len(),
+                    change.path
+                ))),
+            }
+        })
+    }
+
+    /// Number of presets that were checked.
+    pub fn total(&self) -> usize {
+        self.passed + self.unsupported.len() + self.failures.len()
+    }
+
+    /// Failures at a stage.
+    pub fn failures_at(&self, stage: RoundTripStage) -> impl Iterator<Item = &RoundTripFailure> {
+        self.failures
+            .iter()
+            .filter(move |failure| failure.stage == stage)
+    }
+}
+
+/// Summarizes how many presets failed at each stage and the most common
+/// error messages.
+impl Display for RoundTripReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} presets: {} passed, {} unsupported, {} failed",
+            self.total(),
+            self.passed,
+            self.unsupported.len(),
+            self.failures.len()
+        )?;
+        let mut messages: BTreeMap<(RoundTripStage, &str), usize> = BTreeMap::new();
+        for failure in &self.failures {
+            *messages
+                .entry((failure.stage, failure.message.as_str()))
+                .or_default() += 1;
+        }
+        let mut messages = messages.into_iter().collect::<Vec<_>>();
+        messages.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        for ((stage, message), count) in messages {
+            writeln!(f, "{count:>6} {stage}: {message}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Run a stage of a round trip, turning a panic into a failure.
+fn run_stage<T, F>(stage: RoundTripStage, f: F) -> Result<T, StageError>
+where
+    F: FnOnce() -> io::Result<T>,
+{
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => result.map_err(|error| StageError {
+            stage,
+            error,
+            panicked: false,
+        }),
+        Err(payload) => Err(StageError {
+            stage,
+            error: io::Error::other(format!("Panicked: {}", panic_message(payload.as_ref()))),
+            panicked: true,
+        }),
+    }
+}
+
+/// The checks a preset must pass to be written, and that every modulation
+/// refers to something in the preset.
+fn validate(preset: &Preset) -> io::Result<()> {
+    preset.check_limits()?;
+    preset.check_names()?;
+    for lane in &preset.lanes {
+        lane.check_groups()?;
+    }
+    match preset.check_modulations().first() {
+        None => Ok(()),
+        Some((index, not_found)) => Err(io::Error::new(
+            ErrorKind::InvalidData,
+            format!("Modulation {index}: {not_found}"),
+        )),
+    }
+}
+
+/// The message given to `panic!`.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause")
+}
+
+/// A preset written by [`write_many`].
+#[derive(Debug)]
+pub struct WrittenPreset {
+    pub path: PathBuf,
+
+    /// Writers that panic are reported as an error.
+    pub result: io::Result<()>,
+}
+
+/// Write presets into a directory, in parallel with the `parallel` feature.
+/// Each file is named after the
+/// preset, or its position if the preset doesn't have a name, and names that
+/// repeat are numbered. A preset that can't be written doesn't stop the
+/// others from being written. The results are in the same order as the
+/// presets.
+pub fn write_many<P: AsRef<Path>>(presets: &[Preset], out_dir: P) -> Vec<WrittenPreset> {
+    let paths = preset_file_names(presets)
+        .into_iter()
+        .map(|file_name| out_dir.as_ref().join(file_name))
+        .collect::<Vec<_>>();
+    map_parallel(presets.iter().zip(paths).collect(), |(preset, path)| {
+        let result =
+            catch_unwind(AssertUnwindSafe(|| preset.write_file(&path))).unwrap_or_else(|payload| {
+                Err(io::Error::other(format!(
+                    "Writing panicked: {}",
+                    panic_message(payload.as_ref())
+                )))
+            });
+        WrittenPreset { path, result }
+    })
+}
+
+/// Map the items on every core with the `parallel` feature, otherwise one at
+/// a time. The results are in the same order as the items.
+#[cfg(feature = "parallel")]
+pub(crate) fn map_parallel<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Send + Sync,
+{
+    items.into_par_iter().map(f).collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn map_parallel<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    F: Fn(T) -> R,
+{
+    items.into_iter().map(f).collect()
+}
+
+/// File names that are safe on the common file systems and don't repeat,
+/// ignoring case.
+fn preset_file_names(presets: &[Preset]) -> Vec<String> {
+    let mut used = HashSet::new();
+    presets
+        .iter()
+        .enumerate()
+        .map(|(index, preset)| {
+            let stem = preset
+                .metadata
+                .name
+                .as_deref()
+                .map(|name| {
+                    name.trim()
+                        .replace(|c: char| c.is_control() || "/\\:*?\"<>|".contains(c), "_")
+                })
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("Preset {}", index + 1));
+            let mut file_name = format!("{stem}.{PRESET_EXTENSION}");
+            let mut number = 1;
+            while !used.insert(file_name.to_lowercase()) {
+                number += 1;
+                file_name = format!("{stem} {number}.{PRESET_EXTENSION}");
+            }
+            file_name
+        })
+        .collect()
+}
+
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum AssetKind {
+    Sample,
+    Wavetable,
+}
+
+/// Where an asset is used in a preset.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AssetLocation {
+    /// Index of a Sampler, Granular or Wavetable generator in
+    /// [`Preset::generators`].
+    Generator(usize),
+
+    /// Index of an LFO Table modulator in
+    /// [`Preset::modulator_containers`].
+    Modulator(usize),
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct AssetUse {
+    /// Index of the preset in the presets that were scanned.
+    pub preset: usize,
+    pub location: AssetLocation,
+}
+
+/// The contents of a sample or wavetable that is embedded more than once.
+#[derive(Clone, Debug)]
+pub struct SharedAsset {
+    pub kind: AssetKind,
+
+    /// 64-bit FNV-1a hash of the contents. It doesn't change between
+    /// releases so it can be used to name a shared file.
+    pub hash: u64,
+
+    pub contents: Vec<u8>,
+    pub uses: Vec<AssetUse>,
+}
+
+impl SharedAsset {
+    /// Bytes saved if the asset was stored once instead of in every use.
+    pub fn savings(&self) -> usize {
+        self.contents.len() * (self.uses.len() - 1)
+    }
+}
+
+/// Samples and wavetables that are embedded in more than one place across a
+/// collection of presets. Presets often embed the same sample, which can be
+/// moved to a shared file with [`share`](Self::share).
+#[derive(Clone, Debug, Default)]
+pub struct AssetSharingReport {
+    /// Assets that are used more than once, largest savings first.
+    pub duplicates: Vec<SharedAsset>,
+
+    /// Total size of the embedded assets, including the ones used once.
+    pub embedded_size: usize,
+}
+
+impl AssetSharingReport {
+    pub fn new(presets: &[Preset]) -> Self {
+        let mut embedded_size = 0;
+        let mut uses: HashMap<(AssetKind, &[u8]), Vec<AssetUse>> = HashMap::new();
+        for (preset_index, preset) in presets.iter().enumerate() {
+            for (location, kind, contents) in embedded_assets(preset) {
+                embedded_size += contents.len();
+                uses.entry((kind, contents)).or_default().push(AssetUse {
+                    preset: preset_index,
+                    location,
+                });
+            }
+        }
+        let mut duplicates = uses
+            .into_iter()
+            .filter(|(_, uses)| uses.len() > 1)
+            .map(|((kind, contents), uses)| SharedAsset {
+                kind,
+                hash: fnv1a(contents),
+                contents: contents.to_vec(),
+                uses,
+            })
+            .collect::<Vec<_>>();
+        duplicates.sort_by(|a, b| {
+            b.savings()
+                .cmp(&a.savings())
+                .then(a.kind.cmp(&b.kind))
+                .then(a.hash.cmp(&b.hash))
+        });
+        Self {
+            duplicates,
+            embedded_size,
+        }
+    }
+
+    /// Bytes saved if every duplicate was stored once.
+    pub fn savings(&self) -> usize {
+        self.duplicates.iter().map(SharedAsset::savings).sum()
+    }
+
+    /// Make the duplicated assets reference a shared file instead of being
+    /// embedded. The path of each asset is chosen by the caller, who is
+    /// responsible for writing the contents to it where Phase Plant can find
+    /// it. The presets must be the ones the report was made from.
+    pub fn share<F>(&self, presets: &mut [Preset], mut path: F)
+    where
+        F: FnMut(&SharedAsset) -> String,
+    {
+        for asset in &self.duplicates {
+            let path = path(asset);
+            for asset_use in &asset.uses {
+                let preset = &mut presets[asset_use.preset];
+                if let Some((contents, asset_path)) = asset_mut(preset, asset_use.location) {
+                    contents.clear();
+                    *asset_path = Some(path.clone());
+                }
+            }
+        }
+    }
+}
+
+/// The assets embedded in a preset. Assets that are only referenced by path
+/// are not included.
+fn embedded_assets(preset: &Preset) -> Vec<(AssetLocation, AssetKind, &[u8])> {
+    let mut assets = Vec::new();
+    for (index, generator) in preset.generators.iter().enumerate() {
+        let location = AssetLocation::Generator(index);
+        if let Some(sampler) = generator.as_sampler() {
+            assets.push((location, AssetKind::Sample, &sampler.sample_contents[..]));
+        } else if let Some(granular) = generator.as_granular() {
+            assets.push((location, AssetKind::Sample, &granular.sample_contents[..]));
+        } else if let Some(wavetable) = generator.as_wavetable() {
+            assets.push((
+                location,
+                AssetKind::Wavetable,
+                &wavetable.wavetable_contents[..],
+            ));
+        }
+    }
+    for (index, container) in preset.modulator_containers.iter().enumerate() {
+        if let Some(lfo_table) = container.modulator.as_lfo_table() {
+            assets.push((
+                AssetLocation::Modulator(index),
+                AssetKind::Wavetable,
+                &lfo_table.wavetable_contents[..],
+            ));
+        }
+    }
+    assets.retain(|(_, _, contents)| !contents.is_empty());
+    assets
+}
+
+/// The embedded contents and the path of the asset.
+pub(crate) fn asset(preset: &Preset, location: AssetLocation) -> Option<(&[u8], Option<&str>)> {
+    match location {
+        AssetLocation::Generator(index) => {
+            let generator = preset.generators.get(index)?;
+            if let Some(sampler) = generator.as_sampler() {
+                Some((&sampler.sample_contents, sampler.sample_path.as_deref()))
+            } else if let Some(granular) = generator.as_granular() {
+                Some((&granular.sample_contents, granular.sample_path.as_deref()))
+            } else {
+                let wavetable = generator.as_wavetable()?;
+                Some((
+                    &wavetable.wavetable_contents,
+                    wavetable.wavetable_path.as_deref(),
+                ))
+            }
+        }
+        AssetLocation::Modulator(index) => {
+            let lfo_table = preset
+                .modulator_containers
+                .get(index)?
+                .modulator
+                .as_lfo_table()?;
+            Some((
+                &lfo_table.wavetable_contents,
+                lfo_table.wavetable_path.as_deref(),
+            ))
+        }
+    }
+}
+
+pub(crate) fn asset_mut(
+    preset: &mut Preset,
+    location: AssetLocation,
+) -> Option<(&mut Vec<u8>, &mut Option<String>)> {
+    match location {
+        AssetLocation::Generator(index) => {
+            let generator = preset.generators.get_mut(index)?;
+            if generator.is::<SamplePlayer>() {
+                let sampler = generator.downcast_mut::<SamplePlayer>()?;
+                Some((&mut sampler.sample_contents, &mut sampler.sample_path))
+            } else if generator.is::<GranularGenerator>() {
+                let granular = generator.downcast_mut::<GranularGenerator>()?;
+                Some((&mut granular.sample_contents, &mut granular.sample_path))
+            } else {
+                let wavetable = generator.downcast_mut::<WavetableOscillator>()?;
+                Some((
+                    &mut wavetable.wavetable_contents,
+                    &mut wavetable.wavetable_path,
+                ))
+            }
+        }
+        AssetLocation::Modulator(index) => {
+            let container = preset.modulator_containers.get_mut(index)?;
+            let lfo_table = container.modulator.downcast_mut::<LfoTableModulator>()?;
+            Some((
+                &mut lfo_table.wavetable_contents,
+                &mut lfo_table.wavetable_path,
+            ))
+        }
+    }
+}
+
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Recursively find the presets in a directory, sorted by path.
+pub fn preset_paths(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for dir_entry in dir.read_dir()? {
+            let path = dir_entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().unwrap_or_default() == PRESET_EXTENSION {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::tests::test_data_path;
+
+    use super::*;
+
+    #[test]
+    fn factory_presets() {
+        let index = FactoryPresetIndex::scan_dir(test_data_path(&["effects", "haas"])).unwrap();
+        assert!(index.unreadable.is_empty());
+        assert_eq!(index.len(), 1);
+
+        let factory_preset = index.factory_presets().next().unwrap();
+        assert_eq!(factory_preset, ["factory", "Small Width.ksha"]);
+        let presets = index.presets_using(factory_preset);
+        assert_eq!(presets.len(), 1);
+        assert!(presets[0].ends_with("haas-small_width-1.8.13.phaseplant"));
+
+        assert!(index
+            .presets_using(&["factory".to_owned(), "Missing.ksha".to_owned()])
+            .is_empty());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut report = RoundTripReport::new();
+        report.check_file(&test_data_path(&["init", "init-2.2.0.phaseplant"]));
+        report.check_file(&test_data_path(&["effects", "missing.phaseplant"]));
+        assert_eq!(report.total(), 2);
+        assert_eq!(report.unsupported.len(), 1);
+        assert_eq!(report.failures_at(RoundTripStage::Read).count(), 1);
+        assert!(report.to_string().starts_with("2 presets: 0 passed"));
+    }
+
+    /// Every parameter of these presets is written back the same.
+    #[test]
+    fn round_trip_lossless() {
+        for dir in [
+            &["generators", "curve_output"][..],
+            &["generators", "wavetable_oscillator"],
+            &["modulators", "lfo_table"],
+        ] {
+            let report = RoundTripReport::scan_dir(test_data_path(dir)).unwrap();
+            assert!(report.failures.is_empty(), "{report}");
+            assert_eq!(report.passed, report.total());
+        }
+    }
+
+    #[test]
+    fn round_trip_validate() {
+        let mut preset = Preset::read_file(test_data_path(&[
+            "macros",
+            "macro-5-haasdelay50and75-1.8.14.phaseplant",
+        ]))
+        .unwrap();
+        preset.lanes[0].snapins.retain(|snapin| snapin.id != 1);
+        let path = std::env::temp_dir().join(format!(
+            "round_trip_validate-{}.phaseplant",
+            std::process::id()
+        ));
+        preset.write_file(&path).unwrap();
+
+        let mut report = RoundTripReport::new();
+        report.check_file(&path);
+        fs::remove_file(&path).unwrap();
+        let failure = report.failures_at(RoundTripStage::Validate).next().unwrap();
+        assert_eq!(
+            failure.message,
+            "Modulation 0: There is no snapin with ID 1"
+        );
+        assert!(!failure.panicked);
+    }
+
+    #[test]
+    fn round_trip_panic() {
+        let error = run_stage::<(), _>(RoundTripStage::Write, || panic!("Block {} is missing", 3))
+            .unwrap_err();
+        assert_eq!(error.stage, RoundTripStage::Write);
+        assert_eq!(error.error.to_string(), "Panicked: Block 3 is missing");
+        assert!(error.panicked);
+    }
+
+    #[test]
+    fn write_many() {
+        let out_dir = std::env::temp_dir().join(format!("write_many-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&out_dir);
+        fs::create_dir_all(out_dir.join("Blocked.phaseplant")).unwrap();
+
+        let named = |name: &str| {
+            let mut preset = Preset::default();
+            preset.metadata.name = Some(name.to_owned());
+            preset
+        };
+        let presets = [
+            named("Bass"),
+            named("bass"),
+            named("Lead: A/B"),
+            Preset::default(),
+            named("Blocked"),
+        ];
+        let written = super::write_many(&presets, &out_dir);
+        let file_names = written
+            .iter()
+            .map(|written| written.path.file_name().unwrap().to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            file_names,
+            [
+                "Bass.phaseplant",
+                "bass 2.phaseplant",
+                "Lead_ A_B.phaseplant",
+                "Preset 4.phaseplant",
+                "Blocked.phaseplant",
+            ]
+        );
+        assert!(written[..4].iter().all(|written| written.result.is_ok()));
+        assert!(written[4].result.is_err());
+        assert!(fs::metadata(&written[2].path).unwrap().len() > 0);
+
+        fs::remove_dir_all(&out_dir).unwrap();
+    }
+
+    #[test]
+    fn asset_sharing() {
+        let read = |generator_name: &str, file_name: &str| {
+            Preset::read_file(test_data_path(&["generators", generator_name, file_name])).unwrap()
+        };
+        let sampler = read(
+            "sample_player",
+            "sample_player-custom-sample-1.8.16.phaseplant",
+        );
+        let wavetable = read(
+            "wavetable_oscillator",
+            "wavetable_oscillator-brass-edited-1.8.17.phaseplant",
+        );
+        let mut presets = [sampler.clone(), wavetable, sampler];
+
+        let report = AssetSharingReport::new(&presets);
+        assert_eq!(report.duplicates.len(), 1);
+        let asset = &report.duplicates[0];
+        assert_eq!(asset.kind, AssetKind::Sample);
+        assert_eq!(asset.contents.len(), 217344);
+        assert_eq!(
+            asset.uses,
+            [
+                AssetUse {
+                    preset: 0,
+                    location: AssetLocation::Generator(1),
+                },
+                AssetUse {
+                    preset: 2,
+                    location: AssetLocation::Generator(1),
+                },
+            ]
+        );
+        assert_eq!(report.savings(), 217344);
+        assert!(report.embedded_size > 2 * 217344);
+
+        report.share(&mut presets, |asset| {
+            format!("shared/{:016x}.flac", asset.hash)
+        });
+        let sampler: &SamplePlayer = presets[2].generator(1).unwrap();
+        assert!(sampler.sample_contents.is_empty());
+        assert_eq!(
+            sampler.sample_path,
+            Some(format!("shared/{:016x}.flac", asset.hash))
+        );
+        let shared = AssetSharingReport::new(&presets);
+        assert!(shared.duplicates.is_empty());
+        assert_eq!(shared.embedded_size, report.embedded_size - 2 * 217344);
+    }
+
+    #[test]
+    fn fnv1a() {
+        assert_eq!(super::fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(super::fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    /// Round trip a corpus of presets and report the results. The corpus is
+    /// the directory named by the `PHASE_PLANT_CORPUS` environment variable,
+    /// or the test data when it isn't set. Fails when fewer than
+    /// `PHASE_PLANT_CORPUS_MIN_READABLE` percent of the supported presets can
+    /// be read, 95 by default. Run with
+    /// `cargo test --features corpus-tests corpus -- --nocapture`.
+    #[cfg(feature = "corpus-tests")]
+    #[test]
+    fn corpus() {
+        let dir = std::env::var_os("PHASE_PLANT_CORPUS")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| test_data_path(&[]));
+        let min_readable = std::env::var("PHASE_PLANT_CORPUS_MIN_READABLE")
+            .map(|percent| percent.parse::<f64>().expect("percentage"))
+            .unwrap_or(95.0);
+
+        let report = RoundTripReport::scan_dir(&dir).unwrap();
+        println!("{}: {report}", dir.display());
+        let unreadable = report
+            .failures_at(RoundTripStage::Read)
+            .map(|failure| failure.path.display().to_string())
+            .collect::<Vec<_>>();
+        let supported = report.total() - report.unsupported.len();
+        assert!(supported > 0, "No presets in {}", dir.display());
+        let readable = 100.0 * (supported - unreadable.len()) as f64 / supported as f64;
+        assert!(
+            readable >= min_readable,
+            "Only {readable:.1}% of presets are readable, unreadable presets {unreadable:?}"
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! Fixed capacities of a Phase Plant preset.
+//!
+//! Presets that exceed them can't be written, see
+//! [`Preset::check_limits`](crate::Preset::check_limits). Some of the limits
+//! that protect against corrupt files can be raised when reading with
+//! [`ReadLimits`].
+
+use std::fmt::{Display, Formatter};
+use std::io::{Error, ErrorKind};
+
+use crate::generator::GeneratorId;
+
+/// Number of effect lanes.
+pub const LANE_COUNT: usize = 3;
+
+/// Number of generators. Unused generators in the file are ignored.
+pub const GENERATORS_MAX: GeneratorId = 32;
+
+/// Number of modulator blocks. Unused modulator blocks in the file are ignored.
+pub const MODULATORS_MAX: usize = 32;
+
+/// How many total macro connections that link a control to a parameter.
+pub const MODULATIONS_MAX: usize = 100;
+
+/// Number of macros controls (knobs) in the file.
+pub const MACRO_CONTROL_COUNT: usize = 8;
+
+/// Most unison voices.
+pub const UNISON_VOICES_MAX: u32 = 8; // As of Phase Plant 1.8.20
+
+/// Largest master tuning up or down, in semitones.
+pub const MASTER_PITCH_MAX: f32 = 48.0;
+
+/// Longest name of a generator group in characters.
+pub const GROUP_NAME_LENGTH_MAX: usize = 45; // From Phase Plant 1.8.20
+
+/// Longest text that can be stored, in bytes of UTF-8.
+pub const STRING_LENGTH_MAX: usize = 1024;
+
+/// Default upper limit on the size of the JSON metadata in bytes. The length
+/// is stored as a u32 so it could be used as a denial of service if there was
+/// no other limit.
+pub const METADATA_LENGTH_MAX: usize = 64 * 1024;
+
+/// Default number of parts allowed in a path.
+pub const PATH_COMPONENT_COUNT_MAX: usize = 100; // TODO: Operating system limit?
+
+/// Limits that stop corrupt files from using large amounts of memory. Other
+/// tools may write presets that need more than the defaults.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ReadLimits {
+    /// Largest metadata in bytes.
+    pub metadata_length_max: usize,
+
+    pub path_component_count_max: usize,
+}
+
+impl Default for ReadLimits {
+    fn default() -> Self {
+        Self {
+            metadata_length_max: METADATA_LENGTH_MAX,
+            path_component_count_max: PATH_COMPONENT_COUNT_MAX,
+        }
+    }
+}
+
+/// A limit in [`ReadLimits`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Limit {
+    MetadataLength,
+    PathComponentCount,
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Limit::MetadataLength => "Metadata length",
+            Limit::PathComponentCount => "Path component count",
+        })
+    }
+}
+
+/// The source of the [`ErrorKind::InvalidData`] error returned when reading
+/// a preset that exceeds one of the [`ReadLimits`].
+///
+/// ```
+/// use std::io::{Error, ErrorKind};
+/// use synthahol_phase_plant::limits::{Limit, LimitExceeded};
+///
+/// let error: Error = LimitExceeded {
+///     limit: Limit::MetadataLength,
+///     value: 70_000,
+///     maximum: 65_536,
+///     position: 12,
+/// }
+/// .into();
+/// assert_eq!(error.kind(), ErrorKind::InvalidData);
+/// assert_eq!(LimitExceeded::of(&error).unwrap().maximum, 65_536);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LimitExceeded {
+    pub limit: Limit,
+    pub value: usize,
+    pub maximum: usize,
+
+    /// Position in the file where the value is stored.
+    pub position: u64,
+}
+
+impl LimitExceeded {
+    /// The exceeded limit that caused the error, if that is what it was.
+    pub fn of(error: &Error) -> Option<&LimitExceeded> {
+        error.get_ref()?.downcast_ref()
+    }
+}
+
+impl Display for LimitExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of {} exceeds {} at position {}",
+            self.limit, self.value, self.maximum, self.position
+        )
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+impl From<LimitExceeded> for Error {
+    fn from(exceeded: LimitExceeded) -> Self {
+        Error::new(ErrorKind::InvalidData, exceeded)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::File;
+    use std::io::BufReader;
+
+    use crate::io::ReadOptions;
+    use crate::tests::test_data_path;
+    use crate::Preset;
+
+    use super::*;
+
+    fn read(path: &[&str], limits: ReadLimits) -> Result<Preset, Error> {
+        let mut reader = BufReader::new(File::open(test_data_path(path)).unwrap());
+        let options = ReadOptions {
+            limits,
+            ..Default::default()
+        };
+        Preset::read_with_options(&mut reader, None, &options)
+    }
+
+    #[test]
+    fn read_limits() {
+        let path = ["effects", "haas", "haas-small_width-1.8.13.phaseplant"];
+        assert!(read(&path, ReadLimits::default()).is_ok());
+
+        let limits = ReadLimits {
+            metadata_length_max: 10,
+            ..Default::default()
+        };
+        let error = read(&path, limits).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        let exceeded = LimitExceeded::of(&error).unwrap();
+        assert_eq!(exceeded.limit, Limit::MetadataLength);
+        assert_eq!(exceeded.maximum, 10);
+        assert!(exceeded.value > 10);
+        assert!(error.to_string().starts_with("Metadata length of "));
+
+        // The snapin is stored in the factory folder.
+        let limits = ReadLimits {
+            path_component_count_max: 1,
+            ..Default::default()
+        };
+        let error = read(&path, limits).unwrap_err();
+        let exceeded = LimitExceeded::of(&error).unwrap();
+        assert_eq!(exceeded.limit, Limit::PathComponentCount);
+        assert_eq!(exceeded.value, 2);
+    }
+}
@@ -0,0 +1,227 @@
+//! Read-only access to the generator and modulator blocks of a preset.
+//!
+//! Phase Plant stores every generator and modulator in a fixed size block
+//! that has room for the settings of every mode, followed by more settings
+//! for each block later in the file. [`Preset`] converts the blocks to
+//! specific generators and modulators and discards what isn't understood.
+//! [`Blocks`] keeps them for research into the format.
+//!
+//! Nothing in this module is stable. The views will change as more of the
+//! format is understood.
+
+use std::fmt::{Debug, Formatter};
+use std::io::{BufReader, Error, Read, Seek};
+use std::path::Path;
+
+use crate::generator::{GeneratorId, GeneratorMode};
+use crate::io::{GeneratorBlock, ModulatorBlock};
+use crate::modulator::{GroupId, ModulatorId, ModulatorMode};
+use crate::*;
+
+/// A preset and the blocks it was read from.
+#[derive(Clone)]
+pub struct Blocks {
+    preset: Preset,
+    generators: Vec<GeneratorBlock>,
+    modulators: Vec<ModulatorBlock>,
+}
+
+impl Blocks {
+    pub fn read<R: Read + Seek>(reader: &mut R) -> Result<Self, Error> {
+        let (preset, generators, modulators) = Preset::read_with_blocks(reader, None)?;
+        Ok(Self {
+            preset,
+            generators,
+            modulators,
+        })
+    }
+
+    pub fn read_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = std::fs::File::open(path)?;
+        Self::read(&mut BufReader::new(file))
+    }
+
+    pub fn preset(&self) -> &Preset {
+        &self.preset
+    }
+
+    pub fn into_preset(self) -> Preset {
+        self.preset
+    }
+
+    /// Every generator block in file order, including the blank ones.
+    pub fn generators(&self) -> impl Iterator<Item = GeneratorBlockView<'_>> {
+        self.generators.iter().map(GeneratorBlockView)
+    }
+
+    /// Every modulator block in file order, including the blank ones.
+    pub fn modulators(&self) -> impl Iterator<Item = ModulatorBlockView<'_>> {
+        self.modulators.iter().map(ModulatorBlockView)
+    }
+}
+
+impl Debug for Blocks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Blocks")
+            .field("generators", &self.generators)
+            .field("modulators", &self.modulators)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct GeneratorBlockView<'a>(&'a GeneratorBlock);
+
+impl GeneratorBlockView<'_> {
+    pub fn mode(&self) -> GeneratorMode {
+        self.0.mode
+    }
+
+    pub fn id(&self) -> GeneratorId {
+        self.0.id
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.0.enabled
+    }
+
+    pub fn minimized(&self) -> bool {
+        self.0.minimized
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// How the sample of a Sampler or Granular generator was stored.
+    pub fn sample_data_mode(&self) -> Option<SampleDataMode> {
+        self.0.sample_data_mode
+    }
+
+    /// How the wavetable of a Wavetable Oscillator was stored.
+    pub fn wavetable_data_mode(&self) -> Option<WavetableDataMode> {
+        self.0.wavetable_data_mode
+    }
+
+    /// The fixed size block as it is stored in the file.
+    pub fn raw(&self) -> &[u8] {
+        &self.0.raw
+    }
+
+    /// Raw bits of a value whose meaning is not known.
+    pub fn unknown(&self, name: &str) -> Option<u32> {
+        self.0.unknown.get(name).copied()
+    }
+
+    /// Names and raw bits of the values whose meaning is not known.
+    pub fn unknowns(&self) -> impl Iterator<Item = (&'static str, u32)> + '_ {
+        self.0.unknown.iter().map(|(name, bits)| (*name, *bits))
+    }
+}
+
+/// Shows every field that was read from the block.
+impl Debug for GeneratorBlockView<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct ModulatorBlockView<'a>(&'a ModulatorBlock);
+
+impl ModulatorBlockView<'_> {
+    pub fn mode(&self) -> ModulatorMode {
+        self.0.mode
+    }
+
+    pub fn id(&self) -> ModulatorId {
+        self.0.id
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.0.enabled
+    }
+
+    pub fn minimized(&self) -> bool {
+        self.0.minimized
+    }
+
+    /// Which group contains the modulator.
+    pub fn group_id(&self) -> GroupId {
+        self.0.group_id
+    }
+
+    /// The fixed size block as it is stored in the file, including the mode,
+    /// identifier and enabled flag that precede it.
+    pub fn raw(&self) -> &[u8] {
+        &self.0.raw
+    }
+
+    /// Raw bits of a value whose meaning is not known.
+    pub fn unknown(&self, name: &str) -> Option<u32> {
+        self.0.unknown.get(name).copied()
+    }
+
+    /// Names and raw bits of the values whose meaning is not known.
+    pub fn unknowns(&self) -> impl Iterator<Item = (&'static str, u32)> + '_ {
+        self.0.unknown.iter().map(|(name, bits)| (*name, *bits))
+    }
+}
+
+/// Shows every field that was read from the block.
+impl Debug for ModulatorBlockView<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tests::test_data_path;
+
+    use super::*;
+
+    #[test]
+    fn blocks() {
+        let blocks = Blocks::read_file(test_data_path(&[
+            "modulators",
+            "lfo",
+            "lfo-2.1.0.phaseplant",
+        ]))
+        .unwrap();
+        assert_eq!(blocks.generators().count(), GENERATORS_MAX as usize);
+        assert_eq!(blocks.modulators().count(), MODULATORS_MAX);
+        assert_eq!(blocks.preset().modulator_containers.len(), 1);
+
+        let lfo = blocks.modulators().next().unwrap();
+        assert_eq!(lfo.mode(), ModulatorMode::Lfo);
+        assert!(lfo.enabled());
+        assert_eq!(lfo.raw().len(), 12 + MODULATOR_BLOCK_SIZE);
+        assert_eq!(&lfo.raw()[0..4], &(ModulatorMode::Lfo as u32).to_le_bytes());
+        assert_eq!(lfo.unknown("unknown_1"), Some(1.0_f32.to_bits()));
+        assert!(lfo.unknowns().any(|(name, _)| name == "lfo_table_time"));
+        assert!(format!("{lfo:?}").contains("Lfo"));
+
+        let generator = blocks.generators().next().unwrap();
+        assert_eq!(generator.raw().len(), GeneratorBlock::SIZE);
+        assert!(generator.unknown("curve_unknown_1").is_some());
+        assert!(generator.unknown("granular_unknown_1").is_some());
+
+        // The raw blocks are only kept when asked for.
+        let mut file = std::fs::File::open(test_data_path(&[
+            "modulators",
+            "lfo",
+            "lfo-2.1.0.phaseplant",
+        ]))
+        .unwrap();
+        let (_, generators, modulators) = Preset::read_sections(
+            &mut file,
+            None,
+            &Default::default(),
+            &mut Default::default(),
+        )
+        .unwrap();
+        assert!(generators.iter().all(|block| block.raw.is_empty()));
+        assert!(modulators.iter().all(|block| block.raw.is_empty()));
+    }
+}
@@ -1,7 +1,13 @@
 //! This module is not called "macro" to because it's a keyword.
 
-use crate::modulator::OutputRange;
 use std::fmt;
+use std::io::{Error, ErrorKind};
+
+use serde::{Deserialize, Serialize};
+
+use crate::modulator::OutputRange;
+use crate::path::impl_parameters;
+use crate::Preset;
 
 pub type MacroControlId = u8;
 
@@ -12,9 +18,15 @@ pub struct MacroControl {
     pub polarity: OutputRange,
 }
 
+impl_parameters!(MacroControl {
+    name,
+    value: Fraction,
+    polarity,
+});
+
 impl MacroControl {
     /// Number of macros controls (knobs) in the file.
-    pub const COUNT: usize = 8;
+    pub const COUNT: usize = crate::limits::MACRO_CONTROL_COUNT;
 
     pub fn new<S: AsRef<str>>(name: S) -> Self {
         Self {
@@ -50,11 +62,114 @@ impl fmt::Debug for MacroControl {
     }
 }
 
+/// Values of every macro control saved under a name, such as the settings
+/// for a verse and a chorus of the same patch. Phase Plant doesn't have
+/// snapshots so they are kept in the metadata where Phase Plant ignores them.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MacroSnapshot {
+    pub name: String,
+    pub values: [f32; MacroControl::COUNT],
+}
+
+impl MacroSnapshot {
+    /// Field of the metadata that holds the snapshots.
+    const METADATA_FIELD: &'static str = "macroSnapshots";
+
+    /// The current values of the macro controls of the preset.
+    pub fn capture<S: Into<String>>(name: S, preset: &Preset) -> Self {
+        let mut values = [0.0; MacroControl::COUNT];
+        for (value, macro_control) in values.iter_mut().zip(&preset.macro_controls) {
+            *value = macro_control.value;
+        }
+        Self {
+            name: name.into(),
+            values,
+        }
+    }
+
+    /// Set the macro controls of the preset to the values of the snapshot.
+    pub fn apply(&self, preset: &mut Preset) {
+        for (macro_control, value) in preset.macro_controls.iter_mut().zip(self.values) {
+            macro_control.value = value;
+        }
+    }
+}
+
+impl Preset {
+    /// The macro snapshots stored in the metadata, in the order they were
+    /// saved.
+    pub fn macro_snapshots(&self) -> Result<Vec<MacroSnapshot>, Error> {
+        match self
+            .metadata
+            .other_fields
+            .get(MacroSnapshot::METADATA_FIELD)
+        {
+            Some(value) => serde_json::from_value(value.clone()).map_err(|error| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Macro snapshots can't be read: {error}"),
+                )
+            }),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Replace the macro snapshots stored in the metadata. The field is
+    /// removed if there are none.
+    pub fn set_macro_snapshots(&mut self, snapshots: &[MacroSnapshot]) -> Result<(), Error> {
+        let fields = &mut self.metadata.other_fields;
+        if snapshots.is_empty() {
+            fields.remove(MacroSnapshot::METADATA_FIELD);
+            return Ok(());
+        }
+        let value = serde_json::to_value(snapshots).map_err(|error| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Macro snapshots can't be stored: {error}"),
+            )
+        })?;
+        fields.insert(MacroSnapshot::METADATA_FIELD.to_owned(), value);
+        Ok(())
+    }
+
+    /// Capture the current values of the macro controls and store them,
+    /// replacing a snapshot with the same name.
+    pub fn save_macro_snapshot(&mut self, name: &str) -> Result<(), Error> {
+        let snapshot = MacroSnapshot::capture(name, self);
+        let mut snapshots = self.macro_snapshots()?;
+        match snapshots.iter_mut().find(|existing| existing.name == name) {
+            Some(existing) => *existing = snapshot,
+            None => snapshots.push(snapshot),
+        }
+        self.set_macro_snapshots(&snapshots)
+    }
+
+    /// Set the macro controls to the values of the stored snapshot.
+    pub fn apply_macro_snapshot(&mut self, name: &str) -> Result<(), Error> {
+        let snapshot = self
+            .macro_snapshots()?
+            .into_iter()
+            .find(|snapshot| snapshot.name == name)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("There is no macro snapshot named '{name}'"),
+                )
+            })?;
+        snapshot.apply(self);
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use std::io::Cursor;
+
     use crate::modulator::OutputRange;
     use crate::test::read_preset;
 
+    use super::*;
+
     #[test]
     fn name() {
         let preset = read_preset("macros", "macro-10to80%-1.8.13.phaseplant");
@@ -91,4 +206,40 @@ mod test {
         assert_eq!(preset.macro_controls[6].value, 0.7);
         assert_eq!(preset.macro_controls[7].value, 0.8);
     }
+
+    #[test]
+    fn snapshots() {
+        let mut preset = read_preset("macros", "macro-10to80%-1.8.13.phaseplant");
+        assert!(preset.macro_snapshots().unwrap().is_empty());
+        preset.save_macro_snapshot("Verse").unwrap();
+        preset.macro_controls[0].value = 1.0;
+        preset.save_macro_snapshot("Chorus").unwrap();
+        preset.macro_controls[1].value = 0.0;
+        preset.save_macro_snapshot("Chorus").unwrap();
+
+        let mut cursor = Cursor::new(Vec::new());
+        preset.write(&mut cursor).unwrap();
+        cursor.set_position(0);
+        let mut preset = Preset::read(&mut cursor, None).unwrap();
+        let snapshots = preset.macro_snapshots().unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].name, "Verse");
+        assert_eq!(snapshots[0].values[0], 0.1);
+        assert_eq!(snapshots[1].values[..3], [1.0, 0.0, 0.3]);
+
+        preset.apply_macro_snapshot("Verse").unwrap();
+        assert_eq!(preset.macro_controls[0].value, 0.1);
+        assert_eq!(preset.macro_controls[1].value, 0.2);
+        let error = preset.apply_macro_snapshot("Bridge").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+        preset.set_macro_snapshots(&[]).unwrap();
+        assert!(preset.metadata.other_fields.is_empty());
+        preset
+            .metadata
+            .other_fields
+            .insert("macroSnapshots".to_owned(), "invalid".into());
+        let error = preset.macro_snapshots().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
 }
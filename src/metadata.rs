@@ -1,3 +1,5 @@
+use serde_json::{Map, Value};
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct Metadata {
     pub name: Option<String>,
@@ -7,4 +9,8 @@ pub struct Metadata {
     // TODO: Should it be categories: Vec<String>?
     /// Category such as "bass" or "keys"
     pub category: Option<String>,
+
+    /// Fields that other tools added to the metadata. They are kept so they
+    /// can be written back.
+    pub other_fields: Map<String, Value>,
 }
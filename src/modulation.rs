@@ -1,16 +1,17 @@
 //! Modulation routes control and audio signals.
 
 use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
 
 use uom::si::f32::Ratio;
 use uom::si::ratio::percent;
 
+use crate::diff::{Unit, Value};
 use crate::modulator::ModulatorId;
 
 use super::*;
 
-/// How many total macro connections that link a control to a parameter.
-pub const MODULATIONS_MAX: usize = 100;
+pub use crate::limits::MODULATIONS_MAX;
 
 type SourceId = u16;
 type TargetId = u16;
@@ -61,7 +62,7 @@ impl RateMode {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Modulation {
     pub enabled: bool,
     pub source: ModulationSource,
@@ -79,6 +80,75 @@ impl Modulation {
             ..Default::default()
         }
     }
+
+    /// The amount in the unit Phase Plant shows for the target, such as
+    /// `+10 ct` for unison detune, followed by the range the parameter of the
+    /// preset is modulated over. The amount is stored as a fraction of the
+    /// range of the target, which is shown as a percentage when the target's
+    /// unit or range isn't known.
+    pub fn display_amount(&self, preset: &Preset) -> String {
+        let amount = self.amount.get::<percent>();
+        let Some(descriptor) = self.target.descriptor() else {
+            return format!("{} of range", signed(amount, Unit::Percent));
+        };
+        let base = match preset.get(&descriptor.path) {
+            Ok(Value::Number { value, .. }) => Some(value),
+            _ => None,
+        };
+        match (descriptor.range, base) {
+            (Some(range), base) => {
+                let delta = amount / 100.0 * (range.end() - range.start());
+                let text = signed(delta, descriptor.unit);
+                match base {
+                    Some(base) => {
+                        let end = (base + delta).clamp(*range.start(), *range.end());
+                        format!(
+                            "{text} ({} to {})",
+                            unsigned(base, descriptor.unit),
+                            unsigned(end, descriptor.unit)
+                        )
+                    }
+                    None => text,
+                }
+            }
+            (None, Some(base)) => format!(
+                "{} of range from {}",
+                signed(amount, Unit::Percent),
+                unsigned(base, descriptor.unit)
+            ),
+            (None, None) => format!("{} of range", signed(amount, Unit::Percent)),
+        }
+    }
+}
+
+fn unsigned(value: f32, unit: Unit) -> String {
+    let value = (value * 100.0).round() / 100.0 + 0.0;
+    Value::Number { value, unit }.to_string()
+}
+
+fn signed(value: f32, unit: Unit) -> String {
+    let text = unsigned(value, unit);
+    if text.starts_with('-') {
+        text
+    } else {
+        format!("+{text}")
+    }
+}
+
+impl Modulation {
+    /// How Phase Plant stores a slot without a modulation.
+    pub(crate) fn unused() -> Self {
+        Self::new(0.into(), 0.into(), Ratio::zero())
+    }
+
+    pub(crate) fn is_unused(&self) -> bool {
+        let unused = Self::unused();
+        self.source.id() == unused.source.id()
+            && self.target.id() == unused.target.id()
+            && self.amount == unused.amount
+            && self.curve == unused.curve
+            && self.enabled == unused.enabled
+    }
 }
 
 impl Default for Modulation {
@@ -337,6 +407,14 @@ impl Display for ModulationTarget {
 }
 
 impl ModulationTarget {
+    /// Only some of the host parameters are understood.
+    pub fn descriptor(&self) -> Option<ParameterDescriptor> {
+        match self {
+            ModulationTarget::Host { parameter, .. } => parameter.descriptor(),
+            _ => None,
+        }
+    }
+
     const HOST_CATEGORY_ID: CategoryId = 0xFFFF;
     const MODULATION_CATEGORY_ID: CategoryId = 0xFFFD;
 
@@ -492,6 +570,25 @@ pub enum AudioRateTargetParameter {
     Harmonic,
 }
 
+/// How Phase Plant shows a parameter that can be modulated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParameterDescriptor {
+    /// Location of the parameter for [`Preset::get`].
+    pub path: String,
+
+    pub unit: Unit,
+
+    /// Modulation amounts are a fraction of this range. Not set when the
+    /// knob isn't linear in the unit or its range isn't known.
+    pub range: Option<RangeInclusive<f32>>,
+}
+
+impl ParameterDescriptor {
+    fn new(path: String, unit: Unit, range: Option<RangeInclusive<f32>>) -> Self {
+        Self { path, unit, range }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum HostParameter {
     GlideTime,
@@ -518,6 +615,42 @@ pub enum HostParameter {
 }
 
 impl HostParameter {
+    /// The unit and range of the parameters that are understood.
+    pub fn descriptor(&self) -> Option<ParameterDescriptor> {
+        use HostParameter::*;
+        let full_range = Some(0.0..=100.0);
+        Some(match self {
+            GlideTime => ParameterDescriptor::new("glide_time".to_owned(), Unit::Seconds, None),
+            LaneGain(lane_id) => {
+                ParameterDescriptor::new(format!("lanes.{lane_id}.gain"), Unit::Decibels, None)
+            }
+            LaneMix(lane_id) => {
+                ParameterDescriptor::new(format!("lanes.{lane_id}.mix"), Unit::Percent, full_range)
+            }
+            MasterGain => ParameterDescriptor::new("master_gain".to_owned(), Unit::Decibels, None),
+            MacroControl(macro_control_id) => ParameterDescriptor::new(
+                format!("macro_controls.{macro_control_id}.value"),
+                Unit::Percent,
+                full_range,
+            ),
+            UnisonBias => ParameterDescriptor::new(
+                "unison.bias".to_owned(),
+                Unit::Percent,
+                Some(-100.0..=100.0),
+            ),
+            UnisonBlend => {
+                ParameterDescriptor::new("unison.blend".to_owned(), Unit::Percent, full_range)
+            }
+            UnisonDetune => {
+                ParameterDescriptor::new("unison.detune".to_owned(), Unit::Cents, Some(0.0..=200.0))
+            }
+            UnisonSpread => {
+                ParameterDescriptor::new("unison.spread".to_owned(), Unit::Percent, full_range)
+            }
+            Generator { .. } | Modulator { .. } | Unknown { .. } => return None,
+        })
+    }
+
     fn id(&self) -> TargetId {
         use HostParameter::*;
         match self {
@@ -609,9 +742,12 @@ mod test {
         UnisonDetune, UnisonSpread,
     };
     use crate::modulation::ModulationTarget::Host;
-    use crate::modulation::{HostParameter, ModulationSource, ModulationTarget, RateMode};
+    use crate::modulation::{
+        HostParameter, Modulation, ModulationSource, ModulationTarget, RateMode,
+    };
     use crate::modulator::ModulatorId;
     use crate::test::read_preset;
+    use crate::Preset;
 
     /// The preset has 32 random modulators where there is a modulation from
     /// each to the global detune.
@@ -1094,6 +1230,44 @@ mod test {
                 rate_mode: RateMode::Control,
             }
         );
+
+        let amounts: Vec<_> = preset
+            .modulations
+            .iter()
+            .map(|modulation| modulation.display_amount(&preset))
+            .collect();
+        assert_eq!(
+            amounts,
+            [
+                "+10 ct (25 ct to 35 ct)",
+                "+20% (0% to 20%)",
+                "+40% (0% to 40%)",
+                "+30% (100% to 100%)"
+            ]
+        );
+    }
+
+    #[test]
+    fn display_amount() {
+        let preset = Preset::default();
+        let modulation = Modulation::new(
+            ModulationSource::ModWheel,
+            Host {
+                parameter: MasterGain,
+                rate_mode: RateMode::Control,
+            },
+            Ratio::new::<percent>(-25.0),
+        );
+        assert_eq!(
+            modulation.display_amount(&preset),
+            "-25% of range from 0 dB"
+        );
+        let modulation = Modulation::new(
+            ModulationSource::ModWheel,
+            ModulationTarget::default(),
+            Ratio::new::<percent>(12.5),
+        );
+        assert_eq!(modulation.display_amount(&preset), "+12.5% of range");
     }
 }
 
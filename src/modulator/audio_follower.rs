@@ -7,10 +7,17 @@ use uom::si::f32::Ratio;
 use uom::si::ratio::percent;
 use uom::si::time::millisecond;
 
+use crate::interpolate::{step, Interpolate};
+use crate::path::{impl_parameters, Choice};
 use crate::*;
 
 use super::*;
 
+/// Audio that a modulator listens to. Every modulator slot stores a source,
+/// even when the modulator doesn't use one.
+///
+/// The identifier is four ASCII characters, such as `main` for the master
+/// output and `lan1` for the first lane. The name is what Phase Plant shows.
 #[derive(Clone, Debug, PartialEq)]
 pub struct AudioSourceId {
     id: u32,
@@ -20,7 +27,7 @@ pub struct AudioSourceId {
 /// The default audio source is `Master`
 impl Default for AudioSourceId {
     fn default() -> Self {
-        Self::new(Self::bytes_to_id(b"main"), "Master".to_owned())
+        Self::master()
     }
 }
 
@@ -35,6 +42,33 @@ impl AudioSourceId {
         Self { id, name }
     }
 
+    /// The output of the preset after all the lanes.
+    pub fn master() -> Self {
+        Self::new(Self::bytes_to_id(b"main"), "Master".to_owned())
+    }
+
+    /// The output of a lane, or `None` if there is no such lane.
+    pub fn lane(lane_index: usize) -> Option<Self> {
+        let number = match lane_index {
+            0 => b'1',
+            1 => b'2',
+            2 => b'3',
+            _ => return None,
+        };
+        Some(Self::new(
+            Self::bytes_to_id(&[b'l', b'a', b'n', number]),
+            format!("Lane {}", lane_index + 1),
+        ))
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn is_lane_1(&self) -> bool {
         self.id == Self::bytes_to_id(b"lan1")
     }
@@ -54,17 +88,47 @@ impl AudioSourceId {
     const fn bytes_to_id(bytes: &[u8; 4]) -> u32 {
         u32::from_be_bytes(*bytes)
     }
+
+    /// The master output and the output of each lane.
+    fn sources() -> impl Iterator<Item = Self> {
+        std::iter::once(Self::master()).chain((0..).map_while(Self::lane))
+    }
 }
 
-#[derive(Copy, Clone, Debug, FromRepr, Eq, PartialEq)]
+impl Choice for AudioSourceId {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn names(&self) -> Vec<String> {
+        Self::sources().map(|source| source.name).collect()
+    }
+
+    fn choose(&mut self, name: &str) -> bool {
+        match Self::sources().find(|source| source.name.eq_ignore_ascii_case(name.trim())) {
+            Some(source) => {
+                *self = source;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl_parameters!(AudioSourceId);
+
+#[derive(Copy, Clone, Debug, Default, Display, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum MeteringMode {
     // The discriminants correspond to the file format.
     Peak = 0,
     #[doc(alias = "RMS")]
+    #[default]
     RootMeanSquared = 1,
 }
 
+impl_parameters!(MeteringMode);
+
 impl MeteringMode {
     pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
         Self::from_repr(id).ok_or_else(|| {
@@ -76,7 +140,7 @@ impl MeteringMode {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct AudioFollowerModulator {
     pub depth: Ratio,
     pub output_range: OutputRange,
@@ -101,6 +165,30 @@ impl Default for AudioFollowerModulator {
     }
 }
 
+impl Interpolate for AudioFollowerModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            depth: self.depth.interpolate(&other.depth, t),
+            output_range: step(&self.output_range, &other.output_range, t),
+            gain: self.gain.interpolate(&other.gain, t),
+            attack_time: self.attack_time.interpolate(&other.attack_time, t),
+            release_time: self.release_time.interpolate(&other.release_time, t),
+            audio_source: step(&self.audio_source, &other.audio_source, t),
+            metering_mode: step(&self.metering_mode, &other.metering_mode, t),
+        }
+    }
+}
+
+impl_parameters!(AudioFollowerModulator {
+    depth,
+    output_range,
+    gain,
+    attack_time,
+    release_time,
+    audio_source,
+    metering_mode,
+});
+
 impl Modulator for AudioFollowerModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -117,6 +205,13 @@ impl Modulator for AudioFollowerModulator {
     }
 }
 
+impl dyn Modulator {
+    #[must_use]
+    pub fn as_audio_follower(&self) -> Option<&AudioFollowerModulator> {
+        self.downcast_ref::<AudioFollowerModulator>()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use approx::assert_relative_eq;
@@ -195,4 +290,43 @@ mod test {
         assert!(modulator.audio_source.is_lane_1());
         assert_eq!(modulator.metering_mode, MeteringMode::Peak);
     }
+
+    #[test]
+    fn audio_source() {
+        assert!(AudioSourceId::default().is_master());
+        assert_eq!(AudioSourceId::master().name(), "Master");
+        assert!(AudioSourceId::lane(0).unwrap().is_lane_1());
+        assert!(AudioSourceId::lane(2).unwrap().is_lane_3());
+        assert!(AudioSourceId::lane(3).is_none());
+
+        let preset = read_modulator_preset(
+            "audio_follower",
+            "audio_follower-lane1-peak-2.0.12.phaseplant",
+        )
+        .unwrap();
+        let modulator: &AudioFollowerModulator = preset.modulator(0).unwrap();
+        assert_eq!(modulator.audio_source, AudioSourceId::lane(0).unwrap());
+    }
+
+    #[test]
+    fn audio_source_write() {
+        let mut preset = read_modulator_preset(
+            "audio_follower",
+            "audio_follower-lane1-peak-2.0.12.phaseplant",
+        )
+        .unwrap();
+        preset.modulator_containers[0]
+            .modulator
+            .downcast_mut::<AudioFollowerModulator>()
+            .unwrap()
+            .audio_source = AudioSourceId::lane(2).unwrap();
+
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        preset.write(&mut cursor).unwrap();
+        let bytes = cursor.into_inner();
+        let expected = b"3nal\x06\0\0\0Lane 3niam\x06\0\0\0Master";
+        assert!(bytes
+            .windows(expected.len())
+            .any(|window| window == expected));
+    }
 }
@@ -1,9 +1,11 @@
 use std::any::Any;
 
+use crate::interpolate::Interpolate;
 use crate::modulator::{Modulator, ModulatorMode};
+use crate::path::impl_parameters;
 use crate::*;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct BlankModulator {}
 
 impl Modulator for BlankModulator {
@@ -21,3 +23,11 @@ impl Modulator for BlankModulator {
         ModulatorMode::Blank
     }
 }
+
+impl Interpolate for BlankModulator {
+    fn interpolate(&self, _other: &Self, _t: f32) -> Self {
+        Self {}
+    }
+}
+
+impl_parameters!(BlankModulator {});
@@ -10,11 +10,13 @@ use uom::si::frequency::hertz;
 use uom::si::ratio::ratio;
 
 use crate::generator::LoopMode;
+use crate::interpolate::{step, Interpolate};
 use crate::modulator::{Modulator, ModulatorMode, NoteTriggerMode, OutputRange};
+use crate::path::impl_parameters;
 use crate::point::{CurvePoint, CurvePointMode};
 use crate::*;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct CurveModulator {
     pub output_range: OutputRange,
     pub loop_mode: LoopMode,
@@ -72,6 +74,38 @@ impl Default for CurveModulator {
     }
 }
 
+impl Interpolate for CurveModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            output_range: step(&self.output_range, &other.output_range, t),
+            loop_mode: step(&self.loop_mode, &other.loop_mode, t),
+            note_trigger_mode: step(&self.note_trigger_mode, &other.note_trigger_mode, t),
+            rate: self.rate.interpolate(&other.rate, t),
+            trigger_threshold: self
+                .trigger_threshold
+                .interpolate(&other.trigger_threshold, t),
+            depth: self.depth.interpolate(&other.depth, t),
+            shape: self.shape.interpolate(&other.shape, t),
+            shape_name: step(&self.shape_name, &other.shape_name, t),
+            shape_path: step(&self.shape_path, &other.shape_path, t),
+            shape_edited: step(&self.shape_edited, &other.shape_edited, t),
+        }
+    }
+}
+
+impl_parameters!(CurveModulator {
+    output_range,
+    loop_mode,
+    note_trigger_mode,
+    rate,
+    trigger_threshold,
+    depth,
+    shape,
+    shape_name,
+    shape_path,
+    shape_edited,
+});
+
 impl Modulator for CurveModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
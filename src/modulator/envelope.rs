@@ -5,8 +5,10 @@ use std::any::Any;
 use uom::si::ratio::{percent, ratio};
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct EnvelopeModulator {
     pub envelope: Envelope,
     pub depth: Ratio,
@@ -29,6 +31,28 @@ impl Default for EnvelopeModulator {
     }
 }
 
+impl Interpolate for EnvelopeModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            envelope: self.envelope.interpolate(&other.envelope, t),
+            depth: self.depth.interpolate(&other.depth, t),
+            trigger_threshold: self
+                .trigger_threshold
+                .interpolate(&other.trigger_threshold, t),
+            note_trigger_mode: step(&self.note_trigger_mode, &other.note_trigger_mode, t),
+            seamless: step(&self.seamless, &other.seamless, t),
+        }
+    }
+}
+
+impl_parameters!(EnvelopeModulator {
+    envelope,
+    depth,
+    trigger_threshold,
+    note_trigger_mode,
+    seamless,
+});
+
 impl Modulator for EnvelopeModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -45,6 +69,13 @@ impl Modulator for EnvelopeModulator {
     }
 }
 
+impl dyn Modulator {
+    #[must_use]
+    pub fn as_envelope(&self) -> Option<&EnvelopeModulator> {
+        self.downcast_ref::<EnvelopeModulator>()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use approx::assert_relative_eq;
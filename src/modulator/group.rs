@@ -4,14 +4,26 @@
 
 use std::any::Any;
 
+use crate::interpolate::{step, Interpolate};
 use crate::modulator::{Modulator, ModulatorMode};
+use crate::path::impl_parameters;
 use crate::ModulatorBlock;
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Group {
     pub name: Option<String>,
 }
 
+impl Interpolate for Group {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            name: step(&self.name, &other.name, t),
+        }
+    }
+}
+
+impl_parameters!(Group { name });
+
 impl Modulator for Group {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -31,7 +43,7 @@ impl Modulator for Group {
 #[cfg(test)]
 mod test {
     use crate::modulator::{LfoModulator, ModulatorId};
-    use crate::test::read_modulator_preset;
+    use crate::test::{read_modulator_preset, rewrite};
 
     use super::*;
 
@@ -48,6 +60,25 @@ mod test {
         }
     }
 
+    #[test]
+    fn rewrite_groups() {
+        for file_name in [
+            "group-2.0.12.phaseplant",
+            "group-2.1.0.phaseplant",
+            "group-3contains_lfo-2.0.14.phaseplant",
+            "group-disabled-2.0.12.phaseplant",
+            "group-minimized-2.0.12.phaseplant",
+            "group-new-name-2.0.12.phaseplant",
+        ] {
+            let preset = read_modulator_preset("group", file_name).unwrap();
+            let written = rewrite(&preset).unwrap();
+            assert_eq!(
+                written.modulator_containers, preset.modulator_containers,
+                "{file_name}"
+            );
+        }
+    }
+
     #[test]
     fn default() {
         let modulator = Group::default();
@@ -8,16 +8,19 @@ use uom::si::frequency::hertz;
 use uom::si::ratio::ratio;
 
 use crate::generator::LoopMode;
+use crate::interpolate::{step, Interpolate};
 use crate::modulator::{Modulator, ModulatorMode, OutputRange};
+use crate::path::impl_parameters;
 use crate::point::{CurvePoint, CurvePointMode};
 use crate::*;
 
 /// [Triggering](https://kilohearts.com/docs/modulation#triggering)
-#[derive(Copy, Clone, Debug, FromRepr, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum NoteTriggerMode {
     // The discriminants correspond to the file format. They are in the order
     // they are in the Phase Plant interface.
+    #[default]
     Auto = 3,
     Never = 0,
     #[doc(alias = "NoteOn")]
@@ -48,7 +51,9 @@ impl Display for NoteTriggerMode {
     }
 }
 
-#[derive(Debug, PartialEq)]
+impl_parameters!(NoteTriggerMode);
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct LfoModulator {
     pub output_range: OutputRange,
     pub depth: Ratio,
@@ -117,6 +122,40 @@ impl Default for LfoModulator {
     }
 }
 
+impl Interpolate for LfoModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            output_range: step(&self.output_range, &other.output_range, t),
+            depth: self.depth.interpolate(&other.depth, t),
+            loop_mode: step(&self.loop_mode, &other.loop_mode, t),
+            rate: self.rate.interpolate(&other.rate, t),
+            trigger_threshold: self
+                .trigger_threshold
+                .interpolate(&other.trigger_threshold, t),
+            note_trigger_mode: step(&self.note_trigger_mode, &other.note_trigger_mode, t),
+            phase_offset: self.phase_offset.interpolate(&other.phase_offset, t),
+            shape: self.shape.interpolate(&other.shape, t),
+            shape_name: step(&self.shape_name, &other.shape_name, t),
+            shape_path: step(&self.shape_path, &other.shape_path, t),
+            shape_edited: step(&self.shape_edited, &other.shape_edited, t),
+        }
+    }
+}
+
+impl_parameters!(LfoModulator {
+    output_range,
+    depth,
+    loop_mode,
+    rate,
+    trigger_threshold,
+    note_trigger_mode,
+    phase_offset,
+    shape,
+    shape_name,
+    shape_path,
+    shape_edited,
+});
+
 impl Modulator for LfoModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -310,6 +349,7 @@ mod test {
             read_modulator_preset("lfo", "lfo-retrig_off-1shot-depth50-1.8.14.phaseplant").unwrap();
         assert_eq!(preset.modulator_containers.len(), 1);
         let modulator: &LfoModulator = preset.modulator(0).unwrap();
+        assert_eq!(modulator.note_trigger_mode, NoteTriggerMode::Never);
         assert_eq!(modulator.loop_mode, LoopMode::Off);
         assert_relative_eq!(modulator.depth.get::<percent>(), 50.0);
     }
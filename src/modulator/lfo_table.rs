@@ -9,10 +9,12 @@ use uom::si::frequency::hertz;
 use uom::si::ratio::{percent, ratio};
 
 use crate::generator::LoopMode;
+use crate::interpolate::{step, Interpolate};
 use crate::modulator::{Modulator, ModulatorMode, NoteTriggerMode, OutputRange};
+use crate::path::impl_parameters;
 use crate::*;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct LfoTableModulator {
     pub output_range: OutputRange,
     pub depth: Ratio,
@@ -34,6 +36,8 @@ pub struct LfoTableModulator {
     pub frame: f32,
 
     // Wavetable
+    /// A user wavetable embedded in the preset. Phase Plant stores it as
+    /// FLAC so it is already compressed.
     pub wavetable_contents: Vec<u8>,
     pub wavetable_name: Option<String>,
     pub wavetable_path: Option<String>,
@@ -63,6 +67,42 @@ impl Default for LfoTableModulator {
     }
 }
 
+impl Interpolate for LfoTableModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            output_range: step(&self.output_range, &other.output_range, t),
+            depth: self.depth.interpolate(&other.depth, t),
+            rate: self.rate.interpolate(&other.rate, t),
+            loop_mode: step(&self.loop_mode, &other.loop_mode, t),
+            note_trigger_mode: step(&self.note_trigger_mode, &other.note_trigger_mode, t),
+            trigger_threshold: self
+                .trigger_threshold
+                .interpolate(&other.trigger_threshold, t),
+            phase_offset: self.phase_offset.interpolate(&other.phase_offset, t),
+            smooth: self.smooth.interpolate(&other.smooth, t),
+            frame: self.frame.interpolate(&other.frame, t),
+            wavetable_contents: step(&self.wavetable_contents, &other.wavetable_contents, t),
+            wavetable_name: step(&self.wavetable_name, &other.wavetable_name, t),
+            wavetable_path: step(&self.wavetable_path, &other.wavetable_path, t),
+        }
+    }
+}
+
+impl_parameters!(LfoTableModulator {
+    output_range,
+    depth,
+    rate,
+    loop_mode,
+    note_trigger_mode,
+    trigger_threshold,
+    phase_offset,
+    smooth,
+    frame,
+    wavetable_contents: Data,
+    wavetable_name,
+    wavetable_path,
+});
+
 impl Modulator for LfoTableModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -103,10 +143,26 @@ mod test {
     use uom::si::ratio::percent;
 
     use crate::modulator::{OutputRange, GROUP_ID_NONE};
-    use crate::test::read_modulator_preset;
+    use crate::test::{read_modulator_preset, rewrite};
+    use crate::tests::test_data_path;
 
     use super::*;
 
+    /// The wavetable of every LFO Table is written back the same,
+    /// including the embedded ones.
+    #[test]
+    fn rewrite_wavetables() {
+        for entry in std::fs::read_dir(test_data_path(&["modulators", "lfo_table"])).unwrap() {
+            let path = entry.unwrap().path();
+            let preset = Preset::read_file(&path).unwrap();
+            let written = rewrite(&preset).unwrap();
+            assert_eq!(
+                written.modulator_containers, preset.modulator_containers,
+                "{path:?}"
+            );
+        }
+    }
+
     #[test]
     fn init() {
         for file in &[
@@ -8,11 +8,13 @@ use std::any::Any;
 
 use uom::si::ratio::ratio;
 
+use crate::interpolate::{step, Interpolate};
 use crate::modulator::{Modulator, ModulatorMode, OutputRange};
+use crate::path::impl_parameters;
 use crate::*;
 
 /// Formerly known as "Max"
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct LowerLimitModulator {
     pub depth: Ratio,
     pub output_range: OutputRange,
@@ -31,8 +33,26 @@ impl Default for LowerLimitModulator {
     }
 }
 
+impl Interpolate for LowerLimitModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            depth: self.depth.interpolate(&other.depth, t),
+            output_range: step(&self.output_range, &other.output_range, t),
+            input_a: self.input_a.interpolate(&other.input_a, t),
+            input_b: self.input_b.interpolate(&other.input_b, t),
+        }
+    }
+}
+
+impl_parameters!(LowerLimitModulator {
+    depth,
+    output_range,
+    input_a,
+    input_b,
+});
+
 /// Formerly known as "Min"
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct UpperLimitModulator {
     pub output_range: OutputRange,
     pub input_a: f32,
@@ -51,6 +71,24 @@ impl Default for UpperLimitModulator {
     }
 }
 
+impl Interpolate for UpperLimitModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            output_range: step(&self.output_range, &other.output_range, t),
+            input_a: self.input_a.interpolate(&other.input_a, t),
+            input_b: self.input_b.interpolate(&other.input_b, t),
+            depth: self.depth.interpolate(&other.depth, t),
+        }
+    }
+}
+
+impl_parameters!(UpperLimitModulator {
+    output_range,
+    input_a,
+    input_b,
+    depth,
+});
+
 impl Modulator for LowerLimitModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -5,8 +5,10 @@ use std::any::Any;
 use uom::si::ratio::ratio;
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct MidiCcModulator {
     pub output_range: OutputRange,
     pub depth: Ratio,
@@ -23,6 +25,22 @@ impl Default for MidiCcModulator {
     }
 }
 
+impl Interpolate for MidiCcModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            output_range: step(&self.output_range, &other.output_range, t),
+            depth: self.depth.interpolate(&other.depth, t),
+            controller_slot: step(&self.controller_slot, &other.controller_slot, t),
+        }
+    }
+}
+
+impl_parameters!(MidiCcModulator {
+    output_range,
+    depth,
+    controller_slot,
+});
+
 impl Modulator for MidiCcModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -8,11 +8,15 @@
 // The available options are unipolar (0 to 1), bipolar (−1 to 1) and inverted (1 to 0).
 
 use std::any::Any;
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 use downcast_rs::{impl_downcast, Downcast};
-use strum_macros::FromRepr;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, FromRepr};
 
+use crate::path::impl_parameters;
 use crate::*;
 
 pub use self::audio_follower::*;
@@ -35,6 +39,7 @@ pub use self::remap::*;
 pub use self::sample_and_hold::*;
 pub use self::scale::*;
 pub use self::slew_limiter::*;
+pub use self::trigger::*;
 pub use self::velocity::*;
 
 mod audio_follower;
@@ -57,6 +62,7 @@ mod remap;
 mod sample_and_hold;
 mod scale;
 mod slew_limiter;
+mod trigger;
 mod velocity;
 
 pub type GroupId = u32;
@@ -69,7 +75,7 @@ pub type ModulatorId = u8;
 /// The bipolar, unipolar and inverted output range options in the modulator
 /// interface in Phase Plant do not change the behavior. These options are
 /// shortcuts for setting the high, medium and low depths.
-#[derive(Copy, Clone, Debug, FromRepr, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Display, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum OutputRange {
     // The discriminants correspond to the file format.
@@ -81,6 +87,8 @@ pub enum OutputRange {
     Inverted = 2,
 }
 
+impl_parameters!(OutputRange);
+
 impl OutputRange {
     pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
         Self::from_repr(id).ok_or_else(|| {
@@ -100,7 +108,7 @@ impl OutputRange {
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, FromRepr)]
+#[derive(Copy, Clone, Debug, EnumIter, Eq, FromRepr, Hash, PartialEq)]
 #[repr(u32)]
 pub enum ModulatorMode {
     // The discriminants correspond to the file format.
@@ -142,6 +150,16 @@ impl ModulatorMode {
         self == &ModulatorMode::Blank
     }
 
+    /// A new modulator of this mode with the default settings. Aftertouch
+    /// modulators are read as Pressure modulators.
+    pub(crate) fn default_modulator(&self) -> Box<dyn Modulator> {
+        crate::io::ModulatorBlock {
+            mode: *self,
+            ..Default::default()
+        }
+        .modulator()
+    }
+
     pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
         Self::from_repr(id).ok_or_else(|| {
             Error::new(
@@ -184,8 +202,29 @@ impl Display for ModulatorMode {
     }
 }
 
+/// Parses the name shown by [`Display`] or the name of the variant, ignoring
+/// case.
+impl FromStr for ModulatorMode {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        ModulatorMode::iter()
+            .find(|mode| {
+                mode.to_string().eq_ignore_ascii_case(text)
+                    || format!("{mode:?}").eq_ignore_ascii_case(text)
+            })
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Unknown modulator '{text}'"),
+                )
+            })
+    }
+}
+
 /// Similar to a [`Snapin`] but for modulators instead of generators.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct ModulatorContainer {
     /// Identifier for the contained modulator.
     pub id: ModulatorId,
@@ -194,6 +233,14 @@ pub struct ModulatorContainer {
     pub enabled: bool,
     pub minimized: bool,
     pub modulator: Box<dyn Modulator>,
+
+    /// The second data block stored for the modulator, kept so it is written
+    /// again. It isn't known what Phase Plant stores in it.
+    pub second_data_block: Option<RawDataBlock>,
+
+    /// Raw bits of the values stored for the modulator whose meaning isn't
+    /// known, kept so they are written again.
+    pub unknown: BTreeMap<&'static str, u32>,
 }
 
 impl ModulatorContainer {
@@ -204,6 +251,8 @@ impl ModulatorContainer {
             enabled: true,
             minimized: false,
             modulator,
+            second_data_block: None,
+            unknown: BTreeMap::new(),
         }
     }
 }
@@ -212,16 +261,22 @@ impl Eq for ModulatorContainer {}
 
 impl PartialEq for ModulatorContainer {
     fn eq(&self, other: &Self) -> bool {
-        self.enabled == other.enabled
+        self.id == other.id
+            && self.group_id == other.group_id
+            && self.enabled == other.enabled
             && self.minimized == other.minimized
 
             // Using .eq() instead of == quiets a Clippy warning about
             // unnecessary dereferencing.
             && self.modulator.eq(&other.modulator)
+            && self.second_data_block == other.second_data_block
+
+        // The unknown values are not compared because reading fills them in
+        // for every modulator.
     }
 }
 
-pub trait Modulator: Downcast + std::fmt::Debug {
+pub trait Modulator: Downcast + std::fmt::Debug + ModulatorClone + Send + Sync {
     fn as_block(&self) -> ModulatorBlock;
     fn box_eq(&self, other: &dyn Any) -> bool;
     fn mode(&self) -> ModulatorMode;
@@ -229,9 +284,27 @@ pub trait Modulator: Downcast + std::fmt::Debug {
 
 impl_downcast!(Modulator);
 
+/// Clone a boxed modulator without knowing its concrete type.
+pub trait ModulatorClone {
+    fn clone_box(&self) -> Box<dyn Modulator>;
+}
+
+impl<T: Modulator + Clone> ModulatorClone for T {
+    fn clone_box(&self) -> Box<dyn Modulator> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Modulator> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
 impl PartialEq for Box<dyn Modulator> {
     fn eq(&self, other: &Box<dyn Modulator>) -> bool {
-        self.box_eq(other.as_any())
+        // Dereference so the concrete value is compared rather than the box.
+        self.box_eq((**other).as_any())
     }
 }
 
@@ -245,4 +318,31 @@ impl Preset {
             .modulator
             .downcast_ref::<T>()
     }
+
+    /// Call the visitor with every modulator container. Useful for changing
+    /// modulators in bulk.
+    pub fn visit_modulators_mut<F: FnMut(&mut ModulatorContainer)>(&mut self, mut visitor: F) {
+        for container in &mut self.modulator_containers {
+            visitor(container);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn mode_from_str() {
+        for mode in ModulatorMode::iter() {
+            assert_eq!(mode.to_string().parse::<ModulatorMode>().unwrap(), mode);
+            assert_eq!(format!("{mode:?}").parse::<ModulatorMode>().unwrap(), mode);
+        }
+        assert_eq!(
+            "sample & hold".parse::<ModulatorMode>().unwrap(),
+            ModulatorMode::SampleAndHold
+        );
+        let error = "Wobble".parse::<ModulatorMode>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
 }
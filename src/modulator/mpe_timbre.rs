@@ -5,8 +5,10 @@ use std::any::Any;
 use uom::si::ratio::ratio;
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct MpeTimbreModulator {
     pub output_range: OutputRange,
     pub depth: Ratio,
@@ -21,6 +23,20 @@ impl Default for MpeTimbreModulator {
     }
 }
 
+impl Interpolate for MpeTimbreModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            output_range: step(&self.output_range, &other.output_range, t),
+            depth: self.depth.interpolate(&other.depth, t),
+        }
+    }
+}
+
+impl_parameters!(MpeTimbreModulator {
+    output_range,
+    depth,
+});
+
 impl Modulator for MpeTimbreModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
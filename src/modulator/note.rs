@@ -3,11 +3,13 @@
 use std::any::Any;
 use std::ops::RangeInclusive;
 
+use crate::interpolate::{step, Interpolate};
 use crate::modulator::{Modulator, ModulatorMode, OutputRange};
+use crate::path::impl_parameters;
 
 use super::*;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct NoteModulator {
     pub depth: Ratio,
     pub output_range: OutputRange,
@@ -17,6 +19,24 @@ pub struct NoteModulator {
     pub note_range: u32,
 }
 
+impl Interpolate for NoteModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            depth: self.depth.interpolate(&other.depth, t),
+            output_range: step(&self.output_range, &other.output_range, t),
+            root_note: step(&self.root_note, &other.root_note, t),
+            note_range: step(&self.note_range, &other.note_range, t),
+        }
+    }
+}
+
+impl_parameters!(NoteModulator {
+    depth,
+    output_range,
+    root_note,
+    note_range,
+});
+
 impl NoteModulator {
     /// Legal values for [note_range](Self::note_range).
     pub const NOTE_RANGE: RangeInclusive<u8> = 12..=120;
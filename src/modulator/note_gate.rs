@@ -2,16 +2,32 @@
 
 use std::any::Any;
 
+use crate::interpolate::{step, Interpolate};
 use crate::modulator::{Modulator, ModulatorMode, OutputRange};
+use crate::path::impl_parameters;
 
 use super::*;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct NoteGateModulator {
     pub depth: Ratio,
     pub output_range: OutputRange,
 }
 
+impl Interpolate for NoteGateModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            depth: self.depth.interpolate(&other.depth, t),
+            output_range: step(&self.output_range, &other.output_range, t),
+        }
+    }
+}
+
+impl_parameters!(NoteGateModulator {
+    depth,
+    output_range,
+});
+
 impl Modulator for NoteGateModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
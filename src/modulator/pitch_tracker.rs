@@ -7,11 +7,13 @@ use music_note::midi;
 use uom::si::f32::Ratio;
 use uom::si::ratio::percent;
 
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 use crate::*;
 
 use super::*;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct PitchTrackerModulator {
     pub depth: Ratio,
     pub output_range: OutputRange,
@@ -36,6 +38,30 @@ impl Default for PitchTrackerModulator {
     }
 }
 
+impl Interpolate for PitchTrackerModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            depth: self.depth.interpolate(&other.depth, t),
+            output_range: step(&self.output_range, &other.output_range, t),
+            audio_source: step(&self.audio_source, &other.audio_source, t),
+            sensitivity: self.sensitivity.interpolate(&other.sensitivity, t),
+            lowest_note: step(&self.lowest_note, &other.lowest_note, t),
+            root_note: step(&self.root_note, &other.root_note, t),
+            highest_note: step(&self.highest_note, &other.highest_note, t),
+        }
+    }
+}
+
+impl_parameters!(PitchTrackerModulator {
+    depth,
+    output_range,
+    audio_source,
+    sensitivity,
+    lowest_note,
+    root_note,
+    highest_note,
+});
+
 impl Modulator for PitchTrackerModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -6,8 +6,10 @@ use std::any::Any;
 use uom::si::ratio::ratio;
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct PitchWheelModulator {
     pub depth: Ratio,
     pub output_range: OutputRange,
@@ -22,6 +24,20 @@ impl Default for PitchWheelModulator {
     }
 }
 
+impl Interpolate for PitchWheelModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            depth: self.depth.interpolate(&other.depth, t),
+            output_range: step(&self.output_range, &other.output_range, t),
+        }
+    }
+}
+
+impl_parameters!(PitchWheelModulator {
+    depth,
+    output_range,
+});
+
 impl Modulator for PitchWheelModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -34,7 +50,7 @@ impl Modulator for PitchWheelModulator {
     }
 
     fn mode(&self) -> ModulatorMode {
-        ModulatorMode::Pressure
+        ModulatorMode::PitchWheel
     }
 }
 
@@ -51,6 +67,8 @@ mod test {
         let modulator = PitchWheelModulator::default();
         assert_eq!(modulator.depth.get::<percent>(), 100.0);
         assert_eq!(modulator.output_range, OutputRange::Bipolar);
+        assert_eq!(modulator.mode(), ModulatorMode::PitchWheel);
+        assert_eq!(modulator.as_block().mode, ModulatorMode::PitchWheel);
     }
 
     #[test]
@@ -6,8 +6,10 @@ use std::any::Any;
 use uom::si::ratio::ratio;
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct PressureModulator {
     pub depth: Ratio,
     pub output_range: OutputRange,
@@ -22,6 +24,20 @@ impl Default for PressureModulator {
     }
 }
 
+impl Interpolate for PressureModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            depth: self.depth.interpolate(&other.depth, t),
+            output_range: step(&self.output_range, &other.output_range, t),
+        }
+    }
+}
+
+impl_parameters!(PressureModulator {
+    depth,
+    output_range,
+});
+
 impl Modulator for PressureModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -2,9 +2,15 @@
 
 use std::any::Any;
 
+use uom::si::f32::Frequency;
+use uom::si::frequency::hertz;
+use uom::si::ratio::percent;
+
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
-#[derive(Clone, Copy, Debug, Eq, FromRepr, PartialEq)]
+#[derive(Clone, Copy, Debug, EnumIter, Eq, FromRepr, PartialEq)]
 #[repr(u32)]
 pub enum VoiceMode {
     // The discriminants correspond to the file format.
@@ -29,7 +35,9 @@ impl Display for VoiceMode {
     }
 }
 
-#[derive(Debug, PartialEq)]
+impl_parameters!(VoiceMode);
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct RandomModulator {
     pub output_range: OutputRange,
     pub depth: Ratio,
@@ -42,6 +50,57 @@ pub struct RandomModulator {
     pub voice_mode: VoiceMode,
 }
 
+impl Default for RandomModulator {
+    fn default() -> Self {
+        Self {
+            output_range: OutputRange::Bipolar,
+            depth: Ratio::new::<percent>(100.0),
+            rate: Rate {
+                sync: false,
+                frequency: Frequency::new::<hertz>(1.0),
+                numerator: 4,
+                denominator: NoteValue::Sixteenth,
+            },
+            jitter: Ratio::zero(),
+            smooth: Ratio::zero(),
+            chaos: Ratio::new::<percent>(100.0),
+            trigger_threshold: Ratio::new::<percent>(50.0),
+            note_trigger_mode: NoteTriggerMode::Auto,
+            voice_mode: VoiceMode::Unison,
+        }
+    }
+}
+
+impl Interpolate for RandomModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            output_range: step(&self.output_range, &other.output_range, t),
+            depth: self.depth.interpolate(&other.depth, t),
+            rate: self.rate.interpolate(&other.rate, t),
+            jitter: self.jitter.interpolate(&other.jitter, t),
+            smooth: self.smooth.interpolate(&other.smooth, t),
+            chaos: self.chaos.interpolate(&other.chaos, t),
+            trigger_threshold: self
+                .trigger_threshold
+                .interpolate(&other.trigger_threshold, t),
+            note_trigger_mode: step(&self.note_trigger_mode, &other.note_trigger_mode, t),
+            voice_mode: step(&self.voice_mode, &other.voice_mode, t),
+        }
+    }
+}
+
+impl_parameters!(RandomModulator {
+    output_range,
+    depth,
+    rate,
+    jitter,
+    smooth,
+    chaos,
+    trigger_threshold,
+    note_trigger_mode,
+    voice_mode,
+});
+
 impl Modulator for RandomModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -58,6 +117,13 @@ impl Modulator for RandomModulator {
     }
 }
 
+impl dyn Modulator {
+    #[must_use]
+    pub fn as_random(&self) -> Option<&RandomModulator> {
+        self.downcast_ref::<RandomModulator>()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use approx::assert_relative_eq;
@@ -95,6 +161,12 @@ mod test {
             assert_eq!(modulator.note_trigger_mode, NoteTriggerMode::Auto);
             assert_eq!(modulator.voice_mode, VoiceMode::Unison);
         }
+
+        let preset = read_modulator_preset("random", "random-2.1.0.phaseplant").unwrap();
+        assert_eq!(
+            preset.modulator_containers[0].modulator.as_random(),
+            Some(&RandomModulator::default())
+        );
     }
 
     #[test]
@@ -2,11 +2,13 @@
 
 use std::any::Any;
 
+use crate::interpolate::{step, Interpolate};
 use crate::modulator::{Modulator, ModulatorMode};
+use crate::path::impl_parameters;
 
 use super::*;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct RemapModulator {
     pub depth: Ratio,
 
@@ -20,6 +22,28 @@ pub struct RemapModulator {
     pub shape_edited: bool,
 }
 
+impl Interpolate for RemapModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            depth: self.depth.interpolate(&other.depth, t),
+            bipolar: step(&self.bipolar, &other.bipolar, t),
+            shape: self.shape.interpolate(&other.shape, t),
+            shape_name: step(&self.shape_name, &other.shape_name, t),
+            shape_path: step(&self.shape_path, &other.shape_path, t),
+            shape_edited: step(&self.shape_edited, &other.shape_edited, t),
+        }
+    }
+}
+
+impl_parameters!(RemapModulator {
+    depth,
+    bipolar,
+    shape,
+    shape_name,
+    shape_path,
+    shape_edited,
+});
+
 impl Modulator for RemapModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
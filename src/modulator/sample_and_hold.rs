@@ -4,11 +4,13 @@ use std::any::Any;
 
 use uom::si::ratio::ratio;
 
+use crate::interpolate::{step, Interpolate};
 use crate::modulator::{Modulator, ModulatorMode};
+use crate::path::impl_parameters;
 
 use super::*;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct SampleAndHoldModulator {
     pub depth: Ratio,
     pub note_trigger_mode: NoteTriggerMode,
@@ -28,6 +30,28 @@ impl Default for SampleAndHoldModulator {
         }
     }
 }
+
+impl Interpolate for SampleAndHoldModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            depth: self.depth.interpolate(&other.depth, t),
+            note_trigger_mode: step(&self.note_trigger_mode, &other.note_trigger_mode, t),
+            trigger_threshold: self
+                .trigger_threshold
+                .interpolate(&other.trigger_threshold, t),
+            input_a: self.input_a.interpolate(&other.input_a, t),
+            input_b: self.input_b.interpolate(&other.input_b, t),
+        }
+    }
+}
+
+impl_parameters!(SampleAndHoldModulator {
+    depth,
+    note_trigger_mode,
+    trigger_threshold,
+    input_a,
+    input_b,
+});
 impl Modulator for SampleAndHoldModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -44,6 +68,13 @@ impl Modulator for SampleAndHoldModulator {
     }
 }
 
+impl dyn Modulator {
+    #[must_use]
+    pub fn as_sample_and_hold(&self) -> Option<&SampleAndHoldModulator> {
+        self.downcast_ref::<SampleAndHoldModulator>()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use approx::assert_relative_eq;
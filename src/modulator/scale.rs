@@ -4,8 +4,10 @@
 use std::any::Any;
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct ScaleModulator {
     pub output_range: OutputRange,
     pub input_a: f32,
@@ -14,6 +16,26 @@ pub struct ScaleModulator {
     pub depth: Ratio,
 }
 
+impl Interpolate for ScaleModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            output_range: step(&self.output_range, &other.output_range, t),
+            input_a: self.input_a.interpolate(&other.input_a, t),
+            input_b: self.input_b.interpolate(&other.input_b, t),
+            multiplier: self.multiplier.interpolate(&other.multiplier, t),
+            depth: self.depth.interpolate(&other.depth, t),
+        }
+    }
+}
+
+impl_parameters!(ScaleModulator {
+    output_range,
+    input_a,
+    input_b,
+    multiplier,
+    depth,
+});
+
 impl Modulator for ScaleModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -7,8 +7,10 @@ use std::any::Any;
 use uom::si::time::millisecond;
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct SlewLimiterModulator {
     pub attack: Time,
     pub decay: Time,
@@ -25,6 +27,22 @@ impl Default for SlewLimiterModulator {
     }
 }
 
+impl Interpolate for SlewLimiterModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            attack: self.attack.interpolate(&other.attack, t),
+            decay: self.decay.interpolate(&other.decay, t),
+            linked: step(&self.linked, &other.linked, t),
+        }
+    }
+}
+
+impl_parameters!(SlewLimiterModulator {
+    attack,
+    decay,
+    linked,
+});
+
 impl Modulator for SlewLimiterModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -0,0 +1,225 @@
+//! How notes restart modulators.
+//!
+//! Phase Plant 1 had a retrigger switch on each modulator and a global
+//! retrigger setting that, when disabled, made every voice share a single
+//! free-running modulator. Phase Plant 2 replaced both with a
+//! [note trigger mode](NoteTriggerMode) on each modulator. A
+//! [`TriggerBehavior`] presents the same view regardless of which version
+//! saved the preset.
+
+use crate::*;
+
+use super::*;
+
+impl NoteTriggerMode {
+    /// Convert the retrigger switch from a Phase Plant 1 modulator.
+    pub(crate) fn from_retrigger(retrigger: bool) -> Self {
+        if retrigger {
+            NoteTriggerMode::Auto
+        } else {
+            NoteTriggerMode::Never
+        }
+    }
+
+    /// If playing a note can restart the modulator.
+    pub fn retriggers(&self) -> bool {
+        *self != NoteTriggerMode::Never
+    }
+}
+
+/// How a modulator responds to notes once the global retrigger setting of
+/// Phase Plant 1 has been taken into account.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TriggerBehavior {
+    /// Mode stored with the modulator.
+    pub note_trigger_mode: NoteTriggerMode,
+
+    /// Mode the modulator behaves as. Differs from the stored mode when the
+    /// global retrigger setting of Phase Plant 1 was disabled.
+    pub effective_mode: NoteTriggerMode,
+}
+
+impl TriggerBehavior {
+    /// Combine the mode stored with a modulator and the global retrigger
+    /// setting of the preset. The global setting only applies to presets
+    /// saved before Phase Plant 2.
+    pub fn new(
+        note_trigger_mode: NoteTriggerMode,
+        retrigger_enabled: bool,
+        format_version: &Version<u32>,
+    ) -> Self {
+        let uses_global_retrigger =
+            !format_version.is_at_least(&PhasePlantRelease::V2_0_0.format_version());
+        let effective_mode = if uses_global_retrigger && !retrigger_enabled {
+            NoteTriggerMode::Never
+        } else {
+            note_trigger_mode
+        };
+        Self {
+            note_trigger_mode,
+            effective_mode,
+        }
+    }
+
+    /// If each voice has its own instance of the modulator that notes restart.
+    /// Otherwise all voices share a single free-running modulator.
+    pub fn retriggers(&self) -> bool {
+        self.effective_mode.retriggers()
+    }
+}
+
+impl dyn Modulator {
+    /// The note trigger mode of modulators that can be restarted by notes.
+    #[must_use]
+    pub fn note_trigger_mode(&self) -> Option<NoteTriggerMode> {
+        use ModulatorMode::*;
+        match self.mode() {
+            Curve => self
+                .downcast_ref::<CurveModulator>()
+                .map(|m| m.note_trigger_mode),
+            Envelope => self
+                .downcast_ref::<EnvelopeModulator>()
+                .map(|m| m.note_trigger_mode),
+            Lfo => self
+                .downcast_ref::<LfoModulator>()
+                .map(|m| m.note_trigger_mode),
+            LfoTable => self
+                .downcast_ref::<LfoTableModulator>()
+                .map(|m| m.note_trigger_mode),
+            Random => self
+                .downcast_ref::<RandomModulator>()
+                .map(|m| m.note_trigger_mode),
+            SampleAndHold => self
+                .downcast_ref::<SampleAndHoldModulator>()
+                .map(|m| m.note_trigger_mode),
+            _ => None,
+        }
+    }
+}
+
+impl Preset {
+    /// How notes restart the modulator, or `None` if there is no modulator at
+    /// the index or the modulator can't be triggered by notes.
+    pub fn trigger_behavior(&self, mod_index: usize) -> Option<TriggerBehavior> {
+        let note_trigger_mode = self
+            .modulator_containers
+            .get(mod_index)?
+            .modulator
+            .note_trigger_mode()?;
+        Some(TriggerBehavior::new(
+            note_trigger_mode,
+            self.retrigger_enabled,
+            &self.format_version,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{read_modulator_preset, read_preset};
+
+    use super::*;
+
+    #[test]
+    fn global_retrigger_version_1() {
+        let format_version = PhasePlantRelease::V1_8_11.format_version();
+        let behavior = TriggerBehavior::new(NoteTriggerMode::Auto, false, &format_version);
+        assert_eq!(behavior.note_trigger_mode, NoteTriggerMode::Auto);
+        assert_eq!(behavior.effective_mode, NoteTriggerMode::Never);
+        assert!(!behavior.retriggers());
+
+        let behavior = TriggerBehavior::new(NoteTriggerMode::Auto, true, &format_version);
+        assert_eq!(behavior.effective_mode, NoteTriggerMode::Auto);
+        assert!(behavior.retriggers());
+    }
+
+    #[test]
+    fn global_retrigger_version_2() {
+        let format_version = PhasePlantRelease::V2_1_0.format_version();
+        let behavior = TriggerBehavior::new(NoteTriggerMode::Legato, false, &format_version);
+        assert_eq!(behavior.effective_mode, NoteTriggerMode::Legato);
+        assert!(behavior.retriggers());
+    }
+
+    #[test]
+    fn presets() {
+        let preset = read_modulator_preset("lfo", "lfo-1.8.13.phaseplant").unwrap();
+        let behavior = preset.trigger_behavior(0).unwrap();
+        assert_eq!(behavior.note_trigger_mode, NoteTriggerMode::Auto);
+        assert_eq!(behavior.retriggers(), preset.retrigger_enabled);
+
+        let preset =
+            read_modulator_preset("lfo", "lfo-note_trigger_never-bipolar-2.1.0.phaseplant")
+                .unwrap();
+        let behavior = preset.trigger_behavior(0).unwrap();
+        assert_eq!(behavior.effective_mode, NoteTriggerMode::Never);
+
+        let preset =
+            read_modulator_preset("lfo", "lfo-note_trigger_legato-sync-2.1.0.phaseplant").unwrap();
+        let behavior = preset.trigger_behavior(0).unwrap();
+        assert_eq!(behavior.effective_mode, NoteTriggerMode::Legato);
+        assert!(preset.trigger_behavior(1).is_none());
+
+        let preset = read_preset("misc", "polyphony-4-legato-1.8.13.phaseplant");
+        assert!(preset.trigger_behavior(0).is_none());
+    }
+
+    #[test]
+    fn modes() {
+        assert_eq!(NoteTriggerMode::default(), NoteTriggerMode::Auto);
+        assert_eq!(VelocityTriggerMode::default(), VelocityTriggerMode::Strike);
+        assert_eq!(MeteringMode::default(), MeteringMode::RootMeanSquared);
+
+        let preset =
+            read_modulator_preset("envelope", "envelope-note_trigger_always-2.1.0.phaseplant")
+                .unwrap();
+        let modulator = &preset.modulator_containers[0].modulator;
+        assert_eq!(
+            modulator.as_envelope().unwrap().note_trigger_mode,
+            NoteTriggerMode::Always
+        );
+        assert_eq!(modulator.note_trigger_mode(), Some(NoteTriggerMode::Always));
+
+        let preset = read_modulator_preset(
+            "random",
+            "random-trigger25-legato-independent-2.1.0.phaseplant",
+        )
+        .unwrap();
+        let modulator = &preset.modulator_containers[0].modulator;
+        assert_eq!(
+            modulator.as_random().unwrap().note_trigger_mode,
+            NoteTriggerMode::Legato
+        );
+
+        let preset = read_modulator_preset(
+            "sample_and_hold",
+            "sample_and_hold-thresh25-always-2.1.0.phaseplant",
+        )
+        .unwrap();
+        let modulator = &preset.modulator_containers[0].modulator;
+        assert_eq!(
+            modulator.as_sample_and_hold().unwrap().note_trigger_mode,
+            NoteTriggerMode::Always
+        );
+
+        let preset =
+            read_modulator_preset("velocity", "velocity-both-bipolar-2.1.0.phaseplant").unwrap();
+        let modulator = &preset.modulator_containers[0].modulator;
+        assert_eq!(
+            modulator.as_velocity().unwrap().trigger_mode,
+            VelocityTriggerMode::Both
+        );
+        assert_eq!(modulator.note_trigger_mode(), None);
+
+        let preset = read_modulator_preset(
+            "audio_follower",
+            "audio_follower-lane1-peak-2.0.12.phaseplant",
+        )
+        .unwrap();
+        let modulator = &preset.modulator_containers[0].modulator;
+        assert_eq!(
+            modulator.as_audio_follower().unwrap().metering_mode,
+            MeteringMode::Peak
+        );
+    }
+}
@@ -6,16 +6,21 @@ use strum_macros::{Display, FromRepr};
 use uom::si::ratio::ratio;
 
 use super::*;
+use crate::interpolate::{step, Interpolate};
+use crate::path::impl_parameters;
 
-#[derive(Clone, Copy, Debug, Display, FromRepr, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Display, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum VelocityTriggerMode {
     // The discriminants correspond to the file format.
+    #[default]
     Strike = 0,
     Release = 1,
     Both = 2,
 }
 
+impl_parameters!(VelocityTriggerMode);
+
 impl VelocityTriggerMode {
     pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
         Self::from_repr(id)
@@ -23,7 +28,7 @@ impl VelocityTriggerMode {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct VelocityModulator {
     pub output_range: OutputRange,
     pub depth: Ratio,
@@ -40,6 +45,22 @@ impl Default for VelocityModulator {
     }
 }
 
+impl Interpolate for VelocityModulator {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            output_range: step(&self.output_range, &other.output_range, t),
+            depth: self.depth.interpolate(&other.depth, t),
+            trigger_mode: step(&self.trigger_mode, &other.trigger_mode, t),
+        }
+    }
+}
+
+impl_parameters!(VelocityModulator {
+    output_range,
+    depth,
+    trigger_mode,
+});
+
 impl Modulator for VelocityModulator {
     fn as_block(&self) -> ModulatorBlock {
         self.into()
@@ -56,6 +77,13 @@ impl Modulator for VelocityModulator {
     }
 }
 
+impl dyn Modulator {
+    #[must_use]
+    pub fn as_velocity(&self) -> Option<&VelocityModulator> {
+        self.downcast_ref::<VelocityModulator>()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::test::read_modulator_preset;
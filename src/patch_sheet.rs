@@ -0,0 +1,430 @@
+//! A summary of a preset for documenting preset packs.
+//!
+//! The [patch sheet](Preset::patch_sheet) is Markdown organized like the
+//! Phase Plant window: the global settings, the generators and where their
+//! sound goes, the lanes of effects, the modulators, the modulations and the
+//! macro controls. Only what differs from the init preset is listed.
+//!
+//! Parameters are named by their [path](Preset::get) and shown rounded in the
+//! units Phase Plant displays.
+
+use std::fmt::Write;
+
+use crate::diff::{Change, PresetDiff, Value};
+use crate::io::WRITE_SAME_AS;
+use crate::path::Parameters;
+use crate::*;
+
+/// Lists such as curves and trance gate steps with more changes than this
+/// are only reported as edited.
+const LIST_CHANGES_MAX: usize = 4;
+
+/// Parameters that are shown elsewhere on the sheet.
+const SKIPPED_PARAMETERS: &[&str] = &["name", "enabled", "minimized"];
+
+impl Preset {
+    /// Describe the preset as Markdown for documentation, listing the
+    /// signal flow and every parameter that differs from the init preset.
+    pub fn patch_sheet(&self) -> String {
+        let init = Preset::init_for(WRITE_SAME_AS);
+        let diff = init.diff(self);
+        let mut sheet = String::new();
+        self.write_patch_sheet(&mut sheet, &diff.changes)
+            .expect("writing to a string");
+        sheet
+    }
+
+    fn write_patch_sheet(&self, sheet: &mut String, changes: &[Change]) -> std::fmt::Result {
+        let metadata = &self.metadata;
+        writeln!(
+            sheet,
+            "# {}",
+            metadata.name.as_deref().unwrap_or("Untitled")
+        )?;
+        for (label, value) in [
+            ("Author", &metadata.author),
+            ("Category", &metadata.category),
+            ("Description", &metadata.description),
+        ] {
+            if let Some(value) = value.as_deref().filter(|value| !value.is_empty()) {
+                write!(sheet, "\n{label}: {value}\n")?;
+            }
+        }
+
+        let global: Vec<_> = changes
+            .iter()
+            .filter(|change| {
+                ![
+                    "metadata.",
+                    "macro_controls.",
+                    "lanes.",
+                    "generators.",
+                    "modulators.",
+                    "modulations.",
+                ]
+                .iter()
+                .any(|prefix| change.path.starts_with(prefix))
+            })
+            .collect();
+        if !global.is_empty() {
+            sheet.push_str("\n## Global\n\n");
+            for change in global {
+                write_change(sheet, "", "", change)?;
+            }
+        }
+
+        if !self.generators.is_empty() {
+            sheet.push_str("\n## Generators\n\n");
+        }
+        for (index, generator) in self.generators.iter().enumerate() {
+            write!(
+                sheet,
+                "{}. **{}** \"{}\"",
+                index + 1,
+                generator.mode().name(),
+                generator.name()
+            )?;
+            if let Some(level) = generator.level_db() {
+                write!(sheet, ", level {:.1} dB", level.db())?;
+            }
+            if let Some(destination) = output_destination(generator.as_ref()) {
+                write!(sheet, " → {destination}")?;
+            }
+            if !generator.is_enabled() {
+                sheet.push_str(" (disabled)");
+            }
+            sheet.push('\n');
+            write_parameters(
+                sheet,
+                generator.as_parameters(),
+                generator.mode().default_generator().as_parameters(),
+                &["level", "gain", "destination"],
+                "   ",
+            )?;
+        }
+
+        for (index, lane) in self.lanes.iter().enumerate() {
+            write!(sheet, "\n## Lane {} → {}\n\n", index + 1, lane.destination)?;
+            let prefix = format!("lanes.{index}.");
+            for change in changes.iter().filter(|change| {
+                change.path.starts_with(&prefix)
+                    && !change.path[prefix.len()..].starts_with("snapins")
+            }) {
+                write_change(sheet, "", &prefix, change)?;
+            }
+            if lane.snapins.is_empty() {
+                sheet.push_str("No effects.\n");
+            }
+            write_snapins(sheet, &lane.snapins, "")?;
+        }
+
+        if !self.modulator_containers.is_empty() {
+            sheet.push_str("\n## Modulators\n\n");
+        }
+        for (index, container) in self.modulator_containers.iter().enumerate() {
+            write!(sheet, "{}. **{}**", index + 1, container.modulator.mode())?;
+            if !container.enabled {
+                sheet.push_str(" (disabled)");
+            }
+            sheet.push('\n');
+            write_parameters(
+                sheet,
+                container.modulator.as_parameters(),
+                container
+                    .modulator
+                    .mode()
+                    .default_modulator()
+                    .as_parameters(),
+                &[],
+                "   ",
+            )?;
+        }
+
+        if !self.modulations.is_empty() {
+            sheet.push_str("\n## Modulations\n\n");
+        }
+        for modulation in &self.modulations {
+            writeln!(sheet, "- {modulation}")?;
+        }
+
+        let macro_changes: Vec<_> = changes
+            .iter()
+            .filter(|change| change.path.starts_with("macro_controls."))
+            .collect();
+        if !macro_changes.is_empty() {
+            sheet.push_str("\n## Macro Controls\n\n");
+            for change in macro_changes {
+                let path = &change.path["macro_controls.".len()..];
+                let (index, name) = path.split_once('.').unwrap_or((path, ""));
+                let number = index.parse::<usize>().map_or(0, |index| index + 1);
+                if let Some(after) = &change.after {
+                    writeln!(
+                        sheet,
+                        "- Macro {number} {}: {}",
+                        label(name),
+                        display(after)
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn output_destination(generator: &dyn Generator) -> Option<generator::OutputDestination> {
+    generator
+        .as_envelope_output()
+        .map(|output| output.destination)
+        .or_else(|| generator.as_curve_output().map(|output| output.destination))
+}
+
+fn write_snapins(sheet: &mut String, snapins: &[Snapin], indent: &str) -> std::fmt::Result {
+    for (index, snapin) in snapins.iter().enumerate() {
+        let effect = &snapin.effect;
+        write!(sheet, "{indent}{}. **{}**", index + 1, effect.mode().name())?;
+        if !snapin.preset_name.is_empty() {
+            write!(sheet, " \"{}\"", snapin.preset_name)?;
+        }
+        if let Some(mix) = effect.mix() {
+            if mix.get::<uom::si::ratio::percent>() != 100.0 {
+                write!(sheet, ", mix {:.1}%", mix.get::<uom::si::ratio::percent>())?;
+            }
+        }
+        if !snapin.enabled {
+            sheet.push_str(" (disabled)");
+        }
+        sheet.push('\n');
+        let nested = format!("{indent}   ");
+        write_parameters(
+            sheet,
+            effect.as_parameters(),
+            effect.mode().default_effect().as_parameters(),
+            &["mix"],
+            &nested,
+        )?;
+        if let Some(multipass) = effect.as_multipass() {
+            for (lane_index, lane) in multipass.lanes.iter().enumerate() {
+                if !lane.snapins.is_empty() {
+                    writeln!(sheet, "{nested}- Band {}", lane_index + 1)?;
+                    write_snapins(sheet, &lane.snapins, &format!("{nested}  "))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_change(
+    sheet: &mut String,
+    indent: &str,
+    prefix: &str,
+    change: &Change,
+) -> std::fmt::Result {
+    if let Some(after) = &change.after {
+        writeln!(
+            sheet,
+            "{indent}- {}: {}",
+            label(&change.path[prefix.len()..]),
+            display(after)
+        )?;
+    }
+    Ok(())
+}
+
+/// List the parameters that differ from the default. Lists with many changes
+/// or with items added or removed are only reported as edited.
+fn write_parameters(
+    sheet: &mut String,
+    value: &dyn Parameters,
+    default: &dyn Parameters,
+    skipped: &[&str],
+    indent: &str,
+) -> std::fmt::Result {
+    let mut diff = PresetDiff::default();
+    diff.parameters("", default, value);
+    let changes: Vec<_> = diff
+        .changes
+        .iter()
+        .filter(|change| {
+            let name = change.path.split('.').next().unwrap_or_default();
+            !SKIPPED_PARAMETERS.contains(&name) && !skipped.contains(&name)
+        })
+        .collect();
+
+    let mut edited_lists = Vec::new();
+    for change in &changes {
+        if let Some(list) = list_path(&change.path) {
+            let list_changes: Vec<_> = changes
+                .iter()
+                .filter(|change| list_path(&change.path) == Some(list))
+                .collect();
+            if list_changes.len() > LIST_CHANGES_MAX
+                || list_changes
+                    .iter()
+                    .any(|change| change.before.is_none() || change.after.is_none())
+            {
+                if !edited_lists.contains(&list) {
+                    writeln!(sheet, "{indent}- {}: edited", label(list))?;
+                    edited_lists.push(list);
+                }
+                continue;
+            }
+        }
+        write_change(sheet, indent, "", change)?;
+    }
+    Ok(())
+}
+
+/// The path of the list that holds the parameter, such as `shape` for
+/// `shape.2.x`.
+fn list_path(path: &str) -> Option<&str> {
+    let mut end: usize = 0;
+    for segment in path.split('.') {
+        if segment.parse::<usize>().is_ok() {
+            return Some(&path[..end.saturating_sub(1)]);
+        }
+        end += segment.len() + 1;
+    }
+    None
+}
+
+/// The value as Phase Plant displays it. Numbers are rounded to two decimal
+/// places, or three significant digits if they are smaller than one.
+fn display(value: &Value) -> String {
+    match value {
+        Value::Number { value, unit } => {
+            let decimals = if value.abs() < 1.0 && *value != 0.0 {
+                (2.0 - value.abs().log10().floor()).min(6.0)
+            } else {
+                2.0
+            };
+            let scale = 10f32.powf(decimals);
+            Value::Number {
+                value: (value * scale).round() / scale + 0.0,
+                unit: *unit,
+            }
+            .to_string()
+        }
+        _ => value.to_string(),
+    }
+}
+
+/// A path or field name as it would be written in a sentence, such as
+/// `Unison detune` for `unison.detune`.
+fn label(name: &str) -> String {
+    let words = name.replace(['.', '_'], " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => words,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use uom::si::f32::{Frequency, Ratio};
+    use uom::si::frequency::hertz;
+    use uom::si::ratio::percent;
+
+    use crate::diff::Unit;
+    use crate::effect::{EffectMode, Filter};
+    use crate::generator::{AnalogOscillator, GeneratorMode};
+    use crate::modulator::{LfoModulator, ModulatorMode};
+
+    use crate::test::{read_effect_preset, read_generator_preset, read_preset};
+
+    use super::*;
+
+    #[test]
+    fn init() {
+        let sheet = read_preset("init", "init-2.1.0.phaseplant").patch_sheet();
+        assert!(sheet.starts_with("# init-2.1.0\n\nAuthor: softdev.ca\n"));
+        assert!(!sheet.contains("## Global"));
+        assert!(!sheet.contains("## Generators"));
+        assert!(sheet.contains("## Lane 1 → Lane 2\n\nNo effects.\n\n## Lane 2 → Lane 3\n"));
+    }
+
+    #[test]
+    fn patch_sheet() {
+        let sheet = read_effect_preset(
+            "delay",
+            "delay-ping_pong-duck11%-pan77%left-1.8.13.phaseplant",
+        )
+        .unwrap()
+        .patch_sheet();
+        assert!(sheet.contains("1. **Delay**, mix 50.0%\n"));
+        assert!(sheet.contains("   - Bounce: true\n"));
+        assert!(sheet.contains("   - Pan: -77%\n"), "{sheet}");
+        assert!(!sheet.contains("   - Time:"));
+
+        let sheet = read_generator_preset(
+            "envelope_output",
+            "envelope_output-gain-20-pan50-sideband-1.8.16.phaseplant",
+        )
+        .unwrap()
+        .patch_sheet();
+        assert!(sheet.contains("## Generators\n"));
+        assert!(sheet.contains("**Envelope** \"Envelope\", level -20.0 dB → Sideband"));
+    }
+
+    #[test]
+    fn parameters() {
+        let mut sheet = String::new();
+        let filter = Filter {
+            cutoff: Frequency::new::<hertz>(1500.0),
+            ..Default::default()
+        };
+        write_parameters(
+            &mut sheet,
+            &filter,
+            EffectMode::Filter.default_effect().as_parameters(),
+            &["mix"],
+            "",
+        )
+        .unwrap();
+        assert_eq!(sheet, "- Cutoff: 1500 Hz\n");
+
+        let mut sheet = String::new();
+        let mut oscillator = AnalogOscillator::default();
+        oscillator.unison.voices = 7;
+        oscillator.name = "Bass".to_owned();
+        write_parameters(
+            &mut sheet,
+            &oscillator,
+            GeneratorMode::AnalogOscillator
+                .default_generator()
+                .as_parameters(),
+            &[],
+            "   ",
+        )
+        .unwrap();
+        assert_eq!(sheet, "   - Unison voices: 7\n");
+
+        let mut sheet = String::new();
+        let mut lfo = ModulatorMode::Lfo.default_modulator();
+        let lfo = lfo.downcast_mut::<LfoModulator>().unwrap();
+        lfo.shape = vec![CurvePoint::new_sharp(0.0, 0.0, 0.5, 0.5)];
+        lfo.depth = Ratio::new::<percent>(25.0);
+        write_parameters(
+            &mut sheet,
+            lfo,
+            ModulatorMode::Lfo.default_modulator().as_parameters(),
+            &[],
+            "",
+        )
+        .unwrap();
+        assert_eq!(sheet, "- Depth: 25%\n- Shape: edited\n");
+
+        let seconds = |value| {
+            display(&Value::Number {
+                value,
+                unit: Unit::Seconds,
+            })
+        };
+        assert_eq!(seconds(0.004_321), "0.00432 s");
+        assert_eq!(seconds(-12.345_67), "-12.35 s");
+        assert_eq!(label("unison.detune"), "Unison detune");
+        assert_eq!(list_path("shape.2.x"), Some("shape"));
+        assert_eq!(list_path("unison.voices"), None);
+    }
+}
@@ -0,0 +1,1307 @@
+//! Parameters addressed by a path such as `lanes.0.snapins.2.mix`.
+//!
+//! The paths are the ones used by [`PresetDiff`](crate::diff::PresetDiff), so
+//! a change found by comparing two presets can be applied to a third. Numbers
+//! are read and written in the units Phase Plant displays. Values are set from
+//! text that may include a unit, such as `-6 dB`, `440 Hz` or `25%`.
+//!
+//! The parameters of effects, generators and modulators are the names of
+//! their fields, such as `lanes.0.snapins.1.cutoff`, `generators.2.unison.voices`
+//! or `modulators.0.rate.frequency`. Items in lists are numbered, such as
+//! `lanes.0.snapins.1.patterns.0.steps.3.enabled`. Choices such as the mode
+//! of a filter are set by the name Phase Plant displays.
+
+use std::fmt::{Display, Formatter};
+use std::io::{Error, ErrorKind};
+use std::ops::RangeInclusive;
+
+use strum::IntoEnumIterator;
+use uom::si::f32::{Frequency, Ratio, Time};
+use uom::si::frequency::hertz;
+use uom::si::ratio::percent;
+use uom::si::time::second;
+
+use crate::diff::{Unit, Value};
+use crate::effect::*;
+use crate::generator::{self, Generator, GeneratorMode};
+use crate::modulator::{self, Modulator, ModulatorMode};
+use crate::*;
+
+macro_rules! field {
+    ($name:ident $(, $mut_:tt)?) => {
+        /// A parameter found by its path. The shared and mutable parameters
+        /// have the same variants, so some values are only read from one.
+        #[allow(dead_code)]
+        pub(crate) enum $name<'a> {
+            Bool(&'a $($mut_)? bool),
+            Text(&'a $($mut_)? String),
+
+            /// Text that is empty when not set.
+            OptionalText(&'a $($mut_)? Option<String>),
+
+            /// A number stored in the unit it is displayed in.
+            Number(&'a $($mut_)? f32, Unit),
+
+            Count(&'a $($mut_)? u32),
+
+            /// A count that is empty when not set.
+            OptionalCount(&'a $($mut_)? Option<u32>),
+
+            SmallCount(&'a $($mut_)? u8),
+
+            /// A count that is limited to the range, such as the steps of a
+            /// pattern.
+            Size(&'a $($mut_)? usize, RangeInclusive<usize>),
+
+            Percent(&'a $($mut_)? Ratio),
+
+            /// Between zero and one, displayed as a percentage.
+            Fraction(&'a $($mut_)? f32),
+
+            Gain(&'a $($mut_)? Decibels),
+
+            /// A linear multiplier displayed in decibels.
+            LinearGain(&'a $($mut_)? f32),
+
+            Hertz(&'a $($mut_)? Frequency),
+            Seconds(&'a $($mut_)? Time),
+            Pitch(&'a $($mut_)? Semitones),
+
+            /// One of a list of names, such as the mode of a filter.
+            Choice(&'a $($mut_)? dyn Choice),
+
+            /// The contents of a file, such as a sample. They are compared
+            /// but can't be set from text.
+            Data(&'a $($mut_)? Vec<u8>),
+        }
+    };
+}
+
+field!(Field);
+field!(FieldMut, mut);
+
+/// A parameter that is one of a list of names.
+pub(crate) trait Choice {
+    fn name(&self) -> String;
+
+    fn names(&self) -> Vec<String>;
+
+    /// Change to the choice with the name, ignoring case. Returns `false` if
+    /// there is no such choice.
+    fn choose(&mut self, name: &str) -> bool;
+}
+
+impl<T: IntoEnumIterator + Display> Choice for T {
+    fn name(&self) -> String {
+        self.to_string()
+    }
+
+    fn names(&self) -> Vec<String> {
+        T::iter().map(|choice| choice.to_string()).collect()
+    }
+
+    fn choose(&mut self, name: &str) -> bool {
+        match T::iter().find(|choice| choice.to_string().eq_ignore_ascii_case(name.trim())) {
+            Some(choice) => {
+                *self = choice;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// The parameters of a value found by the names of its fields. Nested values
+/// and lists add their parameters below the name of the field.
+pub(crate) trait Parameters {
+    /// Add each parameter with its path below `path`.
+    fn parameters<'a>(&'a self, path: &str, parameters: &mut Vec<(String, Field<'a>)>);
+
+    fn parameters_mut<'a>(&'a mut self, path: &str, parameters: &mut Vec<(String, FieldMut<'a>)>);
+}
+
+/// Implement [`Parameters`] for a struct from the names of its fields, or
+/// for an enum that is a [`Choice`]. Each field is displayed the usual way
+/// for its type unless the variant of [`Field`] is given, such as
+/// `amount: LinearGain` or `tuning: Number(Semitones)`.
+macro_rules! impl_parameters {
+    ($type:ty) => {
+        impl $crate::path::Parameters for $type {
+            fn parameters<'a>(
+                &'a self,
+                path: &str,
+                parameters: &mut Vec<(String, $crate::path::Field<'a>)>,
+            ) {
+                parameters.push((path.to_owned(), $crate::path::Field::Choice(self)));
+            }
+
+            fn parameters_mut<'a>(
+                &'a mut self,
+                path: &str,
+                parameters: &mut Vec<(String, $crate::path::FieldMut<'a>)>,
+            ) {
+                parameters.push((path.to_owned(), $crate::path::FieldMut::Choice(self)));
+            }
+        }
+    };
+    ($type:ty {}) => {
+        impl $crate::path::Parameters for $type {
+            fn parameters<'a>(
+                &'a self,
+                _path: &str,
+                _parameters: &mut Vec<(String, $crate::path::Field<'a>)>,
+            ) {
+            }
+
+            fn parameters_mut<'a>(
+                &'a mut self,
+                _path: &str,
+                _parameters: &mut Vec<(String, $crate::path::FieldMut<'a>)>,
+            ) {
+            }
+        }
+    };
+    ($type:ty { $($field:ident $(: $variant:ident $(($unit:ident))?)?),+ $(,)? }) => {
+        impl $crate::path::Parameters for $type {
+            fn parameters<'a>(
+                &'a self,
+                path: &str,
+                parameters: &mut Vec<(String, $crate::path::Field<'a>)>,
+            ) {
+                $(impl_parameters!(
+                    @field parameters, Field, parameters,
+                    $crate::path::join(path, stringify!($field)),
+                    &self.$field $(, $variant $(, $unit)?)?
+                );)+
+            }
+
+            fn parameters_mut<'a>(
+                &'a mut self,
+                path: &str,
+                parameters: &mut Vec<(String, $crate::path::FieldMut<'a>)>,
+            ) {
+                $(impl_parameters!(
+                    @field parameters_mut, FieldMut, parameters,
+                    $crate::path::join(path, stringify!($field)),
+                    &mut self.$field $(, $variant $(, $unit)?)?
+                );)+
+            }
+        }
+    };
+    (@field $method:ident, $field:ident, $parameters:ident, $path:expr, $value:expr) => {
+        $crate::path::Parameters::$method($value, &$path, $parameters)
+    };
+    (
+        @field $method:ident,
+        $field:ident,
+        $parameters:ident,
+        $path:expr,
+        $value:expr,
+        $variant:ident
+    ) => {
+        $parameters.push(($path, $crate::path::$field::$variant($value)))
+    };
+    (
+        @field $method:ident,
+        $field:ident,
+        $parameters:ident,
+        $path:expr,
+        $value:expr,
+        $variant:ident,
+        $unit:ident
+    ) => {
+        $parameters.push((
+            $path,
+            $crate::path::$field::$variant($value, $crate::diff::Unit::$unit),
+        ))
+    };
+}
+
+pub(crate) use impl_parameters;
+
+/// The path of a field below the path of the value it is in.
+pub(crate) fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{path}.{name}")
+    }
+}
+
+macro_rules! impl_leaf_parameters {
+    ($($type:ty => $variant:ident),+ $(,)?) => {$(
+        impl Parameters for $type {
+            fn parameters<'a>(&'a self, path: &str, parameters: &mut Vec<(String, Field<'a>)>) {
+                parameters.push((path.to_owned(), Field::$variant(self)));
+            }
+
+            fn parameters_mut<'a>(
+                &'a mut self,
+                path: &str,
+                parameters: &mut Vec<(String, FieldMut<'a>)>,
+            ) {
+                parameters.push((path.to_owned(), FieldMut::$variant(self)));
+            }
+        }
+    )+};
+}
+
+impl_leaf_parameters!(
+    bool => Bool,
+    String => Text,
+    Option<String> => OptionalText,
+    u32 => Count,
+    Option<u32> => OptionalCount,
+    u8 => SmallCount,
+    Ratio => Percent,
+    Decibels => Gain,
+    Frequency => Hertz,
+    Time => Seconds,
+    Semitones => Pitch,
+);
+
+/// Numbers without a unit, such as the Q of a filter.
+impl Parameters for f32 {
+    fn parameters<'a>(&'a self, path: &str, parameters: &mut Vec<(String, Field<'a>)>) {
+        parameters.push((path.to_owned(), Field::Number(self, Unit::Number)));
+    }
+
+    fn parameters_mut<'a>(&'a mut self, path: &str, parameters: &mut Vec<(String, FieldMut<'a>)>) {
+        parameters.push((path.to_owned(), FieldMut::Number(self, Unit::Number)));
+    }
+}
+
+impl<T: Parameters> Parameters for [T] {
+    fn parameters<'a>(&'a self, path: &str, parameters: &mut Vec<(String, Field<'a>)>) {
+        for (index, item) in self.iter().enumerate() {
+            item.parameters(&join(path, &index.to_string()), parameters);
+        }
+    }
+
+    fn parameters_mut<'a>(&'a mut self, path: &str, parameters: &mut Vec<(String, FieldMut<'a>)>) {
+        for (index, item) in self.iter_mut().enumerate() {
+            item.parameters_mut(&join(path, &index.to_string()), parameters);
+        }
+    }
+}
+
+impl<T: Parameters, const N: usize> Parameters for [T; N] {
+    fn parameters<'a>(&'a self, path: &str, parameters: &mut Vec<(String, Field<'a>)>) {
+        self.as_slice().parameters(path, parameters);
+    }
+
+    fn parameters_mut<'a>(&'a mut self, path: &str, parameters: &mut Vec<(String, FieldMut<'a>)>) {
+        self.as_mut_slice().parameters_mut(path, parameters);
+    }
+}
+
+impl<T: Parameters> Parameters for Vec<T> {
+    fn parameters<'a>(&'a self, path: &str, parameters: &mut Vec<(String, Field<'a>)>) {
+        self.as_slice().parameters(path, parameters);
+    }
+
+    fn parameters_mut<'a>(&'a mut self, path: &str, parameters: &mut Vec<(String, FieldMut<'a>)>) {
+        self.as_mut_slice().parameters_mut(path, parameters);
+    }
+}
+
+/// The parameters of a value with the path of each.
+pub(crate) fn parameters(value: &dyn Parameters) -> Vec<(String, Field<'_>)> {
+    let mut parameters = Vec::new();
+    value.parameters("", &mut parameters);
+    parameters
+}
+
+pub(crate) fn parameters_mut(value: &mut dyn Parameters) -> Vec<(String, FieldMut<'_>)> {
+    let mut parameters = Vec::new();
+    value.parameters_mut("", &mut parameters);
+    parameters
+}
+
+/// Downcast to the type of the mode.
+macro_rules! downcast_parameters {
+    (
+        $value:expr,
+        $mode:ident,
+        $downcast:ident,
+        $parameters:ty,
+        [$($($variant:ident)|+ => $type:ty),+ $(,)?]
+    ) => {
+        match $value.mode() {
+            $($($mode::$variant)|+ => $value.$downcast::<$type>().map(|value| value as $parameters),)+
+        }
+        .expect("type of the mode")
+    };
+}
+
+macro_rules! effect_parameters {
+    ($effect:expr, $downcast:ident, $parameters:ty) => {
+        downcast_parameters!($effect, EffectMode, $downcast, $parameters, [
+            Bitcrush => Bitcrush,
+            CarveEq => CarveEq,
+            ChannelMixer => ChannelMixer,
+            Chorus => Chorus,
+            CombFilter => CombFilter,
+            Compressor => Compressor,
+            Convolver => Convolver,
+            Delay => Delay,
+            Disperser => Disperser,
+            Distortion => Distortion,
+            DualDelay => DualDelay,
+            Dynamics => Dynamics,
+            Ensemble => Ensemble,
+            Faturator => Faturator,
+            Filter => Filter,
+            Flanger => Flanger,
+            FormantFilter => FormantFilter,
+            FrequencyShifter => FrequencyShifter,
+            Gain => Gain,
+            Gate => Gate,
+            Group => effect::Group,
+            Haas => Haas,
+            LadderFilter => LadderFilter,
+            Limiter => Limiter,
+            Multipass => Multipass,
+            NonlinearFilter => NonlinearFilter,
+            PhaseDistortion => PhaseDistortion,
+            Phaser => Phaser,
+            PitchShifter => PitchShifter,
+            Resonator => Resonator,
+            Reverb => Reverb,
+            Reverser => Reverser,
+            RingMod => RingMod,
+            SliceEq => SliceEq,
+            SnapHeap => SnapHeap,
+            Stereo => Stereo,
+            TapeStop => TapeStop,
+            ThreeBandEq => ThreeBandEq,
+            TranceGate => TranceGate,
+            TransientShaper => TransientShaper,
+        ])
+    };
+}
+
+macro_rules! generator_parameters {
+    ($generator:expr, $downcast:ident, $parameters:ty) => {
+        downcast_parameters!($generator, GeneratorMode, $downcast, $parameters, [
+            AnalogOscillator => generator::AnalogOscillator,
+            AuxRouting => generator::AuxRouting,
+            Blank => generator::BlankGenerator,
+            CurveOutput => generator::CurveOutput,
+            DistortionEffect => generator::DistortionEffect,
+            EnvelopeOutput => generator::EnvelopeOutput,
+            FilterEffect => generator::FilterEffect,
+            GranularGenerator => generator::GranularGenerator,
+            Group => generator::Group,
+            MixRouting => generator::MixRouting,
+            NoiseGenerator => generator::NoiseGenerator,
+            NonlinearFilterGenerator => generator::NonlinearFilterGenerator,
+            SamplePlayer => generator::SamplePlayer,
+            WavetableOscillator => generator::WavetableOscillator,
+        ])
+    };
+}
+
+macro_rules! modulator_parameters {
+    ($modulator:expr, $downcast:ident, $parameters:ty) => {
+        downcast_parameters!($modulator, ModulatorMode, $downcast, $parameters, [
+            AudioFollower => modulator::AudioFollowerModulator,
+            Aftertouch | Pressure => modulator::PressureModulator,
+            Blank => modulator::BlankModulator,
+            Curve => modulator::CurveModulator,
+            Envelope => modulator::EnvelopeModulator,
+            Group => modulator::Group,
+            Lfo => modulator::LfoModulator,
+            LfoTable => modulator::LfoTableModulator,
+            LowerLimit => modulator::LowerLimitModulator,
+            MidiCc => modulator::MidiCcModulator,
+            MpeTimbre => modulator::MpeTimbreModulator,
+            Note => modulator::NoteModulator,
+            NoteGate => modulator::NoteGateModulator,
+            PitchTracker => modulator::PitchTrackerModulator,
+            PitchWheel => modulator::PitchWheelModulator,
+            Random => modulator::RandomModulator,
+            Remap => modulator::RemapModulator,
+            SampleAndHold => modulator::SampleAndHoldModulator,
+            Scale => modulator::ScaleModulator,
+            SlewLimiter => modulator::SlewLimiterModulator,
+            UpperLimit => modulator::UpperLimitModulator,
+            Velocity => modulator::VelocityModulator,
+        ])
+    };
+}
+
+impl dyn Effect {
+    pub(crate) fn as_parameters(&self) -> &dyn Parameters {
+        effect_parameters!(self, downcast_ref, &dyn Parameters)
+    }
+
+    pub(crate) fn as_parameters_mut(&mut self) -> &mut dyn Parameters {
+        effect_parameters!(self, downcast_mut, &mut dyn Parameters)
+    }
+}
+
+impl dyn Generator {
+    pub(crate) fn as_parameters(&self) -> &dyn Parameters {
+        generator_parameters!(self, downcast_ref, &dyn Parameters)
+    }
+
+    pub(crate) fn as_parameters_mut(&mut self) -> &mut dyn Parameters {
+        generator_parameters!(self, downcast_mut, &mut dyn Parameters)
+    }
+}
+
+impl dyn Modulator {
+    pub(crate) fn as_parameters(&self) -> &dyn Parameters {
+        modulator_parameters!(self, downcast_ref, &dyn Parameters)
+    }
+
+    pub(crate) fn as_parameters_mut(&mut self) -> &mut dyn Parameters {
+        modulator_parameters!(self, downcast_mut, &mut dyn Parameters)
+    }
+}
+
+/// Find a parameter in a preset, and the snapins that may be nested in a
+/// Multipass. Generated for both shared and mutable access.
+macro_rules! resolve {
+    (
+        $preset_fn:ident,
+        $snapins_fn:ident,
+        $find_fn:ident,
+        $field:ident,
+        $get:ident,
+        $parameters:ident,
+        $as_parameters:ident,
+        $multipass:ident
+        $(, $mut_:tt)?
+    ) => {
+        fn $preset_fn<'a>(
+            preset: &'a $($mut_)? Preset,
+            path: &str,
+        ) -> Result<$field<'a>, Error> {
+            use $field::*;
+            let segments: Vec<&str> = path.split('.').collect();
+            Ok(match segments.as_slice() {
+                ["metadata", "name"] => OptionalText(&$($mut_)? preset.metadata.name),
+                ["metadata", "author"] => OptionalText(&$($mut_)? preset.metadata.author),
+                ["metadata", "description"] => {
+                    OptionalText(&$($mut_)? preset.metadata.description)
+                }
+                ["metadata", "category"] => OptionalText(&$($mut_)? preset.metadata.category),
+                ["master_gain"] => LinearGain(&$($mut_)? preset.master_gain),
+                ["master_pitch"] => Number(&$($mut_)? preset.master_pitch, Unit::Semitones),
+                ["polyphony"] => Count(&$($mut_)? preset.polyphony),
+                ["mod_wheel_value"] => Percent(&$($mut_)? preset.mod_wheel_value),
+                ["retrigger_enabled"] => Bool(&$($mut_)? preset.retrigger_enabled),
+                ["glide_enabled"] => Bool(&$($mut_)? preset.glide_enabled),
+                ["glide_legato"] => Bool(&$($mut_)? preset.glide_legato),
+                ["glide_time"] => Number(&$($mut_)? preset.glide_time, Unit::Seconds),
+                ["unison", rest @ ..] => {
+                    return $find_fn(&$($mut_)? preset.unison, path, rest)
+                }
+                ["macro_controls", index, rest @ ..] => {
+                    let macro_control = preset
+                        .macro_controls
+                        .$get(parse_index(path, index)?)
+                        .ok_or_else(|| not_found(path))?;
+                    return $find_fn(macro_control, path, rest);
+                }
+                ["lanes", index, rest @ ..] => {
+                    let lane = preset
+                        .lanes
+                        .$get(parse_index(path, index)?)
+                        .ok_or_else(|| not_found(path))?;
+                    match rest {
+                        ["enabled"] => Bool(&$($mut_)? lane.enabled),
+                        ["poly_count"] => SmallCount(&$($mut_)? lane.poly_count),
+                        ["mute"] => Bool(&$($mut_)? lane.mute),
+                        ["solo"] => Bool(&$($mut_)? lane.solo),
+                        ["gain"] => Gain(&$($mut_)? lane.gain),
+                        ["mix"] => Percent(&$($mut_)? lane.mix),
+                        ["snapins", rest @ ..] => {
+                            return $snapins_fn(&$($mut_)? lane.snapins, path, rest)
+                        }
+                        _ => return Err(not_found(path)),
+                    }
+                }
+                ["generators", index, rest @ ..] => {
+                    let generator = preset
+                        .generators
+                        .$get(parse_index(path, index)?)
+                        .ok_or_else(|| not_found(path))?;
+                    return $find_fn(generator.$as_parameters(), path, rest);
+                }
+                ["modulators", index, rest @ ..] => {
+                    let container = preset
+                        .modulator_containers
+                        .$get(parse_index(path, index)?)
+                        .ok_or_else(|| not_found(path))?;
+                    match rest {
+                        ["enabled"] => Bool(&$($mut_)? container.enabled),
+                        ["minimized"] => Bool(&$($mut_)? container.minimized),
+                        _ => return $find_fn(container.modulator.$as_parameters(), path, rest),
+                    }
+                }
+                ["modulations", index, name] => {
+                    let modulation = preset
+                        .modulations
+                        .$get(parse_index(path, index)?)
+                        .ok_or_else(|| not_found(path))?;
+                    match *name {
+                        "enabled" => Bool(&$($mut_)? modulation.enabled),
+                        "amount" => Percent(&$($mut_)? modulation.amount),
+                        "curve" => Percent(&$($mut_)? modulation.curve),
+                        _ => return Err(not_found(path)),
+                    }
+                }
+                _ => return Err(not_found(path)),
+            })
+        }
+
+        fn $snapins_fn<'a>(
+            snapins: &'a $($mut_)? [Snapin],
+            path: &str,
+            segments: &[&str],
+        ) -> Result<$field<'a>, Error> {
+            use $field::*;
+            let [index, rest @ ..] = segments else {
+                return Err(not_found(path));
+            };
+            let snapin = snapins
+                .$get(parse_index(path, index)?)
+                .ok_or_else(|| not_found(path))?;
+            Ok(match rest {
+                ["enabled"] => Bool(&$($mut_)? snapin.enabled),
+                ["minimized"] => Bool(&$($mut_)? snapin.minimized),
+                ["preset_name"] => Text(&$($mut_)? snapin.preset_name),
+                ["lanes", lane_index, "snapins", rest @ ..] => {
+                    let lane = snapin
+                        .effect
+                        .$multipass()
+                        .and_then(|multipass| {
+                            multipass.lanes.$get(lane_index.parse::<usize>().ok()?)
+                        })
+                        .ok_or_else(|| not_found(path))?;
+                    return $snapins_fn(&$($mut_)? lane.snapins, path, rest);
+                }
+                _ => return $find_fn(snapin.effect.$as_parameters(), path, rest),
+            })
+        }
+
+        /// The parameter of the value whose path is the remaining segments.
+        fn $find_fn<'a>(
+            value: &'a $($mut_)? dyn Parameters,
+            path: &str,
+            segments: &[&str],
+        ) -> Result<$field<'a>, Error> {
+            let name = segments.join(".");
+            let mut parameters = Vec::new();
+            value.$parameters("", &mut parameters);
+            parameters
+                .into_iter()
+                .find(|(parameter_path, _)| *parameter_path == name)
+                .map(|(_, field)| field)
+                .ok_or_else(|| not_found(path))
+        }
+    };
+}
+
+resolve!(
+    field,
+    snapin_field,
+    find,
+    Field,
+    get,
+    parameters,
+    as_parameters,
+    as_multipass
+);
+resolve!(
+    field_mut,
+    snapin_field_mut,
+    find_mut,
+    FieldMut,
+    get_mut,
+    parameters_mut,
+    as_parameters_mut,
+    as_multipass_mut,
+    mut
+);
+
+fn not_found(path: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("The preset does not have a parameter at {path}"),
+    )
+}
+
+fn parse_index(path: &str, index: &str) -> Result<usize, Error> {
+    index.parse().map_err(|_| not_found(path))
+}
+
+impl Field<'_> {
+    pub(crate) fn value(&self) -> Value {
+        use Field::*;
+        match self {
+            Bool(value) => Value::Bool { value: **value },
+            Text(value) => Value::Text {
+                value: value.to_string(),
+            },
+            OptionalText(value) => Value::Text {
+                value: value.as_deref().unwrap_or_default().to_owned(),
+            },
+            Number(value, unit) => Value::Number {
+                value: **value,
+                unit: *unit,
+            },
+            Count(value) => Value::Number {
+                value: **value as f32,
+                unit: Unit::Number,
+            },
+            OptionalCount(value) => match value {
+                Some(value) => Value::Number {
+                    value: *value as f32,
+                    unit: Unit::Number,
+                },
+                None => Value::Text {
+                    value: String::new(),
+                },
+            },
+            SmallCount(value) => Value::Number {
+                value: **value as f32,
+                unit: Unit::Number,
+            },
+            Size(value, _) => Value::Number {
+                value: **value as f32,
+                unit: Unit::Number,
+            },
+            Percent(value) => Value::Number {
+                value: value.get::<percent>(),
+                unit: Unit::Percent,
+            },
+            Fraction(value) => Value::Number {
+                value: **value * 100.0,
+                unit: Unit::Percent,
+            },
+            Gain(value) => Value::Number {
+                value: value.db(),
+                unit: Unit::Decibels,
+            },
+            LinearGain(value) => Value::Number {
+                value: Decibels::from_linear(**value).db(),
+                unit: Unit::Decibels,
+            },
+            Hertz(value) => Value::Number {
+                value: value.get::<hertz>(),
+                unit: Unit::Hertz,
+            },
+            Seconds(value) => Value::Number {
+                value: value.get::<second>(),
+                unit: Unit::Seconds,
+            },
+            Pitch(value) => Value::Number {
+                value: value.semitones(),
+                unit: Unit::Semitones,
+            },
+            Choice(value) => Value::Text {
+                value: value.name(),
+            },
+            Data(value) => Value::Text {
+                value: format!("{} bytes", value.len()),
+            },
+        }
+    }
+}
+
+impl Field<'_> {
+    /// If the parameters have the same value. The contents of files are
+    /// compared rather than their sizes.
+    pub(crate) fn is_same(&self, other: &Field) -> bool {
+        match (self, other) {
+            (Field::Data(value), Field::Data(other)) => value == other,
+            _ => self.value() == other.value(),
+        }
+    }
+}
+
+/// The value of a parameter as it is stored in the preset, so it can be
+/// restored exactly. Gains stored as multipliers, for example, would lose
+/// precision if they were restored from decibels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StoredValue(Stored);
+
+#[derive(Clone, Debug, PartialEq)]
+enum Stored {
+    Bool(bool),
+    Text(String),
+    OptionalText(Option<String>),
+    Number(f32, Unit),
+    Count(u32),
+    OptionalCount(Option<u32>),
+    SmallCount(u8),
+    Size(usize),
+    Percent(Ratio),
+    Fraction(f32),
+    Gain(Decibels),
+    LinearGain(f32),
+    Hertz(Frequency),
+    Seconds(Time),
+    Pitch(Semitones),
+
+    /// The name of the choice.
+    Choice(String),
+
+    Data(Vec<u8>),
+}
+
+impl StoredValue {
+    /// The value in the unit Phase Plant displays.
+    pub fn value(&self) -> Value {
+        let field = match &self.0 {
+            Stored::Bool(value) => Field::Bool(value),
+            Stored::Text(value) => Field::Text(value),
+            Stored::OptionalText(value) => Field::OptionalText(value),
+            Stored::Number(value, unit) => Field::Number(value, *unit),
+            Stored::Count(value) => Field::Count(value),
+            Stored::OptionalCount(value) => Field::OptionalCount(value),
+            Stored::SmallCount(value) => Field::SmallCount(value),
+            Stored::Size(value) => Field::Size(value, *value..=*value),
+            Stored::Percent(value) => Field::Percent(value),
+            Stored::Fraction(value) => Field::Fraction(value),
+            Stored::Gain(value) => Field::Gain(value),
+            Stored::LinearGain(value) => Field::LinearGain(value),
+            Stored::Hertz(value) => Field::Hertz(value),
+            Stored::Seconds(value) => Field::Seconds(value),
+            Stored::Pitch(value) => Field::Pitch(value),
+            Stored::Choice(name) => {
+                return Value::Text {
+                    value: name.clone(),
+                }
+            }
+            Stored::Data(value) => Field::Data(value),
+        };
+        field.value()
+    }
+}
+
+impl Field<'_> {
+    pub(crate) fn stored(&self) -> StoredValue {
+        use Field::*;
+        StoredValue(match self {
+            Bool(value) => Stored::Bool(**value),
+            Text(value) => Stored::Text(value.to_string()),
+            OptionalText(value) => Stored::OptionalText((*value).clone()),
+            Number(value, unit) => Stored::Number(**value, *unit),
+            Count(value) => Stored::Count(**value),
+            OptionalCount(value) => Stored::OptionalCount(**value),
+            SmallCount(value) => Stored::SmallCount(**value),
+            Size(value, _) => Stored::Size(**value),
+            Percent(value) => Stored::Percent(**value),
+            Fraction(value) => Stored::Fraction(**value),
+            Gain(value) => Stored::Gain(**value),
+            LinearGain(value) => Stored::LinearGain(**value),
+            Hertz(value) => Stored::Hertz(**value),
+            Seconds(value) => Stored::Seconds(**value),
+            Pitch(value) => Stored::Pitch(**value),
+            Choice(value) => Stored::Choice(value.name()),
+            Data(value) => Stored::Data(value.to_vec()),
+        })
+    }
+
+    /// The value the text would set the parameter to.
+    fn parse(&self, path: &str, text: &str) -> Result<StoredValue, Error> {
+        use Field::*;
+        Ok(StoredValue(match self {
+            Bool(_) => Stored::Bool(match text.trim().to_ascii_lowercase().as_str() {
+                "true" | "on" => true,
+                "false" | "off" => false,
+                _ => return Err(invalid_value(path, text, "true or false")),
+            }),
+            Text(_) => Stored::Text(text.to_owned()),
+            OptionalText(_) => {
+                Stored::OptionalText(Some(text.to_owned()).filter(|text| !text.is_empty()))
+            }
+            Number(_, unit) => Stored::Number(number(path, text, *unit)?, *unit),
+            Count(_) => Stored::Count(count(path, text)?),
+            OptionalCount(_) => Stored::OptionalCount(match text.trim() {
+                "" => None,
+                text => Some(count(path, text)?),
+            }),
+            SmallCount(_) => Stored::SmallCount(
+                u8::try_from(count(path, text)?)
+                    .map_err(|_| invalid_value(path, text, "a whole number up to 255"))?,
+            ),
+            Size(_, range) => {
+                let size = count(path, text)? as usize;
+                if !range.contains(&size) {
+                    let expected =
+                        format!("a whole number from {} to {}", range.start(), range.end());
+                    return Err(invalid_value(path, text, &expected));
+                }
+                Stored::Size(size)
+            }
+            Percent(_) => {
+                Stored::Percent(Ratio::new::<percent>(number(path, text, Unit::Percent)?))
+            }
+            Fraction(_) => Stored::Fraction(number(path, text, Unit::Percent)? / 100.0),
+            Gain(_) => Stored::Gain(Decibels::new(number(path, text, Unit::Decibels)?)),
+            LinearGain(_) => {
+                Stored::LinearGain(Decibels::new(number(path, text, Unit::Decibels)?).linear())
+            }
+            Hertz(_) => Stored::Hertz(Frequency::new::<hertz>(number(path, text, Unit::Hertz)?)),
+            Seconds(_) => Stored::Seconds(Time::new::<second>(number(path, text, Unit::Seconds)?)),
+            Pitch(_) => Stored::Pitch(Semitones::new(number(path, text, Unit::Semitones)?)),
+            Choice(value) => match value
+                .names()
+                .into_iter()
+                .find(|name| name.eq_ignore_ascii_case(text.trim()))
+            {
+                Some(name) => Stored::Choice(name),
+                None => {
+                    let expected = format!("one of {}", value.names().join(", "));
+                    return Err(invalid_value(path, text, &expected));
+                }
+            },
+            Data(_) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The contents at {path} can't be set from text"),
+                ))
+            }
+        }))
+    }
+}
+
+impl FieldMut<'_> {
+    /// Change the parameter to a value that was stored from a parameter of
+    /// the same type.
+    fn restore(self, path: &str, stored: &StoredValue) -> Result<(), Error> {
+        use FieldMut::*;
+        match (self, &stored.0) {
+            (Bool(value), Stored::Bool(stored)) => *value = *stored,
+            (Text(value), Stored::Text(stored)) => value.clone_from(stored),
+            (OptionalText(value), Stored::OptionalText(stored)) => value.clone_from(stored),
+            (Number(value, unit), Stored::Number(stored, stored_unit)) if unit == *stored_unit => {
+                *value = *stored
+            }
+            (Count(value), Stored::Count(stored)) => *value = *stored,
+            (OptionalCount(value), Stored::OptionalCount(stored)) => *value = *stored,
+            (SmallCount(value), Stored::SmallCount(stored)) => *value = *stored,
+            (Size(value, range), Stored::Size(stored)) if range.contains(stored) => {
+                *value = *stored
+            }
+            (Percent(value), Stored::Percent(stored)) => *value = *stored,
+            (Fraction(value), Stored::Fraction(stored)) => *value = *stored,
+            (Gain(value), Stored::Gain(stored)) => *value = *stored,
+            (LinearGain(value), Stored::LinearGain(stored)) => *value = *stored,
+            (Hertz(value), Stored::Hertz(stored)) => *value = *stored,
+            (Seconds(value), Stored::Seconds(stored)) => *value = *stored,
+            (Pitch(value), Stored::Pitch(stored)) => *value = *stored,
+            (Choice(value), Stored::Choice(name)) => {
+                if !value.choose(name) {
+                    return Err(misfit(path, stored));
+                }
+            }
+            (Data(value), Stored::Data(stored)) => value.clone_from(stored),
+            _ => return Err(misfit(path, stored)),
+        }
+        Ok(())
+    }
+}
+
+fn misfit(path: &str, stored: &StoredValue) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!(
+            "The value \"{}\" doesn't fit the parameter at {path}",
+            stored.value()
+        ),
+    )
+}
+
+fn invalid_value(path: &str, text: &str, expected: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("The value \"{text}\" for {path} is not {expected}"),
+    )
+}
+
+/// A number in the unit of the parameter. Numbers without a unit are assumed
+/// to already be in that unit.
+fn number(path: &str, text: &str, unit: Unit) -> Result<f32, Error> {
+    match Value::parse(text) {
+        Value::Number {
+            value,
+            unit: parsed_unit,
+        } if parsed_unit == unit || parsed_unit == Unit::Number => Ok(value),
+        _ => Err(invalid_value(path, text, unit.description())),
+    }
+}
+
+fn count(path: &str, text: &str) -> Result<u32, Error> {
+    let value = number(path, text, Unit::Number)?;
+    if value < 0.0 || value.fract() != 0.0 || value > u32::MAX as f32 {
+        return Err(invalid_value(path, text, "a whole number"));
+    }
+    Ok(value as u32)
+}
+
+impl Unit {
+    /// Abbreviation Phase Plant displays after the number.
+    pub fn symbol(&self) -> &'static str {
+        use Unit::*;
+        match self {
+            Number => "",
+            Cents => "ct",
+            Decibels => "dB",
+            Hertz => "Hz",
+            Percent => "%",
+            Seconds => "s",
+            Semitones => "st",
+        }
+    }
+
+    pub(crate) fn description(&self) -> &'static str {
+        use Unit::*;
+        match self {
+            Number => "a number",
+            Cents => "a number of cents",
+            Decibels => "a number of decibels",
+            Hertz => "a frequency",
+            Percent => "a percentage",
+            Seconds => "a time",
+            Semitones => "a number of semitones",
+        }
+    }
+}
+
+impl Value {
+    /// Interpret text as a number with an optional unit, such as `-6 dB`,
+    /// `-inf dB`, `1.5 kHz`, `250 ms` or `25%`, a boolean, or otherwise as text.
+    /// Infinite numbers other than `-inf dB` are text. Kilohertz
+    /// and milliseconds are converted to hertz and seconds.
+    pub fn parse(text: &str) -> Value {
+        let trimmed = text.trim();
+        match trimmed {
+            "true" => return Value::Bool { value: true },
+            "false" => return Value::Bool { value: false },
+            _ => {}
+        }
+
+        // Gains turned all the way down are negative infinity decibels.
+        let unsigned = trimmed.trim_start_matches(['+', '-']);
+        let split = if unsigned
+            .get(..3)
+            .is_some_and(|start| start.eq_ignore_ascii_case("inf"))
+        {
+            trimmed.len() - unsigned.len() + 3
+        } else {
+            trimmed
+                .find(|c: char| !(c.is_ascii_digit() || "+-.".contains(c)))
+                .unwrap_or(trimmed.len())
+        };
+        let (number, suffix) = trimmed.split_at(split);
+        let (scale, unit) = match suffix.trim().to_ascii_lowercase().as_str() {
+            "" => (1.0, Unit::Number),
+            "ct" | "cents" => (1.0, Unit::Cents),
+            "db" => (1.0, Unit::Decibels),
+            "hz" => (1.0, Unit::Hertz),
+            "khz" => (1000.0, Unit::Hertz),
+            "%" => (1.0, Unit::Percent),
+            "s" => (1.0, Unit::Seconds),
+            "ms" => (0.001, Unit::Seconds),
+            "st" | "semitones" => (1.0, Unit::Semitones),
+            _ => (0.0, Unit::Number),
+        };
+        match number.parse::<f32>() {
+            // Only gains can be turned all the way down, and other numbers
+            // that are too large to store are text.
+            Ok(value)
+                if scale != 0.0
+                    && (value.is_finite()
+                        || (value == f32::NEG_INFINITY && unit == Unit::Decibels)) =>
+            {
+                Value::Number {
+                    value: value * scale,
+                    unit,
+                }
+            }
+            _ => Value::Text {
+                value: text.to_owned(),
+            },
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Number {
+                value,
+                unit: Unit::Number,
+            } => write!(f, "{value}"),
+            Value::Number {
+                value,
+                unit: Unit::Percent,
+            } => write!(f, "{value}%"),
+            Value::Number { value, unit } => write!(f, "{value} {}", unit.symbol()),
+            Value::Bool { value } => write!(f, "{value}"),
+            Value::Text { value } => f.write_str(value),
+        }
+    }
+}
+
+impl Preset {
+    /// The value of the parameter at the path, such as
+    /// `lanes.0.snapins.2.mix`, in the unit Phase Plant displays.
+    pub fn get(&self, path: &str) -> Result<Value, Error> {
+        Ok(field(self, path)?.value())
+    }
+
+    /// Change the parameter at the path. The value is parsed with
+    /// [`Value::parse`] and must be in the unit of the parameter, or have no
+    /// unit.
+    ///
+    /// ```
+    /// use synthahol_phase_plant::Preset;
+    ///
+    /// let mut preset = Preset::default();
+    /// preset.set("master_gain", "-6 dB").unwrap();
+    /// preset.set("lanes.1.mix", "25%").unwrap();
+    /// assert_eq!(preset.get("lanes.1.mix").unwrap().to_string(), "25%");
+    /// assert!(preset.set("master_gain", "440 Hz").is_err());
+    /// ```
+    pub fn set(&mut self, path: &str, value: &str) -> Result<(), Error> {
+        let value = self.parse(path, value)?;
+        self.restore(path, &value)
+    }
+
+    /// The value of the parameter at the path as it is stored.
+    pub(crate) fn stored(&self, path: &str) -> Result<StoredValue, Error> {
+        Ok(field(self, path)?.stored())
+    }
+
+    /// The value the text would set the parameter at the path to, without
+    /// changing it.
+    pub(crate) fn parse(&self, path: &str, text: &str) -> Result<StoredValue, Error> {
+        field(self, path)?.parse(path, text)
+    }
+
+    pub(crate) fn restore(&mut self, path: &str, value: &StoredValue) -> Result<(), Error> {
+        field_mut(self, path)?.restore(path, value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use uom::si::frequency::hertz;
+
+    use crate::effect::{Distortion, Filter, FilterMode, TranceGate};
+    use crate::generator::AnalogOscillator;
+    use crate::modulator::{LfoModulator, ModulatorContainer};
+    use crate::test::read_effect_preset;
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Value::parse("-6 dB"),
+            Value::Number {
+                value: -6.0,
+                unit: Unit::Decibels
+            }
+        );
+        assert_eq!(
+            Value::parse("440Hz"),
+            Value::Number {
+                value: 440.0,
+                unit: Unit::Hertz
+            }
+        );
+        assert_eq!(
+            Value::parse("1.5 kHz"),
+            Value::Number {
+                value: 1500.0,
+                unit: Unit::Hertz
+            }
+        );
+        assert_eq!(
+            Value::parse("250 ms"),
+            Value::Number {
+                value: 0.25,
+                unit: Unit::Seconds
+            }
+        );
+        assert_eq!(
+            Value::parse("25%"),
+            Value::Number {
+                value: 25.0,
+                unit: Unit::Percent
+            }
+        );
+        assert_eq!(Value::parse("true"), Value::Bool { value: true });
+        assert_eq!(
+            Value::parse("4 on the floor"),
+            Value::Text {
+                value: "4 on the floor".to_owned()
+            }
+        );
+        assert_eq!(Value::parse("-6 dB").to_string(), "-6 dB");
+        assert_eq!(
+            Value::parse("-inf dB"),
+            Value::Number {
+                value: f32::NEG_INFINITY,
+                unit: Unit::Decibels
+            }
+        );
+        assert_eq!(Value::parse("-inf dB").to_string(), "-inf dB");
+        for text in ["inf%", "-inf", "+inf dB", "NaN", "1e40", &"9".repeat(40)] {
+            assert!(matches!(Value::parse(text), Value::Text { .. }), "{text}");
+        }
+        assert_eq!(
+            Value::parse("information"),
+            Value::Text {
+                value: "information".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn get() {
+        let preset = read_effect_preset("gain", "gain-2.0.16.phaseplant").unwrap();
+        assert_eq!(
+            preset.get("polyphony").unwrap(),
+            Value::Number {
+                value: preset.polyphony as f32,
+                unit: Unit::Number
+            }
+        );
+        assert_eq!(
+            preset.get("lanes.0.snapins.0.enabled").unwrap(),
+            Value::Bool { value: true }
+        );
+        assert_eq!(
+            preset.get("macro_controls.0.name").unwrap(),
+            Value::Text {
+                value: preset.macro_controls[0].name.clone()
+            }
+        );
+
+        // Gain doesn't have a mix.
+        let error = preset.get("lanes.0.snapins.0.mix").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert!(preset.get("lanes.3.mix").is_err());
+        assert!(preset.get("lanes.0.snapins.x.enabled").is_err());
+        assert!(preset.get("unknown").is_err());
+    }
+
+    #[test]
+    fn set() {
+        let mut preset = Preset::default();
+        preset.lanes[0]
+            .snapins
+            .push(Snapin::new(Box::<Distortion>::default(), 1, true, false));
+        preset.lanes[0]
+            .snapins
+            .push(Snapin::new(Box::<Filter>::default(), 2, true, false));
+
+        preset.set("master_gain", "-6 dB").unwrap();
+        assert_relative_eq!(preset.master_gain, Decibels::new(-6.0).linear());
+        preset.set("lanes.0.snapins.0.mix", "25%").unwrap();
+        let distortion: &Distortion = preset.lanes[0].snapins[0].effect.downcast_ref().unwrap();
+        assert_relative_eq!(distortion.mix.get::<percent>(), 25.0);
+        preset.set("glide_time", "250 ms").unwrap();
+        assert_relative_eq!(preset.glide_time, 0.25);
+        preset.set("macro_controls.2.value", "50").unwrap();
+        assert_relative_eq!(preset.macro_controls[2].value, 0.5);
+        preset.set("unison.voices", "4").unwrap();
+        assert_eq!(preset.unison.voices, 4);
+        preset.set("lanes.1.solo", "on").unwrap();
+        assert!(preset.lanes[1].solo);
+        preset.set("metadata.author", "").unwrap();
+        assert_eq!(preset.metadata.author, None);
+
+        assert!(preset.set("master_gain", "440 Hz").is_err());
+        assert!(preset.set("master_gain", "inf dB").is_err());
+        assert!(preset.set("lanes.0.mix", "inf%").is_err());
+        assert!(preset.set("master_pitch", "NaN").is_err());
+        preset.set("master_gain", "-inf dB").unwrap();
+        assert_eq!(preset.master_gain, 0.0);
+        assert!(preset.set("unison.voices", "2.5").is_err());
+        assert!(preset.set("lanes.0.poly_count", "300").is_err());
+        assert!(preset.set("lanes.1.solo", "maybe").is_err());
+        assert!(preset.set("lanes.0.snapins.1.mix", "25%").is_err());
+    }
+
+    #[test]
+    fn effect_generator_and_modulator_fields() {
+        let mut preset = Preset::default();
+        preset.lanes[0]
+            .snapins
+            .push(Snapin::new(Box::<Filter>::default(), 1, true, false));
+        preset.lanes[0]
+            .snapins
+            .push(Snapin::new(Box::<TranceGate>::default(), 2, true, false));
+        preset.generators.push(Box::<AnalogOscillator>::default());
+        preset
+            .modulator_containers
+            .push(ModulatorContainer::new(1, Box::<LfoModulator>::default()));
+
+        preset.set("lanes.0.snapins.0.cutoff", "1.5 kHz").unwrap();
+        preset
+            .set("lanes.0.snapins.0.filter_mode", "high pass")
+            .unwrap();
+        let filter: &Filter = preset.lanes[0].snapins[0].effect.downcast_ref().unwrap();
+        assert_relative_eq!(filter.cutoff.get::<hertz>(), 1500.0);
+        assert_eq!(filter.filter_mode, FilterMode::HighPass);
+        assert_eq!(
+            preset.get("lanes.0.snapins.0.filter_mode").unwrap(),
+            Value::Text {
+                value: "High pass".to_owned()
+            }
+        );
+        let error = preset
+            .set("lanes.0.snapins.0.filter_mode", "sideways")
+            .unwrap_err();
+        assert!(error.to_string().contains("one of Low pass, Band pass"));
+
+        preset
+            .set("lanes.0.snapins.1.patterns.2.steps.40.enabled", "on")
+            .unwrap();
+        preset
+            .set("lanes.0.snapins.1.patterns.2.step_count", "48")
+            .unwrap();
+        let trance_gate: &TranceGate = preset.lanes[0].snapins[1].effect.downcast_ref().unwrap();
+        assert!(trance_gate.patterns[2].steps[40].enabled);
+        assert_eq!(trance_gate.patterns[2].step_count(), 48);
+        assert!(preset
+            .set("lanes.0.snapins.1.patterns.2.step_count", "65")
+            .is_err());
+
+        preset.set("generators.0.unison.voices", "4").unwrap();
+        preset.set("generators.0.tuning", "-7 st").unwrap();
+        let oscillator: &AnalogOscillator = preset.generators[0].downcast_ref().unwrap();
+        assert_eq!(oscillator.unison.voices, 4);
+        assert_relative_eq!(oscillator.tuning, -7.0);
+        assert_eq!(
+            preset.get("generators.0.enabled").unwrap(),
+            Value::Bool { value: true }
+        );
+
+        preset.set("modulators.0.rate.frequency", "4 Hz").unwrap();
+        preset.set("modulators.0.enabled", "false").unwrap();
+        let lfo: &LfoModulator = preset.modulator_containers[0]
+            .modulator
+            .downcast_ref()
+            .unwrap();
+        assert_relative_eq!(lfo.rate.frequency.get::<hertz>(), 4.0);
+        assert!(!preset.modulator_containers[0].enabled);
+
+        assert!(preset.get("generators.0.id").is_err());
+        assert!(preset.get("generators.1.name").is_err());
+        assert!(preset.get("modulators.0.shape.99.x").is_err());
+    }
+}
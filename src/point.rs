@@ -1,7 +1,10 @@
 use std::fmt::{Display, Formatter};
 use std::io::{Error, ErrorKind};
+use std::str::FromStr;
 
-use strum_macros::FromRepr;
+use crate::path::impl_parameters;
+use strum::IntoEnumIterator;
+use strum_macros::{EnumIter, FromRepr};
 
 // TODO: Why are there separate curve points for LFOs and Curves?
 
@@ -15,6 +18,14 @@ pub struct CurvePoint {
     pub curve_y: f32,
 }
 
+impl_parameters!(CurvePoint {
+    mode,
+    x,
+    y,
+    curve_x,
+    curve_y,
+});
+
 impl CurvePoint {
     pub fn new_sharp(x: f32, y: f32, curve_x: f32, curve_y: f32) -> Self {
         Self {
@@ -36,6 +47,21 @@ impl CurvePoint {
         }
     }
 
+    /// A sharp point joined to the next point by a straight line.
+    pub fn sharp(x: f32, y: f32) -> Self {
+        Self::new_sharp(x, y, 1.0, 1.0)
+    }
+
+    /// A smooth point without any bend.
+    pub fn smooth(x: f32, y: f32) -> Self {
+        Self::new_smooth(x, y, 0.0, 0.0)
+    }
+
+    /// Two sharp points that hold the value `y` from `start_x` to `end_x`.
+    pub fn hold(start_x: f32, end_x: f32, y: f32) -> [Self; 2] {
+        [Self::sharp(start_x, y), Self::sharp(end_x, y)]
+    }
+
     pub fn is_sharp(&self) -> bool {
         self.mode == CurvePointMode::Sharp
     }
@@ -43,11 +69,75 @@ impl CurvePoint {
     pub fn is_smooth(&self) -> bool {
         self.mode == CurvePointMode::Smooth
     }
+
+    /// The same point with a different mode. The position and the bend of
+    /// the curve are kept so only the way the point joins its neighbours
+    /// changes.
+    pub fn with_mode(&self, mode: CurvePointMode) -> Self {
+        Self {
+            mode,
+            ..self.clone()
+        }
+    }
+
+    pub fn to_sharp(&self) -> Self {
+        self.with_mode(CurvePointMode::Sharp)
+    }
+
+    pub fn to_smooth(&self) -> Self {
+        self.with_mode(CurvePointMode::Smooth)
+    }
+
+    /// Remove the points that are closer than `tolerance` to the line
+    /// between their neighbours using the Ramer–Douglas–Peucker algorithm.
+    /// The first and last points are always kept.
+    pub fn simplify(points: &[CurvePoint], tolerance: f32) -> Vec<CurvePoint> {
+        if points.len() < 3 {
+            return points.to_vec();
+        }
+        let mut keep = vec![false; points.len()];
+        keep[0] = true;
+        keep[points.len() - 1] = true;
+        let mut ranges = vec![(0, points.len() - 1)];
+        while let Some((start, end)) = ranges.pop() {
+            let farthest = (start + 1..end)
+                .map(|index| {
+                    (
+                        index,
+                        points[index].distance_to_line(&points[start], &points[end]),
+                    )
+                })
+                .max_by(|(_, a), (_, b)| a.total_cmp(b));
+            if let Some((index, distance)) = farthest {
+                if distance > tolerance {
+                    keep[index] = true;
+                    ranges.push((start, index));
+                    ranges.push((index, end));
+                }
+            }
+        }
+        points
+            .iter()
+            .zip(keep)
+            .filter(|(_, keep)| *keep)
+            .map(|(point, _)| point.clone())
+            .collect()
+    }
+
+    /// Perpendicular distance from the line through `start` and `end`.
+    fn distance_to_line(&self, start: &CurvePoint, end: &CurvePoint) -> f32 {
+        let (dx, dy) = (end.x - start.x, end.y - start.y);
+        let length = dx.hypot(dy);
+        if length == 0.0 {
+            return (self.x - start.x).hypot(self.y - start.y);
+        }
+        (dy * (self.x - start.x) - dx * (self.y - start.y)).abs() / length
+    }
 }
 
 /// Ordinals match the file format. The value for 2 for smooth and 3 for sharp
 /// appear to legacy from older versions of Phase Plant.
-#[derive(Clone, Copy, Debug, FromRepr, PartialEq)]
+#[derive(Clone, Copy, Debug, EnumIter, Eq, FromRepr, Hash, PartialEq)]
 #[repr(u32)]
 pub enum CurvePointMode {
     Smooth = 0,
@@ -75,6 +165,27 @@ impl CurvePointMode {
     }
 }
 
+/// Accepts the name of the mode, ignoring case. "Hard" is the same as
+/// [`CurvePointMode::Sharp`].
+impl FromStr for CurvePointMode {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        if text.eq_ignore_ascii_case("hard") {
+            return Ok(CurvePointMode::Sharp);
+        }
+        CurvePointMode::iter()
+            .find(|mode| mode.to_string().eq_ignore_ascii_case(text))
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Unknown curve point mode '{text}'"),
+                )
+            })
+    }
+}
+
 impl Display for CurvePointMode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let msg = match self {
@@ -85,6 +196,8 @@ impl Display for CurvePointMode {
     }
 }
 
+impl_parameters!(CurvePointMode);
+
 /// Returns a string representation of the coordinates of the point. The mode
 /// is not included.
 ///
@@ -115,5 +228,66 @@ mod tests {
         assert!(!CurvePoint::new_smooth(1.0, 2.0, 3.0, 4.0).is_sharp());
         assert!(CurvePoint::new_smooth(1.0, 2.0, 3.0, 4.0).is_smooth());
         assert!(!CurvePoint::new_sharp(1.0, 2.0, 3.0, 4.0).is_smooth());
+        assert_eq!(
+            CurvePoint::sharp(0.5, 1.0),
+            CurvePoint::new_sharp(0.5, 1.0, 1.0, 1.0)
+        );
+        assert_eq!(
+            CurvePoint::smooth(0.5, 1.0),
+            CurvePoint::new_smooth(0.5, 1.0, 0.0, 0.0)
+        );
+        let [start, end] = CurvePoint::hold(0.25, 0.5, -1.0);
+        assert!(start.is_sharp() && end.is_sharp());
+        assert_eq!((start.x, end.x, start.y, end.y), (0.25, 0.5, -1.0, -1.0));
+    }
+
+    #[test]
+    fn conversion() {
+        let point = CurvePoint::new_sharp(1.0, 2.0, 3.0, 4.0);
+        let smooth = point.to_smooth();
+        assert_eq!(smooth, CurvePoint::new_smooth(1.0, 2.0, 3.0, 4.0));
+        assert_eq!(smooth.to_sharp(), point);
+        assert_eq!(point.with_mode(CurvePointMode::Sharp), point);
+    }
+
+    #[test]
+    fn modes() {
+        for mode in CurvePointMode::iter() {
+            assert_eq!(CurvePointMode::from_id(mode as u32).unwrap(), mode);
+            assert_eq!(mode.to_string().parse::<CurvePointMode>().unwrap(), mode);
+        }
+        assert_eq!(CurvePointMode::from_id(2).unwrap(), CurvePointMode::Smooth);
+        assert_eq!(CurvePointMode::from_id(3).unwrap(), CurvePointMode::Sharp);
+        assert!(CurvePointMode::from_id(4).is_err());
+        assert_eq!(
+            " hard ".parse::<CurvePointMode>().unwrap(),
+            CurvePointMode::Sharp
+        );
+        let error = "hold".parse::<CurvePointMode>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn simplify() {
+        let points = [
+            CurvePoint::sharp(0.0, 0.0),
+            CurvePoint::sharp(0.25, 0.26),
+            CurvePoint::smooth(0.5, 0.5),
+            CurvePoint::sharp(0.75, 1.0),
+            CurvePoint::sharp(1.0, 1.0),
+        ];
+        let simplified = CurvePoint::simplify(&points, 0.05);
+        assert_eq!(
+            simplified,
+            [
+                points[0].clone(),
+                points[2].clone(),
+                points[3].clone(),
+                points[4].clone()
+            ]
+        );
+        assert_eq!(CurvePoint::simplify(&points, 0.0).len(), points.len());
+        assert_eq!(CurvePoint::simplify(&points, 10.0).len(), 2);
+        assert_eq!(CurvePoint::simplify(&points[..2], 10.0).len(), 2);
     }
 }
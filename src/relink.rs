@@ -0,0 +1,296 @@
+//! Repair the sample and wavetable paths of presets after the files moved.
+//!
+//! Samples and wavetables that aren't embedded are referenced by a path
+//! relative to the Phase Plant content folder, such as
+//! `user/Drums/Kick.wav`. Moving or reorganizing the folder breaks the
+//! references. [`Preset::relink_samples`] asks a [`SampleResolver`] for the
+//! new location of every broken path. [`SearchRootResolver`] looks for files
+//! with the same name or, when the preset also embeds the contents, the same
+//! contents.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::library::{asset_mut, fnv1a, AssetKind, AssetLocation};
+use crate::Preset;
+
+/// Paths of the factory content start with this. The factory content is
+/// managed by Phase Plant so it is never relinked.
+const FACTORY_PREFIX: &str = "factory/";
+
+/// How a new location was found.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RelinkMethod {
+    /// A file with the same name. The contents could not be compared because
+    /// they aren't embedded in the preset.
+    FileName,
+
+    /// A file with the same contents as the embedded contents.
+    Contents,
+}
+
+pub trait SampleResolver {
+    /// If the file referenced by a preset can be found.
+    fn exists(&self, path: &str) -> bool;
+
+    /// The new path of a file that can't be found. The contents are empty
+    /// unless the preset also embeds them.
+    fn resolve(&mut self, path: &str, contents: &[u8]) -> Option<(String, RelinkMethod)>;
+
+    /// The contents of a file referenced by a preset. Used to
+    /// [verify](Preset::verify_samples) samples that aren't embedded.
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("The resolver can't read {path}"),
+        ))
+    }
+}
+
+/// Find moved files in a set of folders. The paths of the files that are
+/// found are relative to the content folder, or absolute if they are outside
+/// of it.
+#[derive(Debug)]
+pub struct SearchRootResolver {
+    content_dir: PathBuf,
+    files: Vec<PathBuf>,
+    by_name: HashMap<OsString, Vec<usize>>,
+}
+
+impl SearchRootResolver {
+    /// Index every file in the search roots.
+    pub fn new<P: AsRef<Path>>(content_dir: P, search_roots: &[PathBuf]) -> io::Result<Self> {
+        let mut files = Vec::new();
+        let mut pending = search_roots.to_vec();
+        while let Some(dir) = pending.pop() {
+            for dir_entry in dir.read_dir()? {
+                let path = dir_entry?.path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else {
+                    files.push(path);
+                }
+            }
+        }
+        files.sort();
+        files.dedup();
+
+        let mut by_name: HashMap<OsString, Vec<usize>> = HashMap::new();
+        for (index, file) in files.iter().enumerate() {
+            if let Some(name) = file.file_name() {
+                by_name.entry(name.to_owned()).or_default().push(index);
+            }
+        }
+        Ok(Self {
+            content_dir: content_dir.as_ref().to_path_buf(),
+            files,
+            by_name,
+        })
+    }
+
+    fn preset_path(&self, file: &Path) -> String {
+        match file.strip_prefix(&self.content_dir) {
+            Ok(relative) => relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            Err(_) => file.to_string_lossy().into_owned(),
+        }
+    }
+
+    fn has_contents(file: &Path, contents: &[u8], hash: u64) -> bool {
+        let same_length = fs::metadata(file)
+            .map(|metadata| metadata.len() == contents.len() as u64)
+            .unwrap_or(false);
+        same_length && fs::read(file).is_ok_and(|bytes| fnv1a(&bytes) == hash)
+    }
+}
+
+impl SampleResolver for SearchRootResolver {
+    fn exists(&self, path: &str) -> bool {
+        // Joining an absolute path replaces the content folder.
+        self.content_dir.join(path).is_file()
+    }
+
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        fs::read(self.content_dir.join(path))
+    }
+
+    fn resolve(&mut self, path: &str, contents: &[u8]) -> Option<(String, RelinkMethod)> {
+        let same_name: &[usize] = Path::new(path)
+            .file_name()
+            .and_then(|name| self.by_name.get(name))
+            .map_or(&[], Vec::as_slice);
+        if contents.is_empty() {
+            let file = &self.files[*same_name.first()?];
+            return Some((self.preset_path(file), RelinkMethod::FileName));
+        }
+
+        // Files with the same name are checked first since they are the most
+        // likely to match.
+        let hash = fnv1a(contents);
+        let file = same_name
+            .iter()
+            .map(|index| &self.files[*index])
+            .chain(&self.files)
+            .find(|file| Self::has_contents(file, contents, hash))?;
+        Some((self.preset_path(file), RelinkMethod::Contents))
+    }
+}
+
+/// A path that was changed by [`Preset::relink_samples`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Relinked {
+    pub location: AssetLocation,
+    pub kind: AssetKind,
+    pub old_path: String,
+    pub new_path: String,
+    pub method: RelinkMethod,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RelinkReport {
+    pub relinked: Vec<Relinked>,
+
+    /// Broken paths that the resolver couldn't find.
+    pub unresolved: Vec<(AssetLocation, String)>,
+}
+
+impl Preset {
+    /// Replace the sample and wavetable paths that the resolver can't find
+    /// with their new locations. Factory content is skipped.
+    pub fn relink_samples<R: SampleResolver>(&mut self, resolver: &mut R) -> RelinkReport {
+        let mut report = RelinkReport::default();
+        for (location, kind) in asset_locations(self) {
+            let Some((contents, path)) = asset_mut(self, location) else {
+                continue;
+            };
+            let Some(old_path) = path.clone() else {
+                continue;
+            };
+            if old_path.is_empty()
+                || old_path.starts_with(FACTORY_PREFIX)
+                || resolver.exists(&old_path)
+            {
+                continue;
+            }
+            match resolver.resolve(&old_path, contents) {
+                Some((new_path, method)) => {
+                    *path = Some(new_path.clone());
+                    report.relinked.push(Relinked {
+                        location,
+                        kind,
+                        old_path,
+                        new_path,
+                        method,
+                    });
+                }
+                None => report.unresolved.push((location, old_path)),
+            }
+        }
+        report
+    }
+}
+
+/// Every generator and modulator that references a sample or wavetable.
+pub(crate) fn asset_locations(preset: &Preset) -> Vec<(AssetLocation, AssetKind)> {
+    let generators = preset
+        .generators
+        .iter()
+        .enumerate()
+        .filter_map(|(index, generator)| {
+            let kind = if generator.as_sampler().is_some() || generator.as_granular().is_some() {
+                AssetKind::Sample
+            } else if generator.as_wavetable().is_some() {
+                AssetKind::Wavetable
+            } else {
+                return None;
+            };
+            Some((AssetLocation::Generator(index), kind))
+        });
+    let modulators = preset
+        .modulator_containers
+        .iter()
+        .enumerate()
+        .filter(|(_, container)| container.modulator.as_lfo_table().is_some())
+        .map(|(index, _)| (AssetLocation::Modulator(index), AssetKind::Wavetable));
+    generators.chain(modulators).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::generator::{GranularGenerator, SamplePlayer};
+
+    use super::*;
+
+    #[test]
+    fn relink_samples() {
+        let dir = std::env::temp_dir().join(format!("relink_samples-{}", std::process::id()));
+        let moved = dir.join("user").join("Moved");
+        fs::create_dir_all(&moved).unwrap();
+        fs::write(moved.join("kick.wav"), b"kick").unwrap();
+        fs::write(moved.join("renamed.wav"), b"snare").unwrap();
+
+        let mut preset = Preset::default();
+        preset.generators.push(Box::new(SamplePlayer {
+            sample_path: Some("user/kick.wav".to_owned()),
+            ..Default::default()
+        }));
+        preset.generators.push(Box::new(GranularGenerator {
+            sample_path: Some("user/snare.wav".to_owned()),
+            sample_contents: b"snare".to_vec(),
+            ..Default::default()
+        }));
+        preset.generators.push(Box::new(SamplePlayer {
+            sample_path: Some("user/missing.wav".to_owned()),
+            ..Default::default()
+        }));
+        preset.generators.push(Box::new(SamplePlayer {
+            sample_path: Some("factory/Drums/kick.wav".to_owned()),
+            ..Default::default()
+        }));
+        preset.generators.push(Box::new(SamplePlayer {
+            sample_path: Some("user/Moved/kick.wav".to_owned()),
+            ..Default::default()
+        }));
+
+        let mut resolver = SearchRootResolver::new(&dir, &[dir.join("user")]).unwrap();
+        let report = preset.relink_samples(&mut resolver);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            report.relinked,
+            [
+                Relinked {
+                    location: AssetLocation::Generator(0),
+                    kind: AssetKind::Sample,
+                    old_path: "user/kick.wav".to_owned(),
+                    new_path: "user/Moved/kick.wav".to_owned(),
+                    method: RelinkMethod::FileName,
+                },
+                Relinked {
+                    location: AssetLocation::Generator(1),
+                    kind: AssetKind::Sample,
+                    old_path: "user/snare.wav".to_owned(),
+                    new_path: "user/Moved/renamed.wav".to_owned(),
+                    method: RelinkMethod::Contents,
+                }
+            ]
+        );
+        assert_eq!(
+            report.unresolved,
+            [(AssetLocation::Generator(2), "user/missing.wav".to_owned())]
+        );
+        let sampler: &SamplePlayer = preset.generator(0).unwrap();
+        assert_eq!(sampler.sample_path.as_deref(), Some("user/Moved/kick.wav"));
+        let sampler: &SamplePlayer = preset.generator(3).unwrap();
+        assert_eq!(
+            sampler.sample_path.as_deref(),
+            Some("factory/Drums/kick.wav")
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Find what a modulation targets.
+//!
+//! A [`ModulationTarget`] refers to generators, modulators, lanes and snapins
+//! by their identifier or position. Nothing stops the object from being removed while
+//! the modulation is kept, which leaves the modulation doing nothing.
+//! [`Preset::resolve_target`] finds the object and
+//! [`Preset::check_modulations`] reports the modulations whose target is
+//! gone.
+
+use std::fmt::{Display, Formatter};
+
+use crate::generator::Generator;
+use crate::modulation::{HostParameter, ModulationTarget};
+use crate::modulator::ModulatorContainer;
+use crate::*;
+
+/// The object in a preset a modulation target refers to.
+#[derive(Clone, Copy, Debug)]
+pub enum Resolved<'a> {
+    /// A setting of the preset itself, such as the master gain or unison.
+    Preset,
+
+    Generator(&'a dyn Generator),
+    Modulator(&'a ModulatorContainer),
+    MacroControl(&'a MacroControl),
+
+    Lane {
+        index: usize,
+        lane: &'a Lane,
+    },
+
+    /// Snapin targets don't say which lane the snapin is in so the first
+    /// lane with a snapin of the ID is used.
+    Snapin {
+        lane: usize,
+        snapin: &'a Snapin,
+    },
+
+    /// The target isn't understood well enough to know what it refers to.
+    /// Where generators and modulators are in the host parameters is only
+    /// partly known so generators and modulators that can't be found are
+    /// unknown rather than missing.
+    Unknown,
+
+    NotFound(NotFound),
+}
+
+/// Why the object a modulation target refers to couldn't be found.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NotFound {
+    MacroControl(MacroControlId),
+    Lane(LaneId),
+    Snapin(SnapinId),
+}
+
+impl Display for NotFound {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotFound::MacroControl(id) => write!(f, "There is no macro control {id}"),
+            NotFound::Lane(id) => write!(f, "There is no lane {id}"),
+            NotFound::Snapin(id) => write!(f, "There is no snapin with ID {id}"),
+        }
+    }
+}
+
+impl Preset {
+    pub fn resolve_target(&self, target: &ModulationTarget) -> Resolved<'_> {
+        match target {
+            ModulationTarget::Host { parameter, .. } => self.resolve_host_parameter(parameter),
+            ModulationTarget::Snapin { snapin_id, .. } => self
+                .lanes
+                .iter()
+                .enumerate()
+                .find_map(|(lane, snapins)| {
+                    let snapin = snapins
+                        .snapins
+                        .iter()
+                        .find(|snapin| snapin.id == *snapin_id)?;
+                    Some(Resolved::Snapin { lane, snapin })
+                })
+                .unwrap_or(Resolved::NotFound(NotFound::Snapin(*snapin_id))),
+            ModulationTarget::Modulation { .. } | ModulationTarget::Unknown { .. } => {
+                Resolved::Unknown
+            }
+        }
+    }
+
+    fn resolve_host_parameter(&self, parameter: &HostParameter) -> Resolved<'_> {
+        use HostParameter::*;
+        match parameter {
+            GlideTime | MasterGain | UnisonBias | UnisonBlend | UnisonDetune | UnisonSpread => {
+                Resolved::Preset
+            }
+            Generator { generator_id, .. } => self
+                .generators
+                .iter()
+                .find(|generator| generator.id() == Some(*generator_id))
+                .map(|generator| Resolved::Generator(generator.as_ref()))
+                .unwrap_or(Resolved::Unknown),
+            Modulator { modulator_id, .. } => self
+                .modulator_containers
+                .iter()
+                .find(|container| container.id == *modulator_id)
+                .map(Resolved::Modulator)
+                .unwrap_or(Resolved::Unknown),
+            MacroControl(id) => self
+                .macro_controls
+                .get(*id as usize)
+                .map(Resolved::MacroControl)
+                .unwrap_or(Resolved::NotFound(NotFound::MacroControl(*id))),
+            LaneGain(id) | LaneMix(id) => self
+                .lanes
+                .get(*id as usize)
+                .map(|lane| Resolved::Lane {
+                    index: *id as usize,
+                    lane,
+                })
+                .unwrap_or(Resolved::NotFound(NotFound::Lane(*id))),
+            Unknown { .. } => Resolved::Unknown,
+        }
+    }
+
+    /// Index of each modulation whose target can't be found and why.
+    /// Targets that aren't understood aren't reported.
+    pub fn check_modulations(&self) -> Vec<(usize, NotFound)> {
+        self.modulations
+            .iter()
+            .enumerate()
+            .filter_map(
+                |(index, modulation)| match self.resolve_target(&modulation.target) {
+                    Resolved::NotFound(reason) => Some((index, reason)),
+                    _ => None,
+                },
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::modulation::{Modulation, ModulationSource, RateMode};
+    use crate::test::read_preset;
+
+    use super::*;
+
+    #[test]
+    fn resolve_target() {
+        let mut preset = read_preset("macros", "macro-5-haasdelay50and75-1.8.14.phaseplant");
+        assert!(preset.check_modulations().is_empty());
+        let target = preset.modulations[0].target.clone();
+        assert!(matches!(
+            preset.resolve_target(&target),
+            Resolved::Snapin { lane: 0, snapin } if snapin.id == 1
+        ));
+
+        // Removing the snapin leaves the modulation without a target.
+        preset.lanes[0].snapins.retain(|snapin| snapin.id != 1);
+        assert_eq!(preset.check_modulations(), [(0, NotFound::Snapin(1))]);
+
+        let modulation = |parameter| {
+            Modulation::new(
+                ModulationSource::ModWheel,
+                ModulationTarget::Host {
+                    parameter,
+                    rate_mode: RateMode::Control,
+                },
+                Default::default(),
+            )
+        };
+        let preset = Preset {
+            modulations: vec![
+                modulation(HostParameter::MasterGain),
+                modulation(HostParameter::LaneMix(2)),
+                modulation(HostParameter::LaneGain(3)),
+                modulation(HostParameter::Generator {
+                    generator_id: 1,
+                    parameter_id: 0,
+                }),
+                modulation(HostParameter::Unknown {
+                    target_id: 0,
+                    reason: None,
+                }),
+            ],
+            ..Default::default()
+        };
+        assert!(matches!(
+            preset.resolve_target(&preset.modulations[1].target),
+            Resolved::Lane { index: 2, .. }
+        ));
+        assert_eq!(preset.check_modulations(), [(2, NotFound::Lane(3))]);
+        assert_eq!(NotFound::Lane(3).to_string(), "There is no lane 3");
+    }
+}
@@ -0,0 +1,312 @@
+//! Check where the output generators send their audio.
+//!
+//! The [Envelope Output](generator::EnvelopeOutput) and
+//! [Curve Output](generator::CurveOutput) generators send the sound of a
+//! generator group to a lane, to the master output or to the sideband. A
+//! preset whose outputs only reach lanes that are disabled or muted makes no
+//! sound, which is easy to miss when presets are edited in bulk.
+
+use std::fmt::{Display, Formatter};
+use std::io::{Error, ErrorKind};
+
+use log::warn;
+
+use crate::generator::{Generator, GeneratorId, OutputDestination};
+use crate::*;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RoutingIssue {
+    /// An output generator sends to a lane the preset doesn't have.
+    MissingLane {
+        generator_id: GeneratorId,
+        destination: OutputDestination,
+    },
+
+    /// An output generator sends to a lane that is disabled, muted or
+    /// silenced by another lane being solo. The lane may also send to
+    /// another lane that is silent.
+    SilentLane {
+        generator_id: GeneratorId,
+        destination: OutputDestination,
+    },
+
+    /// None of the enabled output generators reach the master output.
+    NoAudibleOutput,
+}
+
+impl Display for RoutingIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RoutingIssue::MissingLane {
+                generator_id,
+                destination,
+            } => write!(
+                f,
+                "Generator {generator_id} outputs to {destination} which does not exist"
+            ),
+            RoutingIssue::SilentLane {
+                generator_id,
+                destination,
+            } => write!(
+                f,
+                "Generator {generator_id} outputs to {destination} which is not audible"
+            ),
+            RoutingIssue::NoAudibleOutput => f.write_str("No output reaches the master output"),
+        }
+    }
+}
+
+/// The routing of an output generator.
+struct Output {
+    id: GeneratorId,
+    audible: bool,
+    destination: OutputDestination,
+}
+
+impl Output {
+    fn of(generator: &dyn Generator) -> Option<Self> {
+        if let Some(output) = generator.as_envelope_output() {
+            Some(Self {
+                id: output.id,
+                audible: output.enabled && output.output_enabled,
+                destination: output.destination,
+            })
+        } else {
+            generator.as_curve_output().map(|output| Self {
+                id: output.id,
+                audible: output.enabled && output.output_enabled,
+                destination: output.destination,
+            })
+        }
+    }
+}
+
+impl Preset {
+    /// Whether the lane with the given index reaches the master output. The
+    /// lane and any lanes it sends to must be enabled and not muted. If any
+    /// lane is solo then only the solo lanes are heard, although they are
+    /// still processed by the lanes they send to.
+    pub fn is_lane_audible(&self, index: usize) -> bool {
+        if self.lanes.iter().any(|lane| lane.solo)
+            && !self.lanes.get(index).is_some_and(|lane| lane.solo)
+        {
+            return false;
+        }
+        let mut index = index;
+
+        // Lanes can only send to other lanes so a longer chain is a loop.
+        for _ in 0..self.lanes.len() {
+            let Some(lane) = self.lanes.get(index) else {
+                return false;
+            };
+            if !lane.enabled || lane.mute {
+                return false;
+            }
+            match lane.destination {
+                LaneDestination::Master => return true,
+                LaneDestination::Sideband => return false,
+                destination => {
+                    index = destination.lane_index().expect("lane destination");
+                }
+            }
+        }
+        false
+    }
+
+    /// Output generators that send to missing or silent lanes, and whether
+    /// any output reaches the master output. A warning is logged if the
+    /// preset makes no sound.
+    pub fn check_routing(&self) -> Vec<RoutingIssue> {
+        let mut issues = Vec::new();
+        let mut audible = false;
+        for output in self
+            .generators
+            .iter()
+            .filter_map(|generator| Output::of(generator.as_ref()))
+        {
+            let reaches_master = match output.destination.lane_index() {
+                Some(index) if index >= self.lanes.len() => {
+                    issues.push(RoutingIssue::MissingLane {
+                        generator_id: output.id,
+                        destination: output.destination,
+                    });
+                    false
+                }
+                Some(index) if !self.is_lane_audible(index) => {
+                    if output.audible {
+                        issues.push(RoutingIssue::SilentLane {
+                            generator_id: output.id,
+                            destination: output.destination,
+                        });
+                    }
+                    false
+                }
+                Some(_) => true,
+                None => output.destination == OutputDestination::Master,
+            };
+            audible |= output.audible && reaches_master;
+        }
+        if !audible {
+            let name = self.metadata.name.as_deref().unwrap_or("Unnamed");
+            warn!("Preset {name} has no audible output");
+            issues.push(RoutingIssue::NoAudibleOutput);
+        }
+        issues
+    }
+
+    /// Send every output generator with the `from` destination to the `to`
+    /// destination. Returns how many generators were changed.
+    pub fn retarget_outputs(
+        &mut self,
+        from: OutputDestination,
+        to: OutputDestination,
+    ) -> Result<usize, Error> {
+        self.check_destination(to)?;
+        let mut count = 0;
+        for generator in &mut self.generators {
+            let matches =
+                Output::of(generator.as_ref()).is_some_and(|output| output.destination == from);
+            if matches {
+                generator.set_output_destination(to)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Send the output generator with the given ID to a destination.
+    pub fn retarget_output(
+        &mut self,
+        generator_id: GeneratorId,
+        to: OutputDestination,
+    ) -> Result<(), Error> {
+        self.check_destination(to)?;
+        self.generators
+            .iter_mut()
+            .find(|generator| generator.id() == Some(generator_id))
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("There is no generator {generator_id}"),
+                )
+            })?
+            .set_output_destination(to)
+    }
+
+    fn check_destination(&self, destination: OutputDestination) -> Result<(), Error> {
+        match destination.lane_index() {
+            Some(index) if index >= self.lanes.len() => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("The preset does not have {destination}"),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::generator::EnvelopeOutput;
+    use crate::test::read_preset;
+
+    use super::*;
+
+    #[test]
+    fn audible() {
+        let preset = read_preset("generators", "generators-all-1.8.13.phaseplant");
+        assert!(preset.check_routing().is_empty());
+        assert!(preset.is_lane_audible(0));
+
+        let mut muted = preset.clone();
+        muted.lanes[0].mute = true;
+        assert!(!muted.is_lane_audible(0));
+        let output_id = preset.generators[9].id().unwrap();
+        assert_eq!(
+            muted.check_routing(),
+            [
+                RoutingIssue::SilentLane {
+                    generator_id: output_id,
+                    destination: OutputDestination::Lane1,
+                },
+                RoutingIssue::NoAudibleOutput,
+            ]
+        );
+
+        // Another lane being solo silences the first.
+        let mut solo = preset.clone();
+        solo.lanes[1].solo = true;
+        assert!(!solo.is_lane_audible(0));
+        assert!(solo.is_lane_audible(1));
+
+        // Lane 1 sends through lanes 2 and 3 to the master.
+        let mut chained = preset.clone();
+        assert_eq!(chained.lanes[0].destination, LaneDestination::Lane2);
+        chained.lanes[2].enabled = false;
+        assert!(!chained.is_lane_audible(0));
+
+        // Lanes that send to each other never reach the master.
+        let mut looped = preset;
+        looped.lanes[1].destination = LaneDestination::Lane1;
+        assert!(!looped.is_lane_audible(0));
+
+        assert_eq!(
+            Preset::default().check_routing(),
+            [RoutingIssue::NoAudibleOutput]
+        );
+    }
+
+    #[test]
+    fn missing_lane() {
+        let mut preset = read_preset("generators", "generators-all-1.8.13.phaseplant");
+        preset.lanes.truncate(1);
+        let output_id = preset.generators[9].id().unwrap();
+        preset.generators[9]
+            .set_output_destination(OutputDestination::Lane3)
+            .unwrap();
+        let issues = preset.check_routing();
+        assert_eq!(
+            issues[0],
+            RoutingIssue::MissingLane {
+                generator_id: output_id,
+                destination: OutputDestination::Lane3,
+            }
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            format!("Generator {output_id} outputs to Lane 3 which does not exist")
+        );
+        let error = preset
+            .retarget_output(output_id, OutputDestination::Lane2)
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn retarget() {
+        let mut preset = read_preset("generators", "generators-all-1.8.13.phaseplant");
+        assert_eq!(
+            preset
+                .retarget_outputs(OutputDestination::Lane1, OutputDestination::Master)
+                .unwrap(),
+            1
+        );
+        let output: &EnvelopeOutput = preset.generator(9).unwrap();
+        assert_eq!(output.destination, OutputDestination::Master);
+
+        let output_id = output.id;
+        preset
+            .retarget_output(output_id, OutputDestination::Lane2)
+            .unwrap();
+        let output: &EnvelopeOutput = preset.generator(9).unwrap();
+        assert_eq!(output.destination, OutputDestination::Lane2);
+
+        // The group is not an output generator.
+        let group_id = preset.generators[0].id().unwrap();
+        assert!(preset
+            .retarget_output(group_id, OutputDestination::Lane1)
+            .is_err());
+        assert!(preset
+            .retarget_output(9999, OutputDestination::Lane1)
+            .is_err());
+    }
+}
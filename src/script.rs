@@ -0,0 +1,453 @@
+//! Change presets with short scripts of assignments.
+//!
+//! Each statement assigns the result of an expression to a
+//! [parameter path](Preset::set). Statements are separated by new lines or
+//! semicolons and `#` starts a comment, except in quoted text. Expressions can use numbers with
+//! units, quoted text, `true` and `false`, the current value of other
+//! parameters, parentheses and `+`, `-`, `*` and `/`. The compound assignments
+//! `+=`, `-=`, `*=` and `/=` change a parameter relative to its current value.
+//!
+//! ```text
+//! # Leave some headroom
+//! master_gain -= 3 dB
+//! lanes.0.snapins.1.mix = 35%
+//! macro_controls.1.value = macro_controls.0.value / 2
+//! metadata.author = "Sound Design Team"
+//! ```
+//!
+//! Numbers without a unit take the unit of the number they are combined with.
+//! Adding or subtracting numbers with different units is an error, except that
+//! multiplying a number with a unit by a percentage scales it.
+
+use std::fmt::{Display, Formatter};
+use std::io::{Error, ErrorKind};
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::diff::{Unit, Value};
+use crate::*;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Value(Value),
+    Identifier(String),
+    Symbol(char),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Value(Value::Text { value }) => write!(f, "\"{value}\""),
+            Token::Value(value) => write!(f, "{value}"),
+            Token::Identifier(identifier) => f.write_str(identifier),
+            Token::Symbol(symbol) => write!(f, "{symbol}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expression {
+    Literal(Value),
+    Parameter(String),
+    Negate(Box<Expression>),
+    Binary(Box<Expression>, char, Box<Expression>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Statement {
+    line: usize,
+    path: String,
+    expression: Expression,
+}
+
+fn script_error(line: usize, message: impl AsRef<str>) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("Line {line}: {}", message.as_ref()),
+    )
+}
+
+/// Split a line into tokens. Semicolons become symbols so statements can be
+/// separated after the line is tokenized, and `#` ends the line unless it is
+/// in quoted text.
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '#' {
+            break;
+        } else if c.is_ascii_digit() || c == '.' {
+            tokens.push(Token::Value(number(&mut chars)?));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let identifier = take_while(&mut chars, |c| {
+                c.is_ascii_alphanumeric() || c == '_' || c == '.'
+            });
+            tokens.push(match identifier.as_str() {
+                "true" => Token::Value(Value::Bool { value: true }),
+                "false" => Token::Value(Value::Bool { value: false }),
+                _ => Token::Identifier(identifier),
+            });
+        } else if c == '"' {
+            chars.next();
+            let value = take_while(&mut chars, |c| c != '"');
+            if chars.next() != Some('"') {
+                return Err("Text is missing the closing quote".to_owned());
+            }
+            tokens.push(Token::Value(Value::Text { value }));
+        } else if "+-*/=();".contains(c) {
+            chars.next();
+            tokens.push(Token::Symbol(c));
+        } else {
+            return Err(format!("Unexpected character '{c}'"));
+        }
+    }
+    Ok(tokens)
+}
+
+fn take_while(chars: &mut Peekable<Chars>, predicate: impl Fn(char) -> bool) -> String {
+    let mut taken = String::new();
+    while let Some(&c) = chars.peek().filter(|c| predicate(**c)) {
+        taken.push(c);
+        chars.next();
+    }
+    taken
+}
+
+/// A number and the unit that follows it, if any.
+fn number(chars: &mut Peekable<Chars>) -> Result<Value, String> {
+    let digits = take_while(chars, |c| c.is_ascii_digit() || c == '.');
+    let mut lookahead = chars.clone();
+    take_while(&mut lookahead, char::is_whitespace);
+    let unit = if lookahead.peek() == Some(&'%') {
+        "%".to_owned()
+    } else {
+        take_while(&mut lookahead, |c| c.is_ascii_alphabetic())
+    };
+    if !unit.is_empty() {
+        if let number @ Value::Number { .. } = Value::parse(&format!("{digits} {unit}")) {
+            take_while(chars, char::is_whitespace);
+            for _ in 0..unit.len() {
+                chars.next();
+            }
+            return Ok(number);
+        }
+    }
+    match Value::parse(&digits) {
+        number @ Value::Number { .. } => Ok(number),
+        _ => Err(format!("{digits} is not a number")),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn next_symbol_in(&mut self, symbols: &str) -> Option<char> {
+        match self.peek() {
+            Some(Token::Symbol(symbol)) if symbols.contains(*symbol) => {
+                let symbol = *symbol;
+                self.position += 1;
+                Some(symbol)
+            }
+            _ => None,
+        }
+    }
+
+    fn statement(&mut self, line: usize) -> Result<Statement, String> {
+        let path = match self.next() {
+            Some(Token::Identifier(path)) => path,
+            _ => return Err("Expected a parameter path".to_owned()),
+        };
+        let operator = self.next_symbol_in("+-*/");
+        if self.next_symbol_in("=").is_none() {
+            return Err(format!("Expected = after {path}"));
+        }
+        let mut expression = self.expression()?;
+        if let Some(token) = self.peek() {
+            return Err(format!("Unexpected {token} after the expression"));
+        }
+        if let Some(operator) = operator {
+            expression = Expression::Binary(
+                Box::new(Expression::Parameter(path.clone())),
+                operator,
+                Box::new(expression),
+            );
+        }
+        Ok(Statement {
+            line,
+            path,
+            expression,
+        })
+    }
+
+    fn expression(&mut self) -> Result<Expression, String> {
+        let mut expression = self.term()?;
+        while let Some(operator) = self.next_symbol_in("+-") {
+            expression = Expression::Binary(Box::new(expression), operator, Box::new(self.term()?));
+        }
+        Ok(expression)
+    }
+
+    fn term(&mut self) -> Result<Expression, String> {
+        let mut expression = self.unary()?;
+        while let Some(operator) = self.next_symbol_in("*/") {
+            expression =
+                Expression::Binary(Box::new(expression), operator, Box::new(self.unary()?));
+        }
+        Ok(expression)
+    }
+
+    fn unary(&mut self) -> Result<Expression, String> {
+        if self.next_symbol_in("-").is_some() {
+            return Ok(Expression::Negate(Box::new(self.unary()?)));
+        }
+        match self.next() {
+            Some(Token::Value(value)) => Ok(Expression::Literal(value)),
+            Some(Token::Identifier(path)) => Ok(Expression::Parameter(path)),
+            Some(Token::Symbol('(')) => {
+                let expression = self.expression()?;
+                match self.next() {
+                    Some(Token::Symbol(')')) => Ok(expression),
+                    _ => Err("Expected )".to_owned()),
+                }
+            }
+            Some(token) => Err(format!("Unexpected {token}")),
+            None => Err("Expected a value".to_owned()),
+        }
+    }
+}
+
+fn parse(script: &str) -> Result<Vec<Statement>, Error> {
+    let mut statements = Vec::new();
+    for (index, line) in script.lines().enumerate() {
+        let line_number = index + 1;
+        let tokens = tokenize(line).map_err(|message| script_error(line_number, message))?;
+        for tokens in tokens
+            .split(|token| *token == Token::Symbol(';'))
+            .filter(|tokens| !tokens.is_empty())
+        {
+            let mut parser = Parser {
+                tokens: tokens.to_vec(),
+                position: 0,
+            };
+            statements.push(
+                parser
+                    .statement(line_number)
+                    .map_err(|message| script_error(line_number, message))?,
+            );
+        }
+    }
+    Ok(statements)
+}
+
+fn evaluate(preset: &Preset, expression: &Expression) -> Result<Value, String> {
+    match expression {
+        Expression::Literal(value) => Ok(value.clone()),
+        Expression::Parameter(path) => preset.get(path).map_err(|error| error.to_string()),
+        Expression::Negate(expression) => match evaluate(preset, expression)? {
+            Value::Number { value, unit } => Ok(Value::Number {
+                value: -value,
+                unit,
+            }),
+            value => Err(format!("Only numbers can be negated, not {value}")),
+        },
+        Expression::Binary(left, operator, right) => {
+            arithmetic(evaluate(preset, left)?, *operator, evaluate(preset, right)?)
+        }
+    }
+}
+
+fn arithmetic(left: Value, operator: char, right: Value) -> Result<Value, String> {
+    let (
+        Value::Number {
+            value: a,
+            unit: a_unit,
+        },
+        Value::Number {
+            value: b,
+            unit: b_unit,
+        },
+    ) = (&left, &right)
+    else {
+        return Err(format!(
+            "Only numbers can be used with {operator}, not {left} and {right}"
+        ));
+    };
+    let (a, b, a_unit, b_unit) = (*a, *b, *a_unit, *b_unit);
+    let mismatch = || {
+        format!(
+            "Can't use {operator} with {} and {}",
+            a_unit.description(),
+            b_unit.description()
+        )
+    };
+    let (value, unit) = match operator {
+        '+' | '-' => {
+            let unit = match (a_unit, b_unit) {
+                (a_unit, b_unit) if a_unit == b_unit => a_unit,
+                (unit, Unit::Number) | (Unit::Number, unit) => unit,
+                _ => return Err(mismatch()),
+            };
+            (if operator == '+' { a + b } else { a - b }, unit)
+        }
+        '*' => match (a_unit, b_unit) {
+            (unit, Unit::Number) | (Unit::Number, unit) => (a * b, unit),
+            (unit, Unit::Percent) => (a * b / 100.0, unit),
+            (Unit::Percent, unit) => (a * b / 100.0, unit),
+            _ => return Err(mismatch()),
+        },
+        '/' => {
+            if b == 0.0 {
+                return Err("Division by zero".to_owned());
+            }
+            match (a_unit, b_unit) {
+                (unit, Unit::Number) => (a / b, unit),
+                (a_unit, b_unit) if a_unit == b_unit => (a / b, Unit::Number),
+                _ => return Err(mismatch()),
+            }
+        }
+        _ => unreachable!("operators are limited by the parser"),
+    };
+    Ok(Value::Number { value, unit })
+}
+
+impl Preset {
+    /// Run a script of assignments to parameter paths. The whole script is
+    /// checked for syntax errors before anything is changed. Statements are
+    /// then applied in order, so an error partway through, such as a path the
+    /// preset doesn't have, leaves the earlier statements applied.
+    ///
+    /// ```
+    /// use synthahol_phase_plant::Preset;
+    ///
+    /// let mut preset = Preset::default();
+    /// preset
+    ///     .apply_script("lanes.0.gain = -6 dB\nlanes.1.gain = lanes.0.gain + 3 dB")
+    ///     .unwrap();
+    /// assert_eq!(preset.get("lanes.1.gain").unwrap().to_string(), "-3 dB");
+    /// ```
+    pub fn apply_script(&mut self, script: &str) -> Result<(), Error> {
+        for statement in parse(script)? {
+            let value = evaluate(self, &statement.expression)
+                .map_err(|message| script_error(statement.line, message))?;
+            self.set(&statement.path, &value.to_string())
+                .map_err(|error| script_error(statement.line, error.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use uom::si::f32::Ratio;
+    use uom::si::ratio::percent;
+
+    use super::*;
+
+    #[test]
+    fn tokens() {
+        assert_eq!(
+            tokenize("lanes.0.mix *= 50 %").unwrap(),
+            vec![
+                Token::Identifier("lanes.0.mix".to_owned()),
+                Token::Symbol('*'),
+                Token::Symbol('='),
+                Token::Value(Value::Number {
+                    value: 50.0,
+                    unit: Unit::Percent
+                }),
+            ]
+        );
+        assert_eq!(
+            tokenize("1.5kHz").unwrap(),
+            vec![Token::Value(Value::Number {
+                value: 1500.0,
+                unit: Unit::Hertz
+            })]
+        );
+        assert_eq!(
+            tokenize("a = \"#1; b\" # Comment").unwrap(),
+            vec![
+                Token::Identifier("a".to_owned()),
+                Token::Symbol('='),
+                Token::Value(Value::Text {
+                    value: "#1; b".to_owned()
+                }),
+            ]
+        );
+        assert!(tokenize("\"unterminated").is_err());
+        assert!(tokenize("a = $").is_err());
+    }
+
+    #[test]
+    fn apply_script() {
+        let mut preset = Preset::default();
+        preset.lanes[0].mix = Ratio::new::<percent>(80.0);
+        preset
+            .apply_script(
+                "# Headroom\n\
+                 master_gain = -6 dB\n\
+                 master_gain -= 3 dB; polyphony = 2 * (3 + 1)\n\
+                 lanes.0.mix *= 50%\n\
+                 lanes.1.gain = -(master_gain / 3)\n\
+                 macro_controls.1.value = macro_controls.0.value + 10%\n\
+                 glide_time = 250 ms\n\
+                 glide_enabled = true\n\
+                 metadata.author = \"Team #1; Sound Design\" # Quoted",
+            )
+            .unwrap();
+        assert_relative_eq!(
+            Decibels::from_linear(preset.master_gain).db(),
+            -9.0,
+            epsilon = 0.0001
+        );
+        assert_eq!(preset.polyphony, 8);
+        assert_relative_eq!(preset.lanes[0].mix.get::<percent>(), 40.0);
+        assert_relative_eq!(preset.lanes[1].gain.db(), 3.0, epsilon = 0.0001);
+        assert_relative_eq!(
+            preset.macro_controls[1].value,
+            preset.macro_controls[0].value + 0.1
+        );
+        assert_relative_eq!(preset.glide_time, 0.25);
+        assert!(preset.glide_enabled);
+        assert_eq!(
+            preset.metadata.author.as_deref(),
+            Some("Team #1; Sound Design")
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut preset = Preset::default();
+
+        // Syntax errors are found before anything changes.
+        let error = preset
+            .apply_script("polyphony = 2\nmaster_gain = (1 dB")
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert!(error.to_string().starts_with("Line 2:"));
+        assert_eq!(preset.polyphony, Preset::default().polyphony);
+
+        assert!(preset.apply_script("master_gain = 1 dB + 1 s").is_err());
+        assert!(preset.apply_script("master_gain = \"loud\" * 2").is_err());
+        assert!(preset.apply_script("master_gain /= 0").is_err());
+        assert!(preset.apply_script("unknown = 1").is_err());
+        assert!(preset.apply_script("= 1").is_err());
+        assert!(preset.apply_script("polyphony = 1 2").is_err());
+    }
+}
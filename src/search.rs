@@ -0,0 +1,754 @@
+//! Searching a library of presets.
+//!
+//! A [`LibraryIndex`] stores what is needed to search the presets without
+//! reading them again. It can be [written](LibraryIndex::write_file) to disk
+//! and [read](LibraryIndex::read_file) back when the library is opened
+//! again, then [updated](LibraryIndex::update) by reading only the presets
+//! that changed.
+//!
+//! Queries are terms separated by spaces and every term must match:
+//!
+//! * `category:bass` matches presets with the category or the hashtag `#bass`.
+//! * `tag:pad` matches presets with the hashtag `#pad` in the description.
+//! * `uses:granular` matches presets that use a feature, such as an effect,
+//!   generator or modulator. Spaces in the feature name are ignored.
+//! * `author:`, `name:`, `description:`, `release:` and `fingerprint:`
+//!   match those values of the preset.
+//! * A term without a field matches the name, author, description or tags.
+//!
+//! Values are matched exactly, ignoring case. A value that starts with `~`
+//! matches if it is contained in the preset's value instead, such as
+//! `author:~kilo`. A term without a field is always matched this way.
+//! Values with spaces can be quoted, such as `author:"Some One"`.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Cursor, Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::preset_features;
+use crate::library::{fnv1a, map_parallel, preset_paths};
+use crate::Preset;
+
+/// What is known about a preset in a [`LibraryIndex`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct IndexedPreset {
+    pub path: PathBuf,
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub category: Option<String>,
+
+    /// Hashtags in the description, without the hash mark and in lowercase.
+    pub tags: Vec<String>,
+
+    /// Names of the effects, generators, modulators and newer features that
+    /// the preset uses, sorted.
+    pub features: Vec<String>,
+
+    /// The earliest release of Phase Plant that can load the preset.
+    pub required_release: String,
+
+    /// Presets that sound the same have the same fingerprint regardless of
+    /// their name or metadata. Not set if the preset can't be written.
+    pub fingerprint: Option<u64>,
+
+    /// When the file was last modified, if the file system records it.
+    pub modified: Option<SystemTime>,
+
+    /// Size of the file in bytes.
+    pub size: u64,
+
+    /// Hash of the contents of the file to recognize it when it is renamed.
+    pub file_hash: u64,
+}
+
+impl IndexedPreset {
+    pub fn new(path: &Path, preset: &Preset) -> Self {
+        let metadata = &preset.metadata;
+        let tags = metadata
+            .description
+            .as_deref()
+            .map(hashtags)
+            .unwrap_or_default();
+        let mut features: Vec<String> = preset_features(preset)
+            .into_keys()
+            .map(|(_, name)| name)
+            .collect();
+        features.sort();
+        features.dedup();
+        Self {
+            path: path.to_path_buf(),
+            name: metadata.name.clone(),
+            author: metadata.author.clone(),
+            description: metadata.description.clone(),
+            category: metadata.category.clone(),
+            tags,
+            features,
+            required_release: preset.required_release().to_string(),
+            fingerprint: fingerprint(preset),
+            modified: None,
+            size: 0,
+            file_hash: 0,
+        }
+    }
+
+    /// If the file is the one that was indexed, judging by when it was
+    /// modified and its size.
+    fn is_current(&self, file: &FileState) -> bool {
+        self.modified.is_some() && self.modified == file.modified && self.size == file.size
+    }
+}
+
+/// What the file system says about a preset file.
+struct FileState {
+    modified: Option<SystemTime>,
+    size: u64,
+}
+
+impl FileState {
+    fn read(path: &Path) -> Result<Self, Error> {
+        let metadata = fs::metadata(path)?;
+        Ok(Self {
+            modified: metadata.modified().ok(),
+            size: metadata.len(),
+        })
+    }
+}
+
+/// How a [`LibraryIndex`] changed when it was [updated](LibraryIndex::update).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IndexUpdate {
+    pub added: Vec<PathBuf>,
+
+    /// Presets whose files were modified and read again.
+    pub changed: Vec<PathBuf>,
+
+    pub removed: Vec<PathBuf>,
+
+    /// The previous and new path of presets whose files were moved. They
+    /// aren't read again.
+    pub renamed: Vec<(PathBuf, PathBuf)>,
+
+    /// How many presets didn't need to be read again.
+    pub unchanged: usize,
+}
+
+/// Read the file once to both index and hash it.
+fn read_indexed(path: &Path) -> Result<IndexedPreset, Error> {
+    let file = FileState::read(path)?;
+    let contents = fs::read(path)?;
+    let name = path
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_string());
+    let preset = Preset::read(&mut Cursor::new(&contents), name)?;
+    Ok(IndexedPreset {
+        modified: file.modified,
+        size: file.size,
+        file_hash: fnv1a(&contents),
+        ..IndexedPreset::new(path, &preset)
+    })
+}
+
+/// The hashtags at the end of descriptions are how Phase Plant categorizes
+/// presets.
+fn hashtags(description: &str) -> Vec<String> {
+    description
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('#'))
+        .map(|tag| tag.trim_start_matches('#').to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// A hash of the canonical form of the preset without its metadata.
+fn fingerprint(preset: &Preset) -> Option<u64> {
+    let mut canonical = preset.clone();
+    canonical.metadata = Default::default();
+    canonical.canonicalize();
+    let mut cursor = Cursor::new(Vec::new());
+    canonical.write(&mut cursor).ok()?;
+    Some(fnv1a(cursor.get_ref()))
+}
+
+/// Searchable summary of a library of presets.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LibraryIndex {
+    /// Layout of the index on disk so old indexes can be rebuilt.
+    format: u32,
+
+    /// Sorted by path.
+    pub presets: Vec<IndexedPreset>,
+
+    /// Presets that could not be read while building the index.
+    pub unreadable: Vec<PathBuf>,
+}
+
+impl LibraryIndex {
+    pub(crate) const FORMAT: u32 = 2;
+
+    pub fn new() -> Self {
+        Self {
+            format: Self::FORMAT,
+            ..Default::default()
+        }
+    }
+
+    /// Read the presets, in parallel with the `parallel` feature, and index
+    /// them. Presets that cannot be
+    /// read are recorded in [`unreadable`](Self::unreadable) instead of
+    /// stopping the build.
+    pub fn build<I, P>(paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut index = Self::new();
+        index.update(paths);
+        index
+    }
+
+    /// Recursively index the presets in a directory.
+    pub fn scan_dir<P: AsRef<Path>>(dir: P) -> Result<Self, Error> {
+        Ok(Self::build(preset_paths(dir.as_ref())?))
+    }
+
+    /// Make the index match the presets at the paths. Only presets that are
+    /// new or whose file was modified or changed size are read. A new path
+    /// with the same contents as a preset that is no longer listed is
+    /// treated as that preset being renamed. Presets that couldn't be read
+    /// before are tried again.
+    pub fn update<I, P>(&mut self, paths: I) -> IndexUpdate
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let paths: HashSet<PathBuf> = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_path_buf())
+            .collect();
+        let mut previous: HashMap<PathBuf, IndexedPreset> = std::mem::take(&mut self.presets)
+            .into_iter()
+            .map(|preset| (preset.path.clone(), preset))
+            .collect();
+        self.unreadable.clear();
+        let mut update = IndexUpdate::default();
+
+        // Keep the presets whose files are the same.
+        let mut pending = Vec::new();
+        for path in paths {
+            let file = match FileState::read(&path) {
+                Ok(file) => file,
+                Err(error) => {
+                    warn!("Unable to read preset {path:?}: {error}");
+                    self.unreadable.push(path);
+                    continue;
+                }
+            };
+            match previous.remove(&path) {
+                Some(preset) if preset.is_current(&file) => {
+                    update.unchanged += 1;
+                    self.presets.push(preset);
+                }
+                Some(_) => {
+                    update.changed.push(path.clone());
+                    pending.push(path);
+                }
+                None => pending.push(path),
+            }
+        }
+
+        // Presets that weren't found again may have been renamed.
+        let hashes = map_parallel(pending, |path| {
+            let hash = fs::read(&path).map(|contents| fnv1a(&contents));
+            (path, hash)
+        });
+        // Files with the same contents have the same hash.
+        let mut moved: HashMap<u64, Vec<IndexedPreset>> = HashMap::new();
+        for preset in previous.into_values() {
+            moved.entry(preset.file_hash).or_default().push(preset);
+        }
+        let mut unread = Vec::new();
+        for (path, hash) in hashes {
+            match hash {
+                Ok(hash) => {
+                    let renamed = if update.changed.contains(&path) {
+                        None
+                    } else {
+                        moved.get_mut(&hash).and_then(Vec::pop)
+                    };
+                    match renamed {
+                        Some(mut preset) => {
+                            update.renamed.push((preset.path.clone(), path.clone()));
+                            // Presets are named after their file.
+                            preset.name = path
+                                .file_stem()
+                                .map(|name| name.to_string_lossy().to_string());
+                            preset.path = path;
+                            preset.modified = FileState::read(&preset.path)
+                                .ok()
+                                .and_then(|file| file.modified);
+                            self.presets.push(preset);
+                        }
+                        None => unread.push(path),
+                    }
+                }
+                Err(error) => {
+                    warn!("Unable to read preset {path:?}: {error}");
+                    self.unreadable.push(path);
+                }
+            }
+        }
+        update.removed = moved
+            .into_values()
+            .flatten()
+            .map(|preset| preset.path)
+            .collect();
+
+        let read = map_parallel(unread, |path| match read_indexed(&path) {
+            Ok(preset) => Ok(preset),
+            Err(error) => {
+                warn!("Unable to read preset {path:?}: {error}");
+                Err(path)
+            }
+        });
+        for result in read {
+            match result {
+                Ok(preset) => {
+                    if !update.changed.contains(&preset.path) {
+                        update.added.push(preset.path.clone());
+                    }
+                    self.presets.push(preset);
+                }
+                Err(path) => self.unreadable.push(path),
+            }
+        }
+
+        self.presets.sort_by(|a, b| a.path.cmp(&b.path));
+        self.unreadable.sort();
+        update.added.sort();
+        update.changed.sort();
+        update.removed.sort();
+        update.renamed.sort();
+        update
+    }
+
+    /// Recursively [update](Self::update) the index with the presets in a
+    /// directory.
+    pub fn update_dir<P: AsRef<Path>>(&mut self, dir: P) -> Result<IndexUpdate, Error> {
+        Ok(self.update(preset_paths(dir.as_ref())?))
+    }
+
+    pub fn read_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let reader = BufReader::new(File::open(path)?);
+        let index: Self = serde_json::from_reader(reader)?;
+        if index.format != Self::FORMAT {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Library index format {} is not supported, rebuild the index",
+                    index.format
+                ),
+            ));
+        }
+        Ok(index)
+    }
+
+    pub fn write_file<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    /// The presets that match the query, in path order. See the
+    /// [module](self) for the syntax of queries.
+    pub fn search(&self, query: &str) -> Result<Vec<&IndexedPreset>, Error> {
+        let query = Query::parse(query)?;
+        Ok(self
+            .presets
+            .iter()
+            .filter(|preset| query.matches(preset))
+            .collect())
+    }
+
+    /// Groups of presets with the same fingerprint.
+    pub fn duplicates(&self) -> Vec<Vec<&IndexedPreset>> {
+        let mut by_fingerprint: Vec<&IndexedPreset> = self
+            .presets
+            .iter()
+            .filter(|preset| preset.fingerprint.is_some())
+            .collect();
+        by_fingerprint.sort_by_key(|preset| preset.fingerprint);
+        by_fingerprint
+            .chunk_by(|a, b| a.fingerprint == b.fingerprint)
+            .filter(|group| group.len() > 1)
+            .map(|group| group.to_vec())
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Field {
+    /// A term without a field.
+    Any,
+    Author,
+    Category,
+    Description,
+    Fingerprint,
+    Name,
+    Release,
+    Tag,
+    Uses,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Result<Self, Error> {
+        use Field::*;
+        Ok(match name.to_lowercase().as_str() {
+            "author" => Author,
+            "category" => Category,
+            "description" => Description,
+            "fingerprint" => Fingerprint,
+            "name" => Name,
+            "release" => Release,
+            "tag" => Tag,
+            "uses" => Uses,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Unknown search field {name:?}"),
+                ))
+            }
+        })
+    }
+
+    fn values(&self, preset: &IndexedPreset) -> Vec<String> {
+        use Field::*;
+        let optional = |value: &Option<String>| value.iter().cloned().collect::<Vec<_>>();
+        match self {
+            Any => {
+                let mut values = optional(&preset.name);
+                values.extend(optional(&preset.author));
+                values.extend(optional(&preset.description));
+                values.extend(preset.tags.iter().cloned());
+                values
+            }
+            Author => optional(&preset.author),
+            Category => {
+                let mut values = optional(&preset.category);
+                values.extend(preset.tags.iter().cloned());
+                values
+            }
+            Description => optional(&preset.description),
+            Fingerprint => preset
+                .fingerprint
+                .iter()
+                .map(|fingerprint| format!("{fingerprint:016x}"))
+                .collect(),
+            Name => optional(&preset.name),
+            Release => vec![preset.required_release.clone()],
+            Tag => preset.tags.clone(),
+            Uses => preset
+                .features
+                .iter()
+                .map(|feature| feature.replace(' ', ""))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Term {
+    field: Field,
+
+    /// In lowercase.
+    value: String,
+
+    /// Match if the value is contained instead of equal.
+    contains: bool,
+}
+
+impl Term {
+    fn matches(&self, preset: &IndexedPreset) -> bool {
+        self.field.values(preset).iter().any(|value| {
+            let value = value.to_lowercase();
+            if self.contains {
+                value.contains(&self.value)
+            } else {
+                value == self.value
+            }
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct Query {
+    terms: Vec<Term>,
+}
+
+impl Query {
+    fn parse(query: &str) -> Result<Self, Error> {
+        let mut terms = Vec::new();
+        let mut chars = query.chars().peekable();
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if chars.peek().is_none() {
+                break;
+            }
+
+            // Read up to the end of the term, keeping spaces in quotes.
+            let mut field_name = None;
+            let mut value = String::new();
+            let mut quoted = false;
+            while let Some(c) = chars.next_if(|c| quoted || !c.is_whitespace()) {
+                match c {
+                    '"' => quoted = !quoted,
+                    ':' if !quoted && field_name.is_none() => {
+                        field_name = Some(std::mem::take(&mut value))
+                    }
+                    _ => value.push(c),
+                }
+            }
+            if quoted {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Missing closing quote in search {query:?}"),
+                ));
+            }
+
+            let field = match &field_name {
+                Some(name) => Field::from_name(name)?,
+                None => Field::Any,
+            };
+            let contains = field == Field::Any || value.starts_with('~');
+            let mut value = value.strip_prefix('~').unwrap_or(&value).to_lowercase();
+            if field == Field::Uses {
+                value.retain(|c| c != ' ');
+            }
+            if value.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Missing value in search {query:?}"),
+                ));
+            }
+            terms.push(Term {
+                field,
+                value,
+                contains,
+            });
+        }
+        Ok(Self { terms })
+    }
+
+    fn matches(&self, preset: &IndexedPreset) -> bool {
+        self.terms.iter().all(|term| term.matches(preset))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::library::write_many;
+    use crate::tests::test_data_path;
+
+    use super::*;
+
+    #[test]
+    fn hashtags() {
+        assert_eq!(
+            super::hashtags("Deep and wide #Bass #808 ## #"),
+            ["bass", "808"]
+        );
+        assert!(super::hashtags("No tags").is_empty());
+    }
+
+    #[test]
+    fn parse() {
+        let query =
+            Query::parse(r#"  category:bass uses:"Sample Player" author:~Kilo warm "#).unwrap();
+        assert_eq!(
+            query.terms,
+            [
+                Term {
+                    field: Field::Category,
+                    value: "bass".to_owned(),
+                    contains: false,
+                },
+                Term {
+                    field: Field::Uses,
+                    value: "sampleplayer".to_owned(),
+                    contains: false,
+                },
+                Term {
+                    field: Field::Author,
+                    value: "kilo".to_owned(),
+                    contains: true,
+                },
+                Term {
+                    field: Field::Any,
+                    value: "warm".to_owned(),
+                    contains: true,
+                },
+            ]
+        );
+        assert!(Query::parse("").unwrap().terms.is_empty());
+
+        for invalid in ["colour:red", "author:", "author:\"Some One", "name:~"] {
+            let error = Query::parse(invalid).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidInput, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn library_index() {
+        let dir = std::env::temp_dir().join(format!("library_index-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut bass = Preset::default();
+        bass.metadata.name = Some("Deep".to_owned());
+        bass.metadata.author = Some("Kilohearts".to_owned());
+        bass.metadata.description = Some("Round and deep #bass".to_owned());
+        let mut copy = bass.clone();
+        copy.metadata.name = Some("Copy".to_owned());
+        copy.metadata.description = None;
+        let written = write_many(&[bass, copy], &dir);
+        assert!(written.iter().all(|written| written.result.is_ok()));
+
+        let granular = test_data_path(&[
+            "generators",
+            "granular_generator",
+            "granular_generator-2.1.0.phaseplant",
+        ]);
+        let missing = dir.join("missing.phaseplant");
+        let paths = [written[0].path.clone(), written[1].path.clone(), granular];
+        let index = LibraryIndex::build(paths.iter().chain([&missing]));
+        assert_eq!(index.presets.len(), 3);
+        assert_eq!(index.unreadable, [missing]);
+
+        let names = |query: &str| {
+            index
+                .search(query)
+                .unwrap()
+                .iter()
+                .map(|preset| preset.name.clone().unwrap_or_default())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("category:bass author:~kilo"), ["Deep"]);
+        assert_eq!(names("tag:BASS"), ["Deep"]);
+        assert_eq!(names("author:kilo"), Vec::<String>::new());
+        assert_eq!(names("uses:granular"), ["granular_generator-2.1.0"]);
+        assert_eq!(
+            names("release:2.1.0 granular"),
+            ["granular_generator-2.1.0"]
+        );
+        assert_eq!(names("round"), ["Deep"]);
+        assert_eq!(names("").len(), 3);
+        assert!(index.search("colour:red").is_err());
+
+        // Only the metadata differs.
+        let duplicates = index.duplicates();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].len(), 2);
+        let fingerprint = format!("{:016x}", duplicates[0][0].fingerprint.unwrap());
+        assert_eq!(names(&format!("fingerprint:{fingerprint}")).len(), 2);
+
+        let index_path = dir.join("index.json");
+        index.write_file(&index_path).unwrap();
+        assert_eq!(LibraryIndex::read_file(&index_path).unwrap(), index);
+
+        fs::write(&index_path, r#"{"format":1,"presets":[],"unreadable":[]}"#).unwrap();
+        let error = LibraryIndex::read_file(&index_path).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn update() {
+        let dir = std::env::temp_dir().join(format!("library_update-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let named = |name: &str, description: &str| {
+            let mut preset = Preset::default();
+            preset.metadata.name = Some(name.to_owned());
+            preset.metadata.description = Some(description.to_owned());
+            preset
+        };
+        write_many(&[named("Bass", "#bass"), named("Lead", "#lead")], &dir);
+
+        // Two copies of the same preset.
+        let untitled = dir.join("Untitled.phaseplant");
+        let copy = dir.join("Copy.phaseplant");
+        Preset::default().write_file(&untitled).unwrap();
+        fs::copy(&untitled, &copy).unwrap();
+
+        let mut index = LibraryIndex::scan_dir(&dir).unwrap();
+        assert_eq!(index.presets.len(), 4);
+        assert!(index.presets.iter().all(|preset| preset.size > 0));
+        let update = index.update_dir(&dir).unwrap();
+        assert_eq!(
+            update,
+            IndexUpdate {
+                unchanged: 4,
+                ..Default::default()
+            }
+        );
+
+        // Renamed files with the same contents are both found and take the
+        // name of their new file.
+        let keys = dir.join("Keys.phaseplant");
+        let organ = dir.join("Organ.phaseplant");
+        fs::rename(&untitled, &keys).unwrap();
+        fs::rename(&copy, &organ).unwrap();
+        let update = index.update_dir(&dir).unwrap();
+        assert_eq!(update.renamed.len(), 2);
+        assert!(update.removed.is_empty());
+        assert_eq!(update.unchanged, 2);
+        for (path, name) in [(&keys, "Keys"), (&organ, "Organ")] {
+            let preset = index.presets.iter().find(|p| &p.path == path).unwrap();
+            assert_eq!(preset.name.as_deref(), Some(name));
+        }
+
+        let bass = dir.join("Bass.phaseplant");
+        let renamed = dir.join("Low.phaseplant");
+        let lead = dir.join("Lead.phaseplant");
+        let pad = dir.join("Pad.phaseplant");
+        fs::rename(&bass, &renamed).unwrap();
+        named("Lead", "Longer description #lead")
+            .write_file(&lead)
+            .unwrap();
+        named("Pad", "#pad").write_file(&pad).unwrap();
+        let update = index.update_dir(&dir).unwrap();
+        assert_eq!(
+            update,
+            IndexUpdate {
+                added: vec![pad.clone()],
+                changed: vec![lead.clone()],
+                renamed: vec![(bass.clone(), renamed.clone())],
+                unchanged: 2,
+                ..Default::default()
+            }
+        );
+        assert_eq!(index.search("tag:bass").unwrap()[0].path, renamed);
+        assert_eq!(
+            index.search("tag:bass").unwrap()[0].name.as_deref(),
+            Some("Low")
+        );
+        assert_eq!(index.search("longer").unwrap()[0].path, lead);
+
+        fs::remove_file(&pad).unwrap();
+        let update = index.update_dir(&dir).unwrap();
+        assert_eq!(update.removed, [pad]);
+        assert_eq!(update.unchanged, 4);
+        assert_eq!(index.presets.len(), 4);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt::{Display, Formatter};
+
+use uom::si::f32::Frequency;
+
+use crate::limits::MASTER_PITCH_MAX;
+use crate::Preset;
+
+/// Musical interval used for pitch shifting. There are 100 cents in a
+/// semitone and 12 semitones in an octave.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Semitones(f32);
+
+impl Semitones {
+    pub const ZERO: Semitones = Semitones::new(0.0);
+    pub const OCTAVE: Semitones = Semitones::new(12.0);
+
+    pub const fn new(semitones: f32) -> Semitones {
+        Semitones(semitones)
+    }
+
+    pub fn from_cents(cents: f32) -> Semitones {
+        Semitones::new(cents / 100.0)
+    }
+
+    /// Interval between two frequencies, such as `2.0` for an octave.
+    pub fn from_frequency_ratio(ratio: f32) -> Semitones {
+        Semitones::new(ratio.log2() * 12.0)
+    }
+
+    pub fn semitones(&self) -> f32 {
+        self.0
+    }
+
+    pub fn cents(&self) -> f32 {
+        self.0 * 100.0
+    }
+
+    /// Ratio between the shifted and the original frequency.
+    pub fn frequency_ratio(&self) -> f32 {
+        2.0_f32.powf(self.0 / 12.0)
+    }
+
+    /// Shift a frequency by the interval.
+    pub fn shift(&self, frequency: Frequency) -> Frequency {
+        frequency * self.frequency_ratio()
+    }
+}
+
+impl Display for Semitones {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} st", &self.0)
+    }
+}
+
+/// The master tuning of a preset as the whole semitones and cents shown by
+/// Phase Plant. The preset stores it as semitones with the cents as the
+/// fraction, see [`Preset::master_pitch`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MasterPitch {
+    pub semitones: i32,
+
+    /// Hundredths of a semitone, from -99 to 99. Has the same sign as the
+    /// semitones.
+    pub cents: f32,
+}
+
+impl MasterPitch {
+    /// Split semitones with the cents as the fraction, limited to the range
+    /// of Phase Plant. The pitch is rounded to the nearest cent first so a
+    /// pitch just below a semitone doesn't have 100 cents.
+    pub fn new(pitch: f32) -> Self {
+        let pitch = pitch.clamp(-MASTER_PITCH_MAX, MASTER_PITCH_MAX);
+        let cents = (pitch * 100.0).round();
+        Self {
+            semitones: (cents / 100.0).trunc() as i32,
+            // Adding zero turns negative zero into zero.
+            cents: cents % 100.0 + 0.0,
+        }
+    }
+
+    /// Semitones with the cents as the fraction, limited to the range of
+    /// Phase Plant.
+    pub fn pitch(&self) -> f32 {
+        (self.semitones as f32 + self.cents / 100.0).clamp(-MASTER_PITCH_MAX, MASTER_PITCH_MAX)
+    }
+}
+
+impl Display for MasterPitch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} st {} ct", self.semitones, self.cents.round())
+    }
+}
+
+impl Preset {
+    pub fn master_tuning(&self) -> MasterPitch {
+        MasterPitch::new(self.master_pitch)
+    }
+
+    pub fn set_master_tuning(&mut self, tuning: &MasterPitch) {
+        self.master_pitch = tuning.pitch();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use uom::si::frequency::hertz;
+
+    use super::*;
+
+    #[test]
+    fn cents() {
+        assert_eq!(Semitones::from_cents(150.0).semitones(), 1.5);
+        assert_eq!(Semitones::new(-2.0).cents(), -200.0);
+    }
+
+    #[test]
+    fn master_pitch() {
+        let tuning = MasterPitch::new(12.5);
+        assert_eq!(tuning.semitones, 12);
+        assert_relative_eq!(tuning.cents, 50.0);
+        assert_eq!(tuning.to_string(), "12 st 50 ct");
+
+        let tuning = MasterPitch::new(-3.25);
+        assert_eq!(tuning.semitones, -3);
+        assert_relative_eq!(tuning.cents, -25.0);
+        assert_relative_eq!(tuning.pitch(), -3.25);
+
+        assert_eq!(MasterPitch::new(100.0).semitones, 48);
+        assert_eq!(MasterPitch::new(12.999).to_string(), "13 st 0 ct");
+        assert_eq!(MasterPitch::new(-0.999).to_string(), "-1 st 0 ct");
+        let tuning = MasterPitch {
+            semitones: 2,
+            cents: 150.0,
+        };
+        assert_relative_eq!(tuning.pitch(), 3.5);
+
+        let mut preset = Preset::default();
+        preset.set_master_tuning(&MasterPitch {
+            semitones: -60,
+            cents: 0.0,
+        });
+        assert_relative_eq!(preset.master_pitch, -48.0);
+    }
+
+    #[test]
+    fn frequency_ratio() {
+        assert_relative_eq!(Semitones::OCTAVE.frequency_ratio(), 2.0);
+        assert_relative_eq!(Semitones::new(-12.0).frequency_ratio(), 0.5);
+        assert_relative_eq!(Semitones::from_frequency_ratio(1.5).semitones(), 7.01955);
+        assert_relative_eq!(
+            Semitones::new(7.0)
+                .shift(Frequency::new::<hertz>(440.0))
+                .get::<hertz>(),
+            659.2551,
+            epsilon = 0.001
+        );
+    }
+}
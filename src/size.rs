@@ -0,0 +1,109 @@
+//! Why a preset file is as large as it is.
+//!
+//! Embedded samples are usually most of a large preset. [`SizeBreakdown`]
+//! splits the size of the written file into the embedded samples and
+//! wavetables, the effects and everything else, which shows how much
+//! referencing the samples instead of embedding them would save.
+
+use std::io::{Cursor, Error};
+
+use crate::library::{asset_mut, AssetKind, AssetLocation};
+use crate::relink::asset_locations;
+use crate::Preset;
+
+/// Estimated bytes of each part of a written preset. The parts add up to
+/// the total.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SizeBreakdown {
+    /// Size of the whole file.
+    pub total: u64,
+
+    /// Samples embedded in Sampler and Granular generators.
+    pub samples: u64,
+
+    /// Wavetables embedded in Wavetable generators.
+    pub wavetables: u64,
+
+    /// Wavetables embedded in LFO Table modulators.
+    pub modulator_shapes: u64,
+
+    pub effects: u64,
+
+    /// The header, settings and the blocks every generator and modulator
+    /// has room for, which don't change much between presets.
+    pub overhead: u64,
+}
+
+impl SizeBreakdown {
+    /// Bytes saved if nothing was embedded.
+    pub fn embedded(&self) -> u64 {
+        self.samples + self.wavetables + self.modulator_shapes
+    }
+}
+
+impl Preset {
+    /// Estimate how much of the written preset each part takes. The effects
+    /// are measured by writing the preset without them. Fails if the preset
+    /// can't be written.
+    pub fn size_breakdown(&self) -> Result<SizeBreakdown, Error> {
+        let written_size = |preset: &Preset| -> Result<u64, Error> {
+            let mut cursor = Cursor::new(Vec::new());
+            preset.write(&mut cursor)?;
+            Ok(cursor.into_inner().len() as u64)
+        };
+        let mut breakdown = SizeBreakdown {
+            total: written_size(self)?,
+            ..Default::default()
+        };
+
+        let mut stripped = self.clone();
+        for (location, kind) in asset_locations(self) {
+            let Some((contents, _)) = asset_mut(&mut stripped, location) else {
+                continue;
+            };
+            let size = std::mem::take(contents).len() as u64;
+            match (location, kind) {
+                (AssetLocation::Modulator(_), _) => breakdown.modulator_shapes += size,
+                (_, AssetKind::Sample) => breakdown.samples += size,
+                (_, AssetKind::Wavetable) => breakdown.wavetables += size,
+            }
+        }
+        let with_effects = written_size(&stripped)?;
+        for lane in &mut stripped.lanes {
+            lane.snapins.clear();
+        }
+        breakdown.effects = with_effects.saturating_sub(written_size(&stripped)?);
+        breakdown.overhead = breakdown
+            .total
+            .saturating_sub(breakdown.embedded() + breakdown.effects);
+        Ok(breakdown)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{read_effect_preset, read_generator_preset};
+
+    #[test]
+    fn size_breakdown() {
+        let preset = read_generator_preset(
+            "sample_player",
+            "sample_player-custom-sample-1.8.16.phaseplant",
+        )
+        .unwrap();
+        let breakdown = preset.size_breakdown().unwrap();
+        assert!(breakdown.samples > breakdown.overhead);
+        assert_eq!(breakdown.wavetables, 0);
+        assert_eq!(breakdown.effects, 0);
+        assert_eq!(
+            breakdown.embedded() + breakdown.effects + breakdown.overhead,
+            breakdown.total
+        );
+
+        let preset = read_effect_preset("filter", "filter-2.0.16.phaseplant").unwrap();
+        let breakdown = preset.size_breakdown().unwrap();
+        assert!(breakdown.effects > 0);
+        assert_eq!(breakdown.embedded(), 0);
+        assert!(breakdown.effects < breakdown.overhead);
+    }
+}
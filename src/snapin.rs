@@ -3,11 +3,33 @@
 use crate::effect::{Effect, EffectVersion, Filter};
 use crate::io::WRITE_SAME_AS;
 use crate::version::Version;
-use crate::Metadata;
+use crate::{Lane, Metadata};
 
 pub type SnapinId = u16;
 
-#[derive(Debug)]
+/// The state of a snapin in Phase Plant rather than the settings of its
+/// effect. Effects store it amongst their settings but it belongs to the
+/// snapin.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SnapinState {
+    pub enabled: bool,
+    pub minimized: bool,
+
+    /// Position of the group that contains the snapin.
+    pub group_id: Option<SnapinId>,
+}
+
+impl Default for SnapinState {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            minimized: false,
+            group_id: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct Snapin {
     /// Unique ID of the snapin in the lane. It does not represent the order
     /// of snapins in the lane.
@@ -38,6 +60,10 @@ pub struct Snapin {
 impl Snapin {
     pub const MIN_POSITION: SnapinId = 1;
 
+    /// First component of the preset path for presets that are included with
+    /// the effect.
+    pub const FACTORY_PRESET_ROOT: &'static str = "factory";
+
     /// Create a snapin that contains the effect.
     pub fn new(effect: Box<dyn Effect>, id: SnapinId, enabled: bool, minimized: bool) -> Snapin {
         Snapin {
@@ -51,6 +77,57 @@ impl Snapin {
         }
     }
 
+    pub fn state(&self) -> SnapinState {
+        SnapinState {
+            enabled: self.enabled,
+            minimized: self.minimized,
+            group_id: self.group_id,
+        }
+    }
+
+    pub fn set_state(&mut self, state: SnapinState) {
+        self.enabled = state.enabled;
+        self.minimized = state.minimized;
+        self.group_id = state.group_id;
+    }
+
+    /// The [`Group`](crate::effect::Group) snapin in the lane that contains
+    /// this snapin. See [`Lane::set_group`] to change it.
+    pub fn group<'a>(&self, lane: &'a Lane) -> Option<&'a Snapin> {
+        let group_id = self.group_id?;
+        lane.snapins
+            .iter()
+            .find(|snapin| snapin.id == group_id && snapin.effect.as_group().is_some())
+    }
+
+    /// The effect if it has the given type.
+    #[must_use]
+    pub fn effect_as<T: Effect>(&self) -> Option<&T> {
+        self.effect.downcast_ref::<T>()
+    }
+
+    #[must_use]
+    pub fn effect_as_mut<T: Effect>(&mut self) -> Option<&mut T> {
+        self.effect.downcast_mut::<T>()
+    }
+
+    /// If the selected preset is one of the presets that Kilohearts ships with
+    /// the effect.
+    pub fn is_factory_preset(&self) -> bool {
+        self.preset_path
+            .first()
+            .is_some_and(|root| root == Self::FACTORY_PRESET_ROOT)
+    }
+
+    /// File name of the selected preset, such as `Small Width.ksha`. Phase
+    /// Plant 1.7 does not store a preset path so there will be no file name.
+    pub fn preset_file_name(&self) -> Option<&str> {
+        self.preset_path
+            .last()
+            .map(String::as_str)
+            .filter(|name| !name.is_empty())
+    }
+
     /// Update the identifiers of the snapin to match the order they are in the
     /// list of snapins.
     pub fn update_ids_to_match_order(snapins: &mut [Snapin]) {
@@ -94,6 +171,61 @@ impl PartialEq for Snapin {
             && self.effect_version == other.effect_version
             && self.preset_name == other.preset_name
             && self.preset_path == other.preset_path
-            && self.effect.box_eq(&other.effect)
+            && self.effect.box_eq(other.effect.as_any())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::effect::{Filter, Gain};
+    use crate::test::read_effect_preset;
+
+    use super::*;
+
+    #[test]
+    fn factory_preset() {
+        let preset = read_effect_preset("haas", "haas-small_width-1.8.13.phaseplant").unwrap();
+        let snapin = &preset.lanes[0].snapins[0];
+        assert!(snapin.is_factory_preset());
+        assert_eq!(snapin.preset_file_name(), Some("Small Width.ksha"));
+
+        let preset = read_effect_preset("haas", "haas-1.8.13.phaseplant").unwrap();
+        let snapin = &preset.lanes[0].snapins[0];
+        assert!(!snapin.is_factory_preset());
+        assert_eq!(snapin.preset_file_name(), None);
+    }
+
+    #[test]
+    fn effect_as() {
+        let mut preset = read_effect_preset("gain", "gain-2.0.16.phaseplant").unwrap();
+        let snapin = &mut preset.lanes[0].snapins[0];
+        assert!(snapin.effect_as::<Gain>().is_some());
+        assert!(snapin.effect_as::<Filter>().is_none());
+        assert!(snapin.effect_as_mut::<Gain>().is_some());
+    }
+
+    #[test]
+    fn state() {
+        let preset = read_effect_preset("gain", "gain-minimized-2.0.16.phaseplant").unwrap();
+        let snapin = &preset.lanes[0].snapins[0];
+        assert_eq!(
+            snapin.state(),
+            SnapinState {
+                enabled: true,
+                minimized: true,
+                group_id: None,
+            }
+        );
+
+        let mut snapin = Snapin::new(Box::<Gain>::default(), 1, true, false);
+        let state = SnapinState {
+            enabled: false,
+            minimized: true,
+            group_id: Some(2),
+        };
+        snapin.set_state(state);
+        assert_eq!(snapin.state(), state);
+        assert!(!snapin.enabled);
+        assert_eq!(snapin.group_id, Some(2));
     }
 }
@@ -0,0 +1,284 @@
+//! Keep a [`LibraryIndex`] in a SQLite database.
+//!
+//! Reading the JSON written by [`LibraryIndex::write_file`] means parsing
+//! every preset in the index, which is slow to start with for large
+//! libraries. A database can be opened right away and is updated in place,
+//! so writing the index again after a re-scan only changes the presets that
+//! were added, changed or removed.
+
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use rusqlite::{params, Connection, OpenFlags, Row};
+
+use crate::search::{IndexedPreset, LibraryIndex};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS presets (
+        path TEXT PRIMARY KEY,
+        name TEXT,
+        author TEXT,
+        description TEXT,
+        category TEXT,
+        tags TEXT NOT NULL,
+        features TEXT NOT NULL,
+        required_release TEXT NOT NULL,
+        fingerprint INTEGER,
+        modified INTEGER,
+        size INTEGER NOT NULL,
+        file_hash INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS unreadable (path TEXT PRIMARY KEY);
+";
+
+const UPSERT_PRESET: &str = "
+    INSERT INTO presets (path, name, author, description, category, tags, features,
+        required_release, fingerprint, modified, size, file_hash)
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+    ON CONFLICT (path) DO UPDATE SET
+        name = excluded.name,
+        author = excluded.author,
+        description = excluded.description,
+        category = excluded.category,
+        tags = excluded.tags,
+        features = excluded.features,
+        required_release = excluded.required_release,
+        fingerprint = excluded.fingerprint,
+        modified = excluded.modified,
+        size = excluded.size,
+        file_hash = excluded.file_hash
+";
+
+pub(crate) fn database_error(error: rusqlite::Error) -> Error {
+    Error::other(error)
+}
+
+/// SQLite only stores signed integers so hashes are stored with the same
+/// bits.
+fn to_sql_hash(hash: u64) -> i64 {
+    hash as i64
+}
+
+fn from_sql_hash(hash: i64) -> u64 {
+    hash as u64
+}
+
+/// Nanoseconds since the Unix epoch. Times before the epoch aren't stored so
+/// the preset is read again when the index is updated.
+fn to_sql_time(time: SystemTime) -> Option<i64> {
+    let since_epoch = time.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+    i64::try_from(since_epoch.as_nanos()).ok()
+}
+
+fn from_sql_time(nanos: i64) -> Option<SystemTime> {
+    let nanos = u64::try_from(nanos).ok()?;
+    SystemTime::UNIX_EPOCH.checked_add(Duration::from_nanos(nanos))
+}
+
+/// Paths are stored as text so paths that aren't valid Unicode can't be
+/// stored exactly.
+fn path_text(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+fn read_preset(row: &Row) -> rusqlite::Result<IndexedPreset> {
+    let list = |index: usize| -> rusqlite::Result<Vec<String>> {
+        let text: String = row.get(index)?;
+        serde_json::from_str(&text).map_err(|error| {
+            rusqlite::Error::FromSqlConversionFailure(
+                index,
+                rusqlite::types::Type::Text,
+                Box::new(error),
+            )
+        })
+    };
+    Ok(IndexedPreset {
+        path: PathBuf::from(row.get::<_, String>(0)?),
+        name: row.get(1)?,
+        author: row.get(2)?,
+        description: row.get(3)?,
+        category: row.get(4)?,
+        tags: list(5)?,
+        features: list(6)?,
+        required_release: row.get(7)?,
+        fingerprint: row.get::<_, Option<i64>>(8)?.map(from_sql_hash),
+        modified: row.get::<_, Option<i64>>(9)?.and_then(from_sql_time),
+        size: row.get::<_, i64>(10)? as u64,
+        file_hash: from_sql_hash(row.get(11)?),
+    })
+}
+
+impl LibraryIndex {
+    /// Read an index from a database written by
+    /// [`write_database`](Self::write_database). Fails with
+    /// [`ErrorKind::InvalidData`] if the database was written by a version
+    /// of the crate with a different index format.
+    pub fn read_database<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(database_error)?;
+        let format: u32 = connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(database_error)?;
+        if format != Self::FORMAT {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Library index format {format} is not supported, rebuild the index"),
+            ));
+        }
+
+        let mut index = Self::new();
+        let mut statement = connection
+            .prepare("SELECT * FROM presets ORDER BY path")
+            .map_err(database_error)?;
+        index.presets = statement
+            .query_map([], read_preset)
+            .and_then(|rows| rows.collect())
+            .map_err(database_error)?;
+        let mut statement = connection
+            .prepare("SELECT path FROM unreadable ORDER BY path")
+            .map_err(database_error)?;
+        index.unreadable = statement
+            .query_map([], |row| row.get::<_, String>(0).map(PathBuf::from))
+            .and_then(|rows| rows.collect())
+            .map_err(database_error)?;
+        Ok(index)
+    }
+
+    /// Write the index to a database, creating it if it doesn't exist.
+    /// Presets whose file hash or modification time changed are updated,
+    /// presets that didn't change are left alone and presets no longer in
+    /// the index are removed. A database of an older index format is
+    /// replaced.
+    pub fn write_database<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut connection = Connection::open(path).map_err(database_error)?;
+        let transaction = connection.transaction().map_err(database_error)?;
+        let format: u32 = transaction
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(database_error)?;
+        if format != Self::FORMAT {
+            transaction
+                .execute_batch("DROP TABLE IF EXISTS presets; DROP TABLE IF EXISTS unreadable;")
+                .map_err(database_error)?;
+        }
+        transaction.execute_batch(SCHEMA).map_err(database_error)?;
+        transaction
+            .pragma_update(None, "user_version", Self::FORMAT)
+            .map_err(database_error)?;
+
+        {
+            // The file hash and modification time of each stored preset.
+            let mut statement = transaction
+                .prepare("SELECT path, file_hash, modified FROM presets")
+                .map_err(database_error)?;
+            let stored: HashMap<String, (i64, Option<i64>)> = statement
+                .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))
+                .and_then(|rows| rows.collect())
+                .map_err(database_error)?;
+            let indexed: HashMap<String, &IndexedPreset> = self
+                .presets
+                .iter()
+                .map(|preset| (path_text(&preset.path), preset))
+                .collect();
+            let mut delete = transaction
+                .prepare("DELETE FROM presets WHERE path = ?1")
+                .map_err(database_error)?;
+            for path in stored.keys().filter(|path| !indexed.contains_key(*path)) {
+                delete.execute([path]).map_err(database_error)?;
+            }
+
+            let mut upsert = transaction.prepare(UPSERT_PRESET).map_err(database_error)?;
+            for (path, preset) in indexed {
+                let modified = preset.modified.and_then(to_sql_time);
+                let file_hash = to_sql_hash(preset.file_hash);
+                if stored.get(&path) == Some(&(file_hash, modified)) {
+                    continue;
+                }
+                upsert
+                    .execute(params![
+                        path,
+                        preset.name,
+                        preset.author,
+                        preset.description,
+                        preset.category,
+                        serde_json::to_string(&preset.tags)?,
+                        serde_json::to_string(&preset.features)?,
+                        preset.required_release,
+                        preset.fingerprint.map(to_sql_hash),
+                        modified,
+                        preset.size as i64,
+                        file_hash,
+                    ])
+                    .map_err(database_error)?;
+            }
+
+            transaction
+                .execute("DELETE FROM unreadable", [])
+                .map_err(database_error)?;
+            let mut insert = transaction
+                .prepare("INSERT INTO unreadable (path) VALUES (?1)")
+                .map_err(database_error)?;
+            for path in &self.unreadable {
+                insert.execute([path_text(path)]).map_err(database_error)?;
+            }
+        }
+        transaction.commit().map_err(database_error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::library::write_many;
+    use crate::Preset;
+
+    use super::*;
+
+    #[test]
+    fn database() {
+        let dir = std::env::temp_dir().join(format!("library_database-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("presets")).unwrap();
+        let mut preset = Preset::default();
+        preset.metadata.description = Some("Warm #pad".to_owned());
+        let written = write_many(&[preset.clone(), preset], dir.join("presets"));
+        let database = dir.join("index.sqlite");
+
+        let mut index = LibraryIndex::scan_dir(dir.join("presets")).unwrap();
+        index.unreadable.push(dir.join("broken.phaseplant"));
+        index.write_database(&database).unwrap();
+        assert_eq!(LibraryIndex::read_database(&database).unwrap(), index);
+
+        // Presets that didn't change aren't written again.
+        let path = path_text(&index.presets[0].path);
+        Connection::open(&database)
+            .unwrap()
+            .execute("UPDATE presets SET name = 'Kept' WHERE path = ?1", [&path])
+            .unwrap();
+        index.write_database(&database).unwrap();
+        let read = LibraryIndex::read_database(&database).unwrap();
+        assert_eq!(read.presets[0].name.as_deref(), Some("Kept"));
+        Connection::open(&database)
+            .unwrap()
+            .execute("UPDATE presets SET file_hash = 0 WHERE path = ?1", [&path])
+            .unwrap();
+        index.write_database(&database).unwrap();
+        assert_eq!(LibraryIndex::read_database(&database).unwrap(), index);
+
+        // Writing again after a re-scan removes the missing preset.
+        fs::remove_file(&written[1].path).unwrap();
+        let update = index.update_dir(dir.join("presets")).unwrap();
+        assert_eq!(update.removed.len(), 1);
+        index.write_database(&database).unwrap();
+        let read = LibraryIndex::read_database(&database).unwrap();
+        assert_eq!(read, index);
+        assert_eq!(read.presets[0].tags, ["pad"]);
+        assert_eq!(read.search("tag:pad").unwrap().len(), 1);
+
+        let error = LibraryIndex::read_database(dir.join("missing.sqlite")).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Other);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,359 @@
+//! Where presets are kept.
+//!
+//! A [`PresetStore`] loads and saves the bytes of presets by a key, such as
+//! `Bass/Deep.phaseplant`, so presets can be read from and written to
+//! storage other than a folder of files without copying them to files
+//! first. [`FileStore`] keeps presets in a folder, [`MemoryStore`] keeps
+//! them in memory and, with the `sqlite` feature, `SqliteStore` keeps a bank
+//! of presets in a single database file.
+//!
+//! Stores are shared by reference between threads. Applications using async
+//! can put a store in an `Arc` and call it from a blocking task.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Cursor, Error, ErrorKind};
+use std::path::{Component, Path, PathBuf};
+#[cfg(feature = "sqlite")]
+use std::sync::Mutex;
+use std::sync::RwLock;
+
+#[cfg(feature = "sqlite")]
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::library::{preset_paths, PRESET_EXTENSION};
+#[cfg(feature = "sqlite")]
+use crate::sqlite::database_error;
+use crate::Preset;
+
+/// Storage of presets by key. Keys are paths separated by `/`, relative to
+/// the store, and end with the preset extension. Every store fails with
+/// [`ErrorKind::InvalidInput`] if a key isn't valid.
+pub trait PresetStore: Send + Sync {
+    /// Keys of every preset in the store, sorted.
+    fn keys(&self) -> Result<Vec<String>, Error>;
+
+    /// Fails with [`ErrorKind::NotFound`] if there is no preset with the key.
+    fn load(&self, key: &str) -> Result<Vec<u8>, Error>;
+
+    /// Add the preset or replace the preset with the same key.
+    fn save(&self, key: &str, contents: &[u8]) -> Result<(), Error>;
+
+    /// Fails with [`ErrorKind::NotFound`] if there is no preset with the key.
+    fn remove(&self, key: &str) -> Result<(), Error>;
+
+    /// Read a preset. The name of the preset is the last part of the key
+    /// without the extension if the preset doesn't have one.
+    fn read_preset(&self, key: &str) -> Result<Preset, Error> {
+        let contents = self.load(key)?;
+        let name = Path::new(key)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned());
+        Preset::read(&mut Cursor::new(contents), name)
+    }
+
+    /// Write a preset. Nothing is saved if the preset can't be written.
+    fn write_preset(&self, key: &str, preset: &Preset) -> Result<(), Error> {
+        let mut cursor = Cursor::new(Vec::new());
+        preset.write(&mut cursor)?;
+        self.save(key, &cursor.into_inner())
+    }
+}
+
+/// Keys can't leave the store, so they must be relative and can't contain
+/// `..`. Only presets are kept so they must have the preset extension.
+fn check_key(key: &str) -> Result<(), Error> {
+    let path = Path::new(key);
+    let valid = !key.is_empty()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if !valid {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Invalid preset key '{key}'"),
+        ));
+    }
+    if path.extension().unwrap_or_default() != PRESET_EXTENSION {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Preset key '{key}' must end with .{PRESET_EXTENSION}"),
+        ));
+    }
+    Ok(())
+}
+
+fn not_found(key: &str) -> Error {
+    Error::new(ErrorKind::NotFound, format!("There is no preset '{key}'"))
+}
+
+/// Presets in a folder and its subfolders. Only files with the preset
+/// extension are listed.
+#[derive(Clone, Debug)]
+pub struct FileStore {
+    root: PathBuf,
+}
+
+impl FileStore {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, Error> {
+        check_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+impl PresetStore for FileStore {
+    fn keys(&self) -> Result<Vec<String>, Error> {
+        if !self.root.exists() {
+            return Ok(Vec::new());
+        }
+        let mut keys: Vec<String> = preset_paths(&self.root)?
+            .iter()
+            .filter_map(|path| path.strip_prefix(&self.root).ok())
+            .map(|path| {
+                path.components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn load(&self, key: &str) -> Result<Vec<u8>, Error> {
+        let path = self.path(key)?;
+        fs::read(&path).map_err(|error| match error.kind() {
+            ErrorKind::NotFound => not_found(key),
+            _ => error,
+        })
+    }
+
+    fn save(&self, key: &str, contents: &[u8]) -> Result<(), Error> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)
+    }
+
+    fn remove(&self, key: &str) -> Result<(), Error> {
+        let path = self.path(key)?;
+        fs::remove_file(path).map_err(|error| match error.kind() {
+            ErrorKind::NotFound => not_found(key),
+            _ => error,
+        })
+    }
+}
+
+/// Presets kept in memory, such as presets downloaded or unpacked from a
+/// bank.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    presets: RwLock<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PresetStore for MemoryStore {
+    fn keys(&self) -> Result<Vec<String>, Error> {
+        let presets = self.presets.read().expect("store lock");
+        Ok(presets.keys().cloned().collect())
+    }
+
+    fn load(&self, key: &str) -> Result<Vec<u8>, Error> {
+        check_key(key)?;
+        let presets = self.presets.read().expect("store lock");
+        presets.get(key).cloned().ok_or_else(|| not_found(key))
+    }
+
+    fn save(&self, key: &str, contents: &[u8]) -> Result<(), Error> {
+        check_key(key)?;
+        let mut presets = self.presets.write().expect("store lock");
+        presets.insert(key.to_owned(), contents.to_vec());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), Error> {
+        check_key(key)?;
+        let mut presets = self.presets.write().expect("store lock");
+        presets
+            .remove(key)
+            .map(|_| ())
+            .ok_or_else(|| not_found(key))
+    }
+}
+
+/// Presets kept in a SQLite database, such as a bank of presets shipped as a
+/// single file. Each preset is a row with its key and contents.
+#[cfg(feature = "sqlite")]
+#[derive(Debug)]
+pub struct SqliteStore {
+    connection: Mutex<Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteStore {
+    const SCHEMA: &'static str = "
+        CREATE TABLE IF NOT EXISTS preset_store (
+            key TEXT PRIMARY KEY,
+            contents BLOB NOT NULL
+        );
+    ";
+
+    /// Open the database, creating it if it doesn't exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::with_connection(Connection::open(path).map_err(database_error)?)
+    }
+
+    /// A database that is only kept in memory.
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Self::with_connection(Connection::open_in_memory().map_err(database_error)?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, Error> {
+        connection
+            .execute_batch(Self::SCHEMA)
+            .map_err(database_error)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl PresetStore for SqliteStore {
+    fn keys(&self) -> Result<Vec<String>, Error> {
+        let connection = self.connection.lock().expect("store lock");
+        let mut statement = connection
+            .prepare("SELECT key FROM preset_store ORDER BY key")
+            .map_err(database_error)?;
+        let keys = statement
+            .query_map([], |row| row.get(0))
+            .and_then(|rows| rows.collect());
+        keys.map_err(database_error)
+    }
+
+    fn load(&self, key: &str) -> Result<Vec<u8>, Error> {
+        check_key(key)?;
+        let connection = self.connection.lock().expect("store lock");
+        connection
+            .query_row(
+                "SELECT contents FROM preset_store WHERE key = ?1",
+                [key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(database_error)?
+            .ok_or_else(|| not_found(key))
+    }
+
+    fn save(&self, key: &str, contents: &[u8]) -> Result<(), Error> {
+        check_key(key)?;
+        let connection = self.connection.lock().expect("store lock");
+        connection
+            .execute(
+                "INSERT INTO preset_store (key, contents) VALUES (?1, ?2)
+                ON CONFLICT (key) DO UPDATE SET contents = excluded.contents",
+                params![key, contents],
+            )
+            .map_err(database_error)?;
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), Error> {
+        check_key(key)?;
+        let connection = self.connection.lock().expect("store lock");
+        let removed = connection
+            .execute("DELETE FROM preset_store WHERE key = ?1", [key])
+            .map_err(database_error)?;
+        if removed == 0 {
+            return Err(not_found(key));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn check_store(store: &dyn PresetStore) {
+        assert!(store.keys().unwrap().is_empty());
+        let mut preset = Preset::default();
+        preset.metadata.author = Some("Someone".to_owned());
+        store.write_preset("Bass/Deep.phaseplant", &preset).unwrap();
+        store.write_preset("Pad.phaseplant", &preset).unwrap();
+        assert_eq!(
+            store.keys().unwrap(),
+            ["Bass/Deep.phaseplant", "Pad.phaseplant"]
+        );
+
+        let read = store.read_preset("Bass/Deep.phaseplant").unwrap();
+        assert_eq!(read.metadata.name.as_deref(), Some("Deep"));
+        assert_eq!(read.metadata.author, preset.metadata.author);
+
+        store.remove("Pad.phaseplant").unwrap();
+        assert_eq!(store.keys().unwrap(), ["Bass/Deep.phaseplant"]);
+        let error = store.load("Pad.phaseplant").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+        let error = store.save("../Escape.phaseplant", &[]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        for key in ["", "Bass/Deep.txt", "Bass/Deep", "../Pad.phaseplant"] {
+            assert_eq!(
+                store.save(key, &[]).unwrap_err().kind(),
+                ErrorKind::InvalidInput,
+                "{key}"
+            );
+            assert_eq!(
+                store.load(key).unwrap_err().kind(),
+                ErrorKind::InvalidInput,
+                "{key}"
+            );
+            assert_eq!(
+                store.remove(key).unwrap_err().kind(),
+                ErrorKind::InvalidInput,
+                "{key}"
+            );
+        }
+    }
+
+    #[test]
+    fn stores() {
+        check_store(&MemoryStore::new());
+
+        let dir = std::env::temp_dir().join(format!("preset_store-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        check_store(&FileStore::new(&dir));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_store() {
+        check_store(&SqliteStore::open_in_memory().unwrap());
+
+        let path = std::env::temp_dir().join(format!("preset_store-{}.sqlite", std::process::id()));
+        let _ = fs::remove_file(&path);
+        SqliteStore::open(&path)
+            .unwrap()
+            .write_preset("Lead.phaseplant", &Preset::default())
+            .unwrap();
+        let store = SqliteStore::open(&path).unwrap();
+        assert_eq!(store.keys().unwrap(), ["Lead.phaseplant"]);
+        assert!(store.read_preset("Lead.phaseplant").is_ok());
+        drop(store);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -16,6 +16,30 @@ impl dyn HashTag {
     }
 }
 
+/// The start of the text with at most `max_chars` characters. A character is
+/// never split.
+pub(crate) fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((index, _)) => &text[..index],
+        None => text,
+    }
+}
+
+/// Names are shown on a single line so they can't contain control
+/// characters such as line breaks.
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    !name.chars().any(char::is_control)
+}
+
+/// Replace the control characters in a name with spaces and trim it.
+pub(crate) fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect::<String>()
+        .trim()
+        .to_owned()
+}
+
 pub trait TextOptionExt<T> {
     /// Trim the string inside the `Option`. Returns `Some` if there is text, otherwise `None`.
     fn trim(&self) -> Option<T>;
@@ -76,4 +100,23 @@ mod test {
         assert_eq!(Some(" ".to_owned()).trim(), Some("".to_owned()));
         assert_eq!(Some(" \t ".to_owned()).trim(), Some("".to_owned()));
     }
+
+    #[test]
+    fn truncate_chars() {
+        assert_eq!(super::truncate_chars("abc", 2), "ab");
+        assert_eq!(super::truncate_chars("abc", 5), "abc");
+        assert_eq!(super::truncate_chars("", 0), "");
+
+        // Multibyte characters are not split.
+        assert_eq!(super::truncate_chars("💖💖💖", 2), "💖💖");
+        assert_eq!(super::truncate_chars("aé", 1), "a");
+    }
+
+    #[test]
+    fn names() {
+        assert!(is_valid_name("Bass 💖"));
+        assert!(!is_valid_name("Bass\nLead"));
+        assert_eq!(sanitize_name(" Bass\nLead\t"), "Bass Lead");
+        assert_eq!(sanitize_name("Bass\0"), "Bass");
+    }
 }
@@ -0,0 +1,307 @@
+//! Record which parameters of a preset were changed.
+//!
+//! Editors need to know if a preset has unsaved changes and how to undo
+//! them. Comparing the whole preset with the one that was loaded after every
+//! edit is slow, so [`Tracked`] makes the changes through
+//! [parameter paths](Preset::set) or [commands](Command) and records each
+//! one as it happens.
+//!
+//! ```
+//! use synthahol_phase_plant::tracked::Tracked;
+//! use synthahol_phase_plant::Preset;
+//!
+//! let mut preset = Tracked::new(Preset::default());
+//! preset.set("master_gain", "-6 dB").unwrap();
+//! assert!(preset.is_modified());
+//! assert_eq!(preset.modified_paths().collect::<Vec<_>>(), ["master_gain"]);
+//!
+//! preset.undo().unwrap();
+//! assert!(!preset.is_modified());
+//! ```
+
+use std::collections::BTreeMap;
+use std::io::Error;
+use std::ops::Deref;
+
+use crate::diff::Change;
+use crate::edit::{Command, StoredValue};
+use crate::modulation::Modulation;
+use crate::{Preset, Snapin};
+
+/// A value with a history of the changes made to it. Only shared access to
+/// the value is given so every change is recorded.
+#[derive(Clone, Debug)]
+pub struct Tracked<T> {
+    value: T,
+
+    /// What the modified parts of the value were when it was loaded or last
+    /// saved, by the [target](Command::target) of the commands.
+    saved: BTreeMap<String, Snapshot>,
+
+    undo: Vec<Command>,
+    redo: Vec<Command>,
+}
+
+/// The part of a preset a command changes.
+#[derive(Clone, Debug, PartialEq)]
+enum Snapshot {
+    Parameter(StoredValue),
+    Snapins(Vec<Snapin>),
+    Modulations(Vec<Modulation>),
+}
+
+impl<T> Tracked<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            saved: BTreeMap::new(),
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// If anything is different from when the value was loaded or
+    /// [saved](Self::mark_saved).
+    pub fn is_modified(&self) -> bool {
+        !self.saved.is_empty()
+    }
+
+    /// Paths of the parameters and lists that are different from when the
+    /// value was loaded or saved, in order.
+    pub fn modified_paths(&self) -> impl Iterator<Item = &str> {
+        self.saved.keys().map(String::as_str)
+    }
+
+    /// Commands that can be undone, oldest first.
+    pub fn history(&self) -> &[Command] {
+        &self.undo
+    }
+
+    /// Make the current values the ones that are compared with. The history
+    /// is kept so changes from before the save can still be undone.
+    pub fn mark_saved(&mut self) {
+        self.saved.clear();
+    }
+}
+
+impl Tracked<Preset> {
+    /// Change the parameter at the path the same way as [`Preset::set`] and
+    /// return the change. Nothing is recorded if the value didn't change.
+    /// Clears the commands that could be redone.
+    pub fn set(&mut self, path: &str, value: &str) -> Result<Option<Change>, Error> {
+        let command = Command::set_parameter(&self.value, path, value)?;
+        Ok(self.execute(command)?.and_then(Command::change))
+    }
+
+    /// Apply the command and return it, or `None` if it didn't change
+    /// anything. Clears the commands that could be redone.
+    pub fn execute(&mut self, command: Command) -> Result<Option<&Command>, Error> {
+        if !self.run(&command)? {
+            return Ok(None);
+        }
+        self.redo.clear();
+        self.undo.push(command);
+        Ok(self.undo.last())
+    }
+
+    /// Revert the most recent command and return it, or `None` if there is
+    /// nothing to undo.
+    pub fn undo(&mut self) -> Result<Option<&Command>, Error> {
+        let Some(command) = self.undo.pop() else {
+            return Ok(None);
+        };
+        if let Err(error) = self.run(&command.inverse()) {
+            self.undo.push(command);
+            return Err(error);
+        }
+        self.redo.push(command);
+        Ok(self.redo.last())
+    }
+
+    /// Apply the most recently undone command again and return it, or `None`
+    /// if there is nothing to redo.
+    pub fn redo(&mut self) -> Result<Option<&Command>, Error> {
+        let Some(command) = self.redo.pop() else {
+            return Ok(None);
+        };
+        if let Err(error) = self.run(&command) {
+            self.redo.push(command);
+            return Err(error);
+        }
+        self.undo.push(command);
+        Ok(self.undo.last())
+    }
+
+    /// Apply the command and update what is modified. Returns `false` if
+    /// nothing changed.
+    fn run(&mut self, command: &Command) -> Result<bool, Error> {
+        let before = self.snapshot(command)?;
+        self.value.apply(command)?;
+        let after = self.snapshot(command)?;
+        if before == after {
+            return Ok(false);
+        }
+        let target = command.target();
+        match self.saved.get(&target) {
+            Some(saved) if saved == &after => {
+                self.saved.remove(&target);
+            }
+            Some(_) => {}
+            None => {
+                self.saved.insert(target, before);
+            }
+        }
+        Ok(true)
+    }
+
+    fn snapshot(&self, command: &Command) -> Result<Snapshot, Error> {
+        Ok(match command {
+            Command::SetParameter { path, .. } => Snapshot::Parameter(self.value.stored(path)?),
+            Command::AddSnapin { lane, .. } | Command::RemoveSnapin { lane, .. } => {
+                Snapshot::Snapins(
+                    self.value
+                        .lanes
+                        .get(*lane)
+                        .map(|lane| lane.snapins.clone())
+                        .unwrap_or_default(),
+                )
+            }
+            Command::AddModulation { .. } | Command::RemoveModulation { .. } => {
+                Snapshot::Modulations(self.value.modulations.clone())
+            }
+        })
+    }
+}
+
+impl<T> Deref for Tracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> From<T> for Tracked<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::test::{read_effect_preset, read_preset};
+
+    use super::*;
+
+    #[test]
+    fn undo_redo() {
+        let preset = read_effect_preset("filter", "filter-2.0.16.phaseplant").unwrap();
+        let mut tracked = Tracked::new(preset.clone());
+        let change = tracked.set("lanes.0.mix", "25%").unwrap().unwrap();
+        assert_eq!(change.path, "lanes.0.mix");
+        assert_relative_eq!(change.delta.unwrap(), -75.0);
+        assert!(tracked.set("lanes.0.mix", "25%").unwrap().is_none());
+        tracked.set("master_pitch", "2 st").unwrap();
+        tracked.set("lanes.0.mix", "50%").unwrap();
+        assert_eq!(tracked.history().len(), 3);
+        assert_eq!(
+            tracked.modified_paths().collect::<Vec<_>>(),
+            ["lanes.0.mix", "master_pitch"]
+        );
+        assert!(tracked.set("lanes.0.snapins.0.nothing", "1").is_err());
+
+        // Setting a parameter back to the loaded value isn't a modification.
+        tracked.set("master_pitch", "0").unwrap();
+        assert_eq!(
+            tracked.modified_paths().collect::<Vec<_>>(),
+            ["lanes.0.mix"]
+        );
+
+        tracked.undo().unwrap();
+        assert_eq!(tracked.get("master_pitch").unwrap().to_string(), "2 st");
+        let redone = tracked.redo().unwrap().unwrap();
+        let after = redone.change().unwrap().after.unwrap();
+        assert_eq!(after.to_string(), "0 st");
+        assert!(tracked.redo().unwrap().is_none());
+
+        while tracked.undo().unwrap().is_some() {}
+        assert!(!tracked.is_modified());
+        assert_eq!(tracked.into_inner(), preset);
+    }
+
+    #[test]
+    fn commands() {
+        let preset = read_effect_preset("filter", "filter-2.0.16.phaseplant").unwrap();
+        let mut tracked = Tracked::new(preset.clone());
+        let remove = Command::remove_snapin(&tracked, 0, 0).unwrap();
+        tracked.execute(remove.clone()).unwrap();
+        assert!(tracked.lanes[0].snapins.is_empty());
+        assert_eq!(
+            tracked.modified_paths().collect::<Vec<_>>(),
+            ["lanes.0.snapins"]
+        );
+
+        // Failed commands aren't recorded.
+        assert!(tracked.execute(remove.clone()).is_err());
+        assert_eq!(tracked.history(), std::slice::from_ref(&remove));
+
+        tracked.execute(remove.inverse()).unwrap();
+        assert!(!tracked.is_modified());
+        tracked.undo().unwrap();
+        assert!(tracked.is_modified());
+        tracked.undo().unwrap();
+        assert_eq!(tracked.into_inner(), preset);
+    }
+
+    #[test]
+    fn mark_saved() {
+        let mut tracked = Tracked::new(Preset::default());
+        tracked.set("master_gain", "-6 dB").unwrap();
+        tracked.mark_saved();
+        assert!(!tracked.is_modified());
+
+        // Undoing a saved change modifies the preset again.
+        tracked.undo().unwrap();
+        assert_eq!(
+            tracked.modified_paths().collect::<Vec<_>>(),
+            ["master_gain"]
+        );
+
+        // A new change can't be redone.
+        tracked.redo().unwrap();
+        tracked.undo().unwrap();
+        tracked.set("master_pitch", "1 st").unwrap();
+        assert!(tracked.redo().unwrap().is_none());
+    }
+
+    #[test]
+    fn undo_master_gain() {
+        // Gains turned all the way down can be undone.
+        let preset = read_preset("misc", "master-gain--inf-1.8.13.phaseplant");
+        assert_eq!(preset.get("master_gain").unwrap().to_string(), "-inf dB");
+        let mut tracked = Tracked::new(preset.clone());
+        tracked.set("master_gain", "1").unwrap();
+        tracked.undo().unwrap();
+        assert!(!tracked.is_modified());
+        assert_eq!(tracked.into_inner(), preset);
+
+        // Gains are restored exactly rather than from their decibels.
+        let preset = read_preset("misc", "master-gain--20db-1.8.13.phaseplant");
+        let mut tracked = Tracked::new(preset.clone());
+        tracked.set("master_gain", "-6 dB").unwrap();
+        tracked.undo().unwrap();
+        assert!(!tracked.is_modified());
+        assert_eq!(tracked.master_gain, preset.master_gain);
+        assert_eq!(tracked.into_inner(), preset);
+
+        let mut tracked = Tracked::new(Preset::default());
+        tracked.set("master_gain", "-inf dB").unwrap();
+        assert_eq!(tracked.get("master_gain").unwrap().to_string(), "-inf dB");
+    }
+}
@@ -1,11 +1,18 @@
 use std::io::{Error, ErrorKind};
 
-use strum_macros::FromRepr;
+use strum_macros::{Display, EnumIter, FromRepr};
 use uom::num::Zero;
 use uom::si::f32::Ratio;
 use uom::si::ratio::percent;
 
-#[derive(Copy, Clone, Debug, FromRepr, Eq, PartialEq)]
+use crate::diff::Unit;
+use crate::generator::{
+    AnalogOscillator, Generator, GeneratorMode, SamplePlayer, WavetableOscillator,
+};
+use crate::path::{impl_parameters, join, Field, FieldMut, Parameters};
+use crate::Preset;
+
+#[derive(Copy, Clone, Debug, Display, EnumIter, FromRepr, Eq, PartialEq)]
 #[repr(u32)]
 pub enum UnisonMode {
     // The discriminants correspond to the file format.
@@ -35,11 +42,85 @@ pub enum UnisonMode {
     Harmonics = 14,
 }
 
+impl_parameters!(UnisonMode);
+
 impl UnisonMode {
     pub(crate) fn from_id(id: u32) -> Result<Self, Error> {
         Self::from_repr(id)
             .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("Unknown unison mode {id}")))
     }
+
+    /// Semitones above the note of each note of the chord that the voices
+    /// play in turn. The intervals are the textbook ones for the chord each
+    /// mode is named after in the Phase Plant interface. Not set for
+    /// Harmonics or the unison and creative modes.
+    pub fn chord_intervals(&self) -> Option<&'static [u8]> {
+        use UnisonMode::*;
+        match self {
+            Octaves => Some(&[0]),
+            Fifths => Some(&[0, 7]),
+            Minor => Some(&[0, 3, 7]),
+            Minor7 => Some(&[0, 3, 7, 10]),
+            MinorMaj7 => Some(&[0, 3, 7, 11]),
+            Major => Some(&[0, 4, 7]),
+            Major7 => Some(&[0, 4, 7, 10]),
+            MajorMaj7 => Some(&[0, 4, 7, 11]),
+            Sus2 => Some(&[0, 2, 7]),
+            Sus4 => Some(&[0, 5, 7]),
+            Dim => Some(&[0, 3, 6]),
+            Hard | Smooth | Synthetic | FreqStack | PitchStack | Shepard | Harmonics => None,
+        }
+    }
+
+    /// Approximate pitch of each voice in cents from the note, in the order
+    /// of the voices. Phase Plant doesn't document its spreads and they
+    /// haven't been measured from rendered audio, so these are only as good
+    /// as what the mode names promise.
+    ///
+    /// * Hard and Smooth are approximated by spreading the voices evenly
+    ///   from `-detune_cents` to `detune_cents`.
+    /// * Harmonics plays the harmonic series of the note.
+    /// * The chords play the [intervals](Self::chord_intervals) without
+    ///   detuning, assuming each repeat of the chord is an octave higher.
+    ///
+    /// Not set for Synthetic, Freq Stack, Pitch Stack and Shepard because
+    /// their spread isn't known or changes over time.
+    ///
+    /// ```
+    /// use synthahol_phase_plant::UnisonMode;
+    ///
+    /// let major = UnisonMode::Major.voice_pitches(4, 25.0).unwrap();
+    /// assert_eq!(major, [0.0, 400.0, 700.0, 1200.0]);
+    /// ```
+    pub fn voice_pitches(&self, voices: u32, detune_cents: f32) -> Option<Vec<f32>> {
+        use UnisonMode::*;
+        let voices = 0..voices;
+        let pitches = match self {
+            Hard | Smooth if voices.end == 1 => vec![0.0],
+            Hard | Smooth => {
+                let last = voices.end.saturating_sub(1) as f32;
+                voices
+                    .map(|voice| detune_cents * (2.0 * voice as f32 / last - 1.0))
+                    .collect()
+            }
+            Harmonics => voices
+                .map(|voice| 1200.0 * ((voice + 1) as f32).log2())
+                .collect(),
+            Synthetic | FreqStack | PitchStack | Shepard => return None,
+            _ => {
+                let intervals = self.chord_intervals()?;
+                let count = intervals.len() as u32;
+                voices
+                    .map(|voice| {
+                        let octave = voice / count;
+                        let interval = intervals[(voice % count) as usize] as u32;
+                        100.0 * (interval + 12 * octave) as f32
+                    })
+                    .collect()
+            }
+        };
+        Some(pitches)
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -59,8 +140,132 @@ pub struct Unison {
     pub bias: Ratio,
 }
 
+/// The detune is named without its unit, the same as in Phase Plant.
+impl Parameters for Unison {
+    fn parameters<'a>(&'a self, path: &str, parameters: &mut Vec<(String, Field<'a>)>) {
+        parameters.extend([
+            (join(path, "enabled"), Field::Bool(&self.enabled)),
+            (join(path, "voices"), Field::Count(&self.voices)),
+            (join(path, "mode"), Field::Choice(&self.mode)),
+            (
+                join(path, "detune"),
+                Field::Number(&self.detune_cents, Unit::Cents),
+            ),
+            (join(path, "spread"), Field::Percent(&self.spread)),
+            (join(path, "blend"), Field::Percent(&self.blend)),
+            (join(path, "bias"), Field::Percent(&self.bias)),
+        ]);
+    }
+
+    fn parameters_mut<'a>(&'a mut self, path: &str, parameters: &mut Vec<(String, FieldMut<'a>)>) {
+        parameters.extend([
+            (join(path, "enabled"), FieldMut::Bool(&mut self.enabled)),
+            (join(path, "voices"), FieldMut::Count(&mut self.voices)),
+            (join(path, "mode"), FieldMut::Choice(&mut self.mode)),
+            (
+                join(path, "detune"),
+                FieldMut::Number(&mut self.detune_cents, Unit::Cents),
+            ),
+            (join(path, "spread"), FieldMut::Percent(&mut self.spread)),
+            (join(path, "blend"), FieldMut::Percent(&mut self.blend)),
+            (join(path, "bias"), FieldMut::Percent(&mut self.bias)),
+        ]);
+    }
+}
+
 impl Unison {
-    pub const VOICES_MAX: u32 = 8; // As of Phase Plant 1.8.20
+    pub const VOICES_MAX: u32 = crate::limits::UNISON_VOICES_MAX;
+
+    /// Pitch of each voice in cents from the note. A single voice is played
+    /// at the note if unison is disabled. See [`UnisonMode::voice_pitches`].
+    pub fn voice_pitches(&self) -> Option<Vec<f32>> {
+        if !self.enabled {
+            return Some(vec![0.0]);
+        }
+        self.mode.voice_pitches(self.voices, self.detune_cents)
+    }
+}
+
+impl Unison {
+    /// Voices played for each note, one if unison is disabled.
+    pub fn voice_count(&self) -> u32 {
+        if self.enabled {
+            self.voices.max(1)
+        } else {
+            1
+        }
+    }
+}
+
+/// The most Phase Plant renders at once when every note is played, from
+/// the settings in the preset. Modulators aren't included.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VoiceEstimate {
+    /// Notes that can play at once.
+    pub notes: u32,
+
+    /// Voices with the unison of the preset.
+    pub voices: u32,
+
+    /// Sounds made by the enabled generators in every voice, with the unison
+    /// of each generator.
+    pub generator_voices: u32,
+
+    /// Enabled lanes that process each voice separately.
+    pub poly_lanes: u32,
+}
+
+impl Preset {
+    /// If the snapins of the lane with the zero-based index process each
+    /// voice separately. The first lane holds how many lanes from left to
+    /// right are poly.
+    pub fn is_lane_poly(&self, index: usize) -> bool {
+        self.lanes
+            .first()
+            .is_some_and(|lane| index < lane.poly_count as usize)
+    }
+
+    pub fn voices_estimate(&self) -> VoiceEstimate {
+        let notes = self.polyphony.max(1);
+        let voices = notes * self.unison.voice_count();
+        let sounds_per_voice: u32 = self
+            .generators
+            .iter()
+            .filter(|generator| generator.is_enabled())
+            .map(|generator| generator_sounds(generator.as_ref()))
+            .sum();
+        let poly_lanes = self
+            .lanes
+            .iter()
+            .enumerate()
+            .filter(|(index, lane)| lane.enabled && self.is_lane_poly(*index))
+            .count() as u32;
+        VoiceEstimate {
+            notes,
+            voices,
+            generator_voices: voices * sounds_per_voice,
+            poly_lanes,
+        }
+    }
+}
+
+/// How many sounds a generator makes for each voice. Effects, groups and
+/// routing don't make sounds of their own.
+fn generator_sounds(generator: &dyn Generator) -> u32 {
+    let unison = match generator.mode() {
+        GeneratorMode::AnalogOscillator => generator
+            .downcast_ref::<AnalogOscillator>()
+            .map(|oscillator| oscillator.unison),
+        GeneratorMode::SamplePlayer => generator
+            .downcast_ref::<SamplePlayer>()
+            .map(|player| player.unison),
+        GeneratorMode::WavetableOscillator => generator
+            .downcast_ref::<WavetableOscillator>()
+            .map(|oscillator| oscillator.unison),
+        GeneratorMode::GranularGenerator | GeneratorMode::NoiseGenerator => return 1,
+        _ => return 0,
+    };
+    unison.map_or(1, |unison| unison.voice_count())
 }
 
 impl Default for Unison {
@@ -76,3 +281,87 @@ impl Default for Unison {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::generator::{FilterEffect, NoiseGenerator};
+
+    use super::*;
+
+    #[test]
+    fn voices_estimate() {
+        let mut preset = Preset {
+            polyphony: 4,
+            ..Default::default()
+        };
+        preset.unison.enabled = true;
+        preset.unison.voices = 2;
+        preset.lanes[0].poly_count = 2;
+        preset.lanes[1].enabled = false;
+        preset.generators = vec![
+            Box::new(AnalogOscillator::default()),
+            Box::new(WavetableOscillator {
+                unison: Unison {
+                    enabled: true,
+                    voices: 3,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            Box::new(NoiseGenerator::default()),
+            Box::new(AnalogOscillator {
+                enabled: false,
+                ..Default::default()
+            }),
+            Box::new(FilterEffect::default()),
+        ];
+        assert_eq!(
+            preset.voices_estimate(),
+            VoiceEstimate {
+                notes: 4,
+                voices: 8,
+                generator_voices: 8 * (1 + 3 + 1),
+                poly_lanes: 1,
+            }
+        );
+        assert_eq!(Preset::default().voices_estimate().generator_voices, 0);
+    }
+
+    #[test]
+    fn voice_pitches() {
+        use UnisonMode::*;
+        assert_eq!(Hard.voice_pitches(3, 25.0).unwrap(), [-25.0, 0.0, 25.0]);
+        assert_eq!(
+            Smooth.voice_pitches(5, 10.0).unwrap(),
+            [-10.0, -5.0, 0.0, 5.0, 10.0]
+        );
+        assert_eq!(Hard.voice_pitches(1, 25.0).unwrap(), [0.0]);
+        assert!(Hard.voice_pitches(0, 25.0).unwrap().is_empty());
+        assert_eq!(
+            Octaves.voice_pitches(3, 25.0).unwrap(),
+            [0.0, 1200.0, 2400.0]
+        );
+        assert_eq!(
+            Minor7.voice_pitches(6, 25.0).unwrap(),
+            [0.0, 300.0, 700.0, 1000.0, 1200.0, 1500.0]
+        );
+        let harmonics = Harmonics.voice_pitches(4, 25.0).unwrap();
+        assert_eq!(harmonics[..2], [0.0, 1200.0]);
+        assert_relative_eq!(harmonics[2], 1901.955, epsilon = 0.001);
+        assert_eq!(harmonics[3], 2400.0);
+        for mode in [Synthetic, FreqStack, PitchStack, Shepard] {
+            assert!(mode.voice_pitches(4, 25.0).is_none());
+            assert!(mode.chord_intervals().is_none());
+        }
+
+        let unison = Unison::default();
+        assert_eq!(unison.voice_pitches().unwrap(), [0.0]);
+        let unison = Unison {
+            enabled: true,
+            ..unison
+        };
+        assert_eq!(unison.voice_pitches().unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,376 @@
+//! Variations of a preset that only store what they change.
+//!
+//! Generating many near-identical presets, such as velocity layers or round
+//! robins, by cloning a preset for each one duplicates everything the preset
+//! contains, including the sample data of every Sampler. A
+//! [`PresetVariation`] shares its base preset and keeps a list of
+//! [overrides](Override) instead. The full preset is only built when
+//! [`materialize`](PresetVariation::materialize) is called.
+
+use std::fmt::{Debug, Formatter};
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+use uom::si::f32::Ratio;
+
+use crate::effect::Effect;
+use crate::generator::Generator;
+use crate::modulator::Modulator;
+use crate::*;
+
+/// Change any part of a preset. Used by [`Override::Edit`].
+pub type PresetEdit = Arc<dyn Fn(&mut Preset) + Send + Sync>;
+
+/// A parameter that is different from the base preset.
+#[derive(Clone)]
+pub enum Override {
+    MasterGain(Decibels),
+    MasterPitch(f32),
+    ModWheel(Ratio),
+    Polyphony(u32),
+    GlideTime(f32),
+    MacroValue {
+        index: usize,
+        value: f32,
+    },
+    LaneGain {
+        lane_index: usize,
+        gain: Decibels,
+    },
+    SnapinEnabled {
+        lane_index: usize,
+        snapin_index: usize,
+        enabled: bool,
+    },
+
+    /// Replace the effect of a snapin.
+    Effect {
+        lane_index: usize,
+        snapin_index: usize,
+        effect: Box<dyn Effect>,
+    },
+
+    /// Replace a generator. Prefer [`Edit`](Self::Edit) for changing a
+    /// Sampler so the sample data is not stored again.
+    Generator {
+        index: usize,
+        generator: Box<dyn Generator>,
+    },
+
+    /// Replace the modulator of a modulator container.
+    Modulator {
+        index: usize,
+        modulator: Box<dyn Modulator>,
+    },
+
+    /// Change parameters that don't have their own override.
+    Edit(PresetEdit),
+}
+
+impl Debug for Override {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Override::*;
+        match self {
+            MasterGain(gain) => f.debug_tuple("MasterGain").field(gain).finish(),
+            MasterPitch(pitch) => f.debug_tuple("MasterPitch").field(pitch).finish(),
+            ModWheel(value) => f.debug_tuple("ModWheel").field(value).finish(),
+            Polyphony(polyphony) => f.debug_tuple("Polyphony").field(polyphony).finish(),
+            GlideTime(time) => f.debug_tuple("GlideTime").field(time).finish(),
+            MacroValue { index, value } => f
+                .debug_struct("MacroValue")
+                .field("index", index)
+                .field("value", value)
+                .finish(),
+            LaneGain { lane_index, gain } => f
+                .debug_struct("LaneGain")
+                .field("lane_index", lane_index)
+                .field("gain", gain)
+                .finish(),
+            SnapinEnabled {
+                lane_index,
+                snapin_index,
+                enabled,
+            } => f
+                .debug_struct("SnapinEnabled")
+                .field("lane_index", lane_index)
+                .field("snapin_index", snapin_index)
+                .field("enabled", enabled)
+                .finish(),
+            Effect {
+                lane_index,
+                snapin_index,
+                effect,
+            } => f
+                .debug_struct("Effect")
+                .field("lane_index", lane_index)
+                .field("snapin_index", snapin_index)
+                .field("effect", effect)
+                .finish(),
+            Generator { index, generator } => f
+                .debug_struct("Generator")
+                .field("index", index)
+                .field("generator", generator)
+                .finish(),
+            Modulator { index, modulator } => f
+                .debug_struct("Modulator")
+                .field("index", index)
+                .field("modulator", modulator)
+                .finish(),
+            Edit(_) => f.write_str("Edit(..)"),
+        }
+    }
+}
+
+impl Override {
+    fn apply(&self, preset: &mut Preset) -> Result<(), Error> {
+        use Override::*;
+        match self {
+            MasterGain(gain) => preset.master_gain = gain.linear(),
+            MasterPitch(pitch) => preset.master_pitch = *pitch,
+            ModWheel(value) => preset.mod_wheel_value = *value,
+            Polyphony(polyphony) => preset.polyphony = *polyphony,
+            GlideTime(time) => preset.glide_time = *time,
+            MacroValue { index, value } => {
+                let macro_control = preset
+                    .macro_controls
+                    .get_mut(*index)
+                    .ok_or_else(|| out_of_range("macro control", *index))?;
+                macro_control.value = *value;
+            }
+            LaneGain { lane_index, gain } => {
+                lane(preset, *lane_index)?.gain = *gain;
+            }
+            SnapinEnabled {
+                lane_index,
+                snapin_index,
+                enabled,
+            } => snapin(preset, *lane_index, *snapin_index)?.enabled = *enabled,
+            Effect {
+                lane_index,
+                snapin_index,
+                effect,
+            } => snapin(preset, *lane_index, *snapin_index)?.effect = effect.clone(),
+            Generator { index, generator } => {
+                let existing = preset
+                    .generators
+                    .get_mut(*index)
+                    .ok_or_else(|| out_of_range("generator", *index))?;
+                *existing = generator.clone();
+            }
+            Modulator { index, modulator } => {
+                let container = preset
+                    .modulator_containers
+                    .get_mut(*index)
+                    .ok_or_else(|| out_of_range("modulator", *index))?;
+                container.modulator = modulator.clone();
+            }
+            Edit(edit) => edit(preset),
+        }
+        Ok(())
+    }
+}
+
+fn out_of_range(kind: &str, index: usize) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("The base preset does not have {kind} {index}"),
+    )
+}
+
+fn lane(preset: &mut Preset, lane_index: usize) -> Result<&mut Lane, Error> {
+    preset
+        .lanes
+        .get_mut(lane_index)
+        .ok_or_else(|| out_of_range("lane", lane_index))
+}
+
+fn snapin(
+    preset: &mut Preset,
+    lane_index: usize,
+    snapin_index: usize,
+) -> Result<&mut Snapin, Error> {
+    lane(preset, lane_index)?
+        .snapins
+        .get_mut(snapin_index)
+        .ok_or_else(|| out_of_range(&format!("snapin in lane {lane_index}"), snapin_index))
+}
+
+/// A preset described by the parameters it overrides in a shared base preset.
+#[derive(Clone, Debug)]
+pub struct PresetVariation {
+    base: Arc<Preset>,
+    overrides: Vec<Override>,
+}
+
+impl PresetVariation {
+    pub fn new(base: Arc<Preset>) -> Self {
+        Self {
+            base,
+            overrides: Vec::new(),
+        }
+    }
+
+    pub fn base(&self) -> &Arc<Preset> {
+        &self.base
+    }
+
+    /// Overrides in the order they are applied.
+    pub fn overrides(&self) -> &[Override] {
+        &self.overrides
+    }
+
+    /// Add an override. Later overrides of the same parameter win.
+    pub fn push(&mut self, parameter: Override) {
+        self.overrides.push(parameter);
+    }
+
+    #[must_use]
+    pub fn with(mut self, parameter: Override) -> Self {
+        self.push(parameter);
+        self
+    }
+
+    /// Add an override that can change any part of the preset.
+    #[must_use]
+    pub fn with_edit<F>(self, edit: F) -> Self
+    where
+        F: Fn(&mut Preset) + Send + Sync + 'static,
+    {
+        self.with(Override::Edit(Arc::new(edit)))
+    }
+
+    /// If the variation is the same as the base preset.
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    /// Build the full preset by applying the overrides to a copy of the base
+    /// preset. Fails if an override refers to something the base preset
+    /// doesn't have.
+    pub fn materialize(&self) -> Result<Preset, Error> {
+        let mut preset = Preset::clone(&self.base);
+        for parameter in &self.overrides {
+            parameter.apply(&mut preset)?;
+        }
+        Ok(preset)
+    }
+}
+
+impl Preset {
+    /// Start a variation of the preset. The preset is shared by every
+    /// variation created from the same [`Arc`].
+    pub fn variation(self: &Arc<Self>) -> PresetVariation {
+        PresetVariation::new(Arc::clone(self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use uom::si::ratio::percent;
+
+    use crate::effect::{Distortion, Gain};
+    use crate::generator::SamplePlayer;
+    use crate::test::{read_effect_preset, read_generator_preset};
+
+    use super::*;
+
+    #[test]
+    fn materialize() {
+        let base = Arc::new(read_effect_preset("gain", "gain-2.0.16.phaseplant").unwrap());
+        let variation = base
+            .variation()
+            .with(Override::MasterGain(Decibels::new(-6.0)))
+            .with(Override::MacroValue {
+                index: 2,
+                value: 0.25,
+            })
+            .with(Override::Polyphony(4))
+            .with(Override::Polyphony(2))
+            .with(Override::SnapinEnabled {
+                lane_index: 0,
+                snapin_index: 0,
+                enabled: false,
+            });
+        assert_eq!(variation.overrides().len(), 5);
+
+        let preset = variation.materialize().unwrap();
+        assert_relative_eq!(preset.master_gain, Decibels::new(-6.0).linear());
+        assert_eq!(preset.macro_controls[2].value, 0.25);
+        assert_eq!(preset.polyphony, 2);
+        assert!(!preset.lanes[0].snapins[0].enabled);
+
+        // The base is unchanged.
+        assert_eq!(base.polyphony, Preset::default().polyphony);
+        assert!(base.lanes[0].snapins[0].enabled);
+        assert!(base.variation().is_empty());
+        assert_eq!(base.variation().materialize().unwrap(), *base);
+    }
+
+    #[test]
+    fn replace() {
+        let base = Arc::new(read_effect_preset("gain", "gain-2.0.16.phaseplant").unwrap());
+        let variation = base.variation().with(Override::Effect {
+            lane_index: 0,
+            snapin_index: 0,
+            effect: Box::new(Distortion::default()),
+        });
+        let preset = variation.materialize().unwrap();
+        assert!(preset.lanes[0].snapins[0]
+            .effect
+            .downcast_ref::<Distortion>()
+            .is_some());
+        assert!(base.lanes[0].snapins[0]
+            .effect
+            .downcast_ref::<Gain>()
+            .is_some());
+    }
+
+    #[test]
+    fn edit() {
+        let base = Arc::new(
+            read_generator_preset(
+                "sample_player",
+                "sample_player-custom-sample-1.8.16.phaseplant",
+            )
+            .unwrap(),
+        );
+        let variation = base.variation().with_edit(|preset| {
+            preset.generators[1]
+                .downcast_mut::<SamplePlayer>()
+                .unwrap()
+                .offset_position = Ratio::new::<percent>(25.0);
+        });
+        let preset = variation.materialize().unwrap();
+        let sample_player: &SamplePlayer = preset.generator(1).unwrap();
+        assert_relative_eq!(sample_player.offset_position.get::<percent>(), 25.0);
+        let base_sample_player: &SamplePlayer = base.generator(1).unwrap();
+        assert_eq!(sample_player.sample_contents.len(), 217344);
+        assert_eq!(
+            sample_player.sample_contents,
+            base_sample_player.sample_contents
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        let base = Arc::new(Preset::default());
+        let error = base
+            .variation()
+            .with(Override::LaneGain {
+                lane_index: 3,
+                gain: Decibels::ZERO,
+            })
+            .materialize()
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert!(base
+            .variation()
+            .with(Override::MacroValue {
+                index: MacroControl::COUNT,
+                value: 0.0
+            })
+            .materialize()
+            .is_err());
+    }
+}
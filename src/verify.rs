@@ -0,0 +1,215 @@
+//! Check that the samples and wavetables a preset references haven't changed.
+//!
+//! Samples that aren't embedded are only referenced by their path so
+//! replacing the file changes how the preset sounds without any sign in the
+//! preset. [`Preset::record_sample_hashes`] stores a hash of each referenced
+//! file in the metadata, where Phase Plant ignores it, and
+//! [`Preset::verify_samples`] compares the files with the hashes later.
+
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+
+use serde_json::{Map, Value};
+
+use crate::library::{asset, fnv1a, AssetKind, AssetLocation};
+use crate::relink::{asset_locations, SampleResolver};
+use crate::Preset;
+
+/// Field of the metadata that holds the hashes by path.
+const METADATA_FIELD: &str = "sampleHashes";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SampleStatus {
+    /// The file has the contents it had when the hash was recorded.
+    Unchanged,
+
+    /// The file was replaced or edited.
+    Changed { expected: u64, actual: u64 },
+
+    /// The file can't be read.
+    Missing(ErrorKind),
+
+    /// No hash was recorded for the path.
+    Unrecorded { actual: u64 },
+}
+
+/// The result of checking one sample or wavetable.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SampleVerification {
+    pub location: AssetLocation,
+    pub kind: AssetKind,
+    pub path: String,
+    pub status: SampleStatus,
+}
+
+impl Preset {
+    /// Store a hash of each sample and wavetable that is referenced but not
+    /// embedded, replacing any hashes recorded before. Fails if a file can't
+    /// be read.
+    pub fn record_sample_hashes<R: SampleResolver>(&mut self, resolver: &R) -> Result<(), Error> {
+        let mut hashes = Map::new();
+        for (path, _, _) in external_assets(self) {
+            let contents = resolver.read(&path)?;
+            hashes.insert(path, Value::from(format!("{:016x}", fnv1a(&contents))));
+        }
+        let fields = &mut self.metadata.other_fields;
+        if hashes.is_empty() {
+            fields.remove(METADATA_FIELD);
+        } else {
+            fields.insert(METADATA_FIELD.to_owned(), Value::Object(hashes));
+        }
+        Ok(())
+    }
+
+    /// Compare each sample and wavetable that is referenced but not embedded
+    /// with the hash that was [recorded](Self::record_sample_hashes).
+    pub fn verify_samples<R: SampleResolver>(
+        &self,
+        resolver: &R,
+    ) -> Result<Vec<SampleVerification>, Error> {
+        let recorded = self.sample_hashes()?;
+        let verifications = external_assets(self)
+            .into_iter()
+            .map(|(path, location, kind)| {
+                let status = match resolver.read(&path) {
+                    Err(error) => SampleStatus::Missing(error.kind()),
+                    Ok(contents) => {
+                        let actual = fnv1a(&contents);
+                        match recorded.get(&path) {
+                            None => SampleStatus::Unrecorded { actual },
+                            Some(&expected) if expected == actual => SampleStatus::Unchanged,
+                            Some(&expected) => SampleStatus::Changed { expected, actual },
+                        }
+                    }
+                };
+                SampleVerification {
+                    location,
+                    kind,
+                    path,
+                    status,
+                }
+            })
+            .collect();
+        Ok(verifications)
+    }
+
+    fn sample_hashes(&self) -> Result<HashMap<String, u64>, Error> {
+        let invalid = || Error::new(ErrorKind::InvalidData, "Sample hashes can't be read");
+        let Some(value) = self.metadata.other_fields.get(METADATA_FIELD) else {
+            return Ok(HashMap::new());
+        };
+        value
+            .as_object()
+            .ok_or_else(invalid)?
+            .iter()
+            .map(|(path, hash)| {
+                let hash = hash
+                    .as_str()
+                    .and_then(|hash| u64::from_str_radix(hash, 16).ok())
+                    .ok_or_else(invalid)?;
+                Ok((path.clone(), hash))
+            })
+            .collect()
+    }
+}
+
+/// Paths of the samples and wavetables that are referenced but not embedded.
+fn external_assets(preset: &Preset) -> Vec<(String, AssetLocation, AssetKind)> {
+    asset_locations(preset)
+        .into_iter()
+        .filter_map(|(location, kind)| {
+            let (contents, path) = asset(preset, location)?;
+            let path = path.filter(|path| !path.is_empty() && contents.is_empty())?;
+            Some((path.to_owned(), location, kind))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::io::Cursor;
+
+    use crate::generator::{SamplePlayer, WavetableOscillator};
+    use crate::relink::SearchRootResolver;
+
+    use super::*;
+
+    #[test]
+    fn verify_samples() {
+        let dir = std::env::temp_dir().join(format!("verify_samples-{}", std::process::id()));
+        let user = dir.join("user");
+        fs::create_dir_all(&user).unwrap();
+        fs::write(user.join("kick.wav"), b"kick").unwrap();
+        fs::write(user.join("snare.wav"), b"snare").unwrap();
+        fs::write(user.join("table.wav"), b"table").unwrap();
+
+        let mut preset = Preset::default();
+        for path in ["user/kick.wav", "user/snare.wav"] {
+            preset.generators.push(Box::new(SamplePlayer {
+                sample_path: Some(path.to_owned()),
+                ..Default::default()
+            }));
+        }
+        preset.generators.push(Box::new(WavetableOscillator {
+            wavetable_path: Some("user/table.wav".to_owned()),
+            ..Default::default()
+        }));
+        preset.generators.push(Box::new(SamplePlayer {
+            sample_path: Some("user/embedded.wav".to_owned()),
+            sample_contents: b"embedded".to_vec(),
+            ..Default::default()
+        }));
+
+        let resolver = SearchRootResolver::new(&dir, &[]).unwrap();
+        let statuses = |preset: &Preset| {
+            preset
+                .verify_samples(&resolver)
+                .unwrap()
+                .into_iter()
+                .map(|verification| verification.status)
+                .collect::<Vec<_>>()
+        };
+        assert!(matches!(
+            statuses(&preset)[..],
+            [
+                SampleStatus::Unrecorded { .. },
+                SampleStatus::Unrecorded { .. },
+                SampleStatus::Unrecorded { .. }
+            ]
+        ));
+
+        // The hashes are kept when the metadata is written.
+        preset.record_sample_hashes(&resolver).unwrap();
+        let metadata_only = Preset {
+            metadata: preset.metadata.clone(),
+            ..Default::default()
+        };
+        let mut cursor = Cursor::new(Vec::new());
+        metadata_only.write(&mut cursor).unwrap();
+        cursor.set_position(0);
+        preset.metadata = Preset::read(&mut cursor, None).unwrap().metadata;
+
+        fs::write(user.join("snare.wav"), b"new snare").unwrap();
+        fs::remove_file(user.join("table.wav")).unwrap();
+        let verifications = preset.verify_samples(&resolver).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(verifications.len(), 3);
+        assert_eq!(verifications[0].path, "user/kick.wav");
+        assert_eq!(verifications[0].status, SampleStatus::Unchanged);
+        assert_eq!(verifications[1].location, AssetLocation::Generator(1));
+        assert_eq!(
+            verifications[1].status,
+            SampleStatus::Changed {
+                expected: fnv1a(b"snare"),
+                actual: fnv1a(b"new snare")
+            }
+        );
+        assert_eq!(verifications[2].kind, AssetKind::Wavetable);
+        assert_eq!(
+            verifications[2].status,
+            SampleStatus::Missing(ErrorKind::NotFound)
+        );
+    }
+}
@@ -1,10 +1,16 @@
 //! Phase Plant version numbers
 
+use crate::generator::GeneratorMode;
+use crate::modulator::{ModulatorMode, NoteTriggerMode};
 use crate::PhasePlantRelease::V1_6_9;
+use crate::{Preset, Snapin, Unison};
 use std::fmt::{Display, Formatter};
 
+use serde::{Serialize, Serializer};
+
 /// Not all versions are listed. Only versions that indicate the the start or
-/// end of a new init preset are included.
+/// end of a new init preset are included. The variants are in release order.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
 pub enum PhasePlantRelease {
     /// Some factory presets were created with these versions that were made
     /// before the public release.
@@ -39,7 +45,7 @@ impl PhasePlantRelease {
         use PhasePlantRelease::*;
         match self {
             V1_6_9 => Version::new(1, 6, 9, 0),
-            V1_6_10 => Version::new(1, 6, 9, 0),
+            V1_6_10 => Version::new(1, 6, 10, 0),
             V1_7_0 => Version::new(1, 7, 0, 0),
             V1_7_1 => Version::new(1, 7, 1, 0),
             V1_7_3 => Version::new(1, 7, 3, 0),
@@ -91,6 +97,50 @@ impl PhasePlantRelease {
         }
     }
 
+    /// Every listed release, oldest first.
+    pub const fn all() -> &'static [PhasePlantRelease] {
+        use PhasePlantRelease::*;
+        &[
+            V1_6_9, V1_6_10, V1_7_0, V1_7_1, V1_7_3, V1_7_4, V1_7_5, V1_7_11, V1_8_0, V1_8_4,
+            V1_8_5, V1_8_9, V1_8_11, V1_8_28, V2_0_0, V2_0_11, V2_0_12, V2_0_13, V2_0_16, V2_1_0,
+            V2_1_1,
+        ]
+    }
+
+    /// What the release added to the preset format. Only changes that are
+    /// understood are listed, so releases with a new format version may not
+    /// have any.
+    pub fn format_changes(&self) -> &'static [&'static str] {
+        use PhasePlantRelease::*;
+        match self {
+            V1_6_10 => &["Removed an unused value before the mod wheel"],
+            V1_7_3 => &["Added settings to each modulator"],
+            V1_7_4 => &["Added Sampler base pitch, offset and loop locks"],
+            V1_8_0 => &[
+                "Changed the major format version to 6",
+                "Added edited LFO shapes and their paths",
+                "Added Filter generator slope",
+                "Added Distortion generator spread",
+            ],
+            V1_8_5 => &[
+                "Added global unison and the unison mode and bias of generators",
+                "Added Note modulator root note and range",
+                "Added Sampler loop enable",
+            ],
+            V2_0_0 => &[
+                "Added Curve output generator",
+                "Added Audio Follower and LFO Table modulators",
+                "Added modulation curves and disabling modulations",
+                "Replaced the retrigger setting with note trigger modes",
+            ],
+            V2_0_12 => &["Added Slew Limiter modulator attack and decay"],
+            V2_0_13 => &["Added Slew Limiter modulator linking"],
+            V2_1_0 => &["Added Granular generator"],
+            V2_1_1 => &["Added Nonlinear Filter generator"],
+            _ => &[],
+        }
+    }
+
     /// Determine if a preset format version is probably one used by Phase Plant.
     pub fn is_likely_format_version(format_version: &Version<u32>) -> bool {
         format_version.minor == 2 // All start with two
@@ -99,6 +149,184 @@ impl PhasePlantRelease {
     }
 }
 
+impl Display for PhasePlantRelease {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.version().fmt(f)
+    }
+}
+
+impl Serialize for PhasePlantRelease {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// A feature that can't be loaded by the earliest releases of Phase Plant.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GatedFeature {
+    pub name: &'static str,
+
+    /// The first release that supports the feature.
+    pub release: PhasePlantRelease,
+}
+
+impl GatedFeature {
+    const fn new(name: &'static str, release: PhasePlantRelease) -> Self {
+        Self { name, release }
+    }
+}
+
+impl Preset {
+    /// The preset Phase Plant starts with when a new preset is created in the
+    /// release. Every release starts with three empty lanes and no
+    /// generators or modulators, so only the format version differs from
+    /// [`Preset::default`].
+    pub fn init_for(release: PhasePlantRelease) -> Preset {
+        Preset {
+            format_version: release.format_version(),
+            ..Default::default()
+        }
+    }
+
+    /// Every use of a feature that requires a newer release of Phase Plant
+    /// than the first one supported. Features that aren't understood are not
+    /// included. Effects require the release that introduced their
+    /// [version](crate::effect::EffectMode::versions).
+    pub fn gated_features(&self) -> Vec<GatedFeature> {
+        use PhasePlantRelease::*;
+        let mut features = Vec::new();
+
+        let default_unison = Unison::default();
+        if self.unison.enabled
+            || self.unison.mode != default_unison.mode
+            || self.unison.bias != default_unison.bias
+        {
+            features.push(GatedFeature::new("Unison", V1_8_5));
+        }
+
+        for generator in &self.generators {
+            let release = match generator.mode() {
+                GeneratorMode::CurveOutput => V2_0_0,
+                GeneratorMode::GranularGenerator => V2_1_0,
+                GeneratorMode::NonlinearFilterGenerator => V2_1_1,
+                _ => continue,
+            };
+            features.push(GatedFeature::new(generator.mode().name(), release));
+        }
+
+        for container in &self.modulator_containers {
+            let modulator = &container.modulator;
+            match modulator.mode() {
+                ModulatorMode::AudioFollower => {
+                    features.push(GatedFeature::new("Audio Follower", V2_0_0))
+                }
+                ModulatorMode::LfoTable => features.push(GatedFeature::new("LFO Table", V2_0_0)),
+                ModulatorMode::SlewLimiter => {
+                    features.push(GatedFeature::new("Slew Limiter", V2_0_12))
+                }
+                _ => (),
+            }
+            match modulator.note_trigger_mode() {
+                Some(NoteTriggerMode::Always) => {
+                    features.push(GatedFeature::new("Note Trigger Always", V2_0_0))
+                }
+                Some(NoteTriggerMode::Legato) => {
+                    features.push(GatedFeature::new("Note Trigger Legato", V2_0_0))
+                }
+                _ => (),
+            }
+        }
+
+        for lane in &self.lanes {
+            effect_features(&lane.snapins, &mut features);
+        }
+
+        for modulation in &self.modulations {
+            if !modulation.enabled {
+                features.push(GatedFeature::new("Disabled Modulation", V2_0_0));
+            }
+            if modulation.curve.value != 0.0 {
+                features.push(GatedFeature::new("Modulation Curve", V2_0_0));
+            }
+        }
+
+        features
+    }
+
+    /// The oldest release of Phase Plant that can load the preset, based on
+    /// the features it uses. Features that aren't understood are ignored so
+    /// a newer release may be required.
+    pub fn required_release(&self) -> PhasePlantRelease {
+        self.gated_features()
+            .iter()
+            .map(|feature| feature.release)
+            .max()
+            .unwrap_or(PhasePlantRelease::V1_7_0)
+    }
+
+    /// The release of Phase Plant that saved the preset, as precisely as it
+    /// can be determined. [`format_version`](Preset::format_version) is
+    /// shared by several releases, but every snapin records the release that
+    /// saved it. Without any snapins the release is only known if the format
+    /// version is used by a single release that has been superseded. The
+    /// result is never older than the [required release](Preset::required_release).
+    pub fn saved_with(&self) -> Option<Version<u8>> {
+        let mut host_version = None;
+        for lane in &self.lanes {
+            newest_host_version(&lane.snapins, &mut host_version);
+        }
+        let version = host_version.or_else(|| {
+            let all = PhasePlantRelease::all();
+            let mut releases = all
+                .iter()
+                .filter(|release| release.format_version() == self.format_version);
+            match (releases.next(), releases.next()) {
+                (Some(release), None) if Some(release) != all.last() => Some(release.version()),
+                _ => None,
+            }
+        })?;
+        let required = self.required_release().version();
+        Some(if required.is_at_least(&version) {
+            required
+        } else {
+            version
+        })
+    }
+}
+
+/// Effects whose version was introduced after the first public release,
+/// including the effects hosted by Multipass.
+fn effect_features(snapins: &[Snapin], features: &mut Vec<GatedFeature>) {
+    for snapin in snapins {
+        let mode = snapin.effect.mode();
+        if let Some(release) = mode.release_for_version(snapin.effect_version) {
+            if release > PhasePlantRelease::V1_7_0 {
+                features.push(GatedFeature::new(mode.name(), release));
+            }
+        }
+        if let Some(multipass) = snapin.effect.as_multipass() {
+            for lane in &multipass.lanes {
+                effect_features(&lane.snapins, features);
+            }
+        }
+    }
+}
+
+/// Hosts such as Multipass have a version of zero so they are skipped.
+fn newest_host_version(snapins: &[Snapin], newest: &mut Option<Version<u8>>) {
+    for snapin in snapins {
+        let version = snapin.host_version;
+        if !version.is_zero() && newest.is_none_or(|newest| !newest.is_at_least(&version)) {
+            *newest = Some(version);
+        }
+        if let Some(multipass) = snapin.effect.as_multipass() {
+            for lane in &multipass.lanes {
+                newest_host_version(&lane.snapins, newest);
+            }
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Version<T: PartialOrd> {
     pub major: T,
@@ -149,8 +377,11 @@ impl<T: Display + Default + PartialEq + PartialOrd> Display for Version<T> {
 
 #[cfg(test)]
 mod test {
+    use crate::test::{
+        read_effect_preset, read_generator_preset, read_modulator_preset, read_preset,
+    };
     use crate::version::Version;
-    use crate::PhasePlantRelease;
+    use crate::{PhasePlantRelease, Preset};
 
     #[test]
     fn at_least() {
@@ -198,4 +429,112 @@ mod test {
         assert!(!Version::new(1, 8, 17, 0).is_zero());
         assert!(!Version::new(0, 0, 0, 42).is_zero());
     }
+
+    #[test]
+    fn all() {
+        let all = PhasePlantRelease::all();
+        assert_eq!(all.first(), Some(&PhasePlantRelease::V1_6_9));
+        assert_eq!(all.last(), Some(&PhasePlantRelease::V2_1_1));
+        for pair in all.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert!(pair[1].version().is_at_least(&pair[0].version()));
+            assert!(pair[1]
+                .format_version()
+                .is_at_least(&pair[0].format_version()));
+        }
+        assert_eq!(PhasePlantRelease::V1_6_10.to_string(), "1.6.10");
+        assert!(PhasePlantRelease::V1_7_0.format_changes().is_empty());
+        assert!(!PhasePlantRelease::V2_1_0.format_changes().is_empty());
+    }
+
+    #[test]
+    fn required_release() {
+        assert_eq!(
+            Preset::default().required_release(),
+            PhasePlantRelease::V1_7_0
+        );
+
+        let preset = read_generator_preset(
+            "nonlinear_filter_generator",
+            "nonlinear_filter_generator-2.1.1.phaseplant",
+        )
+        .unwrap();
+        assert_eq!(preset.required_release(), PhasePlantRelease::V2_1_1);
+        let preset =
+            read_modulator_preset("slew_limiter", "slew_limiter-2.1.0.phaseplant").unwrap();
+        assert_eq!(preset.required_release(), PhasePlantRelease::V2_0_12);
+        let preset =
+            read_modulator_preset("lfo", "lfo-note_trigger_legato-sync-2.1.0.phaseplant").unwrap();
+        assert_eq!(preset.required_release(), PhasePlantRelease::V2_0_0);
+        let preset = read_modulator_preset("lfo", "lfo-1.8.13.phaseplant").unwrap();
+        assert_eq!(preset.required_release(), PhasePlantRelease::V1_7_0);
+        let preset = read_preset("unison", "unison-mode-hard-1.8.13.phaseplant");
+        assert_eq!(preset.required_release(), PhasePlantRelease::V1_8_5);
+        let preset = read_effect_preset("filter", "filter-2.0.16.phaseplant").unwrap();
+        assert_eq!(preset.required_release(), PhasePlantRelease::V2_0_16);
+    }
+
+    #[test]
+    fn init_for() {
+        let mut compared = 0;
+        for release in PhasePlantRelease::all() {
+            let file_name = format!("init-{release}.phaseplant");
+            if !crate::tests::test_data_path(&["init", &file_name]).exists() {
+                continue;
+            }
+            let mut preset = read_preset("init", &file_name);
+            preset.metadata.author = None;
+            preset.metadata.name = None;
+            assert_eq!(preset, Preset::init_for(*release), "{file_name}");
+            compared += 1;
+        }
+        assert!(compared > 15);
+    }
+
+    #[test]
+    fn saved_with() {
+        let preset = read_effect_preset("gain", "gain-2.0.16.phaseplant").unwrap();
+        assert_eq!(
+            preset.format_version,
+            PhasePlantRelease::V2_0_13.format_version()
+        );
+        assert_eq!(preset.saved_with(), Some(Version::new(2, 0, 16, 0)));
+
+        // The hosted snapins are included.
+        let preset =
+            read_effect_preset("multipass", "multipass-metadata-2.1.0.phaseplant").unwrap();
+        assert_eq!(preset.saved_with(), Some(Version::new(2, 1, 0, 0)));
+
+        // Without snapins the format version has to belong to a single release.
+        let mut known = 0;
+        for entry in std::fs::read_dir(crate::tests::test_data_path(&["init"])).unwrap() {
+            let path = entry.unwrap().path();
+            let file_name = path.file_name().unwrap().to_string_lossy();
+            let Some(version) = file_name
+                .strip_prefix("init-")
+                .and_then(|name| name.strip_suffix(".phaseplant"))
+            else {
+                continue;
+            };
+            // Some releases can't be read yet.
+            let Ok(preset) = Preset::read_file(&path) else {
+                continue;
+            };
+            if let Some(saved_with) = preset.saved_with() {
+                assert_eq!(saved_with.to_string(), version);
+                known += 1;
+            }
+        }
+        assert!(known > 0);
+        assert_eq!(
+            Preset::init_for(PhasePlantRelease::V2_1_1).saved_with(),
+            None
+        );
+
+        // Gated features set the oldest possible release.
+        let mut preset = Preset::init_for(PhasePlantRelease::V1_7_3);
+        assert_eq!(preset.saved_with(), Some(Version::new(1, 7, 3, 0)));
+        preset.unison.enabled = true;
+        assert_eq!(preset.saved_with(), Some(Version::new(1, 8, 5, 0)));
+    }
 }